@@ -0,0 +1,30 @@
+//! File checksum throughput: SHA-256 vs BLAKE3
+//!
+//! Run with `cargo bench -p aapi-adapters --bench file_checksum`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use aapi_adapters::StateSnapshot;
+use aapi_core::types::HashAlgorithm;
+
+fn file_checksum(c: &mut Criterion) {
+    let mut group = c.benchmark_group("file_checksum");
+
+    for size in [64 * 1024, 1024 * 1024, 16 * 1024 * 1024] {
+        let data: Vec<u8> = (0..size).map(|i| (i % 251) as u8).collect();
+        group.throughput(Throughput::Bytes(size as u64));
+
+        for algorithm in [HashAlgorithm::Sha256, HashAlgorithm::Blake3] {
+            group.bench_with_input(
+                BenchmarkId::new(algorithm.as_str(), size),
+                &data,
+                |b, data| b.iter(|| StateSnapshot::from_bytes_with(black_box(data), algorithm)),
+            );
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, file_checksum);
+criterion_main!(benches);
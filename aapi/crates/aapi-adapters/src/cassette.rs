@@ -0,0 +1,223 @@
+//! Record-and-replay of HTTP exchanges for adapter tests
+//!
+//! A [`Cassette`] attached to an [`HttpAdapter`](crate::HttpAdapter) with
+//! `with_cassette` either records every request the adapter sends, together
+//! with its response, or replays previously recorded responses without
+//! touching the network. Cassettes are JSON files meant to be checked in
+//! next to the tests that use them.
+//!
+//! # Matching
+//!
+//! A request matches a recorded interaction when its method, full URL
+//! (including the query string built from the VĀKYA's `query`) and body bytes
+//! are identical. Headers are not compared. Identical requests replay their
+//! recordings in order, each recording at most once, so a test that polls the
+//! same URL sees the same sequence of responses it saw while recording. A
+//! request with no unused match fails with `AdapterError::Http` rather than
+//! reaching the network.
+//!
+//! Only `HttpAdapter`'s generic requests (`http.get`, `http.post`, ...) go
+//! through the cassette; `http.download` and `http.upload` do not.
+//!
+//! # Re-recording
+//!
+//! `CassetteMode::Auto` records when the file does not exist and replays
+//! otherwise, so deleting a cassette re-records it on the next run.
+//! `CassetteMode::Record` always starts an empty recording and overwrites the
+//! file. The file is rewritten after every recorded exchange.
+//!
+//! Requires the `recording` feature.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use reqwest::{Client, Request, StatusCode};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{AdapterError, AdapterResult};
+use crate::file::write_atomic;
+
+/// Whether a cassette records or replays
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CassetteMode {
+    /// Send requests and record them, replacing any existing cassette
+    Record,
+    /// Answer from the cassette only; it must exist
+    Replay,
+    /// Replay if the cassette exists, otherwise record
+    #[default]
+    Auto,
+}
+
+/// A request as matched against recordings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+}
+
+/// A recorded response
+///
+/// Bodies that are valid UTF-8 are stored as text so cassettes stay readable;
+/// anything else is stored as base64.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_base64: Option<String>,
+}
+
+/// One recorded exchange
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct CassetteFile {
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Default)]
+struct CassetteState {
+    interactions: Vec<Interaction>,
+    /// Per interaction, whether a replay has consumed it
+    used: Vec<bool>,
+}
+
+/// Recorded HTTP exchanges backed by a JSON file
+pub struct Cassette {
+    path: PathBuf,
+    recording: bool,
+    state: Mutex<CassetteState>,
+}
+
+impl Cassette {
+    /// Open the cassette at `path` in `mode`
+    pub fn open(path: impl Into<PathBuf>, mode: CassetteMode) -> AdapterResult<Self> {
+        let path = path.into();
+        let recording = match mode {
+            CassetteMode::Record => true,
+            CassetteMode::Replay => false,
+            CassetteMode::Auto => !path.exists(),
+        };
+
+        let interactions = if recording {
+            Vec::new()
+        } else {
+            load(&path)?
+        };
+        Ok(Self {
+            path,
+            recording,
+            state: Mutex::new(CassetteState {
+                used: vec![false; interactions.len()],
+                interactions,
+            }),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    /// Number of interactions recorded or loaded
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().interactions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Send `request` and record the exchange, or answer it from the
+    /// recording
+    pub(crate) async fn exchange(
+        &self,
+        client: &Client,
+        request: Request,
+    ) -> AdapterResult<(StatusCode, HashMap<String, String>, Vec<u8>)> {
+        let recorded = RecordedRequest {
+            method: request.method().to_string(),
+            url: request.url().to_string(),
+            body: request.body()
+                .and_then(|body| body.as_bytes())
+                .map(|bytes| String::from_utf8_lossy(bytes).into_owned()),
+        };
+
+        if !self.recording {
+            return self.replay(&recorded);
+        }
+
+        let (status, headers, body) = crate::http::fetch(client, request).await?;
+        let response = RecordedResponse {
+            status: status.as_u16(),
+            headers: headers.clone(),
+            body: std::str::from_utf8(&body).ok().map(str::to_string),
+            body_base64: std::str::from_utf8(&body).is_err().then(|| {
+                use base64::Engine;
+                base64::engine::general_purpose::STANDARD.encode(&body)
+            }),
+        };
+
+        let contents = {
+            let mut state = self.state.lock().unwrap();
+            state.interactions.push(Interaction { request: recorded, response });
+            state.used.push(true);
+            serde_json::to_vec_pretty(&CassetteFile { interactions: state.interactions.clone() })?
+        };
+        write_atomic(&self.path, &contents).await?;
+
+        Ok((status, headers, body))
+    }
+
+    fn replay(&self, request: &RecordedRequest) -> AdapterResult<(StatusCode, HashMap<String, String>, Vec<u8>)> {
+        let mut state = self.state.lock().unwrap();
+        let CassetteState { interactions, used } = &mut *state;
+        let index = interactions.iter()
+            .zip(used.iter())
+            .position(|(interaction, used)| !used && interaction.request == *request)
+            .ok_or_else(|| AdapterError::Http(format!(
+                "no recorded interaction in {} for {} {}",
+                self.path.display(),
+                request.method,
+                request.url
+            )))?;
+        used[index] = true;
+
+        let response = &interactions[index].response;
+        let status = StatusCode::from_u16(response.status)
+            .map_err(|e| AdapterError::Http(format!("invalid recorded status: {}", e)))?;
+        let body = match (&response.body, &response.body_base64) {
+            (_, Some(b64)) => {
+                use base64::Engine;
+                base64::engine::general_purpose::STANDARD
+                    .decode(b64)
+                    .map_err(|e| AdapterError::Http(format!("invalid recorded body: {}", e)))?
+            }
+            (Some(text), None) => text.clone().into_bytes(),
+            (None, None) => Vec::new(),
+        };
+        Ok((status, response.headers.clone(), body))
+    }
+}
+
+fn load(path: &Path) -> AdapterResult<Vec<Interaction>> {
+    let data = std::fs::read(path).map_err(|e| {
+        AdapterError::NotFound(format!("cassette {}: {}", path.display(), e))
+    })?;
+    let file: CassetteFile = serde_json::from_slice(&data)?;
+    Ok(file.interactions)
+}
@@ -0,0 +1,198 @@
+//! Body codecs for non-JSON VĀKYA payloads
+//!
+//! `body_type.content_type` says how `vakya.body` is encoded. JSON bodies
+//! are used as-is. For any other content type the body carries the raw
+//! payload, either as a string or as `{"content_base64": "..."}`, and the
+//! codec registered for that type decodes it into JSON before an adapter
+//! reads it.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use aapi_core::Vakya;
+use serde_json::Value;
+
+use crate::error::{AdapterError, AdapterResult};
+
+/// Decodes raw payload bytes of one or more content types into JSON
+pub trait BodyCodec: Send + Sync {
+    /// Content types handled, without parameters (e.g. `application/yaml`)
+    fn content_types(&self) -> Vec<&str>;
+
+    /// Decode a raw payload
+    fn decode(&self, raw: &[u8]) -> AdapterResult<Value>;
+}
+
+/// `application/yaml`
+pub struct YamlCodec;
+
+impl BodyCodec for YamlCodec {
+    fn content_types(&self) -> Vec<&str> {
+        vec!["application/yaml", "application/x-yaml", "text/yaml"]
+    }
+
+    fn decode(&self, raw: &[u8]) -> AdapterResult<Value> {
+        serde_yaml::from_slice(raw)
+            .map_err(|e| AdapterError::InvalidInput(format!("Invalid YAML body: {}", e)))
+    }
+}
+
+/// `application/x-www-form-urlencoded`
+///
+/// Decodes to an object of strings; a key given more than once becomes an
+/// array of its values.
+pub struct FormCodec;
+
+impl BodyCodec for FormCodec {
+    fn content_types(&self) -> Vec<&str> {
+        vec!["application/x-www-form-urlencoded"]
+    }
+
+    fn decode(&self, raw: &[u8]) -> AdapterResult<Value> {
+        let mut fields = serde_json::Map::new();
+        for (key, value) in url::form_urlencoded::parse(raw) {
+            let value = Value::String(value.into_owned());
+            match fields.get_mut(key.as_ref()) {
+                Some(Value::Array(values)) => values.push(value),
+                Some(existing) => *existing = Value::Array(vec![existing.take(), value]),
+                None => {
+                    fields.insert(key.into_owned(), value);
+                }
+            }
+        }
+        Ok(Value::Object(fields))
+    }
+}
+
+/// Codecs keyed by content type
+#[derive(Clone)]
+pub struct BodyCodecRegistry {
+    codecs: HashMap<String, Arc<dyn BodyCodec>>,
+}
+
+impl Default for BodyCodecRegistry {
+    fn default() -> Self {
+        let mut registry = Self::new();
+        registry.register(YamlCodec);
+        registry.register(FormCodec);
+        registry
+    }
+}
+
+impl BodyCodecRegistry {
+    /// Registry with no codecs; only JSON bodies are accepted
+    pub fn new() -> Self {
+        Self {
+            codecs: HashMap::new(),
+        }
+    }
+
+    /// Register a codec for each of its content types, replacing any
+    /// codec previously registered for them
+    pub fn register<C: BodyCodec + 'static>(&mut self, codec: C) {
+        let codec: Arc<dyn BodyCodec> = Arc::new(codec);
+        for content_type in codec.content_types() {
+            self.codecs.insert(essence(content_type), Arc::clone(&codec));
+        }
+    }
+
+    /// Whether bodies of `content_type` can be decoded
+    pub fn supports(&self, content_type: &str) -> bool {
+        let essence = essence(content_type);
+        is_json(&essence) || self.codecs.contains_key(&essence)
+    }
+
+    /// Decode `body` according to `content_type`
+    ///
+    /// JSON bodies, and bodies that are already structured, are returned
+    /// unchanged.
+    pub fn decode<'a>(&self, content_type: &str, body: &'a Value) -> AdapterResult<Cow<'a, Value>> {
+        let essence = essence(content_type);
+        if is_json(&essence) {
+            return Ok(Cow::Borrowed(body));
+        }
+
+        let codec = self.codecs.get(&essence)
+            .ok_or_else(|| AdapterError::InvalidInput(format!("Unsupported body content type: {}", content_type)))?;
+
+        let raw = match body {
+            Value::String(text) => text.as_bytes().to_vec(),
+            Value::Object(fields) if fields.len() == 1 && fields.contains_key("content_base64") => {
+                use base64::Engine;
+                let b64 = fields["content_base64"].as_str()
+                    .ok_or_else(|| AdapterError::InvalidInput("'content_base64' must be a string".to_string()))?;
+                base64::engine::general_purpose::STANDARD
+                    .decode(b64)
+                    .map_err(|e| AdapterError::InvalidInput(format!("Invalid base64: {}", e)))?
+            }
+            _ => return Ok(Cow::Borrowed(body)),
+        };
+
+        codec.decode(&raw).map(Cow::Owned)
+    }
+
+    /// The VĀKYA with its body decoded to JSON
+    ///
+    /// Borrows the original when no decoding is needed.
+    pub fn decode_vakya<'a>(&self, vakya: &'a Vakya) -> AdapterResult<Cow<'a, Vakya>> {
+        match self.decode(&vakya.body_type.content_type, &vakya.body)? {
+            Cow::Borrowed(_) => Ok(Cow::Borrowed(vakya)),
+            Cow::Owned(body) => {
+                let mut decoded = vakya.clone();
+                decoded.body = body;
+                decoded.body_type.content_type = "application/json".to_string();
+                Ok(Cow::Owned(decoded))
+            }
+        }
+    }
+}
+
+/// Media type without parameters, lowercased (`Text/YAML; charset=utf-8` -> `text/yaml`)
+fn essence(content_type: &str) -> String {
+    content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase()
+}
+
+fn is_json(essence: &str) -> bool {
+    essence.is_empty() || essence == "application/json" || essence.ends_with("+json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_decode_yaml_and_form() {
+        let codecs = BodyCodecRegistry::default();
+
+        let yaml = json!("path: /tmp/out.txt\ncontent: hello\ntags: [a, b]\n");
+        let decoded = codecs.decode("application/yaml; charset=utf-8", &yaml).unwrap();
+        assert_eq!(*decoded, json!({"path": "/tmp/out.txt", "content": "hello", "tags": ["a", "b"]}));
+
+        let form = json!({"content_base64": "bmFtZT1hJTIwYiZ0YWc9eCZ0YWc9eQ=="});
+        let decoded = codecs.decode("application/x-www-form-urlencoded", &form).unwrap();
+        assert_eq!(*decoded, json!({"name": "a b", "tag": ["x", "y"]}));
+
+        // JSON and already-structured bodies pass through untouched
+        let body = json!("just a string");
+        assert!(matches!(codecs.decode("application/json", &body).unwrap(), Cow::Borrowed(_)));
+        let body = json!({"content": "hi"});
+        assert!(matches!(codecs.decode("text/yaml", &body).unwrap(), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_decode_rejects_unknown_and_malformed() {
+        let codecs = BodyCodecRegistry::default();
+        assert!(!codecs.supports("application/xml"));
+        assert!(matches!(
+            codecs.decode("application/xml", &json!("<a/>")),
+            Err(AdapterError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            codecs.decode("application/yaml", &json!("key: [unclosed")),
+            Err(AdapterError::InvalidInput(_))
+        ));
+        assert!(BodyCodecRegistry::new().decode("application/yaml", &json!("a: 1")).is_err());
+    }
+}
@@ -2,10 +2,12 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sha2::{Sha256, Digest};
 use std::collections::HashMap;
 
-use aapi_core::types::EffectBucket;
+use aapi_core::patch::json_patch;
+use aapi_core::types::{EffectBucket, HashAlgorithm};
+
+pub use aapi_core::patch::JsonPatchOp;
 
 /// Captured effect from an action execution
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -82,8 +84,13 @@ impl CapturedEffect {
 
     /// Compute delta from before/after states
     pub fn compute_delta(&mut self) {
+        self.compute_delta_with(DeltaPrecision::Compact);
+    }
+
+    /// Compute delta from before/after states with the given numeric precision
+    pub fn compute_delta_with(&mut self, precision: DeltaPrecision) {
         if let (Some(before), Some(after)) = (&self.before, &self.after) {
-            self.delta = Some(StateDelta::compute(before, after));
+            self.delta = Some(StateDelta::compute_with(before, after, precision));
         }
     }
 }
@@ -106,14 +113,15 @@ pub struct StateSnapshot {
 }
 
 impl StateSnapshot {
-    /// Create a snapshot from bytes
+    /// Create a snapshot from bytes (SHA-256)
     pub fn from_bytes(data: &[u8]) -> Self {
-        let mut hasher = Sha256::new();
-        hasher.update(data);
-        let hash = hex::encode(hasher.finalize());
+        Self::from_bytes_with(data, HashAlgorithm::Sha256)
+    }
 
+    /// Create a snapshot from bytes using the given hash algorithm
+    pub fn from_bytes_with(data: &[u8], algorithm: HashAlgorithm) -> Self {
         Self {
-            hash,
+            hash: algorithm.label(&algorithm.digest_hex(data)),
             size: Some(data.len() as u64),
             content_type: None,
             content: None,
@@ -122,15 +130,17 @@ impl StateSnapshot {
         }
     }
 
-    /// Create a snapshot from JSON value
+    /// Create a snapshot from JSON value (SHA-256)
     pub fn from_json(value: &serde_json::Value) -> Self {
+        Self::from_json_with(value, HashAlgorithm::Sha256)
+    }
+
+    /// Create a snapshot from JSON value using the given hash algorithm
+    pub fn from_json_with(value: &serde_json::Value, algorithm: HashAlgorithm) -> Self {
         let json_bytes = serde_json::to_vec(value).unwrap_or_default();
-        let mut hasher = Sha256::new();
-        hasher.update(&json_bytes);
-        let hash = hex::encode(hasher.finalize());
 
         Self {
-            hash,
+            hash: algorithm.label(&algorithm.digest_hex(&json_bytes)),
             size: Some(json_bytes.len() as u64),
             content_type: Some("application/json".to_string()),
             content: Some(value.clone()),
@@ -139,6 +149,11 @@ impl StateSnapshot {
         }
     }
 
+    /// Algorithm the hash was computed with; unlabeled hashes are SHA-256
+    pub fn hash_algorithm(&self) -> HashAlgorithm {
+        HashAlgorithm::split_labeled(&self.hash).0
+    }
+
     /// Create a snapshot with just a hash (for large content)
     pub fn from_hash(hash: impl Into<String>, size: u64) -> Self {
         Self {
@@ -187,6 +202,10 @@ pub struct StateDelta {
     pub size_delta: Option<i64>,
     /// JSON patch (RFC 6902) if applicable
     pub json_patch: Option<Vec<JsonPatchOp>>,
+    /// Numeric leaf changes with both sides string-encoded, recorded in
+    /// `DeltaPrecision::Exact` mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub numeric_changes: Option<Vec<NumericChange>>,
     /// Human-readable summary
     pub summary: Option<String>,
 }
@@ -194,6 +213,11 @@ pub struct StateDelta {
 impl StateDelta {
     /// Compute delta between two snapshots
     pub fn compute(before: &StateSnapshot, after: &StateSnapshot) -> Self {
+        Self::compute_with(before, after, DeltaPrecision::Compact)
+    }
+
+    /// Compute delta between two snapshots with the given numeric precision
+    pub fn compute_with(before: &StateSnapshot, after: &StateSnapshot, precision: DeltaPrecision) -> Self {
         let change_type = if before.hash == "NOT_EXISTS" {
             ChangeType::Created
         } else if after.hash == "NOT_EXISTS" {
@@ -211,21 +235,102 @@ impl StateDelta {
 
         // Compute JSON patch if both are JSON
         let json_patch = match (&before.content, &after.content) {
-            (Some(b), Some(a)) => Some(compute_json_patch(b, a)),
+            (Some(b), Some(a)) => Some(json_patch(b, a)),
             _ => None,
         };
 
-        Self {
+        let numeric_changes = match (precision, &before.content, &after.content, &json_patch) {
+            (DeltaPrecision::Exact, Some(b), Some(a), Some(patch)) => Some(numeric_changes(b, a, patch)),
+            _ => None,
+        };
+
+        let mut delta = Self {
             change_type,
             before_hash: before.hash.clone(),
             after_hash: after.hash.clone(),
             size_delta,
             json_patch,
+            numeric_changes,
             summary: None,
+        };
+        delta.summary = Some(delta.describe());
+        delta
+    }
+
+    /// One-line description, e.g. `modified 3 keys, +120 bytes`
+    pub fn describe(&self) -> String {
+        let mut summary = match self.change_type {
+            ChangeType::Created => "created".to_string(),
+            ChangeType::Deleted => "deleted".to_string(),
+            ChangeType::Unchanged => return "unchanged".to_string(),
+            ChangeType::Modified => match self.json_patch.as_ref().map(Vec::len) {
+                Some(1) => "modified 1 key".to_string(),
+                Some(n) => format!("modified {} keys", n),
+                None => "modified".to_string(),
+            },
+        };
+
+        match self.size_delta {
+            Some(bytes) if bytes != 0 => summary.push_str(&format!(", {:+} bytes", bytes)),
+            _ => {}
+        }
+        summary
+    }
+}
+
+/// How numeric leaf changes are recorded in a `StateDelta`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeltaPrecision {
+    /// JSON patch only
+    #[default]
+    Compact,
+    /// JSON patch plus the old and new value of every numeric leaf as a
+    /// string, so the change can be inverted without float round-trips
+    Exact,
+}
+
+/// Old and new value of a numeric leaf, string-encoded at full precision
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NumericChange {
+    /// JSON Pointer to the leaf
+    pub path: String,
+    /// Value before the change; `None` if the leaf was added
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub before: Option<String>,
+    /// Value after the change; `None` if the leaf was removed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+}
+
+impl NumericChange {
+    /// The change that undoes this one
+    pub fn inverse(&self) -> Self {
+        Self {
+            path: self.path.clone(),
+            before: self.after.clone(),
+            after: self.before.clone(),
         }
     }
 }
 
+/// Numeric leaves touched by `patch`, read from both sides of the change
+fn numeric_changes(before: &serde_json::Value, after: &serde_json::Value, patch: &[JsonPatchOp]) -> Vec<NumericChange> {
+    let number_at = |value: &serde_json::Value, path: &str| match value.pointer(path) {
+        Some(serde_json::Value::Number(n)) => Some(n.to_string()),
+        _ => None,
+    };
+
+    patch.iter()
+        .map(|op| NumericChange {
+            path: op.path.clone(),
+            before: number_at(before, &op.path),
+            after: number_at(after, &op.path),
+        })
+        .filter(|change| change.before.is_some() || change.after.is_some())
+        .collect()
+}
+
 /// Type of change
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -236,86 +341,6 @@ pub enum ChangeType {
     Unchanged,
 }
 
-/// JSON Patch operation (RFC 6902)
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct JsonPatchOp {
-    pub op: String,
-    pub path: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub value: Option<serde_json::Value>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub from: Option<String>,
-}
-
-/// Compute JSON patch between two values (simplified)
-fn compute_json_patch(before: &serde_json::Value, after: &serde_json::Value) -> Vec<JsonPatchOp> {
-    let mut ops = Vec::new();
-    compute_json_patch_recursive("", before, after, &mut ops);
-    ops
-}
-
-fn compute_json_patch_recursive(
-    path: &str,
-    before: &serde_json::Value,
-    after: &serde_json::Value,
-    ops: &mut Vec<JsonPatchOp>,
-) {
-    use serde_json::Value;
-
-    match (before, after) {
-        (Value::Object(b), Value::Object(a)) => {
-            // Check for removed keys
-            for key in b.keys() {
-                if !a.contains_key(key) {
-                    ops.push(JsonPatchOp {
-                        op: "remove".to_string(),
-                        path: format!("{}/{}", path, key),
-                        value: None,
-                        from: None,
-                    });
-                }
-            }
-            // Check for added or modified keys
-            for (key, after_val) in a {
-                let new_path = format!("{}/{}", path, key);
-                if let Some(before_val) = b.get(key) {
-                    if before_val != after_val {
-                        compute_json_patch_recursive(&new_path, before_val, after_val, ops);
-                    }
-                } else {
-                    ops.push(JsonPatchOp {
-                        op: "add".to_string(),
-                        path: new_path,
-                        value: Some(after_val.clone()),
-                        from: None,
-                    });
-                }
-            }
-        }
-        (Value::Array(b), Value::Array(a)) => {
-            // Simplified: just replace if different
-            if b != a {
-                ops.push(JsonPatchOp {
-                    op: "replace".to_string(),
-                    path: path.to_string(),
-                    value: Some(Value::Array(a.clone())),
-                    from: None,
-                });
-            }
-        }
-        _ => {
-            if before != after {
-                ops.push(JsonPatchOp {
-                    op: "replace".to_string(),
-                    path: path.to_string(),
-                    value: Some(after.clone()),
-                    from: None,
-                });
-            }
-        }
-    }
-}
-
 /// Instructions for reversing an effect
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReversalInstructions {
@@ -376,15 +401,23 @@ impl EffectCapturer {
 /// Builder for constructing effects
 pub struct EffectBuilder {
     effect: CapturedEffect,
+    precision: DeltaPrecision,
 }
 
 impl EffectBuilder {
     pub fn new(vakya_id: String, bucket: EffectBucket, target: impl Into<String>) -> Self {
         Self {
             effect: CapturedEffect::new(vakya_id, bucket, target),
+            precision: DeltaPrecision::Compact,
         }
     }
 
+    /// Numeric precision for the computed delta
+    pub fn delta_precision(mut self, precision: DeltaPrecision) -> Self {
+        self.precision = precision;
+        self
+    }
+
     pub fn target_type(mut self, target_type: impl Into<String>) -> Self {
         self.effect.target_type = Some(target_type.into());
         self
@@ -416,7 +449,7 @@ impl EffectBuilder {
     }
 
     pub fn build(mut self) -> CapturedEffect {
-        self.effect.compute_delta();
+        self.effect.compute_delta_with(self.precision);
         self.effect
     }
 }
@@ -434,6 +467,18 @@ mod tests {
         assert_eq!(snapshot.size, Some(11));
     }
 
+    #[test]
+    fn test_state_snapshot_hash_algorithm() {
+        let sha = StateSnapshot::from_bytes(b"payload");
+        assert_eq!(sha.hash_algorithm(), HashAlgorithm::Sha256);
+        assert!(!sha.hash.contains(':'));
+
+        let blake = StateSnapshot::from_bytes_with(b"payload", HashAlgorithm::Blake3);
+        assert_eq!(blake.hash_algorithm(), HashAlgorithm::Blake3);
+        assert_eq!(blake.hash, format!("blake3:{}", HashAlgorithm::Blake3.digest_hex(b"payload")));
+        assert_ne!(sha.hash, blake.hash);
+    }
+
     #[test]
     fn test_state_snapshot_from_json() {
         let value = serde_json::json!({"key": "value"});
@@ -468,6 +513,48 @@ mod tests {
         
         let delta = StateDelta::compute(&before, &after);
         assert_eq!(delta.change_type, ChangeType::Modified);
+        assert_eq!(delta.summary.as_deref(), Some("modified"));
+    }
+
+    #[test]
+    fn test_delta_summary() {
+        let before = StateSnapshot::from_json(&serde_json::json!({"a": 1, "b": 2}));
+        let after = StateSnapshot::from_json(&serde_json::json!({"a": 1, "b": 3, "c": "added"}));
+        let delta = StateDelta::compute(&before, &after);
+        let bytes = after.size.unwrap() as i64 - before.size.unwrap() as i64;
+        assert_eq!(delta.summary, Some(format!("modified 2 keys, +{} bytes", bytes)));
+
+        let delta = StateDelta::compute(&StateSnapshot::from_bytes(b"gone"), &StateSnapshot::not_exists());
+        assert!(delta.describe().starts_with("deleted"));
+        assert_eq!(StateDelta::compute(&after, &after).describe(), "unchanged");
+    }
+
+    #[test]
+    fn test_delta_exact_numeric_changes() {
+        let before = StateSnapshot::from_json(&serde_json::json!({
+            "balance": 12345678901234567u64, "rate": 0.1, "name": "a", "gone": 7
+        }));
+        let after = StateSnapshot::from_json(&serde_json::json!({
+            "balance": 12345678901234568u64, "rate": 0.30000000000000004, "name": "b", "fee": -2.5
+        }));
+
+        let compact = StateDelta::compute(&before, &after);
+        assert!(compact.numeric_changes.is_none());
+        assert!(!serde_json::to_string(&compact).unwrap().contains("numeric_changes"));
+
+        let exact = StateDelta::compute_with(&before, &after, DeltaPrecision::Exact);
+        let mut changes = exact.numeric_changes.clone().unwrap();
+        changes.sort_by(|a, b| a.path.cmp(&b.path));
+        assert_eq!(changes, vec![
+            NumericChange { path: "/balance".into(), before: Some("12345678901234567".into()), after: Some("12345678901234568".into()) },
+            NumericChange { path: "/fee".into(), before: None, after: Some("-2.5".into()) },
+            NumericChange { path: "/gone".into(), before: Some("7".into()), after: None },
+            NumericChange { path: "/rate".into(), before: Some("0.1".into()), after: Some("0.30000000000000004".into()) },
+        ]);
+        assert_eq!(changes[0].inverse().after.as_deref(), Some("12345678901234567"));
+
+        let roundtrip: StateDelta = serde_json::from_value(serde_json::to_value(&exact).unwrap()).unwrap();
+        assert_eq!(roundtrip.numeric_changes, exact.numeric_changes);
     }
 
     #[test]
@@ -475,7 +562,7 @@ mod tests {
         let before = serde_json::json!({"a": 1, "b": 2});
         let after = serde_json::json!({"a": 1, "b": 3, "c": 4});
         
-        let patch = compute_json_patch(&before, &after);
+        let patch = json_patch(&before, &after);
         assert!(!patch.is_empty());
     }
 
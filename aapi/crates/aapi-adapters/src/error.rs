@@ -2,6 +2,8 @@
 
 use thiserror::Error;
 
+use aapi_core::error::ReasonCode;
+
 /// Adapter errors
 #[derive(Error, Debug)]
 pub enum AdapterError {
@@ -17,12 +19,23 @@ pub enum AdapterError {
     #[error("Invalid input: {0}")]
     InvalidInput(String),
 
+    /// A conditional request's expected version did not match; `current`
+    /// is the version to retry against
+    #[error("Precondition failed: expected {expected}, current {current}")]
+    Conflict { expected: String, current: String },
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
     #[error("HTTP error: {0}")]
     Http(String),
 
+    #[error("Redis error: {0}")]
+    Redis(String),
+
+    #[error("Git error: {0}")]
+    Git(String),
+
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
 
@@ -35,8 +48,24 @@ pub enum AdapterError {
     #[error("Timeout")]
     Timeout,
 
+    /// The target could not be reached, or its circuit is open; nothing
+    /// was attempted against it
+    #[error("Unavailable: {0}")]
+    Unavailable(String),
+
     #[error("Internal error: {0}")]
     Internal(String),
 }
 
+impl AdapterError {
+    /// Receipt reason code for a failed execution
+    pub fn reason_code(&self) -> ReasonCode {
+        match self {
+            AdapterError::Timeout => ReasonCode::Timeout,
+            AdapterError::Unavailable(_) => ReasonCode::Unavailable,
+            _ => ReasonCode::AdapterError,
+        }
+    }
+}
+
 pub type AdapterResult<T> = Result<T, AdapterError>;
@@ -1,26 +1,38 @@
 //! File system adapter
 
 use async_trait::async_trait;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tokio::fs;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
 use tracing::{debug, info};
 
-use aapi_core::types::EffectBucket;
+use aapi_core::types::{EffectBucket, HashAlgorithm};
 use aapi_core::Vakya;
 
-use crate::effect::{CapturedEffect, EffectBuilder, ReversalMethod, StateSnapshot};
+use crate::codec::BodyCodecRegistry;
+use crate::effect::{CapturedEffect, DeltaPrecision, EffectBuilder, ReversalMethod, StateSnapshot};
 use crate::error::{AdapterError, AdapterResult};
-use crate::traits::{Adapter, ActionDescriptor, ExecutionContext, ExecutionResult, HealthStatus};
+use crate::traits::{Adapter, ActionDescriptor, ExecutionContext, ExecutionResult, HealthStatus, ItemError};
 
 /// File system adapter for file operations
 pub struct FileAdapter {
-    /// Base directory for file operations (sandboxing)
-    base_dir: Option<PathBuf>,
+    /// Directories file operations are confined to; unrestricted when empty
+    roots: Vec<PathBuf>,
     /// Maximum file size for read operations
     max_read_size: usize,
     /// Whether to capture full content in effects
     capture_content: bool,
+    /// Hash algorithm for state snapshots
+    hash_algorithm: HashAlgorithm,
+    /// Numeric precision of write deltas
+    delta_precision: DeltaPrecision,
+    /// Decoders for non-JSON bodies
+    codecs: BodyCodecRegistry,
+    /// Held from a precondition check until the mutation it guards is done
+    write_lock: Mutex<()>,
+    /// Most paths one batched `file.exists` or `file.metadata` may probe
+    max_batch_paths: usize,
 }
 
 impl Default for FileAdapter {
@@ -32,17 +44,33 @@ impl Default for FileAdapter {
 impl FileAdapter {
     pub fn new() -> Self {
         Self {
-            base_dir: None,
+            roots: Vec::new(),
             max_read_size: 10 * 1024 * 1024, // 10MB
             capture_content: true,
+            hash_algorithm: HashAlgorithm::Sha256,
+            delta_precision: DeltaPrecision::Compact,
+            codecs: BodyCodecRegistry::default(),
+            write_lock: Mutex::new(()),
+            max_batch_paths: 256,
         }
     }
 
+    /// Confine file operations to a single directory
     pub fn with_base_dir(mut self, base_dir: impl Into<PathBuf>) -> Self {
-        self.base_dir = Some(base_dir.into());
+        self.roots = vec![base_dir.into()];
         self
     }
 
+    /// Confine file operations to paths under any of these directories
+    pub fn with_allowed_roots(mut self, roots: Vec<PathBuf>) -> Self {
+        self.roots = roots;
+        self
+    }
+
+    pub fn allowed_roots(&self) -> &[PathBuf] {
+        &self.roots
+    }
+
     pub fn with_max_read_size(mut self, size: usize) -> Self {
         self.max_read_size = size;
         self
@@ -53,6 +81,29 @@ impl FileAdapter {
         self
     }
 
+    pub fn with_hash_algorithm(mut self, algorithm: HashAlgorithm) -> Self {
+        self.hash_algorithm = algorithm;
+        self
+    }
+
+    /// Record numeric leaf changes in write deltas at full precision
+    pub fn with_delta_precision(mut self, precision: DeltaPrecision) -> Self {
+        self.delta_precision = precision;
+        self
+    }
+
+    /// Replace the codecs used to decode non-JSON bodies
+    pub fn with_body_codecs(mut self, codecs: BodyCodecRegistry) -> Self {
+        self.codecs = codecs;
+        self
+    }
+
+    /// Cap the `paths` array of batched `file.exists` and `file.metadata`
+    pub fn with_max_batch_paths(mut self, max: usize) -> Self {
+        self.max_batch_paths = max;
+        self
+    }
+
     /// Resolve and validate a file path
     fn resolve_path(&self, resource_id: &str) -> AdapterResult<PathBuf> {
         // Remove file: prefix if present
@@ -61,30 +112,38 @@ impl FileAdapter {
             .or_else(|| resource_id.strip_prefix("file://"))
             .unwrap_or(resource_id);
 
-        let path = PathBuf::from(path_str);
+        sandboxed_in_roots(&self.roots, Path::new(path_str))
+    }
 
-        // If base_dir is set, ensure path is within it
-        if let Some(ref base) = self.base_dir {
-            let canonical_base = base.canonicalize().unwrap_or_else(|_| base.clone());
-            
-            // For new files, check parent directory
-            let check_path = if path.exists() {
-                path.canonicalize().map_err(AdapterError::Io)?
-            } else {
-                path.parent()
-                    .map(|p| p.canonicalize().unwrap_or_else(|_| p.to_path_buf()))
-                    .unwrap_or_else(|| PathBuf::from("."))
-            };
+    /// Fail with `Conflict` unless the file at `path` matches the VĀKYA's
+    /// expected version (`v2_karma.version`, or `if_match` in the body)
+    ///
+    /// The expected version is a content hash, labeled with any supported
+    /// algorithm; `NOT_EXISTS` requires the file to be absent.
+    async fn check_precondition(&self, vakya: &Vakya, path: &Path) -> AdapterResult<()> {
+        let expected = match vakya.v2_karma.version.as_deref()
+            .or_else(|| vakya.body.get("if_match").and_then(|v| v.as_str()))
+        {
+            Some(expected) => expected,
+            None => return Ok(()),
+        };
 
-            if !check_path.starts_with(&canonical_base) {
-                return Err(AdapterError::PermissionDenied(format!(
-                    "Path {} is outside base directory",
-                    path.display()
-                )));
+        let current = match fs::read(path).await {
+            Ok(data) => {
+                let (algorithm, _) = HashAlgorithm::split_labeled(expected);
+                algorithm.label(&algorithm.digest_hex(&data))
             }
-        }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => "NOT_EXISTS".to_string(),
+            Err(e) => return Err(e.into()),
+        };
 
-        Ok(path)
+        if !current.eq_ignore_ascii_case(expected) {
+            return Err(AdapterError::Conflict {
+                expected: expected.to_string(),
+                current,
+            });
+        }
+        Ok(())
     }
 
     /// Capture state of a file
@@ -122,11 +181,11 @@ impl FileAdapter {
                 };
 
                 let mut snapshot = if let Some(ref content) = content {
-                    StateSnapshot::from_json(content)
+                    StateSnapshot::from_json_with(content, self.hash_algorithm)
                 } else {
                     // Just compute hash from file
                     match fs::read(path).await {
-                        Ok(data) => StateSnapshot::from_bytes(&data),
+                        Ok(data) => StateSnapshot::from_bytes_with(&data, self.hash_algorithm),
                         Err(_) => StateSnapshot::from_hash("ERROR", 0),
                     }
                 };
@@ -185,18 +244,27 @@ impl FileAdapter {
 
         let duration_ms = start.elapsed().as_millis() as u64;
 
-        // Try to return as JSON if possible
+        // Return JSON as is, other text as a string and only binary as base64
         let data = if let Ok(json) = serde_json::from_slice::<serde_json::Value>(&content) {
             json
         } else {
-            serde_json::json!({
-                "content_type": "application/octet-stream",
-                "size": content.len(),
-                "content_base64": base64::Engine::encode(
-                    &base64::engine::general_purpose::STANDARD,
-                    &content
-                )
-            })
+            let content_type = sniff_content_type(path, &content);
+            match std::str::from_utf8(&content) {
+                Ok(text) if !content_type.binary && !text.contains('\0') => serde_json::json!({
+                    "content_type": content_type.mime,
+                    "charset": "utf-8",
+                    "size": content.len(),
+                    "content": text.strip_prefix('\u{feff}').unwrap_or(text),
+                }),
+                _ => serde_json::json!({
+                    "content_type": if content_type.binary { content_type.mime } else { "application/octet-stream" },
+                    "size": content.len(),
+                    "content_base64": base64::Engine::encode(
+                        &base64::engine::general_purpose::STANDARD,
+                        &content
+                    )
+                }),
+            }
         };
 
         Ok(ExecutionResult::success(data, vec![effect], duration_ms))
@@ -208,14 +276,141 @@ impl FileAdapter {
         vakya: &Vakya,
         path: &PathBuf,
         context: &ExecutionContext,
+    ) -> AdapterResult<ExecutionResult> {
+        // Get content from body
+        let content = self.extract_content(&vakya.body)?;
+        self.write_content(vakya, path, &content, context).await
+    }
+
+    /// Execute file.copy action
+    ///
+    /// The source comes from Apādāna (`v6_apadana.source`), falling back to
+    /// a `source` field in the body; the destination is the Karma resource.
+    async fn execute_copy(
+        &self,
+        vakya: &Vakya,
+        path: &PathBuf,
+        context: &ExecutionContext,
+    ) -> AdapterResult<ExecutionResult> {
+        let source = match &vakya.v6_apadana {
+            Some(apadana) => apadana.source.0.as_str(),
+            None => vakya.body.get("source")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| AdapterError::InvalidInput(
+                    "file.copy requires v6_apadana.source or a 'source' field in the body".to_string()
+                ))?,
+        };
+        let source = self.resolve_path(source)?;
+
+        if !source.is_file() {
+            return Err(AdapterError::NotFound(format!("File not found: {}", source.display())));
+        }
+        let metadata = fs::metadata(&source).await?;
+        if metadata.len() > self.max_read_size as u64 {
+            return Err(AdapterError::InvalidInput(format!(
+                "File too large: {} bytes (max {})",
+                metadata.len(),
+                self.max_read_size
+            )));
+        }
+
+        let content = fs::read(&source).await?;
+        let mut result = self.write_content(vakya, path, &content, context).await?;
+        if let Some(data) = result.data.as_mut() {
+            data["source"] = serde_json::json!(source.to_string_lossy());
+        }
+        Ok(result)
+    }
+
+    /// Execute file.move action
+    ///
+    /// The source is named like file.copy's; the destination is the Karma
+    /// resource. Moves across filesystems fall back to copy-then-delete.
+    async fn execute_move(
+        &self,
+        vakya: &Vakya,
+        path: &PathBuf,
+        context: &ExecutionContext,
     ) -> AdapterResult<ExecutionResult> {
         let start = std::time::Instant::now();
 
-        // Capture before state
+        let source = match &vakya.v6_apadana {
+            Some(apadana) => apadana.source.0.as_str(),
+            None => vakya.body.get("source")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| AdapterError::InvalidInput(
+                    "file.move requires v6_apadana.source or a 'source' field in the body".to_string()
+                ))?,
+        };
+        let source = self.resolve_path(source)?;
+
+        let _guard = self.write_lock.lock().await;
+        if !source.is_file() {
+            return Err(AdapterError::NotFound(format!("File not found: {}", source.display())));
+        }
+        self.check_precondition(vakya, path).await?;
+
         let before = self.capture_state(path).await;
 
-        // Get content from body
-        let content = self.extract_content(&vakya.body)?;
+        if context.dry_run {
+            let duration_ms = start.elapsed().as_millis() as u64;
+            return Ok(ExecutionResult::success(
+                serde_json::json!({"dry_run": true, "would_move": source.to_string_lossy()}),
+                vec![],
+                duration_ms,
+            ));
+        }
+
+        let copied = move_file(&source, path).await?;
+        let after = self.capture_state(path).await;
+
+        // Reversal moves the file back, then restores whatever it replaced
+        let effect = EffectBuilder::new(
+            vakya.vakya_id.0.clone(),
+            if before.hash == "NOT_EXISTS" { EffectBucket::Create } else { EffectBucket::Update },
+            vakya.v2_karma.rid.0.clone(),
+        )
+        .target_type("file")
+        .before(before.clone())
+        .after(after)
+        .reversible(
+            ReversalMethod::InverseOperation,
+            serde_json::json!({
+                "path": path.to_string_lossy(),
+                "source": source.to_string_lossy(),
+                "before_content": before.content,
+            }),
+        )
+        .build();
+
+        let duration_ms = start.elapsed().as_millis() as u64;
+
+        Ok(ExecutionResult::success(
+            serde_json::json!({
+                "path": path.to_string_lossy(),
+                "source": source.to_string_lossy(),
+                "copied": copied,
+            }),
+            vec![effect],
+            duration_ms,
+        ))
+    }
+
+    /// Write `content` to `path`, capturing a reversible effect
+    async fn write_content(
+        &self,
+        vakya: &Vakya,
+        path: &PathBuf,
+        content: &[u8],
+        context: &ExecutionContext,
+    ) -> AdapterResult<ExecutionResult> {
+        let start = std::time::Instant::now();
+
+        let _guard = self.write_lock.lock().await;
+        self.check_precondition(vakya, path).await?;
+
+        // Capture before state
+        let before = self.capture_state(path).await;
 
         if context.dry_run {
             let duration_ms = start.elapsed().as_millis() as u64;
@@ -232,7 +427,7 @@ impl FileAdapter {
         }
 
         // Write file
-        fs::write(path, &content).await?;
+        write_atomic(path, content).await?;
 
         // Capture after state
         let after = self.capture_state(path).await;
@@ -246,6 +441,7 @@ impl FileAdapter {
         .target_type("file")
         .before(before.clone())
         .after(after)
+        .delta_precision(self.delta_precision)
         .reversible(
             ReversalMethod::RestoreState,
             serde_json::json!({
@@ -263,6 +459,7 @@ impl FileAdapter {
                 "path": path.to_string_lossy(),
                 "size": content.len(),
                 "created": before.hash == "NOT_EXISTS",
+                "hash": self.hash_algorithm.label(&self.hash_algorithm.digest_hex(content)),
             }),
             vec![effect],
             duration_ms,
@@ -278,9 +475,11 @@ impl FileAdapter {
     ) -> AdapterResult<ExecutionResult> {
         let start = std::time::Instant::now();
 
+        let _guard = self.write_lock.lock().await;
         if !path.exists() {
             return Err(AdapterError::NotFound(format!("File not found: {}", path.display())));
         }
+        self.check_precondition(vakya, path).await?;
 
         // Capture before state
         let before = self.capture_state(path).await;
@@ -330,6 +529,102 @@ impl FileAdapter {
         ))
     }
 
+    /// Execute file.truncate action
+    ///
+    /// Sets the file to `length` bytes (default 0) with `set_len`, padding
+    /// with zeros when it grows. The prior bytes are captured verbatim so the
+    /// effect restores them; a file too large to capture, or any file when
+    /// content capture is off, is refused rather than truncated irreversibly.
+    async fn execute_truncate(
+        &self,
+        vakya: &Vakya,
+        path: &PathBuf,
+        context: &ExecutionContext,
+    ) -> AdapterResult<ExecutionResult> {
+        let start = std::time::Instant::now();
+
+        let length = match vakya.body.get("length") {
+            None | Some(serde_json::Value::Null) => 0,
+            Some(value) => value.as_u64().ok_or_else(|| AdapterError::InvalidInput(
+                "file.truncate length must be a non-negative integer".to_string()
+            ))?,
+        };
+
+        let _guard = self.write_lock.lock().await;
+        if !path.exists() {
+            return Err(AdapterError::NotFound(format!("File not found: {}", path.display())));
+        }
+        if path.is_dir() {
+            return Err(AdapterError::InvalidInput(format!("Cannot truncate a directory: {}", path.display())));
+        }
+        self.check_precondition(vakya, path).await?;
+
+        let before = self.capture_state(path).await;
+        let before_length = fs::metadata(path).await?.len();
+        if !self.capture_content || before_length > self.max_read_size as u64 {
+            return Err(AdapterError::EffectCapture(format!(
+                "{} ({} bytes) cannot be captured, so truncating it could not be rolled back",
+                path.display(),
+                before_length
+            )));
+        }
+
+        if context.dry_run {
+            let duration_ms = start.elapsed().as_millis() as u64;
+            return Ok(ExecutionResult::success(
+                serde_json::json!({"dry_run": true, "would_truncate": before_length, "length": length}),
+                vec![],
+                duration_ms,
+            ));
+        }
+
+        // Raw bytes, so the restore is exact whatever the content type
+        let data = fs::read(path).await?;
+        let before_content = serde_json::json!({
+            "_type": "binary",
+            "_encoding": "base64",
+            "_data": base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &data),
+        });
+
+        let file = fs::OpenOptions::new().write(true).open(path).await?;
+        file.set_len(length).await?;
+        file.sync_all().await?;
+
+        let after = self.capture_state(path).await;
+
+        let effect = EffectBuilder::new(
+            vakya.vakya_id.0.clone(),
+            EffectBucket::Update,
+            vakya.v2_karma.rid.0.clone(),
+        )
+        .target_type("file")
+        .before(before.clone())
+        .after(after)
+        .delta_precision(self.delta_precision)
+        .metadata("before_length", serde_json::json!(before_length))
+        .reversible(
+            ReversalMethod::RestoreState,
+            serde_json::json!({
+                "path": path.to_string_lossy(),
+                "before_hash": before.hash,
+                "before_content": before_content,
+            }),
+        )
+        .build();
+
+        let duration_ms = start.elapsed().as_millis() as u64;
+
+        Ok(ExecutionResult::success(
+            serde_json::json!({
+                "path": path.to_string_lossy(),
+                "before_length": before_length,
+                "length": length,
+            }),
+            vec![effect],
+            duration_ms,
+        ))
+    }
+
     /// Execute file.list action
     async fn execute_list(
         &self,
@@ -348,10 +643,19 @@ impl FileAdapter {
         }
 
         let mut entries = Vec::new();
+        let mut item_errors = Vec::new();
         let mut dir = fs::read_dir(path).await?;
 
         while let Some(entry) = dir.next_entry().await? {
-            let metadata = entry.metadata().await?;
+            // An entry that cannot be inspected (removed mid-listing, no
+            // permission) is reported without failing the whole listing
+            let metadata = match entry.metadata().await {
+                Ok(metadata) => metadata,
+                Err(e) => {
+                    item_errors.push(ItemError::new(entry.path().to_string_lossy(), e.to_string()));
+                    continue;
+                }
+            };
             entries.push(serde_json::json!({
                 "name": entry.file_name().to_string_lossy(),
                 "path": entry.path().to_string_lossy(),
@@ -371,17 +675,98 @@ impl FileAdapter {
 
         let duration_ms = start.elapsed().as_millis() as u64;
 
-        Ok(ExecutionResult::success(
+        Ok(ExecutionResult::partial(
             serde_json::json!({
                 "path": path.to_string_lossy(),
                 "entries": entries,
                 "count": entries.len(),
             }),
+            item_errors,
             vec![effect],
             duration_ms,
         ))
     }
 
+    /// Execute file.metadata action
+    ///
+    /// Timestamps are RFC 3339. Ones the platform does not record (creation
+    /// time on many Linux filesystems) are omitted rather than failing.
+    async fn execute_metadata(&self, path: &Path) -> AdapterResult<ExecutionResult> {
+        let start = std::time::Instant::now();
+
+        if !path.exists() {
+            return Err(AdapterError::NotFound(format!("File not found: {}", path.display())));
+        }
+        let output = metadata_json(path).await?;
+
+        Ok(ExecutionResult::success(output, vec![], start.elapsed().as_millis() as u64))
+    }
+
+    /// The body's `paths` array for a batched probe, if it has one
+    ///
+    /// Every entry resolves through `resolve_path`, so one path outside the
+    /// sandbox fails the whole batch.
+    fn batch_paths(&self, body: &serde_json::Value) -> AdapterResult<Option<Vec<(String, PathBuf)>>> {
+        let paths = match body.get("paths") {
+            Some(paths) => paths,
+            None => return Ok(None),
+        };
+        let paths = paths.as_array()
+            .ok_or_else(|| AdapterError::InvalidInput("`paths` must be an array of strings".to_string()))?;
+        if paths.len() > self.max_batch_paths {
+            return Err(AdapterError::InvalidInput(format!(
+                "{} paths exceeds the batch limit of {}",
+                paths.len(),
+                self.max_batch_paths
+            )));
+        }
+        paths.iter()
+            .map(|p| {
+                let p = p.as_str()
+                    .ok_or_else(|| AdapterError::InvalidInput("`paths` must be an array of strings".to_string()))?;
+                Ok((p.to_string(), self.resolve_path(p)?))
+            })
+            .collect::<AdapterResult<Vec<_>>>()
+            .map(Some)
+    }
+
+    /// Execute a batched file.exists: `{"exists": {path: bool}}`
+    fn execute_exists_batch(&self, paths: Vec<(String, PathBuf)>) -> ExecutionResult {
+        let exists: serde_json::Map<_, _> = paths.into_iter()
+            .map(|(requested, path)| (requested, serde_json::json!(path.exists())))
+            .collect();
+        ExecutionResult::success(serde_json::json!({"exists": exists}), vec![], 0)
+    }
+
+    /// Execute a batched file.metadata: `{"metadata": {path: {..} | null}}`,
+    /// with null for paths that do not exist and an item error for ones
+    /// that exist but cannot be inspected
+    async fn execute_metadata_batch(&self, paths: Vec<(String, PathBuf)>) -> AdapterResult<ExecutionResult> {
+        let start = std::time::Instant::now();
+
+        let mut metadata = serde_json::Map::new();
+        let mut item_errors = Vec::new();
+        for (requested, path) in paths {
+            if !path.exists() {
+                metadata.insert(requested, serde_json::Value::Null);
+                continue;
+            }
+            match metadata_json(&path).await {
+                Ok(entry) => {
+                    metadata.insert(requested, entry);
+                }
+                Err(e) => item_errors.push(ItemError::new(requested, e.to_string())),
+            }
+        }
+
+        Ok(ExecutionResult::partial(
+            serde_json::json!({"metadata": metadata}),
+            item_errors,
+            vec![],
+            start.elapsed().as_millis() as u64,
+        ))
+    }
+
     /// Extract content from VĀKYA body
     fn extract_content(&self, body: &serde_json::Value) -> AdapterResult<Vec<u8>> {
         // Check for direct content
@@ -420,7 +805,10 @@ impl Adapter for FileAdapter {
         vec![
             "file.read",
             "file.write",
+            "file.copy",
+            "file.move",
             "file.delete",
+            "file.truncate",
             "file.list",
             "file.exists",
             "file.metadata",
@@ -428,6 +816,7 @@ impl Adapter for FileAdapter {
     }
 
     async fn execute(&self, vakya: &Vakya, context: &ExecutionContext) -> AdapterResult<ExecutionResult> {
+        let vakya = &*self.codecs.decode_vakya(vakya)?;
         let path = self.resolve_path(&vakya.v2_karma.rid.0)?;
         let action = &vakya.v3_kriya.action;
 
@@ -436,9 +825,15 @@ impl Adapter for FileAdapter {
         match action.as_str() {
             "file.read" => self.execute_read(vakya, &path, context).await,
             "file.write" => self.execute_write(vakya, &path, context).await,
+            "file.copy" => self.execute_copy(vakya, &path, context).await,
+            "file.move" => self.execute_move(vakya, &path, context).await,
             "file.delete" => self.execute_delete(vakya, &path, context).await,
+            "file.truncate" => self.execute_truncate(vakya, &path, context).await,
             "file.list" => self.execute_list(vakya, &path, context).await,
             "file.exists" => {
+                if let Some(paths) = self.batch_paths(&vakya.body)? {
+                    return Ok(self.execute_exists_batch(paths));
+                }
                 let exists = path.exists();
                 Ok(ExecutionResult::success(
                     serde_json::json!({"exists": exists}),
@@ -446,28 +841,20 @@ impl Adapter for FileAdapter {
                     0,
                 ))
             }
-            "file.metadata" => {
-                if !path.exists() {
-                    return Err(AdapterError::NotFound(format!("File not found: {}", path.display())));
-                }
-                let metadata = fs::metadata(&path).await?;
-                Ok(ExecutionResult::success(
-                    serde_json::json!({
-                        "size": metadata.len(),
-                        "is_file": metadata.is_file(),
-                        "is_dir": metadata.is_dir(),
-                        "readonly": metadata.permissions().readonly(),
-                    }),
-                    vec![],
-                    0,
-                ))
-            }
+            "file.metadata" => match self.batch_paths(&vakya.body)? {
+                Some(paths) => self.execute_metadata_batch(paths).await,
+                None => self.execute_metadata(&path).await,
+            },
             _ => Err(AdapterError::UnsupportedAction(action.clone())),
         }
     }
 
+    fn action_descriptors(&self) -> Vec<ActionDescriptor> {
+        file_action_descriptors()
+    }
+
     fn can_rollback(&self, action: &str) -> bool {
-        matches!(action, "file.write" | "file.delete")
+        matches!(action, "file.write" | "file.copy" | "file.move" | "file.delete" | "file.truncate")
     }
 
     async fn rollback(&self, effect: &CapturedEffect) -> AdapterResult<()> {
@@ -482,25 +869,18 @@ impl Adapter for FileAdapter {
 
         match reversal.method {
             ReversalMethod::RestoreState | ReversalMethod::Recreate => {
-                // Restore from before content
                 if let Some(content) = reversal.data.get("before_content") {
-                    if content.is_null() || content.get("_type").and_then(|v| v.as_str()) == Some("NOT_EXISTS") {
-                        // File didn't exist before, delete it
-                        if path.exists() {
-                            fs::remove_file(&path).await?;
-                        }
-                    } else {
-                        // Restore content
-                        let bytes = if let Some(data) = content.get("_data").and_then(|v| v.as_str()) {
-                            use base64::Engine;
-                            base64::engine::general_purpose::STANDARD
-                                .decode(data)
-                                .map_err(|e| AdapterError::RollbackFailed(e.to_string()))?
-                        } else {
-                            serde_json::to_vec_pretty(content)?
-                        };
-                        fs::write(&path, bytes).await?;
-                    }
+                    restore_content(&path, content).await?;
+                }
+            }
+            ReversalMethod::InverseOperation => {
+                // Undo a move: put the file back, then restore what it replaced
+                let source = reversal.data.get("source")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| AdapterError::RollbackFailed("Missing source in reversal".to_string()))?;
+                move_file(&path, Path::new(source)).await?;
+                if let Some(content) = reversal.data.get("before_content") {
+                    restore_content(&path, content).await?;
                 }
             }
             ReversalMethod::Delete => {
@@ -523,20 +903,243 @@ impl Adapter for FileAdapter {
     async fn health_check(&self) -> AdapterResult<HealthStatus> {
         let start = std::time::Instant::now();
 
-        // Check base directory if set
-        if let Some(ref base) = self.base_dir {
-            if !base.exists() {
-                return Ok(HealthStatus::unhealthy(format!(
-                    "Base directory does not exist: {}",
-                    base.display()
-                )));
-            }
+        // Check sandbox roots if set
+        if let Some(missing) = self.roots.iter().find(|root| !root.exists()) {
+            return Ok(HealthStatus::unhealthy(format!(
+                "Sandbox root does not exist: {}",
+                missing.display()
+            )));
         }
 
         Ok(HealthStatus::healthy().with_latency(start.elapsed().as_millis() as u64))
     }
 }
 
+/// Check that `path` lies within `base_dir`, when one is set
+pub(crate) fn sandboxed_path(base_dir: Option<&Path>, path: &Path) -> AdapterResult<PathBuf> {
+    sandboxed_in_roots(base_dir.as_slice(), path)
+}
+
+/// Check that `path` lies within at least one of `roots`, when any are set
+pub(crate) fn sandboxed_in_roots<P: AsRef<Path>>(roots: &[P], path: &Path) -> AdapterResult<PathBuf> {
+    if roots.is_empty() {
+        return Ok(path.to_path_buf());
+    }
+
+    // `..` under a directory that does not exist yet cannot be resolved
+    // by canonicalize, so refuse it outright
+    if path.components().any(|c| matches!(c, std::path::Component::ParentDir)) {
+        return Err(AdapterError::PermissionDenied(format!(
+            "Path {} must not contain '..'",
+            path.display()
+        )));
+    }
+
+    // For new files, check parent directory
+    let check_path = if path.exists() {
+        path.canonicalize().map_err(AdapterError::Io)?
+    } else {
+        path.parent()
+            .map(|p| p.canonicalize().unwrap_or_else(|_| p.to_path_buf()))
+            .unwrap_or_else(|| PathBuf::from("."))
+    };
+
+    let within = |root: &Path| {
+        let canonical_root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        check_path.starts_with(&canonical_root)
+    };
+    if !roots.iter().any(|root| within(root.as_ref())) {
+        return Err(AdapterError::PermissionDenied(format!(
+            "Path {} is outside the allowed directories",
+            path.display()
+        )));
+    }
+
+    Ok(path.to_path_buf())
+}
+
+/// A file written to a temporary sibling and renamed into place on commit,
+/// so readers never observe a partially written file. The temporary file is
+/// removed if the writer is dropped without committing.
+pub struct AtomicFile {
+    dest: PathBuf,
+    temp: PathBuf,
+    file: Option<fs::File>,
+}
+
+impl AtomicFile {
+    /// Start writing `dest`, creating its parent directories if needed
+    pub async fn create(dest: impl Into<PathBuf>) -> AdapterResult<Self> {
+        let dest = dest.into();
+        let parent = dest.parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."));
+        fs::create_dir_all(parent).await?;
+
+        let name = dest.file_name()
+            .ok_or_else(|| AdapterError::InvalidInput(format!("Not a file path: {}", dest.display())))?;
+        let temp = parent.join(format!(".{}.{}.tmp", name.to_string_lossy(), uuid::Uuid::new_v4()));
+        let file = fs::File::create(&temp).await?;
+
+        Ok(Self { dest, temp, file: Some(file) })
+    }
+
+    pub async fn write_all(&mut self, data: &[u8]) -> AdapterResult<()> {
+        let file = self.file.as_mut()
+            .ok_or_else(|| AdapterError::Internal("Atomic file already committed".to_string()))?;
+        file.write_all(data).await?;
+        Ok(())
+    }
+
+    /// Flush to disk and move the file into place, keeping the permissions
+    /// of any file it replaces
+    pub async fn commit(mut self) -> AdapterResult<PathBuf> {
+        let result = self.finish().await;
+        if result.is_err() {
+            let _ = fs::remove_file(&self.temp).await;
+        }
+        result.map(|()| std::mem::take(&mut self.dest))
+    }
+
+    async fn finish(&mut self) -> AdapterResult<()> {
+        if let Some(mut file) = self.file.take() {
+            file.flush().await?;
+            file.sync_all().await?;
+        }
+        if let Ok(metadata) = fs::metadata(&self.dest).await {
+            fs::set_permissions(&self.temp, metadata.permissions()).await?;
+        }
+        fs::rename(&self.temp, &self.dest).await?;
+        Ok(())
+    }
+}
+
+impl Drop for AtomicFile {
+    fn drop(&mut self) {
+        if self.file.is_some() {
+            let _ = std::fs::remove_file(&self.temp);
+        }
+    }
+}
+
+/// Replace `path` with `content` atomically
+pub async fn write_atomic(path: &Path, content: &[u8]) -> AdapterResult<()> {
+    let mut file = AtomicFile::create(path).await?;
+    file.write_all(content).await?;
+    file.commit().await?;
+    Ok(())
+}
+
+/// Write back a file's captured content; a null or `NOT_EXISTS` capture
+/// means the file did not exist, so it is removed
+async fn restore_content(path: &Path, content: &serde_json::Value) -> AdapterResult<()> {
+    if content.is_null() || content.get("_type").and_then(|v| v.as_str()) == Some("NOT_EXISTS") {
+        if path.exists() {
+            fs::remove_file(path).await?;
+        }
+        return Ok(());
+    }
+
+    let bytes = if let Some(data) = content.get("_data").and_then(|v| v.as_str()) {
+        use base64::Engine;
+        base64::engine::general_purpose::STANDARD
+            .decode(data)
+            .map_err(|e| AdapterError::RollbackFailed(e.to_string()))?
+    } else {
+        serde_json::to_vec_pretty(content)?
+    };
+    fs::write(path, bytes).await?;
+    Ok(())
+}
+
+/// Move `source` to `dest`, creating `dest`'s parent directories
+///
+/// A rename is atomic but cannot cross filesystems; on `EXDEV` the file is
+/// copied instead. Returns whether the copy fallback was taken.
+pub async fn move_file(source: &Path, dest: &Path) -> AdapterResult<bool> {
+    if let Some(parent) = dest.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).await?;
+    }
+    match fs::rename(source, dest).await {
+        Ok(()) => Ok(false),
+        Err(e) if crosses_devices(&e) => {
+            copy_then_remove(source, dest).await?;
+            Ok(true)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Whether a rename failed because its paths are on different filesystems
+fn crosses_devices(e: &std::io::Error) -> bool {
+    #[cfg(unix)]
+    let cross_device = Some(libc::EXDEV);
+    // ERROR_NOT_SAME_DEVICE
+    #[cfg(windows)]
+    let cross_device = Some(17);
+    #[cfg(not(any(unix, windows)))]
+    let cross_device = None;
+    cross_device.is_some_and(|code| e.raw_os_error() == Some(code))
+}
+
+/// Copy `source` into a temporary file beside `dest`, rename it into place,
+/// then delete `source`. Readers of `dest` never see a partial file, and
+/// `source` is only removed once `dest` is complete.
+async fn copy_then_remove(source: &Path, dest: &Path) -> AdapterResult<()> {
+    use tokio::io::AsyncReadExt;
+
+    let permissions = fs::metadata(source).await?.permissions();
+    let mut reader = fs::File::open(source).await?;
+    let mut file = AtomicFile::create(dest).await?;
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = reader.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        file.write_all(&buf[..n]).await?;
+    }
+    file.commit().await?;
+    fs::set_permissions(dest, permissions).await?;
+    fs::remove_file(source).await?;
+    Ok(())
+}
+
+/// Metadata of an existing file as reported by `file.metadata`
+async fn metadata_json(path: &Path) -> AdapterResult<serde_json::Value> {
+    let metadata = fs::metadata(path).await?;
+    let is_symlink = fs::symlink_metadata(path).await?.file_type().is_symlink();
+
+    let mut output = serde_json::json!({
+        "size": metadata.len(),
+        "is_file": metadata.is_file(),
+        "is_dir": metadata.is_dir(),
+        "is_symlink": is_symlink,
+        "readonly": metadata.permissions().readonly(),
+    });
+
+    let times = [
+        ("modified", metadata.modified()),
+        ("created", metadata.created()),
+        ("accessed", metadata.accessed()),
+    ];
+    for (field, time) in times {
+        if let Ok(time) = time {
+            output[field] = serde_json::json!(
+                chrono::DateTime::<chrono::Utc>::from(time).to_rfc3339()
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        output["mode"] = serde_json::json!(format!("{:o}", metadata.permissions().mode() & 0o7777));
+    }
+
+    Ok(output)
+}
+
 /// Get action descriptors for the file adapter
 pub fn file_action_descriptors() -> Vec<ActionDescriptor> {
     vec![
@@ -546,21 +1149,76 @@ pub fn file_action_descriptors() -> Vec<ActionDescriptor> {
         ActionDescriptor::new("file.write", "Write content to file")
             .with_effect(EffectBucket::Update)
             .reversible(),
+        ActionDescriptor::new("file.copy", "Copy the Apādāna source file to the resource")
+            .with_effect(EffectBucket::Update)
+            .reversible(),
+        ActionDescriptor::new("file.move", "Move the Apādāna source file to the resource")
+            .with_effect(EffectBucket::Update)
+            .reversible(),
         ActionDescriptor::new("file.delete", "Delete a file")
             .with_effect(EffectBucket::Delete)
             .reversible(),
+        ActionDescriptor::new("file.truncate", "Set a file's length, to `length` bytes or empty")
+            .with_effect(EffectBucket::Update)
+            .reversible(),
         ActionDescriptor::new("file.list", "List directory contents")
             .with_effect(EffectBucket::Read)
             .idempotent(),
-        ActionDescriptor::new("file.exists", "Check if file exists")
+        ActionDescriptor::new("file.exists", "Check if a file, or each of `paths`, exists")
             .with_effect(EffectBucket::None)
             .idempotent(),
-        ActionDescriptor::new("file.metadata", "Get file metadata")
+        ActionDescriptor::new("file.metadata", "Get metadata of a file, or of each of `paths`")
             .with_effect(EffectBucket::Read)
             .idempotent(),
     ]
 }
 
+/// Media type of a file's content and whether it is binary
+struct ContentType {
+    mime: &'static str,
+    binary: bool,
+}
+
+/// Signatures of common binary formats, checked before the extension
+const MAGIC_NUMBERS: &[(&[u8], &str)] = &[
+    (b"\x89PNG\r\n\x1a\n", "image/png"),
+    (b"\xff\xd8\xff", "image/jpeg"),
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"%PDF-", "application/pdf"),
+    (b"PK\x03\x04", "application/zip"),
+    (b"\x1f\x8b", "application/gzip"),
+    (b"\x7fELF", "application/x-elf"),
+];
+
+/// Sniff a file's media type from its leading bytes, then its extension
+///
+/// Unrecognised content is `text/plain` when it decodes as UTF-8 and
+/// `application/octet-stream` otherwise.
+fn sniff_content_type(path: &Path, content: &[u8]) -> ContentType {
+    if let Some((_, mime)) = MAGIC_NUMBERS.iter().find(|(magic, _)| content.starts_with(magic)) {
+        return ContentType { mime, binary: true };
+    }
+
+    let extension = path.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase);
+    let text = |mime| ContentType { mime, binary: false };
+    match extension.as_deref() {
+        Some("json") => text("application/json"),
+        Some("csv") => text("text/csv"),
+        Some("tsv") => text("text/tab-separated-values"),
+        Some("md" | "markdown") => text("text/markdown"),
+        Some("html" | "htm") => text("text/html"),
+        Some("css") => text("text/css"),
+        Some("js" | "mjs") => text("text/javascript"),
+        Some("xml") => text("application/xml"),
+        Some("yaml" | "yml") => text("application/yaml"),
+        Some("toml") => text("application/toml"),
+        Some("txt" | "log") => text("text/plain"),
+        _ if std::str::from_utf8(content).is_ok() => text("text/plain"),
+        _ => ContentType { mime: "application/octet-stream", binary: true },
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -591,6 +1249,7 @@ mod tests {
                 ttl: Some(TtlConstraint {
                     expires_at: Timestamp(chrono::Utc::now() + chrono::Duration::hours(1)),
                     max_duration_ms: None,
+                    not_before: None,
                 }),
                 budgets: vec![],
                 approval_lane: ApprovalLane::None,
@@ -632,6 +1291,205 @@ mod tests {
         assert!(read_result.success);
     }
 
+    #[tokio::test]
+    async fn test_file_truncate_is_reversible() {
+        let temp_dir = TempDir::new().unwrap();
+        let adapter = FileAdapter::new().with_base_dir(temp_dir.path());
+        let context = ExecutionContext::default();
+
+        let path = temp_dir.path().join("app.log");
+        std::fs::write(&path, b"line one\nline two\n").unwrap();
+        let resource = format!("file:{}", path.display());
+
+        let result = adapter
+            .execute(&create_test_vakya("file.truncate", &resource, serde_json::json!({"length": 9})), &context)
+            .await
+            .unwrap();
+        assert_eq!(result.data.unwrap()["before_length"], 18);
+        assert_eq!(std::fs::read(&path).unwrap(), b"line one\n");
+        assert_eq!(result.effects[0].bucket, EffectBucket::Update);
+
+        adapter.rollback(&result.effects[0]).await.unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"line one\nline two\n");
+
+        // Without a length the file is emptied
+        adapter
+            .execute(&create_test_vakya("file.truncate", &resource, serde_json::json!({})), &context)
+            .await
+            .unwrap();
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), 0);
+
+        let dir = create_test_vakya("file.truncate", &format!("file:{}", temp_dir.path().display()), serde_json::json!({}));
+        assert!(matches!(adapter.execute(&dir, &context).await, Err(AdapterError::InvalidInput(_))));
+
+        let outside = create_test_vakya("file.truncate", "file:/etc/hostname", serde_json::json!({}));
+        assert!(matches!(adapter.execute(&outside, &context).await, Err(AdapterError::PermissionDenied(_))));
+
+        // Content too large to capture is left alone rather than lost
+        std::fs::write(&path, b"line one\nline two\n").unwrap();
+        let small = FileAdapter::new().with_base_dir(temp_dir.path()).with_max_read_size(8);
+        let refused = small
+            .execute(&create_test_vakya("file.truncate", &resource, serde_json::json!({})), &context)
+            .await;
+        assert!(matches!(refused, Err(AdapterError::EffectCapture(_))));
+        assert_eq!(std::fs::read(&path).unwrap(), b"line one\nline two\n");
+    }
+
+    #[tokio::test]
+    async fn test_file_read_reports_content_type() {
+        let temp_dir = TempDir::new().unwrap();
+        let adapter = FileAdapter::new().with_base_dir(temp_dir.path());
+        let context = ExecutionContext::default();
+        let read = |name: &str, content: &[u8]| {
+            let path = temp_dir.path().join(name);
+            std::fs::write(&path, content).unwrap();
+            create_test_vakya("file.read", &format!("file:{}", path.display()), serde_json::json!({}))
+        };
+
+        let csv = adapter.execute(&read("rows.csv", b"id,name\n1,ada\n"), &context).await.unwrap().data.unwrap();
+        assert_eq!(csv["content_type"], "text/csv");
+        assert_eq!(csv["charset"], "utf-8");
+        assert_eq!(csv["content"], "id,name\n1,ada\n");
+
+        let notes = adapter.execute(&read("notes", "# Notes\u{2014}draft".as_bytes()), &context).await.unwrap().data.unwrap();
+        assert_eq!(notes["content_type"], "text/plain");
+        assert_eq!(notes["content"], "# Notes\u{2014}draft");
+
+        // Binary content keeps base64, with its real type when recognised
+        let png = adapter.execute(&read("image.txt", b"\x89PNG\r\n\x1a\n\x00\x01"), &context).await.unwrap().data.unwrap();
+        assert_eq!(png["content_type"], "image/png");
+        assert!(png.get("content").is_none());
+        assert!(png["content_base64"].is_string());
+
+        let blob = adapter.execute(&read("blob.csv", &[0xff, 0xfe, 0x00]), &context).await.unwrap().data.unwrap();
+        assert_eq!(blob["content_type"], "application/octet-stream");
+        assert!(blob["content_base64"].is_string());
+
+        // JSON is still returned parsed
+        let json = adapter.execute(&read("doc.json", br#"{"a":1}"#), &context).await.unwrap().data.unwrap();
+        assert_eq!(json, serde_json::json!({"a": 1}));
+    }
+
+    #[tokio::test]
+    async fn test_file_write_decodes_non_json_bodies() {
+        let temp_dir = TempDir::new().unwrap();
+        let adapter = FileAdapter::new().with_base_dir(temp_dir.path());
+        let context = ExecutionContext::default();
+
+        let file_path = temp_dir.path().join("from_yaml.txt");
+        let resource = format!("file:{}", file_path.display());
+
+        let mut vakya = create_test_vakya("file.write", &resource, serde_json::json!("content: from yaml\n"));
+        vakya.body_type.content_type = "application/yaml".to_string();
+        assert!(adapter.execute(&vakya, &context).await.unwrap().success);
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "from yaml");
+
+        let mut vakya = create_test_vakya("file.write", &resource, serde_json::json!("content=from+form"));
+        vakya.body_type.content_type = "application/x-www-form-urlencoded".to_string();
+        assert!(adapter.execute(&vakya, &context).await.unwrap().success);
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "from form");
+
+        vakya.body_type.content_type = "application/xml".to_string();
+        assert!(matches!(
+            adapter.execute(&vakya, &context).await,
+            Err(AdapterError::InvalidInput(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_file_copy_from_apadana() {
+        let temp_dir = TempDir::new().unwrap();
+        let adapter = FileAdapter::new().with_base_dir(temp_dir.path());
+        let context = ExecutionContext::default();
+
+        let source = temp_dir.path().join("source.txt");
+        std::fs::write(&source, "copy me").unwrap();
+        let dest = temp_dir.path().join("nested/dest.txt");
+        let resource = format!("file:{}", dest.display());
+
+        let mut vakya = create_test_vakya("file.copy", &resource, serde_json::json!({}));
+        vakya.v6_apadana = Some(Apadana {
+            source: ResourceId::new(format!("file:{}", source.display())),
+            source_type: Some("file".to_string()),
+            location: None,
+        });
+        let result = adapter.execute(&vakya, &context).await.unwrap();
+        assert!(result.success);
+        assert_eq!(std::fs::read_to_string(&dest).unwrap(), "copy me");
+        assert_eq!(result.data.unwrap()["source"], source.to_string_lossy().as_ref());
+
+        // Rolling back removes the copy it created
+        adapter.rollback(&result.effects[0]).await.unwrap();
+        assert!(!dest.exists());
+
+        // Without Apādāna the body names the source
+        let vakya = create_test_vakya(
+            "file.copy",
+            &resource,
+            serde_json::json!({"source": format!("file:{}", source.display())}),
+        );
+        assert!(adapter.execute(&vakya, &context).await.unwrap().success);
+
+        let vakya = create_test_vakya("file.copy", &resource, serde_json::json!({"source": "/etc/passwd"}));
+        assert!(matches!(adapter.execute(&vakya, &context).await, Err(AdapterError::PermissionDenied(_))));
+    }
+
+    #[tokio::test]
+    async fn test_file_move_and_rollback() {
+        let temp_dir = TempDir::new().unwrap();
+        let adapter = FileAdapter::new().with_base_dir(temp_dir.path());
+        let context = ExecutionContext::default();
+
+        let source = temp_dir.path().join("source.txt");
+        std::fs::write(&source, "move me").unwrap();
+        let dest = temp_dir.path().join("archive/dest.txt");
+        std::fs::create_dir_all(dest.parent().unwrap()).unwrap();
+        std::fs::write(&dest, "old").unwrap();
+        let resource = format!("file:{}", dest.display());
+
+        let vakya = create_test_vakya(
+            "file.move",
+            &resource,
+            serde_json::json!({"source": format!("file:{}", source.display())}),
+        );
+        let result = adapter.execute(&vakya, &context).await.unwrap();
+        assert!(result.success);
+        assert_eq!(result.data.unwrap()["copied"], false);
+        assert!(!source.exists());
+        assert_eq!(std::fs::read_to_string(&dest).unwrap(), "move me");
+
+        // Rolling back moves the file home and restores what it replaced
+        adapter.rollback(&result.effects[0]).await.unwrap();
+        assert_eq!(std::fs::read_to_string(&source).unwrap(), "move me");
+        assert_eq!(std::fs::read_to_string(&dest).unwrap(), "old");
+
+        // Both ends are sandboxed
+        let vakya = create_test_vakya("file.move", &resource, serde_json::json!({"source": "/etc/hostname"}));
+        assert!(matches!(adapter.execute(&vakya, &context).await, Err(AdapterError::PermissionDenied(_))));
+        let vakya = create_test_vakya(
+            "file.move",
+            "file:/tmp/outside.txt",
+            serde_json::json!({"source": format!("file:{}", source.display())}),
+        );
+        assert!(matches!(adapter.execute(&vakya, &context).await, Err(AdapterError::PermissionDenied(_))));
+        assert!(source.exists());
+    }
+
+    #[tokio::test]
+    async fn test_cross_device_copy_fallback() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("source.bin");
+        std::fs::write(&source, vec![7u8; 200 * 1024]).unwrap();
+        let dest = temp_dir.path().join("other/dest.bin");
+        std::fs::create_dir_all(dest.parent().unwrap()).unwrap();
+
+        copy_then_remove(&source, &dest).await.unwrap();
+        assert!(!source.exists());
+        assert_eq!(std::fs::read(&dest).unwrap(), vec![7u8; 200 * 1024]);
+        // No temporary file is left beside the destination
+        assert_eq!(std::fs::read_dir(dest.parent().unwrap()).unwrap().count(), 1);
+    }
+
     #[tokio::test]
     async fn test_file_delete() {
         let temp_dir = TempDir::new().unwrap();
@@ -649,6 +1507,115 @@ mod tests {
         assert!(!file_path.exists());
     }
 
+    #[tokio::test]
+    async fn test_conditional_write_and_delete() {
+        let temp_dir = TempDir::new().unwrap();
+        let adapter = FileAdapter::new().with_base_dir(temp_dir.path());
+        let context = ExecutionContext::default();
+
+        let file_path = temp_dir.path().join("shared.txt");
+        let resource = format!("file:{}", file_path.display());
+
+        // Create only if absent
+        let vakya = create_test_vakya("file.write", &resource, serde_json::json!({"content": "v1", "if_match": "NOT_EXISTS"}));
+        let base = adapter.execute(&vakya, &context).await.unwrap().data.unwrap()["hash"].as_str().unwrap().to_string();
+
+        // Another writer moves the file on
+        let vakya = create_test_vakya("file.write", &resource, serde_json::json!({"content": "v2"}));
+        let current = adapter.execute(&vakya, &context).await.unwrap().data.unwrap()["hash"].as_str().unwrap().to_string();
+
+        // A write against the stale base is rejected with the current hash
+        let mut vakya = create_test_vakya("file.write", &resource, serde_json::json!({"content": "v3"}));
+        vakya.v2_karma.version = Some(base.clone());
+        match adapter.execute(&vakya, &context).await {
+            Err(AdapterError::Conflict { expected, current: actual }) => {
+                assert_eq!(expected, base);
+                assert_eq!(actual, current);
+            }
+            other => panic!("expected conflict, got {:?}", other.map(|r| r.data)),
+        }
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "v2");
+
+        // Retrying against the current hash succeeds
+        vakya.v2_karma.version = Some(current.clone());
+        assert!(adapter.execute(&vakya, &context).await.unwrap().success);
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "v3");
+
+        // Other algorithms are accepted when labeled
+        let mut vakya = create_test_vakya("file.delete", &resource, serde_json::json!({}));
+        vakya.v2_karma.version = Some(current);
+        assert!(matches!(adapter.execute(&vakya, &context).await, Err(AdapterError::Conflict { .. })));
+        vakya.v2_karma.version = Some(format!("blake3:{}", HashAlgorithm::Blake3.digest_hex(b"v3")));
+        assert!(adapter.execute(&vakya, &context).await.unwrap().success);
+        assert!(!file_path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_file_metadata() {
+        let temp_dir = TempDir::new().unwrap();
+        let adapter = FileAdapter::new().with_base_dir(temp_dir.path());
+        let context = ExecutionContext::default();
+
+        let file_path = temp_dir.path().join("meta.txt");
+        std::fs::write(&file_path, "metadata").unwrap();
+
+        let resource = format!("file:{}", file_path.display());
+        let vakya = create_test_vakya("file.metadata", &resource, serde_json::json!({}));
+        let result = adapter.execute(&vakya, &context).await.unwrap();
+        let output = result.data.unwrap();
+
+        assert_eq!(output["size"], 8);
+        assert_eq!(output["is_file"], true);
+        assert_eq!(output["is_symlink"], false);
+        let modified = output["modified"].as_str().unwrap();
+        assert!(chrono::DateTime::parse_from_rfc3339(modified).is_ok());
+        assert!(output["accessed"].is_string());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&file_path, std::fs::Permissions::from_mode(0o640)).unwrap();
+            let link_path = temp_dir.path().join("meta.link");
+            std::os::unix::fs::symlink(&file_path, &link_path).unwrap();
+
+            let resource = format!("file:{}", link_path.display());
+            let vakya = create_test_vakya("file.metadata", &resource, serde_json::json!({}));
+            let output = adapter.execute(&vakya, &context).await.unwrap().data.unwrap();
+            assert_eq!(output["is_symlink"], true);
+            assert_eq!(output["is_file"], true);
+            assert_eq!(output["mode"], "640");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batched_exists_and_metadata() {
+        let temp_dir = TempDir::new().unwrap();
+        let adapter = FileAdapter::new().with_base_dir(temp_dir.path()).with_max_batch_paths(3);
+        let context = ExecutionContext::default();
+
+        let present = temp_dir.path().join("present.txt");
+        std::fs::write(&present, "here").unwrap();
+        let present = present.display().to_string();
+        let absent = temp_dir.path().join("absent.txt").display().to_string();
+        let resource = format!("file:{}", temp_dir.path().display());
+
+        let vakya = create_test_vakya("file.exists", &resource, serde_json::json!({"paths": [present, absent]}));
+        let output = adapter.execute(&vakya, &context).await.unwrap().data.unwrap();
+        assert_eq!(output["exists"][&present], true);
+        assert_eq!(output["exists"][&absent], false);
+
+        let vakya = create_test_vakya("file.metadata", &resource, serde_json::json!({"paths": [present, absent]}));
+        let output = adapter.execute(&vakya, &context).await.unwrap().data.unwrap();
+        assert_eq!(output["metadata"][&present]["size"], 4);
+        assert!(output["metadata"][&absent].is_null());
+
+        // Every path is sandboxed, and the batch size is capped
+        let vakya = create_test_vakya("file.exists", &resource, serde_json::json!({"paths": [present, "/etc/passwd"]}));
+        assert!(adapter.execute(&vakya, &context).await.is_err());
+        let vakya = create_test_vakya("file.exists", &resource, serde_json::json!({"paths": [present, present, present, present]}));
+        assert!(matches!(adapter.execute(&vakya, &context).await, Err(AdapterError::InvalidInput(_))));
+    }
+
     #[tokio::test]
     async fn test_path_sandboxing() {
         let temp_dir = TempDir::new().unwrap();
@@ -658,4 +1625,37 @@ mod tests {
         let result = adapter.resolve_path("/etc/passwd");
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_allowed_roots() {
+        let data = TempDir::new().unwrap();
+        let shared = TempDir::new().unwrap();
+        let outside = TempDir::new().unwrap();
+        let adapter = FileAdapter::new()
+            .with_allowed_roots(vec![data.path().to_path_buf(), shared.path().to_path_buf()]);
+        let context = ExecutionContext::default();
+
+        // Allowed under the second root, for new and existing files
+        let report = shared.path().join("report.txt");
+        let write = create_test_vakya(
+            "file.write",
+            &format!("file:{}", report.display()),
+            serde_json::json!({"content": "q3"}),
+        );
+        assert!(adapter.execute(&write, &context).await.unwrap().success);
+        assert!(adapter.resolve_path(&report.display().to_string()).is_ok());
+
+        let stray = outside.path().join("stray.txt");
+        std::fs::write(&stray, "stray").unwrap();
+        let read = create_test_vakya("file.read", &format!("file:{}", stray.display()), serde_json::json!({}));
+        assert!(matches!(adapter.execute(&read, &context).await, Err(AdapterError::PermissionDenied(_))));
+
+        // A symlink in an allowed root still resolves outside every root
+        #[cfg(unix)]
+        {
+            let link = data.path().join("escape.txt");
+            std::os::unix::fs::symlink(&stray, &link).unwrap();
+            assert!(adapter.resolve_path(&link.display().to_string()).is_err());
+        }
+    }
 }
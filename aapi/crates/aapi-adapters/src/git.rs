@@ -0,0 +1,687 @@
+//! Git adapter for version-controlled resources
+//!
+//! Operates on a configured local clone. Resource IDs are paths inside the
+//! repository tree (`git:config/app.yaml`); the ref or branch is taken from
+//! the VĀKYA body. Commits are written directly to the branch ref without
+//! touching the working tree, so bare clones work too. libgit2 is blocking,
+//! so every operation runs on the blocking thread pool.
+
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use git2::{BranchType, ErrorCode, Index, IndexEntry, IndexTime, ObjectType, Oid, Repository, Signature};
+use tracing::{debug, info};
+
+use aapi_core::types::EffectBucket;
+use aapi_core::Vakya;
+
+use crate::effect::{CapturedEffect, EffectBuilder, ReversalMethod, StateSnapshot};
+use crate::error::{AdapterError, AdapterResult};
+use crate::traits::{Adapter, ActionDescriptor, ExecutionContext, ExecutionResult, HealthStatus};
+
+/// Git adapter for `git.read`, `git.list` and `git.commit`
+pub struct GitAdapter {
+    /// Local clone all operations are confined to
+    repo_dir: PathBuf,
+    /// Branch used when the body names none
+    default_branch: String,
+    /// Maximum blob size for read operations
+    max_read_size: usize,
+    /// Committer identity
+    committer_name: String,
+    committer_email: String,
+}
+
+impl GitAdapter {
+    pub fn new(repo_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            repo_dir: repo_dir.into(),
+            default_branch: "main".to_string(),
+            max_read_size: 10 * 1024 * 1024, // 10MB
+            committer_name: "AAPI".to_string(),
+            committer_email: "aapi@localhost".to_string(),
+        }
+    }
+
+    pub fn with_default_branch(mut self, branch: impl Into<String>) -> Self {
+        self.default_branch = branch.into();
+        self
+    }
+
+    pub fn with_max_read_size(mut self, size: usize) -> Self {
+        self.max_read_size = size;
+        self
+    }
+
+    pub fn with_committer(mut self, name: impl Into<String>, email: impl Into<String>) -> Self {
+        self.committer_name = name.into();
+        self.committer_email = email.into();
+        self
+    }
+
+    /// Run a blocking libgit2 operation against the repository
+    async fn with_repo<T, F>(&self, op: F) -> AdapterResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Repository) -> AdapterResult<T> + Send + 'static,
+    {
+        let repo_dir = self.repo_dir.clone();
+        tokio::task::spawn_blocking(move || {
+            let repo = Repository::open(&repo_dir).map_err(git_error)?;
+            op(&repo)
+        })
+        .await
+        .map_err(|e| AdapterError::Internal(e.to_string()))?
+    }
+
+    /// Ref named in the body, or the default branch
+    fn body_ref(&self, vakya: &Vakya, key: &str) -> String {
+        vakya.body.get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or(&self.default_branch)
+            .to_string()
+    }
+
+    /// Execute git.read action
+    async fn execute_read(&self, vakya: &Vakya, path: String) -> AdapterResult<ExecutionResult> {
+        let start = std::time::Instant::now();
+        if path.is_empty() {
+            return Err(AdapterError::InvalidInput("git.read requires a file path".to_string()));
+        }
+
+        let reference = self.body_ref(vakya, "ref");
+        let max_read_size = self.max_read_size;
+        let (commit, content) = {
+            let path = path.clone();
+            let reference = reference.clone();
+            self.with_repo(move |repo| {
+                let commit = repo.revparse_single(&reference)
+                    .and_then(|o| o.peel_to_commit())
+                    .map_err(git_error)?;
+                let entry = commit.tree()
+                    .and_then(|tree| tree.get_path(Path::new(&path)))
+                    .map_err(|_| AdapterError::NotFound(format!("{} not found at {}", path, reference)))?;
+                let blob = entry.to_object(repo)
+                    .and_then(|o| o.peel_to_blob())
+                    .map_err(|_| AdapterError::InvalidInput(format!("Not a file: {}", path)))?;
+
+                if blob.size() > max_read_size {
+                    return Err(AdapterError::InvalidInput(format!(
+                        "File too large: {} bytes (max {})",
+                        blob.size(),
+                        max_read_size
+                    )));
+                }
+                Ok((commit.id().to_string(), blob.content().to_vec()))
+            })
+            .await?
+        };
+
+        let effect = EffectBuilder::new(
+            vakya.vakya_id.0.clone(),
+            EffectBucket::Read,
+            vakya.v2_karma.rid.0.clone(),
+        )
+        .target_type("git")
+        .after(StateSnapshot::from_bytes(&content))
+        .metadata("ref", serde_json::json!(reference))
+        .metadata("commit", serde_json::json!(commit))
+        .build();
+
+        let mut data = serde_json::json!({
+            "path": path,
+            "ref": reference,
+            "commit": commit,
+            "size": content.len(),
+        });
+        match String::from_utf8(content) {
+            Ok(text) => data["content"] = serde_json::json!(text),
+            Err(e) => {
+                data["content_base64"] = serde_json::json!(base64::Engine::encode(
+                    &base64::engine::general_purpose::STANDARD,
+                    e.as_bytes()
+                ));
+            }
+        }
+
+        Ok(ExecutionResult::success(data, vec![effect], start.elapsed().as_millis() as u64))
+    }
+
+    /// Execute git.list action
+    async fn execute_list(&self, vakya: &Vakya, path: String) -> AdapterResult<ExecutionResult> {
+        let start = std::time::Instant::now();
+
+        let reference = self.body_ref(vakya, "ref");
+        let (commit, entries) = {
+            let path = path.clone();
+            let reference = reference.clone();
+            self.with_repo(move |repo| {
+                let commit = repo.revparse_single(&reference)
+                    .and_then(|o| o.peel_to_commit())
+                    .map_err(git_error)?;
+                let root = commit.tree().map_err(git_error)?;
+                let tree = if path.is_empty() {
+                    root
+                } else {
+                    root.get_path(Path::new(&path))
+                        .and_then(|entry| entry.to_object(repo))
+                        .and_then(|o| o.peel_to_tree())
+                        .map_err(|_| AdapterError::NotFound(format!("Directory not found: {}", path)))?
+                };
+
+                let entries: Vec<serde_json::Value> = tree.iter()
+                    .map(|entry| {
+                        let name = entry.name().unwrap_or_default().to_string();
+                        serde_json::json!({
+                            "path": if path.is_empty() { name.clone() } else { format!("{}/{}", path, name) },
+                            "name": name,
+                            "is_dir": entry.kind() == Some(ObjectType::Tree),
+                            "oid": entry.id().to_string(),
+                        })
+                    })
+                    .collect();
+                Ok((commit.id().to_string(), entries))
+            })
+            .await?
+        };
+
+        let effect = EffectBuilder::new(
+            vakya.vakya_id.0.clone(),
+            EffectBucket::Read,
+            vakya.v2_karma.rid.0.clone(),
+        )
+        .target_type("git")
+        .metadata("ref", serde_json::json!(reference))
+        .metadata("commit", serde_json::json!(commit))
+        .build();
+
+        Ok(ExecutionResult::success(
+            serde_json::json!({
+                "path": path,
+                "ref": reference,
+                "commit": commit,
+                "entries": entries,
+                "count": entries.len(),
+            }),
+            vec![effect],
+            start.elapsed().as_millis() as u64,
+        ))
+    }
+
+    /// Execute git.commit action
+    ///
+    /// The body carries `message`, an optional `branch`, and `files`: a map
+    /// of paths (relative to the resource path) to new content, with `null`
+    /// deleting the file. A missing branch is created from the default branch.
+    async fn execute_commit(
+        &self,
+        vakya: &Vakya,
+        base: String,
+        context: &ExecutionContext,
+    ) -> AdapterResult<ExecutionResult> {
+        let start = std::time::Instant::now();
+
+        let branch = self.body_ref(vakya, "branch");
+        if !git2::Branch::name_is_valid(&branch).unwrap_or(false) {
+            return Err(AdapterError::InvalidInput(format!("Invalid branch name: {}", branch)));
+        }
+        let message = vakya.body.get("message")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| AdapterError::InvalidInput("Missing 'message' in body".to_string()))?;
+        let changes = extract_changes(&base, &vakya.body)?;
+
+        if context.dry_run {
+            return Ok(ExecutionResult::success(
+                serde_json::json!({
+                    "dry_run": true,
+                    "branch": branch,
+                    "would_change": changes.iter().map(|(path, _)| path).collect::<Vec<_>>(),
+                }),
+                vec![],
+                start.elapsed().as_millis() as u64,
+            ));
+        }
+
+        let message = format!("{}\n\nAAPI-Vakya: {}\n", message.trim_end(), vakya.vakya_id.0);
+        let author_name = vakya.v1_karta.pid.0.clone();
+        let committer = (self.committer_name.clone(), self.committer_email.clone());
+        let default_branch = self.default_branch.clone();
+        let paths: Vec<String> = changes.iter().map(|(path, _)| path.clone()).collect();
+
+        let (before, after) = {
+            let branch = branch.clone();
+            self.with_repo(move |repo| {
+                let before = branch_tip(repo, &branch)?;
+                let parent = match before {
+                    Some(oid) => Some(oid),
+                    None => branch_tip(repo, &default_branch)?,
+                };
+                let parent = parent.map(|oid| repo.find_commit(oid)).transpose().map_err(git_error)?;
+
+                let mut index = Index::new().map_err(git_error)?;
+                if let Some(parent) = &parent {
+                    index.read_tree(&parent.tree().map_err(git_error)?).map_err(git_error)?;
+                }
+                for (path, content) in &changes {
+                    match content {
+                        Some(bytes) => {
+                            let oid = repo.blob(bytes).map_err(git_error)?;
+                            index.add(&blob_entry(path, oid, bytes.len())).map_err(git_error)?;
+                        }
+                        None => {
+                            if index.get_path(Path::new(path), 0).is_none() {
+                                return Err(AdapterError::NotFound(format!("{} not found on {}", path, branch)));
+                            }
+                            index.remove_path(Path::new(path)).map_err(git_error)?;
+                        }
+                    }
+                }
+
+                let tree = repo.find_tree(index.write_tree_to(repo).map_err(git_error)?).map_err(git_error)?;
+                if let Some(parent) = &parent {
+                    if parent.tree_id() == tree.id() {
+                        return Err(AdapterError::InvalidInput("Commit would not change any files".to_string()));
+                    }
+                }
+
+                let author = Signature::now(&author_name, &committer.1).map_err(git_error)?;
+                let committer = Signature::now(&committer.0, &committer.1).map_err(git_error)?;
+                let parents: Vec<&git2::Commit> = parent.iter().collect();
+
+                // Creating the branch goes through `reference`; updating it
+                // goes through `commit`, which fails if the tip moved meanwhile.
+                let after = if before.is_none() {
+                    let oid = repo.commit(None, &author, &committer, &message, &tree, &parents).map_err(git_error)?;
+                    repo.reference(&branch_ref(&branch), oid, false, "aapi: create branch").map_err(git_error)?;
+                    oid
+                } else {
+                    repo.commit(Some(&branch_ref(&branch)), &author, &committer, &message, &tree, &parents)
+                        .map_err(git_error)?
+                };
+
+                Ok((before.map(|oid| oid.to_string()), after.to_string()))
+            })
+            .await?
+        };
+
+        info!(branch = %branch, commit = %after, files = paths.len(), "Committed to git branch");
+
+        let effect = EffectBuilder::new(
+            vakya.vakya_id.0.clone(),
+            if before.is_none() { EffectBucket::Create } else { EffectBucket::Update },
+            vakya.v2_karma.rid.0.clone(),
+        )
+        .target_type("git")
+        .before(before.as_deref().map(commit_snapshot).unwrap_or_else(StateSnapshot::not_exists))
+        .after(commit_snapshot(&after))
+        .reversible(
+            ReversalMethod::RestoreState,
+            serde_json::json!({
+                "branch": branch,
+                "before_commit": before,
+                "after_commit": after,
+            }),
+        )
+        .metadata("branch", serde_json::json!(branch))
+        .metadata("before_commit", serde_json::json!(before))
+        .metadata("after_commit", serde_json::json!(after))
+        .metadata("files", serde_json::json!(paths))
+        .build();
+
+        Ok(ExecutionResult::success(
+            serde_json::json!({
+                "branch": branch,
+                "commit": after,
+                "parent": before,
+                "files": paths,
+            }),
+            vec![effect],
+            start.elapsed().as_millis() as u64,
+        ))
+    }
+}
+
+#[async_trait]
+impl Adapter for GitAdapter {
+    fn domain(&self) -> &str {
+        "git"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn supported_actions(&self) -> Vec<&str> {
+        vec!["git.read", "git.list", "git.commit"]
+    }
+
+    async fn execute(&self, vakya: &Vakya, context: &ExecutionContext) -> AdapterResult<ExecutionResult> {
+        let path = resolve_repo_path(&vakya.v2_karma.rid.0)?;
+        let action = &vakya.v3_kriya.action;
+
+        debug!(action = %action, path = %path, repo = %self.repo_dir.display(), "Executing git action");
+
+        match action.as_str() {
+            "git.read" => self.execute_read(vakya, path).await,
+            "git.list" => self.execute_list(vakya, path).await,
+            "git.commit" => self.execute_commit(vakya, path, context).await,
+            _ => Err(AdapterError::UnsupportedAction(action.clone())),
+        }
+    }
+
+    fn action_descriptors(&self) -> Vec<ActionDescriptor> {
+        git_action_descriptors()
+    }
+
+    fn can_rollback(&self, action: &str) -> bool {
+        action == "git.commit"
+    }
+
+    /// Reset the branch to its prior commit, or delete it if the commit
+    /// created it. Refuses if the branch has moved since.
+    async fn rollback(&self, effect: &CapturedEffect) -> AdapterResult<()> {
+        let reversal = effect.reversal.as_ref()
+            .ok_or_else(|| AdapterError::RollbackFailed("No reversal instructions".to_string()))?;
+
+        let field = |key: &str| reversal.data.get(key).and_then(|v| v.as_str()).map(String::from);
+        let branch = field("branch")
+            .ok_or_else(|| AdapterError::RollbackFailed("Missing branch in reversal".to_string()))?;
+        let after = field("after_commit")
+            .ok_or_else(|| AdapterError::RollbackFailed("Missing after_commit in reversal".to_string()))?;
+        let before = field("before_commit");
+
+        {
+            let branch = branch.clone();
+            self.with_repo(move |repo| {
+                let after = Oid::from_str(&after).map_err(|e| AdapterError::RollbackFailed(e.to_string()))?;
+                let mut reference = repo.find_reference(&branch_ref(&branch))
+                    .map_err(|e| AdapterError::RollbackFailed(e.message().to_string()))?;
+                if reference.target() != Some(after) {
+                    return Err(AdapterError::RollbackFailed(format!(
+                        "Branch {} has moved since commit {}",
+                        branch, after
+                    )));
+                }
+
+                match before {
+                    Some(before) => {
+                        let before = Oid::from_str(&before).map_err(|e| AdapterError::RollbackFailed(e.to_string()))?;
+                        repo.reference_matching(&branch_ref(&branch), before, true, after, "aapi: rollback")
+                            .map_err(|e| AdapterError::RollbackFailed(e.message().to_string()))?;
+                    }
+                    None => {
+                        reference.delete().map_err(|e| AdapterError::RollbackFailed(e.message().to_string()))?;
+                    }
+                }
+                Ok(())
+            })
+            .await?;
+        }
+
+        info!(branch = %branch, "Rollback completed");
+        Ok(())
+    }
+
+    async fn health_check(&self) -> AdapterResult<HealthStatus> {
+        let start = std::time::Instant::now();
+
+        match self.with_repo(|_| Ok(())).await {
+            Ok(()) => Ok(HealthStatus::healthy().with_latency(start.elapsed().as_millis() as u64)),
+            Err(e) => Ok(HealthStatus::unhealthy(format!(
+                "Repository unavailable at {}: {}",
+                self.repo_dir.display(),
+                e
+            ))),
+        }
+    }
+}
+
+/// Turn a resource ID into a normalized path inside the repository tree
+///
+/// The empty string is the repository root. Parent, current-directory and
+/// `.git` components are rejected.
+fn resolve_repo_path(resource_id: &str) -> AdapterResult<String> {
+    let path = resource_id.strip_prefix("git:").unwrap_or(resource_id);
+    join_repo_path("", path)
+}
+
+fn join_repo_path(base: &str, path: &str) -> AdapterResult<String> {
+    let mut components = Vec::new();
+    for part in base.split('/').chain(path.split('/')) {
+        match part {
+            "" => continue,
+            "." | ".." => {
+                return Err(AdapterError::PermissionDenied(format!(
+                    "Path {} escapes the repository",
+                    path
+                )));
+            }
+            _ if part.eq_ignore_ascii_case(".git") => {
+                return Err(AdapterError::PermissionDenied(format!(
+                    "Path {} is inside .git",
+                    path
+                )));
+            }
+            _ => components.push(part),
+        }
+    }
+    Ok(components.join("/"))
+}
+
+/// Parse the `files` map of a git.commit body
+fn extract_changes(base: &str, body: &serde_json::Value) -> AdapterResult<Vec<(String, Option<Vec<u8>>)>> {
+    let files = body.get("files")
+        .and_then(|v| v.as_object())
+        .filter(|files| !files.is_empty())
+        .ok_or_else(|| AdapterError::InvalidInput("Missing 'files' in body".to_string()))?;
+
+    files.iter()
+        .map(|(path, content)| {
+            let path = join_repo_path(base, path)?;
+            if path.is_empty() {
+                return Err(AdapterError::InvalidInput("File path is empty".to_string()));
+            }
+            let content = match content {
+                serde_json::Value::Null => None,
+                serde_json::Value::String(s) => Some(s.as_bytes().to_vec()),
+                other => Some(serde_json::to_vec_pretty(other)?),
+            };
+            Ok((path, content))
+        })
+        .collect()
+}
+
+fn branch_ref(branch: &str) -> String {
+    format!("refs/heads/{}", branch)
+}
+
+/// Current tip of a local branch, if it exists
+fn branch_tip(repo: &Repository, branch: &str) -> AdapterResult<Option<Oid>> {
+    match repo.find_branch(branch, BranchType::Local) {
+        Ok(branch) => Ok(branch.get().target()),
+        Err(e) if e.code() == ErrorCode::NotFound => Ok(None),
+        Err(e) => Err(git_error(e)),
+    }
+}
+
+/// Index entry for a regular file blob
+fn blob_entry(path: &str, id: Oid, size: usize) -> IndexEntry {
+    IndexEntry {
+        ctime: IndexTime::new(0, 0),
+        mtime: IndexTime::new(0, 0),
+        dev: 0,
+        ino: 0,
+        mode: 0o100644,
+        uid: 0,
+        gid: 0,
+        file_size: size as u32,
+        id,
+        flags: path.len().min(0xfff) as u16,
+        flags_extended: 0,
+        path: path.as_bytes().to_vec(),
+    }
+}
+
+/// Branch state identified by its commit SHA
+fn commit_snapshot(commit: &str) -> StateSnapshot {
+    let mut snapshot = StateSnapshot::from_hash(commit, 0)
+        .with_property("commit", serde_json::json!(commit));
+    snapshot.size = None;
+    snapshot
+}
+
+fn git_error(e: git2::Error) -> AdapterError {
+    match e.code() {
+        ErrorCode::NotFound => AdapterError::NotFound(e.message().to_string()),
+        _ => AdapterError::Git(e.message().to_string()),
+    }
+}
+
+/// Get action descriptors for the git adapter
+pub fn git_action_descriptors() -> Vec<ActionDescriptor> {
+    vec![
+        ActionDescriptor::new("git.read", "Read a file at a ref")
+            .with_effect(EffectBucket::Read)
+            .idempotent(),
+        ActionDescriptor::new("git.list", "List a tree at a ref")
+            .with_effect(EffectBucket::Read)
+            .idempotent(),
+        ActionDescriptor::new("git.commit", "Write files and commit to a branch")
+            .with_effect(EffectBucket::Update)
+            .reversible(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    use aapi_core::types::*;
+    use aapi_core::vakya::*;
+    use tempfile::TempDir;
+
+    fn make_vakya(action: &str, rid: &str, body: serde_json::Value) -> Vakya {
+        let (domain, verb) = action.split_once('.').unwrap();
+        Vakya::builder()
+            .karta(Karta {
+                pid: PrincipalId::new("agent:gitops"),
+                role: None,
+                realm: None,
+                key_id: None,
+                actor_type: ActorType::Agent,
+                delegation_chain: vec![],
+            })
+            .karma(Karma {
+                rid: ResourceId::new(rid),
+                kind: Some("git".to_string()),
+                ns: None,
+                version: None,
+                labels: HashMap::new(),
+            })
+            .kriya(Kriya::new(domain, verb))
+            .adhikarana(Adhikarana {
+                cap: CapabilityRef::Reference { cap_ref: "cap:test".to_string() },
+                policy_ref: None,
+                ttl: None,
+                budgets: vec![],
+                approval_lane: ApprovalLane::None,
+                scopes: vec![],
+                context: None,
+                delegation_chain_cid: None,
+                execution_constraints: None,
+                port_id: None,
+                required_phase: None,
+                required_role: None,
+            })
+            .body(body)
+            .build()
+            .unwrap()
+    }
+
+    fn adapter() -> (GitAdapter, TempDir) {
+        let dir = TempDir::new().unwrap();
+        Repository::init(dir.path()).unwrap();
+        (GitAdapter::new(dir.path()), dir)
+    }
+
+    async fn read(adapter: &GitAdapter, rid: &str) -> AdapterResult<ExecutionResult> {
+        adapter.execute(&make_vakya("git.read", rid, serde_json::json!({})), &ExecutionContext::new("read")).await
+    }
+
+    #[tokio::test]
+    async fn test_commit_read_list_and_rollback() {
+        let (adapter, _dir) = adapter();
+        let ctx = ExecutionContext::new("req-1");
+
+        let first = make_vakya("git.commit", "git:config", serde_json::json!({
+            "message": "Add config",
+            "files": { "app.yaml": "replicas: 1\n", "db.yaml": "pool: 5\n" },
+        }));
+        let result = adapter.execute(&first, &ctx).await.unwrap();
+        assert_eq!(result.effects[0].bucket, EffectBucket::Create);
+        let first_commit = result.data.unwrap()["commit"].as_str().unwrap().to_string();
+
+        let data = read(&adapter, "git:config/app.yaml").await.unwrap().data.unwrap();
+        assert_eq!(data["content"], "replicas: 1\n");
+        assert_eq!(data["commit"], first_commit.as_str());
+
+        let list = adapter
+            .execute(&make_vakya("git.list", "git:config", serde_json::json!({})), &ctx)
+            .await
+            .unwrap();
+        assert_eq!(list.data.unwrap()["count"], 2);
+
+        let second = make_vakya("git.commit", "git:config", serde_json::json!({
+            "message": "Scale up",
+            "files": { "app.yaml": "replicas: 3\n", "db.yaml": null },
+        }));
+        let result = adapter.execute(&second, &ctx).await.unwrap();
+        let effect = &result.effects[0];
+        assert_eq!(effect.bucket, EffectBucket::Update);
+        assert_eq!(effect.before.as_ref().unwrap().hash, first_commit);
+        assert!(adapter.can_rollback("git.commit"));
+        assert!(matches!(read(&adapter, "git:config/db.yaml").await, Err(AdapterError::NotFound(_))));
+
+        // Reading the previous commit by ref still works
+        let old = adapter
+            .execute(
+                &make_vakya("git.read", "git:config/db.yaml", serde_json::json!({ "ref": first_commit })),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        assert_eq!(old.data.unwrap()["content"], "pool: 5\n");
+
+        adapter.rollback(effect).await.unwrap();
+        let data = read(&adapter, "git:config/app.yaml").await.unwrap().data.unwrap();
+        assert_eq!(data["content"], "replicas: 1\n");
+        assert_eq!(data["commit"], first_commit.as_str());
+
+        // The branch no longer points at the rolled-back commit
+        assert!(matches!(adapter.rollback(effect).await, Err(AdapterError::RollbackFailed(_))));
+    }
+
+    #[tokio::test]
+    async fn test_paths_are_sandboxed() {
+        let (adapter, _dir) = adapter();
+        let ctx = ExecutionContext::new("req-2");
+
+        assert!(matches!(read(&adapter, "git:../etc/passwd").await, Err(AdapterError::PermissionDenied(_))));
+        assert!(matches!(read(&adapter, "git:.git/config").await, Err(AdapterError::PermissionDenied(_))));
+
+        let escape = make_vakya("git.commit", "git:config", serde_json::json!({
+            "message": "Escape",
+            "files": { "../../hooks/post-commit": "#!/bin/sh" },
+        }));
+        assert!(matches!(adapter.execute(&escape, &ctx).await, Err(AdapterError::PermissionDenied(_))));
+
+        let dry = make_vakya("git.commit", "git:config", serde_json::json!({
+            "message": "Dry",
+            "files": { "app.yaml": "x" },
+        }));
+        let result = adapter.execute(&dry, &ExecutionContext::new("req-3").dry_run()).await.unwrap();
+        assert!(result.effects.is_empty());
+        assert!(read(&adapter, "git:config/app.yaml").await.is_err());
+    }
+}
@@ -1,29 +1,64 @@
 //! HTTP adapter for external API calls
 
 use async_trait::async_trait;
-use reqwest::{Client, Method, Response};
+use reqwest::{Client, Method, Request, StatusCode};
+use hyper::client::connect::dns::Name;
+use reqwest::dns::{Addrs, Resolve, Resolving};
+use reqwest::RequestBuilder;
 use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::Duration;
-use tracing::{debug, info, warn};
+use tracing::{debug, info};
 
-use aapi_core::types::EffectBucket;
+use aapi_core::types::{EffectBucket, HashAlgorithm};
 use aapi_core::Vakya;
 
-use crate::effect::{CapturedEffect, EffectBuilder, StateSnapshot};
+use crate::codec::BodyCodecRegistry;
+use crate::effect::{CapturedEffect, EffectBuilder, ReversalMethod, StateSnapshot};
+use crate::file::{sandboxed_path, AtomicFile};
 use crate::error::{AdapterError, AdapterResult};
 use crate::traits::{Adapter, ActionDescriptor, ExecutionContext, ExecutionResult, HealthStatus};
 
+/// How much of an HTTP response body is kept in the captured effect
+///
+/// Only the stored effect is affected; the caller always receives the full
+/// body in the `ExecutionResult`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResponseCapture {
+    /// Status and headers only
+    None,
+    /// Status, headers, and the body's hash and size
+    #[default]
+    HashOnly,
+    /// As `HashOnly`, plus the first `n` bytes of the body
+    Truncate(usize),
+    /// The entire body alongside its hash and size
+    Full,
+}
+
 /// HTTP adapter for making external API calls
 pub struct HttpAdapter {
     client: Client,
-    /// Allowed hosts (empty = all allowed)
-    allowed_hosts: Vec<String>,
-    /// Denied hosts
-    denied_hosts: Vec<String>,
+    /// Hosts requests and their redirects may reach
+    hosts: HostRules,
     /// Default timeout in seconds
     default_timeout_secs: u64,
-    /// Maximum response size
+    /// Maximum response size, and largest file `http.upload` will send
     max_response_size: usize,
+    /// Directory `http.download` may write into; downloads are refused
+    /// when unset
+    download_dir: Option<PathBuf>,
+    /// Directory `http.upload` may read from; uploads are refused when unset
+    upload_dir: Option<PathBuf>,
+    /// How much of each response body the effect records
+    response_capture: ResponseCapture,
+    /// Decoders for non-JSON VĀKYA bodies
+    codecs: BodyCodecRegistry,
+    /// Records or replays generic requests
+    #[cfg(feature = "recording")]
+    cassette: Option<Arc<crate::cassette::Cassette>>,
 }
 
 impl Default for HttpAdapter {
@@ -34,28 +69,46 @@ impl Default for HttpAdapter {
 
 impl HttpAdapter {
     pub fn new() -> Self {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(30))
-            .user_agent("AAPI-HttpAdapter/1.0")
-            .build()
-            .expect("Failed to create HTTP client");
-
         Self {
-            client,
-            allowed_hosts: vec![],
-            denied_hosts: vec![],
+            client: build_client(&HostRules::default()),
+            hosts: HostRules::default(),
             default_timeout_secs: 30,
             max_response_size: 10 * 1024 * 1024, // 10MB
+            download_dir: None,
+            upload_dir: None,
+            response_capture: ResponseCapture::default(),
+            codecs: BodyCodecRegistry::default(),
+            #[cfg(feature = "recording")]
+            cassette: None,
         }
     }
 
     pub fn with_allowed_hosts(mut self, hosts: Vec<String>) -> Self {
-        self.allowed_hosts = hosts;
+        self.hosts.allowed_hosts = hosts;
+        self.client = build_client(&self.hosts);
         self
     }
 
     pub fn with_denied_hosts(mut self, hosts: Vec<String>) -> Self {
-        self.denied_hosts = hosts;
+        self.hosts.denied_hosts = hosts;
+        self.client = build_client(&self.hosts);
+        self
+    }
+
+    /// Deny every host unless it is on the allow-list
+    pub fn with_allow_list_required(mut self, required: bool) -> Self {
+        self.hosts.require_allow_list = required;
+        self.client = build_client(&self.hosts);
+        self
+    }
+
+    /// Refuse requests to loopback, private and link-local addresses
+    ///
+    /// Host names are checked after DNS resolution, and every redirect hop is
+    /// checked too, so a public name cannot be used to reach internal services.
+    pub fn with_private_networks_blocked(mut self, block: bool) -> Self {
+        self.hosts.block_private = block;
+        self.client = build_client(&self.hosts);
         self
     }
 
@@ -64,65 +117,103 @@ impl HttpAdapter {
         self
     }
 
+    pub fn with_max_response_size(mut self, size: usize) -> Self {
+        self.max_response_size = size;
+        self
+    }
+
+    /// Allow `http.download` to write files under `dir`
+    pub fn with_download_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.download_dir = Some(dir.into());
+        self
+    }
+
+    /// Allow `http.upload` to send files from under `dir`
+    pub fn with_upload_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.upload_dir = Some(dir.into());
+        self
+    }
+
+    /// Choose how much of each response body is stored in the effect
+    pub fn capture_response_body(mut self, mode: ResponseCapture) -> Self {
+        self.response_capture = mode;
+        self
+    }
+
+    /// Replace the codecs used to decode non-JSON VĀKYA bodies
+    pub fn with_body_codecs(mut self, codecs: BodyCodecRegistry) -> Self {
+        self.codecs = codecs;
+        self
+    }
+
+    /// Record generic requests to, or replay them from, `cassette`
+    #[cfg(feature = "recording")]
+    pub fn with_cassette(mut self, cassette: Arc<crate::cassette::Cassette>) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+
     /// Check if a URL is allowed
     fn is_url_allowed(&self, url: &str) -> AdapterResult<()> {
         let parsed = url::Url::parse(url)
             .map_err(|e| AdapterError::InvalidInput(format!("Invalid URL: {}", e)))?;
+        self.hosts.check(&parsed)
+    }
 
-        let host = parsed.host_str()
-            .ok_or_else(|| AdapterError::InvalidInput("URL has no host".to_string()))?;
-
-        // Check denied hosts first
-        for denied in &self.denied_hosts {
-            if host == denied || host.ends_with(&format!(".{}", denied)) {
-                return Err(AdapterError::PermissionDenied(format!(
-                    "Host {} is denied",
-                    host
-                )));
-            }
+    /// Reject host names that resolve to private addresses
+    ///
+    /// The client's resolver enforces the same rule at connect time; checking
+    /// up front reports it as a permission error rather than a network error.
+    async fn check_resolved_host(&self, url: &str) -> AdapterResult<()> {
+        if !self.hosts.block_private {
+            return Ok(());
         }
-
-        // Check allowed hosts if specified
-        if !self.allowed_hosts.is_empty() {
-            let allowed = self.allowed_hosts.iter().any(|allowed| {
-                host == allowed || host.ends_with(&format!(".{}", allowed))
-            });
-            if !allowed {
-                return Err(AdapterError::PermissionDenied(format!(
-                    "Host {} is not in allowed list",
-                    host
-                )));
-            }
+        let parsed = url::Url::parse(url)
+            .map_err(|e| AdapterError::InvalidInput(format!("Invalid URL: {}", e)))?;
+        if let Some(url::Host::Domain(domain)) = parsed.host() {
+            resolve_public(domain).await.map_err(AdapterError::PermissionDenied)?;
         }
-
         Ok(())
     }
 
     /// Parse method from action or body
-    fn parse_method(&self, action: &str, body: &serde_json::Value) -> Method {
-        // Check body for explicit method
-        if let Some(method_str) = body.get("method").and_then(|v| v.as_str()) {
-            return match method_str.to_uppercase().as_str() {
-                "GET" => Method::GET,
-                "POST" => Method::POST,
-                "PUT" => Method::PUT,
-                "DELETE" => Method::DELETE,
-                "PATCH" => Method::PATCH,
-                "HEAD" => Method::HEAD,
-                "OPTIONS" => Method::OPTIONS,
-                _ => Method::GET,
-            };
-        }
+    ///
+    /// `http.request` has no implied method, so the body must name one. Any
+    /// valid method token is accepted, including extension methods. Other
+    /// actions imply their method, and a body `method` must agree with it.
+    fn parse_method(&self, action: &str, body: &serde_json::Value) -> AdapterResult<Method> {
+        let requested = body.get("method")
+            .map(|method| {
+                let method_str = method.as_str().ok_or_else(|| {
+                    AdapterError::InvalidInput("'method' must be a string".to_string())
+                })?;
+                Method::from_bytes(method_str.to_uppercase().as_bytes()).map_err(|_| {
+                    AdapterError::InvalidInput(format!("Invalid HTTP method: {}", method_str))
+                })
+            })
+            .transpose()?;
 
         // Infer from action
-        match action {
+        let implied = match action {
             "http.get" => Method::GET,
             "http.post" => Method::POST,
             "http.put" => Method::PUT,
             "http.delete" => Method::DELETE,
             "http.patch" => Method::PATCH,
             "http.head" => Method::HEAD,
+            "http.request" => return requested.ok_or_else(|| AdapterError::InvalidInput(
+                "http.request requires a 'method' field in the body".to_string(),
+            )),
             _ => Method::GET,
+        };
+
+        // Policy is keyed on the action, so the body may not change its method
+        match requested {
+            Some(method) if method != implied => Err(AdapterError::InvalidInput(format!(
+                "{} cannot send {}; use http.request for other methods",
+                action, method
+            ))),
+            _ => Ok(implied),
         }
     }
 
@@ -135,22 +226,13 @@ impl HttpAdapter {
         let start = std::time::Instant::now();
 
         // Get URL from resource ID
-        let url = vakya.v2_karma.rid.0
-            .strip_prefix("http://")
-            .or_else(|| vakya.v2_karma.rid.0.strip_prefix("https://"))
-            .map(|s| {
-                if vakya.v2_karma.rid.0.starts_with("https://") {
-                    format!("https://{}", s)
-                } else {
-                    format!("http://{}", s)
-                }
-            })
-            .unwrap_or_else(|| vakya.v2_karma.rid.0.clone());
+        let url = request_url(vakya);
 
         // Validate URL
         self.is_url_allowed(&url)?;
+        self.check_resolved_host(&url).await?;
 
-        let method = self.parse_method(&vakya.v3_kriya.action, &vakya.body);
+        let method = self.parse_method(&vakya.v3_kriya.action, &vakya.body)?;
         let body = &vakya.body;
 
         debug!(url = %url, method = %method, "Executing HTTP request");
@@ -168,28 +250,11 @@ impl HttpAdapter {
             ));
         }
 
-        // Build request
-        let mut request = self.client.request(method.clone(), &url);
-
-        // Add headers
-        if let Some(headers) = body.get("headers").and_then(|v| v.as_object()) {
-            for (key, value) in headers {
-                if let Some(v) = value.as_str() {
-                    request = request.header(key.as_str(), v);
-                }
-            }
-        }
-
-        // Add query parameters
-        if let Some(query) = body.get("query").and_then(|v| v.as_object()) {
-            let params: Vec<(String, String)> = query.iter()
-                .filter_map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string())))
-                .collect();
-            request = request.query(&params);
-        }
+        // Build request with headers and query parameters
+        let mut request = with_headers_and_query(self.client.request(method.clone(), &url), body);
 
-        // Add body for POST/PUT/PATCH
-        if matches!(method, Method::POST | Method::PUT | Method::PATCH) {
+        // Add body for any method that may carry one
+        if !matches!(method, Method::GET | Method::HEAD) {
             if let Some(json_body) = body.get("body") {
                 request = request.json(json_body);
             } else if let Some(form) = body.get("form").and_then(|v| v.as_object()) {
@@ -201,25 +266,11 @@ impl HttpAdapter {
         }
 
         // Set timeout
-        let timeout = context.timeout_ms
-            .map(Duration::from_millis)
-            .unwrap_or_else(|| Duration::from_secs(self.default_timeout_secs));
-        request = request.timeout(timeout);
+        request = request.timeout(self.timeout(context));
 
         // Execute request
-        let response = request.send().await
-            .map_err(|e| AdapterError::Http(e.to_string()))?;
-
-        // Capture response
-        let status = response.status();
-        let headers: HashMap<String, String> = response.headers()
-            .iter()
-            .filter_map(|(k, v)| v.to_str().ok().map(|s| (k.to_string(), s.to_string())))
-            .collect();
-
-        // Read response body
-        let response_body = response.bytes().await
-            .map_err(|e| AdapterError::Http(e.to_string()))?;
+        let request = request.build().map_err(http_error)?;
+        let (status, headers, response_body) = self.send(request).await?;
 
         if response_body.len() > self.max_response_size {
             return Err(AdapterError::Http(format!(
@@ -255,10 +306,7 @@ impl HttpAdapter {
             vakya.v2_karma.rid.0.clone(),
         )
         .target_type("http")
-        .after(StateSnapshot::from_json(&serde_json::json!({
-            "status": status.as_u16(),
-            "headers": headers,
-        })))
+        .after(self.response_snapshot(status.as_u16(), &headers, &response_body, &response_data))
         .metadata("url", serde_json::json!(url))
         .metadata("method", serde_json::json!(method.as_str()))
         .metadata("status", serde_json::json!(status.as_u16()))
@@ -286,6 +334,378 @@ impl HttpAdapter {
     }
 }
 
+impl HttpAdapter {
+    /// Send a generic request, through the cassette when one is attached
+    async fn send(&self, request: Request) -> AdapterResult<(StatusCode, HashMap<String, String>, Vec<u8>)> {
+        #[cfg(feature = "recording")]
+        if let Some(cassette) = &self.cassette {
+            return cassette.exchange(&self.client, request).await;
+        }
+        fetch(&self.client, request).await
+    }
+
+    /// Snapshot of a response for the effect, trimmed per `response_capture`
+    ///
+    /// Except under `ResponseCapture::None`, the snapshot hash and size are
+    /// those of the raw body, so a stored effect can prove what was received
+    /// without keeping the body itself.
+    fn response_snapshot(
+        &self,
+        status: u16,
+        headers: &HashMap<String, String>,
+        body: &[u8],
+        body_data: &serde_json::Value,
+    ) -> StateSnapshot {
+        let mut content = serde_json::json!({
+            "status": status,
+            "headers": headers,
+        });
+
+        match self.response_capture {
+            ResponseCapture::None => return StateSnapshot::from_json(&content),
+            ResponseCapture::HashOnly => {}
+            ResponseCapture::Truncate(limit) => {
+                content["body_preview"] = serde_json::json!(
+                    String::from_utf8_lossy(&body[..body.len().min(limit)])
+                );
+                content["body_truncated"] = serde_json::json!(body.len() > limit);
+            }
+            ResponseCapture::Full => content["body"] = body_data.clone(),
+        }
+
+        let mut snapshot = StateSnapshot::from_bytes(body);
+        snapshot.content = Some(content);
+        snapshot.content_type = headers.get("content-type").cloned();
+        snapshot
+    }
+
+    fn timeout(&self, context: &ExecutionContext) -> Duration {
+        context.timeout_ms
+            .map(Duration::from_millis)
+            .unwrap_or_else(|| Duration::from_secs(self.default_timeout_secs))
+    }
+
+    /// Execute http.download: stream a GET response body into a file
+    ///
+    /// The body is written to a temporary file chunk by chunk and renamed
+    /// into place only once complete, so failed or oversized downloads leave
+    /// no partial file behind.
+    ///
+    /// When Apādāna is present its source is the URL and the Karma resource
+    /// the destination file; otherwise the resource is the URL and the
+    /// body's `dest` the file.
+    async fn execute_download(
+        &self,
+        vakya: &Vakya,
+        context: &ExecutionContext,
+    ) -> AdapterResult<ExecutionResult> {
+        let start = std::time::Instant::now();
+
+        // With Apādāna the source is the URL and the resource the file;
+        // otherwise the resource is the URL and the body names the file
+        let (url, dest) = match &vakya.v6_apadana {
+            Some(apadana) => (normalize_url(&apadana.source.0), vakya.v2_karma.rid.0.as_str()),
+            None => {
+                let dest = vakya.body.get("dest")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| AdapterError::InvalidInput("Missing 'dest' in body".to_string()))?;
+                (request_url(vakya), dest)
+            }
+        };
+        self.is_url_allowed(&url)?;
+        self.check_resolved_host(&url).await?;
+
+        let download_dir = self.download_dir.as_deref()
+            .ok_or_else(|| AdapterError::PermissionDenied("Downloads are not enabled".to_string()))?;
+        let dest = Path::new(dest.strip_prefix("file:").unwrap_or(dest));
+        let dest = sandboxed_path(Some(download_dir), &download_dir.join(dest))?;
+
+        debug!(url = %url, dest = %dest.display(), "Executing HTTP download");
+
+        if context.dry_run {
+            let duration_ms = start.elapsed().as_millis() as u64;
+            return Ok(ExecutionResult::success(
+                serde_json::json!({
+                    "dry_run": true,
+                    "url": url,
+                    "dest": dest.to_string_lossy(),
+                }),
+                vec![],
+                duration_ms,
+            ));
+        }
+
+        let existed = dest.exists();
+        let mut response = with_headers_and_query(self.client.get(&url), &vakya.body)
+            .timeout(self.timeout(context))
+            .send()
+            .await
+            .map_err(http_error)?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Ok(ExecutionResult::failure(
+                format!("HTTP {} {}", status.as_u16(), status.canonical_reason().unwrap_or("Error")),
+                start.elapsed().as_millis() as u64,
+            ));
+        }
+
+        let too_large = |size: u64| AdapterError::Http(format!(
+            "Response too large: {} bytes (max {})",
+            size,
+            self.max_response_size
+        ));
+        if let Some(length) = response.content_length() {
+            if length > self.max_response_size as u64 {
+                return Err(too_large(length));
+            }
+        }
+
+        let algorithm = HashAlgorithm::Sha256;
+        let mut hasher = algorithm.hasher();
+        let mut file = AtomicFile::create(&dest).await?;
+        let mut written: u64 = 0;
+
+        while let Some(chunk) = response.chunk().await.map_err(|e| AdapterError::Http(e.to_string()))? {
+            written += chunk.len() as u64;
+            if written > self.max_response_size as u64 {
+                return Err(too_large(written));
+            }
+            hasher.update(&chunk);
+            file.write_all(&chunk).await?;
+        }
+        file.commit().await?;
+
+        let checksum = algorithm.label(&hasher.finalize_hex());
+        info!(url = %url, dest = %dest.display(), bytes = written, "Download complete");
+
+        let target = format!("file:{}", dest.display());
+        let mut effect = EffectBuilder::new(vakya.vakya_id.0.clone(), EffectBucket::Create, target)
+            .target_type("file")
+            .after(StateSnapshot::from_hash(checksum.clone(), written))
+            .metadata("url", serde_json::json!(url))
+            .metadata("status", serde_json::json!(status.as_u16()));
+        if !existed {
+            effect = effect.reversible(
+                ReversalMethod::Delete,
+                serde_json::json!({ "path": dest.to_string_lossy() }),
+            );
+        }
+
+        let duration_ms = start.elapsed().as_millis() as u64;
+
+        Ok(ExecutionResult::success(
+            serde_json::json!({
+                "url": url,
+                "dest": dest.to_string_lossy(),
+                "bytes_written": written,
+                "checksum": checksum,
+                "status": status.as_u16(),
+            }),
+            vec![effect.build()],
+            duration_ms,
+        ))
+    }
+
+    /// Execute http.upload: send a local file as the request body
+    ///
+    /// The file comes from Apādāna (`v6_apadana.source`), falling back to a
+    /// `source` field in the body, and must lie under the upload directory.
+    /// The body may set `method` (POST or PUT) and `content_type`.
+    async fn execute_upload(
+        &self,
+        vakya: &Vakya,
+        context: &ExecutionContext,
+    ) -> AdapterResult<ExecutionResult> {
+        let start = std::time::Instant::now();
+
+        let url = request_url(vakya);
+        self.is_url_allowed(&url)?;
+        self.check_resolved_host(&url).await?;
+
+        let upload_dir = self.upload_dir.as_deref()
+            .ok_or_else(|| AdapterError::PermissionDenied("Uploads are not enabled".to_string()))?;
+        let source = match &vakya.v6_apadana {
+            Some(apadana) => apadana.source.0.as_str(),
+            None => vakya.body.get("source")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| AdapterError::InvalidInput(
+                    "http.upload requires v6_apadana.source or a 'source' field in the body".to_string()
+                ))?,
+        };
+        let source = Path::new(source.strip_prefix("file:").unwrap_or(source));
+        let source = sandboxed_path(Some(upload_dir), &upload_dir.join(source))?;
+
+        let method = match vakya.body.get("method").and_then(|v| v.as_str()) {
+            None => Method::POST,
+            Some(m) if m.eq_ignore_ascii_case("post") => Method::POST,
+            Some(m) if m.eq_ignore_ascii_case("put") => Method::PUT,
+            Some(m) => {
+                return Err(AdapterError::InvalidInput(format!(
+                    "http.upload supports POST or PUT, not {}",
+                    m
+                )))
+            }
+        };
+        let content_type = vakya.body.get("content_type")
+            .and_then(|v| v.as_str())
+            .unwrap_or("application/octet-stream");
+
+        if !source.is_file() {
+            return Err(AdapterError::NotFound(format!("File not found: {}", source.display())));
+        }
+        let size = tokio::fs::metadata(&source).await?.len();
+        if size > self.max_response_size as u64 {
+            return Err(AdapterError::InvalidInput(format!(
+                "File too large: {} bytes (max {})",
+                size,
+                self.max_response_size
+            )));
+        }
+
+        debug!(url = %url, source = %source.display(), method = %method, "Executing HTTP upload");
+
+        if context.dry_run {
+            let duration_ms = start.elapsed().as_millis() as u64;
+            return Ok(ExecutionResult::success(
+                serde_json::json!({
+                    "dry_run": true,
+                    "url": url,
+                    "method": method.as_str(),
+                    "source": source.to_string_lossy(),
+                }),
+                vec![],
+                duration_ms,
+            ));
+        }
+
+        let content = tokio::fs::read(&source).await?;
+        let algorithm = HashAlgorithm::Sha256;
+        let mut hasher = algorithm.hasher();
+        hasher.update(&content);
+        let checksum = algorithm.label(&hasher.finalize_hex());
+        let bytes_sent = content.len();
+
+        let request = self.client.request(method.clone(), &url)
+            .header(reqwest::header::CONTENT_TYPE, content_type)
+            .body(content);
+        let response = with_headers_and_query(request, &vakya.body)
+            .timeout(self.timeout(context))
+            .send()
+            .await
+            .map_err(http_error)?;
+
+        let status = response.status();
+        let headers: HashMap<String, String> = response.headers()
+            .iter()
+            .filter_map(|(k, v)| v.to_str().ok().map(|s| (k.to_string(), s.to_string())))
+            .collect();
+        let response_body = response.bytes().await
+            .map_err(|e| AdapterError::Http(e.to_string()))?;
+        if response_body.len() > self.max_response_size {
+            return Err(AdapterError::Http(format!(
+                "Response too large: {} bytes",
+                response_body.len()
+            )));
+        }
+        let response_data = serde_json::from_slice::<serde_json::Value>(&response_body)
+            .unwrap_or_else(|_| serde_json::json!(String::from_utf8_lossy(&response_body)));
+
+        info!(url = %url, source = %source.display(), bytes = bytes_sent, "Upload complete");
+
+        let effect = EffectBuilder::new(
+            vakya.vakya_id.0.clone(),
+            if method == Method::PUT { EffectBucket::Update } else { EffectBucket::Create },
+            vakya.v2_karma.rid.0.clone(),
+        )
+        .target_type("http")
+        .after(self.response_snapshot(status.as_u16(), &headers, &response_body, &response_data))
+        .metadata("url", serde_json::json!(url))
+        .metadata("method", serde_json::json!(method.as_str()))
+        .metadata("status", serde_json::json!(status.as_u16()))
+        .metadata("source", serde_json::json!(source.to_string_lossy()))
+        .metadata("checksum", serde_json::json!(checksum))
+        .build();
+
+        let duration_ms = start.elapsed().as_millis() as u64;
+
+        let result = serde_json::json!({
+            "url": url,
+            "source": source.to_string_lossy(),
+            "bytes_sent": bytes_sent,
+            "checksum": checksum,
+            "status": status.as_u16(),
+            "body": response_data,
+        });
+
+        if status.is_success() {
+            Ok(ExecutionResult::success(result, vec![effect], duration_ms))
+        } else {
+            Ok(ExecutionResult::failure(
+                format!("HTTP {} {}", status.as_u16(), status.canonical_reason().unwrap_or("Error")),
+                duration_ms,
+            ).with_metadata("response", result))
+        }
+    }
+}
+
+/// Send `request` and read the status, headers and whole body
+pub(crate) async fn fetch(
+    client: &Client,
+    request: Request,
+) -> AdapterResult<(StatusCode, HashMap<String, String>, Vec<u8>)> {
+    let response = client.execute(request).await.map_err(http_error)?;
+
+    let status = response.status();
+    let headers: HashMap<String, String> = response.headers()
+        .iter()
+        .filter_map(|(k, v)| v.to_str().ok().map(|s| (k.to_string(), s.to_string())))
+        .collect();
+
+    let body = response.bytes().await
+        .map_err(|e| AdapterError::Http(e.to_string()))?;
+    Ok((status, headers, body.to_vec()))
+}
+
+/// URL of the resource a VĀKYA targets
+fn request_url(vakya: &Vakya) -> String {
+    normalize_url(&vakya.v2_karma.rid.0)
+}
+
+fn normalize_url(rid: &str) -> String {
+    rid
+        .strip_prefix("http://")
+        .or_else(|| rid.strip_prefix("https://"))
+        .map(|s| {
+            if rid.starts_with("https://") {
+                format!("https://{}", s)
+            } else {
+                format!("http://{}", s)
+            }
+        })
+        .unwrap_or_else(|| rid.to_string())
+}
+
+/// Apply `headers` and `query` from the VĀKYA body to a request
+fn with_headers_and_query(mut request: RequestBuilder, body: &serde_json::Value) -> RequestBuilder {
+    if let Some(headers) = body.get("headers").and_then(|v| v.as_object()) {
+        for (key, value) in headers {
+            if let Some(v) = value.as_str() {
+                request = request.header(key.as_str(), v);
+            }
+        }
+    }
+
+    if let Some(query) = body.get("query").and_then(|v| v.as_object()) {
+        let params: Vec<(String, String)> = query.iter()
+            .filter_map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string())))
+            .collect();
+        request = request.query(&params);
+    }
+
+    request
+}
+
 #[async_trait]
 impl Adapter for HttpAdapter {
     fn domain(&self) -> &str {
@@ -305,11 +725,22 @@ impl Adapter for HttpAdapter {
             "http.patch",
             "http.head",
             "http.request",
+            "http.download",
+            "http.upload",
         ]
     }
 
     async fn execute(&self, vakya: &Vakya, context: &ExecutionContext) -> AdapterResult<ExecutionResult> {
-        self.execute_request(vakya, context).await
+        let vakya = &*self.codecs.decode_vakya(vakya)?;
+        match vakya.v3_kriya.action.as_str() {
+            "http.download" => self.execute_download(vakya, context).await,
+            "http.upload" => self.execute_upload(vakya, context).await,
+            _ => self.execute_request(vakya, context).await,
+        }
+    }
+
+    fn action_descriptors(&self) -> Vec<ActionDescriptor> {
+        http_action_descriptors()
     }
 
     fn can_rollback(&self, _action: &str) -> bool {
@@ -345,15 +776,355 @@ pub fn http_action_descriptors() -> Vec<ActionDescriptor> {
         ActionDescriptor::new("http.head", "Make HTTP HEAD request")
             .with_effect(EffectBucket::Read)
             .idempotent(),
-        ActionDescriptor::new("http.request", "Make generic HTTP request")
-            .with_effect(EffectBucket::External),
+        ActionDescriptor::new("http.request", "Make generic HTTP request; body must set `method`")
+            .with_effect(EffectBucket::External)
+            .with_input_schema(serde_json::json!({
+                "type": "object",
+                "required": ["method"],
+                "properties": {
+                    "method": {"type": "string", "description": "Any HTTP method, e.g. OPTIONS or HEAD"},
+                },
+            })),
+        ActionDescriptor::new("http.download", "Stream a response body to a file")
+            .with_effect(EffectBucket::Create)
+            .reversible(),
+        ActionDescriptor::new("http.upload", "Send a local file as the request body")
+            .with_effect(EffectBucket::Create),
     ]
 }
 
+/// Classify a transport failure so timeouts and unreachable hosts keep
+/// their own reason codes
+fn http_error(e: reqwest::Error) -> AdapterError {
+    let refused = std::error::Error::source(&e).and_then(|source| source.downcast_ref::<RedirectRefused>());
+    if let Some(refused) = refused {
+        AdapterError::PermissionDenied(refused.to_string())
+    } else if e.is_timeout() {
+        AdapterError::Timeout
+    } else if e.is_connect() {
+        AdapterError::Unavailable(e.to_string())
+    } else {
+        AdapterError::Http(e.to_string())
+    }
+}
+
+/// Host restrictions applied to every request and each redirect it follows
+#[derive(Debug, Clone, Default)]
+struct HostRules {
+    /// Allowed hosts (empty = all allowed)
+    allowed_hosts: Vec<String>,
+    /// Denied hosts
+    denied_hosts: Vec<String>,
+    /// Treat an empty `allowed_hosts` as "nothing allowed"
+    require_allow_list: bool,
+    /// Refuse loopback, private and link-local destinations, including
+    /// names that resolve to them
+    block_private: bool,
+}
+
+impl HostRules {
+    fn check(&self, parsed: &url::Url) -> AdapterResult<()> {
+        let host = parsed.host_str()
+            .ok_or_else(|| AdapterError::InvalidInput("URL has no host".to_string()))?;
+
+        // Check denied hosts first
+        for denied in &self.denied_hosts {
+            if host == denied || host.ends_with(&format!(".{}", denied)) {
+                return Err(AdapterError::PermissionDenied(format!(
+                    "Host {} is denied",
+                    host
+                )));
+            }
+        }
+
+        if self.block_private {
+            let literal = match parsed.host() {
+                Some(url::Host::Ipv4(ip)) => Some(IpAddr::V4(ip)),
+                Some(url::Host::Ipv6(ip)) => Some(IpAddr::V6(ip)),
+                _ => None,
+            };
+            if let Some(ip) = literal.filter(|ip| is_private_address(*ip)) {
+                return Err(AdapterError::PermissionDenied(format!(
+                    "Host {} is a private network address",
+                    ip
+                )));
+            }
+        }
+
+        if self.require_allow_list && self.allowed_hosts.is_empty() {
+            return Err(AdapterError::PermissionDenied(format!(
+                "Host {} is not allowed: no HTTP hosts are allow-listed",
+                host
+            )));
+        }
+
+        // Check allowed hosts if specified
+        if !self.allowed_hosts.is_empty() {
+            let allowed = self.allowed_hosts.iter().any(|allowed| {
+                host == allowed || host.ends_with(&format!(".{}", allowed))
+            });
+            if !allowed {
+                return Err(AdapterError::PermissionDenied(format!(
+                    "Host {} is not in allowed list",
+                    host
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Build a client that applies `hosts` to every redirect hop as well as
+/// the initial request
+fn build_client(hosts: &HostRules) -> Client {
+    let mut builder = Client::builder()
+        .timeout(Duration::from_secs(30))
+        .user_agent("AAPI-HttpAdapter/1.0");
+
+    if hosts.block_private {
+        builder = builder.dns_resolver(Arc::new(PublicOnlyResolver));
+    }
+
+    let rules = hosts.clone();
+    builder = builder.redirect(reqwest::redirect::Policy::custom(move |attempt| {
+        if let Err(e) = rules.check(attempt.url()) {
+            attempt.error(RedirectRefused(e.to_string()))
+        } else if attempt.previous().len() >= 10 {
+            attempt.error("too many redirects")
+        } else {
+            attempt.follow()
+        }
+    }));
+
+    builder.build().expect("Failed to create HTTP client")
+}
+
+/// A redirect the adapter's host rules would not have allowed as a request
+#[derive(Debug)]
+struct RedirectRefused(String);
+
+impl std::fmt::Display for RedirectRefused {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "redirect refused: {}", self.0)
+    }
+}
+
+impl std::error::Error for RedirectRefused {}
+
+/// DNS resolver that refuses names resolving to private addresses
+struct PublicOnlyResolver;
+
+impl Resolve for PublicOnlyResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs = resolve_public(name.as_str()).await?;
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+async fn resolve_public(host: &str) -> Result<Vec<SocketAddr>, String> {
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, 0))
+        .await
+        .map_err(|e| format!("Failed to resolve {}: {}", host, e))?
+        .collect();
+
+    // Any private answer disqualifies the name, so a mixed record set
+    // cannot be used to reach an internal address
+    if let Some(addr) = addrs.iter().find(|addr| is_private_address(addr.ip())) {
+        return Err(format!("Host {} resolves to private network address {}", host, addr.ip()));
+    }
+    Ok(addrs)
+}
+
+/// Loopback, private, link-local, shared and unspecified addresses
+fn is_private_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                // Carrier-grade NAT, 100.64.0.0/10
+                || (a == 100 && (b & 0xc0) == 64)
+        }
+        IpAddr::V6(ip) => {
+            if let Some(v4) = ip.to_ipv4_mapped() {
+                return is_private_address(IpAddr::V4(v4));
+            }
+            let first = ip.segments()[0];
+            ip.is_loopback()
+                || ip.is_unspecified()
+                // Unique local, fc00::/7
+                || (first & 0xfe00) == 0xfc00
+                // Link-local, fe80::/10
+                || (first & 0xffc0) == 0xfe80
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use aapi_core::types::*;
+    use aapi_core::vakya::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn make_vakya(action: &str, rid: &str, body: serde_json::Value) -> Vakya {
+        let (domain, verb) = action.split_once('.').unwrap();
+        Vakya::builder()
+            .karta(Karta {
+                pid: PrincipalId::new("agent:fetcher"),
+                role: None,
+                realm: None,
+                key_id: None,
+                actor_type: ActorType::Agent,
+                delegation_chain: vec![],
+            })
+            .karma(Karma {
+                rid: ResourceId::new(rid),
+                kind: Some("http".to_string()),
+                ns: None,
+                version: None,
+                labels: HashMap::new(),
+            })
+            .kriya(Kriya::new(domain, verb))
+            .adhikarana(Adhikarana {
+                cap: CapabilityRef::Reference { cap_ref: "cap:test".to_string() },
+                policy_ref: None,
+                ttl: None,
+                budgets: vec![],
+                approval_lane: ApprovalLane::None,
+                scopes: vec![],
+                context: None,
+                delegation_chain_cid: None,
+                execution_constraints: None,
+                port_id: None,
+                required_phase: None,
+                required_role: None,
+            })
+            .body(body)
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_download_streams_to_file() {
+        let server = MockServer::start().await;
+        let payload: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+        Mock::given(method("GET"))
+            .and(path("/artifact.bin"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(payload.clone()))
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let adapter = HttpAdapter::new().with_download_dir(dir.path());
+        let vakya = make_vakya(
+            "http.download",
+            &format!("{}/artifact.bin", server.uri()),
+            serde_json::json!({ "dest": "artifacts/artifact.bin" }),
+        );
+
+        let result = adapter.execute(&vakya, &ExecutionContext::new("req-1")).await.unwrap();
+        let data = result.data.unwrap();
+        assert_eq!(data["bytes_written"], payload.len());
+        assert_eq!(data["checksum"], StateSnapshot::from_bytes(&payload).hash);
+
+        let dest = dir.path().join("artifacts/artifact.bin");
+        assert_eq!(std::fs::read(&dest).unwrap(), payload);
+        assert_eq!(std::fs::read_dir(dest.parent().unwrap()).unwrap().count(), 1);
+
+        let effect = &result.effects[0];
+        assert_eq!(effect.bucket, EffectBucket::Create);
+        assert_eq!(effect.target, format!("file:{}", dest.display()));
+        assert!(effect.reversible);
+    }
+
+    #[tokio::test]
+    async fn test_download_limits() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![7u8; 4096]))
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let url = format!("{}/big", server.uri());
+        let ctx = ExecutionContext::new("req-2");
+
+        // Oversized bodies are rejected without leaving files behind
+        let adapter = HttpAdapter::new().with_download_dir(dir.path()).with_max_response_size(1024);
+        let vakya = make_vakya("http.download", &url, serde_json::json!({ "dest": "big.bin" }));
+        assert!(adapter.execute(&vakya, &ctx).await.is_err());
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+
+        for dest in ["../escape.bin", "new/../../escape.bin"] {
+            let escape = make_vakya("http.download", &url, serde_json::json!({ "dest": dest }));
+            assert!(matches!(adapter.execute(&escape, &ctx).await, Err(AdapterError::PermissionDenied(_))));
+        }
+
+        let disabled = HttpAdapter::new();
+        assert!(matches!(disabled.execute(&vakya, &ctx).await, Err(AdapterError::PermissionDenied(_))));
+    }
+
+    #[tokio::test]
+    async fn test_apadana_download_and_upload() {
+        use wiremock::matchers::{body_bytes, header};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/source.txt"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"remote bytes".to_vec()))
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/upload"))
+            .and(header("content-type", "text/plain"))
+            .and(body_bytes(b"remote bytes".to_vec()))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({"stored": true})))
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let adapter = HttpAdapter::new()
+            .with_download_dir(dir.path())
+            .with_upload_dir(dir.path());
+        let ctx = ExecutionContext::new("req-3");
+        let apadana = |source: String| Apadana { source: ResourceId::new(source), source_type: None, location: None };
+
+        // Download: the source URL is Apādāna, the resource is the file
+        let mut vakya = make_vakya("http.download", "file:copies/source.txt", serde_json::json!({}));
+        vakya.v6_apadana = Some(apadana(format!("{}/source.txt", server.uri())));
+        let result = adapter.execute(&vakya, &ctx).await.unwrap();
+        assert!(result.success);
+        assert_eq!(std::fs::read(dir.path().join("copies/source.txt")).unwrap(), b"remote bytes");
+
+        // Upload: the source file is Apādāna, the resource is the URL
+        let mut vakya = make_vakya(
+            "http.upload",
+            &format!("{}/upload", server.uri()),
+            serde_json::json!({"method": "PUT", "content_type": "text/plain"}),
+        );
+        vakya.v6_apadana = Some(apadana("file:copies/source.txt".to_string()));
+        let result = adapter.execute(&vakya, &ctx).await.unwrap();
+        assert!(result.success, "{:?}", result.error);
+        let data = result.data.unwrap();
+        assert_eq!(data["bytes_sent"], 12);
+        assert_eq!(data["body"]["stored"], true);
+        assert_eq!(result.effects[0].bucket, EffectBucket::Update);
+
+        // Sources outside the upload directory, or with uploads disabled, are refused
+        vakya.v6_apadana = Some(apadana("file:../outside.txt".to_string()));
+        assert!(matches!(adapter.execute(&vakya, &ctx).await, Err(AdapterError::PermissionDenied(_))));
+        assert!(matches!(HttpAdapter::new().execute(&vakya, &ctx).await, Err(AdapterError::PermissionDenied(_))));
+    }
+
     #[test]
     fn test_url_validation_allowed() {
         let adapter = HttpAdapter::new()
@@ -374,15 +1145,214 @@ mod tests {
         assert!(adapter.is_url_allowed("http://internal.local/secret").is_err());
     }
 
+    #[tokio::test]
+    async fn test_private_networks_blocked() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("internal"))
+            .mount(&server)
+            .await;
+        let ctx = ExecutionContext::new("req-3");
+        let vakya = make_vakya("http.get", &format!("{}/admin", server.uri()), serde_json::json!({}));
+
+        // The mock server listens on loopback, which is reachable by default
+        assert!(HttpAdapter::new().execute(&vakya, &ctx).await.is_ok());
+
+        let adapter = HttpAdapter::new().with_private_networks_blocked(true);
+        assert!(matches!(adapter.execute(&vakya, &ctx).await, Err(AdapterError::PermissionDenied(_))));
+        for url in [
+            "http://10.1.2.3/",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://[::1]:8080/",
+            "http://[::ffff:192.168.0.1]/",
+            "http://100.64.0.1/",
+        ] {
+            assert!(adapter.is_url_allowed(url).is_err(), "{}", url);
+        }
+        assert!(adapter.is_url_allowed("http://93.184.216.34/").is_ok());
+
+        let localhost = make_vakya("http.get", "http://localhost:9/", serde_json::json!({}));
+        assert!(matches!(adapter.execute(&localhost, &ctx).await, Err(AdapterError::PermissionDenied(_))));
+    }
+
+    #[tokio::test]
+    async fn test_redirects_follow_host_rules() {
+        let server = MockServer::start().await;
+        let secret = format!("http://localhost:{}/secret", server.address().port());
+        Mock::given(method("GET"))
+            .and(path("/hop"))
+            .respond_with(ResponseTemplate::new(302).insert_header("location", secret.as_str()))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/secret"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("internal"))
+            .mount(&server)
+            .await;
+        let ctx = ExecutionContext::new("req-redirect");
+        let vakya = make_vakya("http.get", &format!("{}/hop", server.uri()), serde_json::json!({}));
+
+        assert!(HttpAdapter::new().execute(&vakya, &ctx).await.is_ok());
+
+        // The first hop is allowed; the redirect target is not
+        let denied = HttpAdapter::new().with_denied_hosts(vec!["localhost".to_string()]);
+        assert!(matches!(denied.execute(&vakya, &ctx).await, Err(AdapterError::PermissionDenied(_))));
+        let allowed = HttpAdapter::new().with_allowed_hosts(vec!["127.0.0.1".to_string()]);
+        assert!(matches!(allowed.execute(&vakya, &ctx).await, Err(AdapterError::PermissionDenied(_))));
+    }
+
+    #[test]
+    fn test_allow_list_required() {
+        let adapter = HttpAdapter::new().with_allow_list_required(true);
+        assert!(adapter.is_url_allowed("https://api.example.com/v1").is_err());
+
+        let adapter = adapter.with_allowed_hosts(vec!["api.example.com".to_string()]);
+        assert!(adapter.is_url_allowed("https://api.example.com/v1").is_ok());
+        assert!(adapter.is_url_allowed("https://other.com/v1").is_err());
+    }
+
     #[test]
     fn test_method_parsing() {
         let adapter = HttpAdapter::new();
 
-        assert_eq!(adapter.parse_method("http.get", &serde_json::json!({})), Method::GET);
-        assert_eq!(adapter.parse_method("http.post", &serde_json::json!({})), Method::POST);
+        assert_eq!(adapter.parse_method("http.get", &serde_json::json!({})).unwrap(), Method::GET);
+        assert_eq!(adapter.parse_method("http.post", &serde_json::json!({})).unwrap(), Method::POST);
         assert_eq!(
-            adapter.parse_method("http.request", &serde_json::json!({"method": "DELETE"})),
+            adapter.parse_method("http.request", &serde_json::json!({"method": "DELETE"})).unwrap(),
             Method::DELETE
         );
+        assert_eq!(
+            adapter.parse_method("http.request", &serde_json::json!({"method": "options"})).unwrap(),
+            Method::OPTIONS
+        );
+        assert_eq!(
+            adapter.parse_method("http.request", &serde_json::json!({"method": "PROPFIND"})).unwrap().as_str(),
+            "PROPFIND"
+        );
+        assert!(matches!(
+            adapter.parse_method("http.request", &serde_json::json!({})),
+            Err(AdapterError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            adapter.parse_method("http.request", &serde_json::json!({"method": "BAD METHOD"})),
+            Err(AdapterError::InvalidInput(_))
+        ));
+
+        // Named actions keep their method
+        assert_eq!(adapter.parse_method("http.get", &serde_json::json!({"method": "get"})).unwrap(), Method::GET);
+        for (action, method) in [("http.get", "DELETE"), ("http.get", "POST"), ("http.head", "GET")] {
+            assert!(matches!(
+                adapter.parse_method(action, &serde_json::json!({ "method": method })),
+                Err(AdapterError::InvalidInput(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_response_capture_modes() {
+        let server = MockServer::start().await;
+        let payload = serde_json::json!({"secret": "s3cr3t-token", "items": [1, 2, 3]});
+        Mock::given(method("GET"))
+            .and(path("/data"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(payload.clone()))
+            .mount(&server)
+            .await;
+
+        let rid = format!("{}/data", server.uri());
+        let vakya = make_vakya("http.get", &rid, serde_json::json!({}));
+        let context = ExecutionContext::new("req-capture");
+        let raw = serde_json::to_vec(&payload).unwrap();
+
+        // Default keeps the body out of the effect but still proves it
+        let result = HttpAdapter::new().execute(&vakya, &context).await.unwrap();
+        assert_eq!(result.data.unwrap()["body"], payload);
+        let after = result.effects[0].after.clone().unwrap();
+        assert_eq!(after.hash, StateSnapshot::from_bytes(&raw).hash);
+        assert_eq!(after.size, Some(raw.len() as u64));
+        let content = after.content.unwrap();
+        assert_eq!(content["status"], 200);
+        assert!(content.get("body").is_none());
+
+        let adapter = HttpAdapter::new().capture_response_body(ResponseCapture::Truncate(10));
+        let result = adapter.execute(&vakya, &context).await.unwrap();
+        let content = result.effects[0].after.clone().unwrap().content.unwrap();
+        assert_eq!(content["body_preview"], String::from_utf8_lossy(&raw[..10]).as_ref());
+        assert_eq!(content["body_truncated"], true);
+
+        let adapter = HttpAdapter::new().capture_response_body(ResponseCapture::Full);
+        let result = adapter.execute(&vakya, &context).await.unwrap();
+        let content = result.effects[0].after.clone().unwrap().content.unwrap();
+        assert_eq!(content["body"], payload);
+
+        let adapter = HttpAdapter::new().capture_response_body(ResponseCapture::None);
+        let result = adapter.execute(&vakya, &context).await.unwrap();
+        let after = result.effects[0].after.clone().unwrap();
+        assert_ne!(after.hash, StateSnapshot::from_bytes(&raw).hash);
+        assert!(after.content.unwrap().get("body").is_none());
+    }
+
+    #[cfg(feature = "recording")]
+    #[tokio::test]
+    async fn test_cassette_records_then_replays() {
+        use crate::cassette::{Cassette, CassetteMode};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let cassette_path = dir.path().join("echo.json");
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/echo"))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({"id": 7})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let rid = format!("{}/echo", server.uri());
+        let vakya = make_vakya("http.post", &rid, serde_json::json!({"body": {"name": "a"}}));
+        let context = ExecutionContext::new("req-cassette");
+
+        let cassette = Arc::new(Cassette::open(&cassette_path, CassetteMode::Auto).unwrap());
+        assert!(cassette.is_recording());
+        let adapter = HttpAdapter::new().with_cassette(Arc::clone(&cassette));
+        let recorded = adapter.execute(&vakya, &context).await.unwrap().data.unwrap();
+        assert_eq!(cassette.len(), 1);
+
+        // The server is gone; the replay answers from the file alone
+        drop(server);
+        let cassette = Arc::new(Cassette::open(&cassette_path, CassetteMode::Auto).unwrap());
+        assert!(!cassette.is_recording());
+        let adapter = HttpAdapter::new().with_cassette(cassette);
+        let replayed = adapter.execute(&vakya, &context).await.unwrap().data.unwrap();
+        assert_eq!(replayed["status"], 201);
+        assert_eq!(replayed["body"], recorded["body"]);
+
+        // Each recording replays once, and a different body does not match
+        assert!(adapter.execute(&vakya, &context).await.is_err());
+        let other = make_vakya("http.post", &rid, serde_json::json!({"body": {"name": "b"}}));
+        assert!(adapter.execute(&other, &context).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_generic_request_passes_method_through() {
+        let server = MockServer::start().await;
+        Mock::given(method("OPTIONS"))
+            .and(path("/api"))
+            .respond_with(ResponseTemplate::new(204).insert_header("allow", "GET, POST"))
+            .mount(&server)
+            .await;
+
+        let adapter = HttpAdapter::new();
+        let context = ExecutionContext::default();
+        let rid = format!("{}/api", server.uri());
+
+        let vakya = make_vakya("http.request", &rid, serde_json::json!({"method": "OPTIONS"}));
+        let result = adapter.execute(&vakya, &context).await.unwrap();
+        assert!(result.success);
+        let data = result.data.unwrap();
+        assert_eq!(data["method"], "OPTIONS");
+        assert_eq!(data["headers"]["allow"], "GET, POST");
+
+        let vakya = make_vakya("http.request", &rid, serde_json::json!({}));
+        let err = adapter.execute(&vakya, &context).await.unwrap_err();
+        assert!(matches!(err, AdapterError::InvalidInput(_)));
     }
 }
@@ -1,20 +1,34 @@
 //! AAPI Adapters - Karaṇa Adapters for Action Execution
 //!
 //! Adapters translate VĀKYA requests into concrete actions and capture effects.
-//! Each adapter handles a specific domain (file, http, database, etc.).
+//! Each adapter handles a specific domain (file, http, redis, git, process, etc.).
 
 pub mod traits;
 pub mod file;
 pub mod http;
+pub mod redis;
+pub mod queue;
+pub mod git;
+pub mod process;
 pub mod remote;
 pub mod effect;
+pub mod codec;
 pub mod registry;
 pub mod error;
+#[cfg(feature = "recording")]
+pub mod cassette;
 
 pub use traits::*;
 pub use file::*;
 pub use http::*;
+pub use redis::*;
+pub use queue::*;
+pub use git::*;
+pub use process::*;
 pub use remote::*;
 pub use effect::*;
+pub use codec::*;
 pub use registry::*;
 pub use error::*;
+#[cfg(feature = "recording")]
+pub use cassette::*;
@@ -0,0 +1,565 @@
+//! Process adapter for governed command execution
+//!
+//! Runs allow-listed programs as child processes. On Linux the child is
+//! confined before `exec` with `setrlimit` (CPU time, address space) and,
+//! optionally, a fresh network namespace; elsewhere only the timeout applies.
+//! Output capture is bounded so a chatty command cannot exhaust gateway
+//! memory. Running a command is an external side effect and cannot be
+//! rolled back.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::process::Command;
+use tracing::{debug, warn};
+
+use aapi_core::types::EffectBucket;
+use aapi_core::Vakya;
+
+use crate::effect::{CapturedEffect, EffectBuilder, StateSnapshot};
+use crate::error::{AdapterError, AdapterResult};
+use crate::traits::{Adapter, ActionDescriptor, ExecutionContext, ExecutionResult, HealthStatus};
+
+/// Limits applied to every spawned child
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessSandbox {
+    /// Directory commands run in; a requested `cwd` must resolve inside it
+    pub jail_dir: Option<PathBuf>,
+    /// `RLIMIT_CPU`, in seconds of CPU time
+    pub cpu_time_secs: Option<u64>,
+    /// `RLIMIT_AS`, in bytes of address space
+    pub memory_bytes: Option<u64>,
+    /// Run the child in an empty network namespace
+    pub no_network: bool,
+    /// Bytes kept from each of stdout and stderr; the rest is discarded
+    pub max_output_bytes: usize,
+}
+
+impl Default for ProcessSandbox {
+    fn default() -> Self {
+        Self {
+            jail_dir: None,
+            cpu_time_secs: Some(10),
+            memory_bytes: Some(512 * 1024 * 1024), // 512MB
+            no_network: false,
+            max_output_bytes: 64 * 1024, // 64KB
+        }
+    }
+}
+
+/// Adapter for `process.run`
+///
+/// The resource ID names the program, optionally prefixed with `process:`,
+/// and must be on the allow list; bare names are looked up on the gateway's
+/// `PATH`. The body carries optional `args`, `cwd` (relative to the jail
+/// when one is set) and `env`, whose names must be allow-listed; the child
+/// does not inherit the gateway's environment beyond `PATH`.
+pub struct ProcessAdapter {
+    /// Programs that may be run, by name or path
+    allowed_programs: Vec<String>,
+    /// Environment variables a request may set
+    allowed_env: Vec<String>,
+    sandbox: ProcessSandbox,
+    /// Default timeout in seconds
+    default_timeout_secs: u64,
+}
+
+impl ProcessAdapter {
+    pub fn new() -> Self {
+        Self {
+            allowed_programs: Vec::new(),
+            allowed_env: Vec::new(),
+            sandbox: ProcessSandbox::default(),
+            default_timeout_secs: 30,
+        }
+    }
+
+    pub fn with_allowed_program(mut self, program: impl Into<String>) -> Self {
+        self.allowed_programs.push(program.into());
+        self
+    }
+
+    /// Let requests set the environment variable `name`
+    ///
+    /// `PATH` and the dynamic loader's `LD_*` and `DYLD_*` variables are
+    /// refused even when listed, since they change which code runs.
+    pub fn with_allowed_env(mut self, name: impl Into<String>) -> Self {
+        self.allowed_env.push(name.into());
+        self
+    }
+
+    pub fn with_sandbox(mut self, sandbox: ProcessSandbox) -> Self {
+        self.sandbox = sandbox;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout_secs: u64) -> Self {
+        self.default_timeout_secs = timeout_secs;
+        self
+    }
+
+    /// Program named by the resource ID, if it is allow-listed
+    fn program(&self, vakya: &Vakya) -> AdapterResult<String> {
+        let program = vakya.v2_karma.rid.0
+            .strip_prefix("process:")
+            .unwrap_or(&vakya.v2_karma.rid.0);
+        if program.is_empty() {
+            return Err(AdapterError::InvalidInput("Program is empty".to_string()));
+        }
+        if !self.allowed_programs.iter().any(|allowed| allowed == program) {
+            return Err(AdapterError::PermissionDenied(format!(
+                "Program is not allow-listed: {}",
+                program
+            )));
+        }
+        Ok(program.to_string())
+    }
+
+    /// Working directory for the child, confined to the jail when one is set
+    fn working_dir(&self, requested: Option<&str>) -> AdapterResult<Option<PathBuf>> {
+        let Some(jail) = &self.sandbox.jail_dir else {
+            return Ok(requested.map(PathBuf::from));
+        };
+
+        let jail = jail.canonicalize()?;
+        let dir = match requested {
+            Some(requested) => jail.join(Path::new(requested).strip_prefix("/").unwrap_or(Path::new(requested))),
+            None => jail.clone(),
+        };
+        let dir = dir.canonicalize()
+            .map_err(|_| AdapterError::NotFound(format!("Working directory not found: {}", dir.display())))?;
+        if !dir.starts_with(&jail) {
+            return Err(AdapterError::PermissionDenied(format!(
+                "Working directory escapes the jail: {}",
+                dir.display()
+            )));
+        }
+        Ok(Some(dir))
+    }
+
+    fn build_command(&self, program: &str, vakya: &Vakya) -> AdapterResult<Command> {
+        let body = &vakya.body;
+        let args = match body.get("args") {
+            None => Vec::new(),
+            Some(serde_json::Value::Array(args)) => args.iter()
+                .map(|arg| arg.as_str().map(String::from)
+                    .ok_or_else(|| AdapterError::InvalidInput("'args' must be strings".to_string())))
+                .collect::<AdapterResult<Vec<_>>>()?,
+            Some(_) => return Err(AdapterError::InvalidInput("'args' must be an array".to_string())),
+        };
+        let env: HashMap<String, String> = body.get("env")
+            .and_then(|v| v.as_object())
+            .map(|env| {
+                env.iter()
+                    .filter_map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string())))
+                    .collect()
+            })
+            .unwrap_or_default();
+        for name in env.keys() {
+            self.check_env_name(name)?;
+        }
+
+        let mut command = Command::new(resolve_program(program)?);
+        command
+            .args(&args)
+            .env_clear()
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        if let Some(path) = std::env::var_os("PATH") {
+            command.env("PATH", path);
+        }
+        command.envs(env);
+        if let Some(dir) = self.working_dir(body.get("cwd").and_then(|v| v.as_str()))? {
+            command.current_dir(dir);
+        }
+        self.confine(&mut command);
+        Ok(command)
+    }
+
+    fn check_env_name(&self, name: &str) -> AdapterResult<()> {
+        let changes_code = name == "PATH" || name.starts_with("LD_") || name.starts_with("DYLD_");
+        if changes_code || !self.allowed_env.iter().any(|allowed| allowed == name) {
+            return Err(AdapterError::PermissionDenied(format!(
+                "Environment variable is not allow-listed: {}",
+                name
+            )));
+        }
+        Ok(())
+    }
+
+    #[cfg(target_os = "linux")]
+    fn confine(&self, command: &mut Command) {
+        let sandbox = self.sandbox.clone();
+        // SAFETY: the hook only makes async-signal-safe system calls
+        unsafe {
+            command.pre_exec(move || sandbox::apply(&sandbox));
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn confine(&self, _command: &mut Command) {
+        let sandbox = &self.sandbox;
+        if sandbox.cpu_time_secs.is_some() || sandbox.memory_bytes.is_some() || sandbox.no_network {
+            warn!("Process resource limits are only enforced on Linux; falling back to timeout only");
+        }
+    }
+}
+
+impl Default for ProcessAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod sandbox {
+    use super::ProcessSandbox;
+
+    /// Runs in the child between `fork` and `exec`
+    pub(super) fn apply(sandbox: &ProcessSandbox) -> std::io::Result<()> {
+        let limits = [
+            (libc::RLIMIT_CPU, sandbox.cpu_time_secs),
+            (libc::RLIMIT_AS, sandbox.memory_bytes),
+        ];
+        for (resource, value) in limits {
+            let Some(value) = value else { continue };
+            let limit = libc::rlimit { rlim_cur: value, rlim_max: value };
+            if unsafe { libc::setrlimit(resource, &limit) } != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+        }
+        // A user namespace lets an unprivileged gateway create the network
+        // namespace; the child then sees only a downed loopback
+        if sandbox.no_network && unsafe { libc::unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNET) } != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+/// Absolute path of `program`, with bare names looked up on the gateway's
+/// `PATH` so the request cannot influence which binary runs
+fn resolve_program(program: &str) -> AdapterResult<PathBuf> {
+    if program.contains('/') {
+        return Ok(PathBuf::from(program));
+    }
+    std::env::var_os("PATH")
+        .iter()
+        .flat_map(std::env::split_paths)
+        .map(|dir| dir.join(program))
+        .find(|candidate| is_executable(candidate))
+        .ok_or_else(|| AdapterError::NotFound(format!("Program not found on PATH: {}", program)))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata().is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Read a stream to the end, keeping at most `max` bytes
+async fn read_bounded<R: AsyncRead + Unpin>(mut reader: R, max: usize) -> std::io::Result<(Vec<u8>, bool)> {
+    let mut kept = Vec::new();
+    let mut truncated = false;
+    let mut chunk = [0u8; 8192];
+    loop {
+        let n = reader.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok((kept, truncated));
+        }
+        let room = max.saturating_sub(kept.len());
+        kept.extend_from_slice(&chunk[..n.min(room)]);
+        truncated |= n > room;
+    }
+}
+
+#[async_trait]
+impl Adapter for ProcessAdapter {
+    fn domain(&self) -> &str {
+        "process"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn supported_actions(&self) -> Vec<&str> {
+        vec!["process.run"]
+    }
+
+    async fn execute(&self, vakya: &Vakya, context: &ExecutionContext) -> AdapterResult<ExecutionResult> {
+        let start = std::time::Instant::now();
+
+        if vakya.v3_kriya.action != "process.run" {
+            return Err(AdapterError::UnsupportedAction(vakya.v3_kriya.action.clone()));
+        }
+
+        let program = self.program(vakya)?;
+        let mut command = self.build_command(&program, vakya)?;
+
+        if context.dry_run {
+            let duration_ms = start.elapsed().as_millis() as u64;
+            return Ok(ExecutionResult::success(
+                serde_json::json!({
+                    "dry_run": true,
+                    "program": program,
+                    "args": vakya.body.get("args").cloned().unwrap_or(serde_json::json!([])),
+                }),
+                vec![],
+                duration_ms,
+            ));
+        }
+
+        debug!(program = %program, "Running process");
+
+        let mut child = command.spawn()?;
+        let stdout = child.stdout.take().ok_or_else(|| AdapterError::Internal("stdout not captured".to_string()))?;
+        let stderr = child.stderr.take().ok_or_else(|| AdapterError::Internal("stderr not captured".to_string()))?;
+        let max_output = self.sandbox.max_output_bytes;
+
+        let timeout = context.timeout_ms
+            .map(Duration::from_millis)
+            .unwrap_or_else(|| Duration::from_secs(self.default_timeout_secs));
+
+        let run = async {
+            let (stdout, stderr, status) = tokio::join!(
+                read_bounded(stdout, max_output),
+                read_bounded(stderr, max_output),
+                child.wait(),
+            );
+            Ok::<_, std::io::Error>((stdout?, stderr?, status?))
+        };
+        let ((stdout, stdout_truncated), (stderr, stderr_truncated), status) =
+            match tokio::time::timeout(timeout, run).await {
+                Ok(result) => result?,
+                Err(_) => {
+                    if let Err(e) = child.kill().await {
+                        warn!(program = %program, error = %e, "Failed to kill timed-out process");
+                    }
+                    return Err(AdapterError::Timeout);
+                }
+            };
+
+        let stdout_snapshot = StateSnapshot::from_bytes(&stdout);
+        let effect = EffectBuilder::new(
+            vakya.vakya_id.0.clone(),
+            EffectBucket::External,
+            vakya.v2_karma.rid.0.clone(),
+        )
+        .target_type("process")
+        .after(stdout_snapshot.clone())
+        .metadata("program", serde_json::json!(program))
+        .metadata("args", vakya.body.get("args").cloned().unwrap_or(serde_json::json!([])))
+        .metadata("exit_code", serde_json::json!(status.code()))
+        .build();
+
+        let duration_ms = start.elapsed().as_millis() as u64;
+
+        let mut result = ExecutionResult::success(
+            serde_json::json!({
+                "exit_code": status.code(),
+                "stdout": String::from_utf8_lossy(&stdout),
+                "stderr": String::from_utf8_lossy(&stderr),
+                "stdout_truncated": stdout_truncated,
+                "stderr_truncated": stderr_truncated,
+                "stdout_hash": stdout_snapshot.hash,
+            }),
+            vec![effect],
+            duration_ms,
+        );
+        if !status.success() {
+            result.success = false;
+            result.error = Some(format!("{} exited with {}", program, status));
+        }
+        Ok(result)
+    }
+
+    fn action_descriptors(&self) -> Vec<ActionDescriptor> {
+        process_action_descriptors()
+    }
+
+    fn can_rollback(&self, _action: &str) -> bool {
+        false // A command's side effects are unknown to the gateway
+    }
+
+    async fn rollback(&self, _effect: &CapturedEffect) -> AdapterResult<()> {
+        Err(AdapterError::RollbackFailed(
+            "Process executions cannot be rolled back".to_string()
+        ))
+    }
+
+    async fn health_check(&self) -> AdapterResult<HealthStatus> {
+        if let Some(jail) = &self.sandbox.jail_dir {
+            if !jail.is_dir() {
+                return Ok(HealthStatus::unhealthy(format!("Jail directory missing: {}", jail.display())));
+            }
+        }
+        Ok(HealthStatus::healthy())
+    }
+}
+
+/// Get action descriptors for the process adapter
+pub fn process_action_descriptors() -> Vec<ActionDescriptor> {
+    vec![
+        ActionDescriptor::new("process.run", "Run an allow-listed program")
+            .with_effect(EffectBucket::External),
+    ]
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    use aapi_core::types::*;
+    use aapi_core::vakya::*;
+
+    fn make_vakya(program: &str, body: serde_json::Value) -> Vakya {
+        Vakya::builder()
+            .karta(Karta {
+                pid: PrincipalId::new("agent:operator"),
+                role: None,
+                realm: None,
+                key_id: None,
+                actor_type: ActorType::Agent,
+                delegation_chain: vec![],
+            })
+            .karma(Karma {
+                rid: ResourceId::new(format!("process:{}", program)),
+                kind: Some("process".to_string()),
+                ns: None,
+                version: None,
+                labels: HashMap::new(),
+            })
+            .kriya(Kriya::new("process", "run"))
+            .adhikarana(Adhikarana {
+                cap: CapabilityRef::Reference { cap_ref: "cap:test".to_string() },
+                policy_ref: None,
+                ttl: None,
+                budgets: vec![],
+                approval_lane: ApprovalLane::None,
+                scopes: vec![],
+                context: None,
+                delegation_chain_cid: None,
+                execution_constraints: None,
+                port_id: None,
+                required_phase: None,
+                required_role: None,
+            })
+            .body(body)
+            .build()
+            .unwrap()
+    }
+
+    fn sh(script: &str) -> Vakya {
+        make_vakya("sh", serde_json::json!({ "args": ["-c", script] }))
+    }
+
+    #[tokio::test]
+    async fn test_run_captures_output_and_exit_code() {
+        let adapter = ProcessAdapter::new().with_allowed_program("sh");
+
+        let result = adapter.execute(&sh("echo hello; echo oops >&2"), &ExecutionContext::new("req-1")).await.unwrap();
+        assert!(result.success);
+        let data = result.data.unwrap();
+        assert_eq!(data["stdout"], "hello\n");
+        assert_eq!(data["stderr"], "oops\n");
+        assert_eq!(result.effects[0].bucket, EffectBucket::External);
+        assert!(!adapter.can_rollback("process.run"));
+
+        let result = adapter.execute(&sh("exit 3"), &ExecutionContext::new("req-2")).await.unwrap();
+        assert!(!result.success);
+        assert_eq!(result.data.unwrap()["exit_code"], 3);
+
+        let denied = make_vakya("rm", serde_json::json!({ "args": ["-rf", "/tmp/x"] }));
+        assert!(matches!(
+            adapter.execute(&denied, &ExecutionContext::new("req-3")).await,
+            Err(AdapterError::PermissionDenied(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_output_is_bounded_and_timeout_kills() {
+        let adapter = ProcessAdapter::new()
+            .with_allowed_program("sh")
+            .with_sandbox(ProcessSandbox { max_output_bytes: 16, ..Default::default() });
+
+        let result = adapter.execute(&sh("head -c 100000 /dev/zero"), &ExecutionContext::new("req-4")).await.unwrap();
+        let data = result.data.unwrap();
+        assert_eq!(data["stdout"].as_str().unwrap().len(), 16);
+        assert_eq!(data["stdout_truncated"], true);
+
+        let started = std::time::Instant::now();
+        let result = adapter.execute(&sh("sleep 5"), &ExecutionContext::new("req-5").with_timeout(100)).await;
+        assert!(matches!(result, Err(AdapterError::Timeout)));
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_working_directory_jail() {
+        let jail = tempfile::tempdir().unwrap();
+        std::fs::create_dir(jail.path().join("work")).unwrap();
+        let adapter = ProcessAdapter::new()
+            .with_allowed_program("pwd")
+            .with_sandbox(ProcessSandbox { jail_dir: Some(jail.path().to_path_buf()), ..Default::default() });
+
+        let vakya = make_vakya("pwd", serde_json::json!({ "cwd": "work" }));
+        let result = adapter.execute(&vakya, &ExecutionContext::new("req-6")).await.unwrap();
+        let expected = jail.path().canonicalize().unwrap().join("work");
+        assert_eq!(result.data.unwrap()["stdout"].as_str().unwrap().trim_end(), expected.to_str().unwrap());
+
+        let escape = make_vakya("pwd", serde_json::json!({ "cwd": "../" }));
+        assert!(matches!(
+            adapter.execute(&escape, &ExecutionContext::new("req-7")).await,
+            Err(AdapterError::PermissionDenied(_))
+        ));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_resource_limits_apply_to_child() {
+        let adapter = ProcessAdapter::new()
+            .with_allowed_program("sh")
+            .with_sandbox(ProcessSandbox { cpu_time_secs: Some(3), ..Default::default() });
+
+        let result = adapter.execute(&sh("ulimit -t"), &ExecutionContext::new("req-8")).await.unwrap();
+        assert_eq!(result.data.unwrap()["stdout"], "3\n");
+    }
+
+    #[tokio::test]
+    async fn test_request_env_cannot_change_the_program() {
+        // A decoy `sh` the request tries to put first on the PATH
+        let decoy = tempfile::tempdir().unwrap();
+        let fake = decoy.path().join("sh");
+        std::fs::write(&fake, "#!/bin/sh\necho decoy\n").unwrap();
+        std::fs::set_permissions(&fake, std::os::unix::fs::PermissionsExt::from_mode(0o755)).unwrap();
+
+        let adapter = ProcessAdapter::new()
+            .with_allowed_program("sh")
+            .with_allowed_env("GREETING")
+            .with_allowed_env("PATH")
+            .with_allowed_env("LD_PRELOAD");
+
+        let path = decoy.path().to_str().unwrap();
+        for (name, value) in [("PATH", path), ("LD_PRELOAD", "/tmp/evil.so"), ("HOME", "/")] {
+            let vakya = make_vakya("sh", serde_json::json!({ "args": ["-c", "echo real"], "env": { name: value } }));
+            let result = adapter.execute(&vakya, &ExecutionContext::new("req-9")).await;
+            assert!(matches!(result, Err(AdapterError::PermissionDenied(_))), "{}", name);
+        }
+
+        let vakya = make_vakya("sh", serde_json::json!({ "args": ["-c", "echo $GREETING"], "env": { "GREETING": "hi" } }));
+        let result = adapter.execute(&vakya, &ExecutionContext::new("req-10")).await.unwrap();
+        assert_eq!(result.data.unwrap()["stdout"], "hi\n");
+    }
+}
@@ -0,0 +1,358 @@
+//! Queue adapter for publishing messages to a broker
+//!
+//! The broker client is abstracted behind the `MessageBroker` trait so that
+//! NATS, Kafka or an in-process channel can be plugged in. Publishing is an
+//! external side effect and cannot be rolled back.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use aapi_core::types::EffectBucket;
+use aapi_core::Vakya;
+
+use crate::effect::{CapturedEffect, EffectBuilder, StateSnapshot};
+use crate::error::{AdapterError, AdapterResult};
+use crate::traits::{Adapter, ActionDescriptor, ExecutionContext, ExecutionResult, HealthStatus};
+
+// ============================================================================
+// MessageBroker trait
+// ============================================================================
+
+/// Abstraction over a message broker client.
+#[async_trait]
+pub trait MessageBroker: Send + Sync {
+    /// Broker kind, e.g. `"nats"` or `"kafka"`; recorded in effect metadata.
+    fn kind(&self) -> &str;
+
+    /// Publish a message and wait for the broker's acknowledgement.
+    async fn publish(&self, message: OutboundMessage) -> AdapterResult<PublishAck>;
+
+    /// Health check the broker connection.
+    async fn health_check(&self) -> AdapterResult<HealthStatus>;
+}
+
+/// Message handed to the broker.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboundMessage {
+    /// Topic (Kafka) or subject (NATS)
+    pub topic: String,
+    /// Partitioning / deduplication key
+    pub key: Option<String>,
+    pub payload: Vec<u8>,
+    pub headers: HashMap<String, String>,
+}
+
+/// Broker acknowledgement for a published message.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PublishAck {
+    /// Partition the message landed in (Kafka)
+    pub partition: Option<i32>,
+    /// Offset (Kafka) or stream sequence (NATS JetStream)
+    pub offset: Option<i64>,
+    /// Any other broker-specific acknowledgement fields
+    pub metadata: HashMap<String, serde_json::Value>,
+}
+
+// ============================================================================
+// QueueAdapter
+// ============================================================================
+
+/// Adapter for `queue.publish`
+///
+/// The resource ID is the topic, optionally prefixed with `queue:`. The body
+/// carries `message` (string or JSON), and optional `key` and `headers`.
+pub struct QueueAdapter {
+    broker: Box<dyn MessageBroker>,
+    /// Maximum encoded message size
+    max_message_size: usize,
+    /// Default timeout in seconds
+    default_timeout_secs: u64,
+}
+
+impl QueueAdapter {
+    pub fn new(broker: Box<dyn MessageBroker>) -> Self {
+        Self {
+            broker,
+            max_message_size: 1024 * 1024, // 1MB
+            default_timeout_secs: 10,
+        }
+    }
+
+    pub fn with_max_message_size(mut self, size: usize) -> Self {
+        self.max_message_size = size;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout_secs: u64) -> Self {
+        self.default_timeout_secs = timeout_secs;
+        self
+    }
+
+    /// Build the outbound message from a VĀKYA
+    fn build_message(&self, vakya: &Vakya) -> AdapterResult<OutboundMessage> {
+        let topic = vakya.v2_karma.rid.0
+            .strip_prefix("queue:")
+            .unwrap_or(&vakya.v2_karma.rid.0);
+        if topic.is_empty() {
+            return Err(AdapterError::InvalidInput("Topic is empty".to_string()));
+        }
+
+        let body = &vakya.body;
+        let payload = match body.get("message") {
+            Some(serde_json::Value::String(s)) => s.as_bytes().to_vec(),
+            Some(other) => serde_json::to_vec(other)?,
+            None => return Err(AdapterError::InvalidInput("Missing 'message' in body".to_string())),
+        };
+
+        if payload.len() > self.max_message_size {
+            return Err(AdapterError::InvalidInput(format!(
+                "Message too large: {} bytes (max {})",
+                payload.len(),
+                self.max_message_size
+            )));
+        }
+
+        let headers = body.get("headers")
+            .and_then(|v| v.as_object())
+            .map(|h| {
+                h.iter()
+                    .filter_map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string())))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(OutboundMessage {
+            topic: topic.to_string(),
+            key: body.get("key").and_then(|v| v.as_str()).map(String::from),
+            payload,
+            headers,
+        })
+    }
+}
+
+#[async_trait]
+impl Adapter for QueueAdapter {
+    fn domain(&self) -> &str {
+        "queue"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn supported_actions(&self) -> Vec<&str> {
+        vec!["queue.publish"]
+    }
+
+    async fn execute(&self, vakya: &Vakya, context: &ExecutionContext) -> AdapterResult<ExecutionResult> {
+        let start = std::time::Instant::now();
+
+        if vakya.v3_kriya.action != "queue.publish" {
+            return Err(AdapterError::UnsupportedAction(vakya.v3_kriya.action.clone()));
+        }
+
+        let message = self.build_message(vakya)?;
+        let snapshot = StateSnapshot::from_bytes(&message.payload);
+
+        debug!(topic = %message.topic, broker = %self.broker.kind(), "Publishing message");
+
+        if context.dry_run {
+            let duration_ms = start.elapsed().as_millis() as u64;
+            return Ok(ExecutionResult::success(
+                serde_json::json!({
+                    "dry_run": true,
+                    "topic": message.topic,
+                    "would_publish": message.payload.len(),
+                }),
+                vec![],
+                duration_ms,
+            ));
+        }
+
+        let topic = message.topic.clone();
+        let key = message.key.clone();
+
+        let timeout = context.timeout_ms
+            .map(Duration::from_millis)
+            .unwrap_or_else(|| Duration::from_secs(self.default_timeout_secs));
+
+        let ack = tokio::time::timeout(timeout, self.broker.publish(message))
+            .await
+            .map_err(|_| AdapterError::Timeout)??;
+
+        let effect = EffectBuilder::new(
+            vakya.vakya_id.0.clone(),
+            EffectBucket::External,
+            vakya.v2_karma.rid.0.clone(),
+        )
+        .target_type("queue")
+        .after(snapshot.clone())
+        .metadata("broker", serde_json::json!(self.broker.kind()))
+        .metadata("topic", serde_json::json!(topic))
+        .metadata("key", serde_json::json!(key))
+        .metadata("partition", serde_json::json!(ack.partition))
+        .metadata("offset", serde_json::json!(ack.offset))
+        .metadata("ack", serde_json::json!(ack.metadata))
+        .build();
+
+        let duration_ms = start.elapsed().as_millis() as u64;
+
+        Ok(ExecutionResult::success(
+            serde_json::json!({
+                "topic": topic,
+                "message_hash": snapshot.hash,
+                "size": snapshot.size,
+                "partition": ack.partition,
+                "offset": ack.offset,
+            }),
+            vec![effect],
+            duration_ms,
+        ))
+    }
+
+    fn action_descriptors(&self) -> Vec<ActionDescriptor> {
+        queue_action_descriptors()
+    }
+
+    fn can_rollback(&self, _action: &str) -> bool {
+        false // Published messages cannot be recalled
+    }
+
+    async fn rollback(&self, _effect: &CapturedEffect) -> AdapterResult<()> {
+        Err(AdapterError::RollbackFailed(
+            "Published messages cannot be rolled back".to_string()
+        ))
+    }
+
+    async fn health_check(&self) -> AdapterResult<HealthStatus> {
+        self.broker.health_check().await
+    }
+}
+
+/// Get action descriptors for the queue adapter
+pub fn queue_action_descriptors() -> Vec<ActionDescriptor> {
+    vec![
+        ActionDescriptor::new("queue.publish", "Publish a message to a topic")
+            .with_effect(EffectBucket::External),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    use aapi_core::types::*;
+    use aapi_core::vakya::*;
+
+    /// Broker that records published messages.
+    struct MockBroker {
+        published: Arc<Mutex<Vec<OutboundMessage>>>,
+    }
+
+    #[async_trait]
+    impl MessageBroker for MockBroker {
+        fn kind(&self) -> &str {
+            "mock"
+        }
+
+        async fn publish(&self, message: OutboundMessage) -> AdapterResult<PublishAck> {
+            let mut published = self.published.lock().unwrap();
+            published.push(message);
+            Ok(PublishAck {
+                partition: Some(0),
+                offset: Some(published.len() as i64 - 1),
+                metadata: HashMap::new(),
+            })
+        }
+
+        async fn health_check(&self) -> AdapterResult<HealthStatus> {
+            Ok(HealthStatus::healthy())
+        }
+    }
+
+    fn make_vakya(rid: &str, body: serde_json::Value) -> Vakya {
+        Vakya::builder()
+            .karta(Karta {
+                pid: PrincipalId::new("agent:publisher"),
+                role: None,
+                realm: None,
+                key_id: None,
+                actor_type: ActorType::Agent,
+                delegation_chain: vec![],
+            })
+            .karma(Karma {
+                rid: ResourceId::new(rid),
+                kind: Some("queue".to_string()),
+                ns: None,
+                version: None,
+                labels: HashMap::new(),
+            })
+            .kriya(Kriya::new("queue", "publish"))
+            .adhikarana(Adhikarana {
+                cap: CapabilityRef::Reference { cap_ref: "cap:test".to_string() },
+                policy_ref: None,
+                ttl: None,
+                budgets: vec![],
+                approval_lane: ApprovalLane::None,
+                scopes: vec![],
+                context: None,
+                delegation_chain_cid: None,
+                execution_constraints: None,
+                port_id: None,
+                required_phase: None,
+                required_role: None,
+            })
+            .body(body)
+            .build()
+            .unwrap()
+    }
+
+    fn adapter() -> (QueueAdapter, Arc<Mutex<Vec<OutboundMessage>>>) {
+        let published = Arc::new(Mutex::new(Vec::new()));
+        let broker = MockBroker { published: Arc::clone(&published) };
+        (QueueAdapter::new(Box::new(broker)), published)
+    }
+
+    #[tokio::test]
+    async fn test_publish_captures_ack() {
+        let (adapter, published) = adapter();
+        let vakya = make_vakya(
+            "queue:orders.created",
+            serde_json::json!({"message": {"order_id": 7}, "key": "order-7"}),
+        );
+
+        let result = adapter.execute(&vakya, &ExecutionContext::new("req-1")).await.unwrap();
+        assert!(result.success);
+
+        let published = published.lock().unwrap();
+        assert_eq!(published.len(), 1);
+        assert_eq!(published[0].topic, "orders.created");
+        assert_eq!(published[0].key.as_deref(), Some("order-7"));
+
+        let effect = &result.effects[0];
+        assert_eq!(effect.bucket, EffectBucket::External);
+        assert!(!effect.reversible);
+        assert_eq!(effect.after.as_ref().unwrap().hash, StateSnapshot::from_bytes(&published[0].payload).hash);
+        assert_eq!(effect.metadata.get("offset"), Some(&serde_json::json!(0)));
+        assert!(!adapter.can_rollback("queue.publish"));
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_and_size_limit() {
+        let (adapter, published) = adapter();
+        let adapter = adapter.with_max_message_size(4);
+
+        let vakya = make_vakya("queue:events", serde_json::json!({"message": "ok"}));
+        let result = adapter.execute(&vakya, &ExecutionContext::new("req-2").dry_run()).await.unwrap();
+        assert!(result.effects.is_empty());
+        assert!(published.lock().unwrap().is_empty());
+
+        let vakya = make_vakya("queue:events", serde_json::json!({"message": "too large"}));
+        assert!(adapter.execute(&vakya, &ExecutionContext::new("req-3")).await.is_err());
+    }
+}
@@ -0,0 +1,578 @@
+//! Redis adapter for cache and shared state operations
+
+use async_trait::async_trait;
+use deadpool_redis::redis::{self, AsyncCommands};
+use deadpool_redis::{Config, Connection, Pool, Runtime};
+use std::time::Duration;
+use tracing::{debug, info};
+
+use aapi_core::types::EffectBucket;
+use aapi_core::Vakya;
+
+use crate::effect::{CapturedEffect, EffectBuilder, ReversalMethod, StateSnapshot};
+use crate::error::{AdapterError, AdapterResult};
+use crate::traits::{Adapter, ActionDescriptor, ExecutionContext, ExecutionResult, HealthStatus};
+
+/// Redis adapter for get/set/del/incr on string keys
+///
+/// The resource ID is the key, optionally prefixed with `redis:`.
+pub struct RedisAdapter {
+    pool: Pool,
+    /// Maximum value size accepted by `redis.set` and captured in snapshots
+    max_value_size: usize,
+    /// Default timeout in seconds
+    default_timeout_secs: u64,
+}
+
+impl RedisAdapter {
+    /// Create an adapter with a connection pool for the given URL
+    ///
+    /// Connections are opened lazily, so an unreachable server is reported
+    /// by the first action or health check rather than here.
+    pub fn new(url: &str) -> AdapterResult<Self> {
+        let pool = Config::from_url(url)
+            .create_pool(Some(Runtime::Tokio1))
+            .map_err(|e| AdapterError::Redis(e.to_string()))?;
+
+        Ok(Self::from_pool(pool))
+    }
+
+    /// Create an adapter from an existing pool
+    pub fn from_pool(pool: Pool) -> Self {
+        Self {
+            pool,
+            max_value_size: 1024 * 1024, // 1MB
+            default_timeout_secs: 5,
+        }
+    }
+
+    pub fn with_max_value_size(mut self, size: usize) -> Self {
+        self.max_value_size = size;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout_secs: u64) -> Self {
+        self.default_timeout_secs = timeout_secs;
+        self
+    }
+
+    /// Extract the key from a resource ID
+    fn resolve_key(&self, resource_id: &str) -> AdapterResult<String> {
+        let key = resource_id.strip_prefix("redis:").unwrap_or(resource_id);
+        if key.is_empty() {
+            return Err(AdapterError::InvalidInput("Redis key is empty".to_string()));
+        }
+        Ok(key.to_string())
+    }
+
+    /// Extract the value to store from the request body
+    fn extract_value(&self, body: &serde_json::Value) -> AdapterResult<Vec<u8>> {
+        let value = body.get("value")
+            .ok_or_else(|| AdapterError::InvalidInput("Missing 'value' in body".to_string()))?;
+
+        let bytes = match value {
+            serde_json::Value::String(s) => s.as_bytes().to_vec(),
+            other => serde_json::to_vec(other)?,
+        };
+
+        if bytes.len() > self.max_value_size {
+            return Err(AdapterError::InvalidInput(format!(
+                "Value too large: {} bytes (max {})",
+                bytes.len(),
+                self.max_value_size
+            )));
+        }
+
+        Ok(bytes)
+    }
+
+    /// Snapshot a key's value and remaining TTL
+    fn snapshot(&self, value: Option<&[u8]>, ttl_ms: i64) -> StateSnapshot {
+        let Some(bytes) = value else {
+            return StateSnapshot::not_exists();
+        };
+
+        let mut snapshot = StateSnapshot::from_bytes(bytes);
+        if bytes.len() <= self.max_value_size {
+            snapshot.content = Some(encode_value(bytes));
+        }
+        if ttl_ms > 0 {
+            snapshot = snapshot.with_property("ttl_ms", serde_json::json!(ttl_ms));
+        }
+        snapshot
+    }
+
+    async fn connection(&self) -> AdapterResult<Connection> {
+        self.pool.get().await.map_err(|e| AdapterError::Unavailable(format!("Redis connection: {}", e)))
+    }
+
+    /// Read a key's value and TTL atomically
+    async fn read_state(&self, conn: &mut Connection, key: &str) -> AdapterResult<(Option<Vec<u8>>, i64)> {
+        redis::pipe()
+            .atomic()
+            .get(key)
+            .pttl(key)
+            .query_async(conn)
+            .await
+            .map_err(redis_error)
+    }
+
+    /// Read a key's value and TTL and apply `write` in the same MULTI/EXEC
+    /// transaction, so the captured state is exactly what `write` replaced
+    async fn read_and_write<T: redis::FromRedisValue>(
+        &self,
+        conn: &mut Connection,
+        key: &str,
+        write: impl FnOnce(&mut redis::Pipeline),
+    ) -> AdapterResult<(Option<Vec<u8>>, i64, T)> {
+        read_and_write_pipeline(key, write)
+            .query_async(conn)
+            .await
+            .map_err(redis_error)
+    }
+
+    /// Build a reversible effect restoring the prior value
+    fn mutation_effect(
+        &self,
+        vakya: &Vakya,
+        bucket: EffectBucket,
+        key: &str,
+        before: (Option<&[u8]>, i64),
+        after: StateSnapshot,
+    ) -> CapturedEffect {
+        let (before_value, before_ttl) = before;
+        let before_snapshot = self.snapshot(before_value, before_ttl);
+
+        // A value too large to capture cannot be restored
+        let restorable = before_value.is_none() || before_snapshot.content.is_some();
+
+        let mut builder = EffectBuilder::new(
+            vakya.vakya_id.0.clone(),
+            bucket,
+            vakya.v2_karma.rid.0.clone(),
+        )
+        .target_type("redis")
+        .before(before_snapshot.clone())
+        .after(after)
+        .metadata("key", serde_json::json!(key));
+
+        if restorable {
+            let method = if before_value.is_none() {
+                ReversalMethod::Delete
+            } else {
+                ReversalMethod::RestoreState
+            };
+            builder = builder.reversible(method, serde_json::json!({
+                "key": key,
+                "before_hash": before_snapshot.hash,
+                "before_content": before_snapshot.content,
+                "ttl_ms": (before_ttl > 0).then_some(before_ttl),
+            }));
+        }
+
+        builder.build()
+    }
+
+    /// Execute redis.get action
+    async fn execute_get(&self, vakya: &Vakya, key: &str) -> AdapterResult<ExecutionResult> {
+        let start = std::time::Instant::now();
+        let mut conn = self.connection().await?;
+
+        let (value, ttl_ms) = self.read_state(&mut conn, key).await?;
+
+        let effect = EffectBuilder::new(
+            vakya.vakya_id.0.clone(),
+            EffectBucket::Read,
+            vakya.v2_karma.rid.0.clone(),
+        )
+        .target_type("redis")
+        .after(self.snapshot(value.as_deref(), ttl_ms))
+        .metadata("key", serde_json::json!(key))
+        .build();
+
+        let duration_ms = start.elapsed().as_millis() as u64;
+
+        Ok(ExecutionResult::success(
+            serde_json::json!({
+                "key": key,
+                "exists": value.is_some(),
+                "value": value.as_deref().map(encode_value),
+                "ttl_ms": (ttl_ms > 0).then_some(ttl_ms),
+            }),
+            vec![effect],
+            duration_ms,
+        ))
+    }
+
+    /// Execute redis.set action
+    async fn execute_set(
+        &self,
+        vakya: &Vakya,
+        key: &str,
+        context: &ExecutionContext,
+    ) -> AdapterResult<ExecutionResult> {
+        let start = std::time::Instant::now();
+        let value = self.extract_value(&vakya.body)?;
+        let ttl_secs = vakya.body.get("ttl_secs").and_then(|v| v.as_u64());
+
+        if context.dry_run {
+            let duration_ms = start.elapsed().as_millis() as u64;
+            return Ok(ExecutionResult::success(
+                serde_json::json!({"dry_run": true, "would_write": value.len()}),
+                vec![],
+                duration_ms,
+            ));
+        }
+
+        let mut conn = self.connection().await?;
+        let (before, before_ttl, ()) = self.read_and_write(&mut conn, key, |pipe| {
+            match ttl_secs {
+                Some(ttl) if ttl > 0 => pipe.set_ex(key, &value, ttl),
+                _ => pipe.set(key, &value),
+            };
+        }).await?;
+
+        let mut after = self.snapshot(Some(&value), 0);
+        if let Some(ttl) = ttl_secs.filter(|t| *t > 0) {
+            after = after.with_property("ttl_ms", serde_json::json!(ttl * 1000));
+        }
+
+        let bucket = if before.is_none() { EffectBucket::Create } else { EffectBucket::Update };
+        let effect = self.mutation_effect(vakya, bucket, key, (before.as_deref(), before_ttl), after);
+
+        let duration_ms = start.elapsed().as_millis() as u64;
+
+        Ok(ExecutionResult::success(
+            serde_json::json!({
+                "key": key,
+                "size": value.len(),
+                "created": before.is_none(),
+                "ttl_secs": ttl_secs,
+            }),
+            vec![effect],
+            duration_ms,
+        ))
+    }
+
+    /// Execute redis.del action
+    async fn execute_del(
+        &self,
+        vakya: &Vakya,
+        key: &str,
+        context: &ExecutionContext,
+    ) -> AdapterResult<ExecutionResult> {
+        let start = std::time::Instant::now();
+
+        if context.dry_run {
+            let duration_ms = start.elapsed().as_millis() as u64;
+            return Ok(ExecutionResult::success(
+                serde_json::json!({"dry_run": true, "would_delete": key}),
+                vec![],
+                duration_ms,
+            ));
+        }
+
+        let mut conn = self.connection().await?;
+        let (before, before_ttl, deleted): (_, _, i64) = self.read_and_write(&mut conn, key, |pipe| {
+            pipe.del(key);
+        }).await?;
+
+        let effects = if before.is_some() {
+            vec![self.mutation_effect(
+                vakya,
+                EffectBucket::Delete,
+                key,
+                (before.as_deref(), before_ttl),
+                StateSnapshot::not_exists(),
+            )]
+        } else {
+            vec![]
+        };
+
+        let duration_ms = start.elapsed().as_millis() as u64;
+
+        Ok(ExecutionResult::success(
+            serde_json::json!({
+                "key": key,
+                "deleted": deleted > 0,
+            }),
+            effects,
+            duration_ms,
+        ))
+    }
+
+    /// Execute redis.incr action
+    async fn execute_incr(
+        &self,
+        vakya: &Vakya,
+        key: &str,
+        context: &ExecutionContext,
+    ) -> AdapterResult<ExecutionResult> {
+        let start = std::time::Instant::now();
+        let by = match vakya.body.get("by") {
+            None => 1,
+            Some(v) => v.as_i64()
+                .ok_or_else(|| AdapterError::InvalidInput("'by' must be an integer".to_string()))?,
+        };
+
+        if context.dry_run {
+            let duration_ms = start.elapsed().as_millis() as u64;
+            return Ok(ExecutionResult::success(
+                serde_json::json!({"dry_run": true, "would_increment": key, "by": by}),
+                vec![],
+                duration_ms,
+            ));
+        }
+
+        let mut conn = self.connection().await?;
+        let (before, before_ttl, value): (_, _, i64) = self.read_and_write(&mut conn, key, |pipe| {
+            pipe.incr(key, by);
+        }).await?;
+
+        let bucket = if before.is_none() { EffectBucket::Create } else { EffectBucket::Update };
+        let after = self.snapshot(Some(value.to_string().as_bytes()), before_ttl);
+        let effect = self.mutation_effect(vakya, bucket, key, (before.as_deref(), before_ttl), after);
+
+        let duration_ms = start.elapsed().as_millis() as u64;
+
+        Ok(ExecutionResult::success(
+            serde_json::json!({
+                "key": key,
+                "value": value,
+            }),
+            vec![effect],
+            duration_ms,
+        ))
+    }
+}
+
+/// `GET` and `PTTL` of `key` followed by `write`, as one transaction
+fn read_and_write_pipeline(key: &str, write: impl FnOnce(&mut redis::Pipeline)) -> redis::Pipeline {
+    let mut pipe = redis::pipe();
+    pipe.atomic().get(key).pttl(key);
+    write(&mut pipe);
+    pipe
+}
+
+/// Encode a stored value for JSON, falling back to base64 for binary data
+fn encode_value(bytes: &[u8]) -> serde_json::Value {
+    match std::str::from_utf8(bytes) {
+        Ok(s) => serde_json::Value::String(s.to_string()),
+        Err(_) => serde_json::json!({
+            "_type": "binary",
+            "_encoding": "base64",
+            "_data": base64::Engine::encode(&base64::engine::general_purpose::STANDARD, bytes),
+        }),
+    }
+}
+
+/// Inverse of [`encode_value`]
+fn decode_value(value: &serde_json::Value) -> AdapterResult<Vec<u8>> {
+    if let Some(s) = value.as_str() {
+        return Ok(s.as_bytes().to_vec());
+    }
+
+    let data = value.get("_data")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| AdapterError::RollbackFailed("Unrecognized value encoding".to_string()))?;
+
+    base64::Engine::decode(&base64::engine::general_purpose::STANDARD, data)
+        .map_err(|e| AdapterError::RollbackFailed(e.to_string()))
+}
+
+fn redis_error(e: redis::RedisError) -> AdapterError {
+    if e.is_timeout() {
+        AdapterError::Timeout
+    } else {
+        AdapterError::Redis(e.to_string())
+    }
+}
+
+#[async_trait]
+impl Adapter for RedisAdapter {
+    fn domain(&self) -> &str {
+        "redis"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn supported_actions(&self) -> Vec<&str> {
+        vec![
+            "redis.get",
+            "redis.set",
+            "redis.del",
+            "redis.incr",
+        ]
+    }
+
+    async fn execute(&self, vakya: &Vakya, context: &ExecutionContext) -> AdapterResult<ExecutionResult> {
+        let key = self.resolve_key(&vakya.v2_karma.rid.0)?;
+        let action = vakya.v3_kriya.action.as_str();
+
+        debug!(key = %key, action = %action, "Executing Redis action");
+
+        let timeout = context.timeout_ms
+            .map(Duration::from_millis)
+            .unwrap_or_else(|| Duration::from_secs(self.default_timeout_secs));
+
+        let execution = async {
+            match action {
+                "redis.get" => self.execute_get(vakya, &key).await,
+                "redis.set" => self.execute_set(vakya, &key, context).await,
+                "redis.del" => self.execute_del(vakya, &key, context).await,
+                "redis.incr" => self.execute_incr(vakya, &key, context).await,
+                _ => Err(AdapterError::UnsupportedAction(action.to_string())),
+            }
+        };
+
+        tokio::time::timeout(timeout, execution)
+            .await
+            .map_err(|_| AdapterError::Timeout)?
+    }
+
+    fn action_descriptors(&self) -> Vec<ActionDescriptor> {
+        redis_action_descriptors()
+    }
+
+    fn can_rollback(&self, action: &str) -> bool {
+        matches!(action, "redis.set" | "redis.del" | "redis.incr")
+    }
+
+    async fn rollback(&self, effect: &CapturedEffect) -> AdapterResult<()> {
+        let reversal = effect.reversal.as_ref()
+            .ok_or_else(|| AdapterError::RollbackFailed("No reversal instructions".to_string()))?;
+
+        let key = reversal.data.get("key")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| AdapterError::RollbackFailed("Missing key in reversal".to_string()))?;
+
+        let mut conn = self.connection().await?;
+
+        match reversal.method {
+            ReversalMethod::Delete => {
+                conn.del::<_, ()>(key).await.map_err(redis_error)?;
+            }
+            ReversalMethod::RestoreState | ReversalMethod::Recreate => {
+                let content = reversal.data.get("before_content")
+                    .filter(|v| !v.is_null())
+                    .ok_or_else(|| AdapterError::RollbackFailed("Missing before_content in reversal".to_string()))?;
+                let value = decode_value(content)?;
+
+                match reversal.data.get("ttl_ms").and_then(|v| v.as_u64()) {
+                    Some(ttl_ms) => conn.pset_ex::<_, _, ()>(key, value, ttl_ms).await,
+                    None => conn.set::<_, _, ()>(key, value).await,
+                }
+                .map_err(redis_error)?;
+            }
+            _ => {
+                return Err(AdapterError::RollbackFailed(format!(
+                    "Unsupported reversal method: {:?}",
+                    reversal.method
+                )));
+            }
+        }
+
+        info!(key = %key, "Rollback completed");
+        Ok(())
+    }
+
+    async fn health_check(&self) -> AdapterResult<HealthStatus> {
+        let start = std::time::Instant::now();
+
+        let ping = async {
+            let mut conn = self.connection().await?;
+            redis::cmd("PING")
+                .query_async::<String>(&mut conn)
+                .await
+                .map_err(redis_error)
+        };
+
+        match tokio::time::timeout(Duration::from_secs(self.default_timeout_secs), ping).await {
+            Ok(Ok(_)) => Ok(HealthStatus::healthy()
+                .with_latency(start.elapsed().as_millis() as u64)),
+            Ok(Err(e)) => Ok(HealthStatus::unhealthy(e.to_string())),
+            Err(_) => Ok(HealthStatus::unhealthy("PING timed out")),
+        }
+    }
+}
+
+/// Get action descriptors for the Redis adapter
+pub fn redis_action_descriptors() -> Vec<ActionDescriptor> {
+    vec![
+        ActionDescriptor::new("redis.get", "Read a key")
+            .with_effect(EffectBucket::Read)
+            .idempotent(),
+        ActionDescriptor::new("redis.set", "Write a key, optionally with a TTL")
+            .with_effect(EffectBucket::Update)
+            .idempotent()
+            .reversible(),
+        ActionDescriptor::new("redis.del", "Delete a key")
+            .with_effect(EffectBucket::Delete)
+            .idempotent()
+            .reversible(),
+        ActionDescriptor::new("redis.incr", "Increment an integer key")
+            .with_effect(EffectBucket::Update)
+            .reversible(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn adapter() -> RedisAdapter {
+        RedisAdapter::new("redis://127.0.0.1:6379").unwrap()
+    }
+
+    #[test]
+    fn test_resolve_key() {
+        let adapter = adapter();
+
+        assert_eq!(adapter.resolve_key("redis:session:42").unwrap(), "session:42");
+        assert_eq!(adapter.resolve_key("counter").unwrap(), "counter");
+        assert!(adapter.resolve_key("redis:").is_err());
+    }
+
+    #[test]
+    fn test_value_size_limit() {
+        let adapter = adapter().with_max_value_size(8);
+
+        assert_eq!(adapter.extract_value(&serde_json::json!({"value": "short"})).unwrap(), b"short");
+        assert_eq!(adapter.extract_value(&serde_json::json!({"value": 42})).unwrap(), b"42");
+        assert!(adapter.extract_value(&serde_json::json!({"value": "much too long"})).is_err());
+        assert!(adapter.extract_value(&serde_json::json!({})).is_err());
+    }
+
+    #[test]
+    fn test_value_encoding_roundtrip() {
+        for bytes in [b"plain text".to_vec(), vec![0xff, 0x00, 0xfe]] {
+            assert_eq!(decode_value(&encode_value(&bytes)).unwrap(), bytes);
+        }
+    }
+
+    #[test]
+    fn test_before_state_is_read_in_the_writing_transaction() {
+        let pipe = read_and_write_pipeline("counter", |pipe| {
+            pipe.incr("counter", 2);
+        });
+        let packed = String::from_utf8(pipe.get_packed_pipeline()).unwrap();
+
+        let positions: Vec<usize> = ["MULTI", "GET", "PTTL", "INCRBY", "EXEC"].iter()
+            .map(|command| packed.find(&format!("\r\n{}\r\n", command)).expect(command))
+            .collect();
+        assert!(positions.windows(2).all(|pair| pair[0] < pair[1]), "{:?}", packed);
+    }
+
+    #[test]
+    fn test_snapshot_captures_ttl() {
+        let adapter = adapter();
+
+        assert_eq!(adapter.snapshot(None, -2).hash, "NOT_EXISTS");
+
+        let snapshot = adapter.snapshot(Some(b"v"), 1500);
+        assert_eq!(snapshot.content, Some(serde_json::json!("v")));
+        assert_eq!(snapshot.properties.get("ttl_ms"), Some(&serde_json::json!(1500)));
+    }
+}
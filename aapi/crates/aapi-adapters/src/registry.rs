@@ -2,20 +2,82 @@
 
 use async_trait::async_trait;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, RwLock as SyncRwLock};
+use std::time::Duration;
+use futures::StreamExt;
+use tokio::sync::mpsc::UnboundedSender;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
 use aapi_core::Vakya;
 
 use crate::error::{AdapterError, AdapterResult};
-use crate::traits::{Adapter, ActionDescriptor, ExecutionContext, ExecutionResult, HealthStatus};
+use crate::traits::{Adapter, ActionDescriptor, ExecutionContext, ExecutionEvent, ExecutionResult, HealthStatus};
 use crate::effect::CapturedEffect;
 
+/// What the dispatcher does when a domain's primary adapter was unhealthy
+/// at the last `health_check_all`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailoverPolicy {
+    /// Route to the domain's failover adapter if it is not also unhealthy
+    Failover,
+    /// Reject with `Unavailable` without attempting the primary
+    FailFast,
+}
+
+/// Which of a domain's actions the registry will route, whatever its
+/// adapter supports
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionFilter {
+    /// Only these actions
+    Allow(Vec<String>),
+    /// Every action but these
+    Deny(Vec<String>),
+}
+
+impl ActionFilter {
+    pub fn allow<I, S>(actions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::Allow(actions.into_iter().map(Into::into).collect())
+    }
+
+    pub fn deny<I, S>(actions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::Deny(actions.into_iter().map(Into::into).collect())
+    }
+
+    pub fn permits(&self, action: &str) -> bool {
+        match self {
+            Self::Allow(actions) => actions.iter().any(|a| a == action),
+            Self::Deny(actions) => !actions.iter().any(|a| a == action),
+        }
+    }
+}
+
 /// Registry for managing adapters
+///
+/// A domain may have a failover adapter besides its primary. Domains without
+/// a [`FailoverPolicy`] always route to the primary, whatever its health.
+/// An [`ActionFilter`] disables actions of a domain outright: they are
+/// refused as unsupported before any adapter sees them.
 pub struct AdapterRegistry {
     adapters: HashMap<String, Arc<dyn Adapter>>,
     action_map: HashMap<String, String>, // action -> domain
+    failovers: HashMap<String, Arc<dyn Adapter>>,
+    policies: HashMap<String, FailoverPolicy>,
+    filters: HashMap<String, ActionFilter>,
+    /// Results of the last `health_check_all`, primaries by domain
+    health: SyncRwLock<HashMap<String, HealthStatus>>,
+    failover_health: SyncRwLock<HashMap<String, HealthStatus>>,
+    failover_counts: Mutex<HashMap<String, u64>>,
 }
 
 impl Default for AdapterRegistry {
@@ -29,6 +91,12 @@ impl AdapterRegistry {
         Self {
             adapters: HashMap::new(),
             action_map: HashMap::new(),
+            failovers: HashMap::new(),
+            policies: HashMap::new(),
+            filters: HashMap::new(),
+            health: SyncRwLock::new(HashMap::new()),
+            failover_health: SyncRwLock::new(HashMap::new()),
+            failover_counts: Mutex::new(HashMap::new()),
         }
     }
 
@@ -47,13 +115,70 @@ impl AdapterRegistry {
         self.adapters.insert(domain, Arc::new(adapter));
     }
 
+    /// Register a failover adapter for its domain, e.g. a replica region
+    /// for an S3 endpoint
+    ///
+    /// The domain's policy becomes `Failover` unless one was already set.
+    /// The failover is only used while the primary is unhealthy, so it
+    /// handles the primary's actions.
+    pub fn register_failover<A: Adapter + 'static>(&mut self, adapter: A) {
+        let domain = adapter.domain().to_string();
+        info!(domain = %domain, "Registering failover adapter");
+
+        self.policies.entry(domain.clone()).or_insert(FailoverPolicy::Failover);
+        self.failovers.insert(domain, Arc::new(adapter));
+    }
+
+    /// Set what happens to a domain's requests while its primary is unhealthy
+    pub fn set_failover_policy(&mut self, domain: impl Into<String>, policy: FailoverPolicy) {
+        self.policies.insert(domain.into(), policy);
+    }
+
+    pub fn failover_policy(&self, domain: &str) -> Option<FailoverPolicy> {
+        self.policies.get(domain).copied()
+    }
+
+    /// Restrict the actions routed to a domain, its failover included
+    pub fn set_action_filter(&mut self, domain: impl Into<String>, filter: ActionFilter) {
+        self.filters.insert(domain.into(), filter);
+    }
+
+    pub fn action_filter(&self, domain: &str) -> Option<&ActionFilter> {
+        self.filters.get(domain)
+    }
+
+    /// Whether an action passes its domain's filter; actions of unfiltered
+    /// domains always do
+    pub fn is_action_enabled(&self, action: &str) -> bool {
+        let domain = self.action_map.get(action)
+            .map(String::as_str)
+            .or_else(|| action.split_once('.').map(|(domain, _)| domain));
+        domain
+            .and_then(|domain| self.filters.get(domain))
+            .map_or(true, |filter| filter.permits(action))
+    }
+
+    /// Get a domain's failover adapter
+    pub fn get_failover(&self, domain: &str) -> Option<Arc<dyn Adapter>> {
+        self.failovers.get(domain).cloned()
+    }
+
+    /// Number of requests routed to each domain's failover adapter
+    pub fn failover_counts(&self) -> HashMap<String, u64> {
+        self.failover_counts.lock().unwrap().clone()
+    }
+
     /// Get an adapter by domain
     pub fn get(&self, domain: &str) -> Option<Arc<dyn Adapter>> {
         self.adapters.get(domain).cloned()
     }
 
-    /// Get an adapter for an action
+    /// Get an adapter for an action, unless the action is disabled
     pub fn get_for_action(&self, action: &str) -> Option<Arc<dyn Adapter>> {
+        if !self.is_action_enabled(action) {
+            return None;
+        }
+
         // First try exact match
         if let Some(domain) = self.action_map.get(action) {
             return self.adapters.get(domain).cloned();
@@ -73,9 +198,12 @@ impl AdapterRegistry {
         self.adapters.keys().map(|s| s.as_str()).collect()
     }
 
-    /// List all registered actions
+    /// List all registered actions that are enabled
     pub fn actions(&self) -> Vec<&str> {
-        self.action_map.keys().map(|s| s.as_str()).collect()
+        self.action_map.keys()
+            .map(|s| s.as_str())
+            .filter(|action| self.is_action_enabled(action))
+            .collect()
     }
 
     /// Check if an action is supported
@@ -83,32 +211,110 @@ impl AdapterRegistry {
         self.get_for_action(action).is_some()
     }
 
-    /// Get adapter info for all registered adapters
+    /// Get adapter info for all registered adapters, listing the actions
+    /// each will actually be routed
     pub fn adapter_info(&self) -> Vec<AdapterInfo> {
         self.adapters.values().map(|a| AdapterInfo {
             domain: a.domain().to_string(),
             version: a.version().to_string(),
-            actions: a.supported_actions().iter().map(|s| s.to_string()).collect(),
+            actions: a.supported_actions().into_iter()
+                .filter(|action| self.is_action_enabled(action))
+                .map(str::to_string)
+                .collect(),
         }).collect()
     }
 
+    /// Resolve the adapter for an action and what it declares about it,
+    /// without executing anything
+    pub fn plan(&self, action: &str) -> Option<ActionPlan> {
+        let adapter = self.get_for_action(action)?;
+        Some(ActionPlan {
+            domain: adapter.domain().to_string(),
+            version: adapter.version().to_string(),
+            descriptor: adapter.action_descriptors().into_iter().find(|d| d.name == action),
+            can_rollback: adapter.can_rollback(action),
+        })
+    }
+
     /// Health check all adapters
+    ///
+    /// Results are cached for routing decisions until the next call.
+    /// Failover adapters are checked too but reported only through
+    /// [`failover_health`](Self::failover_health).
     pub async fn health_check_all(&self) -> HashMap<String, HealthStatus> {
-        let mut results = HashMap::new();
-        
-        for (domain, adapter) in &self.adapters {
-            match adapter.health_check().await {
-                Ok(status) => {
-                    results.insert(domain.clone(), status);
-                }
-                Err(e) => {
-                    results.insert(domain.clone(), HealthStatus::unhealthy(e.to_string()));
-                }
+        let results = check_all(&self.adapters).await;
+        let failover_results = check_all(&self.failovers).await;
+
+        *self.health.write().unwrap() = results.clone();
+        *self.failover_health.write().unwrap() = failover_results;
+        results
+    }
+
+    /// Failover adapter health from the last `health_check_all`
+    pub fn failover_health(&self) -> HashMap<String, HealthStatus> {
+        self.failover_health.read().unwrap().clone()
+    }
+
+    /// Choose the adapter to execute an action with, taking the cached
+    /// health of the domain's primary into account
+    ///
+    /// An adapter that has not been health checked yet counts as healthy.
+    pub fn route(&self, action: &str) -> AdapterResult<Arc<dyn Adapter>> {
+        if !self.is_action_enabled(action) {
+            return Err(AdapterError::UnsupportedAction(format!(
+                "Action is disabled: {}",
+                action
+            )));
+        }
+        let primary = self.get_for_action(action)
+            .ok_or_else(|| AdapterError::UnsupportedAction(format!(
+                "No adapter found for action: {}",
+                action
+            )))?;
+        let domain = primary.domain();
+
+        let Some(policy) = self.failover_policy(domain) else {
+            return Ok(primary);
+        };
+        let unhealthy = |cache: &SyncRwLock<HashMap<String, HealthStatus>>| {
+            cache.read().unwrap().get(domain).filter(|status| !status.healthy).cloned()
+        };
+        let Some(status) = unhealthy(&self.health) else {
+            return Ok(primary);
+        };
+        let reason = status.message.unwrap_or_else(|| "health check failed".to_string());
+
+        let failover = self.get_failover(domain)
+            .filter(|_| policy == FailoverPolicy::Failover && unhealthy(&self.failover_health).is_none());
+        match failover {
+            Some(failover) => {
+                warn!(domain = %domain, action = %action, reason = %reason, "Primary adapter unhealthy, failing over");
+                *self.failover_counts.lock().unwrap().entry(domain.to_string()).or_default() += 1;
+                Ok(failover)
             }
+            None => Err(AdapterError::Unavailable(format!(
+                "Adapter for {} is unhealthy: {}",
+                domain, reason
+            ))),
         }
+    }
+}
 
-        results
+async fn check_all(adapters: &HashMap<String, Arc<dyn Adapter>>) -> HashMap<String, HealthStatus> {
+    let mut results = HashMap::new();
+
+    for (domain, adapter) in adapters {
+        match adapter.health_check().await {
+            Ok(status) => {
+                results.insert(domain.clone(), status);
+            }
+            Err(e) => {
+                results.insert(domain.clone(), HealthStatus::unhealthy(e.to_string()));
+            }
+        }
     }
+
+    results
 }
 
 /// Information about a registered adapter
@@ -119,6 +325,25 @@ pub struct AdapterInfo {
     pub actions: Vec<String>,
 }
 
+/// The adapter an action resolves to and what it declares about the action
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ActionPlan {
+    pub domain: String,
+    pub version: String,
+    /// The adapter's descriptor for the action, if it publishes one
+    pub descriptor: Option<ActionDescriptor>,
+    /// Whether the adapter can roll the action back
+    pub can_rollback: bool,
+}
+
+impl ActionPlan {
+    /// Whether the action can be undone: the adapter must support rollback
+    /// and its descriptor, when present, must mark the action reversible
+    pub fn reversible(&self) -> bool {
+        self.can_rollback && self.descriptor.as_ref().map_or(true, |d| d.reversible)
+    }
+}
+
 /// Dispatcher for executing VĀKYA through adapters
 pub struct Dispatcher {
     registry: Arc<RwLock<AdapterRegistry>>,
@@ -135,20 +360,64 @@ impl Dispatcher {
         Self { registry }
     }
 
-    /// Dispatch a VĀKYA to the appropriate adapter
+    /// Dispatch a VĀKYA to the appropriate adapter, failing with `Timeout`
+    /// if it runs past `context.timeout_ms`
     pub async fn dispatch(&self, vakya: &Vakya, context: &ExecutionContext) -> AdapterResult<ExecutionResult> {
         let action = &vakya.v3_kriya.action;
         
         let registry = self.registry.read().await;
-        let adapter = registry.get_for_action(action)
-            .ok_or_else(|| AdapterError::UnsupportedAction(format!(
-                "No adapter found for action: {}",
-                action
-            )))?;
+        let adapter = registry.route(action)?;
 
         debug!(action = %action, domain = %adapter.domain(), "Dispatching to adapter");
 
-        adapter.execute(vakya, context).await
+        match context.timeout_ms {
+            Some(ms) => tokio::time::timeout(Duration::from_millis(ms), adapter.execute(vakya, context))
+                .await
+                .map_err(|_| AdapterError::Timeout)?,
+            None => adapter.execute(vakya, context).await,
+        }
+    }
+
+    /// Dispatch like `dispatch`, forwarding the adapter's progress events to
+    /// `progress` as they arrive
+    ///
+    /// Events sent after the receiver is dropped are discarded; the action
+    /// still runs to completion.
+    pub async fn dispatch_streaming(
+        &self,
+        vakya: &Vakya,
+        context: &ExecutionContext,
+        progress: &UnboundedSender<ExecutionEvent>,
+    ) -> AdapterResult<ExecutionResult> {
+        let action = &vakya.v3_kriya.action;
+
+        let registry = self.registry.read().await;
+        let adapter = registry.route(action)?;
+
+        debug!(action = %action, domain = %adapter.domain(), "Dispatching to adapter with progress");
+
+        let run = async {
+            let mut events = adapter.execute_streaming(vakya, context);
+            while let Some(event) = events.next().await {
+                match event? {
+                    ExecutionEvent::Completed(result) => return Ok(result),
+                    event => {
+                        let _ = progress.send(event);
+                    }
+                }
+            }
+            Err(AdapterError::Internal(format!(
+                "{} adapter ended its event stream without a result",
+                adapter.domain()
+            )))
+        };
+
+        match context.timeout_ms {
+            Some(ms) => tokio::time::timeout(Duration::from_millis(ms), run)
+                .await
+                .map_err(|_| AdapterError::Timeout)?,
+            None => run.await,
+        }
     }
 
     /// Rollback an effect
@@ -176,6 +445,12 @@ impl Dispatcher {
         registry.supports_action(action)
     }
 
+    /// Resolve the adapter for an action without executing it
+    pub async fn plan(&self, action: &str) -> Option<ActionPlan> {
+        let registry = self.registry.read().await;
+        registry.plan(action)
+    }
+
     /// Get adapter info
     pub async fn adapter_info(&self) -> Vec<AdapterInfo> {
         let registry = self.registry.read().await;
@@ -187,6 +462,12 @@ impl Dispatcher {
         let registry = self.registry.read().await;
         registry.health_check_all().await
     }
+
+    /// Number of requests routed to each domain's failover adapter
+    pub async fn failover_counts(&self) -> HashMap<String, u64> {
+        let registry = self.registry.read().await;
+        registry.failover_counts()
+    }
 }
 
 /// Builder for creating a pre-configured registry
@@ -231,12 +512,62 @@ impl RegistryBuilder {
         self
     }
 
+    /// Add a Redis adapter
+    pub fn with_redis_adapter(mut self, adapter: crate::redis::RedisAdapter) -> Self {
+        self.registry.register(adapter);
+        self
+    }
+
+    /// Add a queue adapter
+    pub fn with_queue_adapter(mut self, adapter: crate::queue::QueueAdapter) -> Self {
+        self.registry.register(adapter);
+        self
+    }
+
+    /// Add a git adapter
+    pub fn with_git_adapter(mut self, adapter: crate::git::GitAdapter) -> Self {
+        self.registry.register(adapter);
+        self
+    }
+
     /// Add a custom adapter
     pub fn with_adapter<A: Adapter + 'static>(mut self, adapter: A) -> Self {
         self.registry.register(adapter);
         self
     }
 
+    /// Add a failover adapter for an already added domain
+    pub fn with_failover<A: Adapter + 'static>(mut self, adapter: A) -> Self {
+        self.registry.register_failover(adapter);
+        self
+    }
+
+    /// Set a domain's failover policy
+    pub fn with_failover_policy(mut self, domain: impl Into<String>, policy: FailoverPolicy) -> Self {
+        self.registry.set_failover_policy(domain, policy);
+        self
+    }
+
+    /// Route only these actions of a domain
+    pub fn with_allowed_actions<I, S>(mut self, domain: impl Into<String>, actions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.registry.set_action_filter(domain, ActionFilter::allow(actions));
+        self
+    }
+
+    /// Refuse these actions of a domain
+    pub fn with_denied_actions<I, S>(mut self, domain: impl Into<String>, actions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.registry.set_action_filter(domain, ActionFilter::deny(actions));
+        self
+    }
+
     /// Build the registry
     pub fn build(self) -> AdapterRegistry {
         self.registry
@@ -308,4 +639,121 @@ mod tests {
         assert!(dispatcher.supports_action("file.read").await);
         assert!(!dispatcher.supports_action("unknown.action").await);
     }
+
+    #[test]
+    fn test_action_plan_reversibility() {
+        let registry = default_registry();
+
+        let delete = registry.plan("file.delete").unwrap();
+        assert_eq!(delete.domain, "file");
+        assert_eq!(delete.descriptor.as_ref().unwrap().effect_bucket, aapi_core::types::EffectBucket::Delete);
+        assert!(delete.reversible());
+
+        let read = registry.plan("file.read").unwrap();
+        assert!(!read.reversible());
+
+        let post = registry.plan("http.post").unwrap();
+        assert!(!post.can_rollback);
+        assert!(!post.reversible());
+
+        assert!(registry.plan("unknown.action").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_disabled_actions_are_unsupported() {
+        let dispatcher = RegistryBuilder::new()
+            .with_file_adapter()
+            .with_http_adapter()
+            .with_denied_actions("file", ["file.delete", "file.truncate"])
+            .with_allowed_actions("http", ["http.get"])
+            .build_dispatcher();
+
+        assert!(dispatcher.supports_action("file.write").await);
+        assert!(!dispatcher.supports_action("file.delete").await);
+        assert!(dispatcher.supports_action("http.get").await);
+        assert!(!dispatcher.supports_action("http.post").await);
+        assert!(dispatcher.plan("file.delete").await.is_none());
+
+        let info = dispatcher.adapter_info().await;
+        let actions = |domain: &str| info.iter().find(|a| a.domain == domain).unwrap().actions.clone();
+        assert!(actions("file").contains(&"file.read".to_string()));
+        assert!(!actions("file").contains(&"file.delete".to_string()));
+        assert_eq!(actions("http"), ["http.get"]);
+
+        let registry = dispatcher.registry.read().await;
+        let err = registry.route("file.delete").err().expect("disabled action routed");
+        assert!(matches!(err, AdapterError::UnsupportedAction(ref msg) if msg.contains("disabled")), "{:?}", err);
+    }
+
+    /// S3 endpoint stub whose version names its region
+    struct Region {
+        name: &'static str,
+        healthy: bool,
+    }
+
+    #[async_trait]
+    impl Adapter for Region {
+        fn domain(&self) -> &str {
+            "s3"
+        }
+
+        fn version(&self) -> &str {
+            self.name
+        }
+
+        fn supported_actions(&self) -> Vec<&str> {
+            vec!["s3.put"]
+        }
+
+        async fn execute(&self, _vakya: &Vakya, _context: &ExecutionContext) -> AdapterResult<ExecutionResult> {
+            Ok(ExecutionResult::success(serde_json::json!({}), vec![], 0))
+        }
+
+        fn can_rollback(&self, _action: &str) -> bool {
+            false
+        }
+
+        async fn rollback(&self, _effect: &CapturedEffect) -> AdapterResult<()> {
+            Ok(())
+        }
+
+        async fn health_check(&self) -> AdapterResult<HealthStatus> {
+            if self.healthy {
+                Ok(HealthStatus::healthy())
+            } else {
+                Err(AdapterError::Unavailable(format!("{} is down", self.name)))
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failover_routes_by_cached_health() {
+        let mut registry = RegistryBuilder::new()
+            .with_adapter(Region { name: "us-east-1", healthy: false })
+            .with_failover(Region { name: "us-west-2", healthy: true })
+            .build();
+        assert_eq!(registry.failover_policy("s3"), Some(FailoverPolicy::Failover));
+        let region = |registry: &AdapterRegistry| registry.route("s3.put").unwrap().version().to_string();
+
+        // Not health checked yet: the primary is assumed healthy
+        assert_eq!(region(&registry), "us-east-1");
+
+        let health = registry.health_check_all().await;
+        assert!(!health["s3"].healthy);
+        assert!(registry.failover_health()["s3"].healthy);
+        assert_eq!(region(&registry), "us-west-2");
+        assert_eq!(region(&registry), "us-west-2");
+        assert_eq!(registry.failover_counts()["s3"], 2);
+
+        // Fail-fast rejects without trying either endpoint
+        registry.set_failover_policy("s3", FailoverPolicy::FailFast);
+        assert!(matches!(registry.route("s3.put"), Err(AdapterError::Unavailable(_))));
+
+        // Domains without a policy route to the primary regardless
+        let registry = RegistryBuilder::new()
+            .with_adapter(Region { name: "us-east-1", healthy: false })
+            .build();
+        registry.health_check_all().await;
+        assert_eq!(region(&registry), "us-east-1");
+    }
 }
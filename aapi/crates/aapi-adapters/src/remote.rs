@@ -328,6 +328,7 @@ mod tests {
                 ttl: Some(aapi_core::vakya::TtlConstraint {
                     expires_at: Timestamp(chrono::Utc::now() + chrono::Duration::hours(1)),
                     max_duration_ms: None,
+                    not_before: None,
                 }),
                 budgets: vec![],
                 approval_lane: ApprovalLane::None,
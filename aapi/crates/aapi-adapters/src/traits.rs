@@ -1,6 +1,7 @@
 //! Adapter traits and types
 
 use async_trait::async_trait;
+use futures::stream::BoxStream;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -31,6 +32,22 @@ pub trait Adapter: Send + Sync {
     /// Execute an action and return the result with captured effects
     async fn execute(&self, vakya: &Vakya, context: &ExecutionContext) -> AdapterResult<ExecutionResult>;
 
+    /// Execute an action, reporting progress while it runs
+    ///
+    /// The stream ends with `ExecutionEvent::Completed` or an error. The
+    /// default runs `execute` and yields its result as the only event;
+    /// adapters with incremental output override this.
+    fn execute_streaming<'a>(&'a self, vakya: &'a Vakya, context: &'a ExecutionContext) -> ExecutionStream<'a> {
+        Box::pin(futures::stream::once(async move {
+            self.execute(vakya, context).await.map(ExecutionEvent::Completed)
+        }))
+    }
+
+    /// Describe the supported actions; empty if the adapter publishes none
+    fn action_descriptors(&self) -> Vec<ActionDescriptor> {
+        vec![]
+    }
+
     /// Check if an action can be rolled back
     fn can_rollback(&self, action: &str) -> bool;
 
@@ -41,6 +58,30 @@ pub trait Adapter: Send + Sync {
     async fn health_check(&self) -> AdapterResult<HealthStatus>;
 }
 
+/// Events an adapter reports from `Adapter::execute_streaming`
+pub type ExecutionStream<'a> = BoxStream<'a, AdapterResult<ExecutionEvent>>;
+
+/// Something that happened while an action ran
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExecutionEvent {
+    /// Incremental output, such as a log line or a chunk of a response body
+    Progress {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        message: Option<String>,
+        #[serde(default)]
+        data: serde_json::Value,
+    },
+    /// The action finished; nothing follows it
+    Completed(ExecutionResult),
+}
+
+impl ExecutionEvent {
+    pub fn progress(message: impl Into<String>) -> Self {
+        Self::Progress { message: Some(message.into()), data: serde_json::Value::Null }
+    }
+}
+
 /// Execution context passed to adapters
 #[derive(Debug, Clone)]
 pub struct ExecutionContext {
@@ -122,6 +163,26 @@ pub struct ExecutionResult {
     pub duration_ms: u64,
     /// Additional metadata
     pub metadata: HashMap<String, serde_json::Value>,
+    /// Items that failed while the rest of the action succeeded
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub item_errors: Vec<ItemError>,
+}
+
+/// One item of a multi-item action that could not be processed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemError {
+    /// The item as the action names it (path, key, ...)
+    pub item: String,
+    pub error: String,
+}
+
+impl ItemError {
+    pub fn new(item: impl Into<String>, error: impl Into<String>) -> Self {
+        Self {
+            item: item.into(),
+            error: error.into(),
+        }
+    }
 }
 
 impl ExecutionResult {
@@ -133,6 +194,23 @@ impl ExecutionResult {
             effects,
             duration_ms,
             metadata: HashMap::new(),
+            item_errors: vec![],
+        }
+    }
+
+    /// Success for some items only: `data` covers the items that were
+    /// processed and `item_errors` the ones that were not
+    ///
+    /// With no item errors this is a plain success.
+    pub fn partial(
+        data: serde_json::Value,
+        item_errors: Vec<ItemError>,
+        effects: Vec<CapturedEffect>,
+        duration_ms: u64,
+    ) -> Self {
+        Self {
+            item_errors,
+            ..Self::success(data, effects, duration_ms)
         }
     }
 
@@ -144,9 +222,15 @@ impl ExecutionResult {
             effects: vec![],
             duration_ms,
             metadata: HashMap::new(),
+            item_errors: vec![],
         }
     }
 
+    /// Succeeded, but not for every item
+    pub fn is_partial(&self) -> bool {
+        self.success && !self.item_errors.is_empty()
+    }
+
     pub fn with_metadata(mut self, key: impl Into<String>, value: serde_json::Value) -> Self {
         self.metadata.insert(key.into(), value);
         self
@@ -233,4 +317,9 @@ impl ActionDescriptor {
         self.reversible = true;
         self
     }
+
+    pub fn with_input_schema(mut self, schema: serde_json::Value) -> Self {
+        self.input_schema = Some(schema);
+        self
+    }
 }
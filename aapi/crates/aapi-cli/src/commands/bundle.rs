@@ -0,0 +1,82 @@
+//! Evidence bundle commands
+
+use std::io::IsTerminal;
+
+use aapi_sdk::{verify_bundle, AapiClient, ClientConfig, ExportBundle};
+
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+pub async fn export(
+    gateway: &str,
+    vakya_ids: Vec<String>,
+    output: String,
+    format: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let config = ClientConfig::new(gateway);
+    let client = AapiClient::new(config)?;
+
+    let bundle = client.export_bundle(&vakya_ids).await?;
+    std::fs::write(&output, serde_json::to_string_pretty(&bundle)?)
+        .map_err(|e| format!("Failed to write {}: {}", output, e))?;
+
+    match format {
+        "json" => {
+            println!("{}", serde_json::to_string_pretty(&serde_json::json!({
+                "path": output,
+                "vakyas": bundle.vakyas.len(),
+                "effects": bundle.effects.len(),
+                "receipts": bundle.receipts.len(),
+            }))?);
+        }
+        _ => {
+            println!("Bundle written to {}", output);
+            println!("  VĀKYAs:   {}", bundle.vakyas.len());
+            println!("  Effects:  {}", bundle.effects.len());
+            println!("  Receipts: {}", bundle.receipts.len());
+        }
+    }
+
+    Ok(())
+}
+
+pub fn verify(path: String, format: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let bundle: ExportBundle = serde_json::from_str(&contents)
+        .map_err(|e| format!("Invalid export bundle {}: {}", path, e))?;
+
+    let result = verify_bundle(&bundle);
+    match format {
+        "json" => {
+            println!("{}", serde_json::to_string_pretty(&serde_json::json!({
+                "gateway_id": result.gateway_id,
+                "valid": result.is_valid(),
+                "steps": result.steps,
+            }))?);
+        }
+        _ => {
+            let color = std::io::stdout().is_terminal();
+            let paint = |passed: bool, text: &str| match (color, passed) {
+                (false, _) => text.to_string(),
+                (true, true) => format!("{}{}{}", GREEN, text, RESET),
+                (true, false) => format!("{}{}{}", RED, text, RESET),
+            };
+
+            println!("Bundle Verification ({}):", result.gateway_id);
+            for step in &result.steps {
+                let mark = if step.passed { "✓" } else { "✗" };
+                println!("  {} {} — {}", paint(step.passed, mark), step.check, step.detail);
+            }
+            let summary = if result.is_valid() { "VERIFIED" } else { "FAILED" };
+            println!("  Result: {}", paint(result.is_valid(), summary));
+        }
+    }
+
+    if !result.is_valid() {
+        return Err(format!("Bundle {} failed verification", path).into());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+//! Database maintenance commands
+
+use aapi_indexdb::{IndexDbStore, SqliteIndexDb};
+
+pub async fn maintenance(database: String, format: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let store = SqliteIndexDb::new(&database).await?;
+    let report = store.maintenance().await?;
+
+    match format {
+        "json" => {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+        _ => {
+            let status_icon = if report.is_healthy() { "✓" } else { "✗" };
+            println!("{} Database: {}", status_icon, database);
+            println!("  Size:     {} → {} bytes", report.size_before, report.size_after);
+            println!("  Duration: {}ms", report.duration_ms);
+            for error in &report.integrity_errors {
+                println!("  Integrity: {}", error);
+            }
+            for mismatch in &report.checkpoint_mismatches {
+                println!("  Checkpoint: {}", mismatch);
+            }
+        }
+    }
+
+    if !report.is_healthy() {
+        return Err("database maintenance found problems".into());
+    }
+    Ok(())
+}
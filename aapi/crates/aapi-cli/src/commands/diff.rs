@@ -0,0 +1,59 @@
+//! Diff command - compare two VĀKYA field by field
+
+use aapi_core::Vakya;
+use aapi_sdk::{AapiClient, ClientConfig};
+
+pub async fn run(
+    gateway: &str,
+    first: String,
+    second: String,
+    format: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let config = ClientConfig::new(gateway);
+    let client = AapiClient::new(config)?;
+
+    let before = fetch(&client, &first).await?;
+    let after = fetch(&client, &second).await?;
+    let changes = before.1.diff(&after.1);
+
+    match format {
+        "json" => {
+            println!("{}", serde_json::to_string_pretty(&serde_json::json!({
+                "first": { "vakya_id": first, "vakya_hash": before.0 },
+                "second": { "vakya_id": second, "vakya_hash": after.0 },
+                "changes": changes,
+            }))?);
+        }
+        _ => {
+            println!("VĀKYA Diff:");
+            println!("  {}  {}", first, before.0);
+            println!("  {}  {}", second, after.0);
+
+            if changes.is_empty() {
+                println!("\nNo differences");
+                return Ok(());
+            }
+
+            println!("\nChanged fields ({}):", changes.len());
+            for change in &changes {
+                println!("  {}", change.field);
+                for op in &change.patch {
+                    match &op.value {
+                        Some(value) => println!("    {:<7} {} = {}", op.op, op.path, value),
+                        None => println!("    {:<7} {}", op.op, op.path),
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Fetch a stored VĀKYA and its recorded hash
+async fn fetch(client: &AapiClient, vakya_id: &str) -> Result<(String, Vakya), Box<dyn std::error::Error>> {
+    let record = client.get_vakya(vakya_id).await?;
+    let vakya = serde_json::from_value(record.vakya_json)
+        .map_err(|e| format!("Stored VĀKYA {} is not readable: {}", vakya_id, e))?;
+    Ok((record.vakya_hash, vakya))
+}
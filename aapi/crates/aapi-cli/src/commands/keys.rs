@@ -1,14 +1,21 @@
 //! Key management commands
 
-use aapi_crypto::{KeyStore, KeyPurpose, KeyPair};
+use std::io::Read;
+use std::path::Path;
 
-pub fn generate(purpose: String, format: &str) -> Result<(), Box<dyn std::error::Error>> {
-    let key_purpose = match purpose.as_str() {
+use aapi_crypto::{KeyFilter, KeyStore, KeyPurpose, KeyPair, PublicKeyInfo, SecretKeyEncoding};
+
+fn parse_purpose(purpose: &str) -> KeyPurpose {
+    match purpose {
         "signing" | "vakya" => KeyPurpose::VakyaSigning,
         "capability" | "cap" => KeyPurpose::CapabilitySigning,
         "receipt" => KeyPurpose::ReceiptSigning,
         _ => KeyPurpose::General,
-    };
+    }
+}
+
+pub fn generate(purpose: String, format: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let key_purpose = parse_purpose(&purpose);
 
     let key_pair = KeyPair::generate(key_purpose);
     let public_info = key_pair.to_public_info();
@@ -32,16 +39,142 @@ pub fn generate(purpose: String, format: &str) -> Result<(), Box<dyn std::error:
     Ok(())
 }
 
-pub fn list(format: &str) -> Result<(), Box<dyn std::error::Error>> {
-    // Note: In a real implementation, this would read from a key store file
-    println!("Key listing requires a configured key store.");
-    println!("Use 'aapi keys generate' to create new keys.");
+pub fn list(
+    purpose: Option<String>,
+    principal: Option<String>,
+    key_dir: String,
+    format: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let store = load_key_dir(Path::new(&key_dir))?;
+    let filter = KeyFilter {
+        purpose: purpose.as_deref().map(parse_purpose),
+        principal,
+    };
+    let keys = store.list_public_keys_filtered(&filter)?;
+
+    match format {
+        "json" => {
+            println!("{}", serde_json::to_string_pretty(&keys)?);
+        }
+        _ => {
+            if keys.is_empty() {
+                println!("No keys found in {}", key_dir);
+                return Ok(());
+            }
+            println!("{:<40} {:<20} {:<20} CREATED", "KEY ID", "PURPOSE", "PRINCIPAL");
+            for key in &keys {
+                println!(
+                    "{:<40} {:<20} {:<20} {}",
+                    key.key_id.0,
+                    format!("{:?}", key.purpose),
+                    key.principal.as_deref().unwrap_or("-"),
+                    key.created_at.to_rfc3339(),
+                );
+            }
+        }
+    }
+
     Ok(())
 }
 
+/// Load the public half of every key file in `key_dir`; a missing
+/// directory holds no keys
+fn load_key_dir(key_dir: &Path) -> Result<KeyStore, Box<dyn std::error::Error>> {
+    let store = KeyStore::new();
+    let entries = match std::fs::read_dir(key_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(store),
+        Err(e) => return Err(format!("Failed to read {}: {}", key_dir.display(), e).into()),
+    };
+
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            continue;
+        }
+        let info: PublicKeyInfo = serde_json::from_slice(&std::fs::read(&path)?)
+            .map_err(|e| format!("Invalid key file {}: {}", path.display(), e))?;
+        store.store_public_key(info)?;
+    }
+
+    Ok(store)
+}
+
 pub fn export(key_id: String, format: &str) -> Result<(), Box<dyn std::error::Error>> {
     // Note: In a real implementation, this would read from a key store file
     println!("Key export requires a configured key store.");
     println!("Key ID: {}", key_id);
     Ok(())
 }
+
+pub fn import(
+    purpose: String,
+    from: String,
+    file: Option<String>,
+    key_dir: String,
+    format: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let encoding: SecretKeyEncoding = from.parse()
+        .map_err(|e: aapi_crypto::CryptoError| e.to_string())?;
+
+    let input = match file {
+        Some(path) => std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path, e))?,
+        None => {
+            let mut input = String::new();
+            std::io::stdin().read_to_string(&mut input)?;
+            input
+        }
+    };
+
+    let key_pair = KeyPair::import_secret(&input, encoding, parse_purpose(&purpose))
+        .map_err(|e| format!("Could not import key: {}", e))?;
+
+    let public_info = key_pair.to_public_info();
+    let path = write_key_file(Path::new(&key_dir), &key_pair)?;
+
+    match format {
+        "json" => {
+            println!("{}", serde_json::to_string_pretty(&public_info)?);
+        }
+        _ => {
+            println!("Imported key pair:");
+            println!("  Key ID:     {}", public_info.key_id.0);
+            println!("  Algorithm:  {}", public_info.algorithm);
+            println!("  Purpose:    {:?}", public_info.purpose);
+            println!("  Public Key: {}", public_info.public_key);
+            println!("  Stored at:  {}", path.display());
+        }
+    }
+
+    Ok(())
+}
+
+/// Write the key pair to `<key_dir>/<key_id>.json`, readable only by the owner
+fn write_key_file(key_dir: &Path, key_pair: &KeyPair) -> Result<std::path::PathBuf, Box<dyn std::error::Error>> {
+    std::fs::create_dir_all(key_dir)?;
+
+    let file_name = key_pair.key_id.0.replace(':', "_");
+    let path = key_dir.join(format!("{}.json", file_name));
+    let record = serde_json::json!({
+        "key_id": key_pair.key_id,
+        "algorithm": key_pair.algorithm.name(),
+        "purpose": key_pair.purpose,
+        "principal": key_pair.principal,
+        "public_key": key_pair.public_key_hex(),
+        "secret_key": hex::encode(key_pair.signing_key().to_bytes()),
+        "created_at": key_pair.created_at,
+    });
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&path)?;
+    std::io::Write::write_all(&mut file, serde_json::to_string_pretty(&record)?.as_bytes())?;
+
+    Ok(path)
+}
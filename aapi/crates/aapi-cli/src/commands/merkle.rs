@@ -1,6 +1,6 @@
 //! Merkle tree commands
 
-use aapi_sdk::{AapiClient, ClientConfig};
+use aapi_sdk::{verify_consistency, AapiClient, ClientConfig, SignedTreeHead};
 
 pub async fn root(gateway: &str, tree_type: String, format: &str) -> Result<(), Box<dyn std::error::Error>> {
     let config = ClientConfig::new(gateway);
@@ -50,3 +50,52 @@ pub async fn proof(gateway: &str, tree_type: String, index: i64, format: &str) -
 
     Ok(())
 }
+
+pub async fn consistency(
+    gateway: &str,
+    tree_type: String,
+    from: i64,
+    to: i64,
+    from_root: Option<String>,
+    format: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let config = ClientConfig::new(gateway);
+    let client = AapiClient::new(config)?;
+
+    let response = client.get_consistency_proof(&tree_type, from, to).await?;
+
+    // Pin the earlier checkpoint if the caller recorded it; otherwise take the gateway's word
+    let old_sth = SignedTreeHead::new(from as u64, from_root.unwrap_or_else(|| response.first_root.clone()));
+    let new_sth = SignedTreeHead::new(to as u64, response.second_root.clone());
+    let consistent = verify_consistency(&old_sth, &new_sth, &response);
+
+    match format {
+        "json" => {
+            println!("{}", serde_json::to_string_pretty(&serde_json::json!({
+                "tree_type": tree_type,
+                "first_size": from,
+                "second_size": to,
+                "first_root": old_sth.root_hash,
+                "second_root": new_sth.root_hash,
+                "proof_hashes": response.proof_hashes,
+                "consistent": consistent,
+            }))?);
+        }
+        _ => {
+            println!("Consistency Proof ({}):", tree_type);
+            println!("  From: {} ({})", from, old_sth.root_hash);
+            println!("  To:   {} ({})", to, new_sth.root_hash);
+            println!("  Proof Path ({} nodes):", response.proof_hashes.len());
+            for (i, hash) in response.proof_hashes.iter().enumerate() {
+                println!("    {}: {}", i, hash);
+            }
+            println!("  Result: {}", if consistent { "PASS" } else { "FAIL" });
+        }
+    }
+
+    if !consistent {
+        return Err(format!("Tree {} is not consistent between sizes {} and {}", tree_type, from, to).into());
+    }
+
+    Ok(())
+}
@@ -3,7 +3,11 @@
 pub mod serve;
 pub mod submit;
 pub mod get;
+pub mod diff;
 pub mod query;
 pub mod merkle;
 pub mod keys;
+pub mod verify;
+pub mod bundle;
 pub mod health;
+pub mod db;
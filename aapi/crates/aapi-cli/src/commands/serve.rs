@@ -1,17 +1,64 @@
 //! Serve command - start the gateway server
 
-use aapi_gateway::{GatewayServerBuilder, GatewayConfig};
+use aapi_gateway::{GatewayServerBuilder, GatewayConfig, LogRedaction, TlsConfig};
 use tracing::info;
 
-pub async fn run(host: String, port: u16, database: String) -> Result<(), Box<dyn std::error::Error>> {
-    info!(host = %host, port = %port, database = %database, "Starting AAPI Gateway");
+/// Where file operations are sandboxed and which hosts HTTP may reach
+pub struct Sandbox {
+    pub base_dir: String,
+    /// Octal permission bits, e.g. "700"
+    pub mode: Option<String>,
+    pub http_allowed_hosts: Vec<String>,
+    pub http_denied_hosts: Vec<String>,
+    pub http_block_private: bool,
+}
+
+/// Stricter checks on executions and what the logs may reveal
+pub struct Hardening {
+    pub strict_effect_matching: bool,
+    pub log_redaction: LogRedaction,
+}
 
-    let server = GatewayServerBuilder::new()
+/// Gateway configuration for the serve flags
+pub fn builder(
+    host: String,
+    port: u16,
+    database: String,
+    policy_dir: Option<String>,
+    tls: Option<TlsConfig>,
+    sandbox: Sandbox,
+    hardening: Hardening,
+) -> Result<GatewayServerBuilder, Box<dyn std::error::Error>> {
+    let mut builder = GatewayServerBuilder::new()
         .host(&host)
         .port(port)
         .database_url(&database)
-        .build()
-        .await?;
+        .file_base_dir(sandbox.base_dir)
+        .http_allowed_hosts(sandbox.http_allowed_hosts)
+        .http_denied_hosts(sandbox.http_denied_hosts)
+        .http_block_private(sandbox.http_block_private)
+        .strict_effect_matching(hardening.strict_effect_matching)
+        .log_redaction(hardening.log_redaction);
+    if let Some(mode) = sandbox.mode {
+        let mode = u32::from_str_radix(mode.trim_start_matches("0o"), 8)
+            .map_err(|_| format!("Invalid --file-base-dir-mode '{}': expected octal such as 700", mode))?;
+        builder = builder.file_base_dir_mode(mode);
+    }
+    if let Some(dir) = policy_dir {
+        builder = builder.policy_dir(dir);
+    }
+    if let Some(tls) = tls {
+        builder = builder.tls(tls);
+    }
+
+    Ok(builder)
+}
+
+pub async fn run(builder: GatewayServerBuilder) -> Result<(), Box<dyn std::error::Error>> {
+    let server = builder.build().await?;
+    let state = server.state();
+    let config = &state.config;
+    info!(host = %config.host, port = %config.port, database = %config.database_url, "Starting AAPI Gateway");
 
     // Handle Ctrl+C for graceful shutdown
     let shutdown = async {
@@ -0,0 +1,101 @@
+//! Receipt verification command
+
+use std::io::IsTerminal;
+
+use aapi_sdk::{AapiClient, ClientConfig, ReceiptBundle};
+
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+pub async fn run(
+    gateway: &str,
+    vakya_id: Option<String>,
+    offline: Option<String>,
+    save: Option<String>,
+    format: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let bundle = match offline {
+        Some(path) => {
+            let contents = std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+            serde_json::from_str::<ReceiptBundle>(&contents)
+                .map_err(|e| format!("Invalid receipt bundle {}: {}", path, e))?
+        }
+        None => {
+            let vakya_id = vakya_id.as_deref().ok_or("A VĀKYA ID is required unless --offline is given")?;
+            fetch_bundle(gateway, vakya_id).await?
+        }
+    };
+
+    if let Some(path) = save {
+        std::fs::write(&path, serde_json::to_string_pretty(&bundle)?)?;
+    }
+
+    let result = bundle.verify();
+    if let Some(expected) = vakya_id.filter(|id| *id != result.vakya_id) {
+        return Err(format!("Bundle is for VĀKYA {}, not {}", result.vakya_id, expected).into());
+    }
+
+    match format {
+        "json" => {
+            println!("{}", serde_json::to_string_pretty(&serde_json::json!({
+                "vakya_id": result.vakya_id,
+                "valid": result.is_valid(),
+                "steps": result.steps,
+            }))?);
+        }
+        _ => {
+            let color = std::io::stdout().is_terminal();
+            let paint = |passed: bool, text: &str| match (color, passed) {
+                (false, _) => text.to_string(),
+                (true, true) => format!("{}{}{}", GREEN, text, RESET),
+                (true, false) => format!("{}{}{}", RED, text, RESET),
+            };
+
+            println!("Receipt Verification ({}):", result.vakya_id);
+            for step in &result.steps {
+                let mark = if step.passed { "✓" } else { "✗" };
+                println!("  {} {:<12} {}", paint(step.passed, mark), step.check, step.detail);
+            }
+            let summary = if result.is_valid() { "VERIFIED" } else { "FAILED" };
+            println!("  Result: {}", paint(result.is_valid(), summary));
+        }
+    }
+
+    if !result.is_valid() {
+        return Err(format!("Receipt for {} failed verification", result.vakya_id).into());
+    }
+
+    Ok(())
+}
+
+/// Fetch the receipt, its inclusion proof, the current root and the signing key
+async fn fetch_bundle(gateway: &str, vakya_id: &str) -> Result<ReceiptBundle, Box<dyn std::error::Error>> {
+    let config = ClientConfig::new(gateway);
+    let client = AapiClient::new(config)?;
+
+    let receipt = client.get_signed_receipt(vakya_id).await?;
+    let key_id = receipt.get("key_id").and_then(|v| v.as_str()).ok_or("Receipt is not signed")?;
+    let leaf_index = receipt.get("leaf_index").and_then(|v| v.as_i64()).ok_or("Receipt has no Merkle leaf")?;
+    let public_key = client.get_public_key(key_id).await?;
+
+    let mut proof = client.get_inclusion_proof("receipt", leaf_index).await?;
+    let mut root_hash = current_root(&client).await?;
+    if root_hash != proof.root_hash {
+        // Another receipt was logged between the two requests
+        proof = client.get_inclusion_proof("receipt", leaf_index).await?;
+        root_hash = current_root(&client).await?;
+    }
+
+    Ok(ReceiptBundle {
+        receipt,
+        proof,
+        root_hash,
+        public_key,
+    })
+}
+
+async fn current_root(client: &AapiClient) -> Result<String, Box<dyn std::error::Error>> {
+    Ok(client.get_merkle_root("receipt").await?.root_hash.ok_or("Receipt tree is empty")?)
+}
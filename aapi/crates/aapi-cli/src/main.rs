@@ -2,6 +2,7 @@
 
 use clap::{Parser, Subcommand};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use aapi_gateway::{LogRedaction, RedactionMode, TlsConfig};
 
 mod commands;
 
@@ -40,6 +41,58 @@ enum Commands {
         /// Database URL
         #[arg(short, long, default_value = "sqlite:aapi.db")]
         database: String,
+
+        /// Directory of JSON policy files to load and watch for changes
+        #[arg(long)]
+        policy_dir: Option<String>,
+
+        /// PEM certificate chain; serves HTTPS when set
+        #[arg(long, requires = "tls_key")]
+        tls_cert: Option<String>,
+
+        /// PEM private key for --tls-cert
+        #[arg(long, requires = "tls_cert")]
+        tls_key: Option<String>,
+
+        /// PEM bundle of CAs trusted to issue client certificates (mTLS)
+        #[arg(long, requires = "tls_cert")]
+        client_ca: Option<String>,
+
+        /// Reject connections without a valid client certificate
+        #[arg(long, requires = "client_ca")]
+        require_client_cert: bool,
+
+        /// Sandbox directory for file operations and HTTP downloads
+        #[arg(long, default_value = aapi_gateway::DEFAULT_FILE_BASE_DIR)]
+        file_base_dir: String,
+
+        /// Octal permissions applied to the sandbox directory (e.g. 700)
+        #[arg(long)]
+        file_base_dir_mode: Option<String>,
+
+        /// Host the HTTP adapter may reach (repeatable); any host when omitted
+        #[arg(long = "http-allowed-host")]
+        http_allowed_hosts: Vec<String>,
+
+        /// Host the HTTP adapter must never reach (repeatable)
+        #[arg(long = "http-denied-host")]
+        http_denied_hosts: Vec<String>,
+
+        /// Refuse HTTP requests to loopback, private and link-local addresses
+        #[arg(long)]
+        http_block_private: bool,
+
+        /// Fail executions whose effects diverge from the declared expected effect
+        #[arg(long)]
+        strict_effect_matching: bool,
+
+        /// Regular expression for values to redact from the logs (repeatable)
+        #[arg(long = "log-redact")]
+        log_redact: Vec<String>,
+
+        /// Replace redacted values with a short hash instead of a mask
+        #[arg(long, requires = "log_redact")]
+        log_redact_hash: bool,
     },
 
     /// Submit a VĀKYA request
@@ -83,6 +136,15 @@ enum Commands {
         receipt: bool,
     },
 
+    /// Compare two VĀKYA field by field
+    Diff {
+        /// First VĀKYA ID
+        first: String,
+
+        /// Second VĀKYA ID
+        second: String,
+    },
+
     /// Query VĀKYA records
     Query {
         /// Filter by actor
@@ -114,6 +176,33 @@ enum Commands {
         command: KeyCommands,
     },
 
+    /// Verify a receipt's signature and its inclusion in the receipt log
+    Verify {
+        /// VĀKYA ID whose receipt to verify
+        #[arg(required_unless_present = "offline")]
+        vakya_id: Option<String>,
+
+        /// Verify a saved receipt bundle instead of querying the gateway
+        #[arg(long)]
+        offline: Option<String>,
+
+        /// Save the fetched receipt, proof, root and key as a bundle
+        #[arg(long, conflicts_with = "offline")]
+        save: Option<String>,
+    },
+
+    /// Evidence bundles for external audit
+    Bundle {
+        #[command(subcommand)]
+        command: BundleCommands,
+    },
+
+    /// Local database operations
+    Db {
+        #[command(subcommand)]
+        command: DbCommands,
+    },
+
     /// Health check
     Health,
 }
@@ -137,6 +226,55 @@ enum MerkleCommands {
         #[arg(short, long)]
         index: i64,
     },
+
+    /// Verify the log is append-only between two tree sizes
+    Consistency {
+        /// Tree type
+        #[arg(short, long = "tree", default_value = "vakya")]
+        tree_type: String,
+
+        /// Earlier tree size
+        #[arg(long)]
+        from: i64,
+
+        /// Later tree size
+        #[arg(long)]
+        to: i64,
+
+        /// Trusted root hash for the earlier size (defaults to the gateway's)
+        #[arg(long)]
+        from_root: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum BundleCommands {
+    /// Export VĀKYA records, effects and receipts with their proofs
+    Export {
+        /// VĀKYA IDs to include
+        #[arg(required = true)]
+        vakya_ids: Vec<String>,
+
+        /// File the bundle is written to
+        #[arg(short, long)]
+        output: String,
+    },
+
+    /// Verify a saved bundle offline
+    Verify {
+        /// Bundle file
+        path: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum DbCommands {
+    /// Check integrity, reconcile Merkle checkpoints and compact the database
+    Maintenance {
+        /// Database URL
+        #[arg(short, long, default_value = "sqlite:aapi.db")]
+        database: String,
+    },
 }
 
 #[derive(Subcommand)]
@@ -148,31 +286,112 @@ enum KeyCommands {
         purpose: String,
     },
 
-    /// List keys
-    List,
+    /// List keys, oldest first
+    List {
+        /// Only keys with this purpose (signing, capability, receipt, general)
+        #[arg(short, long)]
+        purpose: Option<String>,
+
+        /// Only keys bound to this principal
+        #[arg(long)]
+        principal: Option<String>,
+
+        /// Directory holding the key files
+        #[arg(long, default_value = ".aapi/keys", env = "AAPI_KEY_DIR")]
+        key_dir: String,
+    },
 
     /// Export public key
     Export {
         /// Key ID
         key_id: String,
     },
+
+    /// Import an existing Ed25519 secret key
+    Import {
+        /// Key purpose (signing, capability, receipt)
+        #[arg(short, long, default_value = "signing")]
+        purpose: String,
+
+        /// Encoding of the secret (pem, hex, base64)
+        #[arg(long)]
+        from: String,
+
+        /// File containing the secret; read from stdin when omitted
+        #[arg(long)]
+        file: Option<String>,
+
+        /// Directory the imported key is written to
+        #[arg(long, default_value = ".aapi/keys", env = "AAPI_KEY_DIR")]
+        key_dir: String,
+    },
+}
+
+/// `RUST_LOG`, or debug/info by verbosity
+fn env_filter(verbose: bool) -> tracing_subscriber::EnvFilter {
+    let level = if verbose { "debug" } else { "info" };
+    tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| level.into())
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
 
-    // Initialize tracing
-    let filter = if cli.verbose { "debug" } else { "info" };
-    tracing_subscriber::registry()
-        .with(tracing_subscriber::EnvFilter::try_from_default_env()
-            .unwrap_or_else(|_| filter.into()))
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+    // The gateway's logs are set up once its configuration is known
+    if !matches!(cli.command, Commands::Serve { .. }) {
+        tracing_subscriber::registry()
+            .with(env_filter(cli.verbose))
+            .with(tracing_subscriber::fmt::layer())
+            .init();
+    }
 
     match cli.command {
-        Commands::Serve { host, port, database } => {
-            commands::serve::run(host, port, database).await?;
+        Commands::Serve {
+            host,
+            port,
+            database,
+            policy_dir,
+            tls_cert,
+            tls_key,
+            client_ca,
+            require_client_cert,
+            file_base_dir,
+            file_base_dir_mode,
+            http_allowed_hosts,
+            http_denied_hosts,
+            http_block_private,
+            strict_effect_matching,
+            log_redact,
+            log_redact_hash,
+        } => {
+            let tls = tls_cert.zip(tls_key).map(|(cert, key)| {
+                let mut tls = TlsConfig::new(cert, key);
+                if let Some(ca) = client_ca {
+                    tls = tls.with_client_ca(ca).match_principal();
+                }
+                if require_client_cert {
+                    tls = tls.require_client_cert();
+                }
+                tls
+            });
+            let sandbox = commands::serve::Sandbox {
+                base_dir: file_base_dir,
+                mode: file_base_dir_mode,
+                http_allowed_hosts,
+                http_denied_hosts,
+                http_block_private,
+            };
+            let log_redaction = LogRedaction {
+                patterns: log_redact,
+                mode: if log_redact_hash { RedactionMode::Hash } else { RedactionMode::Mask },
+            };
+            let hardening = commands::serve::Hardening { strict_effect_matching, log_redaction };
+            let builder = commands::serve::builder(host, port, database, policy_dir, tls, sandbox, hardening)?;
+            tracing_subscriber::registry()
+                .with(env_filter(cli.verbose))
+                .with(builder.tracing_layer()?)
+                .init();
+            commands::serve::run(builder).await?;
         }
         Commands::Submit { actor, resource, action, body, capability, ttl } => {
             commands::submit::run(&cli.gateway, actor, resource, action, body, capability, ttl, &cli.format).await?;
@@ -180,6 +399,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         Commands::Get { vakya_id, effects, receipt } => {
             commands::get::run(&cli.gateway, vakya_id, effects, receipt, &cli.format).await?;
         }
+        Commands::Diff { first, second } => {
+            commands::diff::run(&cli.gateway, first, second, &cli.format).await?;
+        }
         Commands::Query { actor, action, resource, limit } => {
             commands::query::run(&cli.gateway, actor, action, resource, limit, &cli.format).await?;
         }
@@ -191,6 +413,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 MerkleCommands::Proof { tree_type, index } => {
                     commands::merkle::proof(&cli.gateway, tree_type, index, &cli.format).await?;
                 }
+                MerkleCommands::Consistency { tree_type, from, to, from_root } => {
+                    commands::merkle::consistency(&cli.gateway, tree_type, from, to, from_root, &cli.format).await?;
+                }
             }
         }
         Commands::Keys { command } => {
@@ -198,12 +423,35 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 KeyCommands::Generate { purpose } => {
                     commands::keys::generate(purpose, &cli.format)?;
                 }
-                KeyCommands::List => {
-                    commands::keys::list(&cli.format)?;
+                KeyCommands::List { purpose, principal, key_dir } => {
+                    commands::keys::list(purpose, principal, key_dir, &cli.format)?;
                 }
                 KeyCommands::Export { key_id } => {
                     commands::keys::export(key_id, &cli.format)?;
                 }
+                KeyCommands::Import { purpose, from, file, key_dir } => {
+                    commands::keys::import(purpose, from, file, key_dir, &cli.format)?;
+                }
+            }
+        }
+        Commands::Verify { vakya_id, offline, save } => {
+            commands::verify::run(&cli.gateway, vakya_id, offline, save, &cli.format).await?;
+        }
+        Commands::Bundle { command } => {
+            match command {
+                BundleCommands::Export { vakya_ids, output } => {
+                    commands::bundle::export(&cli.gateway, vakya_ids, output, &cli.format).await?;
+                }
+                BundleCommands::Verify { path } => {
+                    commands::bundle::verify(path, &cli.format)?;
+                }
+            }
+        }
+        Commands::Db { command } => {
+            match command {
+                DbCommands::Maintenance { database } => {
+                    commands::db::maintenance(database, &cli.format).await?;
+                }
             }
         }
         Commands::Health => {
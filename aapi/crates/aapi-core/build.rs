@@ -0,0 +1,13 @@
+//! Generate the protobuf VĀKYA types
+//!
+//! `protox` compiles the schema in-process, so building does not need
+//! `protoc` installed.
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("cargo:rerun-if-changed=proto/vakya.proto");
+
+    let descriptors = protox::compile(["proto/vakya.proto"], ["proto"])?;
+    prost_build::Config::new().compile_fds(descriptors)?;
+
+    Ok(())
+}
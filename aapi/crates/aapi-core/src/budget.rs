@@ -0,0 +1,383 @@
+//! Cost estimation and budget accounting
+//!
+//! A [`CostEstimator`] maps a VĀKYA to what it costs in each budget resource
+//! (`api_calls`, `bytes_written`, ...). A [`BudgetTracker`] charges those
+//! costs against the budgets the VĀKYA declares in `Adhikarana.budgets`,
+//! remembering usage per principal across requests.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{AapiError, AapiResult};
+use crate::types::{Budget, EffectBucket};
+use crate::vakya::Vakya;
+
+/// One per VĀKYA
+pub const RESOURCE_API_CALLS: &str = "api_calls";
+/// One per VĀKYA declaring an `External` effect
+pub const RESOURCE_EXTERNAL_CALLS: &str = "external_calls";
+/// Size of the body of a VĀKYA declaring a `Create` or `Update` effect
+pub const RESOURCE_BYTES_WRITTEN: &str = "bytes_written";
+/// Weighted cost of the declared effect bucket
+pub const RESOURCE_COST_UNITS: &str = "cost_units";
+
+/// Estimates what executing a VĀKYA costs, per budget resource
+///
+/// Resources missing from the estimate cost nothing.
+pub trait CostEstimator: Send + Sync {
+    fn estimate(&self, vakya: &Vakya) -> HashMap<String, u64>;
+}
+
+impl Vakya {
+    /// Estimated cost of executing this VĀKYA, per budget resource
+    pub fn estimate_cost(&self, estimator: &dyn CostEstimator) -> HashMap<String, u64> {
+        estimator.estimate(self)
+    }
+}
+
+/// Default estimator, driven by the VĀKYA's declared `expected_effect`
+///
+/// Charges one `api_calls`, the bucket's unit cost in `cost_units`, one
+/// `external_calls` for `External` effects and the body size in
+/// `bytes_written` for `Create` and `Update` effects.
+#[derive(Debug, Clone)]
+pub struct EffectCostEstimator {
+    unit_costs: HashMap<EffectBucket, u64>,
+}
+
+impl Default for EffectCostEstimator {
+    fn default() -> Self {
+        Self {
+            unit_costs: HashMap::from([
+                (EffectBucket::None, 0),
+                (EffectBucket::Read, 1),
+                (EffectBucket::Create, 5),
+                (EffectBucket::Update, 5),
+                (EffectBucket::Delete, 10),
+                (EffectBucket::External, 10),
+            ]),
+        }
+    }
+}
+
+impl EffectCostEstimator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the `cost_units` charged for a bucket
+    pub fn with_unit_cost(mut self, bucket: EffectBucket, cost: u64) -> Self {
+        self.unit_costs.insert(bucket, cost);
+        self
+    }
+}
+
+impl CostEstimator for EffectCostEstimator {
+    fn estimate(&self, vakya: &Vakya) -> HashMap<String, u64> {
+        let bucket = vakya.v3_kriya.expected_effect;
+        let mut costs = HashMap::from([
+            (RESOURCE_API_CALLS.to_string(), 1),
+            (RESOURCE_COST_UNITS.to_string(), self.unit_costs.get(&bucket).copied().unwrap_or(0)),
+        ]);
+        match bucket {
+            EffectBucket::External => {
+                costs.insert(RESOURCE_EXTERNAL_CALLS.to_string(), 1);
+            }
+            EffectBucket::Create | EffectBucket::Update => {
+                let bytes = serde_json::to_vec(&vakya.body).map_or(0, |body| body.len() as u64);
+                costs.insert(RESOURCE_BYTES_WRITTEN.to_string(), bytes);
+            }
+            _ => {}
+        }
+        costs
+    }
+}
+
+/// What charging a VĀKYA took from one of its budgets
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BudgetCharge {
+    pub budget_id: String,
+    pub resource: String,
+    pub cost: u64,
+    pub used: u64,
+    pub remaining: u64,
+}
+
+#[derive(Debug, Clone)]
+struct BudgetUsage {
+    used: u64,
+    window_start: DateTime<Utc>,
+}
+
+/// Usage of declared budgets, kept per principal and budget ID
+///
+/// The first time a budget is seen its usage starts at the declared `used`.
+/// Budgets with a `reset_period_secs` start over once the period has passed
+/// since their window opened.
+#[derive(Debug, Default)]
+pub struct BudgetTracker {
+    usage: Mutex<HashMap<(String, String), BudgetUsage>>,
+}
+
+impl BudgetTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Charge `costs` against `budgets` for `principal`
+    ///
+    /// Either every budget is charged or, if any would go over its limit,
+    /// none is and the first one over is reported as `BudgetExceeded`.
+    pub fn charge(
+        &self,
+        principal: &str,
+        budgets: &[Budget],
+        costs: &HashMap<String, u64>,
+    ) -> AapiResult<Vec<BudgetCharge>> {
+        self.charge_at(principal, budgets, costs, Utc::now())
+    }
+
+    fn charge_at(
+        &self,
+        principal: &str,
+        budgets: &[Budget],
+        costs: &HashMap<String, u64>,
+        now: DateTime<Utc>,
+    ) -> AapiResult<Vec<BudgetCharge>> {
+        let mut usage = self.usage.lock().unwrap();
+
+        let mut charges = Vec::with_capacity(budgets.len());
+        for budget in budgets {
+            let current = usage.get(&(principal.to_string(), budget.id.clone()))
+                .filter(|u| !Self::expired(budget, u, now))
+                .map_or(budget.used, |u| u.used);
+            let cost = costs.get(&budget.resource).copied().unwrap_or(0);
+            let used = current.saturating_add(cost);
+            if used > budget.limit {
+                return Err(AapiError::BudgetExceeded {
+                    resource: budget.resource.clone(),
+                    used,
+                    limit: budget.limit,
+                });
+            }
+            charges.push(BudgetCharge {
+                budget_id: budget.id.clone(),
+                resource: budget.resource.clone(),
+                cost,
+                used,
+                remaining: budget.limit - used,
+            });
+        }
+
+        for (budget, charge) in budgets.iter().zip(&charges) {
+            let entry = usage.entry((principal.to_string(), budget.id.clone()))
+                .or_insert(BudgetUsage { used: 0, window_start: now });
+            if Self::expired(budget, entry, now) {
+                entry.window_start = now;
+            }
+            entry.used = charge.used;
+        }
+        Ok(charges)
+    }
+
+    /// Charge `costs` as [`charge`](Self::charge) does, giving them back if
+    /// the reservation is dropped without being committed
+    ///
+    /// Charging before the work starts keeps concurrent requests from
+    /// overspending; the refund covers work that fails before it is recorded.
+    pub fn reserve(
+        &self,
+        principal: &str,
+        budgets: &[Budget],
+        costs: &HashMap<String, u64>,
+    ) -> AapiResult<BudgetReservation<'_>> {
+        let now = Utc::now();
+        let charges = self.charge_at(principal, budgets, costs, now)?;
+        Ok(BudgetReservation {
+            tracker: self,
+            principal: principal.to_string(),
+            charges,
+            charged_at: now,
+            committed: false,
+        })
+    }
+
+    /// Give back `charges`, except for budgets whose window has reset since
+    fn refund(&self, principal: &str, charges: &[BudgetCharge], charged_at: DateTime<Utc>) {
+        let mut usage = self.usage.lock().unwrap();
+        for charge in charges {
+            if let Some(entry) = usage.get_mut(&(principal.to_string(), charge.budget_id.clone())) {
+                if entry.window_start <= charged_at {
+                    entry.used = entry.used.saturating_sub(charge.cost);
+                }
+            }
+        }
+    }
+
+    /// Remaining allowance of a budget for `principal`, without charging it
+    pub fn remaining(&self, principal: &str, budget: &Budget) -> u64 {
+        let usage = self.usage.lock().unwrap();
+        let used = usage.get(&(principal.to_string(), budget.id.clone()))
+            .filter(|u| !Self::expired(budget, u, Utc::now()))
+            .map_or(budget.used, |u| u.used);
+        budget.limit.saturating_sub(used)
+    }
+
+    fn expired(budget: &Budget, usage: &BudgetUsage, now: DateTime<Utc>) -> bool {
+        budget.reset_period_secs > 0
+            && now - usage.window_start >= chrono::Duration::seconds(budget.reset_period_secs as i64)
+    }
+}
+
+/// Charges taken by [`BudgetTracker::reserve`]
+#[must_use = "dropping a reservation refunds its charges"]
+#[derive(Debug)]
+pub struct BudgetReservation<'a> {
+    tracker: &'a BudgetTracker,
+    principal: String,
+    charges: Vec<BudgetCharge>,
+    charged_at: DateTime<Utc>,
+    committed: bool,
+}
+
+impl BudgetReservation<'_> {
+    pub fn charges(&self) -> &[BudgetCharge] {
+        &self.charges
+    }
+
+    /// Keep the charges
+    pub fn commit(mut self) {
+        self.committed = true;
+    }
+}
+
+impl Drop for BudgetReservation<'_> {
+    fn drop(&mut self) {
+        if !self.committed {
+            self.tracker.refund(&self.principal, &self.charges, self.charged_at);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ApprovalLane, PrincipalId, ResourceId};
+    use crate::vakya::*;
+
+    fn vakya(bucket: EffectBucket, budgets: Vec<Budget>) -> Vakya {
+        let mut kriya = Kriya::new("http", "post");
+        kriya.expected_effect = bucket;
+        Vakya::builder()
+            .karta(Karta {
+                pid: PrincipalId::new("agent:spender"),
+                role: None,
+                realm: None,
+                key_id: None,
+                actor_type: ActorType::Agent,
+                delegation_chain: vec![],
+            })
+            .karma(Karma {
+                rid: ResourceId::new("http:https://api.example.com/charge"),
+                kind: None,
+                ns: None,
+                version: None,
+                labels: HashMap::new(),
+            })
+            .kriya(kriya)
+            .adhikarana(Adhikarana {
+                cap: CapabilityRef::Reference { cap_ref: "cap:spend".to_string() },
+                policy_ref: None,
+                ttl: None,
+                budgets,
+                approval_lane: ApprovalLane::None,
+                scopes: vec![],
+                context: None,
+                delegation_chain_cid: None,
+                execution_constraints: None,
+                port_id: None,
+                required_phase: None,
+                required_role: None,
+            })
+            .body(serde_json::json!({ "amount": 3 }))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_effect_cost_estimator() {
+        let estimator = EffectCostEstimator::new().with_unit_cost(EffectBucket::External, 25);
+
+        let external = vakya(EffectBucket::External, vec![]).estimate_cost(&estimator);
+        assert_eq!(external[RESOURCE_API_CALLS], 1);
+        assert_eq!(external[RESOURCE_EXTERNAL_CALLS], 1);
+        assert_eq!(external[RESOURCE_COST_UNITS], 25);
+        assert!(!external.contains_key(RESOURCE_BYTES_WRITTEN));
+
+        let create = vakya(EffectBucket::Create, vec![]).estimate_cost(&estimator);
+        assert_eq!(create[RESOURCE_BYTES_WRITTEN], br#"{"amount":3}"#.len() as u64);
+        assert_eq!(create[RESOURCE_COST_UNITS], 5);
+    }
+
+    #[test]
+    fn test_budget_tracker_charges_all_or_nothing() {
+        let tracker = BudgetTracker::new();
+        let estimator = EffectCostEstimator::new();
+        let calls = Budget::new("calls", RESOURCE_EXTERNAL_CALLS, 2);
+        let mut units = Budget::new("units", RESOURCE_COST_UNITS, 20);
+        units.used = 5;
+        let v = vakya(EffectBucket::External, vec![calls.clone(), units.clone()]);
+        let costs = v.estimate_cost(&estimator);
+        let pid = "agent:spender";
+
+        let charges = tracker.charge(pid, &v.v7_adhikarana.budgets, &costs).unwrap();
+        assert_eq!((charges[0].used, charges[0].remaining), (1, 1));
+        assert_eq!((charges[1].used, charges[1].remaining), (15, 5));
+
+        // The second call fits the call budget but not the unit budget, so
+        // neither is charged
+        let err = tracker.charge(pid, &v.v7_adhikarana.budgets, &costs).unwrap_err();
+        assert!(matches!(
+            err,
+            AapiError::BudgetExceeded { ref resource, used: 25, limit: 20 } if resource == RESOURCE_COST_UNITS
+        ));
+        assert_eq!(tracker.remaining(pid, &calls), 1);
+        assert_eq!(tracker.remaining(pid, &units), 5);
+
+        // Other principals have their own usage
+        assert_eq!(tracker.remaining("agent:other", &calls), 2);
+    }
+
+    #[test]
+    fn test_budget_tracker_resets_after_period() {
+        let tracker = BudgetTracker::new();
+        let mut budget = Budget::new("hourly", RESOURCE_API_CALLS, 1);
+        budget.reset_period_secs = 3600;
+        let costs = HashMap::from([(RESOURCE_API_CALLS.to_string(), 1)]);
+        let budgets = [budget];
+        let now = Utc::now();
+
+        tracker.charge_at("agent:a", &budgets, &costs, now).unwrap();
+        assert!(tracker.charge_at("agent:a", &budgets, &costs, now + chrono::Duration::minutes(59)).is_err());
+        assert!(tracker.charge_at("agent:a", &budgets, &costs, now + chrono::Duration::minutes(61)).is_ok());
+    }
+
+    #[test]
+    fn test_budget_reservation_refunds_unless_committed() {
+        let tracker = BudgetTracker::new();
+        let budgets = [Budget::new("calls", RESOURCE_API_CALLS, 2)];
+        let costs = HashMap::from([(RESOURCE_API_CALLS.to_string(), 1)]);
+
+        let reservation = tracker.reserve("agent:a", &budgets, &costs).unwrap();
+        assert_eq!(reservation.charges()[0].remaining, 1);
+        assert_eq!(tracker.remaining("agent:a", &budgets[0]), 1);
+        drop(reservation);
+        assert_eq!(tracker.remaining("agent:a", &budgets[0]), 2);
+
+        tracker.reserve("agent:a", &budgets, &costs).unwrap().commit();
+        assert_eq!(tracker.remaining("agent:a", &budgets[0]), 1);
+    }
+}
@@ -36,6 +36,9 @@ pub enum AapiError {
     #[error("TTL expired: expired at {expired_at}")]
     TtlExpired { expired_at: String },
 
+    #[error("Not yet valid: execution deferred until {not_before}")]
+    NotYetValid { not_before: String },
+
     #[error("Scope violation: action '{action}' not in allowed scope")]
     ScopeViolation { action: String },
 
@@ -50,7 +53,7 @@ pub enum AapiError {
 pub type AapiResult<T> = Result<T, AapiError>;
 
 /// Reason codes for PRAMĀṆA receipts
-#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ReasonCode {
     /// Action completed successfully
@@ -77,13 +80,39 @@ pub enum ReasonCode {
     TargetError,
     /// Action timed out
     Timeout,
+    /// Target system unreachable or its circuit open; the action was not attempted
+    Unavailable,
     /// Action was cancelled
     Cancelled,
+    /// Action is waiting for its `not_before` time
+    Deferred,
     /// Internal system error
     InternalError,
 }
 
 impl ReasonCode {
+    /// Wire name, as it appears in serialized receipts
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReasonCode::Success => "SUCCESS",
+            ReasonCode::PartialSuccess => "PARTIAL_SUCCESS",
+            ReasonCode::ValidationFailed => "VALIDATION_FAILED",
+            ReasonCode::AuthorizationDenied => "AUTHORIZATION_DENIED",
+            ReasonCode::ScopeViolation => "SCOPE_VIOLATION",
+            ReasonCode::BudgetExceeded => "BUDGET_EXCEEDED",
+            ReasonCode::TtlExpired => "TTL_EXPIRED",
+            ReasonCode::PolicyDenied => "POLICY_DENIED",
+            ReasonCode::ApprovalRequired => "APPROVAL_REQUIRED",
+            ReasonCode::AdapterError => "ADAPTER_ERROR",
+            ReasonCode::TargetError => "TARGET_ERROR",
+            ReasonCode::Timeout => "TIMEOUT",
+            ReasonCode::Unavailable => "UNAVAILABLE",
+            ReasonCode::Cancelled => "CANCELLED",
+            ReasonCode::Deferred => "DEFERRED",
+            ReasonCode::InternalError => "INTERNAL_ERROR",
+        }
+    }
+
     /// Returns true if this is a success code
     pub fn is_success(&self) -> bool {
         matches!(self, ReasonCode::Success | ReasonCode::PartialSuccess)
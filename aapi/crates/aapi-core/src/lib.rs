@@ -8,9 +8,14 @@ pub mod sandhi;
 pub mod validation;
 pub mod error;
 pub mod types;
+pub mod proto;
+pub mod patch;
+pub mod budget;
 
 pub use vakya::*;
 pub use sandhi::*;
 pub use validation::*;
 pub use error::*;
 pub use types::*;
+pub use patch::*;
+pub use budget::*;
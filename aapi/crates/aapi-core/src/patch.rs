@@ -0,0 +1,97 @@
+//! JSON Patch (RFC 6902) computation
+//!
+//! Used for effect deltas and for comparing VĀKYA requests. Objects are
+//! diffed key by key; arrays and scalars are replaced wholesale.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// JSON Patch operation (RFC 6902)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonPatchOp {
+    pub op: String,
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+}
+
+/// Compute the JSON patch that turns `before` into `after`
+pub fn json_patch(before: &Value, after: &Value) -> Vec<JsonPatchOp> {
+    let mut ops = Vec::new();
+    json_patch_recursive("", before, after, &mut ops);
+    ops
+}
+
+fn json_patch_recursive(path: &str, before: &Value, after: &Value, ops: &mut Vec<JsonPatchOp>) {
+    match (before, after) {
+        (Value::Object(b), Value::Object(a)) => {
+            // Check for removed keys
+            for key in b.keys() {
+                if !a.contains_key(key) {
+                    ops.push(JsonPatchOp {
+                        op: "remove".to_string(),
+                        path: pointer(path, key),
+                        value: None,
+                        from: None,
+                    });
+                }
+            }
+            // Check for added or modified keys
+            for (key, after_val) in a {
+                let new_path = pointer(path, key);
+                if let Some(before_val) = b.get(key) {
+                    if before_val != after_val {
+                        json_patch_recursive(&new_path, before_val, after_val, ops);
+                    }
+                } else {
+                    ops.push(JsonPatchOp {
+                        op: "add".to_string(),
+                        path: new_path,
+                        value: Some(after_val.clone()),
+                        from: None,
+                    });
+                }
+            }
+        }
+        _ => {
+            if before != after {
+                ops.push(JsonPatchOp {
+                    op: "replace".to_string(),
+                    path: path.to_string(),
+                    value: Some(after.clone()),
+                    from: None,
+                });
+            }
+        }
+    }
+}
+
+/// Append `key` to a JSON Pointer, escaping `~` and `/` (RFC 6901)
+fn pointer(path: &str, key: &str) -> String {
+    format!("{}/{}", path, key.replace('~', "~0").replace('/', "~1"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_json_patch_ops() {
+        let before = json!({"a": 1, "b": {"c": 2, "d": [1]}, "gone": true});
+        let after = json!({"a": 1, "b": {"c": 3, "d": [1, 2]}, "app/name": "x"});
+
+        let patch = json_patch(&before, &after);
+        let ops: Vec<(&str, &str)> = patch.iter().map(|op| (op.op.as_str(), op.path.as_str())).collect();
+        assert_eq!(ops, vec![
+            ("remove", "/gone"),
+            ("add", "/app~1name"),
+            ("replace", "/b/c"),
+            ("replace", "/b/d"),
+        ]);
+        assert_eq!(patch[3].value, Some(json!([1, 2])));
+        assert!(json_patch(&before, &before).is_empty());
+    }
+}
@@ -0,0 +1,309 @@
+//! Protobuf wire encoding for VĀKYA
+//!
+//! The schema lives in `proto/vakya.proto`. Protobuf is only a transport:
+//! decoding rebuilds the same [`Vakya`] the JSON form would, so Sandhi
+//! canonicalization and signatures are unaffected by the encoding.
+
+use prost::Message;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::error::{AapiError, AapiResult};
+use crate::types::{EffectBucket, Namespace, PrincipalId, ResourceId, SemanticVersion};
+use crate::vakya::{ActorType, Karma, Karta, Kriya, Vakya, VakyaId};
+
+/// Types generated from `proto/vakya.proto`
+pub mod pb {
+    include!(concat!(env!("OUT_DIR"), "/aapi.v1.rs"));
+}
+
+impl Vakya {
+    /// Encode as a protobuf `aapi.v1.Vakya` message
+    pub fn to_protobuf(&self) -> AapiResult<Vec<u8>> {
+        Ok(pb::Vakya::try_from(self)?.encode_to_vec())
+    }
+
+    /// Decode a protobuf `aapi.v1.Vakya` message
+    pub fn from_protobuf(bytes: &[u8]) -> AapiResult<Self> {
+        let message = pb::Vakya::decode(bytes)
+            .map_err(|e| AapiError::Schema(format!("Invalid protobuf VĀKYA: {}", e)))?;
+        Self::try_from(message)
+    }
+}
+
+impl TryFrom<&Vakya> for pb::Vakya {
+    type Error = AapiError;
+
+    fn try_from(vakya: &Vakya) -> AapiResult<Self> {
+        Ok(Self {
+            vakya_version: Some(pb::SemanticVersion {
+                major: vakya.vakya_version.major,
+                minor: vakya.vakya_version.minor,
+                patch: vakya.vakya_version.patch,
+            }),
+            vakya_id: vakya.vakya_id.0.clone(),
+            v1_karta: Some(pb::Karta::try_from(&vakya.v1_karta)?),
+            v2_karma: Some(pb::Karma::from(&vakya.v2_karma)),
+            v3_kriya: Some(pb::Kriya::try_from(&vakya.v3_kriya)?),
+            v4_karana: vakya.v4_karana.as_ref().map(to_json).transpose()?,
+            v5_sampradana: vakya.v5_sampradana.as_ref().map(to_json).transpose()?,
+            v6_apadana: vakya.v6_apadana.as_ref().map(to_json).transpose()?,
+            v7_adhikarana: to_json(&vakya.v7_adhikarana)?,
+            v8_pratyaya: vakya.v8_pratyaya.as_ref().map(to_json).transpose()?,
+            body_type: to_json(&vakya.body_type)?,
+            body: to_json(&vakya.body)?,
+            meta: to_json(&vakya.meta)?,
+        })
+    }
+}
+
+impl TryFrom<pb::Vakya> for Vakya {
+    type Error = AapiError;
+
+    fn try_from(message: pb::Vakya) -> AapiResult<Self> {
+        let version = message.vakya_version
+            .ok_or_else(|| AapiError::MissingField("vakya_version".into()))?;
+
+        Ok(Self {
+            vakya_version: SemanticVersion::new(version.major, version.minor, version.patch),
+            vakya_id: VakyaId(message.vakya_id),
+            v1_karta: message.v1_karta
+                .ok_or_else(|| AapiError::MissingField("v1_karta".into()))?
+                .try_into()?,
+            v2_karma: message.v2_karma
+                .ok_or_else(|| AapiError::MissingField("v2_karma".into()))?
+                .into(),
+            v3_kriya: message.v3_kriya
+                .ok_or_else(|| AapiError::MissingField("v3_kriya".into()))?
+                .try_into()?,
+            v4_karana: message.v4_karana.as_deref().map(from_json).transpose()?,
+            v5_sampradana: message.v5_sampradana.as_deref().map(from_json).transpose()?,
+            v6_apadana: message.v6_apadana.as_deref().map(from_json).transpose()?,
+            v7_adhikarana: from_json(&message.v7_adhikarana)?,
+            v8_pratyaya: message.v8_pratyaya.as_deref().map(from_json).transpose()?,
+            body_type: from_json(&message.body_type)?,
+            body: from_json(&message.body)?,
+            meta: from_json(&message.meta)?,
+        })
+    }
+}
+
+impl TryFrom<&Karta> for pb::Karta {
+    type Error = AapiError;
+
+    fn try_from(karta: &Karta) -> AapiResult<Self> {
+        let actor_type = match karta.actor_type {
+            ActorType::Human => pb::ActorType::Human,
+            ActorType::Agent => pb::ActorType::Agent,
+            ActorType::Service => pb::ActorType::Service,
+            ActorType::Workflow => pb::ActorType::Workflow,
+        };
+
+        Ok(Self {
+            pid: karta.pid.0.clone(),
+            role: karta.role.clone(),
+            realm: karta.realm.clone(),
+            key_id: karta.key_id.clone(),
+            actor_type: actor_type.into(),
+            delegation_chain: if karta.delegation_chain.is_empty() {
+                vec![]
+            } else {
+                to_json(&karta.delegation_chain)?
+            },
+        })
+    }
+}
+
+impl TryFrom<pb::Karta> for Karta {
+    type Error = AapiError;
+
+    fn try_from(karta: pb::Karta) -> AapiResult<Self> {
+        let actor_type = match pb::ActorType::try_from(karta.actor_type) {
+            Ok(pb::ActorType::Human) => ActorType::Human,
+            Ok(pb::ActorType::Agent) => ActorType::Agent,
+            Ok(pb::ActorType::Service) => ActorType::Service,
+            Ok(pb::ActorType::Workflow) => ActorType::Workflow,
+            Err(_) => {
+                return Err(AapiError::InvalidField {
+                    field: "v1_karta.actor_type".into(),
+                    reason: format!("unknown actor type {}", karta.actor_type),
+                })
+            }
+        };
+
+        Ok(Self {
+            pid: PrincipalId(karta.pid),
+            role: karta.role,
+            realm: karta.realm,
+            key_id: karta.key_id,
+            actor_type,
+            delegation_chain: if karta.delegation_chain.is_empty() {
+                vec![]
+            } else {
+                from_json(&karta.delegation_chain)?
+            },
+        })
+    }
+}
+
+impl From<&Karma> for pb::Karma {
+    fn from(karma: &Karma) -> Self {
+        Self {
+            rid: karma.rid.0.clone(),
+            kind: karma.kind.clone(),
+            ns: karma.ns.as_ref().map(|ns| ns.0.clone()),
+            version: karma.version.clone(),
+            labels: karma.labels.clone(),
+        }
+    }
+}
+
+impl From<pb::Karma> for Karma {
+    fn from(karma: pb::Karma) -> Self {
+        Self {
+            rid: ResourceId(karma.rid),
+            kind: karma.kind,
+            ns: karma.ns.map(Namespace),
+            version: karma.version,
+            labels: karma.labels,
+        }
+    }
+}
+
+impl TryFrom<&Kriya> for pb::Kriya {
+    type Error = AapiError;
+
+    fn try_from(kriya: &Kriya) -> AapiResult<Self> {
+        let expected_effect = match serde_json::to_value(kriya.expected_effect)? {
+            serde_json::Value::String(name) => name,
+            other => other.to_string(),
+        };
+
+        Ok(Self {
+            action: kriya.action.clone(),
+            domain: kriya.domain.clone(),
+            verb: kriya.verb.clone(),
+            expected_effect,
+            idempotent: kriya.idempotent,
+        })
+    }
+}
+
+impl TryFrom<pb::Kriya> for Kriya {
+    type Error = AapiError;
+
+    fn try_from(kriya: pb::Kriya) -> AapiResult<Self> {
+        let expected_effect = if kriya.expected_effect.is_empty() {
+            EffectBucket::default()
+        } else {
+            serde_json::from_value(serde_json::Value::String(kriya.expected_effect))?
+        };
+
+        Ok(Self {
+            action: kriya.action,
+            domain: kriya.domain,
+            verb: kriya.verb,
+            expected_effect,
+            idempotent: kriya.idempotent,
+        })
+    }
+}
+
+fn to_json<T: Serialize>(value: &T) -> AapiResult<Vec<u8>> {
+    Ok(serde_json::to_vec(value)?)
+}
+
+fn from_json<T: DeserializeOwned>(bytes: &[u8]) -> AapiResult<T> {
+    Ok(serde_json::from_slice(bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sandhi::canonicalize;
+    use crate::types::{ApprovalLane, Timestamp};
+    use crate::vakya::*;
+
+    fn sample_vakya() -> Vakya {
+        Vakya::builder()
+            .karta(Karta {
+                pid: PrincipalId::new("agent:ingest"),
+                role: Some("writer".to_string()),
+                realm: None,
+                key_id: Some("key-1".to_string()),
+                actor_type: ActorType::Agent,
+                delegation_chain: vec![DelegationHop {
+                    delegator: PrincipalId::new("user:alice"),
+                    delegated_at: Timestamp::now(),
+                    reason: Some("batch job".to_string()),
+                    attenuation: None,
+                }],
+            })
+            .karma(Karma {
+                rid: ResourceId::new("file:/tmp/aapi/report.json"),
+                kind: Some("file".to_string()),
+                ns: Some(Namespace::new("team-a")),
+                version: None,
+                labels: [("env".to_string(), "prod".to_string())].into(),
+            })
+            .kriya(Kriya::new("file", "write"))
+            .adhikarana(Adhikarana {
+                cap: CapabilityRef::Reference { cap_ref: "cap:ingest".to_string() },
+                policy_ref: None,
+                ttl: None,
+                budgets: vec![],
+                approval_lane: ApprovalLane::None,
+                scopes: vec!["files".to_string()],
+                context: None,
+                delegation_chain_cid: None,
+                execution_constraints: None,
+                port_id: None,
+                required_phase: None,
+                required_role: None,
+            })
+            .body(serde_json::json!({"content": "hello", "ratio": 0.1, "count": 3}))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_protobuf_round_trip_preserves_hash() {
+        let mut vakya = sample_vakya();
+        vakya.v4_karana = Some(Karana {
+            via: Some("grpc".to_string()),
+            adapter: None,
+            tool: Some("writer".to_string()),
+            metadata: Default::default(),
+        });
+        vakya.v3_kriya.expected_effect = EffectBucket::Update;
+        let original = canonicalize(&vakya).unwrap().vakya_hash;
+
+        let json = serde_json::to_vec(&vakya).unwrap();
+        let from_json: Vakya = serde_json::from_slice(&json).unwrap();
+
+        let encoded = vakya.to_protobuf().unwrap();
+        let from_protobuf = Vakya::from_protobuf(&encoded).unwrap();
+
+        assert_eq!(canonicalize(&from_json).unwrap().vakya_hash, original);
+        assert_eq!(canonicalize(&from_protobuf).unwrap().vakya_hash, original);
+
+        // protobuf -> JSON -> protobuf is stable too
+        let reencoded = serde_json::from_value::<Vakya>(serde_json::to_value(&from_protobuf).unwrap())
+            .unwrap()
+            .to_protobuf()
+            .unwrap();
+        assert_eq!(reencoded, encoded);
+    }
+
+    #[test]
+    fn test_protobuf_rejects_malformed_input() {
+        assert!(matches!(Vakya::from_protobuf(b"\xff\xff"), Err(AapiError::Schema(_))));
+
+        let mut message = pb::Vakya::try_from(&sample_vakya()).unwrap();
+        message.v1_karta = None;
+        assert!(matches!(
+            Vakya::from_protobuf(&message.encode_to_vec()),
+            Err(AapiError::MissingField(_))
+        ));
+    }
+}
@@ -87,9 +87,14 @@ impl Namespace {
         &self.0
     }
 
-    /// Check if this namespace contains another namespace
+    /// Check if this namespace contains another namespace: the same
+    /// namespace or one nested under it (`org.example` contains
+    /// `org.example.service` but not `org.examples`)
     pub fn contains(&self, other: &Namespace) -> bool {
-        other.0.starts_with(&self.0)
+        match other.0.strip_prefix(&self.0) {
+            Some(rest) => self.0.is_empty() || rest.is_empty() || rest.starts_with('.'),
+            None => false,
+        }
     }
 }
 
@@ -281,6 +286,7 @@ pub enum HashAlgorithm {
     Sha256,
     Sha384,
     Sha512,
+    Blake3,
 }
 
 impl Default for HashAlgorithm {
@@ -289,6 +295,108 @@ impl Default for HashAlgorithm {
     }
 }
 
+impl HashAlgorithm {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha256 => "sha256",
+            HashAlgorithm::Sha384 => "sha384",
+            HashAlgorithm::Sha512 => "sha512",
+            HashAlgorithm::Blake3 => "blake3",
+        }
+    }
+
+    /// Start an incremental hash
+    pub fn hasher(&self) -> ContentHasher {
+        match self {
+            HashAlgorithm::Sha256 => ContentHasher::Sha256(sha2::Sha256::default()),
+            HashAlgorithm::Sha384 => ContentHasher::Sha384(sha2::Sha384::default()),
+            HashAlgorithm::Sha512 => ContentHasher::Sha512(sha2::Sha512::default()),
+            HashAlgorithm::Blake3 => ContentHasher::Blake3(Box::default()),
+        }
+    }
+
+    /// Hash `data` and return the hex-encoded digest
+    pub fn digest_hex(&self, data: &[u8]) -> String {
+        let mut hasher = self.hasher();
+        hasher.update(data);
+        hasher.finalize_hex()
+    }
+
+    /// Label a hex digest with its algorithm for storage.
+    ///
+    /// SHA-256 digests stay bare so records written before hashing was
+    /// configurable keep their meaning.
+    pub fn label(&self, hex_digest: &str) -> String {
+        match self {
+            HashAlgorithm::Sha256 => hex_digest.to_string(),
+            other => format!("{}:{}", other.as_str(), hex_digest),
+        }
+    }
+
+    /// Split a stored hash into its algorithm and hex digest.
+    ///
+    /// Unlabeled values are SHA-256.
+    pub fn split_labeled(value: &str) -> (HashAlgorithm, &str) {
+        if let Some((prefix, digest)) = value.split_once(':') {
+            if let Ok(algorithm) = prefix.parse() {
+                return (algorithm, digest);
+            }
+        }
+        (HashAlgorithm::Sha256, value)
+    }
+}
+
+impl std::str::FromStr for HashAlgorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "sha256" => Ok(HashAlgorithm::Sha256),
+            "sha384" => Ok(HashAlgorithm::Sha384),
+            "sha512" => Ok(HashAlgorithm::Sha512),
+            "blake3" => Ok(HashAlgorithm::Blake3),
+            other => Err(format!("Unknown hash algorithm: {}", other)),
+        }
+    }
+}
+
+/// Incremental hasher for any supported `HashAlgorithm`
+#[derive(Clone)]
+pub enum ContentHasher {
+    Sha256(sha2::Sha256),
+    Sha384(sha2::Sha384),
+    Sha512(sha2::Sha512),
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl ContentHasher {
+    pub fn update(&mut self, data: &[u8]) {
+        use sha2::Digest;
+        match self {
+            ContentHasher::Sha256(h) => h.update(data),
+            ContentHasher::Sha384(h) => h.update(data),
+            ContentHasher::Sha512(h) => h.update(data),
+            ContentHasher::Blake3(h) => {
+                h.update(data);
+            }
+        }
+    }
+
+    pub fn finalize(self) -> Vec<u8> {
+        use sha2::Digest;
+        match self {
+            ContentHasher::Sha256(h) => h.finalize().to_vec(),
+            ContentHasher::Sha384(h) => h.finalize().to_vec(),
+            ContentHasher::Sha512(h) => h.finalize().to_vec(),
+            ContentHasher::Blake3(h) => h.finalize().as_bytes().to_vec(),
+        }
+    }
+
+    pub fn finalize_hex(self) -> String {
+        hex::encode(self.finalize())
+    }
+}
+
 /// Content hash with algorithm identifier
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ContentHash {
@@ -303,6 +411,28 @@ impl ContentHash {
             value: value.into(),
         }
     }
+
+    /// Hash `data` with the given algorithm
+    pub fn compute(algorithm: HashAlgorithm, data: &[u8]) -> Self {
+        Self {
+            algorithm,
+            value: algorithm.digest_hex(data),
+        }
+    }
+
+    /// Parse a stored (possibly labeled) hash string
+    pub fn from_labeled(value: &str) -> Self {
+        let (algorithm, digest) = HashAlgorithm::split_labeled(value);
+        Self {
+            algorithm,
+            value: digest.to_string(),
+        }
+    }
+
+    /// Storage form: bare hex for SHA-256, `<algo>:<hex>` otherwise
+    pub fn to_labeled(&self) -> String {
+        self.algorithm.label(&self.value)
+    }
 }
 
 impl std::fmt::Display for ContentHash {
@@ -328,7 +458,10 @@ mod tests {
         let other = Namespace::new("com.other");
 
         assert!(parent.contains(&child));
+        assert!(parent.contains(&parent));
         assert!(!parent.contains(&other));
+        assert!(!parent.contains(&Namespace::new("org.examples")));
+        assert!(!child.contains(&parent));
     }
 
     #[test]
@@ -340,6 +473,23 @@ mod tests {
         assert_eq!(budget.remaining(), 50);
     }
 
+    #[test]
+    fn test_hash_algorithm_labels() {
+        let sha = ContentHash::compute(HashAlgorithm::Sha256, b"abc");
+        assert_eq!(sha.value, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(sha.to_labeled(), sha.value);
+        assert_eq!(ContentHash::from_labeled(&sha.value), sha);
+
+        let blake = ContentHash::compute(HashAlgorithm::Blake3, b"abc");
+        assert_eq!(blake.value, "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85");
+        assert!(blake.to_labeled().starts_with("blake3:"));
+        assert_eq!(ContentHash::from_labeled(&blake.to_labeled()), blake);
+
+        // Sentinels and unknown prefixes are left alone
+        assert_eq!(HashAlgorithm::split_labeled("NOT_EXISTS"), (HashAlgorithm::Sha256, "NOT_EXISTS"));
+        assert_eq!(HashAlgorithm::split_labeled("md5:abc"), (HashAlgorithm::Sha256, "md5:abc"));
+    }
+
     #[test]
     fn test_trace_context_child() {
         let parent = TraceContext::new();
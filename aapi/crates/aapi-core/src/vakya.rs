@@ -8,6 +8,7 @@ use uuid::Uuid;
 
 use crate::types::*;
 use crate::error::{AapiError, AapiResult};
+use crate::patch::{json_patch, JsonPatchOp};
 
 /// VĀKYA - The complete Agentic Action Request envelope
 /// 
@@ -73,6 +74,9 @@ impl Vakya {
     }
 
     /// Validate the VĀKYA structure
+    ///
+    /// A future `ttl.not_before` is rejected for immediate-mode VĀKYAs
+    /// (`ApprovalLane::None`); other lanes may be held until that time.
     pub fn validate(&self) -> AapiResult<()> {
         // Validate required fields
         if self.v1_karta.pid.0.is_empty() {
@@ -92,6 +96,20 @@ impl Vakya {
                     expired_at: ttl.expires_at.to_string(),
                 });
             }
+            if let Some(ref not_before) = ttl.not_before {
+                if not_before.0 >= ttl.expires_at.0 {
+                    return Err(AapiError::InvalidField {
+                        field: "v7_adhikarana.ttl.not_before".into(),
+                        reason: "must be earlier than expires_at".into(),
+                    });
+                }
+                // Immediate-mode VĀKYAs run on submission, so they cannot wait
+                if ttl.is_deferred() && matches!(self.v7_adhikarana.approval_lane, ApprovalLane::None) {
+                    return Err(AapiError::NotYetValid {
+                        not_before: not_before.to_string(),
+                    });
+                }
+            }
         }
 
         // Validate budgets
@@ -107,6 +125,59 @@ impl Vakya {
 
         Ok(())
     }
+
+    /// Compare with another VĀKYA, field by field
+    ///
+    /// Returns one entry per top-level field that differs, in declaration
+    /// order. Every field listed feeds the Sandhi hash, so an empty result
+    /// means both requests canonicalize identically.
+    pub fn diff(&self, other: &Vakya) -> Vec<VakyaFieldChange> {
+        let before = serde_json::to_value(self).expect("VĀKYA serializes to JSON");
+        let after = serde_json::to_value(other).expect("VĀKYA serializes to JSON");
+        let patch = json_patch(&before, &after);
+
+        VAKYA_FIELDS
+            .iter()
+            .filter_map(|field| {
+                let ops: Vec<JsonPatchOp> = patch
+                    .iter()
+                    .filter(|op| op.path.split('/').nth(1) == Some(*field))
+                    .cloned()
+                    .collect();
+                (!ops.is_empty()).then(|| VakyaFieldChange {
+                    field: field.to_string(),
+                    patch: ops,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Serialized top-level VĀKYA fields, in declaration order
+const VAKYA_FIELDS: [&str; 13] = [
+    "vakya_version",
+    "vakya_id",
+    "v1_karta",
+    "v2_karma",
+    "v3_kriya",
+    "v4_karana",
+    "v5_sampradana",
+    "v6_apadana",
+    "v7_adhikarana",
+    "v8_pratyaya",
+    "body_type",
+    "body",
+    "meta",
+];
+
+/// A top-level VĀKYA field that differs between two requests
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VakyaFieldChange {
+    /// Field name as serialized, e.g. `v1_karta` or `body`
+    pub field: String,
+    /// Operations turning the first VĀKYA's field into the second's,
+    /// with paths from the VĀKYA root
+    pub patch: Vec<JsonPatchOp>,
 }
 
 /// Unique identifier for a VĀKYA request
@@ -537,24 +608,57 @@ pub enum CapabilityRef {
     /// Reference to an external capability
     Reference { cap_ref: String },
     /// Inline capability token
-    Inline(CapabilityToken),
+    Inline(Box<CapabilityToken>),
 }
 
 /// Inline capability token
+///
+/// Carries every field covered by the issuer's signature so a signed token
+/// survives being embedded in a VĀKYA and can be verified by the gateway.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CapabilityToken {
     /// Token ID
     pub token_id: String,
+    /// Version of the token format
+    #[serde(default)]
+    pub version: u32,
     /// Issuer of the token
     pub issuer: PrincipalId,
     /// Subject (who the token is for)
     pub subject: PrincipalId,
+    /// Audience (intended verifier)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audience: Option<String>,
     /// Allowed actions (glob patterns)
     pub actions: Vec<String>,
     /// Allowed resources (glob patterns)
     pub resources: Vec<String>,
+    /// Allowed namespaces
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub namespaces: Vec<String>,
+    /// Token issuance time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issued_at: Option<Timestamp>,
+    /// Token activation time (not valid before)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not_before: Option<Timestamp>,
     /// Token expiration
     pub expires_at: Timestamp,
+    /// Budget constraints
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub budgets: Vec<Budget>,
+    /// Parent token ID (for delegation chain)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_token_id: Option<String>,
+    /// Delegation depth (0 = root token)
+    #[serde(default)]
+    pub delegation_depth: u32,
+    /// Maximum allowed delegation depth
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_delegation_depth: Option<u32>,
+    /// Key ID used for signing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_id: Option<String>,
     /// Token signature
     #[serde(skip_serializing_if = "Option::is_none")]
     pub signature: Option<String>,
@@ -570,6 +674,9 @@ pub struct Caveat {
     pub caveat_type: String,
     /// Caveat value
     pub value: serde_json::Value,
+    /// Human-readable description
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
 }
 
 /// TTL constraints
@@ -580,6 +687,16 @@ pub struct TtlConstraint {
     /// Maximum duration in milliseconds
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_duration_ms: Option<u64>,
+    /// Earliest time the action may execute
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not_before: Option<Timestamp>,
+}
+
+impl TtlConstraint {
+    /// Returns true if `not_before` is set and still in the future
+    pub fn is_deferred(&self) -> bool {
+        self.not_before.as_ref().is_some_and(|t| !t.is_expired())
+    }
 }
 
 /// Authority context constraints
@@ -883,6 +1000,7 @@ mod tests {
             ttl: Some(TtlConstraint {
                 expires_at: Timestamp(chrono::Utc::now() + Duration::hours(1)),
                 max_duration_ms: None,
+                not_before: None,
             }),
             budgets: vec![],
             approval_lane: ApprovalLane::None,
@@ -948,9 +1066,98 @@ mod tests {
         assert!(matches!(result, Err(AapiError::MissingField(_))));
     }
 
+    #[test]
+    fn test_vakya_validation_not_before() {
+        let build = |approval_lane: ApprovalLane, not_before: Duration| {
+            let mut adhikarana = create_test_adhikarana();
+            adhikarana.approval_lane = approval_lane;
+            if let Some(ttl) = adhikarana.ttl.as_mut() {
+                ttl.not_before = Some(Timestamp(chrono::Utc::now() + not_before));
+            }
+            Vakya::builder()
+                .karta(Karta {
+                    pid: PrincipalId::new("user:alice"),
+                    role: None,
+                    realm: None,
+                    key_id: None,
+                    actor_type: ActorType::Human,
+                    delegation_chain: vec![],
+                })
+                .karma(Karma {
+                    rid: ResourceId::new("file:/data/report.pdf"),
+                    kind: None,
+                    ns: None,
+                    version: None,
+                    labels: std::collections::HashMap::new(),
+                })
+                .kriya(Kriya::new("file", "read"))
+                .adhikarana(adhikarana)
+                .build()
+        };
+
+        // Immediate mode cannot wait for a future not_before
+        let result = build(ApprovalLane::None, Duration::minutes(5));
+        assert!(matches!(result, Err(AapiError::NotYetValid { .. })));
+        assert!(build(ApprovalLane::None, Duration::minutes(-5)).is_ok());
+
+        // Approval lanes defer execution, so a future not_before is allowed
+        assert!(build(ApprovalLane::Sync, Duration::minutes(5)).is_ok());
+
+        // not_before must fall inside the TTL window
+        let result = build(ApprovalLane::Sync, Duration::hours(2));
+        assert!(matches!(result, Err(AapiError::InvalidField { .. })));
+    }
+
     #[test]
     fn test_kriya_parse_action() {
         let kriya = Kriya::new("database", "query");
         assert_eq!(kriya.parse_action(), Some(("database", "query")));
     }
+
+    #[test]
+    fn test_vakya_diff() {
+        let vakya = Vakya::builder()
+            .karta(Karta {
+                pid: PrincipalId::new("user:alice"),
+                role: Some("admin".to_string()),
+                realm: None,
+                key_id: None,
+                actor_type: ActorType::Human,
+                delegation_chain: vec![],
+            })
+            .karma(Karma {
+                rid: ResourceId::new("file:/data/report.pdf"),
+                kind: Some("file".to_string()),
+                ns: None,
+                version: None,
+                labels: std::collections::HashMap::new(),
+            })
+            .kriya(Kriya::new("file", "read"))
+            .adhikarana(create_test_adhikarana())
+            .body(serde_json::json!({"offset": 0}))
+            .build()
+            .unwrap();
+        assert!(vakya.diff(&vakya.clone()).is_empty());
+
+        let mut other = vakya.clone();
+        other.v1_karta.role = Some("viewer".to_string());
+        other.v4_karana = Some(Karana {
+            via: Some("cli".to_string()),
+            adapter: None,
+            tool: None,
+            metadata: Default::default(),
+        });
+        other.body = serde_json::json!({"offset": 10});
+
+        let changes = vakya.diff(&other);
+        let fields: Vec<&str> = changes.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(fields, vec!["v1_karta", "v4_karana", "body"]);
+        assert_eq!(changes[0].patch[0].path, "/v1_karta/role");
+        assert_eq!(changes[0].patch[0].value, Some(serde_json::json!("viewer")));
+        assert_eq!(changes[1].patch[0].op, "add");
+        assert_eq!(changes[2].patch[0].path, "/body/offset");
+
+        // Reversed, the optional slot is removed
+        assert_eq!(other.diff(&vakya)[1].patch[0].op, "remove");
+    }
 }
@@ -1,11 +1,13 @@
 //! Validation module for AAPI schemas and requests
 
+use serde::{Deserialize, Serialize};
+
 use crate::error::{AapiError, AapiResult};
 use crate::vakya::{Vakya, CapabilityRef, CapabilityToken};
-use crate::types::Namespace;
+use crate::types::{ApprovalLane, Namespace};
 
 /// Validation result with detailed errors
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValidationResult {
     pub valid: bool,
     pub errors: Vec<ValidationError>,
@@ -48,7 +50,7 @@ impl ValidationResult {
 }
 
 /// Validation error details
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValidationError {
     pub path: String,
     pub code: ValidationErrorCode,
@@ -66,7 +68,7 @@ impl ValidationError {
 }
 
 /// Validation error codes
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ValidationErrorCode {
     MissingRequired,
     InvalidFormat,
@@ -77,10 +79,11 @@ pub enum ValidationErrorCode {
     SchemaViolation,
     CapabilityInvalid,
     SignatureInvalid,
+    NotYetValid,
 }
 
 /// Validation warning details
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValidationWarning {
     pub path: String,
     pub code: ValidationWarningCode,
@@ -98,7 +101,7 @@ impl ValidationWarning {
 }
 
 /// Validation warning codes
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ValidationWarningCode {
     DeprecatedField,
     NearExpiration,
@@ -106,10 +109,67 @@ pub enum ValidationWarningCode {
     UnknownExtension,
 }
 
+/// VĀKYA slots whose own fields are checked for unknown names
+const VAKYA_SLOTS: [&str; 9] = [
+    "v1_karta",
+    "v2_karma",
+    "v3_kriya",
+    "v4_karana",
+    "v5_sampradana",
+    "v6_apadana",
+    "v7_adhikarana",
+    "v8_pratyaya",
+    "meta",
+];
+
+/// Top-level and slot fields of a raw VĀKYA document that `Vakya` does not
+/// recognise, as dotted paths
+///
+/// Deserialization silently drops these. A field is recognised when it
+/// survives a parse and re-serialize round trip; fields holding `null` or an
+/// empty array or object are not reported, since optional fields with those
+/// values are omitted on serialization too.
+pub fn unknown_vakya_fields(raw: &serde_json::Value) -> AapiResult<Vec<String>> {
+    let vakya: Vakya = serde_json::from_value(raw.clone())?;
+    let known = serde_json::to_value(&vakya)?;
+
+    let mut unknown = unknown_keys(raw, &known, None);
+    for slot in VAKYA_SLOTS {
+        if let (Some(raw_slot), Some(known_slot)) = (raw.get(slot), known.get(slot)) {
+            unknown.extend(unknown_keys(raw_slot, known_slot, Some(slot)));
+        }
+    }
+    Ok(unknown)
+}
+
+fn unknown_keys(raw: &serde_json::Value, known: &serde_json::Value, prefix: Option<&str>) -> Vec<String> {
+    let (Some(raw), Some(known)) = (raw.as_object(), known.as_object()) else {
+        return Vec::new();
+    };
+    let is_empty = |value: &serde_json::Value| match value {
+        serde_json::Value::Null => true,
+        serde_json::Value::Array(items) => items.is_empty(),
+        serde_json::Value::Object(fields) => fields.is_empty(),
+        _ => false,
+    };
+
+    raw.iter()
+        .filter(|(key, value)| !known.contains_key(*key) && !is_empty(value))
+        .map(|(key, _)| match prefix {
+            Some(prefix) => format!("{}.{}", prefix, key),
+            None => key.clone(),
+        })
+        .collect()
+}
+
 /// Validator for VĀKYA requests
 pub struct VakyaValidator {
     /// Strict mode fails on warnings
     strict: bool,
+    /// Reject raw VĀKYAs carrying fields `Vakya` does not recognise
+    deny_unknown_fields: bool,
+    /// Leave inline capability tokens to a full verifier
+    defer_capability_checks: bool,
     /// Custom validators
     custom_validators: Vec<Box<dyn Fn(&Vakya) -> ValidationResult + Send + Sync>>,
 }
@@ -124,6 +184,8 @@ impl VakyaValidator {
     pub fn new() -> Self {
         Self {
             strict: false,
+            deny_unknown_fields: false,
+            defer_capability_checks: false,
             custom_validators: vec![],
         }
     }
@@ -133,6 +195,21 @@ impl VakyaValidator {
         self
     }
 
+    /// Make [`validate_json`](Self::validate_json) report unrecognised
+    /// top-level and slot fields as `SchemaViolation` errors instead of
+    /// dropping them
+    pub fn deny_unknown_fields(mut self) -> Self {
+        self.deny_unknown_fields = true;
+        self
+    }
+
+    /// Skip the structural checks on inline capability tokens, for callers
+    /// that verify them in full (signature, expiry, scope) afterwards
+    pub fn defer_capability_checks(mut self) -> Self {
+        self.defer_capability_checks = true;
+        self
+    }
+
     pub fn add_validator<F>(mut self, validator: F) -> Self
     where
         F: Fn(&Vakya) -> ValidationResult + Send + Sync + 'static,
@@ -179,6 +256,24 @@ impl VakyaValidator {
         result
     }
 
+    /// Parse and validate a raw VĀKYA document
+    pub fn validate_json(&self, raw: &serde_json::Value) -> AapiResult<(Vakya, ValidationResult)> {
+        let vakya: Vakya = serde_json::from_value(raw.clone())?;
+        let mut result = self.validate(&vakya);
+
+        if self.deny_unknown_fields {
+            for path in unknown_vakya_fields(raw)? {
+                result.add_error(ValidationError::new(
+                    path.clone(),
+                    ValidationErrorCode::SchemaViolation,
+                    format!("Unknown field: {}", path),
+                ));
+            }
+        }
+
+        Ok((vakya, result))
+    }
+
     fn validate_karta(&self, vakya: &Vakya) -> ValidationResult {
         let mut result = ValidationResult::ok();
 
@@ -253,6 +348,7 @@ impl VakyaValidator {
                     ));
                 }
             }
+            CapabilityRef::Inline(_) if self.defer_capability_checks => {}
             CapabilityRef::Inline(token) => {
                 result.merge(self.validate_capability_token(token));
             }
@@ -311,6 +407,24 @@ impl VakyaValidator {
                 ));
             }
 
+            if let Some(ref not_before) = ttl.not_before {
+                if not_before.0 >= ttl.expires_at.0 {
+                    result.add_error(ValidationError::new(
+                        "v7_adhikarana.ttl.not_before",
+                        ValidationErrorCode::InvalidValue,
+                        "not_before must be earlier than expires_at",
+                    ));
+                }
+                // Immediate-mode VĀKYAs run on submission, so they cannot wait
+                if ttl.is_deferred() && matches!(vakya.v7_adhikarana.approval_lane, ApprovalLane::None) {
+                    result.add_error(ValidationError::new(
+                        "v7_adhikarana.ttl.not_before",
+                        ValidationErrorCode::NotYetValid,
+                        format!("Request is not valid before {}", not_before),
+                    ));
+                }
+            }
+
             // Warn if expiring soon (within 60 seconds)
             let now = chrono::Utc::now();
             let expires = ttl.expires_at.0;
@@ -506,6 +620,105 @@ impl NamespaceValidator {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::{PrincipalId, ResourceId, Timestamp};
+    use crate::vakya::*;
+    use std::collections::HashMap;
+
+    fn raw_vakya() -> serde_json::Value {
+        let vakya = Vakya::builder()
+            .karta(Karta {
+                pid: PrincipalId::new("user:alice"),
+                role: None,
+                realm: None,
+                key_id: None,
+                actor_type: ActorType::Human,
+                delegation_chain: vec![],
+            })
+            .karma(Karma {
+                rid: ResourceId::new("file:/data/report.pdf"),
+                kind: None,
+                ns: None,
+                version: None,
+                labels: HashMap::new(),
+            })
+            .kriya(Kriya::new("file", "read"))
+            .adhikarana(Adhikarana {
+                cap: CapabilityRef::Reference { cap_ref: "cap:read".to_string() },
+                policy_ref: None,
+                ttl: None,
+                budgets: vec![],
+                approval_lane: ApprovalLane::None,
+                scopes: vec![],
+                context: None,
+                delegation_chain_cid: None,
+                execution_constraints: None,
+                port_id: None,
+                required_phase: None,
+                required_role: None,
+            })
+            .build()
+            .unwrap();
+        serde_json::to_value(&vakya).unwrap()
+    }
+
+    #[test]
+    fn test_unknown_vakya_fields() {
+        let mut raw = raw_vakya();
+        assert!(unknown_vakya_fields(&raw).unwrap().is_empty());
+
+        raw["priority"] = serde_json::json!("high");
+        raw["v1_karta"]["nickname"] = serde_json::json!("al");
+        raw["v3_kriya"]["retries"] = serde_json::json!(3);
+        // Empty values are indistinguishable from omitted optional fields
+        raw["v2_karma"]["extra"] = serde_json::Value::Null;
+        // The body is free-form
+        raw["body"] = serde_json::json!({ "anything": true });
+
+        let mut unknown = unknown_vakya_fields(&raw).unwrap();
+        unknown.sort();
+        assert_eq!(unknown, vec!["priority", "v1_karta.nickname", "v3_kriya.retries"]);
+    }
+
+    #[test]
+    fn test_validate_json_deny_unknown_fields() {
+        let mut raw = raw_vakya();
+        raw["v7_adhikarana"]["sudo"] = serde_json::json!(true);
+
+        let (_, lenient) = VakyaValidator::new().validate_json(&raw).unwrap();
+        assert!(lenient.valid);
+
+        let (_, strict) = VakyaValidator::new().deny_unknown_fields().validate_json(&raw).unwrap();
+        assert!(!strict.valid);
+        assert_eq!(strict.errors.len(), 1);
+        assert_eq!(strict.errors[0].path, "v7_adhikarana.sudo");
+        assert_eq!(strict.errors[0].code, ValidationErrorCode::SchemaViolation);
+    }
+
+    #[test]
+    fn test_validate_reports_every_error() {
+        let mut vakya: Vakya = serde_json::from_value(raw_vakya()).unwrap();
+        vakya.v1_karta.pid = PrincipalId::new("");
+        vakya.v3_kriya.action = "read".to_string();
+        let now = chrono::Utc::now();
+        vakya.v7_adhikarana.ttl = Some(TtlConstraint {
+            expires_at: Timestamp(now + chrono::Duration::hours(1)),
+            max_duration_ms: None,
+            not_before: Some(Timestamp(now + chrono::Duration::minutes(5))),
+        });
+
+        let result = VakyaValidator::new().validate(&vakya);
+        assert!(!result.valid);
+        let codes: Vec<_> = result.errors.iter().map(|e| (e.path.as_str(), e.code)).collect();
+        assert_eq!(codes, vec![
+            ("v1_karta.pid", ValidationErrorCode::MissingRequired),
+            ("v3_kriya.action", ValidationErrorCode::InvalidFormat),
+            ("v7_adhikarana.ttl.not_before", ValidationErrorCode::NotYetValid),
+        ]);
+
+        // Other lanes may hold the VĀKYA until not_before
+        vakya.v7_adhikarana.approval_lane = ApprovalLane::Sync;
+        assert_eq!(VakyaValidator::new().validate(&vakya).errors.len(), 2);
+    }
 
     #[test]
     fn test_scope_pattern_literal() {
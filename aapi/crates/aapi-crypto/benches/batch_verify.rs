@@ -0,0 +1,92 @@
+//! Signature verification throughput: batch vs sequential
+//!
+//! Run with `cargo bench -p aapi-crypto --bench batch_verify`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use aapi_core::{
+    ActorType,
+    Adhikarana,
+    ApprovalLane,
+    CapabilityRef,
+    Karta,
+    Karma,
+    Kriya,
+    PrincipalId,
+    ResourceId,
+    Vakya,
+};
+use aapi_crypto::{KeyPurpose, KeyStore, SignedVakya, VakyaSigner, VakyaVerifier};
+
+const SIGNATURES: usize = 10_000;
+const SIGNING_KEYS: usize = 16;
+
+fn build_vakya(i: usize) -> Vakya {
+    Vakya::builder()
+        .karta(Karta {
+            pid: PrincipalId::new(format!("agent:{}", i % SIGNING_KEYS)),
+            role: None,
+            realm: None,
+            key_id: None,
+            actor_type: ActorType::Agent,
+            delegation_chain: vec![],
+        })
+        .karma(Karma {
+            rid: ResourceId::new(format!("file:/tmp/aapi/log/{}.json", i)),
+            kind: Some("file".to_string()),
+            ns: None,
+            version: None,
+            labels: std::collections::HashMap::new(),
+        })
+        .kriya(Kriya::new("file", "write"))
+        .adhikarana(Adhikarana {
+            cap: CapabilityRef::Reference { cap_ref: "cap:bench".to_string() },
+            policy_ref: None,
+            ttl: None,
+            budgets: vec![],
+            approval_lane: ApprovalLane::None,
+            scopes: vec![],
+            context: None,
+            delegation_chain_cid: None,
+            execution_constraints: None,
+            port_id: None,
+            required_phase: None,
+            required_role: None,
+        })
+        .build()
+        .expect("vakya build")
+}
+
+fn batch_verify(c: &mut Criterion) {
+    let key_store = KeyStore::new();
+    let keys: Vec<_> = (0..SIGNING_KEYS)
+        .map(|_| key_store.generate_key(KeyPurpose::VakyaSigning).expect("key"))
+        .collect();
+    let signer = VakyaSigner::new(key_store.clone());
+    let verifier = VakyaVerifier::new(key_store);
+
+    let signed: Vec<SignedVakya> = (0..SIGNATURES)
+        .map(|i| signer.sign(&build_vakya(i), &keys[i % SIGNING_KEYS]).expect("sign"))
+        .collect();
+
+    let mut group = c.benchmark_group("verify_signatures");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(SIGNATURES as u64));
+
+    group.bench_with_input(BenchmarkId::new("sequential", SIGNATURES), &signed, |b, signed| {
+        b.iter(|| {
+            signed.iter()
+                .map(|item| verifier.verify(black_box(item)).expect("verify"))
+                .filter(|result| result.valid)
+                .count()
+        })
+    });
+    group.bench_with_input(BenchmarkId::new("batch", SIGNATURES), &signed, |b, signed| {
+        b.iter(|| verifier.verify_batch(black_box(signed)).iter().filter(|result| result.valid).count())
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, batch_verify);
+criterion_main!(benches);
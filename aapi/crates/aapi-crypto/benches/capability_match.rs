@@ -0,0 +1,93 @@
+//! Action and resource matching for tokens with many patterns: compiled
+//! once per token vs split on every check
+//!
+//! Run with `cargo bench -p aapi-crypto --bench capability_match`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use aapi_core::PrincipalId;
+use aapi_crypto::{CapabilityToken, CapabilityTokenBuilder, CapabilityVerifier, GlobPattern, KeyPurpose, KeyStore};
+
+const PATTERNS: usize = 64;
+
+/// System allocator that counts allocations, to report them per check
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocations(f: impl Fn() -> bool) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    black_box(f());
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+/// Only the last action and resource pattern match the probe, so every
+/// pattern is tried
+const ACTION: &str = "svc63.orders.archive.read";
+const RESOURCE: &str = "org.team63.reports.q3";
+
+/// Each pattern parsed again for the check, as an uncached matcher must
+fn split_each_time(token: &CapabilityToken) -> bool {
+    token.actions.iter().any(|p| GlobPattern::new(p).matches(ACTION))
+        && token.resources.iter().any(|p| GlobPattern::new(p).matches(RESOURCE))
+}
+
+fn compiled(token: &CapabilityToken) -> bool {
+    token.allows_action(ACTION) && token.allows_resource(RESOURCE)
+}
+
+fn capability_match(c: &mut Criterion) {
+    let key_store = KeyStore::new();
+    let key_id = key_store.generate_key(KeyPurpose::CapabilitySigning).expect("key");
+    let key_pair = key_store.get_key(&key_id).expect("key pair");
+    let token = CapabilityTokenBuilder::new()
+        .issuer(PrincipalId::new("issuer:bench"))
+        .subject(PrincipalId::new("agent:bench"))
+        .actions((0..PATTERNS).map(|i| format!("svc{}.**.read", i)).collect())
+        .resources((0..PATTERNS).map(|i| format!("org.team{}.*.q3", i)).collect())
+        .ttl_seconds(3600)
+        .build_and_sign(&key_pair)
+        .expect("sign token");
+    let verifier = CapabilityVerifier::new(key_store);
+
+    assert!(compiled(&token) && split_each_time(&token));
+    println!(
+        "allocations per check with {} patterns each: split each time {}, compiled {}",
+        PATTERNS,
+        allocations(|| split_each_time(&token)),
+        allocations(|| compiled(&token)),
+    );
+
+    let mut group = c.benchmark_group("match_patterns");
+    group.bench_with_input(BenchmarkId::new("split_each_time", PATTERNS), &token, |b, token| {
+        b.iter(|| split_each_time(black_box(token)))
+    });
+    group.bench_with_input(BenchmarkId::new("compiled", PATTERNS), &token, |b, token| {
+        b.iter(|| compiled(black_box(token)))
+    });
+    group.finish();
+
+    c.bench_function("verify_access", |b| {
+        b.iter(|| verifier.verify_access(black_box(&token), ACTION, RESOURCE).expect("verify").allowed)
+    });
+}
+
+criterion_group!(benches, capability_match);
+criterion_main!(benches);
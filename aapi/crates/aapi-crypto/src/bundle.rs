@@ -0,0 +1,56 @@
+//! Self-contained evidence bundles
+//!
+//! An `ExportBundle` carries stored records together with everything needed
+//! to check them offline: an inclusion proof per record, a signed head for
+//! each tree the proofs lead to, and the gateway's public keys.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::keys::PublicKeyInfo;
+use crate::merkle::SignedTreeHead;
+
+/// Records, proofs, tree heads and keys for an external audit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportBundle {
+    /// Gateway that produced the bundle
+    pub gateway_id: String,
+    pub created_at: DateTime<Utc>,
+    /// VĀKYA records, logged in the `vakya` tree under their `vakya_hash`
+    pub vakyas: Vec<BundleEntry>,
+    /// Effect records, logged in the `effect` tree under their `id`
+    pub effects: Vec<BundleEntry>,
+    /// Signed receipts, logged in the `receipt` tree under their `vakya_hash`
+    pub receipts: Vec<BundleEntry>,
+    /// Signed tree heads keyed by tree type
+    pub tree_heads: BTreeMap<String, SignedTreeHead>,
+    /// Keys that signed the tree heads and receipts
+    pub public_keys: Vec<PublicKeyInfo>,
+}
+
+impl ExportBundle {
+    /// The bundled key with `key_id`
+    pub fn public_key(&self, key_id: &str) -> Option<&PublicKeyInfo> {
+        self.public_keys.iter().find(|key| key.key_id.0 == key_id)
+    }
+}
+
+/// A stored record and the proof of its inclusion in its tree
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleEntry {
+    /// Record as stored by the gateway
+    pub record: serde_json::Value,
+    pub leaf_index: u64,
+    /// Sibling hashes from the leaf up to the tree head's root
+    pub proof: Vec<ProofStep>,
+}
+
+/// One sibling on an inclusion path
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProofStep {
+    pub hash: String,
+    /// Whether the sibling sits to the right of the path
+    pub sibling_on_right: bool,
+}
@@ -3,6 +3,11 @@
 //! Implements Macaroon-style capability tokens with caveats for
 //! fine-grained, attenuable authorization.
 
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::net::IpAddr;
+use std::sync::{Arc, OnceLock, RwLock};
+
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use sha2::{Sha256, Digest};
@@ -10,7 +15,7 @@ use uuid::Uuid;
 
 use aapi_core::types::{Budget, PrincipalId, Timestamp};
 use crate::error::{CryptoError, CryptoResult};
-use crate::keys::{KeyId, KeyPair, KeyStore};
+use crate::keys::{KeyId, KeyPair, KeyPurpose, KeyStore, PublicKeyInfo};
 use crate::signing::sign_bytes;
 
 /// Capability token for authorization
@@ -60,6 +65,33 @@ pub struct CapabilityToken {
     pub key_id: KeyId,
     /// Signature over the token
     pub signature: String,
+    /// `actions` and `resources` compiled on first use
+    #[serde(skip)]
+    patterns: OnceLock<TokenPatterns>,
+}
+
+/// A token's action and resource patterns, compiled
+#[derive(Debug, Clone)]
+struct TokenPatterns {
+    actions: Vec<GlobPattern>,
+    resources: Vec<GlobPattern>,
+}
+
+impl TokenPatterns {
+    fn compile(actions: &[String], resources: &[String]) -> Self {
+        Self {
+            actions: actions.iter().map(|p| GlobPattern::new(p)).collect(),
+            resources: resources.iter().map(|p| GlobPattern::new(p)).collect(),
+        }
+    }
+
+    /// Whether these were compiled from exactly these patterns
+    fn compiled_from(&self, actions: &[String], resources: &[String]) -> bool {
+        let same = |compiled: &[GlobPattern], sources: &[String]| {
+            compiled.len() == sources.len() && compiled.iter().zip(sources).all(|(c, s)| c.as_str() == s)
+        };
+        same(&self.actions, actions) && same(&self.resources, resources)
+    }
 }
 
 impl CapabilityToken {
@@ -82,12 +114,25 @@ impl CapabilityToken {
 
     /// Check if an action is allowed by this token
     pub fn allows_action(&self, action: &str) -> bool {
-        self.actions.iter().any(|pattern| glob_match(pattern, action))
+        match self.patterns() {
+            Some(patterns) => patterns.actions.iter().any(|pattern| pattern.matches(action)),
+            None => self.actions.iter().any(|pattern| glob_match(pattern, action)),
+        }
     }
 
     /// Check if a resource is allowed by this token
     pub fn allows_resource(&self, resource: &str) -> bool {
-        self.resources.iter().any(|pattern| glob_match(pattern, resource))
+        match self.patterns() {
+            Some(patterns) => patterns.resources.iter().any(|pattern| pattern.matches(resource)),
+            None => self.resources.iter().any(|pattern| glob_match(pattern, resource)),
+        }
+    }
+
+    /// The compiled patterns, unless `actions` or `resources` were changed
+    /// since they were compiled
+    fn patterns(&self) -> Option<&TokenPatterns> {
+        let patterns = self.patterns.get_or_init(|| TokenPatterns::compile(&self.actions, &self.resources));
+        patterns.compiled_from(&self.actions, &self.resources).then_some(patterns)
     }
 
     /// Check if a namespace is allowed by this token
@@ -126,6 +171,55 @@ impl CapabilityToken {
     }
 }
 
+/// Recover a signed token from its inline VĀKYA form (`CapabilityRef::Inline`)
+///
+/// Fails when fields the signature covers, such as `key_id` or `issued_at`,
+/// are missing from the inline token.
+impl TryFrom<&aapi_core::CapabilityToken> for CapabilityToken {
+    type Error = CryptoError;
+
+    fn try_from(inline: &aapi_core::CapabilityToken) -> CryptoResult<Self> {
+        // Both forms share field names, so the JSON representation maps across
+        Ok(serde_json::from_value(serde_json::to_value(inline)?)?)
+    }
+}
+
+impl From<&CapabilityToken> for aapi_core::CapabilityToken {
+    fn from(token: &CapabilityToken) -> Self {
+        Self {
+            token_id: token.token_id.clone(),
+            version: token.version,
+            issuer: token.issuer.clone(),
+            subject: token.subject.clone(),
+            audience: token.audience.clone(),
+            actions: token.actions.clone(),
+            resources: token.resources.clone(),
+            namespaces: token.namespaces.clone(),
+            issued_at: Some(Timestamp(token.issued_at)),
+            not_before: token.not_before.map(Timestamp),
+            expires_at: Timestamp(token.expires_at),
+            budgets: token.budgets.clone(),
+            parent_token_id: token.parent_token_id.clone(),
+            delegation_depth: token.delegation_depth,
+            max_delegation_depth: token.max_delegation_depth,
+            key_id: Some(token.key_id.0.clone()),
+            signature: Some(token.signature.clone()),
+            caveats: token.caveats.iter().map(|caveat| aapi_core::Caveat {
+                caveat_type: match &caveat.caveat_type {
+                    // Custom types have no plain-string form and will not
+                    // convert back, so the inline token fails verification
+                    CaveatType::Custom(name) => name.clone(),
+                    other => serde_json::to_value(other).ok()
+                        .and_then(|v| v.as_str().map(str::to_string))
+                        .unwrap_or_default(),
+                },
+                value: caveat.value.clone(),
+                description: caveat.description.clone(),
+            }).collect(),
+        }
+    }
+}
+
 /// Caveat for capability attenuation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Caveat {
@@ -317,6 +411,7 @@ impl CapabilityTokenBuilder {
             max_delegation_depth: self.max_delegation_depth,
             key_id: key_pair.key_id.clone(),
             signature: String::new(),
+            patterns: OnceLock::new(),
         };
 
         // Sign the token
@@ -428,6 +523,7 @@ impl CapabilityIssuer {
             max_delegation_depth: parent.max_delegation_depth,
             key_id: key_pair.key_id.clone(),
             signature: String::new(),
+            patterns: OnceLock::new(),
         };
 
         // Sign the token
@@ -451,18 +547,150 @@ pub struct TokenAttenuation {
     pub audience: Option<String>,
 }
 
+/// Shared set of revoked capability token IDs
+///
+/// Clones share the same underlying set, so a revocation recorded through one
+/// handle is seen by every verifier holding another. Revoking with
+/// `include_delegations` also rejects every token delegated from the revoked
+/// one, however many hops down. A token only names its parent, so the list
+/// keeps the parent of each token whose signature verified and walks those
+/// links up the chain; a descendant is caught once the tokens between it and
+/// the revoked one have been seen.
+#[derive(Debug, Clone, Default)]
+pub struct RevocationList {
+    revoked: Arc<RwLock<HashMap<String, bool>>>,
+    /// Token ID -> parent token ID, for delegated tokens seen so far
+    lineage: Arc<RwLock<HashMap<String, String>>>,
+}
+
+impl RevocationList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Revoke a token, optionally along with the tokens delegated from it
+    ///
+    /// Revoking an already revoked token only ever widens it to its delegations.
+    pub fn revoke(&self, token_id: impl Into<String>, include_delegations: bool) {
+        let mut revoked = self.revoked.write().unwrap();
+        let entry = revoked.entry(token_id.into()).or_insert(false);
+        *entry |= include_delegations;
+    }
+
+    /// Whether the token ID itself has been revoked
+    pub fn is_revoked(&self, token_id: &str) -> bool {
+        self.revoked.read().unwrap().contains_key(token_id)
+    }
+
+    /// Record that `token_id` was delegated from `parent_token_id`
+    ///
+    /// Only call this for tokens whose signature verified. The first link
+    /// recorded for a token is kept. Returns whether the link is new.
+    pub fn record_delegation(&self, token_id: impl Into<String>, parent_token_id: impl Into<String>) -> bool {
+        match self.lineage.write().unwrap().entry(token_id.into()) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                entry.insert(parent_token_id.into());
+                true
+            }
+        }
+    }
+
+    /// Why a token is no longer usable, if it has been revoked directly or
+    /// through one of its ancestors
+    pub fn check(&self, token: &CapabilityToken) -> Option<String> {
+        let revoked = self.revoked.read().unwrap();
+        if revoked.contains_key(&token.token_id) {
+            return Some(format!("Token '{}' has been revoked", token.token_id));
+        }
+
+        // The signed delegation depth bounds the walk, so a cycle in the
+        // recorded links cannot loop
+        let lineage = self.lineage.read().unwrap();
+        let mut ancestor = token.parent_token_id.as_ref();
+        for _ in 0..token.delegation_depth.max(1) {
+            let Some(id) = ancestor else { break };
+            if revoked.get(id).copied().unwrap_or(false) {
+                return Some(format!(
+                    "Token '{}' was delegated from revoked token '{}'",
+                    token.token_id, id
+                ));
+            }
+            ancestor = lineage.get(id);
+        }
+        None
+    }
+
+    pub fn len(&self) -> usize {
+        self.revoked.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
 /// Capability token verifier
 pub struct CapabilityVerifier {
     key_store: KeyStore,
+    revocations: RevocationList,
+    max_delegation_depth: Option<u32>,
 }
 
 impl CapabilityVerifier {
     pub fn new(key_store: KeyStore) -> Self {
-        Self { key_store }
+        Self { key_store, revocations: RevocationList::new(), max_delegation_depth: None }
+    }
+
+    /// Reject tokens delegated more than `depth` times, whatever limit the
+    /// issuer set on the chain
+    pub fn with_max_delegation_depth(mut self, depth: u32) -> Self {
+        self.max_delegation_depth = Some(depth);
+        self
+    }
+
+    /// Check tokens against a shared revocation list
+    pub fn with_revocations(mut self, revocations: RevocationList) -> Self {
+        self.revocations = revocations;
+        self
+    }
+
+    /// Revocation list consulted on every verification
+    pub fn revocations(&self) -> &RevocationList {
+        &self.revocations
+    }
+
+    /// Verifier trusting a single Ed25519 public key (hex) under `key_id`
+    pub fn from_public_key_hex(key_id: KeyId, public_key_hex: &str) -> CryptoResult<Self> {
+        let info = PublicKeyInfo {
+            key_id,
+            public_key: public_key_hex.to_lowercase(),
+            algorithm: "Ed25519".to_string(),
+            created_at: Utc::now(),
+            expires_at: None,
+            purpose: KeyPurpose::CapabilitySigning,
+            principal: None,
+            realm: None,
+        };
+        // Reject malformed keys up front rather than on every verification
+        info.verifying_key()?;
+
+        let key_store = KeyStore::new();
+        key_store.store_public_key(info)?;
+        Ok(Self::new(key_store))
     }
 
     /// Verify a capability token
+    ///
+    /// No request context is available, so any caveat other than
+    /// `time_window` fails; use [`verify_with_context`](Self::verify_with_context)
+    /// at an enforcement point that can supply it.
     pub fn verify(&self, token: &CapabilityToken) -> CryptoResult<CapabilityVerification> {
+        self.verify_with_context(token, &CaveatContext::default())
+    }
+
+    /// Verify a capability token, evaluating its caveats against `context`
+    pub fn verify_with_context(&self, token: &CapabilityToken, context: &CaveatContext) -> CryptoResult<CapabilityVerification> {
         let mut verification = CapabilityVerification {
             valid: true,
             errors: vec![],
@@ -480,9 +708,30 @@ impl CapabilityVerifier {
             }
         }
 
+        if let Some(reason) = self.revocations.check(token) {
+            verification.valid = false;
+            verification.errors.push(reason);
+        }
+
+        if let Some(max) = self.max_delegation_depth {
+            if token.delegation_depth > max {
+                verification.valid = false;
+                verification.errors.push(format!(
+                    "Delegation depth {} exceeds the allowed maximum of {}",
+                    token.delegation_depth, max
+                ));
+            }
+        }
+
         // Verify signature
         match self.verify_signature(token) {
-            Ok(true) => {}
+            Ok(true) => {
+                // Signed, so its parent link can be trusted for later checks
+                // of tokens delegated from it
+                if let Some(parent) = &token.parent_token_id {
+                    self.revocations.record_delegation(token.token_id.clone(), parent.clone());
+                }
+            }
             Ok(false) => {
                 verification.valid = false;
                 verification.errors.push("Invalid signature".to_string());
@@ -493,6 +742,17 @@ impl CapabilityVerifier {
             }
         }
 
+        // Every caveat must hold; one that cannot be evaluated does not
+        for caveat in &token.caveats {
+            match check_caveat(caveat, Utc::now(), context) {
+                CaveatCheck::Satisfied => {}
+                CaveatCheck::Violated(reason) | CaveatCheck::Unsatisfied(reason) => {
+                    verification.valid = false;
+                    verification.errors.push(reason);
+                }
+            }
+        }
+
         // Check budgets
         for budget in &token.budgets {
             if budget.is_exhausted() {
@@ -527,7 +787,19 @@ impl CapabilityVerifier {
         action: &str,
         resource: &str,
     ) -> CryptoResult<AccessDecision> {
-        let verification = self.verify(token)?;
+        self.verify_access_with_context(token, action, resource, &CaveatContext::default())
+    }
+
+    /// [`verify_access`](Self::verify_access) with caveats evaluated
+    /// against `context`
+    pub fn verify_access_with_context(
+        &self,
+        token: &CapabilityToken,
+        action: &str,
+        resource: &str,
+        context: &CaveatContext,
+    ) -> CryptoResult<AccessDecision> {
+        let verification = self.verify_with_context(token, context)?;
         
         if !verification.valid {
             return Ok(AccessDecision {
@@ -573,52 +845,250 @@ pub struct AccessDecision {
     pub reason: String,
 }
 
-/// Simple glob matching for action/resource patterns
-fn glob_match(pattern: &str, value: &str) -> bool {
-    if pattern == "*" || pattern == "**" {
-        return true;
+/// Request context caveats are evaluated against
+///
+/// A caveat needing context that was not supplied fails, so an enforcement
+/// point only grants what it can actually check.
+#[derive(Debug, Clone, Default)]
+pub struct CaveatContext {
+    /// Address of the client making the request, for `ip_address`
+    pub client_ip: Option<IpAddr>,
+    /// Request headers by lowercase name, for `require_header`
+    pub headers: HashMap<String, String>,
+    /// Claims established about the caller, for `require_claim`
+    pub claims: HashMap<String, serde_json::Value>,
+    /// Caveat types the enforcement point checks itself, such as
+    /// `rate_limit`; these pass here
+    pub enforced: Vec<CaveatType>,
+}
+
+/// Outcome of evaluating a single caveat
+enum CaveatCheck {
+    Satisfied,
+    Violated(String),
+    /// Needs context (client IP, an external check, ...) that was not supplied
+    Unsatisfied(String),
+}
+
+/// Evaluate a caveat at `now` against `context`
+///
+/// - `time_window`: `{"start": <RFC 3339>, "end": <RFC 3339>}`, either
+///   bound optional
+/// - `ip_address`: an address or CIDR range, or a list of them
+/// - `require_header` / `require_claim`: a name, or `{"name", "value"}` to
+///   also require a value
+///
+/// `geo`, `rate_limit`, `third_party` and custom caveats hold only when
+/// listed in [`CaveatContext::enforced`].
+fn check_caveat(caveat: &Caveat, now: DateTime<Utc>, context: &CaveatContext) -> CaveatCheck {
+    if caveat.caveat_type != CaveatType::TimeWindow && context.enforced.contains(&caveat.caveat_type) {
+        return CaveatCheck::Satisfied;
     }
 
-    let pattern_parts: Vec<&str> = pattern.split('.').collect();
-    let value_parts: Vec<&str> = value.split('.').collect();
+    match caveat.caveat_type {
+        CaveatType::TimeWindow => {
+            let bound = |name: &str| -> Result<Option<DateTime<Utc>>, String> {
+                match caveat.value.get(name) {
+                    None | Some(serde_json::Value::Null) => Ok(None),
+                    Some(v) => v.as_str()
+                        .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+                        .map(|t| Some(t.with_timezone(&Utc)))
+                        .ok_or_else(|| format!("Malformed time_window caveat: invalid '{}'", name)),
+                }
+            };
+
+            let (start, end) = match (bound("start"), bound("end")) {
+                (Ok(start), Ok(end)) => (start, end),
+                (Err(e), _) | (_, Err(e)) => return CaveatCheck::Violated(e),
+            };
+
+            if start.is_some_and(|start| now < start) {
+                CaveatCheck::Violated("time_window caveat has not opened yet".to_string())
+            } else if end.is_some_and(|end| now >= end) {
+                CaveatCheck::Violated("time_window caveat has closed".to_string())
+            } else {
+                CaveatCheck::Satisfied
+            }
+        }
+        CaveatType::IpAddress => {
+            let Some(client_ip) = context.client_ip else {
+                return CaveatCheck::Unsatisfied("ip_address caveat requires the client address".to_string());
+            };
+            let ranges: Vec<&serde_json::Value> = match &caveat.value {
+                serde_json::Value::Array(ranges) => ranges.iter().collect(),
+                single => vec![single],
+            };
+
+            let mut allowed = false;
+            for range in ranges {
+                match range.as_str().ok_or_else(|| "expected a string".to_string()).and_then(|r| ip_in_range(client_ip, r)) {
+                    Ok(matched) => allowed |= matched,
+                    Err(e) => return CaveatCheck::Violated(format!("Malformed ip_address caveat: {}", e)),
+                }
+            }
+            if allowed {
+                CaveatCheck::Satisfied
+            } else {
+                CaveatCheck::Violated(format!("ip_address caveat does not allow {}", client_ip))
+            }
+        }
+        CaveatType::RequireHeader => match named_requirement(&caveat.value) {
+            Some((name, expected)) => {
+                let actual = context.headers.get(&name.to_ascii_lowercase());
+                match (actual, expected) {
+                    (None, _) => CaveatCheck::Unsatisfied(format!("require_header caveat needs header '{}'", name)),
+                    (Some(actual), Some(expected)) if expected.as_str() != Some(actual.as_str()) => {
+                        CaveatCheck::Violated(format!("Header '{}' does not have the value the caveat requires", name))
+                    }
+                    _ => CaveatCheck::Satisfied,
+                }
+            }
+            None => CaveatCheck::Violated("Malformed require_header caveat".to_string()),
+        },
+        CaveatType::RequireClaim => match named_requirement(&caveat.value) {
+            Some((name, expected)) => match (context.claims.get(name), expected) {
+                (None, _) => CaveatCheck::Unsatisfied(format!("require_claim caveat needs claim '{}'", name)),
+                (Some(actual), Some(expected)) if actual != expected => {
+                    CaveatCheck::Violated(format!("Claim '{}' does not have the value the caveat requires", name))
+                }
+                _ => CaveatCheck::Satisfied,
+            },
+            None => CaveatCheck::Violated("Malformed require_claim caveat".to_string()),
+        },
+        ref other => CaveatCheck::Unsatisfied(format!(
+            "Caveat {:?} cannot be checked by this verifier",
+            other
+        )),
+    }
+}
+
+/// Name and optional value of a `require_header`/`require_claim` caveat
+fn named_requirement(value: &serde_json::Value) -> Option<(&str, Option<&serde_json::Value>)> {
+    match value {
+        serde_json::Value::String(name) => Some((name, None)),
+        serde_json::Value::Object(fields) => {
+            let name = fields.get("name")?.as_str()?;
+            Some((name, fields.get("value").filter(|v| !v.is_null())))
+        }
+        _ => None,
+    }
+}
+
+/// Whether `ip` is `range`, an address or CIDR block
+///
+/// IPv4-mapped IPv6 clients are compared as IPv4.
+fn ip_in_range(ip: IpAddr, range: &str) -> Result<bool, String> {
+    let unmap = |ip: IpAddr| match ip {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(ip, IpAddr::V4),
+        v4 => v4,
+    };
+
+    let (addr, prefix) = match range.split_once('/') {
+        Some((addr, prefix)) => (addr, Some(prefix)),
+        None => (range, None),
+    };
+    let network: IpAddr = addr.parse().map_err(|_| format!("invalid address '{}'", addr))?;
+    let (network, ip) = (unmap(network), unmap(ip));
+
+    let (network, ip, bits) = match (network, ip) {
+        (IpAddr::V4(n), IpAddr::V4(i)) => (u32::from(n) as u128, u32::from(i) as u128, 32),
+        (IpAddr::V6(n), IpAddr::V6(i)) => (u128::from(n), u128::from(i), 128),
+        _ => return Ok(false),
+    };
+    let prefix = match prefix {
+        Some(prefix) => prefix.parse::<u32>().ok()
+            .filter(|p| *p <= bits)
+            .ok_or_else(|| format!("invalid prefix length in '{}'", range))?,
+        None => bits,
+    };
+
+    // Compare the top `prefix` bits of the family's width
+    let shift = bits - prefix;
+    Ok(shift >= bits || (network >> shift) == (ip >> shift))
+}
+
+/// Action or resource pattern, split into segments once so matching does
+/// not allocate
+///
+/// Segments are separated by `.`: `*` matches exactly one segment and `**`
+/// zero or more, except that trailing wildcards also match nothing. A
+/// pattern of just `*` or `**` matches any value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlobPattern {
+    source: String,
+    segments: Vec<GlobSegment>,
+}
 
-    glob_match_parts(&pattern_parts, &value_parts)
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum GlobSegment {
+    Literal(String),
+    One,
+    AnyDepth,
 }
 
-fn glob_match_parts(pattern: &[&str], value: &[&str]) -> bool {
-    if pattern.is_empty() {
-        return value.is_empty();
+impl GlobPattern {
+    pub fn new(pattern: &str) -> Self {
+        let segments = if pattern == "*" || pattern == "**" {
+            vec![GlobSegment::AnyDepth]
+        } else {
+            pattern.split('.')
+                .map(|segment| match segment {
+                    "*" => GlobSegment::One,
+                    "**" => GlobSegment::AnyDepth,
+                    literal => GlobSegment::Literal(literal.to_string()),
+                })
+                .collect()
+        };
+        Self { source: pattern.to_string(), segments }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
     }
 
-    if value.is_empty() {
-        return pattern.iter().all(|p| *p == "*" || *p == "**");
+    pub fn matches(&self, value: &str) -> bool {
+        match_segments(&self.segments, Some(value))
     }
+}
 
-    match pattern[0] {
-        "**" => {
+/// Match `pattern` against the segments of `value`; `None` means no segments
+/// are left, which differs from a trailing empty segment
+fn match_segments(pattern: &[GlobSegment], value: Option<&str>) -> bool {
+    let Some((first, rest)) = pattern.split_first() else {
+        return value.is_none();
+    };
+    let Some(value) = value else {
+        return pattern.iter().all(|segment| !matches!(segment, GlobSegment::Literal(_)));
+    };
+    let (head, tail) = match value.split_once('.') {
+        Some((head, tail)) => (head, Some(tail)),
+        None => (value, None),
+    };
+
+    match first {
+        GlobSegment::AnyDepth => {
             // Match zero or more segments
-            for i in 0..=value.len() {
-                if glob_match_parts(&pattern[1..], &value[i..]) {
+            let mut remaining = Some(value);
+            loop {
+                if match_segments(rest, remaining) {
                     return true;
                 }
-            }
-            false
-        }
-        "*" => {
-            // Match exactly one segment
-            glob_match_parts(&pattern[1..], &value[1..])
-        }
-        p => {
-            // Literal match
-            if p == value[0] {
-                glob_match_parts(&pattern[1..], &value[1..])
-            } else {
-                false
+                match remaining {
+                    Some(value) => remaining = value.split_once('.').map(|(_, tail)| tail),
+                    None => return false,
+                }
             }
         }
+        GlobSegment::One => match_segments(rest, tail),
+        GlobSegment::Literal(literal) => literal == head && match_segments(rest, tail),
     }
 }
 
+/// Match a pattern that is used only once, compiling it on the spot
+fn glob_match(pattern: &str, value: &str) -> bool {
+    GlobPattern::new(pattern).matches(value)
+}
+
 /// Merge budgets, taking the minimum of each resource type
 fn merge_budgets(parent: &[Budget], child: &[Budget]) -> Vec<Budget> {
     let mut result = parent.to_vec();
@@ -638,7 +1108,6 @@ fn merge_budgets(parent: &[Budget], child: &[Budget]) -> Vec<Budget> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::keys::KeyPurpose;
 
     #[test]
     fn test_capability_token_builder() {
@@ -672,6 +1141,63 @@ mod tests {
         assert!(glob_match("org.*.read", "org.team.read"));
     }
 
+    /// The original matcher, splitting both sides on every call
+    fn split_glob_match(pattern: &str, value: &str) -> bool {
+        fn parts(pattern: &[&str], value: &[&str]) -> bool {
+            match (pattern.split_first(), value.is_empty()) {
+                (None, _) => value.is_empty(),
+                (Some(_), true) => pattern.iter().all(|p| *p == "*" || *p == "**"),
+                (Some((&"**", rest)), false) => (0..=value.len()).any(|i| parts(rest, &value[i..])),
+                (Some((&"*", rest)), false) => parts(rest, &value[1..]),
+                (Some((literal, rest)), false) => *literal == value[0] && parts(rest, &value[1..]),
+            }
+        }
+        if pattern == "*" || pattern == "**" {
+            return true;
+        }
+        parts(&pattern.split('.').collect::<Vec<_>>(), &value.split('.').collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_compiled_glob_matches_like_splitting() {
+        let patterns = [
+            "*", "**", "file", "file.*", "file.**", "*.read", "**.read", "org.*.read", "org.**.read",
+            "org.**", "a.*.*", "a.**.b.**", "", ".", "a..b", "*.*", "file.read",
+        ];
+        let values = [
+            "", ".", "file", "file.read", "file.read.all", "org.read", "org.team.read", "org.a.b.read",
+            "a.b", "a.x.b", "a.x.b.y", "a..b", "database.read", "org..read",
+        ];
+        for pattern in patterns {
+            let compiled = GlobPattern::new(pattern);
+            for value in values {
+                assert_eq!(
+                    compiled.matches(value),
+                    split_glob_match(pattern, value),
+                    "{:?} against {:?}",
+                    pattern,
+                    value
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_changed_patterns_are_not_matched_stale() {
+        let key_pair = KeyPair::generate(KeyPurpose::CapabilitySigning);
+        let mut token = CapabilityTokenBuilder::new()
+            .issuer(PrincipalId::new("issuer:test"))
+            .subject(PrincipalId::new("subject:test"))
+            .action("file.read")
+            .resource("**")
+            .build_and_sign(&key_pair)
+            .unwrap();
+        assert!(!token.allows_action("file.write"));
+
+        token.actions.push("file.write".to_string());
+        assert!(token.allows_action("file.write"));
+    }
+
     #[test]
     fn test_token_verification() {
         let key_store = KeyStore::new();
@@ -692,6 +1218,117 @@ mod tests {
         assert!(result.valid);
     }
 
+    #[test]
+    fn test_revoked_tokens_fail_verification() {
+        let key_store = KeyStore::new();
+        let key_id = key_store.generate_key(KeyPurpose::CapabilitySigning).unwrap();
+        let key_pair = key_store.get_key(&key_id).unwrap();
+
+        let parent = CapabilityTokenBuilder::new()
+            .issuer(PrincipalId::new("issuer:test"))
+            .subject(PrincipalId::new("agent:parent"))
+            .action("file.read")
+            .resource("**")
+            .ttl_seconds(3600)
+            .max_delegation_depth(2)
+            .build_and_sign(&key_pair)
+            .unwrap();
+        let issuer = CapabilityIssuer::new(key_store.clone(), key_id, PrincipalId::new("issuer:test"));
+        let child = issuer
+            .attenuate(&parent, PrincipalId::new("agent:child"), TokenAttenuation::default())
+            .unwrap();
+
+        let revocations = RevocationList::new();
+        let verifier = CapabilityVerifier::new(key_store).with_revocations(revocations.clone());
+
+        revocations.revoke(parent.token_id.clone(), false);
+        assert!(!verifier.verify(&parent).unwrap().valid);
+        assert!(verifier.verify(&child).unwrap().valid);
+
+        revocations.revoke(parent.token_id.clone(), true);
+        let result = verifier.verify(&child).unwrap();
+        assert!(!result.valid);
+        assert!(result.errors[0].contains("delegated from revoked token"));
+        assert_eq!(revocations.len(), 1);
+    }
+
+    #[test]
+    fn test_revocation_reaches_every_delegation_hop() {
+        let key_store = KeyStore::new();
+        let key_id = key_store.generate_key(KeyPurpose::CapabilitySigning).unwrap();
+        let key_pair = key_store.get_key(&key_id).unwrap();
+
+        let root = CapabilityTokenBuilder::new()
+            .issuer(PrincipalId::new("issuer:test"))
+            .subject(PrincipalId::new("agent:root"))
+            .action("file.read")
+            .resource("**")
+            .ttl_seconds(3600)
+            .max_delegation_depth(3)
+            .build_and_sign(&key_pair)
+            .unwrap();
+        let issuer = CapabilityIssuer::new(key_store.clone(), key_id, PrincipalId::new("issuer:test"));
+        let child = issuer
+            .attenuate(&root, PrincipalId::new("agent:child"), TokenAttenuation::default())
+            .unwrap();
+        let grandchild = issuer
+            .attenuate(&child, PrincipalId::new("agent:grandchild"), TokenAttenuation::default())
+            .unwrap();
+
+        let revocations = RevocationList::new();
+        let verifier = CapabilityVerifier::new(key_store).with_revocations(revocations.clone());
+
+        // A forged token cannot reroute the child's recorded parent
+        let mut forged = child.clone();
+        forged.parent_token_id = Some("cap:unrelated".to_string());
+        assert!(!verifier.verify(&forged).unwrap().valid);
+
+        assert!(verifier.verify(&child).unwrap().valid);
+        assert!(verifier.verify(&grandchild).unwrap().valid);
+
+        revocations.revoke(root.token_id.clone(), true);
+        let result = verifier.verify(&grandchild).unwrap();
+        assert!(!result.valid);
+        assert!(result.errors[0].contains(&root.token_id));
+
+        // Links recorded elsewhere, e.g. reloaded after a restart, count too
+        let reloaded = RevocationList::new();
+        reloaded.revoke(root.token_id.clone(), true);
+        assert!(reloaded.check(&grandchild).is_none());
+        assert!(reloaded.record_delegation(child.token_id.clone(), root.token_id.clone()));
+        assert!(!reloaded.record_delegation(child.token_id.clone(), "cap:other"));
+        assert!(reloaded.check(&grandchild).is_some());
+    }
+
+    #[test]
+    fn test_verifier_caps_delegation_depth() {
+        let key_store = KeyStore::new();
+        let key_id = key_store.generate_key(KeyPurpose::CapabilitySigning).unwrap();
+        let key_pair = key_store.get_key(&key_id).unwrap();
+
+        // The issuer allows deep chains; the relying party does not
+        let root = CapabilityTokenBuilder::new()
+            .issuer(PrincipalId::new("issuer:test"))
+            .subject(PrincipalId::new("agent:root"))
+            .action("file.read")
+            .resource("**")
+            .ttl_seconds(3600)
+            .max_delegation_depth(5)
+            .build_and_sign(&key_pair)
+            .unwrap();
+        let issuer = CapabilityIssuer::new(key_store.clone(), key_id, PrincipalId::new("issuer:test"));
+        let child = issuer.attenuate(&root, PrincipalId::new("agent:child"), TokenAttenuation::default()).unwrap();
+        let grandchild = issuer.attenuate(&child, PrincipalId::new("agent:grandchild"), TokenAttenuation::default()).unwrap();
+
+        let verifier = CapabilityVerifier::new(key_store).with_max_delegation_depth(1);
+        assert!(verifier.verify(&root).unwrap().valid);
+        assert!(verifier.verify(&child).unwrap().valid);
+
+        let result = verifier.verify(&grandchild).unwrap();
+        assert!(!result.valid);
+        assert_eq!(result.errors, ["Delegation depth 2 exceeds the allowed maximum of 1"]);
+    }
+
     #[test]
     fn test_token_attenuation() {
         let key_store = KeyStore::new();
@@ -729,4 +1366,149 @@ mod tests {
         // Child should not have write permission (attenuated away)
         assert!(!child.actions.contains(&"file.write".to_string()));
     }
+
+    #[test]
+    fn test_verify_with_public_key_and_caveats() {
+        let key_pair = KeyPair::generate(KeyPurpose::CapabilitySigning);
+        let builder = || CapabilityTokenBuilder::new()
+            .issuer(PrincipalId::new("issuer:test"))
+            .subject(PrincipalId::new("agent:browser"))
+            .action("file.read")
+            .resource("**")
+            .ttl_seconds(3600);
+
+        let verifier = CapabilityVerifier::from_public_key_hex(
+            key_pair.key_id.clone(),
+            &key_pair.public_key_hex(),
+        ).unwrap();
+
+        let open = builder()
+            .caveat(Caveat {
+                caveat_type: CaveatType::TimeWindow,
+                value: serde_json::json!({"end": (Utc::now() + Duration::hours(1)).to_rfc3339()}),
+                description: None,
+            })
+            .caveat(Caveat {
+                caveat_type: CaveatType::IpAddress,
+                value: serde_json::json!("10.0.0.0/8"),
+                description: None,
+            })
+            .build_and_sign(&key_pair)
+            .unwrap();
+        let result = verifier.verify(&open).unwrap();
+        assert!(!result.valid);
+        assert_eq!(result.errors, vec!["ip_address caveat requires the client address".to_string()]);
+
+        let inside = CaveatContext { client_ip: Some("10.1.2.3".parse().unwrap()), ..Default::default() };
+        let result = verifier.verify_with_context(&open, &inside).unwrap();
+        assert!(result.valid, "{:?}", result.errors);
+        let outside = CaveatContext { client_ip: Some("192.168.1.5".parse().unwrap()), ..Default::default() };
+        assert!(!verifier.verify_with_context(&open, &outside).unwrap().valid);
+
+        let closed = builder()
+            .caveat(Caveat {
+                caveat_type: CaveatType::TimeWindow,
+                value: serde_json::json!({"end": (Utc::now() - Duration::hours(1)).to_rfc3339()}),
+                description: None,
+            })
+            .build_and_sign(&key_pair)
+            .unwrap();
+        let result = verifier.verify(&closed).unwrap();
+        assert!(!result.valid);
+        assert_eq!(result.errors, vec!["time_window caveat has closed".to_string()]);
+
+        let other_key = KeyPair::generate(KeyPurpose::CapabilitySigning);
+        let verifier = CapabilityVerifier::from_public_key_hex(
+            key_pair.key_id.clone(),
+            &other_key.public_key_hex(),
+        ).unwrap();
+        assert!(!verifier.verify(&open).unwrap().valid);
+
+        assert!(CapabilityVerifier::from_public_key_hex(key_pair.key_id, "abcd").is_err());
+    }
+
+    #[test]
+    fn test_caveats_fail_closed() {
+        let key_pair = KeyPair::generate(KeyPurpose::CapabilitySigning);
+        let verifier = CapabilityVerifier::from_public_key_hex(
+            key_pair.key_id.clone(),
+            &key_pair.public_key_hex(),
+        ).unwrap();
+        let token = |caveat_type: CaveatType, value: serde_json::Value| CapabilityTokenBuilder::new()
+            .issuer(PrincipalId::new("issuer:test"))
+            .subject(PrincipalId::new("agent:test"))
+            .action("file.read")
+            .resource("**")
+            .ttl_seconds(3600)
+            .caveat(Caveat { caveat_type, value, description: None })
+            .build_and_sign(&key_pair)
+            .unwrap();
+        let allowed = |token: &CapabilityToken, context: &CaveatContext| {
+            verifier.verify_access_with_context(token, "file.read", "file:/tmp/a", context).unwrap().allowed
+        };
+
+        // Without context, nothing but a time window passes
+        for (caveat_type, value) in [
+            (CaveatType::IpAddress, serde_json::json!(["10.0.0.0/8", "::1"])),
+            (CaveatType::RateLimit, serde_json::json!({"per_minute": 10})),
+            (CaveatType::RequireHeader, serde_json::json!("x-tenant")),
+            (CaveatType::RequireClaim, serde_json::json!({"name": "mfa", "value": true})),
+            (CaveatType::ThirdParty, serde_json::json!({"location": "https://auth.example"})),
+            (CaveatType::Custom("region".to_string()), serde_json::json!("eu")),
+        ] {
+            let restricted = token(caveat_type.clone(), value);
+            assert!(!verifier.verify_access(&restricted, "file.read", "file:/tmp/a").unwrap().allowed, "{:?}", caveat_type);
+        }
+
+        let by_ip = token(CaveatType::IpAddress, serde_json::json!(["10.0.0.0/8", "2001:db8::/32"]));
+        let from = |ip: &str| CaveatContext { client_ip: Some(ip.parse().unwrap()), ..Default::default() };
+        assert!(allowed(&by_ip, &from("10.200.0.1")));
+        assert!(allowed(&by_ip, &from("::ffff:10.0.0.7")));
+        assert!(allowed(&by_ip, &from("2001:db8::42")));
+        assert!(!allowed(&by_ip, &from("11.0.0.1")));
+        assert!(!allowed(&token(CaveatType::IpAddress, serde_json::json!("10.0.0.0/33")), &from("10.0.0.1")));
+
+        let by_header = token(CaveatType::RequireHeader, serde_json::json!({"name": "X-Tenant", "value": "acme"}));
+        let mut context = CaveatContext::default();
+        context.headers.insert("x-tenant".to_string(), "other".to_string());
+        assert!(!allowed(&by_header, &context));
+        context.headers.insert("x-tenant".to_string(), "acme".to_string());
+        assert!(allowed(&by_header, &context));
+
+        let by_claim = token(CaveatType::RequireClaim, serde_json::json!({"name": "mfa", "value": true}));
+        context.claims.insert("mfa".to_string(), serde_json::json!(true));
+        assert!(allowed(&by_claim, &context));
+
+        let rate_limited = token(CaveatType::RateLimit, serde_json::json!({"per_minute": 10}));
+        assert!(!allowed(&rate_limited, &context));
+        context.enforced.push(CaveatType::RateLimit);
+        assert!(allowed(&rate_limited, &context));
+    }
+
+    #[test]
+    fn test_inline_token_round_trip() {
+        let key_pair = KeyPair::generate(KeyPurpose::CapabilitySigning);
+        let token = CapabilityTokenBuilder::new()
+            .issuer(PrincipalId::new("issuer:test"))
+            .subject(PrincipalId::new("agent:inline"))
+            .action("file.read")
+            .resource("file:/tmp/**")
+            .namespace("team-a")
+            .caveat(Caveat {
+                caveat_type: CaveatType::IpAddress,
+                value: serde_json::json!("10.0.0.0/8"),
+                description: Some("internal only".to_string()),
+            })
+            .build_and_sign(&key_pair)
+            .unwrap();
+
+        let inline = aapi_core::CapabilityToken::from(&token);
+        let recovered = CapabilityToken::try_from(&inline).unwrap();
+        assert_eq!(recovered.canonical_bytes().unwrap(), token.canonical_bytes().unwrap());
+        assert_eq!(recovered.signature, token.signature);
+
+        let mut unsigned = inline;
+        unsigned.key_id = None;
+        assert!(CapabilityToken::try_from(&unsigned).is_err());
+    }
 }
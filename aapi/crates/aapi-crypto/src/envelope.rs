@@ -0,0 +1,168 @@
+//! Envelope encryption for data stored at rest
+//!
+//! Each record is encrypted under its own AES-256-GCM data key. The data key
+//! is wrapped by a long-lived master key and stored beside the ciphertext, so
+//! the master key itself never touches the stored data.
+
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{CryptoError, CryptoResult};
+
+/// Length of master and data keys in bytes
+pub const ENVELOPE_KEY_LENGTH: usize = 32;
+
+const NONCE_LENGTH: usize = 12;
+
+/// Master key that wraps per-record data keys
+#[derive(Clone)]
+pub struct MasterKey {
+    key_id: String,
+    key: [u8; ENVELOPE_KEY_LENGTH],
+}
+
+impl std::fmt::Debug for MasterKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MasterKey")
+            .field("key_id", &self.key_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl MasterKey {
+    pub fn new(key_id: impl Into<String>, key: [u8; ENVELOPE_KEY_LENGTH]) -> Self {
+        Self { key_id: key_id.into(), key }
+    }
+
+    /// Generate a random master key
+    pub fn generate(key_id: impl Into<String>) -> Self {
+        let mut key = [0u8; ENVELOPE_KEY_LENGTH];
+        OsRng.fill_bytes(&mut key);
+        Self::new(key_id, key)
+    }
+
+    /// Import a 32-byte master key encoded as standard base64
+    pub fn from_base64(key_id: impl Into<String>, encoded: &str) -> CryptoResult<Self> {
+        let bytes = STANDARD.decode(encoded.trim())?;
+        let key: [u8; ENVELOPE_KEY_LENGTH] = bytes.try_into().map_err(|_| {
+            CryptoError::InvalidKeyFormat(format!("Master key must be {} bytes", ENVELOPE_KEY_LENGTH))
+        })?;
+        Ok(Self::new(key_id, key))
+    }
+
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    /// Create a fresh data key along with its wrapped form for storage
+    pub fn new_data_key(&self) -> CryptoResult<(DataKey, WrappedKey)> {
+        let mut key = [0u8; ENVELOPE_KEY_LENGTH];
+        OsRng.fill_bytes(&mut key);
+        let wrapped = WrappedKey {
+            kid: self.key_id.clone(),
+            key: seal(&self.key, &key, self.key_id.as_bytes())?,
+        };
+        Ok((DataKey(key), wrapped))
+    }
+
+    /// Recover a data key wrapped by this master key
+    pub fn unwrap_key(&self, wrapped: &WrappedKey) -> CryptoResult<DataKey> {
+        if wrapped.kid != self.key_id {
+            return Err(CryptoError::KeyNotFound(wrapped.kid.clone()));
+        }
+        let bytes = open(&self.key, &wrapped.key, self.key_id.as_bytes())?;
+        let key: [u8; ENVELOPE_KEY_LENGTH] = bytes.try_into()
+            .map_err(|_| CryptoError::DecryptionFailed("Wrapped data key has the wrong length".to_string()))?;
+        Ok(DataKey(key))
+    }
+}
+
+/// A data key encrypted under a master key
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WrappedKey {
+    /// ID of the master key that wrapped it
+    pub kid: String,
+    /// Base64 of nonce || ciphertext
+    pub key: String,
+}
+
+/// Per-record data key
+pub struct DataKey([u8; ENVELOPE_KEY_LENGTH]);
+
+impl DataKey {
+    /// Encrypt `plaintext` bound to `aad`; returns base64 of nonce || ciphertext
+    pub fn seal(&self, plaintext: &[u8], aad: &[u8]) -> CryptoResult<String> {
+        seal(&self.0, plaintext, aad)
+    }
+
+    /// Decrypt the output of `seal`; fails if it or `aad` was altered
+    pub fn open(&self, sealed: &str, aad: &[u8]) -> CryptoResult<Vec<u8>> {
+        open(&self.0, sealed, aad)
+    }
+}
+
+fn seal(key: &[u8; ENVELOPE_KEY_LENGTH], plaintext: &[u8], aad: &[u8]) -> CryptoResult<String> {
+    let cipher = Aes256Gcm::new(key.into());
+    let mut nonce = [0u8; NONCE_LENGTH];
+    OsRng.fill_bytes(&mut nonce);
+    let ciphertext = cipher.encrypt(Nonce::from_slice(&nonce), Payload { msg: plaintext, aad })
+        .map_err(|e| CryptoError::EncryptionFailed(e.to_string()))?;
+
+    let mut out = nonce.to_vec();
+    out.extend_from_slice(&ciphertext);
+    Ok(STANDARD.encode(out))
+}
+
+fn open(key: &[u8; ENVELOPE_KEY_LENGTH], sealed: &str, aad: &[u8]) -> CryptoResult<Vec<u8>> {
+    let bytes = STANDARD.decode(sealed)?;
+    if bytes.len() < NONCE_LENGTH {
+        return Err(CryptoError::DecryptionFailed("Ciphertext is truncated".to_string()));
+    }
+    let (nonce, ciphertext) = bytes.split_at(NONCE_LENGTH);
+    Aes256Gcm::new(key.into())
+        .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad })
+        .map_err(|e| CryptoError::DecryptionFailed(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_envelope_roundtrip() {
+        let master = MasterKey::generate("kek-1");
+        let (data_key, wrapped) = master.new_data_key().unwrap();
+        let sealed = data_key.seal(b"ssn=123-45-6789", b"effect-1:before_state").unwrap();
+        assert!(!sealed.contains("6789"));
+
+        let recovered = master.unwrap_key(&wrapped).unwrap();
+        assert_eq!(recovered.open(&sealed, b"effect-1:before_state").unwrap(), b"ssn=123-45-6789");
+
+        // Ciphertext is bound to its associated data
+        assert!(matches!(
+            recovered.open(&sealed, b"effect-2:before_state"),
+            Err(CryptoError::DecryptionFailed(_))
+        ));
+
+        // Only the wrapping master key can recover the data key
+        let other = MasterKey::new("kek-1", [7u8; ENVELOPE_KEY_LENGTH]);
+        assert!(matches!(other.unwrap_key(&wrapped), Err(CryptoError::DecryptionFailed(_))));
+        let rotated = MasterKey::generate("kek-2");
+        assert!(matches!(rotated.unwrap_key(&wrapped), Err(CryptoError::KeyNotFound(_))));
+    }
+
+    #[test]
+    fn test_master_key_from_base64() {
+        let encoded = STANDARD.encode([1u8; ENVELOPE_KEY_LENGTH]);
+        let master = MasterKey::from_base64("kek", &encoded).unwrap();
+        assert_eq!(master.key_id(), "kek");
+        assert!(!format!("{:?}", master).contains("key:"));
+
+        let short = STANDARD.encode([1u8; 16]);
+        assert!(matches!(MasterKey::from_base64("kek", &short), Err(CryptoError::InvalidKeyFormat(_))));
+    }
+}
@@ -41,6 +41,12 @@ pub enum CryptoError {
     #[error("Scope violation: {0}")]
     ScopeViolation(String),
 
+    #[error("Encryption failed: {0}")]
+    EncryptionFailed(String),
+
+    #[error("Decryption failed: {0}")]
+    DecryptionFailed(String),
+
     #[error("Caveat validation failed: {0}")]
     CaveatFailed(String),
 
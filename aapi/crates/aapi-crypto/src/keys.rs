@@ -1,14 +1,17 @@
 //! Key management for AAPI
 //!
-//! Provides Ed25519 key generation, storage, and retrieval.
+//! Provides Ed25519 key generation, import, storage, and retrieval.
 
-use ed25519_dalek::{SigningKey, VerifyingKey, SECRET_KEY_LENGTH};
+use ed25519_dalek::pkcs8::DecodePrivateKey;
+use ed25519_dalek::{SigningKey, VerifyingKey, PUBLIC_KEY_LENGTH, SECRET_KEY_LENGTH};
 use rand::rngs::OsRng;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 
 use crate::error::{CryptoError, CryptoResult};
+use crate::signing::SignatureAlgorithm;
 
 /// Key identifier
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -22,6 +25,39 @@ impl KeyId {
     pub fn generate() -> Self {
         Self(uuid::Uuid::new_v4().to_string())
     }
+
+    /// Derive a stable key ID from an Ed25519 public key
+    pub fn from_public_key(public_key: &[u8; PUBLIC_KEY_LENGTH]) -> Self {
+        let digest = Sha256::digest(public_key);
+        Self(format!("ed25519:{}", hex::encode(&digest[..16])))
+    }
+}
+
+/// Encoding of an externally generated Ed25519 secret key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecretKeyEncoding {
+    /// PKCS#8 `PRIVATE KEY` PEM document
+    Pem,
+    /// 32-byte seed as hex
+    Hex,
+    /// 32-byte seed as standard base64
+    Base64,
+}
+
+impl std::str::FromStr for SecretKeyEncoding {
+    type Err = CryptoError;
+
+    fn from_str(s: &str) -> CryptoResult<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "pem" | "pkcs8" => Ok(Self::Pem),
+            "hex" => Ok(Self::Hex),
+            "base64" | "b64" => Ok(Self::Base64),
+            other => Err(CryptoError::InvalidKeyFormat(format!(
+                "Unknown secret key encoding '{}' (expected pem, hex or base64)",
+                other
+            ))),
+        }
+    }
 }
 
 impl std::fmt::Display for KeyId {
@@ -35,6 +71,8 @@ impl std::fmt::Display for KeyId {
 pub struct KeyPair {
     /// Unique key identifier
     pub key_id: KeyId,
+    /// Algorithm the key signs with
+    pub algorithm: SignatureAlgorithm,
     /// Ed25519 signing key (private)
     signing_key: SigningKey,
     /// Key creation timestamp
@@ -45,6 +83,8 @@ pub struct KeyPair {
     pub purpose: KeyPurpose,
     /// Associated principal
     pub principal: Option<String>,
+    /// Realm the key signs for
+    pub realm: Option<String>,
 }
 
 impl KeyPair {
@@ -53,11 +93,13 @@ impl KeyPair {
         let signing_key = SigningKey::generate(&mut OsRng);
         Self {
             key_id: KeyId::generate(),
+            algorithm: SignatureAlgorithm::Ed25519,
             signing_key,
             created_at: chrono::Utc::now(),
             expires_at: None,
             purpose,
             principal: None,
+            realm: None,
         }
     }
 
@@ -66,11 +108,13 @@ impl KeyPair {
         let signing_key = SigningKey::generate(&mut OsRng);
         Self {
             key_id,
+            algorithm: SignatureAlgorithm::Ed25519,
             signing_key,
             created_at: chrono::Utc::now(),
             expires_at: None,
             purpose,
             principal: None,
+            realm: None,
         }
     }
 
@@ -89,14 +133,53 @@ impl KeyPair {
         
         Ok(Self {
             key_id,
+            algorithm: SignatureAlgorithm::Ed25519,
             signing_key,
             created_at: chrono::Utc::now(),
             expires_at: None,
             purpose,
             principal: None,
+            realm: None,
         })
     }
 
+    /// Import a secret key exported by another system (e.g. an HSM)
+    ///
+    /// The key ID is derived from the public key, so importing the same
+    /// secret twice yields the same ID.
+    pub fn import_secret(input: &str, encoding: SecretKeyEncoding, purpose: KeyPurpose) -> CryptoResult<Self> {
+        let input = input.trim();
+        let bytes = match encoding {
+            SecretKeyEncoding::Pem => SigningKey::from_pkcs8_pem(input)
+                .map_err(|e| CryptoError::InvalidKeyFormat(format!(
+                    "Not a PKCS#8 Ed25519 private key PEM: {}",
+                    e
+                )))?
+                .to_bytes()
+                .to_vec(),
+            SecretKeyEncoding::Hex => hex::decode(input.strip_prefix("0x").unwrap_or(input))
+                .map_err(|e| CryptoError::InvalidKeyFormat(format!("Invalid hex secret key: {}", e)))?,
+            SecretKeyEncoding::Base64 => {
+                use base64::Engine;
+                base64::engine::general_purpose::STANDARD.decode(input)
+                    .map_err(|e| CryptoError::InvalidKeyFormat(format!("Invalid base64 secret key: {}", e)))?
+            }
+        };
+
+        if bytes.len() != SECRET_KEY_LENGTH {
+            return Err(CryptoError::InvalidKeyFormat(format!(
+                "Ed25519 secret key must be {} bytes, got {}",
+                SECRET_KEY_LENGTH,
+                bytes.len()
+            )));
+        }
+
+        let mut seed = [0u8; SECRET_KEY_LENGTH];
+        seed.copy_from_slice(&bytes);
+        let key_id = KeyId::from_public_key(SigningKey::from_bytes(&seed).verifying_key().as_bytes());
+        Self::from_secret_bytes(key_id, &seed, purpose)
+    }
+
     /// Get the signing key
     pub fn signing_key(&self) -> &SigningKey {
         &self.signing_key
@@ -144,16 +227,23 @@ impl KeyPair {
         self
     }
 
+    /// Set realm
+    pub fn with_realm(mut self, realm: impl Into<String>) -> Self {
+        self.realm = Some(realm.into());
+        self
+    }
+
     /// Export as PublicKeyInfo for sharing
     pub fn to_public_info(&self) -> PublicKeyInfo {
         PublicKeyInfo {
             key_id: self.key_id.clone(),
             public_key: self.public_key_hex(),
-            algorithm: "Ed25519".to_string(),
+            algorithm: self.algorithm.name().to_string(),
             created_at: self.created_at,
             expires_at: self.expires_at,
             purpose: self.purpose,
             principal: self.principal.clone(),
+            realm: self.realm.clone(),
         }
     }
 }
@@ -182,9 +272,16 @@ pub struct PublicKeyInfo {
     pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
     pub purpose: KeyPurpose,
     pub principal: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub realm: Option<String>,
 }
 
 impl PublicKeyInfo {
+    /// Algorithm named by `algorithm`
+    pub fn signature_algorithm(&self) -> CryptoResult<SignatureAlgorithm> {
+        self.algorithm.parse()
+    }
+
     /// Parse public key bytes
     pub fn public_key_bytes(&self) -> CryptoResult<[u8; 32]> {
         let bytes = hex::decode(&self.public_key)?;
@@ -204,6 +301,67 @@ impl PublicKeyInfo {
         VerifyingKey::from_bytes(&bytes)
             .map_err(|e| CryptoError::InvalidKeyFormat(e.to_string()))
     }
+
+    /// Express the key as an OKP/Ed25519 JSON Web Key (RFC 8037)
+    pub fn to_jwk(&self) -> CryptoResult<Jwk> {
+        use base64::Engine;
+
+        if self.algorithm != "Ed25519" {
+            return Err(CryptoError::InvalidKeyFormat(format!(
+                "Unsupported JWK algorithm: {}",
+                self.algorithm
+            )));
+        }
+
+        Ok(Jwk {
+            kty: "OKP".to_string(),
+            crv: "Ed25519".to_string(),
+            x: base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(self.public_key_bytes()?),
+            kid: self.key_id.0.clone(),
+            alg: "EdDSA".to_string(),
+            key_use: "sig".to_string(),
+            purpose: self.purpose,
+            exp: self.expires_at.map(|t| t.timestamp()),
+        })
+    }
+}
+
+/// JSON Web Key for an Ed25519 public key
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Jwk {
+    /// Key type, always `OKP`
+    pub kty: String,
+    /// Curve, always `Ed25519`
+    pub crv: String,
+    /// Base64url-encoded public key
+    pub x: String,
+    /// Key ID
+    pub kid: String,
+    /// Signature algorithm, always `EdDSA`
+    pub alg: String,
+    /// Public key use, always `sig`
+    #[serde(rename = "use")]
+    pub key_use: String,
+    /// What the gateway signs with this key
+    pub purpose: KeyPurpose,
+    /// Expiry as seconds since the epoch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exp: Option<i64>,
+}
+
+/// JWKS document (RFC 7517 §5)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JwkSet {
+    pub keys: Vec<Jwk>,
+}
+
+impl JwkSet {
+    /// Build a key set, skipping keys that cannot be expressed as JWKs
+    pub fn from_public_keys(infos: &[PublicKeyInfo]) -> Self {
+        Self {
+            keys: infos.iter().filter_map(|info| info.to_jwk().ok()).collect(),
+        }
+    }
 }
 
 /// In-memory key store
@@ -326,8 +484,14 @@ impl KeyStore {
         Ok(keys.keys().cloned().collect())
     }
 
-    /// List all public key infos
+    /// List all public key infos, oldest first, ties broken by key ID
     pub fn list_public_keys(&self) -> CryptoResult<Vec<PublicKeyInfo>> {
+        self.list_public_keys_filtered(&KeyFilter::default())
+    }
+
+    /// List the public key infos matching `filter`, oldest first, ties
+    /// broken by key ID
+    pub fn list_public_keys_filtered(&self, filter: &KeyFilter) -> CryptoResult<Vec<PublicKeyInfo>> {
         let keys = self.keys.read().map_err(|_| {
             CryptoError::KeyNotFound("Failed to acquire lock".to_string())
         })?;
@@ -338,18 +502,44 @@ impl KeyStore {
         
         let mut result: Vec<PublicKeyInfo> = keys.values()
             .map(|kp| kp.to_public_info())
+            .filter(|info| filter.matches(info))
             .collect();
         
         for (key_id, info) in public_keys.iter() {
-            if !keys.contains_key(key_id) {
+            if !keys.contains_key(key_id) && filter.matches(info) {
                 result.push(info.clone());
             }
         }
         
+        result.sort_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.key_id.0.cmp(&b.key_id.0)));
         Ok(result)
     }
 }
 
+/// Criteria for listing keys; unset fields match every key
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyFilter {
+    pub purpose: Option<KeyPurpose>,
+    pub principal: Option<String>,
+}
+
+impl KeyFilter {
+    pub fn purpose(mut self, purpose: KeyPurpose) -> Self {
+        self.purpose = Some(purpose);
+        self
+    }
+
+    pub fn principal(mut self, principal: impl Into<String>) -> Self {
+        self.principal = Some(principal.into());
+        self
+    }
+
+    pub fn matches(&self, info: &PublicKeyInfo) -> bool {
+        self.purpose.map_or(true, |purpose| info.purpose == purpose)
+            && self.principal.as_ref().map_or(true, |principal| info.principal.as_ref() == Some(principal))
+    }
+}
+
 impl Clone for KeyStore {
     fn clone(&self) -> Self {
         Self {
@@ -402,6 +592,34 @@ mod tests {
         assert!(store.get_key(&key_id).is_err());
     }
 
+    #[test]
+    fn test_list_public_keys_sorted_and_filtered() {
+        let store = KeyStore::new();
+        let now = chrono::Utc::now();
+        let add = |purpose, principal: &str, age_secs| {
+            let mut key_pair = KeyPair::generate(purpose).with_principal(principal);
+            key_pair.created_at = now - chrono::Duration::seconds(age_secs);
+            let key_id = key_pair.key_id.clone();
+            store.store_key(key_pair).unwrap();
+            key_id
+        };
+        let newest = add(KeyPurpose::CapabilitySigning, "user:alice", 0);
+        let oldest = add(KeyPurpose::VakyaSigning, "user:alice", 20);
+        let middle = add(KeyPurpose::CapabilitySigning, "user:bob", 10);
+
+        let ids = |filter: KeyFilter| -> Vec<KeyId> {
+            store.list_public_keys_filtered(&filter).unwrap().into_iter().map(|info| info.key_id).collect()
+        };
+        assert_eq!(ids(KeyFilter::default()), vec![oldest.clone(), middle.clone(), newest.clone()]);
+        assert_eq!(ids(KeyFilter::default().purpose(KeyPurpose::CapabilitySigning)), vec![middle, newest.clone()]);
+        assert_eq!(ids(KeyFilter::default().principal("user:alice")), vec![oldest, newest.clone()]);
+        assert_eq!(
+            ids(KeyFilter::default().purpose(KeyPurpose::CapabilitySigning).principal("user:alice")),
+            vec![newest]
+        );
+        assert!(ids(KeyFilter::default().principal("user:carol")).is_empty());
+    }
+
     #[test]
     fn test_public_key_export() {
         let key_pair = KeyPair::generate(KeyPurpose::General);
@@ -411,4 +629,65 @@ mod tests {
         assert_eq!(hex.len(), 64); // 32 bytes = 64 hex chars
         assert!(!base64.is_empty());
     }
+
+    #[test]
+    fn test_public_key_jwk() {
+        use base64::Engine;
+
+        let key_pair = KeyPair::generate(KeyPurpose::ReceiptSigning);
+        let info = key_pair.to_public_info();
+        let jwk = info.to_jwk().unwrap();
+        assert_eq!((jwk.kty.as_str(), jwk.crv.as_str(), jwk.alg.as_str()), ("OKP", "Ed25519", "EdDSA"));
+        assert_eq!(jwk.kid, info.key_id.0);
+        let x = base64::engine::general_purpose::URL_SAFE_NO_PAD.decode(&jwk.x).unwrap();
+        assert_eq!(x, info.public_key_bytes().unwrap());
+
+        let json = serde_json::to_value(&jwk).unwrap();
+        assert_eq!(json["use"], "sig");
+        assert_eq!(json["purpose"], "receipt_signing");
+        assert!(json.get("exp").is_none());
+
+        let mut other = info.clone();
+        other.algorithm = "RSA".to_string();
+        assert_eq!(JwkSet::from_public_keys(&[info, other]).keys, vec![jwk]);
+    }
+
+    #[test]
+    fn test_import_secret_encodings() {
+        use base64::Engine;
+        use ed25519_dalek::pkcs8::{spki::der::pem::LineEnding, EncodePrivateKey};
+
+        let original = KeyPair::generate(KeyPurpose::CapabilitySigning);
+        let seed = original.signing_key().to_bytes();
+        let pem = original.signing_key().to_pkcs8_pem(LineEnding::LF).unwrap();
+
+        let inputs = [
+            (pem.to_string(), SecretKeyEncoding::Pem),
+            (hex::encode(seed), SecretKeyEncoding::Hex),
+            (format!("0x{}\n", hex::encode(seed)), SecretKeyEncoding::Hex),
+            (base64::engine::general_purpose::STANDARD.encode(seed), SecretKeyEncoding::Base64),
+        ];
+
+        let expected_id = KeyId::from_public_key(&original.public_key_bytes());
+        for (input, encoding) in inputs {
+            let imported = KeyPair::import_secret(&input, encoding, KeyPurpose::CapabilitySigning).unwrap();
+            assert_eq!(imported.public_key_bytes(), original.public_key_bytes());
+            assert_eq!(imported.key_id, expected_id);
+        }
+    }
+
+    #[test]
+    fn test_import_secret_rejects_malformed_input() {
+        let short = KeyPair::import_secret("abcd", SecretKeyEncoding::Hex, KeyPurpose::General);
+        assert!(matches!(short, Err(CryptoError::InvalidKeyFormat(ref m)) if m.contains("got 2")));
+
+        let not_hex = KeyPair::import_secret("zz", SecretKeyEncoding::Hex, KeyPurpose::General);
+        assert!(matches!(not_hex, Err(CryptoError::InvalidKeyFormat(_))));
+
+        let not_pem = KeyPair::import_secret("-----BEGIN NOTHING-----", SecretKeyEncoding::Pem, KeyPurpose::General);
+        assert!(matches!(not_pem, Err(CryptoError::InvalidKeyFormat(_))));
+
+        assert!("der".parse::<SecretKeyEncoding>().is_err());
+        assert_eq!("PEM".parse::<SecretKeyEncoding>().unwrap(), SecretKeyEncoding::Pem);
+    }
 }
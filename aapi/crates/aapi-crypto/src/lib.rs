@@ -5,15 +5,23 @@
 //! - Capability token creation and verification
 //! - DSSE (Dead Simple Signing Envelope) support
 //! - Merkle proof generation and verification
+//! - Evidence bundles for offline audit
+//! - Envelope encryption for data at rest
 
 pub mod keys;
 pub mod signing;
 pub mod capability;
 pub mod dsse;
+pub mod merkle;
+pub mod bundle;
+pub mod envelope;
 pub mod error;
 
 pub use keys::*;
 pub use signing::*;
 pub use capability::*;
 pub use dsse::*;
+pub use merkle::*;
+pub use bundle::*;
+pub use envelope::*;
 pub use error::*;
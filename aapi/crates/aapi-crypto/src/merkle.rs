@@ -0,0 +1,376 @@
+//! RFC 6962 Merkle tree hashing and consistency proofs
+//!
+//! These are the primitives shared by the IndexDB transparency log (which
+//! generates proofs) and by clients (which verify them), so both sides hash
+//! and walk the tree identically. Leaf and proof hashes are lowercase hex
+//! strings; roots are labeled with their hash algorithm (`blake3:<hex>`),
+//! with SHA-256 roots left bare so existing tree heads keep verifying.
+
+use aapi_core::types::HashAlgorithm;
+
+use crate::error::{CryptoError, CryptoResult};
+use crate::keys::{KeyPair, PublicKeyInfo};
+use crate::signing::{sign_bytes, verify_bytes};
+
+/// Hash a leaf: `SHA256(0x00 || data)`
+pub fn merkle_leaf_hash(data: &[u8]) -> String {
+    merkle_leaf_hash_with(HashAlgorithm::Sha256, data)
+}
+
+/// Hash a leaf with the given algorithm: `H(0x00 || data)`
+pub fn merkle_leaf_hash_with(algorithm: HashAlgorithm, data: &[u8]) -> String {
+    let mut hasher = algorithm.hasher();
+    hasher.update(&[0x00]);
+    hasher.update(data);
+    hasher.finalize_hex()
+}
+
+/// Hash an internal node: `SHA256(0x01 || left || right)`
+pub fn merkle_node_hash(left: &str, right: &str) -> String {
+    merkle_node_hash_with(HashAlgorithm::Sha256, left, right)
+}
+
+/// Hash an internal node with the given algorithm: `H(0x01 || left || right)`
+pub fn merkle_node_hash_with(algorithm: HashAlgorithm, left: &str, right: &str) -> String {
+    let mut hasher = algorithm.hasher();
+    hasher.update(&[0x01]);
+    hasher.update(&hex::decode(left).unwrap_or_default());
+    hasher.update(&hex::decode(right).unwrap_or_default());
+    hasher.finalize_hex()
+}
+
+/// Compute the SHA-256 Merkle tree hash of a list of leaf hashes
+pub fn merkle_root(leaves: &[String]) -> Option<String> {
+    merkle_root_with(HashAlgorithm::Sha256, leaves)
+}
+
+/// Compute the labeled Merkle tree hash of a list of leaf hashes
+pub fn merkle_root_with(algorithm: HashAlgorithm, leaves: &[String]) -> Option<String> {
+    tree_hash(algorithm, leaves).map(|hex| algorithm.label(&hex))
+}
+
+/// Generate a SHA-256 consistency proof that the first `first_size` leaves
+/// form a prefix of `leaves`.
+///
+/// Returns `None` if `first_size` exceeds the number of leaves.
+pub fn consistency_proof(leaves: &[String], first_size: usize) -> Option<Vec<String>> {
+    consistency_proof_with(HashAlgorithm::Sha256, leaves, first_size)
+}
+
+/// Generate a consistency proof using the given algorithm
+pub fn consistency_proof_with(algorithm: HashAlgorithm, leaves: &[String], first_size: usize) -> Option<Vec<String>> {
+    if first_size > leaves.len() {
+        return None;
+    }
+
+    let mut proof = Vec::new();
+    if first_size > 0 && first_size < leaves.len() {
+        subproof(algorithm, first_size, leaves, true, &mut proof);
+    }
+    Some(proof)
+}
+
+/// Verify a consistency proof between two tree heads (RFC 9162 §2.1.4.2)
+///
+/// The hash algorithm is taken from the root labels; both roots must use the
+/// same one. An empty first tree is consistent with any second tree.
+pub fn verify_consistency(
+    first_size: u64,
+    second_size: u64,
+    first_root: &str,
+    second_root: &str,
+    proof: &[String],
+) -> bool {
+    if first_size > second_size {
+        return false;
+    }
+    if first_size == second_size {
+        return proof.is_empty() && first_root == second_root;
+    }
+    if first_size == 0 {
+        return proof.is_empty();
+    }
+
+    let (algorithm, first_root) = HashAlgorithm::split_labeled(first_root);
+    let (second_algorithm, second_root) = HashAlgorithm::split_labeled(second_root);
+    if algorithm != second_algorithm {
+        return false;
+    }
+
+    let mut path: Vec<&str> = proof.iter().map(String::as_str).collect();
+    if first_size.is_power_of_two() {
+        path.insert(0, first_root);
+    }
+
+    let Some((seed, rest)) = path.split_first() else {
+        return false;
+    };
+
+    let mut fnode = first_size - 1;
+    let mut snode = second_size - 1;
+    while fnode & 1 == 1 {
+        fnode >>= 1;
+        snode >>= 1;
+    }
+
+    let mut fr = seed.to_string();
+    let mut sr = seed.to_string();
+
+    for node in rest {
+        if snode == 0 {
+            return false;
+        }
+
+        if fnode & 1 == 1 || fnode == snode {
+            fr = merkle_node_hash_with(algorithm, node, &fr);
+            sr = merkle_node_hash_with(algorithm, node, &sr);
+            while fnode & 1 == 0 && fnode != 0 {
+                fnode >>= 1;
+                snode >>= 1;
+            }
+        } else {
+            sr = merkle_node_hash_with(algorithm, &sr, node);
+        }
+
+        fnode >>= 1;
+        snode >>= 1;
+    }
+
+    snode == 0 && fr == first_root && sr == second_root
+}
+
+/// Verify an inclusion proof by folding `leaf_hash` up `path` to `root`
+///
+/// Each path entry is a sibling hash and whether that sibling sits to the
+/// right of the running node, ordered from the leaf upwards. The hash
+/// algorithm is taken from the root's label.
+pub fn verify_inclusion(leaf_hash: &str, path: &[(String, bool)], root: &str) -> bool {
+    let (algorithm, root) = HashAlgorithm::split_labeled(root);
+
+    let computed = path.iter().fold(leaf_hash.to_string(), |node, (sibling, sibling_on_right)| {
+        if *sibling_on_right {
+            merkle_node_hash_with(algorithm, &node, sibling)
+        } else {
+            merkle_node_hash_with(algorithm, sibling, &node)
+        }
+    });
+    computed == root
+}
+
+/// Unlabeled Merkle tree hash
+fn tree_hash(algorithm: HashAlgorithm, leaves: &[String]) -> Option<String> {
+    match leaves.len() {
+        0 => None,
+        1 => Some(leaves[0].clone()),
+        n => {
+            let k = split_point(n);
+            let left = tree_hash(algorithm, &leaves[..k])?;
+            let right = tree_hash(algorithm, &leaves[k..])?;
+            Some(merkle_node_hash_with(algorithm, &left, &right))
+        }
+    }
+}
+
+/// Largest power of two strictly less than `n` (for `n > 1`)
+fn split_point(n: usize) -> usize {
+    let mut k = 1;
+    while k << 1 < n {
+        k <<= 1;
+    }
+    k
+}
+
+/// RFC 6962 SUBPROOF(m, D[n], b)
+fn subproof(algorithm: HashAlgorithm, m: usize, leaves: &[String], complete: bool, proof: &mut Vec<String>) {
+    let n = leaves.len();
+    if m == n {
+        if !complete {
+            proof.extend(tree_hash(algorithm, leaves));
+        }
+        return;
+    }
+
+    let k = split_point(n);
+    if m <= k {
+        subproof(algorithm, m, &leaves[..k], complete, proof);
+        proof.extend(tree_hash(algorithm, &leaves[k..]));
+    } else {
+        subproof(algorithm, m - k, &leaves[k..], false, proof);
+        proof.extend(tree_hash(algorithm, &leaves[..k]));
+    }
+}
+
+/// Signed tree head for checkpointing
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SignedTreeHead {
+    /// Tree size (number of leaves)
+    pub tree_size: u64,
+    /// Root hash
+    pub root_hash: String,
+    /// Timestamp
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    /// Signature over (tree_size || root_hash || timestamp)
+    pub signature: Option<String>,
+    /// Key ID used for signing
+    pub key_id: Option<String>,
+}
+
+impl SignedTreeHead {
+    /// Create a new unsigned tree head
+    pub fn new(tree_size: u64, root_hash: String) -> Self {
+        Self {
+            tree_size,
+            root_hash,
+            timestamp: chrono::Utc::now(),
+            signature: None,
+            key_id: None,
+        }
+    }
+
+    /// Get the bytes to sign
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&self.tree_size.to_be_bytes());
+        bytes.extend_from_slice(self.root_hash.as_bytes());
+        bytes.extend_from_slice(&self.timestamp.timestamp().to_be_bytes());
+        bytes
+    }
+
+    /// Sign the tree head, recording the signing key
+    pub fn sign(mut self, key_pair: &KeyPair) -> CryptoResult<Self> {
+        self.signature = Some(sign_bytes(key_pair, &self.signing_bytes())?);
+        self.key_id = Some(key_pair.key_id.0.clone());
+        Ok(self)
+    }
+
+    /// Verify the tree head signature with `public_info`
+    pub fn verify(&self, public_info: &PublicKeyInfo) -> CryptoResult<bool> {
+        let signature = self.signature.as_deref().ok_or(CryptoError::InvalidSignature)?;
+        if self.key_id.as_deref() != Some(public_info.key_id.0.as_str()) {
+            return Ok(false);
+        }
+        verify_bytes(public_info, &self.signing_bytes(), signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaves(n: usize) -> Vec<String> {
+        (0..n).map(|i| merkle_leaf_hash(format!("leaf{}", i).as_bytes())).collect()
+    }
+
+    #[test]
+    fn test_consistency_all_sizes() {
+        let all = leaves(17);
+        for second in 1..=all.len() {
+            let second_root = merkle_root(&all[..second]).unwrap();
+            for first in 1..=second {
+                let first_root = merkle_root(&all[..first]).unwrap();
+                let proof = consistency_proof(&all[..second], first).unwrap();
+                assert!(
+                    verify_consistency(first as u64, second as u64, &first_root, &second_root, &proof),
+                    "Consistency failed for {} -> {}",
+                    first,
+                    second
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_consistency_rejects_forked_log() {
+        let all = leaves(7);
+        let mut forked = all.clone();
+        forked[2] = merkle_leaf_hash(b"rewritten");
+
+        let first_root = merkle_root(&all[..3]).unwrap();
+        let second_root = merkle_root(&forked).unwrap();
+        let proof = consistency_proof(&forked, 3).unwrap();
+        assert!(!verify_consistency(3, 7, &first_root, &second_root, &proof));
+
+        // Tampered proof against honest roots
+        let honest_root = merkle_root(&all).unwrap();
+        let mut proof = consistency_proof(&all, 3).unwrap();
+        proof[0] = merkle_leaf_hash(b"bogus");
+        assert!(!verify_consistency(3, 7, &first_root, &honest_root, &proof));
+        assert!(!verify_consistency(7, 3, &honest_root, &first_root, &[]));
+    }
+
+    #[test]
+    fn test_blake3_roots_are_labeled() {
+        let algorithm = HashAlgorithm::Blake3;
+        let all: Vec<String> = (0..9)
+            .map(|i| merkle_leaf_hash_with(algorithm, format!("leaf{}", i).as_bytes()))
+            .collect();
+
+        let first_root = merkle_root_with(algorithm, &all[..5]).unwrap();
+        let second_root = merkle_root_with(algorithm, &all).unwrap();
+        assert!(second_root.starts_with("blake3:"));
+        assert_ne!(merkle_root(&all).unwrap(), second_root.trim_start_matches("blake3:"));
+
+        let proof = consistency_proof_with(algorithm, &all, 5).unwrap();
+        assert!(verify_consistency(5, 9, &first_root, &second_root, &proof));
+
+        // Mismatched labels never verify
+        let unlabeled = first_root.trim_start_matches("blake3:");
+        assert!(!verify_consistency(5, 9, unlabeled, &second_root, &proof));
+    }
+
+    /// RFC 6962 PATH(m, D[n]), as (sibling, sibling_on_right) pairs
+    fn inclusion_path(leaves: &[String], index: usize) -> Vec<(String, bool)> {
+        if leaves.len() <= 1 {
+            return vec![];
+        }
+        let k = split_point(leaves.len());
+        if index < k {
+            let mut path = inclusion_path(&leaves[..k], index);
+            path.push((tree_hash(HashAlgorithm::Sha256, &leaves[k..]).unwrap(), true));
+            path
+        } else {
+            let mut path = inclusion_path(&leaves[k..], index - k);
+            path.push((tree_hash(HashAlgorithm::Sha256, &leaves[..k]).unwrap(), false));
+            path
+        }
+    }
+
+    #[test]
+    fn test_inclusion_all_positions() {
+        let all = leaves(11);
+        let root = merkle_root(&all).unwrap();
+        for (index, leaf) in all.iter().enumerate() {
+            let path = inclusion_path(&all, index);
+            assert!(verify_inclusion(leaf, &path, &root), "Inclusion failed for leaf {}", index);
+        }
+
+        let path = inclusion_path(&all, 4);
+        assert!(!verify_inclusion(&all[5], &path, &root));
+        assert!(!verify_inclusion(&all[4], &path, &merkle_root(&all[..10]).unwrap()));
+    }
+
+    #[test]
+    fn test_empty_first_tree() {
+        let all = leaves(4);
+        let root = merkle_root(&all).unwrap();
+        assert_eq!(consistency_proof(&all, 0), Some(vec![]));
+        assert!(verify_consistency(0, 4, "", &root, &[]));
+        assert!(consistency_proof(&all, 5).is_none());
+    }
+
+    #[test]
+    fn test_signed_tree_head() {
+        let key_pair = KeyPair::generate(crate::keys::KeyPurpose::General);
+        let public_info = key_pair.to_public_info();
+        let sth = SignedTreeHead::new(4, merkle_root(&leaves(4)).unwrap()).sign(&key_pair).unwrap();
+        assert!(sth.verify(&public_info).unwrap());
+
+        let mut forged = sth.clone();
+        forged.tree_size = 5;
+        assert!(!forged.verify(&public_info).unwrap());
+
+        let other = KeyPair::generate(crate::keys::KeyPurpose::General).to_public_info();
+        assert!(!sth.verify(&other).unwrap());
+        assert!(SignedTreeHead::new(4, sth.root_hash.clone()).verify(&public_info).is_err());
+    }
+}
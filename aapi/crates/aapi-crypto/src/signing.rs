@@ -1,11 +1,16 @@
 //! Signing and verification for AAPI
 //!
-//! Implements Ed25519 signing for VĀKYA requests and PRAMĀṆA receipts.
+//! Implements signing for VĀKYA requests and PRAMĀṆA receipts. Signing and
+//! verification dispatch on [`SignatureAlgorithm`]; Ed25519 is the only
+//! algorithm implemented so far.
 
-use ed25519_dalek::{Signature, Signer, Verifier};
+use std::collections::HashMap;
+
+use ed25519_dalek::{Signature, Signer, Verifier, VerifyingKey};
+use std::str::FromStr;
 use serde::{Deserialize, Serialize};
 
-use aapi_core::{Vakya, SandhiOutput, canonicalize};
+use aapi_core::{Karta, Vakya, SandhiOutput, canonicalize};
 use crate::error::{CryptoError, CryptoResult};
 use crate::keys::{KeyId, KeyPair, KeyStore, PublicKeyInfo};
 
@@ -34,6 +39,10 @@ pub struct VakyaSignature {
 }
 
 /// Supported signature algorithms
+///
+/// Every signature and key records its algorithm, and signing and
+/// verification branch on it, so adding a scheme means adding a variant and
+/// its arms here.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum SignatureAlgorithm {
@@ -46,14 +55,98 @@ impl Default for SignatureAlgorithm {
     }
 }
 
+impl SignatureAlgorithm {
+    /// Name recorded in `PublicKeyInfo::algorithm`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Ed25519 => "Ed25519",
+        }
+    }
+
+    /// Sign `data` with `key_pair`, returning the raw signature bytes
+    fn sign(&self, key_pair: &KeyPair, data: &[u8]) -> CryptoResult<Vec<u8>> {
+        if key_pair.algorithm != *self {
+            return Err(CryptoError::SigningFailed(format!(
+                "Key {} is a {} key, not {}",
+                key_pair.key_id,
+                key_pair.algorithm,
+                self
+            )));
+        }
+        match self {
+            Self::Ed25519 => Ok(key_pair.signing_key().sign(data).to_bytes().to_vec()),
+        }
+    }
+
+    /// Check a base64 signature over `data`
+    ///
+    /// `Err` means the key or signature cannot be used with this algorithm;
+    /// `Ok(Err(reason))` means the signature does not match.
+    fn verify(
+        &self,
+        public_info: &PublicKeyInfo,
+        data: &[u8],
+        signature_b64: &str,
+    ) -> CryptoResult<Result<(), String>> {
+        let key_algorithm = public_info.signature_algorithm()?;
+        if key_algorithm != *self {
+            return Err(CryptoError::VerificationFailed(format!(
+                "Key {} is a {} key, not {}",
+                public_info.key_id,
+                key_algorithm,
+                self
+            )));
+        }
+        match self {
+            Self::Ed25519 => {
+                let verifying_key = public_info.verifying_key()?;
+                let signature = decode_signature(signature_b64)?;
+                Ok(verifying_key.verify(data, &signature).map_err(|e| e.to_string()))
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for SignatureAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for SignatureAlgorithm {
+    type Err = CryptoError;
+
+    fn from_str(s: &str) -> CryptoResult<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "ed25519" => Ok(Self::Ed25519),
+            other => Err(CryptoError::InvalidKeyFormat(format!(
+                "Unsupported signature algorithm: {}",
+                other
+            ))),
+        }
+    }
+}
+
 /// Signer for VĀKYA requests
 pub struct VakyaSigner {
     key_store: KeyStore,
+    /// Let `sign_auto` use any signing key when none matches the karta
+    allow_fallback: bool,
 }
 
 impl VakyaSigner {
     pub fn new(key_store: KeyStore) -> Self {
-        Self { key_store }
+        Self {
+            key_store,
+            allow_fallback: false,
+        }
+    }
+
+    /// Let [`sign_auto`](Self::sign_auto) fall back to the oldest signing
+    /// key when no key matches the karta
+    pub fn allow_fallback(mut self, allow: bool) -> Self {
+        self.allow_fallback = allow;
+        self
     }
 
     /// Sign a VĀKYA with the specified key
@@ -76,7 +169,7 @@ impl VakyaSigner {
             vakya: vakya.clone(),
             signature: VakyaSignature {
                 key_id: key_id.clone(),
-                algorithm: SignatureAlgorithm::Ed25519,
+                algorithm: key_pair.algorithm,
                 value: signature,
                 signed_at: chrono::Utc::now(),
             },
@@ -84,18 +177,62 @@ impl VakyaSigner {
         })
     }
 
-    /// Sign with automatic key selection based on principal
+    /// Sign with a key selected for the VĀKYA's karta
+    ///
+    /// Keys are tried from most to least specific:
+    /// 1. the key named by `Karta.key_id`;
+    /// 2. a key bound to the karta's principal, preferring one bound to the
+    ///    karta's realm as well;
+    /// 3. a key bound only to the karta's realm.
+    ///
+    /// A key bound to a different principal or realm never matches, and
+    /// expired or public-only keys are never selected. Without a match this
+    /// fails with `KeyNotFound` unless fallback is allowed.
     pub fn sign_auto(&self, vakya: &Vakya) -> CryptoResult<SignedVakya> {
-        // Try to find a key for this principal
-        let principal = &vakya.v1_karta.pid.0;
-        
-        let keys = self.key_store.list_public_keys()?;
-        let key = keys.iter()
-            .find(|k| k.principal.as_deref() == Some(principal))
-            .or_else(|| keys.first())
-            .ok_or_else(|| CryptoError::KeyNotFound("No signing keys available".to_string()))?;
+        let key_id = self.select_key(&vakya.v1_karta)?;
+        self.sign(vakya, &key_id)
+    }
 
-        self.sign(vakya, &key.key_id)
+    fn select_key(&self, karta: &Karta) -> CryptoResult<KeyId> {
+        let principal = karta.pid.0.as_str();
+        let realm = karta.realm.as_deref();
+        let compatible = |key: &PublicKeyInfo| {
+            key.principal.as_deref().map_or(true, |p| p == principal)
+                && key.realm.as_deref().map_or(true, |r| Some(r) == realm)
+        };
+
+        if let Some(ref hint) = karta.key_id {
+            let key = self.key_store.get_key(&KeyId::new(hint.clone()))?.to_public_info();
+            if !compatible(&key) {
+                return Err(CryptoError::KeyNotFound(format!(
+                    "Key {} is not bound to {} in realm {}",
+                    hint,
+                    principal,
+                    realm.unwrap_or("(none)")
+                )));
+            }
+            return Ok(key.key_id);
+        }
+
+        let keys: Vec<PublicKeyInfo> = self.key_store.list_public_keys()?
+            .into_iter()
+            .filter(|key| self.key_store.get_key(&key.key_id).is_ok_and(|pair| !pair.is_expired()))
+            .collect();
+        let in_realm = |key: &&PublicKeyInfo| realm.is_some() && key.realm.as_deref() == realm;
+        let for_principal: Vec<&PublicKeyInfo> = keys.iter()
+            .filter(|key| key.principal.as_deref() == Some(principal) && compatible(key))
+            .collect();
+
+        let selected = for_principal.iter().copied().find(in_realm)
+            .or_else(|| for_principal.first().copied())
+            .or_else(|| keys.iter().filter(|key| key.principal.is_none()).find(in_realm))
+            .or_else(|| if self.allow_fallback { keys.first() } else { None })
+            .ok_or_else(|| CryptoError::KeyNotFound(format!(
+                "No signing key for {} in realm {}",
+                principal,
+                realm.unwrap_or("(none)")
+            )))?;
+        Ok(selected.key_id.clone())
     }
 }
 
@@ -111,93 +248,48 @@ impl VakyaVerifier {
 
     /// Verify a signed VĀKYA
     pub fn verify(&self, signed: &SignedVakya) -> CryptoResult<VerificationResult> {
-        // Get the public key
-        let public_info = self.key_store.get_public_key(&signed.signature.key_id)?;
-        let verifying_key = public_info.verifying_key()?;
-
         // Re-canonicalize the VĀKYA
         let sandhi = canonicalize(&signed.vakya)
             .map_err(|e| CryptoError::VerificationFailed(e.to_string()))?;
 
+        self.verify_canonical(signed, &sandhi)
+    }
+
+    /// Verify a signed VĀKYA against its already computed canonical form
+    ///
+    /// `sandhi` must be `canonicalize(&signed.vakya)`; callers that need the
+    /// canonical bytes anyway use this to canonicalize only once.
+    pub fn verify_canonical(&self, signed: &SignedVakya, sandhi: &SandhiOutput) -> CryptoResult<VerificationResult> {
+        // Get the public key
+        let public_info = self.key_store.get_public_key(&signed.signature.key_id)?;
+
         // Verify hash matches
         if sandhi.vakya_hash.value != signed.vakya_hash {
-            return Ok(VerificationResult {
-                valid: false,
-                reason: Some("Hash mismatch".to_string()),
-                key_id: signed.signature.key_id.clone(),
-                verified_at: chrono::Utc::now(),
-            });
-        }
-
-        // Decode signature
-        use base64::Engine;
-        let sig_bytes = base64::engine::general_purpose::STANDARD
-            .decode(&signed.signature.value)?;
-        
-        if sig_bytes.len() != 64 {
-            return Err(CryptoError::InvalidSignature);
+            return Ok(VerificationResult::invalid(signed, "Hash mismatch".to_string()));
         }
 
-        let mut sig_array = [0u8; 64];
-        sig_array.copy_from_slice(&sig_bytes);
-        let signature = Signature::from_bytes(&sig_array);
-
-        // Verify signature
-        match verifying_key.verify(&sandhi.canonical_bytes, &signature) {
-            Ok(_) => Ok(VerificationResult {
-                valid: true,
-                reason: None,
-                key_id: signed.signature.key_id.clone(),
-                verified_at: chrono::Utc::now(),
-            }),
-            Err(e) => Ok(VerificationResult {
-                valid: false,
-                reason: Some(e.to_string()),
-                key_id: signed.signature.key_id.clone(),
-                verified_at: chrono::Utc::now(),
-            }),
-        }
+        check_signature(signed, &public_info, &sandhi.canonical_bytes)
     }
 
     /// Verify with a specific public key (without key store lookup)
     pub fn verify_with_key(&self, signed: &SignedVakya, public_info: &PublicKeyInfo) -> CryptoResult<VerificationResult> {
-        let verifying_key = public_info.verifying_key()?;
-
         // Re-canonicalize the VĀKYA
         let sandhi = canonicalize(&signed.vakya)
             .map_err(|e| CryptoError::VerificationFailed(e.to_string()))?;
 
-        // Decode signature
-        use base64::Engine;
-        let sig_bytes = base64::engine::general_purpose::STANDARD
-            .decode(&signed.signature.value)?;
-        
-        if sig_bytes.len() != 64 {
-            return Err(CryptoError::InvalidSignature);
-        }
-
-        let mut sig_array = [0u8; 64];
-        sig_array.copy_from_slice(&sig_bytes);
-        let signature = Signature::from_bytes(&sig_array);
-
-        // Verify signature
-        match verifying_key.verify(&sandhi.canonical_bytes, &signature) {
-            Ok(_) => Ok(VerificationResult {
-                valid: true,
-                reason: None,
-                key_id: signed.signature.key_id.clone(),
-                verified_at: chrono::Utc::now(),
-            }),
-            Err(e) => Ok(VerificationResult {
-                valid: false,
-                reason: Some(e.to_string()),
-                key_id: signed.signature.key_id.clone(),
-                verified_at: chrono::Utc::now(),
-            }),
-        }
+        check_signature(signed, public_info, &sandhi.canonical_bytes)
     }
 }
 
+/// Check a VĀKYA signature over its canonical bytes with the algorithm it
+/// names
+fn check_signature(signed: &SignedVakya, public_info: &PublicKeyInfo, message: &[u8]) -> CryptoResult<VerificationResult> {
+    Ok(match signed.signature.algorithm.verify(public_info, message, &signed.signature.value)? {
+        Ok(()) => VerificationResult::valid(signed),
+        Err(reason) => VerificationResult::invalid(signed, reason),
+    })
+}
+
 /// Result of signature verification
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VerificationResult {
@@ -207,30 +299,68 @@ pub struct VerificationResult {
     pub verified_at: chrono::DateTime<chrono::Utc>,
 }
 
-/// Sign arbitrary bytes with a key pair
+/// Sign arbitrary bytes with a key pair, using the key's algorithm
 pub fn sign_bytes(key_pair: &KeyPair, data: &[u8]) -> CryptoResult<String> {
-    let signature = key_pair.signing_key().sign(data);
+    let signature = key_pair.algorithm.sign(key_pair, data)?;
     use base64::Engine;
-    Ok(base64::engine::general_purpose::STANDARD.encode(signature.to_bytes()))
+    Ok(base64::engine::general_purpose::STANDARD.encode(signature))
 }
 
-/// Verify a signature over arbitrary bytes
+/// Verify a signature over arbitrary bytes, using the key's algorithm
 pub fn verify_bytes(public_info: &PublicKeyInfo, data: &[u8], signature_b64: &str) -> CryptoResult<bool> {
-    let verifying_key = public_info.verifying_key()?;
-    
+    let algorithm = public_info.signature_algorithm()?;
+    Ok(algorithm.verify(public_info, data, signature_b64)?.is_ok())
+}
+
+/// Decode a base64 Ed25519 signature
+fn decode_signature(signature_b64: &str) -> CryptoResult<Signature> {
     use base64::Engine;
     let sig_bytes = base64::engine::general_purpose::STANDARD
         .decode(signature_b64)?;
-    
-    if sig_bytes.len() != 64 {
-        return Err(CryptoError::InvalidSignature);
+
+    let sig_array: [u8; 64] = sig_bytes.try_into()
+        .map_err(|_| CryptoError::InvalidSignature)?;
+    Ok(Signature::from_bytes(&sig_array))
+}
+
+/// Receipt record fields assigned after signing, and so not covered by
+/// the receipt signature
+const UNSIGNED_RECEIPT_FIELDS: [&str; 7] = [
+    "id",
+    "leaf_index",
+    "signature",
+    "karta_pid",
+    "chain_seq",
+    "prev_receipt_hash",
+    "chain_hash",
+];
+
+/// Canonical bytes covered by a PRAMĀṆA receipt signature
+///
+/// `receipt` is the stored receipt record as JSON. Its storage ID, Merkle
+/// leaf index, per-actor chain link and the signature itself are excluded;
+/// every other field, including `key_id`, is signed.
+pub fn receipt_signing_bytes(receipt: &serde_json::Value) -> CryptoResult<Vec<u8>> {
+    let mut fields = receipt.as_object()
+        .cloned()
+        .ok_or_else(|| CryptoError::SigningFailed("Receipt must be a JSON object".to_string()))?;
+    for field in UNSIGNED_RECEIPT_FIELDS {
+        fields.remove(field);
     }
+    aapi_core::canonicalize_value(&fields).map_err(|e| CryptoError::SigningFailed(e.to_string()))
+}
 
-    let mut sig_array = [0u8; 64];
-    sig_array.copy_from_slice(&sig_bytes);
-    let signature = Signature::from_bytes(&sig_array);
+/// Sign a receipt record, returning the base64 signature
+pub fn sign_receipt(key_pair: &KeyPair, receipt: &serde_json::Value) -> CryptoResult<String> {
+    sign_bytes(key_pair, &receipt_signing_bytes(receipt)?)
+}
 
-    Ok(verifying_key.verify(data, &signature).is_ok())
+/// Verify the `signature` field of a receipt record
+pub fn verify_receipt(public_info: &PublicKeyInfo, receipt: &serde_json::Value) -> CryptoResult<bool> {
+    let signature = receipt.get("signature")
+        .and_then(serde_json::Value::as_str)
+        .ok_or(CryptoError::InvalidSignature)?;
+    verify_bytes(public_info, &receipt_signing_bytes(receipt)?, signature)
 }
 
 /// Batch signature for multiple VĀKYA requests
@@ -279,7 +409,7 @@ impl VakyaSigner {
             batch_hash,
             batch_signature: VakyaSignature {
                 key_id: key_id.clone(),
-                algorithm: SignatureAlgorithm::Ed25519,
+                algorithm: key_pair.algorithm,
                 value: batch_sig,
                 signed_at: chrono::Utc::now(),
             },
@@ -287,6 +417,94 @@ impl VakyaSigner {
     }
 }
 
+impl VakyaVerifier {
+    /// Verify many signed VĀKYA at once
+    ///
+    /// Ed25519 signatures are checked with a single batch verification.
+    /// If the batch fails, each signature is re-checked individually so the
+    /// results pinpoint which ones are invalid. Items that cannot be checked
+    /// at all (unknown key, hash mismatch, malformed signature) are reported
+    /// as invalid rather than failing the whole batch. Results are returned
+    /// in input order.
+    pub fn verify_batch(&self, signed: &[SignedVakya]) -> Vec<VerificationResult> {
+        let mut keys: HashMap<&KeyId, Option<PublicKeyInfo>> = HashMap::new();
+        let mut results: Vec<Option<VerificationResult>> = vec![None; signed.len()];
+        let mut pending = Vec::with_capacity(signed.len());
+
+        for (index, item) in signed.iter().enumerate() {
+            let key = keys.entry(&item.signature.key_id)
+                .or_insert_with(|| self.key_store.get_public_key(&item.signature.key_id).ok());
+            match prepare_check(item, key.as_ref()) {
+                Ok((message, signature, key)) => pending.push((index, message, signature, key)),
+                Err(reason) => results[index] = Some(VerificationResult::invalid(item, reason)),
+            }
+        }
+
+        let messages: Vec<&[u8]> = pending.iter().map(|(_, message, _, _)| message.as_slice()).collect();
+        let signatures: Vec<Signature> = pending.iter().map(|(_, _, signature, _)| *signature).collect();
+        let verifying_keys: Vec<VerifyingKey> = pending.iter().map(|(_, _, _, key)| *key).collect();
+        let batch_valid = ed25519_dalek::verify_batch(&messages, &signatures, &verifying_keys).is_ok();
+
+        for (index, message, signature, key) in &pending {
+            let item = &signed[*index];
+            let outcome = if batch_valid { Ok(()) } else { key.verify(message, signature) };
+            results[*index] = Some(match outcome {
+                Ok(()) => VerificationResult::valid(item),
+                Err(e) => VerificationResult::invalid(item, e.to_string()),
+            });
+        }
+
+        results.into_iter().map(|result| result.expect("every item has a result")).collect()
+    }
+}
+
+/// Canonical bytes, signature and key for one batch item, or why it
+/// cannot be checked
+fn prepare_check(
+    signed: &SignedVakya,
+    key: Option<&PublicKeyInfo>,
+) -> Result<(Vec<u8>, Signature, VerifyingKey), String> {
+    let key = key.ok_or_else(|| format!("Key not found: {}", signed.signature.key_id))?;
+    let key_algorithm = key.signature_algorithm().map_err(|e| e.to_string())?;
+    if key_algorithm != signed.signature.algorithm {
+        return Err(format!(
+            "Key {} is a {} key, not {}",
+            key.key_id, key_algorithm, signed.signature.algorithm
+        ));
+    }
+    let sandhi = canonicalize(&signed.vakya).map_err(|e| e.to_string())?;
+    if sandhi.vakya_hash.value != signed.vakya_hash {
+        return Err("Hash mismatch".to_string());
+    }
+    match signed.signature.algorithm {
+        SignatureAlgorithm::Ed25519 => {
+            let verifying_key = key.verifying_key().map_err(|e| e.to_string())?;
+            let signature = decode_signature(&signed.signature.value).map_err(|e| e.to_string())?;
+            Ok((sandhi.canonical_bytes, signature, verifying_key))
+        }
+    }
+}
+
+impl VerificationResult {
+    fn valid(signed: &SignedVakya) -> Self {
+        Self {
+            valid: true,
+            reason: None,
+            key_id: signed.signature.key_id.clone(),
+            verified_at: chrono::Utc::now(),
+        }
+    }
+
+    fn invalid(signed: &SignedVakya, reason: String) -> Self {
+        Self {
+            valid: false,
+            reason: Some(reason),
+            key_id: signed.signature.key_id.clone(),
+            verified_at: chrono::Utc::now(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -317,6 +535,7 @@ mod tests {
                 ttl: Some(TtlConstraint {
                     expires_at: Timestamp(chrono::Utc::now() + chrono::Duration::hours(1)),
                     max_duration_ms: None,
+                    not_before: None,
                 }),
                 budgets: vec![],
                 approval_lane: ApprovalLane::None,
@@ -365,6 +584,27 @@ mod tests {
         assert!(!result.valid);
     }
 
+    #[test]
+    fn test_verify_canonical_reuses_sandhi() {
+        let key_store = KeyStore::new();
+        let key_id = key_store.generate_key(KeyPurpose::VakyaSigning).unwrap();
+
+        let signer = VakyaSigner::new(key_store.clone());
+        let verifier = VakyaVerifier::new(key_store);
+
+        let vakya = create_test_vakya();
+        let signed = signer.sign(&vakya, &key_id).unwrap();
+        let sandhi = canonicalize(&vakya).unwrap();
+        assert!(verifier.verify_canonical(&signed, &sandhi).unwrap().valid);
+
+        // A canonical form of some other VĀKYA does not verify
+        let mut other = vakya;
+        other.v3_kriya.action = "tampered.action".to_string();
+        let result = verifier.verify_canonical(&signed, &canonicalize(&other).unwrap()).unwrap();
+        assert!(!result.valid);
+        assert_eq!(result.reason.as_deref(), Some("Hash mismatch"));
+    }
+
     #[test]
     fn test_batch_signing() {
         let key_store = KeyStore::new();
@@ -378,4 +618,140 @@ mod tests {
         assert_eq!(batch.signatures.len(), 3);
         assert!(!batch.batch_hash.is_empty());
     }
+
+    #[test]
+    fn test_verify_batch_pinpoints_failures() {
+        let key_store = KeyStore::new();
+        let key_a = key_store.generate_key(KeyPurpose::VakyaSigning).unwrap();
+        let key_b = key_store.generate_key(KeyPurpose::VakyaSigning).unwrap();
+
+        let signer = VakyaSigner::new(key_store.clone());
+        let verifier = VakyaVerifier::new(key_store);
+
+        let mut signed: Vec<SignedVakya> = (0..8)
+            .map(|i| signer.sign(&create_test_vakya(), if i % 2 == 0 { &key_a } else { &key_b }).unwrap())
+            .collect();
+
+        let results = verifier.verify_batch(&signed);
+        assert_eq!(results.len(), 8);
+        assert!(results.iter().all(|r| r.valid));
+        assert!(verifier.verify_batch(&[]).is_empty());
+
+        // Signature from the wrong key, tampered body, unknown key, garbage signature
+        signed[1].signature.value = signed[0].signature.value.clone();
+        signed[3].vakya.v3_kriya.action = "tampered.action".to_string();
+        signed[5].signature.key_id = KeyId::new("unknown");
+        signed[6].signature.value = "not-base64!".to_string();
+
+        let results = verifier.verify_batch(&signed);
+        let invalid: Vec<usize> = results.iter().enumerate().filter(|(_, r)| !r.valid).map(|(i, _)| i).collect();
+        assert_eq!(invalid, vec![1, 3, 5, 6]);
+        assert_eq!(results[3].reason.as_deref(), Some("Hash mismatch"));
+        assert_eq!(results[5].key_id, KeyId::new("unknown"));
+
+        // Batch results agree with one-by-one verification
+        for (item, result) in signed.iter().zip(&results) {
+            if let Ok(single) = verifier.verify(item) {
+                assert_eq!(single.valid, result.valid);
+            }
+        }
+    }
+
+    #[test]
+    fn test_receipt_signature() {
+        let key_pair = KeyPair::generate(KeyPurpose::ReceiptSigning);
+        let mut receipt = serde_json::json!({
+            "id": "0192",
+            "vakya_id": "vakya-1",
+            "vakya_hash": "abc123",
+            "reason_code": "SUCCESS",
+            "executor_id": "gateway-1",
+            "key_id": key_pair.key_id.0,
+            "receipt_json": {"status": "success"},
+        });
+
+        receipt["signature"] = serde_json::json!(sign_receipt(&key_pair, &receipt).unwrap());
+        // Assigned by storage after signing
+        receipt["leaf_index"] = serde_json::json!(7);
+        assert!(verify_receipt(&key_pair.to_public_info(), &receipt).unwrap());
+
+        receipt["reason_code"] = serde_json::json!("POLICY_DENIED");
+        assert!(!verify_receipt(&key_pair.to_public_info(), &receipt).unwrap());
+
+        receipt.as_object_mut().unwrap().remove("signature");
+        assert!(verify_receipt(&key_pair.to_public_info(), &receipt).is_err());
+    }
+
+    #[test]
+    fn test_algorithm_follows_the_key() {
+        let key_pair = KeyPair::generate(KeyPurpose::VakyaSigning);
+        assert_eq!(key_pair.algorithm, SignatureAlgorithm::Ed25519);
+        let public_info = key_pair.to_public_info();
+        assert_eq!(public_info.signature_algorithm().unwrap(), key_pair.algorithm);
+        assert_eq!("ED25519".parse::<SignatureAlgorithm>().unwrap(), SignatureAlgorithm::Ed25519);
+
+        let key_store = KeyStore::new();
+        key_store.store_key(key_pair.clone()).unwrap();
+        let signed = VakyaSigner::new(key_store).sign(&create_test_vakya(), &key_pair.key_id).unwrap();
+        assert_eq!(signed.signature.algorithm, key_pair.algorithm);
+
+        // A key for an algorithm this build does not implement is refused
+        // rather than checked as Ed25519
+        let mut unsupported = public_info.clone();
+        unsupported.algorithm = "Ed448".to_string();
+        let verifier = VakyaVerifier::new(KeyStore::new());
+        assert!(verifier.verify_with_key(&signed, &unsupported).is_err());
+        assert!(verifier.verify_with_key(&signed, &public_info).unwrap().valid);
+    }
+
+    fn karta_vakya(pid: &str, realm: Option<&str>, key_id: Option<&str>) -> Vakya {
+        let mut vakya = create_test_vakya();
+        vakya.v1_karta.pid = PrincipalId::new(pid);
+        vakya.v1_karta.realm = realm.map(str::to_string);
+        vakya.v1_karta.key_id = key_id.map(str::to_string);
+        vakya
+    }
+
+    #[test]
+    fn test_sign_auto_matches_realm() {
+        let key_store = KeyStore::new();
+        let dev = KeyPair::generate(KeyPurpose::VakyaSigning).with_principal("agent:deployer").with_realm("dev");
+        let prod = KeyPair::generate(KeyPurpose::VakyaSigning).with_principal("agent:deployer").with_realm("prod");
+        let prod_wide = KeyPair::generate(KeyPurpose::VakyaSigning).with_realm("prod");
+        for key in [&dev, &prod, &prod_wide] {
+            key_store.store_key(key.clone()).unwrap();
+        }
+        let signer = VakyaSigner::new(key_store);
+
+        let signed = signer.sign_auto(&karta_vakya("agent:deployer", Some("prod"), None)).unwrap();
+        assert_eq!(signed.signature.key_id, prod.key_id);
+        let signed = signer.sign_auto(&karta_vakya("agent:deployer", Some("dev"), None)).unwrap();
+        assert_eq!(signed.signature.key_id, dev.key_id);
+
+        // Anyone in a realm can use the realm-wide key
+        let signed = signer.sign_auto(&karta_vakya("agent:other", Some("prod"), None)).unwrap();
+        assert_eq!(signed.signature.key_id, prod_wide.key_id);
+
+        // A hint is honoured, but not across realms
+        let signed = signer.sign_auto(&karta_vakya("agent:deployer", Some("prod"), Some(&prod_wide.key_id.0))).unwrap();
+        assert_eq!(signed.signature.key_id, prod_wide.key_id);
+        assert!(signer.sign_auto(&karta_vakya("agent:deployer", Some("prod"), Some(&dev.key_id.0))).is_err());
+    }
+
+    #[test]
+    fn test_sign_auto_without_match_errors_unless_fallback() {
+        let key_store = KeyStore::new();
+        let dev = KeyPair::generate(KeyPurpose::VakyaSigning).with_principal("agent:deployer").with_realm("dev");
+        key_store.store_key(dev.clone()).unwrap();
+
+        let vakya = karta_vakya("agent:deployer", Some("prod"), None);
+        let err = VakyaSigner::new(key_store.clone()).sign_auto(&vakya).unwrap_err();
+        assert!(matches!(err, CryptoError::KeyNotFound(_)), "{:?}", err);
+        assert!(VakyaSigner::new(key_store.clone())
+            .sign_auto(&karta_vakya("agent:unknown", None, None))
+            .is_err());
+
+        let signed = VakyaSigner::new(key_store).allow_fallback(true).sign_auto(&vakya).unwrap();
+        assert_eq!(signed.signature.key_id, dev.key_id);
+    }
 }
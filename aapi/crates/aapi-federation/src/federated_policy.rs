@@ -97,20 +97,20 @@ impl FederatedPolicyEngine {
     }
 
     /// Add a policy to the local engine.
-    pub async fn add_local_policy(&self, policy: Policy) {
-        self.local.add_policy(policy).await;
+    pub async fn add_local_policy(&self, policy: Policy) -> MetaRulesResult<()> {
+        self.local.add_policy(policy).await
     }
 
     /// Add a policy to the cluster engine.
-    pub async fn add_cluster_policy(&self, policy: Policy) {
+    pub async fn add_cluster_policy(&self, policy: Policy) -> MetaRulesResult<()> {
         let engine = self.cluster.read().await;
-        engine.add_policy(policy).await;
+        engine.add_policy(policy).await
     }
 
     /// Add a policy to the federation engine.
-    pub async fn add_federation_policy(&self, policy: Policy) {
+    pub async fn add_federation_policy(&self, policy: Policy) -> MetaRulesResult<()> {
         let engine = self.federation.read().await;
-        engine.add_policy(policy).await;
+        engine.add_policy(policy).await
     }
 
     /// Count policies at each level.
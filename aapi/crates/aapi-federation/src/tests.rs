@@ -43,6 +43,7 @@ fn make_test_vakya(agent_pid: &str, action: &str) -> Vakya {
             ttl: Some(TtlConstraint {
                 expires_at: Timestamp(chrono::Utc::now() + chrono::Duration::hours(1)),
                 max_duration_ms: None,
+                not_before: None,
             }),
             budgets: vec![],
             approval_lane: ApprovalLane::None,
@@ -99,7 +100,7 @@ async fn test_federated_policy_default_deny() {
 #[tokio::test]
 async fn test_federated_policy_local_allow() {
     let engine = FederatedPolicyEngine::new();
-    engine.add_local_policy(make_allow_policy("local-1", "Allow all")).await;
+    engine.add_local_policy(make_allow_policy("local-1", "Allow all")).await.unwrap();
 
     let ctx = make_eval_context("file.read");
     let decision = engine.evaluate(&ctx).await.unwrap();
@@ -110,11 +111,12 @@ async fn test_federated_policy_local_allow() {
 async fn test_federated_federation_deny_overrides_local_allow() {
     let engine = FederatedPolicyEngine::new();
     // Local allows everything
-    engine.add_local_policy(make_allow_policy("local-1", "Allow all")).await;
+    engine.add_local_policy(make_allow_policy("local-1", "Allow all")).await.unwrap();
     // Federation denies file.delete
     engine
         .add_federation_policy(make_deny_policy("fed-1", "No deletes", "file.delete"))
-        .await;
+        .await
+        .unwrap();
 
     // file.read should still be allowed (federation deny doesn't match)
     let ctx_read = make_eval_context("file.read");
@@ -130,10 +132,11 @@ async fn test_federated_federation_deny_overrides_local_allow() {
 #[tokio::test]
 async fn test_federated_cluster_deny_overrides_local_allow() {
     let engine = FederatedPolicyEngine::new();
-    engine.add_local_policy(make_allow_policy("local-1", "Allow all")).await;
+    engine.add_local_policy(make_allow_policy("local-1", "Allow all")).await.unwrap();
     engine
         .add_cluster_policy(make_deny_policy("cluster-1", "No admin", "admin.delete"))
-        .await;
+        .await
+        .unwrap();
 
     let ctx = make_eval_context("admin.delete");
     let decision = engine.evaluate(&ctx).await.unwrap();
@@ -143,10 +146,10 @@ async fn test_federated_cluster_deny_overrides_local_allow() {
 #[tokio::test]
 async fn test_federated_policy_counts() {
     let engine = FederatedPolicyEngine::new();
-    engine.add_local_policy(make_allow_policy("l1", "L1")).await;
-    engine.add_local_policy(make_allow_policy("l2", "L2")).await;
-    engine.add_cluster_policy(make_allow_policy("c1", "C1")).await;
-    engine.add_federation_policy(make_allow_policy("f1", "F1")).await;
+    engine.add_local_policy(make_allow_policy("l1", "L1")).await.unwrap();
+    engine.add_local_policy(make_allow_policy("l2", "L2")).await.unwrap();
+    engine.add_cluster_policy(make_allow_policy("c1", "C1")).await.unwrap();
+    engine.add_federation_policy(make_allow_policy("f1", "F1")).await.unwrap();
 
     let (local, cluster, fed) = engine.policy_counts().await;
     assert_eq!(local, 2);
@@ -0,0 +1,129 @@
+//! Content-based deduplication of retried submissions
+//!
+//! `vakya_id` makes a submission idempotent only while the client reuses it.
+//! [`DedupIndex`] also catches retries that regenerate the ID: within the
+//! configured window, a VĀKYA from the same actor whose content matches an
+//! earlier one is answered with the earlier receipt instead of executing.
+//!
+//! A submission claims its content with [`DedupIndex::reserve`] before it is
+//! stored or executed, so two concurrent retries cannot both run. The
+//! [`Reservation`] is released if the submission fails and kept once it
+//! completes.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use aapi_core::{canonicalize_value, hash_bytes, Vakya};
+
+use crate::error::{GatewayError, GatewayResult};
+
+/// Short-lived index from submission content to the VĀKYA that carried it
+pub struct DedupIndex {
+    window: Duration,
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+struct Entry {
+    vakya_id: String,
+    /// When the submission completed; `None` while it is still running
+    completed_at: Option<Instant>,
+}
+
+/// Outcome of [`DedupIndex::reserve`]
+pub enum Claim<'a> {
+    /// No identical submission is known; this one may proceed
+    Reserved(Reservation<'a>),
+    /// An identical submission, recorded under this VĀKYA ID, is still running
+    InProgress(String),
+    /// An identical submission completed within the window under this VĀKYA ID
+    Completed(String),
+}
+
+impl DedupIndex {
+    pub fn new(window_secs: u64) -> Self {
+        Self {
+            window: Duration::from_secs(window_secs),
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Key identifying what a VĀKYA asks for, ignoring its ID and metadata
+    ///
+    /// Retries typically regenerate `vakya_id` and `meta` (creation time,
+    /// trace), so neither takes part; the actor does, through `v1_karta`.
+    pub fn content_key(vakya: &Vakya) -> GatewayResult<String> {
+        let mut value = serde_json::to_value(vakya).map_err(|e| GatewayError::Internal(e.to_string()))?;
+        if let Some(fields) = value.as_object_mut() {
+            fields.remove("vakya_id");
+            fields.remove("meta");
+        }
+        let canonical = canonicalize_value(&value).map_err(|e| GatewayError::Internal(e.to_string()))?;
+        Ok(hash_bytes(&canonical).value)
+    }
+
+    /// Claim `key` for `vakya_id` unless an identical submission is running
+    /// or completed within the window
+    ///
+    /// Checking and inserting happen under one lock, so of two concurrent
+    /// submissions with the same content only one is reserved.
+    pub fn reserve(&self, key: String, vakya_id: String) -> Claim<'_> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, entry| entry.completed_at.map_or(true, |done| done.elapsed() < self.window));
+        if let Some(entry) = entries.get(&key) {
+            return match entry.completed_at {
+                Some(_) => Claim::Completed(entry.vakya_id.clone()),
+                None => Claim::InProgress(entry.vakya_id.clone()),
+            };
+        }
+        entries.insert(key.clone(), Entry { vakya_id: vakya_id.clone(), completed_at: None });
+        Claim::Reserved(Reservation {
+            index: self,
+            key,
+            vakya_id,
+            completed: false,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A submission's claim on its content, released on drop unless completed
+///
+/// Dropping covers both failed submissions and cancelled ones, so a retry
+/// after either is not mistaken for a duplicate.
+pub struct Reservation<'a> {
+    index: &'a DedupIndex,
+    key: String,
+    vakya_id: String,
+    completed: bool,
+}
+
+impl Reservation<'_> {
+    /// Keep the entry for the window, answering later retries with this
+    /// submission's receipt
+    pub fn complete(mut self) {
+        if let Some(entry) = self.index.entries.lock().unwrap().get_mut(&self.key) {
+            entry.completed_at = Some(Instant::now());
+        }
+        self.completed = true;
+    }
+}
+
+impl Drop for Reservation<'_> {
+    fn drop(&mut self) {
+        if self.completed {
+            return;
+        }
+        let mut entries = self.index.entries.lock().unwrap();
+        if entries.get(&self.key).is_some_and(|entry| entry.vakya_id == self.vakya_id) {
+            entries.remove(&self.key);
+        }
+    }
+}
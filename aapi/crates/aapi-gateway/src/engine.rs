@@ -0,0 +1,481 @@
+//! In-process execution of VĀKYAs
+//!
+//! [`Engine`] runs the gateway's submission pipeline (validation,
+//! authorization, persistence, policy evaluation, dispatch and receipts)
+//! against an [`AppState`] without an HTTP server, so another service can
+//! embed AAPI governance directly. `POST /v1/vakya` is a thin wrapper over it.
+
+use std::sync::Arc;
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::{info, warn};
+
+use aapi_adapters::ExecutionEvent;
+use aapi_core::{CapabilityRef, SandhiOutput, ValidationWarning, Vakya, canonicalize, error::ReasonCode};
+use aapi_indexdb::{ApprovalRecord, AuditEventType, AuditLogEntry, IndexDbError, ReceiptRecord, VakyaRecord};
+use aapi_metarules::DecisionType;
+
+use crate::dedup::{Claim, DedupIndex};
+use crate::error::{GatewayError, GatewayResult};
+use crate::handlers::{
+    authorize_submission, deferred_receipt, deferred_until, execute_vakya, policy_context, record_audit,
+    spawn_deferred_execution, with_annotations, PolicyDecisionResponse, ReceiptResponse,
+    SubmitVakyaResponse,
+};
+use crate::state::AppState;
+use crate::tls::PeerIdentity;
+
+/// Who is submitting a VĀKYA, beyond what the VĀKYA itself says
+#[derive(Debug, Clone, Default)]
+pub struct SubmissionContext {
+    /// Signature over the canonical VĀKYA bytes
+    pub signature: Option<String>,
+    /// Key the signature was made with
+    pub key_id: Option<String>,
+    /// Client certificate of the submitting connection, checked against the
+    /// karta when `TlsConfig::match_principal` is set, and its address, for
+    /// inline capabilities with `ip_address` caveats
+    pub peer: PeerIdentity,
+    /// Receives the adapter's progress events while the VĀKYA executes
+    pub progress: Option<UnboundedSender<ExecutionEvent>>,
+}
+
+/// Runs VĀKYAs through the gateway pipeline in process
+#[derive(Clone)]
+pub struct Engine {
+    state: Arc<AppState>,
+}
+
+impl Engine {
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    pub fn state(&self) -> &Arc<AppState> {
+        &self.state
+    }
+
+    /// Validate, authorize, record and execute a VĀKYA, returning what
+    /// `POST /v1/vakya` would
+    ///
+    /// Denied, approval-pending and deferred VĀKYAs return `Ok` with the
+    /// matching `status`; `Err` is reserved for submissions that are refused
+    /// outright (invalid, unauthorized, duplicate, no execution slot).
+    pub async fn execute(&self, vakya: Vakya, context: SubmissionContext) -> GatewayResult<SubmitVakyaResponse> {
+        let state = &*self.state;
+        let start = std::time::Instant::now();
+
+        context.peer.check_principal(state.config.tls.as_ref(), &vakya.v1_karta.pid.0)?;
+
+        info!(vakya_id = %vakya.vakya_id, action = %vakya.v3_kriya.action, "Received VĀKYA submission");
+
+        // Validate the VĀKYA, reporting every problem at once
+        let mut validation = state.config.validator().validate(&vakya);
+        if state.config.strict_validation {
+            // Strict mode already turned each warning into an error
+            validation.warnings.clear();
+        }
+        if !validation.valid {
+            warn!(vakya_id = %vakya.vakya_id, errors = validation.errors.len(), "VĀKYA validation failed");
+            return Err(GatewayError::InvalidVakya(validation));
+        }
+        let warnings = validation.warnings;
+
+        // Canonicalized once: the signature, the stored hash and the receipt
+        // all cover these same bytes
+        let sandhi = canonicalize(&vakya)
+            .map_err(|e| GatewayError::Internal(e.to_string()))?;
+
+        if let Err(e) = authorize_submission(state, &vakya, &sandhi, context.signature.as_ref(), context.key_id.as_ref(), &context.peer) {
+            if let GatewayError::AuthorizationDenied(ref reason) = e {
+                record_audit(state, AuditLogEntry::new(
+                    AuditEventType::AuthorizationFailed,
+                    serde_json::json!({
+                        "vakya_id": vakya.vakya_id.0,
+                        "action": vakya.v3_kriya.action,
+                        "reason": reason,
+                    }),
+                )
+                .with_actor(vakya.v1_karta.pid.0.clone())
+                .with_target(vakya.v2_karma.rid.0.clone())).await;
+            }
+            return Err(e);
+        }
+
+        // Persist the verified token's parent link, so revoking any ancestor
+        // with its delegations still reaches tokens below it after a restart
+        if let CapabilityRef::Inline(ref token) = vakya.v7_adhikarana.cap {
+            if let Some(ref parent) = token.parent_token_id {
+                state.index_db.record_capability_delegation(&token.token_id, parent).await
+                    .map_err(|e| GatewayError::Database(e.to_string()))?;
+            }
+        }
+
+        // Retries that regenerated the vakya_id get the earlier answer; the
+        // content is claimed before anything is stored, so a concurrent retry
+        // cannot run alongside this submission
+        let reservation = match state.dedup {
+            Some(ref index) => match index.reserve(DedupIndex::content_key(&vakya)?, vakya.vakya_id.0.clone()) {
+                Claim::Reserved(reservation) => Some(reservation),
+                Claim::InProgress(prior_id) => {
+                    info!(vakya_id = %vakya.vakya_id, prior = %prior_id, "Duplicate VĀKYA content still being processed");
+                    return Err(GatewayError::Conflict(format!("Identical VĀKYA {} is still being processed", prior_id)));
+                }
+                Claim::Completed(prior_id) => {
+                    info!(vakya_id = %vakya.vakya_id, prior = %prior_id, "Duplicate VĀKYA content, returning earlier receipt");
+                    return deduplicated_response(state, &prior_id).await;
+                }
+            },
+            None => None,
+        };
+
+        let response = self.record_and_run(vakya, context, &sandhi, start, warnings).await;
+        // A failed submission gives up its claim, so a retry runs again
+        if let (Ok(_), Some(reservation)) = (&response, reservation) {
+            reservation.complete();
+        }
+        response
+    }
+
+    /// Store, evaluate and execute a validated, authorized VĀKYA
+    async fn record_and_run(
+        &self,
+        vakya: Vakya,
+        context: SubmissionContext,
+        sandhi: &SandhiOutput,
+        start: std::time::Instant,
+        warnings: Vec<ValidationWarning>,
+    ) -> GatewayResult<SubmitVakyaResponse> {
+        let state = &*self.state;
+
+        // Held until the submission returns; refuses with 503 when the pool is saturated
+        let _slot = state.acquire_execution_slot().await.map_err(|e| {
+            warn!(vakya_id = %vakya.vakya_id, "No execution slot available, refusing submission");
+            e
+        })?;
+        // A VĀKYA that must wait for its not_before time needs a deferred place too
+        let queued = match deferred_until(&vakya) {
+            Some(_) => Some(state.reserve_deferred_slot().await?),
+            None => None,
+        };
+
+        let vakya_hash = sandhi.vakya_hash.value.clone();
+
+        // Store the VĀKYA record
+        let mut record = VakyaRecord::new(
+            vakya.vakya_id.0.clone(),
+            vakya_hash.clone(),
+            vakya.v1_karta.pid.0.clone(),
+            vakya.v2_karma.rid.0.clone(),
+            vakya.v3_kriya.action.clone(),
+            serde_json::to_value(&vakya).unwrap_or_default(),
+        );
+
+        record.karta_type = format!("{:?}", vakya.v1_karta.actor_type).to_lowercase();
+        record.karma_kind = vakya.v2_karma.kind.clone();
+        record.karma_ns = vakya.v2_karma.ns.as_ref().map(|ns| ns.0.clone());
+        record.expected_effect = vakya.v3_kriya.expected_effect;
+        record.signature = context.signature;
+        record.key_id = context.key_id;
+
+        if let Some(ref trace) = vakya.meta.trace {
+            record.trace_id = Some(trace.trace_id.clone());
+            record.span_id = Some(trace.span_id.clone());
+            record.parent_span_id = trace.parent_span_id.clone();
+        }
+
+        let stored = state.index_db.store_vakya(record).await
+            .map_err(|e| match e {
+                IndexDbError::DuplicateVakya(id) => GatewayError::Conflict(format!("VĀKYA already submitted: {}", id)),
+                e => GatewayError::Database(e.to_string()),
+            })?;
+
+        // Evaluate policy before execution
+        let eval_ctx = policy_context(state, vakya.clone()).await;
+        let policy_decision = state.policy_engine.evaluate(&eval_ctx).await
+            .map_err(|e| GatewayError::Internal(format!("Policy evaluation failed: {}", e)))?;
+
+        info!(
+            vakya_id = %vakya.vakya_id,
+            decision = ?policy_decision.decision,
+            "Policy evaluation complete"
+        );
+
+        // Handle deny/pending_approval before execution
+        match policy_decision.decision {
+            DecisionType::Deny => {
+                let duration_ms = start.elapsed().as_millis() as i64;
+
+                // Record denial in metrics
+                {
+                    let mut metrics = state.metrics.write().await;
+                    metrics.record_auth_denial();
+                    metrics.record_request(&vakya.v3_kriya.action, &vakya.v1_karta.pid.0, false, duration_ms as f64);
+                    metrics.record_reason(ReasonCode::PolicyDenied);
+                }
+
+                // Create denial receipt
+                let receipt = ReceiptRecord::new(
+                    vakya.vakya_id.0.clone(),
+                    vakya_hash.clone(),
+                    ReasonCode::PolicyDenied,
+                    state.config.gateway_id.clone(),
+                    serde_json::json!({
+                        "status": "denied",
+                        "reason": policy_decision.reason,
+                    }),
+                );
+                let stored_receipt = state.index_db.store_receipt(state.sign_receipt(with_annotations(receipt, &policy_decision))?).await
+                    .map_err(|e| GatewayError::Database(e.to_string()))?;
+
+                record_audit(state, AuditLogEntry::new(
+                    AuditEventType::VakyaDenied,
+                    serde_json::json!({
+                        "vakya_id": vakya.vakya_id.0,
+                        "action": vakya.v3_kriya.action,
+                        "reason": policy_decision.reason,
+                        "matched_rules": policy_decision.matched_rules.iter().map(|r| &r.rule_id).collect::<Vec<_>>(),
+                    }),
+                )
+                .with_actor(vakya.v1_karta.pid.0.clone())
+                .with_target(vakya.v2_karma.rid.0.clone())).await;
+
+                return Ok(SubmitVakyaResponse {
+                    vakya_id: vakya.vakya_id.0,
+                    vakya_hash,
+                    status: "denied".to_string(),
+                    receipt: Some(ReceiptResponse {
+                        vakya_id: stored_receipt.vakya_id,
+                        vakya_hash: stored_receipt.vakya_hash,
+                        reason_code: stored_receipt.reason_code,
+                        message: Some(policy_decision.reason.clone()),
+                        duration_ms: Some(duration_ms),
+                        effect_ids: vec![],
+                        executor_id: stored_receipt.executor_id,
+                        created_at: stored_receipt.created_at.to_rfc3339(),
+                    }),
+                    merkle_root: stored.merkle_root,
+                    leaf_index: stored.leaf_index,
+                    policy_decision: Some(PolicyDecisionResponse {
+                        decision: "deny".to_string(),
+                        message: policy_decision.reason,
+                        matched_rules: Some(policy_decision.matched_rules.iter().map(|r| r.rule_name.clone()).collect()),
+                        approval_id: None,
+                    }),
+                    deduplicated: false,
+                    warnings,
+                });
+            }
+            DecisionType::PendingApproval => {
+                let duration_ms = start.elapsed().as_millis() as i64;
+                let requirement = policy_decision.required_approvals.first();
+                let approval_id = requirement
+                    .map(|r| r.approval_id.clone())
+                    .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+                // Persist the approval request so it can be voted on later
+                let mut approval = ApprovalRecord::new(
+                    approval_id.clone(),
+                    vakya.vakya_id.0.clone(),
+                    vakya_hash.clone(),
+                    requirement.map(|r| r.min_approvals).unwrap_or(1),
+                    policy_decision.reason.clone(),
+                );
+                approval.rule_id = policy_decision.matched_rules.last().map(|r| r.rule_id.clone());
+                if let Some(req) = requirement {
+                    approval.approvers = req.approvers.clone();
+                    approval.weighted_approvers = req.weighted_approvers.clone();
+                    approval.required_weight = req.required_weight;
+                    approval.approval_type = serde_json::to_value(req.approval_type)
+                        .ok()
+                        .and_then(|v| v.as_str().map(String::from))
+                        .unwrap_or_else(|| "human".to_string());
+                    approval.expires_at = req.timeout_secs
+                        .map(|secs| approval.created_at + chrono::Duration::seconds(secs as i64));
+                }
+                state.index_db.store_approval(approval).await
+                    .map_err(|e| GatewayError::Database(e.to_string()))?;
+
+                record_audit(state, AuditLogEntry::new(
+                    AuditEventType::ApprovalRequested,
+                    serde_json::json!({
+                        "vakya_id": vakya.vakya_id.0,
+                        "action": vakya.v3_kriya.action,
+                        "approval_id": approval_id,
+                        "reason": policy_decision.reason,
+                        "matched_rules": policy_decision.matched_rules.iter().map(|r| &r.rule_id).collect::<Vec<_>>(),
+                    }),
+                )
+                .with_actor(vakya.v1_karta.pid.0.clone())
+                .with_target(vakya.v2_karma.rid.0.clone())).await;
+
+                // Create pending approval receipt
+                let receipt = ReceiptRecord::new(
+                    vakya.vakya_id.0.clone(),
+                    vakya_hash.clone(),
+                    ReasonCode::ApprovalRequired,
+                    state.config.gateway_id.clone(),
+                    serde_json::json!({
+                        "status": "pending_approval",
+                        "approval_id": approval_id,
+                        "reason": policy_decision.reason,
+                    }),
+                );
+                let stored_receipt = state.index_db.store_receipt(state.sign_receipt(with_annotations(receipt, &policy_decision))?).await
+                    .map_err(|e| GatewayError::Database(e.to_string()))?;
+
+                return Ok(SubmitVakyaResponse {
+                    vakya_id: vakya.vakya_id.0,
+                    vakya_hash,
+                    status: "pending_approval".to_string(),
+                    receipt: Some(ReceiptResponse {
+                        vakya_id: stored_receipt.vakya_id,
+                        vakya_hash: stored_receipt.vakya_hash,
+                        reason_code: stored_receipt.reason_code,
+                        message: Some(policy_decision.reason.clone()),
+                        duration_ms: Some(duration_ms),
+                        effect_ids: vec![],
+                        executor_id: stored_receipt.executor_id,
+                        created_at: stored_receipt.created_at.to_rfc3339(),
+                    }),
+                    merkle_root: stored.merkle_root,
+                    leaf_index: stored.leaf_index,
+                    policy_decision: Some(PolicyDecisionResponse {
+                        decision: "pending_approval".to_string(),
+                        message: policy_decision.reason,
+                        matched_rules: Some(policy_decision.matched_rules.iter().map(|r| r.rule_name.clone()).collect()),
+                        approval_id: Some(approval_id),
+                    }),
+                    deduplicated: false,
+                    warnings,
+                });
+            }
+            _ => {
+                // Allow or NotApplicable - proceed with execution
+            }
+        }
+
+        // Hold the VĀKYA until its not_before time if it arrived early
+        if let (Some(not_before), Some(queued)) = (deferred_until(&vakya), queued) {
+            let receipt = deferred_receipt(state, &vakya.vakya_id.0, &vakya_hash, &not_before);
+            let stored_receipt = state.index_db.store_receipt(state.sign_receipt(with_annotations(receipt, &policy_decision))?).await
+                .map_err(|e| GatewayError::Database(e.to_string()))?;
+            spawn_deferred_execution(Arc::clone(&self.state), vakya.clone(), vakya_hash.clone(), not_before, queued);
+
+            return Ok(SubmitVakyaResponse {
+                vakya_id: vakya.vakya_id.0,
+                vakya_hash,
+                status: "deferred".to_string(),
+                receipt: Some(ReceiptResponse {
+                    vakya_id: stored_receipt.vakya_id,
+                    vakya_hash: stored_receipt.vakya_hash,
+                    reason_code: stored_receipt.reason_code,
+                    message: stored_receipt.message,
+                    duration_ms: None,
+                    effect_ids: vec![],
+                    executor_id: stored_receipt.executor_id,
+                    created_at: stored_receipt.created_at.to_rfc3339(),
+                }),
+                merkle_root: stored.merkle_root,
+                leaf_index: stored.leaf_index,
+                policy_decision: None,
+                deduplicated: false,
+                warnings,
+            });
+        }
+
+        // Execute the action and store its receipt
+        let outcome = execute_vakya(state, &vakya, start, context.progress.as_ref()).await?;
+        let duration_ms = outcome.duration_ms;
+        let (receipt, budget) = outcome.into_receipt(&vakya, &vakya_hash, &state.config.gateway_id);
+
+        let stored_receipt = state
+            .index_db
+            .store_receipt(state.sign_receipt(with_annotations(receipt, &policy_decision))?)
+            .await
+            .map_err(|e| GatewayError::Database(e.to_string()))?;
+        if let Some(budget) = budget {
+            budget.commit();
+        }
+
+        record_audit(state, AuditLogEntry::new(
+            AuditEventType::VakyaExecuted,
+            serde_json::json!({
+                "vakya_id": vakya.vakya_id.0,
+                "action": vakya.v3_kriya.action,
+                "reason_code": stored_receipt.reason_code,
+                "effect_ids": stored_receipt.effect_ids,
+            }),
+        )
+        .with_actor(vakya.v1_karta.pid.0.clone())
+        .with_target(vakya.v2_karma.rid.0.clone())).await;
+
+        Ok(SubmitVakyaResponse {
+            vakya_id: vakya.vakya_id.0,
+            vakya_hash,
+            status: submission_status(stored_receipt.reason_code).to_string(),
+            receipt: Some(ReceiptResponse {
+                vakya_id: stored_receipt.vakya_id,
+                vakya_hash: stored_receipt.vakya_hash,
+                reason_code: stored_receipt.reason_code,
+                message: stored_receipt.message,
+                duration_ms: Some(duration_ms),
+                effect_ids: stored_receipt.effect_ids,
+                executor_id: stored_receipt.executor_id,
+                created_at: stored_receipt.created_at.to_rfc3339(),
+            }),
+            merkle_root: stored.merkle_root,
+            leaf_index: stored.leaf_index,
+            policy_decision: None,
+            deduplicated: false,
+            warnings,
+        })
+    }
+}
+
+/// Submission status for an executed VĀKYA's reason code
+fn submission_status(reason_code: ReasonCode) -> &'static str {
+    match reason_code {
+        ReasonCode::PartialSuccess => "partial",
+        code if code.is_success() => "accepted",
+        _ => "failed",
+    }
+}
+
+/// What the submission recorded as `prior_id` was answered with
+async fn deduplicated_response(state: &AppState, prior_id: &str) -> GatewayResult<SubmitVakyaResponse> {
+    let prior = state.index_db.get_vakya(prior_id).await
+        .map_err(|e| GatewayError::Database(e.to_string()))?
+        .ok_or_else(|| GatewayError::Internal(format!("Deduplicated VĀKYA {} is not recorded", prior_id)))?;
+    // The receipt is only written once the earlier submission finishes
+    let receipt = state.index_db.get_receipt(prior_id).await
+        .map_err(|e| GatewayError::Database(e.to_string()))?
+        .ok_or_else(|| GatewayError::Conflict(format!("Identical VĀKYA {} is still being processed", prior_id)))?;
+
+    let status = match receipt.receipt_json.get("status").and_then(|s| s.as_str()) {
+        Some("success") => "accepted",
+        Some("partial") => "partial",
+        Some(status @ ("denied" | "pending_approval" | "deferred")) => status,
+        _ => "failed",
+    };
+
+    Ok(SubmitVakyaResponse {
+        vakya_id: prior.vakya_id,
+        vakya_hash: prior.vakya_hash,
+        status: status.to_string(),
+        receipt: Some(ReceiptResponse {
+            vakya_id: receipt.vakya_id,
+            vakya_hash: receipt.vakya_hash,
+            reason_code: receipt.reason_code,
+            message: receipt.message,
+            duration_ms: receipt.duration_ms,
+            effect_ids: receipt.effect_ids,
+            executor_id: receipt.executor_id,
+            created_at: receipt.created_at.to_rfc3339(),
+        }),
+        merkle_root: prior.merkle_root,
+        leaf_index: prior.leaf_index,
+        policy_decision: None,
+        deduplicated: true,
+        warnings: vec![],
+    })
+}
@@ -1,19 +1,25 @@
 //! Error types for the Gateway
 
 use axum::{
-    http::StatusCode,
+    http::{header, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
 use serde::Serialize;
 use thiserror::Error;
 
+use aapi_core::ValidationResult;
+
 /// Gateway errors
 #[derive(Error, Debug)]
 pub enum GatewayError {
     #[error("Validation error: {0}")]
     Validation(String),
 
+    /// Every problem the VĀKYA validator found, rather than the first
+    #[error("Validation failed: {}", validation_summary(.0))]
+    InvalidVakya(ValidationResult),
+
     #[error("Authorization denied: {0}")]
     AuthorizationDenied(String),
 
@@ -26,8 +32,11 @@ pub enum GatewayError {
     #[error("Conflict: {0}")]
     Conflict(String),
 
-    #[error("Rate limited")]
-    RateLimited,
+    #[error("Rate limited, retry after {retry_after_secs}s")]
+    RateLimited { retry_after_secs: u64 },
+
+    #[error("Gateway overloaded, retry after {retry_after_secs}s")]
+    Overloaded { retry_after_secs: u64 },
 
     #[error("Adapter error: {0}")]
     Adapter(String),
@@ -53,6 +62,17 @@ impl IntoResponse for GatewayError {
                     details: None,
                 },
             ),
+            GatewayError::InvalidVakya(result) => (
+                StatusCode::BAD_REQUEST,
+                ErrorResponse {
+                    error: "VALIDATION_ERROR".to_string(),
+                    message: validation_summary(result),
+                    details: Some(serde_json::json!({
+                        "errors": result.errors,
+                        "warnings": result.warnings,
+                    })),
+                },
+            ),
             GatewayError::AuthorizationDenied(msg) => (
                 StatusCode::FORBIDDEN,
                 ErrorResponse {
@@ -85,7 +105,7 @@ impl IntoResponse for GatewayError {
                     details: None,
                 },
             ),
-            GatewayError::RateLimited => (
+            GatewayError::RateLimited { .. } => (
                 StatusCode::TOO_MANY_REQUESTS,
                 ErrorResponse {
                     error: "RATE_LIMITED".to_string(),
@@ -93,6 +113,14 @@ impl IntoResponse for GatewayError {
                     details: None,
                 },
             ),
+            GatewayError::Overloaded { .. } => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                ErrorResponse {
+                    error: "OVERLOADED".to_string(),
+                    message: "No execution slot available".to_string(),
+                    details: None,
+                },
+            ),
             GatewayError::Adapter(msg) => (
                 StatusCode::BAD_GATEWAY,
                 ErrorResponse {
@@ -127,7 +155,11 @@ impl IntoResponse for GatewayError {
             ),
         };
 
-        (status, Json(error_response)).into_response()
+        let mut response = (status, Json(error_response)).into_response();
+        if let GatewayError::Overloaded { retry_after_secs } | GatewayError::RateLimited { retry_after_secs } = self {
+            response.headers_mut().insert(header::RETRY_AFTER, retry_after_secs.into());
+        }
+        response
     }
 }
 
@@ -141,3 +173,11 @@ pub struct ErrorResponse {
 }
 
 pub type GatewayResult<T> = Result<T, GatewayError>;
+
+/// Each validation error as `path: message`, in the order found
+fn validation_summary(result: &ValidationResult) -> String {
+    result.errors.iter()
+        .map(|e| format!("{}: {}", e.path, e.message))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
@@ -1,29 +1,54 @@
 //! HTTP request handlers for the Gateway
 
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
+    body::{Body, Bytes},
+    extract::{FromRequest, Path, Query, Request, State},
+    http::{header, HeaderMap},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
     Json,
 };
+use futures::Stream;
 use chrono::Utc;
+use prost::Message;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::convert::Infallible;
 use std::sync::Arc;
+use tokio::sync::mpsc::{self, UnboundedSender};
+use tokio::sync::OwnedSemaphorePermit;
 use tracing::{debug, info, warn};
 
-use aapi_adapters::ExecutionContext;
+use aapi_adapters::{ActionPlan, CapturedEffect, ChangeType, ExecutionContext, ExecutionEvent, JsonPatchOp, StateDelta};
 use aapi_core::{
-    Vakya, VakyaId, canonicalize,
+    BudgetReservation, CapabilityRef, CapabilityToken, SandhiOutput, Vakya, canonicalize, unknown_vakya_fields, ValidationWarning,
     error::ReasonCode,
     types::Timestamp,
 };
-use aapi_crypto::SignedVakya;
+use aapi_core::proto::pb;
+use aapi_core::types::EffectBucket;
+use aapi_crypto::{BundleEntry, ExportBundle, JwkSet, KeyId, ProofStep, PublicKeyInfo, SignedTreeHead, SignedVakya};
 use aapi_indexdb::{
     VakyaRecord, EffectRecord, ReceiptRecord,
-    TreeType, IndexDbStore,
+    ApprovalRecord, ApprovalRecordStatus, ApprovalVote, VoteDecision, CapabilityRevocation,
+    TreeType, IndexDbError, VakyaQuery, ExportFilter, ProofPosition, InclusionProof, models::ConsistencyProof,
+    AuditLogEntry, AuditEventType, AuditFilter, CacheStats, Page, QueryResult,
+};
+use aapi_metarules::{
+    ApprovalRequirement, EvaluationContext, DecisionType, DecisionTrace, MatchedRule, Policy, PolicyDecision,
+    PolicyEngineBuilder, Reversibility,
 };
-use aapi_metarules::{EvaluationContext, DecisionType};
 
+use crate::engine::{Engine, SubmissionContext};
 use crate::error::{GatewayError, GatewayResult};
+use crate::middleware::apply_budget_headers;
+use crate::metrics::{render_cache_prometheus, render_prometheus, LatencyPercentiles, PROMETHEUS_CONTENT_TYPE};
+use crate::identity::Caller;
+use crate::namespace::CallerScope;
+use crate::tls::PeerIdentity;
+use crate::replay::{Replayer, ReplayReport};
 use crate::state::AppState;
 
 /// Health check response
@@ -55,6 +80,25 @@ pub struct SubmitVakyaRequest {
     pub key_id: Option<String>,
 }
 
+/// Content type selecting the protobuf encoding of `POST /v1/vakya`
+pub const PROTOBUF_CONTENT_TYPE: &str = "application/protobuf";
+
+impl SubmitVakyaRequest {
+    /// Decode a protobuf `aapi.v1.SubmitVakyaRequest`
+    pub fn from_protobuf(bytes: &[u8]) -> GatewayResult<Self> {
+        let message = pb::SubmitVakyaRequest::decode(bytes)
+            .map_err(|e| GatewayError::Validation(format!("Invalid protobuf request: {}", e)))?;
+        let vakya = message.vakya
+            .ok_or_else(|| GatewayError::Validation("Missing vakya".to_string()))?;
+
+        Ok(Self {
+            vakya: Vakya::try_from(vakya).map_err(|e| GatewayError::Validation(e.to_string()))?,
+            signature: message.signature,
+            key_id: message.key_id,
+        })
+    }
+}
+
 /// Submit VĀKYA response
 #[derive(Debug, Serialize)]
 pub struct SubmitVakyaResponse {
@@ -66,6 +110,12 @@ pub struct SubmitVakyaResponse {
     pub leaf_index: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub policy_decision: Option<PolicyDecisionResponse>,
+    /// The content matched an earlier submission within the dedup window;
+    /// the fields describe that submission and nothing was executed
+    pub deduplicated: bool,
+    /// Validation warnings the VĀKYA was accepted with
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<ValidationWarning>,
 }
 
 /// Policy decision response (for deny/pending_approval)
@@ -92,43 +142,73 @@ pub struct ReceiptResponse {
     pub created_at: String,
 }
 
-/// Submit a VĀKYA for execution
-pub async fn submit_vakya(
-    State(state): State<Arc<AppState>>,
-    Json(request): Json<SubmitVakyaRequest>,
-) -> GatewayResult<Json<SubmitVakyaResponse>> {
-    let start = std::time::Instant::now();
-    let vakya = request.vakya;
-    
-    info!(vakya_id = %vakya.vakya_id, action = %vakya.v3_kriya.action, "Received VĀKYA submission");
+/// Verify an inline capability token against the VĀKYA it authorizes
+///
+/// The token must be issued to the Kartā, pass signature, time and caveat
+/// checks, and cover the requested action and resource. `ip_address`
+/// caveats are checked against the peer's address; any other caveat the
+/// gateway cannot evaluate rejects the token.
+fn verify_inline_capability(state: &AppState, vakya: &Vakya, token: &CapabilityToken, peer: &PeerIdentity) -> GatewayResult<()> {
+    if token.subject != vakya.v1_karta.pid {
+        warn!(vakya_id = %vakya.vakya_id, subject = %token.subject.0, "Inline capability subject mismatch");
+        return Err(GatewayError::AuthorizationDenied(format!(
+            "Capability subject {} does not match kartā {}",
+            token.subject.0, vakya.v1_karta.pid.0
+        )));
+    }
 
-    // Validate the VĀKYA
-    if let Err(e) = vakya.validate() {
-        warn!(vakya_id = %vakya.vakya_id, error = %e, "VĀKYA validation failed");
-        return Err(GatewayError::Validation(e.to_string()));
+    let token = aapi_crypto::CapabilityToken::try_from(token)
+        .map_err(|e| GatewayError::AuthorizationDenied(format!("Malformed inline capability: {}", e)))?;
+
+    let context = aapi_crypto::CaveatContext {
+        client_ip: peer.addr.map(|addr| addr.ip()),
+        ..Default::default()
+    };
+    let decision = state.cap_verifier
+        .verify_access_with_context(&token, &vakya.v3_kriya.action, &vakya.v2_karma.rid.0, &context)
+        .map_err(|e| GatewayError::AuthorizationDenied(format!("Capability verification error: {}", e)))?;
+
+    if !decision.allowed {
+        warn!(vakya_id = %vakya.vakya_id, token_id = %token.token_id, reason = %decision.reason, "Inline capability rejected");
+        return Err(GatewayError::AuthorizationDenied(format!("Capability rejected: {}", decision.reason)));
     }
 
+    info!(vakya_id = %vakya.vakya_id, token_id = %token.token_id, "Inline capability verified");
+    Ok(())
+}
+
+/// Check the request signature (when required) and any inline capability
+pub(crate) fn authorize_submission(
+    state: &AppState,
+    vakya: &Vakya,
+    sandhi: &SandhiOutput,
+    signature: Option<&String>,
+    key_id: Option<&String>,
+    peer: &PeerIdentity,
+) -> GatewayResult<()> {
     // Production mode security checks
     if state.config.signatures_required() {
-        match (&request.signature, &request.key_id) {
+        match (signature, key_id) {
             (Some(sig), Some(key_id)) => {
+                let key_id_ref = aapi_crypto::KeyId(key_id.clone());
+                // The signature is checked with the algorithm of the key it names
+                let algorithm = state.key_store.get_public_key(&key_id_ref)
+                    .and_then(|info| info.signature_algorithm())
+                    .unwrap_or_default();
+
                 // Build SignedVakya for verification
                 let signed = SignedVakya {
                     vakya: vakya.clone(),
-                    vakya_hash: {
-                        let sandhi = canonicalize(&vakya)
-                            .map_err(|e| GatewayError::Internal(e.to_string()))?;
-                        sandhi.vakya_hash.value.clone()
-                    },
+                    vakya_hash: sandhi.vakya_hash.value.clone(),
                     signature: aapi_crypto::VakyaSignature {
-                        key_id: aapi_crypto::KeyId(key_id.clone()),
-                        algorithm: aapi_crypto::SignatureAlgorithm::Ed25519,
+                        key_id: key_id_ref,
+                        algorithm,
                         value: sig.clone(),
                         signed_at: chrono::Utc::now(),
                     },
                 };
 
-                match state.verifier.verify(&signed) {
+                match state.verifier.verify_canonical(&signed, sandhi) {
                     Ok(result) if result.valid => {
                         info!(vakya_id = %vakya.vakya_id, "Signature verified");
                     }
@@ -165,161 +245,511 @@ pub async fn submit_vakya(
         }
     }
 
-    // Note: Capability verification requires a CapabilityToken, which is not part of the
-    // current request schema. For now, we log a warning if capabilities are required but
-    // no token is provided. Full capability enforcement requires extending the request schema.
-    if state.config.capabilities_required() {
-        // TODO: Add capability_token to SubmitVakyaRequest and verify here
-        // For now, log that capability verification is enabled but not enforced
-        info!(
-            vakya_id = %vakya.vakya_id,
-            "Capability verification enabled (token-based verification pending)"
-        );
+    // Self-contained requests carry their capability inline
+    if let CapabilityRef::Inline(ref token) = vakya.v7_adhikarana.cap {
+        verify_inline_capability(state, vakya, token, peer)?;
     }
 
-    // Canonicalize and hash
-    let sandhi = canonicalize(&vakya)
-        .map_err(|e| GatewayError::Internal(e.to_string()))?;
-    
-    let vakya_hash = sandhi.vakya_hash.value.clone();
-
-    // Store the VĀKYA record
-    let mut record = VakyaRecord::new(
-        vakya.vakya_id.0.clone(),
-        vakya_hash.clone(),
-        vakya.v1_karta.pid.0.clone(),
-        vakya.v2_karma.rid.0.clone(),
-        vakya.v3_kriya.action.clone(),
-        serde_json::to_value(&vakya).unwrap_or_default(),
-    );
-    
-    record.karta_type = format!("{:?}", vakya.v1_karta.actor_type).to_lowercase();
-    record.karma_kind = vakya.v2_karma.kind.clone();
-    record.expected_effect = vakya.v3_kriya.expected_effect;
-    record.signature = request.signature;
-    record.key_id = request.key_id;
-    
-    if let Some(ref trace) = vakya.meta.trace {
-        record.trace_id = Some(trace.trace_id.clone());
-        record.span_id = Some(trace.span_id.clone());
-        record.parent_span_id = trace.parent_span_id.clone();
+    Ok(())
+}
+
+/// Submit a VĀKYA encoded as JSON, or as protobuf when the request has
+/// `Content-Type: application/protobuf`
+///
+/// Both encodings decode to the same VĀKYA, so its hash and any signature
+/// over it do not depend on the encoding used.
+pub async fn submit_vakya_encoded(
+    State(state): State<Arc<AppState>>,
+    peer: PeerIdentity,
+    request: Request,
+) -> Result<(HeaderMap, Json<SubmitVakyaResponse>), Response> {
+    let submission = if is_protobuf(request.headers()) {
+        let body = Bytes::from_request(request, &state).await
+            .map_err(IntoResponse::into_response)?;
+        SubmitVakyaRequest::from_protobuf(&body).map_err(IntoResponse::into_response)?
+    } else if state.config.reject_unknown_fields {
+        let raw = Json::<serde_json::Value>::from_request(request, &state).await
+            .map_err(IntoResponse::into_response)?
+            .0;
+        reject_unknown_fields(&raw).map_err(IntoResponse::into_response)?;
+        serde_json::from_value(raw)
+            .map_err(|e| GatewayError::Validation(format!("Invalid submission: {}", e)).into_response())?
+    } else {
+        Json::<SubmitVakyaRequest>::from_request(request, &state).await
+            .map_err(IntoResponse::into_response)?
+            .0
+    };
+
+    let principal = submission.vakya.v1_karta.pid.0.clone();
+    let budgets = submission.vakya.v7_adhikarana.budgets.clone();
+    let result = submit_vakya(State(state.clone()), peer, Json(submission)).await;
+
+    // Report what is left whether or not this submission went through
+    let mut headers = HeaderMap::new();
+    apply_budget_headers(&mut headers, &state.budget_tracker, &principal, &budgets);
+    match result {
+        Ok(response) => Ok((headers, response)),
+        Err(e) => Err((headers, e).into_response()),
     }
+}
 
-    let stored = state.index_db.store_vakya(record).await
-        .map_err(|e| GatewayError::Database(e.to_string()))?;
+/// Refuse a submission whose VĀKYA carries fields `Vakya` would drop
+fn reject_unknown_fields(raw: &serde_json::Value) -> GatewayResult<()> {
+    let Some(vakya) = raw.get("vakya") else {
+        return Ok(());
+    };
+    let unknown = unknown_vakya_fields(vakya)
+        .map_err(|e| GatewayError::Validation(format!("Invalid VĀKYA: {}", e)))?;
+    if unknown.is_empty() {
+        return Ok(());
+    }
+    Err(GatewayError::Validation(format!(
+        "SchemaViolation: unknown VĀKYA fields: {}",
+        unknown.join(", ")
+    )))
+}
+
+fn is_protobuf(headers: &HeaderMap) -> bool {
+    headers.get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(';').next())
+        .is_some_and(|mime| {
+            let mime = mime.trim();
+            mime.eq_ignore_ascii_case(PROTOBUF_CONTENT_TYPE)
+                || mime.eq_ignore_ascii_case("application/x-protobuf")
+        })
+}
+
+/// Effect a planned VĀKYA is expected to have
+#[derive(Debug, Serialize)]
+pub struct PlannedEffect {
+    pub bucket: EffectBucket,
+    pub target: String,
+    /// Whether the effect could be rolled back; read-only effects have
+    /// nothing to undo and count as reversible
+    pub reversible: bool,
+}
+
+/// What submitting a VĀKYA would do
+#[derive(Debug, Serialize)]
+pub struct VakyaPlanResponse {
+    pub vakya_id: String,
+    pub action: String,
+    pub decision: DecisionType,
+    pub reason: String,
+    pub matched_rules: Vec<MatchedRule>,
+    /// Approvals the VĀKYA would wait for when the decision is
+    /// `pending_approval`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub required_approvals: Vec<ApprovalRequirement>,
+    /// Adapter the action resolves to; `None` if no adapter supports it
+    pub adapter: Option<ActionPlan>,
+    pub effects: Vec<PlannedEffect>,
+    /// Whether every predicted effect could be undone
+    pub reversible: bool,
+    /// Adapter output from the dry run; absent unless the policy allows the
+    /// action and an adapter supports it
+    pub dry_run: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dry_run_error: Option<String>,
+}
+
+/// Plan a VĀKYA without executing it
+///
+/// Runs validation, authorization, policy evaluation and an adapter dry run,
+/// and reports the predicted effects and whether they could be undone.
+/// Nothing is stored and no receipt is issued; adapters are expected to
+/// honour `ExecutionContext::dry_run`. Dry runs can still read state, so
+/// they only happen for actions the policy allows outright: a VĀKYA awaiting
+/// approval gets its approval requirements instead. A dry run takes an
+/// execution slot like a submission, and is refused with 503 when none is free.
+pub async fn plan_vakya(
+    State(state): State<Arc<AppState>>,
+    peer: PeerIdentity,
+    Json(request): Json<SubmitVakyaRequest>,
+) -> GatewayResult<Json<VakyaPlanResponse>> {
+    let vakya = request.vakya;
 
-    // Evaluate policy before execution
-    let eval_ctx = EvaluationContext::new(vakya.clone());
+    peer.check_principal(state.config.tls.as_ref(), &vakya.v1_karta.pid.0)?;
+    let validation = state.config.validator().validate(&vakya);
+    if !validation.valid {
+        return Err(GatewayError::InvalidVakya(validation));
+    }
+    let sandhi = canonicalize(&vakya).map_err(|e| GatewayError::Internal(e.to_string()))?;
+    authorize_submission(&state, &vakya, &sandhi, request.signature.as_ref(), request.key_id.as_ref(), &peer)?;
+
+    let adapter = state.dispatcher.plan(&vakya.v3_kriya.action).await;
+    let eval_ctx = with_reversibility(EvaluationContext::new(vakya.clone()), adapter.as_ref());
     let policy_decision = state.policy_engine.evaluate(&eval_ctx).await
         .map_err(|e| GatewayError::Internal(format!("Policy evaluation failed: {}", e)))?;
+    let mut effects = Vec::new();
+    let mut dry_run = None;
+    let mut dry_run_error = None;
 
-    info!(
-        vakya_id = %vakya.vakya_id,
-        decision = ?policy_decision.decision,
-        "Policy evaluation complete"
-    );
+    if let Some(ref plan) = adapter {
+        let bucket = plan.descriptor.as_ref()
+            .map(|d| d.effect_bucket)
+            .unwrap_or(vakya.v3_kriya.expected_effect);
+        effects.push(PlannedEffect {
+            bucket,
+            target: vakya.v2_karma.rid.0.clone(),
+            reversible: bucket.is_read_only() || plan.reversible(),
+        });
 
-    // Handle deny/pending_approval before execution
-    match policy_decision.decision {
-        DecisionType::Deny => {
-            let duration_ms = start.elapsed().as_millis() as i64;
-            
-            // Record denial in metrics
-            {
-                let mut metrics = state.metrics.write().await;
-                metrics.record_auth_denial();
-                metrics.record_request(&vakya.v3_kriya.action, &vakya.v1_karta.pid.0, false, duration_ms as f64);
+        if policy_decision.decision == DecisionType::Allow {
+            // Dry runs still read from adapters, so they share the execution limit
+            let _slot = state.acquire_execution_slot().await?;
+            let mut exec_ctx = ExecutionContext::new(vakya.vakya_id.0.clone()).dry_run();
+            exec_ctx.timeout_ms = Some(execution_timeout_ms(&state, &vakya, Utc::now()));
+            match state.dispatcher.dispatch(&vakya, &exec_ctx).await {
+                Ok(result) if result.success => dry_run = result.data,
+                Ok(result) => dry_run_error = result.error,
+                Err(e) => dry_run_error = Some(e.to_string()),
             }
+        }
+    }
 
-            // Create denial receipt
-            let receipt = ReceiptRecord::new(
-                vakya.vakya_id.0.clone(),
-                vakya_hash.clone(),
-                ReasonCode::PolicyDenied,
-                state.config.gateway_id.clone(),
-                serde_json::json!({
-                    "status": "denied",
-                    "reason": policy_decision.reason,
-                }),
-            );
-            let stored_receipt = state.index_db.store_receipt(receipt).await
-                .map_err(|e| GatewayError::Database(e.to_string()))?;
+    debug!(vakya_id = %vakya.vakya_id, decision = ?policy_decision.decision, "Planned VĀKYA");
 
-            return Ok(Json(SubmitVakyaResponse {
-                vakya_id: vakya.vakya_id.0,
-                vakya_hash,
-                status: "denied".to_string(),
-                receipt: Some(ReceiptResponse {
-                    vakya_id: stored_receipt.vakya_id,
-                    vakya_hash: stored_receipt.vakya_hash,
-                    reason_code: stored_receipt.reason_code,
-                    message: Some(policy_decision.reason.clone()),
-                    duration_ms: Some(duration_ms),
-                    effect_ids: vec![],
-                    executor_id: stored_receipt.executor_id,
-                    created_at: stored_receipt.created_at.to_rfc3339(),
-                }),
-                merkle_root: stored.merkle_root,
-                leaf_index: stored.leaf_index,
-                policy_decision: Some(PolicyDecisionResponse {
-                    decision: "deny".to_string(),
-                    message: policy_decision.reason,
-                    matched_rules: Some(policy_decision.matched_rules.iter().map(|r| r.rule_name.clone()).collect()),
-                    approval_id: None,
-                }),
-            }));
+    Ok(Json(VakyaPlanResponse {
+        vakya_id: vakya.vakya_id.0,
+        action: vakya.v3_kriya.action,
+        decision: policy_decision.decision,
+        reason: policy_decision.reason,
+        matched_rules: policy_decision.matched_rules,
+        required_approvals: policy_decision.required_approvals,
+        reversible: !effects.is_empty() && effects.iter().all(|e| e.reversible),
+        adapter,
+        effects,
+        dry_run,
+        dry_run_error,
+    }))
+}
+
+/// Submit a VĀKYA for execution
+pub async fn submit_vakya(
+    State(state): State<Arc<AppState>>,
+    peer: PeerIdentity,
+    Json(request): Json<SubmitVakyaRequest>,
+) -> GatewayResult<Json<SubmitVakyaResponse>> {
+    let context = SubmissionContext {
+        signature: request.signature,
+        key_id: request.key_id,
+        peer,
+        progress: None,
+    };
+    Engine::new(state).execute(request.vakya, context).await.map(Json)
+}
+
+/// Submit a VĀKYA and follow its execution as server-sent events
+///
+/// Streams a `progress` event for each update the adapter reports, then a
+/// final `result` event carrying what `POST /v1/vakya` would return, or an
+/// `error` event if the submission was refused.
+pub async fn submit_vakya_stream(
+    State(state): State<Arc<AppState>>,
+    peer: PeerIdentity,
+    Json(request): Json<SubmitVakyaRequest>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let (events, receiver) = mpsc::unbounded_channel();
+    let (progress, mut progress_events) = mpsc::unbounded_channel();
+    let context = SubmissionContext {
+        signature: request.signature,
+        key_id: request.key_id,
+        peer,
+        progress: Some(progress),
+    };
+
+    let engine = Engine::new(state);
+    tokio::spawn(async move {
+        let forward = async {
+            while let Some(event) = progress_events.recv().await {
+                let _ = events.send(sse_json("progress", &event));
+            }
+        };
+        // Forwarding ends once the engine drops the context's sender
+        let (result, ()) = tokio::join!(engine.execute(request.vakya, context), forward);
+        let last = match result {
+            Ok(response) => sse_json("result", &response),
+            Err(e) => sse_json("error", &serde_json::json!({ "error": e.to_string() })),
+        };
+        let _ = events.send(last);
+    });
+
+    let stream = futures::stream::unfold(receiver, |mut receiver| async move {
+        receiver.recv().await.map(|event| (Ok(event), receiver))
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+fn sse_json(name: &str, data: &impl Serialize) -> Event {
+    Event::default()
+        .event(name)
+        .json_data(data)
+        .unwrap_or_else(|e| Event::default().event("error").data(e.to_string()))
+}
+
+/// Outcome of dispatching a VĀKYA to its adapter
+pub(crate) struct ExecutionOutcome<'a> {
+    reason_code: ReasonCode,
+    message: Option<String>,
+    result_json: serde_json::Value,
+    pub(crate) duration_ms: i64,
+    effect_ids: Vec<String>,
+    budget: Option<BudgetReservation<'a>>,
+}
+
+impl<'a> ExecutionOutcome<'a> {
+    /// The receipt for this outcome, with the budget reservation it
+    /// charged; commit the reservation once the receipt is stored, or
+    /// drop it to refund the charges
+    pub(crate) fn into_receipt(
+        self,
+        vakya: &Vakya,
+        vakya_hash: &str,
+        executor_id: &str,
+    ) -> (ReceiptRecord, Option<BudgetReservation<'a>>) {
+        let mut receipt = ReceiptRecord::new(
+            vakya.vakya_id.0.clone(),
+            vakya_hash.to_string(),
+            self.reason_code,
+            executor_id.to_string(),
+            self.result_json,
+        );
+        receipt.message = self.message;
+        receipt.duration_ms = Some(self.duration_ms);
+        receipt.effect_ids = self.effect_ids;
+        (receipt, self.budget)
+    }
+}
+
+/// Execution budget in milliseconds: the gateway's request timeout,
+/// narrowed by the VĀKYA's `ttl.max_duration_ms` and by the time left
+/// before `ttl.expires_at`
+fn execution_timeout_ms(state: &AppState, vakya: &Vakya, now: chrono::DateTime<Utc>) -> u64 {
+    let mut timeout_ms = state.config.request_timeout_secs.saturating_mul(1000);
+    if let Some(ref ttl) = vakya.v7_adhikarana.ttl {
+        if let Some(max_duration_ms) = ttl.max_duration_ms {
+            timeout_ms = timeout_ms.min(max_duration_ms);
         }
-        DecisionType::PendingApproval => {
-            let duration_ms = start.elapsed().as_millis() as i64;
-            let approval_id = uuid::Uuid::new_v4().to_string();
+        let remaining_ms = (ttl.expires_at.0 - now).num_milliseconds().max(0) as u64;
+        timeout_ms = timeout_ms.min(remaining_ms);
+    }
+    timeout_ms
+}
+
+/// Effects that diverge from the VĀKYA's declared `expected_effect`,
+/// described as `<bucket> on <target>`
+///
+/// An undeclared (`None`) expectation is not checked, and read-only effects
+/// never diverge: only a mutation other than the declared one is reported.
+fn effect_divergences(vakya: &Vakya, effects: &[CapturedEffect]) -> Vec<String> {
+    let expected = vakya.v3_kriya.expected_effect;
+    if expected == EffectBucket::None {
+        return Vec::new();
+    }
+
+    effects.iter()
+        .filter(|effect| effect.bucket != expected && effect.bucket.is_mutating())
+        .map(|effect| format!("{:?} on {}", effect.bucket, effect.target))
+        .collect()
+}
+
+/// Policy context for a VĀKYA, with what the adapter it resolves to
+/// declares about undoing the action
+pub(crate) async fn policy_context(state: &AppState, vakya: Vakya) -> EvaluationContext {
+    let plan = state.dispatcher.plan(&vakya.v3_kriya.action).await;
+    with_reversibility(EvaluationContext::new(vakya), plan.as_ref())
+}
+
+fn with_reversibility(context: EvaluationContext, plan: Option<&ActionPlan>) -> EvaluationContext {
+    match plan {
+        Some(plan) => context.with_reversibility(Reversibility::new(
+            plan.can_rollback,
+            plan.descriptor.as_ref().map(|d| d.reversible),
+        )),
+        None => context,
+    }
+}
+
+/// Record the values captured by the policy decision in a receipt
+pub(crate) fn with_annotations(mut receipt: ReceiptRecord, decision: &PolicyDecision) -> ReceiptRecord {
+    let annotations = decision.annotations();
+    if !annotations.is_empty() {
+        receipt.receipt_json["annotations"] = serde_json::Value::Object(annotations);
+    }
+    receipt
+}
+
+/// The `ttl.not_before` time if the VĀKYA must not run yet
+pub(crate) fn deferred_until(vakya: &Vakya) -> Option<Timestamp> {
+    vakya.v7_adhikarana.ttl.as_ref()
+        .filter(|ttl| ttl.is_deferred())
+        .and_then(|ttl| ttl.not_before.clone())
+}
+
+/// Placeholder receipt for a VĀKYA waiting on its `not_before` time
+pub(crate) fn deferred_receipt(state: &AppState, vakya_id: &str, vakya_hash: &str, not_before: &Timestamp) -> ReceiptRecord {
+    let mut receipt = ReceiptRecord::new(
+        vakya_id.to_string(),
+        vakya_hash.to_string(),
+        ReasonCode::Deferred,
+        state.config.gateway_id.clone(),
+        serde_json::json!({
+            "status": "deferred",
+            "not_before": not_before.to_string(),
+        }),
+    );
+    receipt.message = Some(format!("Execution deferred until {}", not_before));
+    receipt
+}
+
+/// Run a deferred VĀKYA once its `not_before` time has passed
+///
+/// `queued` is the VĀKYA's place from [`AppState::reserve_deferred_slot`],
+/// held until it has run. Once due it waits for an execution slot like any
+/// other submission. A restart drops the waiting task but not the deferred
+/// receipt, from which [`resume_deferred_executions`] queues it again.
+pub(crate) fn spawn_deferred_execution(
+    state: Arc<AppState>,
+    vakya: Vakya,
+    vakya_hash: String,
+    not_before: Timestamp,
+    queued: OwnedSemaphorePermit,
+) {
+    tokio::spawn(async move {
+        let _queued = queued;
+        let wait = (not_before.0 - Utc::now()).to_std().unwrap_or_default();
+        tokio::time::sleep(wait).await;
+        let Ok(_slot) = Arc::clone(&state.execution_slots).acquire_owned().await else {
+            return;
+        };
+        if let Err(e) = run_deferred(&state, &vakya, &vakya_hash).await {
+            warn!(vakya_id = %vakya.vakya_id, error = %e, "Deferred VĀKYA execution failed");
+        }
+    });
+}
+
+/// Queue every VĀKYA whose stored receipt is still deferred, returning how
+/// many were queued
+///
+/// Called when a gateway starts. VĀKYAs that no longer fit in the deferred
+/// queue stay deferred and are picked up by a later start.
+pub async fn resume_deferred_executions(state: &Arc<AppState>) -> GatewayResult<usize> {
+    let receipts = state.index_db.receipts_with_reason(ReasonCode::Deferred).await
+        .map_err(|e| GatewayError::Database(e.to_string()))?;
+
+    let mut queued = 0;
+    for receipt in receipts {
+        let Some(record) = state.index_db.get_vakya(&receipt.vakya_id).await
+            .map_err(|e| GatewayError::Database(e.to_string()))?
+        else {
+            warn!(vakya_id = %receipt.vakya_id, "Deferred receipt has no stored VĀKYA");
+            continue;
+        };
+        let vakya: Vakya = serde_json::from_value(record.vakya_json)?;
+        let Ok(slot) = state.reserve_deferred_slot().await else {
+            warn!(queued, "Deferred queue is full; remaining VĀKYAs stay deferred");
+            break;
+        };
+        // Past its not_before time already: run it straight away
+        let not_before = deferred_until(&vakya).unwrap_or_else(Timestamp::now);
+        spawn_deferred_execution(Arc::clone(state), vakya, receipt.vakya_hash, not_before, slot);
+        queued += 1;
+    }
 
-            // Create pending approval receipt
-            let receipt = ReceiptRecord::new(
+    if queued > 0 {
+        info!(count = queued, "Resumed deferred VĀKYA executions");
+    }
+    Ok(queued)
+}
+
+/// Validate and execute a deferred VĀKYA, replacing its placeholder receipt
+async fn run_deferred(state: &AppState, vakya: &Vakya, vakya_hash: &str) -> GatewayResult<()> {
+    let start = std::time::Instant::now();
+    let (receipt, budget) = match vakya.validate() {
+        Ok(()) => execute_vakya(state, vakya, start, None).await?
+            .into_receipt(vakya, vakya_hash, &state.config.gateway_id),
+        Err(e) => {
+            // The VĀKYA may have expired while it was waiting
+            let mut receipt = ReceiptRecord::new(
                 vakya.vakya_id.0.clone(),
-                vakya_hash.clone(),
-                ReasonCode::ApprovalRequired,
+                vakya_hash.to_string(),
+                ReasonCode::ValidationFailed,
                 state.config.gateway_id.clone(),
                 serde_json::json!({
-                    "status": "pending_approval",
-                    "approval_id": approval_id,
-                    "reason": policy_decision.reason,
+                    "status": "failed",
+                    "error": e.to_string(),
                 }),
             );
-            let stored_receipt = state.index_db.store_receipt(receipt).await
-                .map_err(|e| GatewayError::Database(e.to_string()))?;
+            receipt.message = Some(e.to_string());
+            (receipt, None)
+        }
+    };
+    let receipt = state.index_db.update_receipt(state.sign_receipt(receipt)?).await
+        .map_err(|e| GatewayError::Database(e.to_string()))?;
+    if let Some(budget) = budget {
+        budget.commit();
+    }
 
-            return Ok(Json(SubmitVakyaResponse {
-                vakya_id: vakya.vakya_id.0,
-                vakya_hash,
-                status: "pending_approval".to_string(),
-                receipt: Some(ReceiptResponse {
-                    vakya_id: stored_receipt.vakya_id,
-                    vakya_hash: stored_receipt.vakya_hash,
-                    reason_code: stored_receipt.reason_code,
-                    message: Some(policy_decision.reason.clone()),
-                    duration_ms: Some(duration_ms),
-                    effect_ids: vec![],
-                    executor_id: stored_receipt.executor_id,
-                    created_at: stored_receipt.created_at.to_rfc3339(),
-                }),
-                merkle_root: stored.merkle_root,
-                leaf_index: stored.leaf_index,
-                policy_decision: Some(PolicyDecisionResponse {
-                    decision: "pending_approval".to_string(),
-                    message: policy_decision.reason,
-                    matched_rules: Some(policy_decision.matched_rules.iter().map(|r| r.rule_name.clone()).collect()),
-                    approval_id: Some(approval_id),
+    record_audit(state, AuditLogEntry::new(
+        AuditEventType::VakyaExecuted,
+        serde_json::json!({
+            "vakya_id": vakya.vakya_id.0,
+            "action": vakya.v3_kriya.action,
+            "reason_code": receipt.reason_code,
+            "effect_ids": receipt.effect_ids,
+            "deferred": true,
+        }),
+    )
+    .with_actor(vakya.v1_karta.pid.0.clone())
+    .with_target(vakya.v2_karma.rid.0.clone())).await;
+
+    Ok(())
+}
+
+/// Dispatch a VĀKYA, store its effects and record metrics
+///
+/// With a `progress` sender the adapter runs through its streaming
+/// interface and its progress events are forwarded as they arrive.
+pub(crate) async fn execute_vakya<'a>(
+    state: &'a AppState,
+    vakya: &Vakya,
+    start: std::time::Instant,
+    progress: Option<&UnboundedSender<ExecutionEvent>>,
+) -> GatewayResult<ExecutionOutcome<'a>> {
+    // Reserve the VĀKYA's declared budgets up front; an exhausted budget
+    // stops it before anything runs, and the charges are refunded unless
+    // the caller commits them once its receipt is stored
+    let costs = vakya.estimate_cost(state.cost_estimator.as_ref());
+    let budget = match state.budget_tracker.reserve(&vakya.v1_karta.pid.0, &vakya.v7_adhikarana.budgets, &costs) {
+        Ok(budget) => budget,
+        Err(e) => {
+            warn!(vakya_id = %vakya.vakya_id, error = %e, "VĀKYA over budget");
+            let duration_ms = start.elapsed().as_millis() as i64;
+            {
+                let mut metrics = state.metrics.write().await;
+                metrics.record_request(&vakya.v3_kriya.action, &vakya.v1_karta.pid.0, false, duration_ms as f64);
+                metrics.record_reason(ReasonCode::BudgetExceeded);
+            }
+            return Ok(ExecutionOutcome {
+                reason_code: ReasonCode::BudgetExceeded,
+                message: Some(e.to_string()),
+                result_json: serde_json::json!({
+                    "status": "failed",
+                    "error": e.to_string(),
+                    "costs": costs,
                 }),
-            }));
-        }
-        _ => {
-            // Allow or NotApplicable - proceed with execution
+                duration_ms,
+                effect_ids: vec![],
+                budget: None,
+            });
         }
-    }
+    };
 
     // Execute the action via adapter dispatcher
     let mut exec_ctx = ExecutionContext::new(vakya.vakya_id.0.clone());
-    exec_ctx.timeout_ms = Some(state.config.request_timeout_secs.saturating_mul(1000));
+    exec_ctx.timeout_ms = Some(execution_timeout_ms(state, vakya, Utc::now()));
     exec_ctx.capture_state = true;
     exec_ctx.dry_run = false;
     if let Some(ref trace) = vakya.meta.trace {
@@ -327,17 +757,17 @@ pub async fn submit_vakya(
         exec_ctx.span_id = Some(trace.span_id.clone());
     }
 
-    let execution = state
-        .dispatcher
-        .dispatch(&vakya, &exec_ctx)
-        .await;
+    let execution = match progress {
+        Some(progress) => state.dispatcher.dispatch_streaming(vakya, &exec_ctx, progress).await,
+        None => state.dispatcher.dispatch(vakya, &exec_ctx).await,
+    };
 
     let mut effect_ids: Vec<String> = Vec::new();
-    let mut stored_effects: Vec<EffectRecord> = Vec::new();
 
     let (reason_code, message, result_json, duration_ms, success_for_metrics) = match execution {
         Ok(exec_result) => {
-            // Store effects
+            // Store effects as one batch so a failure leaves none of them behind
+            let mut records = Vec::with_capacity(exec_result.effects.len());
             for eff in exec_result.effects.iter() {
                 let mut rec = EffectRecord::new(
                     eff.vakya_id.clone(),
@@ -354,31 +784,59 @@ pub async fn submit_vakya(
                 rec.reversible = eff.reversible;
                 rec.reversal_instructions = eff.reversal.as_ref().and_then(|r| serde_json::to_value(r).ok());
                 rec.created_at = eff.timestamp;
-
-                let stored_eff = state
-                    .index_db
-                    .store_effect(rec)
-                    .await
-                    .map_err(|e| GatewayError::Database(e.to_string()))?;
-                effect_ids.push(stored_eff.id.to_string());
-                stored_effects.push(stored_eff);
+                records.push(rec);
             }
+            let stored_effects = state
+                .index_db
+                .store_effects(records)
+                .await
+                .map_err(|e| GatewayError::Database(e.to_string()))?;
+            effect_ids.extend(stored_effects.iter().map(|e| e.id.to_string()));
 
             let duration_ms = start.elapsed().as_millis() as i64;
-            let reason_code = if exec_result.success {
-                ReasonCode::Success
+            let divergences = effect_divergences(vakya, &exec_result.effects);
+            let (mut reason_code, status) = if exec_result.is_partial() {
+                (ReasonCode::PartialSuccess, "partial")
+            } else if exec_result.success {
+                (ReasonCode::Success, "success")
             } else {
-                ReasonCode::AdapterError
+                (ReasonCode::AdapterError, "failed")
             };
-            let message = exec_result.error.clone();
-            let receipt_json = serde_json::json!({
-                "status": if exec_result.success { "success" } else { "failed" },
+            let mut message = exec_result.error.clone();
+            let mut success = exec_result.success;
+            let mut receipt_json = serde_json::json!({
+                "status": status,
                 "duration_ms": duration_ms,
                 "result": exec_result.data,
                 "metadata": exec_result.metadata,
             });
+            if exec_result.is_partial() {
+                message = Some(format!("{} item(s) failed", exec_result.item_errors.len()));
+                receipt_json["item_errors"] = serde_json::json!(exec_result.item_errors);
+            }
+            if !budget.charges().is_empty() {
+                receipt_json["budgets"] = serde_json::json!(budget.charges());
+            }
 
-            (reason_code, message, receipt_json, duration_ms, exec_result.success)
+            if !divergences.is_empty() {
+                let warning = format!(
+                    "Effects diverge from declared {:?}: {}",
+                    vakya.v3_kriya.expected_effect,
+                    divergences.join(", ")
+                );
+                warn!(vakya_id = %vakya.vakya_id, "{}", warning);
+                receipt_json["effect_warnings"] = serde_json::json!(divergences);
+                if state.config.strict_effect_matching && success {
+                    // The effects already happened and stay recorded; the
+                    // receipt marks the execution as out of scope
+                    reason_code = ReasonCode::ScopeViolation;
+                    receipt_json["status"] = serde_json::json!("failed");
+                    message = Some(warning);
+                    success = false;
+                }
+            }
+
+            (reason_code, message, receipt_json, duration_ms, success)
         }
         Err(e) => {
             let duration_ms = start.elapsed().as_millis() as i64;
@@ -387,28 +845,10 @@ pub async fn submit_vakya(
                 "duration_ms": duration_ms,
                 "error": e.to_string(),
             });
-            (ReasonCode::AdapterError, Some(e.to_string()), receipt_json, duration_ms, false)
+            (e.reason_code(), Some(e.to_string()), receipt_json, duration_ms, false)
         }
     };
 
-    // Create and store receipt
-    let mut receipt = ReceiptRecord::new(
-        vakya.vakya_id.0.clone(),
-        vakya_hash.clone(),
-        reason_code,
-        state.config.gateway_id.clone(),
-        result_json,
-    );
-    receipt.message = message;
-    receipt.duration_ms = Some(duration_ms);
-    receipt.effect_ids = effect_ids;
-
-    let stored_receipt = state
-        .index_db
-        .store_receipt(receipt)
-        .await
-        .map_err(|e| GatewayError::Database(e.to_string()))?;
-
     // Update metrics
     {
         let mut metrics = state.metrics.write().await;
@@ -418,36 +858,36 @@ pub async fn submit_vakya(
             success_for_metrics,
             duration_ms as f64,
         );
+        metrics.record_reason(reason_code);
     }
 
-    Ok(Json(SubmitVakyaResponse {
-        vakya_id: vakya.vakya_id.0,
-        vakya_hash,
-        status: if stored_receipt.reason_code.is_success() { "accepted".to_string() } else { "failed".to_string() },
-        receipt: Some(ReceiptResponse {
-            vakya_id: stored_receipt.vakya_id,
-            vakya_hash: stored_receipt.vakya_hash,
-            reason_code: stored_receipt.reason_code,
-            message: stored_receipt.message,
-            duration_ms: Some(duration_ms),
-            effect_ids: stored_receipt.effect_ids,
-            executor_id: stored_receipt.executor_id,
-            created_at: stored_receipt.created_at.to_rfc3339(),
-        }),
-        merkle_root: stored.merkle_root,
-        leaf_index: stored.leaf_index,
-        policy_decision: None,
-    }))
+    Ok(ExecutionOutcome {
+        reason_code,
+        message,
+        result_json,
+        duration_ms,
+        effect_ids,
+        budget: Some(budget),
+    })
+}
+
+/// Look up a stored VĀKYA the caller is allowed to see
+async fn visible_vakya(state: &AppState, scope: &CallerScope, vakya_id: &str) -> GatewayResult<VakyaRecord> {
+    let record = state.index_db.get_vakya(vakya_id).await
+        .map_err(|e| GatewayError::Database(e.to_string()))?
+        .ok_or_else(|| GatewayError::NotFound(format!("VĀKYA not found: {}", vakya_id)))?;
+
+    scope.check_record(&record)?;
+    Ok(record)
 }
 
 /// Get VĀKYA by ID
 pub async fn get_vakya(
     State(state): State<Arc<AppState>>,
+    scope: CallerScope,
     Path(vakya_id): Path<String>,
 ) -> GatewayResult<Json<VakyaRecord>> {
-    let record = state.index_db.get_vakya(&vakya_id).await
-        .map_err(|e| GatewayError::Database(e.to_string()))?
-        .ok_or_else(|| GatewayError::NotFound(format!("VĀKYA not found: {}", vakya_id)))?;
+    let record = visible_vakya(&state, &scope, &vakya_id).await?;
 
     Ok(Json(record))
 }
@@ -455,8 +895,13 @@ pub async fn get_vakya(
 /// Get receipt by VĀKYA ID
 pub async fn get_receipt(
     State(state): State<Arc<AppState>>,
+    scope: CallerScope,
     Path(vakya_id): Path<String>,
 ) -> GatewayResult<Json<ReceiptRecord>> {
+    if scope.is_restricted() {
+        visible_vakya(&state, &scope, &vakya_id).await?;
+    }
+
     let record = state.index_db.get_receipt(&vakya_id).await
         .map_err(|e| GatewayError::Database(e.to_string()))?
         .ok_or_else(|| GatewayError::NotFound(format!("Receipt not found for: {}", vakya_id)))?;
@@ -464,17 +909,127 @@ pub async fn get_receipt(
     Ok(Json(record))
 }
 
+/// Get a public key held by the gateway, e.g. to verify receipt signatures
+pub async fn get_public_key(
+    State(state): State<Arc<AppState>>,
+    Path(key_id): Path<String>,
+) -> GatewayResult<Json<PublicKeyInfo>> {
+    let info = state.key_store.get_public_key(&KeyId::new(key_id.as_str()))
+        .map_err(|_| GatewayError::NotFound(format!("Key not found: {}", key_id)))?;
+
+    Ok(Json(info))
+}
+
+/// List the public keys held by the gateway, oldest first
+pub async fn list_public_keys(
+    State(state): State<Arc<AppState>>,
+) -> GatewayResult<Json<Vec<PublicKeyInfo>>> {
+    let keys = state.key_store.list_public_keys()
+        .map_err(|e| GatewayError::Internal(e.to_string()))?;
+
+    Ok(Json(keys))
+}
+
+/// The gateway's public keys as a JWKS document for JOSE tooling
+pub async fn get_jwks(
+    State(state): State<Arc<AppState>>,
+) -> GatewayResult<Json<JwkSet>> {
+    let Json(keys) = list_public_keys(State(state)).await?;
+
+    Ok(Json(JwkSet::from_public_keys(&keys)))
+}
+
 /// Get effects for a VĀKYA
 pub async fn get_effects(
     State(state): State<Arc<AppState>>,
+    scope: CallerScope,
     Path(vakya_id): Path<String>,
 ) -> GatewayResult<Json<Vec<EffectRecord>>> {
+    if scope.is_restricted() {
+        visible_vakya(&state, &scope, &vakya_id).await?;
+    }
+
     let records = state.index_db.get_effects(&vakya_id).await
         .map_err(|e| GatewayError::Database(e.to_string()))?;
 
     Ok(Json(records))
 }
 
+/// Change report for a single effect
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EffectDiff {
+    pub effect_id: String,
+    pub target: String,
+    pub bucket: EffectBucket,
+    /// `None` when no delta was captured for the effect
+    pub change_type: Option<ChangeType>,
+    pub summary: Option<String>,
+    pub json_patch: Option<Vec<JsonPatchOp>>,
+}
+
+/// Change report for a VĀKYA
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VakyaDiffResponse {
+    pub vakya_id: String,
+    /// One-liner across all effects, e.g. `2 effects: 1 created, 1 modified`
+    pub summary: String,
+    pub effects: Vec<EffectDiff>,
+}
+
+/// Get the combined state changes made by a VĀKYA
+pub async fn get_vakya_diff(
+    State(state): State<Arc<AppState>>,
+    scope: CallerScope,
+    Path(vakya_id): Path<String>,
+) -> GatewayResult<Json<VakyaDiffResponse>> {
+    visible_vakya(&state, &scope, &vakya_id).await?;
+
+    let records = state.index_db.get_effects(&vakya_id).await
+        .map_err(|e| GatewayError::Database(e.to_string()))?;
+
+    let effects: Vec<EffectDiff> = records.into_iter().map(|record| {
+        let delta = record.delta
+            .and_then(|d| serde_json::from_value::<StateDelta>(d).ok());
+        EffectDiff {
+            effect_id: record.id.to_string(),
+            target: record.target_rid,
+            bucket: record.effect_bucket,
+            change_type: delta.as_ref().map(|d| d.change_type),
+            // Deltas stored before summaries were generated have none
+            summary: delta.as_ref().map(|d| d.summary.clone().unwrap_or_else(|| d.describe())),
+            json_patch: delta.and_then(|d| d.json_patch),
+        }
+    }).collect();
+
+    Ok(Json(VakyaDiffResponse {
+        summary: summarize_effect_diffs(&effects),
+        vakya_id,
+        effects,
+    }))
+}
+
+fn summarize_effect_diffs(effects: &[EffectDiff]) -> String {
+    let noun = if effects.len() == 1 { "effect" } else { "effects" };
+    let counts = [
+        (ChangeType::Created, "created"),
+        (ChangeType::Modified, "modified"),
+        (ChangeType::Deleted, "deleted"),
+        (ChangeType::Unchanged, "unchanged"),
+    ]
+    .into_iter()
+    .filter_map(|(change_type, label)| {
+        let n = effects.iter().filter(|e| e.change_type == Some(change_type)).count();
+        (n > 0).then(|| format!("{} {}", n, label))
+    })
+    .collect::<Vec<_>>();
+
+    if counts.is_empty() {
+        format!("{} {}", effects.len(), noun)
+    } else {
+        format!("{} {}: {}", effects.len(), noun, counts.join(", "))
+    }
+}
+
 /// Get Merkle root for a tree type
 #[derive(Debug, Deserialize)]
 pub struct MerkleRootQuery {
@@ -534,6 +1089,309 @@ pub async fn get_inclusion_proof(
     Ok(Json(serde_json::to_value(proof).unwrap_or_default()))
 }
 
+/// Get the inclusion proof for a VĀKYA by ID
+pub async fn get_vakya_proof(
+    State(state): State<Arc<AppState>>,
+    scope: CallerScope,
+    Path(vakya_id): Path<String>,
+) -> GatewayResult<Json<InclusionProof>> {
+    if scope.is_restricted() {
+        visible_vakya(&state, &scope, &vakya_id).await?;
+    }
+
+    let proof = state.index_db.inclusion_proof_for_vakya(&vakya_id).await
+        .map_err(|e| GatewayError::Database(e.to_string()))?
+        .ok_or_else(|| GatewayError::NotFound(format!("No proof for VĀKYA: {}", vakya_id)))?;
+
+    Ok(Json(proof))
+}
+
+/// Get the inclusion proof for the receipt of a VĀKYA
+pub async fn get_receipt_proof(
+    State(state): State<Arc<AppState>>,
+    scope: CallerScope,
+    Path(vakya_id): Path<String>,
+) -> GatewayResult<Json<InclusionProof>> {
+    if scope.is_restricted() {
+        visible_vakya(&state, &scope, &vakya_id).await?;
+    }
+
+    let proof = state.index_db.inclusion_proof_for_receipt(&vakya_id).await
+        .map_err(|e| GatewayError::Database(e.to_string()))?
+        .ok_or_else(|| GatewayError::NotFound(format!("No proof for receipt of: {}", vakya_id)))?;
+
+    Ok(Json(proof))
+}
+
+/// Get the inclusion proof for an effect by effect ID
+pub async fn get_effect_proof(
+    State(state): State<Arc<AppState>>,
+    scope: CallerScope,
+    Path(effect_id): Path<String>,
+) -> GatewayResult<Json<InclusionProof>> {
+    if scope.is_restricted() {
+        let effect = state.index_db.get_effect(&effect_id).await
+            .map_err(|e| GatewayError::Database(e.to_string()))?
+            .ok_or_else(|| GatewayError::NotFound(format!("Effect not found: {}", effect_id)))?;
+        visible_vakya(&state, &scope, &effect.vakya_id).await?;
+    }
+
+    let proof = state.index_db.inclusion_proof_for_effect(&effect_id).await
+        .map_err(|e| GatewayError::Database(e.to_string()))?
+        .ok_or_else(|| GatewayError::NotFound(format!("No proof for effect: {}", effect_id)))?;
+
+    Ok(Json(proof))
+}
+
+/// Get consistency proof between two tree sizes
+#[derive(Debug, Deserialize)]
+pub struct ConsistencyProofQuery {
+    pub tree_type: String,
+    pub first_size: i64,
+    pub second_size: i64,
+}
+
+pub async fn get_consistency_proof(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ConsistencyProofQuery>,
+) -> GatewayResult<Json<ConsistencyProof>> {
+    let tree_type = match query.tree_type.as_str() {
+        "vakya" => TreeType::Vakya,
+        "effect" => TreeType::Effect,
+        "receipt" => TreeType::Receipt,
+        _ => return Err(GatewayError::Validation(format!("Invalid tree type: {}", query.tree_type))),
+    };
+
+    if query.first_size < 0 || query.first_size > query.second_size {
+        return Err(GatewayError::Validation(format!(
+            "Invalid tree sizes: {} -> {}",
+            query.first_size, query.second_size
+        )));
+    }
+
+    let proof = state.index_db.get_consistency_proof(tree_type, query.first_size, query.second_size).await
+        .map_err(|e| GatewayError::Database(e.to_string()))?
+        .ok_or_else(|| GatewayError::NotFound(format!("Tree has fewer than {} leaves", query.second_size)))?;
+
+    Ok(Json(proof))
+}
+
+/// Export the evidence log as JSON lines
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    pub from: Option<chrono::DateTime<Utc>>,
+    pub to: Option<chrono::DateTime<Utc>>,
+    /// Comma-separated record types (vakya, effect, receipt); defaults to all
+    pub types: Option<String>,
+}
+
+impl ExportQuery {
+    fn to_filter(&self) -> GatewayResult<ExportFilter> {
+        let mut filter = ExportFilter {
+            from_time: self.from,
+            to_time: self.to,
+            ..Default::default()
+        };
+
+        for name in self.types.iter().flat_map(|t| t.split(',')).map(str::trim).filter(|t| !t.is_empty()) {
+            let tree_type = match name {
+                "vakya" => TreeType::Vakya,
+                "effect" => TreeType::Effect,
+                "receipt" => TreeType::Receipt,
+                _ => return Err(GatewayError::Validation(format!("Invalid record type: {}", name))),
+            };
+            filter.record_types.push(tree_type);
+        }
+
+        Ok(filter)
+    }
+}
+
+/// Stream matching records as `application/x-ndjson`.
+///
+/// The export runs in a background task writing into a bounded pipe, so the
+/// response is produced as the client reads it. If the export fails partway
+/// the stream ends early, without the trailing checkpoint lines.
+pub async fn export_evidence(
+    State(state): State<Arc<AppState>>,
+    scope: CallerScope,
+    Query(query): Query<ExportQuery>,
+) -> GatewayResult<Response> {
+    if scope.is_restricted() {
+        return Err(GatewayError::AuthorizationDenied(
+            "Evidence export is not available to namespace-scoped API keys".to_string(),
+        ));
+    }
+    let filter = query.to_filter()?;
+
+    let (mut writer, reader) = tokio::io::duplex(64 * 1024);
+    let index_db = Arc::clone(&state.index_db);
+    tokio::spawn(async move {
+        match index_db.export_jsonl(&filter, &mut writer).await {
+            Ok(lines) => debug!(lines = lines, "Evidence export complete"),
+            Err(e) => warn!(error = %e, "Evidence export aborted"),
+        }
+    });
+
+    let body = Body::from_stream(tokio_util::io::ReaderStream::new(reader));
+    Ok(([(header::CONTENT_TYPE, "application/x-ndjson")], body).into_response())
+}
+
+/// Select the VĀKYA requests to bundle
+#[derive(Debug, Deserialize)]
+pub struct ExportBundleRequest {
+    pub vakya_ids: Vec<String>,
+}
+
+/// Largest number of VĀKYA requests in one bundle
+pub const MAX_BUNDLE_VAKYAS: usize = 1000;
+
+/// Build a self-contained evidence bundle for the given VĀKYA requests
+///
+/// The bundle holds their records, effects and receipts, an inclusion
+/// proof for each against a signed head of its tree, and the public keys
+/// needed to check every signature offline.
+pub async fn export_bundle(
+    State(state): State<Arc<AppState>>,
+    scope: CallerScope,
+    Json(request): Json<ExportBundleRequest>,
+) -> GatewayResult<Json<ExportBundle>> {
+    if scope.is_restricted() {
+        return Err(GatewayError::AuthorizationDenied(
+            "Evidence export is not available to namespace-scoped API keys".to_string(),
+        ));
+    }
+    if request.vakya_ids.is_empty() || request.vakya_ids.len() > MAX_BUNDLE_VAKYAS {
+        return Err(GatewayError::Validation(format!(
+            "A bundle needs between 1 and {} VĀKYA IDs",
+            MAX_BUNDLE_VAKYAS
+        )));
+    }
+
+    let db_error = |e: IndexDbError| GatewayError::Database(e.to_string());
+    let mut vakyas = Vec::new();
+    let mut effects = Vec::new();
+    let mut receipts = Vec::new();
+    for vakya_id in &request.vakya_ids {
+        let record = state.index_db.get_vakya(vakya_id).await
+            .map_err(db_error)?
+            .ok_or_else(|| GatewayError::NotFound(format!("VĀKYA not found: {}", vakya_id)))?;
+        vakyas.push((serde_json::to_value(&record)?, record.leaf_index));
+
+        for effect in state.index_db.get_effects(vakya_id).await.map_err(db_error)? {
+            effects.push((serde_json::to_value(&effect)?, effect.leaf_index));
+        }
+        if let Some(receipt) = state.index_db.get_receipt(vakya_id).await.map_err(db_error)? {
+            receipts.push((serde_json::to_value(&receipt)?, receipt.leaf_index));
+        }
+    }
+
+    let mut tree_heads = BTreeMap::new();
+    let vakyas = bundle_entries(&state, TreeType::Vakya, vakyas, &mut tree_heads).await?;
+    let effects = bundle_entries(&state, TreeType::Effect, effects, &mut tree_heads).await?;
+    let receipts = bundle_entries(&state, TreeType::Receipt, receipts, &mut tree_heads).await?;
+
+    let mut key_ids: Vec<&str> = receipts.iter()
+        .filter_map(|entry| entry.record.get("key_id").and_then(|v| v.as_str()))
+        .chain(tree_heads.values().filter_map(|head| head.key_id.as_deref()))
+        .collect();
+    key_ids.sort_unstable();
+    key_ids.dedup();
+    let public_keys = key_ids.into_iter()
+        .filter_map(|key_id| state.key_store.get_public_key(&KeyId::new(key_id)).ok())
+        .collect();
+
+    Ok(Json(ExportBundle {
+        gateway_id: state.config.gateway_id.clone(),
+        created_at: Utc::now(),
+        vakyas,
+        effects,
+        receipts,
+        tree_heads,
+        public_keys,
+    }))
+}
+
+/// Attach inclusion proofs to `records`, all against one signed head of
+/// `tree_type`, which is added to `tree_heads`
+async fn bundle_entries(
+    state: &AppState,
+    tree_type: TreeType,
+    records: Vec<(serde_json::Value, Option<i64>)>,
+    tree_heads: &mut BTreeMap<String, SignedTreeHead>,
+) -> GatewayResult<Vec<BundleEntry>> {
+    if records.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let leaf_indices = records.iter()
+        .map(|(_, leaf_index)| leaf_index.ok_or_else(|| {
+            GatewayError::Internal(format!("A {} record has no Merkle leaf", tree_type))
+        }))
+        .collect::<GatewayResult<Vec<_>>>()?;
+    let (head, proofs) = state.index_db.get_inclusion_proofs(tree_type, &leaf_indices).await
+        .map_err(|e| GatewayError::Database(e.to_string()))?;
+    tree_heads.insert(tree_type.to_string(), state.sign_tree_head(head)?);
+
+    Ok(records.into_iter().zip(proofs).map(|((record, _), proof)| BundleEntry {
+        record,
+        leaf_index: proof.leaf_index as u64,
+        proof: proof.proof_hashes.into_iter().map(|node| ProofStep {
+            hash: node.hash,
+            sibling_on_right: node.position == ProofPosition::Right,
+        }).collect(),
+    }).collect())
+}
+
+/// Write an audit log entry; a failed write is logged rather than failing
+/// the request it describes
+pub(crate) async fn record_audit(state: &AppState, entry: AuditLogEntry) {
+    if let Err(e) = state.index_db.store_audit_log(entry).await {
+        warn!(error = %e, "Failed to write audit log entry");
+    }
+}
+
+/// Query the audit log
+#[derive(Debug, Deserialize)]
+pub struct AuditQuery {
+    pub event_type: Option<AuditEventType>,
+    pub actor: Option<String>,
+    pub from: Option<chrono::DateTime<Utc>>,
+    pub to: Option<chrono::DateTime<Utc>>,
+    pub offset: Option<u32>,
+    /// Page size, at most `MAX_AUDIT_PAGE`
+    pub limit: Option<u32>,
+}
+
+/// Largest page `GET /v1/audit` returns
+pub const MAX_AUDIT_PAGE: u32 = 1000;
+
+/// Get audit log entries, newest first
+pub async fn get_audit_log(
+    State(state): State<Arc<AppState>>,
+    scope: CallerScope,
+    Query(query): Query<AuditQuery>,
+) -> GatewayResult<Json<QueryResult<AuditLogEntry>>> {
+    if scope.is_restricted() {
+        return Err(GatewayError::AuthorizationDenied(
+            "The audit log is not available to namespace-scoped API keys".to_string(),
+        ));
+    }
+
+    let filter = AuditFilter {
+        event_type: query.event_type,
+        actor: query.actor,
+        from_time: query.from,
+        to_time: query.to,
+    };
+    let page = Page::new(query.offset.unwrap_or(0), query.limit.unwrap_or(100).min(MAX_AUDIT_PAGE));
+
+    let entries = state.index_db.get_audit_log(&filter, page).await
+        .map_err(|e| GatewayError::Database(e.to_string()))?;
+
+    Ok(Json(entries))
+}
+
 /// Gateway metrics response
 #[derive(Debug, Serialize)]
 pub struct MetricsResponse {
@@ -541,9 +1399,20 @@ pub struct MetricsResponse {
     pub requests_success: u64,
     pub requests_failed: u64,
     pub auth_denials: u64,
+    pub backpressure_rejections: u64,
     pub avg_latency_ms: f64,
+    pub latency: LatencyPercentiles,
+    pub latency_by_action: BTreeMap<String, LatencyPercentiles>,
+    /// Requests per second over the last `rate_window_secs`
+    pub request_rate: f64,
+    pub rate_window_secs: u64,
     pub top_actions: Vec<(String, u64)>,
     pub top_actors: Vec<(String, u64)>,
+    /// Outcomes keyed by receipt reason code
+    pub requests_by_reason: BTreeMap<String, u64>,
+    /// Record cache counters, when the cache is enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub record_cache: Option<CacheStats>,
 }
 
 pub async fn get_metrics(
@@ -568,12 +1437,35 @@ pub async fn get_metrics(
         requests_success: metrics.requests_success,
         requests_failed: metrics.requests_failed,
         auth_denials: metrics.auth_denials,
+        backpressure_rejections: metrics.backpressure_rejections,
         avg_latency_ms: metrics.avg_latency_ms,
+        latency: metrics.latency_percentiles(),
+        latency_by_action: metrics.latency_by_action.iter()
+            .map(|(action, histogram)| (action.clone(), histogram.percentiles()))
+            .collect(),
+        request_rate: metrics.request_rate(),
+        rate_window_secs: metrics.recent_requests.window().as_secs(),
         top_actions,
         top_actors,
+        requests_by_reason: metrics.requests_by_reason.iter()
+            .map(|(reason, count)| (reason.as_str().to_string(), *count))
+            .collect(),
+        record_cache: state.record_cache.as_ref().map(|cache| cache.stats()),
     })
 }
 
+/// Gateway metrics in the Prometheus text exposition format
+pub async fn get_metrics_prometheus(
+    State(state): State<Arc<AppState>>,
+) -> Response {
+    let metrics = state.metrics.read().await;
+    let mut body = render_prometheus(&metrics);
+    if let Some(ref cache) = state.record_cache {
+        render_cache_prometheus(&mut body, &cache.stats());
+    }
+    ([(header::CONTENT_TYPE, PROMETHEUS_CONTENT_TYPE)], body).into_response()
+}
+
 /// List adapters
 #[derive(Debug, Serialize)]
 pub struct AdapterListResponse {
@@ -614,3 +1506,606 @@ pub async fn list_adapters(
         adapters: adapter_list,
     })
 }
+
+/// Maximum number of stored VĀKYAs replayed by one simulation or adapter replay
+const MAX_SIMULATION_RECORDS: u32 = 1000;
+
+/// Selects stored VĀKYAs to replay through a policy simulation or the adapters
+#[derive(Debug, Default, Deserialize)]
+pub struct SimulationQuery {
+    pub actor: Option<String>,
+    /// Exact action, or a prefix ending in `*`
+    pub action: Option<String>,
+    pub resource_prefix: Option<String>,
+    /// Resource namespace, including child namespaces
+    pub namespace: Option<String>,
+    pub from: Option<chrono::DateTime<Utc>>,
+    pub to: Option<chrono::DateTime<Utc>>,
+    pub limit: Option<u32>,
+}
+
+impl SimulationQuery {
+    pub(crate) fn to_vakya_query(&self) -> VakyaQuery {
+        VakyaQuery {
+            karta_pid: self.actor.clone(),
+            kriya_action: self.action.clone(),
+            karma_rid_prefix: self.resource_prefix.clone(),
+            karma_ns: self.namespace.clone(),
+            from_time: self.from,
+            to_time: self.to,
+            limit: Some(self.limit.unwrap_or(100).min(MAX_SIMULATION_RECORDS)),
+            ..Default::default()
+        }
+    }
+}
+
+/// Policy simulation request
+#[derive(Debug, Deserialize)]
+pub struct PolicySimulationRequest {
+    /// Candidate policy set, evaluated in place of the live policies
+    pub policies: Vec<Policy>,
+    /// Allow when no candidate policy matches (default deny)
+    #[serde(default)]
+    pub default_allow: bool,
+    /// Ad-hoc VĀKYAs to evaluate
+    #[serde(default)]
+    pub vakyas: Vec<Vakya>,
+    /// Stored VĀKYAs to evaluate
+    #[serde(default)]
+    pub query: Option<SimulationQuery>,
+    /// Include a per-rule decision trace in each result
+    #[serde(default)]
+    pub explain: bool,
+}
+
+/// Simulated decision for a single VĀKYA
+#[derive(Debug, Serialize)]
+pub struct SimulatedDecision {
+    pub vakya_id: String,
+    pub action: String,
+    pub actor: String,
+    pub resource: String,
+    pub decision: DecisionType,
+    pub reason: String,
+    pub matched_rules: Vec<MatchedRule>,
+    /// Reason code of the stored receipt, for replayed VĀKYAs
+    pub recorded_reason_code: Option<ReasonCode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace: Option<DecisionTrace>,
+}
+
+/// Policy simulation response
+#[derive(Debug, Serialize)]
+pub struct PolicySimulationResponse {
+    pub evaluated: usize,
+    pub allowed: usize,
+    pub denied: usize,
+    pub pending_approval: usize,
+    pub results: Vec<SimulatedDecision>,
+}
+
+/// Evaluate a candidate policy set without touching the live engine
+pub async fn simulate_policy(
+    State(state): State<Arc<AppState>>,
+    scope: CallerScope,
+    Json(request): Json<PolicySimulationRequest>,
+) -> GatewayResult<Json<PolicySimulationResponse>> {
+    if request.vakyas.is_empty() && request.query.is_none() {
+        return Err(GatewayError::Validation(
+            "Simulation requires `vakyas` or a `query`".to_string(),
+        ));
+    }
+    if let Some(query) = &request.query {
+        scope.check_query(query.namespace.as_deref())?;
+    }
+
+    let mut builder = PolicyEngineBuilder::new();
+    if request.default_allow {
+        builder = builder.with_default_allow();
+    }
+    for policy in request.policies {
+        builder = builder.with_policy(policy);
+    }
+    let engine = builder.build().await
+        .map_err(|e| GatewayError::Validation(e.to_string()))?;
+
+    // (vakya, evaluation time, recorded outcome)
+    let mut inputs: Vec<(Vakya, Option<chrono::DateTime<Utc>>, Option<ReasonCode>)> = request
+        .vakyas
+        .into_iter()
+        .map(|v| (v, None, None))
+        .collect();
+
+    if let Some(query) = request.query {
+        let records = state.index_db.query_vakyas(&query.to_vakya_query()).await
+            .map_err(|e| GatewayError::Database(e.to_string()))?;
+
+        for record in records {
+            let vakya: Vakya = match serde_json::from_value(record.vakya_json) {
+                Ok(vakya) => vakya,
+                Err(e) => {
+                    warn!(vakya_id = %record.vakya_id, error = %e, "Skipping unreadable VĀKYA in simulation");
+                    continue;
+                }
+            };
+            let recorded = state.index_db.get_receipt(&record.vakya_id).await
+                .map_err(|e| GatewayError::Database(e.to_string()))?
+                .map(|r| r.reason_code);
+            inputs.push((vakya, Some(record.created_at), recorded));
+        }
+    }
+
+    let mut results = Vec::with_capacity(inputs.len());
+    for (vakya, timestamp, recorded_reason_code) in inputs {
+        let mut eval_ctx = policy_context(&state, vakya).await;
+        eval_ctx.explain = request.explain;
+        // Replayed traffic is judged as of its original submission time
+        if let Some(timestamp) = timestamp {
+            eval_ctx.timestamp = timestamp;
+        }
+
+        let decision = engine.evaluate(&eval_ctx).await
+            .map_err(|e| GatewayError::Internal(format!("Policy evaluation failed: {}", e)))?;
+
+        let vakya = eval_ctx.vakya;
+        results.push(SimulatedDecision {
+            vakya_id: vakya.vakya_id.0,
+            action: vakya.v3_kriya.action,
+            actor: vakya.v1_karta.pid.0,
+            resource: vakya.v2_karma.rid.0,
+            decision: decision.decision,
+            reason: decision.reason,
+            matched_rules: decision.matched_rules,
+            recorded_reason_code,
+            trace: decision.trace,
+        });
+    }
+
+    let count = |d: DecisionType| results.iter().filter(|r| r.decision == d).count();
+    debug!(evaluated = results.len(), "Policy simulation complete");
+
+    Ok(Json(PolicySimulationResponse {
+        evaluated: results.len(),
+        allowed: count(DecisionType::Allow),
+        denied: count(DecisionType::Deny),
+        pending_approval: count(DecisionType::PendingApproval),
+        results,
+    }))
+}
+
+/// Vote on a pending approval
+///
+/// The approver is the authenticated [`Caller`], not a field of the request.
+#[derive(Debug, Deserialize)]
+pub struct ApprovalVoteRequest {
+    pub decision: VoteDecision,
+    #[serde(default)]
+    pub comment: Option<String>,
+}
+
+/// Approval state response
+#[derive(Debug, Serialize)]
+pub struct ApprovalResponse {
+    pub approval_id: String,
+    pub vakya_id: String,
+    pub status: ApprovalRecordStatus,
+    pub approvals_received: u32,
+    pub min_approvals: u32,
+    /// Summed weight of the approvals, for weighted approvals
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approval_weight: Option<u32>,
+    /// Weight needed to resolve, for weighted approvals
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub required_weight: Option<u32>,
+    pub votes: Vec<ApprovalVote>,
+    pub expires_at: Option<String>,
+    /// Receipt written when the approval was resolved
+    pub receipt: Option<ReceiptResponse>,
+}
+
+impl ApprovalResponse {
+    fn new(approval: ApprovalRecord, receipt: Option<ReceiptRecord>) -> Self {
+        Self {
+            approvals_received: approval.approval_count(),
+            approval_weight: (!approval.weighted_approvers.is_empty()).then(|| approval.approval_weight()),
+            required_weight: (!approval.weighted_approvers.is_empty())
+                .then(|| approval.required_weight.unwrap_or(approval.min_approvals)),
+            approval_id: approval.approval_id,
+            vakya_id: approval.vakya_id,
+            status: approval.status,
+            min_approvals: approval.min_approvals,
+            votes: approval.votes,
+            expires_at: approval.expires_at.map(|t| t.to_rfc3339()),
+            receipt: receipt.map(|r| ReceiptResponse {
+                vakya_id: r.vakya_id,
+                vakya_hash: r.vakya_hash,
+                reason_code: r.reason_code,
+                message: r.message,
+                duration_ms: r.duration_ms,
+                effect_ids: r.effect_ids,
+                executor_id: r.executor_id,
+                created_at: r.created_at.to_rfc3339(),
+            }),
+        }
+    }
+}
+
+/// Get approval by ID
+pub async fn get_approval(
+    State(state): State<Arc<AppState>>,
+    scope: CallerScope,
+    Path(approval_id): Path<String>,
+) -> GatewayResult<Json<ApprovalResponse>> {
+    let (approval, receipt) = load_approval(&state, &scope, &approval_id).await?;
+    Ok(Json(ApprovalResponse::new(approval, receipt)))
+}
+
+/// Vote on a pending approval; executes the VĀKYA once enough approvals are in
+pub async fn vote_approval(
+    State(state): State<Arc<AppState>>,
+    scope: CallerScope,
+    Path(approval_id): Path<String>,
+    caller: Caller,
+    Json(request): Json<ApprovalVoteRequest>,
+) -> GatewayResult<Json<ApprovalResponse>> {
+    let approver = caller.require("Voting on an approval")?.to_string();
+    let (mut approval, _) = load_approval(&state, &scope, &approval_id).await?;
+
+    if approval.status != ApprovalRecordStatus::Pending {
+        return Err(GatewayError::Conflict(format!(
+            "Approval {} is already {:?}",
+            approval_id, approval.status
+        )));
+    }
+    if !approval.is_authorized_approver(&approver) {
+        warn!(approval_id = %approval_id, approver = %approver, "Unauthorized approver");
+        return Err(GatewayError::AuthorizationDenied(format!(
+            "{} is not an approver for {}",
+            approver, approval_id
+        )));
+    }
+
+    let record = state.index_db.get_vakya(&approval.vakya_id).await
+        .map_err(|e| GatewayError::Database(e.to_string()))?
+        .ok_or_else(|| GatewayError::NotFound(format!("VĀKYA not found: {}", approval.vakya_id)))?;
+    if record.karta_pid == approver {
+        warn!(approval_id = %approval_id, approver = %approver, "Submitter voted on own VĀKYA");
+        return Err(GatewayError::AuthorizationDenied(format!(
+            "{} submitted {} and cannot vote on it",
+            approver, approval.vakya_id
+        )));
+    }
+
+    if approval.has_voted(&approver) {
+        return Err(GatewayError::Conflict(format!(
+            "{} has already voted on {}",
+            approver, approval_id
+        )));
+    }
+
+    info!(approval_id = %approval_id, approver = %approver, decision = ?request.decision, "Approval vote received");
+
+    approval.votes.push(ApprovalVote {
+        approver,
+        decision: request.decision,
+        comment: request.comment,
+        voted_at: Utc::now(),
+    });
+
+    match request.decision {
+        VoteDecision::Deny => {
+            approval.status = ApprovalRecordStatus::Rejected;
+            approval.resolved_at = Some(Utc::now());
+            let approval = save_approval(&state, approval).await?;
+
+            let receipt = resolve_receipt(
+                &state,
+                &approval,
+                ReasonCode::PolicyDenied,
+                "Approval rejected",
+                serde_json::json!({
+                    "status": "denied",
+                    "approval_id": approval.approval_id,
+                }),
+            ).await?;
+
+            {
+                let mut metrics = state.metrics.write().await;
+                metrics.record_auth_denial();
+            }
+
+            Ok(Json(ApprovalResponse::new(approval, Some(receipt))))
+        }
+        VoteDecision::Approve if !approval.is_satisfied() => {
+            let approval = save_approval(&state, approval).await?;
+            Ok(Json(ApprovalResponse::new(approval, None)))
+        }
+        VoteDecision::Approve => {
+            let vakya: Vakya = serde_json::from_value(record.vakya_json)?;
+            // Claim a deferred place, or an execution slot to run in now,
+            // before resolving, so a saturated gateway leaves the vote retryable
+            let (queued, _slot) = match deferred_until(&vakya) {
+                Some(_) => (Some(state.reserve_deferred_slot().await?), None),
+                None => (None, Some(state.acquire_execution_slot().await?)),
+            };
+
+            // Persist the resolution before executing so a retry cannot run the action twice
+            approval.status = ApprovalRecordStatus::Approved;
+            approval.resolved_at = Some(Utc::now());
+            let approval = save_approval(&state, approval).await?;
+
+            let start = std::time::Instant::now();
+            if let Err(e) = vakya.validate() {
+                // The VĀKYA may have expired while waiting for approval
+                warn!(vakya_id = %vakya.vakya_id, error = %e, "Approved VĀKYA failed validation");
+                let receipt = resolve_receipt(
+                    &state,
+                    &approval,
+                    ReasonCode::ValidationFailed,
+                    &e.to_string(),
+                    serde_json::json!({
+                        "status": "failed",
+                        "approval_id": approval.approval_id,
+                        "error": e.to_string(),
+                    }),
+                ).await?;
+                return Ok(Json(ApprovalResponse::new(approval, Some(receipt))));
+            }
+
+            // Approved ahead of its not_before time: hold execution until then
+            if let (Some(not_before), Some(queued)) = (deferred_until(&vakya), queued) {
+                let receipt = deferred_receipt(&state, &approval.vakya_id, &approval.vakya_hash, &not_before);
+                let receipt = state.index_db.update_receipt(state.sign_receipt(receipt)?).await
+                    .map_err(|e| GatewayError::Database(e.to_string()))?;
+                spawn_deferred_execution(Arc::clone(&state), vakya, approval.vakya_hash.clone(), not_before, queued);
+                return Ok(Json(ApprovalResponse::new(approval, Some(receipt))));
+            }
+
+            let outcome = execute_vakya(&state, &vakya, start, None).await?;
+            let (receipt, budget) = outcome.into_receipt(&vakya, &approval.vakya_hash, &state.config.gateway_id);
+            let receipt = state.index_db.update_receipt(state.sign_receipt(receipt)?).await
+                .map_err(|e| GatewayError::Database(e.to_string()))?;
+            if let Some(budget) = budget {
+                budget.commit();
+            }
+
+            Ok(Json(ApprovalResponse::new(approval, Some(receipt))))
+        }
+    }
+}
+
+/// Load an approval and its receipt, timing it out if it has expired
+async fn load_approval(
+    state: &AppState,
+    scope: &CallerScope,
+    approval_id: &str,
+) -> GatewayResult<(ApprovalRecord, Option<ReceiptRecord>)> {
+    let mut approval = state.index_db.get_approval(approval_id).await
+        .map_err(|e| GatewayError::Database(e.to_string()))?
+        .ok_or_else(|| GatewayError::NotFound(format!("Approval not found: {}", approval_id)))?;
+    if scope.is_restricted() {
+        visible_vakya(state, scope, &approval.vakya_id).await
+            .map_err(|_| GatewayError::NotFound(format!("Approval not found: {}", approval_id)))?;
+    }
+
+    if approval.status == ApprovalRecordStatus::Pending && approval.is_expired() {
+        info!(approval_id = %approval_id, "Approval timed out");
+        approval.status = ApprovalRecordStatus::TimedOut;
+        approval.resolved_at = Some(Utc::now());
+        let approval = save_approval(state, approval).await?;
+
+        let receipt = resolve_receipt(
+            state,
+            &approval,
+            ReasonCode::Timeout,
+            "Approval timed out",
+            serde_json::json!({
+                "status": "timed_out",
+                "approval_id": approval.approval_id,
+            }),
+        ).await?;
+        return Ok((approval, Some(receipt)));
+    }
+
+    let receipt = state.index_db.get_receipt(&approval.vakya_id).await
+        .map_err(|e| GatewayError::Database(e.to_string()))?;
+    Ok((approval, receipt))
+}
+
+/// Persist an approval update, refusing it when another request resolved
+/// or voted on the approval since it was loaded
+async fn save_approval(state: &AppState, approval: ApprovalRecord) -> GatewayResult<ApprovalRecord> {
+    state.index_db.update_approval(approval).await.map_err(|e| match e {
+        IndexDbError::Conflict(reason) => GatewayError::Conflict(reason),
+        e => GatewayError::Database(e.to_string()),
+    })
+}
+
+/// Replace the pending-approval receipt with the final outcome
+async fn resolve_receipt(
+    state: &AppState,
+    approval: &ApprovalRecord,
+    reason_code: ReasonCode,
+    message: &str,
+    result_json: serde_json::Value,
+) -> GatewayResult<ReceiptRecord> {
+    let mut receipt = ReceiptRecord::new(
+        approval.vakya_id.clone(),
+        approval.vakya_hash.clone(),
+        reason_code,
+        state.config.gateway_id.clone(),
+        result_json,
+    );
+    receipt.message = Some(message.to_string());
+
+    state.index_db.update_receipt(state.sign_receipt(receipt)?).await
+        .map_err(|e| GatewayError::Database(e.to_string()))
+}
+
+/// Policy reload response
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PolicyReloadResponse {
+    /// Policy set version after the reload
+    pub version: u64,
+    /// Number of policies loaded
+    pub policies: usize,
+}
+
+/// Revoke a capability token
+#[derive(Debug, Default, Deserialize)]
+pub struct RevokeCapabilityRequest {
+    /// Also revoke tokens delegated from this one
+    #[serde(default)]
+    pub include_delegations: bool,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// Revocation status of a capability token
+#[derive(Debug, Serialize)]
+pub struct CapabilityStatusResponse {
+    pub token_id: String,
+    pub revoked: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revocation: Option<CapabilityRevocation>,
+}
+
+impl CapabilityStatusResponse {
+    fn new(token_id: String, revocation: Option<CapabilityRevocation>) -> Self {
+        Self { token_id, revoked: revocation.is_some(), revocation }
+    }
+}
+
+/// The calling capability operator, refusing anyone else
+fn check_capability_admin<'a>(state: &AppState, scope: &CallerScope, caller: &'a Caller) -> GatewayResult<&'a str> {
+    if scope.is_restricted() {
+        return Err(GatewayError::AuthorizationDenied(
+            "Capability revocation is not available to namespace-scoped API keys".to_string(),
+        ));
+    }
+    let operator = caller.require("Capability administration")?;
+    if !state.config.capability_operators.iter().any(|o| o == operator) {
+        warn!(caller = %operator, "Capability administration by a non-operator");
+        return Err(GatewayError::AuthorizationDenied(format!("{} is not a capability operator", operator)));
+    }
+    Ok(operator)
+}
+
+/// The calling policy operator, refusing anyone else
+fn check_policy_admin<'a>(state: &AppState, scope: &CallerScope, caller: &'a Caller) -> GatewayResult<&'a str> {
+    if scope.is_restricted() {
+        return Err(GatewayError::AuthorizationDenied(
+            "Policy reloads are not available to namespace-scoped API keys".to_string(),
+        ));
+    }
+    let operator = caller.require("Reloading policies")?;
+    if !state.config.policy_operators.iter().any(|o| o == operator) {
+        warn!(caller = %operator, "Policy reload by a non-operator");
+        return Err(GatewayError::AuthorizationDenied(format!("{} is not a policy operator", operator)));
+    }
+    Ok(operator)
+}
+
+/// Get the revocation status of a capability token
+pub async fn get_capability(
+    State(state): State<Arc<AppState>>,
+    scope: CallerScope,
+    caller: Caller,
+    Path(token_id): Path<String>,
+) -> GatewayResult<Json<CapabilityStatusResponse>> {
+    check_capability_admin(&state, &scope, &caller)?;
+
+    let revocation = state.index_db.get_capability_revocation(&token_id).await
+        .map_err(|e| GatewayError::Database(e.to_string()))?;
+
+    Ok(Json(CapabilityStatusResponse::new(token_id, revocation)))
+}
+
+/// Revoke a capability token so that it no longer verifies
+///
+/// The revocation is persisted before the in-memory list is updated, so a
+/// restarted gateway keeps rejecting the token.
+pub async fn revoke_capability(
+    State(state): State<Arc<AppState>>,
+    scope: CallerScope,
+    caller: Caller,
+    Path(token_id): Path<String>,
+    request: Option<Json<RevokeCapabilityRequest>>,
+) -> GatewayResult<Json<CapabilityStatusResponse>> {
+    let operator = check_capability_admin(&state, &scope, &caller)?;
+    let request = request.map(|Json(r)| r).unwrap_or_default();
+
+    let mut revocation = CapabilityRevocation::new(token_id.clone());
+    revocation.include_delegations = request.include_delegations;
+    revocation.reason = request.reason;
+    revocation.revoked_by = Some(operator.to_string());
+
+    let revocation = state.index_db.revoke_capability(revocation).await
+        .map_err(|e| GatewayError::Database(e.to_string()))?;
+    state.cap_verifier.revocations().revoke(token_id.clone(), revocation.include_delegations);
+
+    info!(token_id = %token_id, operator = %operator, include_delegations = revocation.include_delegations, "Capability revoked");
+
+    record_audit(&state, AuditLogEntry::new(
+        AuditEventType::CapabilityRevoked,
+        serde_json::json!({
+            "include_delegations": revocation.include_delegations,
+            "reason": revocation.reason,
+        }),
+    )
+    .with_actor(operator.to_string())
+    .with_target(token_id.clone())).await;
+
+    Ok(Json(CapabilityStatusResponse::new(token_id, Some(revocation))))
+}
+
+/// Reload policies from the configured policy directory
+///
+/// Only configured policy operators may reload. On failure the previous
+/// policy set stays active.
+pub async fn reload_policies(
+    State(state): State<Arc<AppState>>,
+    scope: CallerScope,
+    caller: Caller,
+) -> GatewayResult<Json<PolicyReloadResponse>> {
+    let operator = check_policy_admin(&state, &scope, &caller)?;
+    let dir = state.config.policy_dir.as_ref().ok_or_else(|| {
+        GatewayError::Validation("No policy directory configured".to_string())
+    })?;
+
+    let policies = state.policy_engine.reload_from_dir(dir).await
+        .map_err(|e| GatewayError::Validation(e.to_string()))?;
+
+    let version = state.policy_engine.version();
+    info!(dir = %dir.display(), policies, version, operator = %operator, "Policies reloaded via API");
+
+    Ok(Json(PolicyReloadResponse { version, policies }))
+}
+
+/// Adapter replay request
+#[derive(Debug, Default, Deserialize)]
+pub struct ReplayRequest {
+    /// Stored VĀKYAs to replay
+    #[serde(default)]
+    pub filter: SimulationQuery,
+}
+
+/// Re-dispatch stored VĀKYAs in dry-run mode and report divergences
+pub async fn replay_vakyas(
+    State(state): State<Arc<AppState>>,
+    scope: CallerScope,
+    Json(request): Json<ReplayRequest>,
+) -> GatewayResult<Json<ReplayReport>> {
+    scope.check_query(request.filter.namespace.as_deref())?;
+
+    let report = Replayer::from_state(&state).replay(&request.filter).await?;
+
+    info!(
+        replayed = report.replayed,
+        diverged = report.diverged,
+        skipped = report.skipped,
+        "Replay complete"
+    );
+
+    Ok(Json(report))
+}
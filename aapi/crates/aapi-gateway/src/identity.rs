@@ -0,0 +1,123 @@
+//! Authenticated identity of the calling client
+//!
+//! Approval votes and capability revocations act on behalf of the caller,
+//! so who the caller is must come from a credential the gateway checked
+//! itself, never from the request body. [`Caller`] resolves it from an API key bound in
+//! `GatewayConfig::api_key_principals`, or otherwise from the verified client
+//! certificate of the connection.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use axum::extract::FromRequestParts;
+use axum::http::{header, request::Parts};
+
+use crate::error::{GatewayError, GatewayResult};
+use crate::state::AppState;
+use crate::tls::PeerIdentity;
+
+/// Principal the calling client authenticated as, if any
+#[derive(Debug, Clone, Default)]
+pub struct Caller {
+    principal: Option<String>,
+}
+
+impl Caller {
+    /// A caller that presented no recognised credential
+    pub fn anonymous() -> Self {
+        Self { principal: None }
+    }
+
+    /// A caller authenticated as `principal`
+    pub fn authenticated(principal: impl Into<String>) -> Self {
+        Self { principal: Some(principal.into()) }
+    }
+
+    /// The principal a verified client certificate names: its common name,
+    /// otherwise its first subject alternative name
+    pub fn from_peer(peer: &PeerIdentity) -> Self {
+        let principal = peer.certificate.as_ref().and_then(|cert| {
+            cert.common_name.clone().or_else(|| cert.subject_alt_names.first().cloned())
+        });
+        Self { principal }
+    }
+
+    pub fn principal(&self) -> Option<&str> {
+        self.principal.as_deref()
+    }
+
+    /// The caller's principal, refusing anonymous callers
+    pub fn require(&self, action: &str) -> GatewayResult<&str> {
+        self.principal().ok_or_else(|| {
+            GatewayError::AuthorizationDenied(format!("{} requires an authenticated caller", action))
+        })
+    }
+}
+
+#[async_trait]
+impl FromRequestParts<Arc<AppState>> for Caller {
+    type Rejection = GatewayError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &Arc<AppState>) -> Result<Self, Self::Rejection> {
+        let key = parts.headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "));
+        if let Some(principal) = key.and_then(|key| state.config.api_key_principals.get(key)) {
+            return Ok(Self::authenticated(principal.clone()));
+        }
+
+        let peer = parts.extensions.get::<PeerIdentity>().cloned().unwrap_or_default();
+        Ok(Self::from_peer(&peer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Request;
+
+    use crate::state::GatewayConfig;
+    use crate::tls::ClientCertificate;
+
+    fn certificate(common_name: Option<&str>, sans: &[&str]) -> PeerIdentity {
+        PeerIdentity {
+            certificate: Some(ClientCertificate {
+                subject: "O=Acme".to_string(),
+                common_name: common_name.map(String::from),
+                subject_alt_names: sans.iter().map(|s| s.to_string()).collect(),
+            }),
+            addr: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_caller_resolution() {
+        let mut config = GatewayConfig::default();
+        config.api_key_principals.insert("key-alice".to_string(), "user:alice".to_string());
+        let state = Arc::new(AppState::in_memory(config).await.unwrap());
+
+        let resolve = |authorization: Option<&str>, peer: PeerIdentity| {
+            let mut request = Request::builder();
+            if let Some(value) = authorization {
+                request = request.header(header::AUTHORIZATION, value);
+            }
+            let (mut parts, _) = request.extension(peer).body(()).unwrap().into_parts();
+            let state = Arc::clone(&state);
+            async move { Caller::from_request_parts(&mut parts, &state).await.unwrap() }
+        };
+
+        let bound = resolve(Some("Bearer key-alice"), certificate(Some("user:bob"), &[])).await;
+        assert_eq!(bound.principal(), Some("user:alice"));
+
+        // Unbound keys authenticate nothing; the certificate still does
+        let cert = resolve(Some("Bearer key-unknown"), certificate(Some("user:bob"), &[])).await;
+        assert_eq!(cert.principal(), Some("user:bob"));
+        let san = resolve(None, certificate(None, &["spiffe://acme/carol"])).await;
+        assert_eq!(san.principal(), Some("spiffe://acme/carol"));
+
+        let anonymous = resolve(Some("Bearer key-unknown"), PeerIdentity::default()).await;
+        assert!(anonymous.principal().is_none());
+        assert!(matches!(anonymous.require("Voting"), Err(GatewayError::AuthorizationDenied(_))));
+    }
+}
@@ -2,19 +2,46 @@
 //!
 //! The Gateway provides:
 //! - REST API for VĀKYA submission and execution
+//! - An in-process [`Engine`] running the same pipeline without HTTP
 //! - Capability token validation
 //! - Effect capture and logging
+//! - Latency percentiles and Prometheus metrics
 //! - Receipt generation
+//! - Optional content-based deduplication of retried submissions
 //! - Transparency log integration
+//! - Dry-run replay of stored VĀKYAs against current adapters
+//! - Dry-run planning with reversibility of predicted effects
+//! - Namespace isolation for API-key-bound callers
+//! - Caller authentication by bound API key or client certificate
+//! - TLS termination with optional client-certificate (mTLS) verification
+//! - Configurable redaction of sensitive values in logs
+
+// The OpenAPI document in `routes` is one large `json!` literal
+#![recursion_limit = "256"]
 
 pub mod server;
 pub mod handlers;
+pub mod engine;
 pub mod middleware;
 pub mod state;
 pub mod error;
+pub mod metrics;
 pub mod routes;
+pub mod replay;
+pub mod namespace;
+pub mod identity;
+pub mod tls;
+pub mod dedup;
+pub mod redaction;
 
 pub use server::*;
 pub use handlers::*;
+pub use engine::*;
 pub use state::*;
 pub use error::*;
+pub use replay::*;
+pub use namespace::*;
+pub use identity::*;
+pub use tls::*;
+pub use dedup::*;
+pub use redaction::*;
@@ -0,0 +1,315 @@
+//! Latency histograms, windowed request rates and Prometheus exposition
+//!
+//! `GatewayMetrics` keeps all-time totals; the types here add what is needed
+//! to SLO the gateway: tail latency per action and the recent request rate.
+
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+use aapi_indexdb::CacheStats;
+use hdrhistogram::Histogram;
+use serde::Serialize;
+
+use crate::state::GatewayMetrics;
+
+/// Window over which the recent request rate is computed
+pub const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Content type of the Prometheus text exposition format
+pub const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Latency percentiles in milliseconds
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct LatencyPercentiles {
+    pub count: u64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+/// Latency histogram with microsecond resolution
+pub struct LatencyHistogram {
+    histogram: Histogram<u64>,
+    sum_ms: f64,
+}
+
+impl LatencyHistogram {
+    pub fn new() -> Self {
+        Self {
+            histogram: Histogram::new(3).expect("3 significant figures is a valid precision"),
+            sum_ms: 0.0,
+        }
+    }
+
+    pub fn record(&mut self, latency_ms: f64) {
+        let latency_ms = latency_ms.max(0.0);
+        let micros = (latency_ms * 1000.0).round() as u64;
+        // The histogram grows on demand; only clamp if it cannot
+        if self.histogram.record(micros).is_err() {
+            self.histogram.saturating_record(micros);
+        }
+        self.sum_ms += latency_ms;
+    }
+
+    pub fn count(&self) -> u64 {
+        self.histogram.len()
+    }
+
+    /// Sum of all recorded latencies in milliseconds
+    pub fn sum_ms(&self) -> f64 {
+        self.sum_ms
+    }
+
+    /// Latency at `quantile` (0.0..=1.0) in milliseconds
+    pub fn quantile_ms(&self, quantile: f64) -> f64 {
+        if self.histogram.is_empty() {
+            return 0.0;
+        }
+        self.histogram.value_at_quantile(quantile) as f64 / 1000.0
+    }
+
+    pub fn percentiles(&self) -> LatencyPercentiles {
+        if self.histogram.is_empty() {
+            return LatencyPercentiles::default();
+        }
+        LatencyPercentiles {
+            count: self.count(),
+            p50_ms: self.quantile_ms(0.50),
+            p95_ms: self.quantile_ms(0.95),
+            p99_ms: self.quantile_ms(0.99),
+            max_ms: self.histogram.max() as f64 / 1000.0,
+        }
+    }
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Request counts bucketed per second over a sliding window
+pub struct RateWindow {
+    window_secs: u64,
+    origin: Instant,
+    /// (seconds since origin, requests in that second), oldest first
+    buckets: VecDeque<(u64, u64)>,
+}
+
+impl RateWindow {
+    pub fn new(window: Duration) -> Self {
+        Self::starting_at(window, Instant::now())
+    }
+
+    pub fn starting_at(window: Duration, origin: Instant) -> Self {
+        Self {
+            window_secs: window.as_secs().max(1),
+            origin,
+            buckets: VecDeque::new(),
+        }
+    }
+
+    pub fn window(&self) -> Duration {
+        Duration::from_secs(self.window_secs)
+    }
+
+    pub fn record(&mut self) {
+        self.record_at(Instant::now());
+    }
+
+    pub fn record_at(&mut self, at: Instant) {
+        let second = self.second_of(at);
+        match self.buckets.back_mut() {
+            Some((last, count)) if *last == second => *count += 1,
+            _ => self.buckets.push_back((second, 1)),
+        }
+
+        while matches!(self.buckets.front(), Some((first, _)) if first + self.window_secs <= second) {
+            self.buckets.pop_front();
+        }
+    }
+
+    /// Requests per second over the window ending now
+    pub fn rate(&self) -> f64 {
+        self.rate_at(Instant::now())
+    }
+
+    /// Requests per second over the window ending at `now`
+    ///
+    /// Before a full window has elapsed the rate is taken over the time
+    /// since the window started, so a fresh gateway is not under-reported.
+    pub fn rate_at(&self, now: Instant) -> f64 {
+        let current = self.second_of(now);
+        let requests: u64 = self.buckets.iter()
+            .filter(|(second, _)| second + self.window_secs > current && *second <= current)
+            .map(|(_, count)| count)
+            .sum();
+        let span = (current + 1).min(self.window_secs);
+        requests as f64 / span as f64
+    }
+
+    fn second_of(&self, at: Instant) -> u64 {
+        at.saturating_duration_since(self.origin).as_secs()
+    }
+}
+
+/// Render gateway metrics in the Prometheus text exposition format
+pub fn render_prometheus(metrics: &GatewayMetrics) -> String {
+    let mut out = String::new();
+
+    let counters = [
+        ("aapi_requests_total", "Total VĀKYA requests received", metrics.requests_total),
+        ("aapi_requests_success_total", "Requests that executed successfully", metrics.requests_success),
+        ("aapi_requests_failed_total", "Requests that failed or were denied", metrics.requests_failed),
+        ("aapi_auth_denials_total", "Authorization denials", metrics.auth_denials),
+        ("aapi_backpressure_rejections_total", "Submissions refused for lack of an execution slot", metrics.backpressure_rejections),
+    ];
+    for (name, help, value) in counters {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} counter", name);
+        let _ = writeln!(out, "{} {}", name, value);
+    }
+
+    let _ = writeln!(out, "# HELP aapi_requests_by_reason_total Requests by receipt reason code");
+    let _ = writeln!(out, "# TYPE aapi_requests_by_reason_total counter");
+    let mut reasons: Vec<_> = metrics.requests_by_reason.iter()
+        .map(|(reason, count)| (reason.as_str(), *count))
+        .collect();
+    reasons.sort();
+    for (reason, count) in reasons {
+        let _ = writeln!(out, "aapi_requests_by_reason_total{{reason=\"{}\"}} {}", reason, count);
+    }
+
+    let window = metrics.recent_requests.window().as_secs();
+    let _ = writeln!(out, "# HELP aapi_request_rate Requests per second over the last {}s", window);
+    let _ = writeln!(out, "# TYPE aapi_request_rate gauge");
+    let _ = writeln!(out, "aapi_request_rate{{window=\"{}s\"}} {}", window, metrics.recent_requests.rate());
+
+    let _ = writeln!(out, "# HELP aapi_request_latency_ms Request latency in milliseconds");
+    let _ = writeln!(out, "# TYPE aapi_request_latency_ms summary");
+    write_summary(&mut out, None, &metrics.latency);
+
+    let mut actions: Vec<_> = metrics.latency_by_action.iter().collect();
+    actions.sort_by(|a, b| a.0.cmp(b.0));
+    for (action, histogram) in actions {
+        write_summary(&mut out, Some(action), histogram);
+    }
+
+    out
+}
+
+/// Append record cache counters to a Prometheus exposition
+pub fn render_cache_prometheus(out: &mut String, stats: &CacheStats) {
+    let counters = [
+        ("aapi_record_cache_hits_total", "Record reads served from the cache", stats.hits),
+        ("aapi_record_cache_misses_total", "Record reads that went to the database", stats.misses),
+        ("aapi_record_cache_evictions_total", "Cached records evicted to stay within capacity", stats.evictions),
+    ];
+    for (name, help, value) in counters {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} counter", name);
+        let _ = writeln!(out, "{} {}", name, value);
+    }
+
+    let _ = writeln!(out, "# HELP aapi_record_cache_entries Records currently cached");
+    let _ = writeln!(out, "# TYPE aapi_record_cache_entries gauge");
+    let _ = writeln!(out, "aapi_record_cache_entries {}", stats.entries);
+}
+
+fn write_summary(out: &mut String, action: Option<&str>, histogram: &LatencyHistogram) {
+    let action_label = action
+        .map(|action| format!("action=\"{}\"", escape_label(action)))
+        .unwrap_or_default();
+
+    for quantile in [0.5, 0.95, 0.99] {
+        let labels = if action_label.is_empty() {
+            format!("quantile=\"{}\"", quantile)
+        } else {
+            format!("{},quantile=\"{}\"", action_label, quantile)
+        };
+        let _ = writeln!(out, "aapi_request_latency_ms{{{}}} {}", labels, histogram.quantile_ms(quantile));
+    }
+
+    let labels = if action_label.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", action_label)
+    };
+    let _ = writeln!(out, "aapi_request_latency_ms_sum{} {}", labels, histogram.sum_ms());
+    let _ = writeln!(out, "aapi_request_latency_ms_count{} {}", labels, histogram.count());
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_percentiles() {
+        let mut histogram = LatencyHistogram::new();
+        assert_eq!(histogram.percentiles(), LatencyPercentiles::default());
+
+        for ms in 1..=100 {
+            histogram.record(ms as f64);
+        }
+
+        let p = histogram.percentiles();
+        assert_eq!(p.count, 100);
+        assert!((p.p50_ms - 50.0).abs() < 0.1, "{:?}", p);
+        assert!((p.p95_ms - 95.0).abs() < 0.1, "{:?}", p);
+        assert!((p.p99_ms - 99.0).abs() < 0.1, "{:?}", p);
+        assert!((p.max_ms - 100.0).abs() < 0.1, "{:?}", p);
+        assert_eq!(histogram.sum_ms(), 5050.0);
+    }
+
+    #[test]
+    fn test_rate_window_slides() {
+        let origin = Instant::now();
+        let at = |secs: u64| origin + Duration::from_secs(secs);
+        let mut window = RateWindow::starting_at(Duration::from_secs(10), origin);
+
+        for _ in 0..20 {
+            window.record_at(at(0));
+        }
+        // Less than a full window elapsed: rate over the elapsed span
+        assert_eq!(window.rate_at(at(1)), 10.0);
+
+        for _ in 0..10 {
+            window.record_at(at(12));
+        }
+        // The burst at t=0 has left the window
+        assert_eq!(window.rate_at(at(12)), 1.0);
+        assert_eq!(window.rate_at(at(30)), 0.0);
+    }
+
+    #[test]
+    fn test_prometheus_output() {
+        let mut metrics = GatewayMetrics::new();
+        metrics.record_request("file.read", "agent:a", true, 12.0);
+        metrics.record_request("file.read", "agent:a", true, 40.0);
+        metrics.record_request("http.\"get\"", "agent:b", false, 3.0);
+        metrics.record_auth_denial();
+        metrics.record_backpressure_rejection();
+        metrics.record_reason(aapi_core::error::ReasonCode::Success);
+        metrics.record_reason(aapi_core::error::ReasonCode::Success);
+        metrics.record_reason(aapi_core::error::ReasonCode::Unavailable);
+
+        let text = render_prometheus(&metrics);
+        assert!(text.contains("aapi_requests_total 3\n"));
+        assert!(text.contains("aapi_auth_denials_total 1\n"));
+        assert!(text.contains("aapi_backpressure_rejections_total 1\n"));
+        assert!(text.contains("aapi_requests_by_reason_total{reason=\"SUCCESS\"} 2\n"));
+        assert!(text.contains("aapi_requests_by_reason_total{reason=\"UNAVAILABLE\"} 1\n"));
+        assert!(text.contains("# TYPE aapi_request_latency_ms summary\n"));
+        assert!(text.contains("aapi_request_latency_ms{quantile=\"0.99\"} 40"));
+        assert!(text.contains("aapi_request_latency_ms_count{action=\"file.read\"} 2\n"));
+        assert!(text.contains("aapi_request_latency_ms_sum{action=\"file.read\"} 52\n"));
+        assert!(text.contains("action=\"http.\\\"get\\\"\",quantile=\"0.5\""));
+        assert!(text.contains("aapi_request_rate{window=\"60s\"} "));
+    }
+}
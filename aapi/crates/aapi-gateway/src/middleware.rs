@@ -2,15 +2,23 @@
 
 use axum::{
     body::Body,
-    extract::Request,
-    http::{header, StatusCode},
+    extract::{ConnectInfo, Request, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
 };
-use std::time::Instant;
-use tracing::{debug, info, span, Level};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, span, warn, Level};
 use uuid::Uuid;
 
+use aapi_core::{Budget, BudgetTracker};
+
+use crate::error::GatewayError;
+use crate::state::AppState;
+use crate::tls::PeerIdentity;
+
 /// Request ID middleware - adds unique request ID to each request
 pub async fn request_id(mut request: Request, next: Next) -> Response {
     let request_id = request
@@ -90,6 +98,9 @@ pub fn cors_layer() -> tower_http::cors::CorsLayer {
 }
 
 /// Rate limiting state
+///
+/// Clones share the same counters.
+#[derive(Clone)]
 pub struct RateLimiter {
     requests: std::sync::Arc<tokio::sync::RwLock<std::collections::HashMap<String, RateLimitEntry>>>,
     max_requests: u32,
@@ -101,6 +112,30 @@ struct RateLimitEntry {
     window_start: Instant,
 }
 
+/// A caller's standing in its current rate limit window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitStatus {
+    /// Whether this request was admitted
+    pub allowed: bool,
+    pub limit: u32,
+    /// Requests left in the window after this one
+    pub remaining: u32,
+    /// Seconds until the window resets
+    pub reset_secs: u64,
+}
+
+impl RateLimitStatus {
+    /// Add `X-RateLimit-*` headers, and `Retry-After` when denied
+    pub fn apply_headers(&self, headers: &mut HeaderMap) {
+        headers.insert("x-ratelimit-limit", HeaderValue::from(self.limit));
+        headers.insert("x-ratelimit-remaining", HeaderValue::from(self.remaining));
+        headers.insert("x-ratelimit-reset", HeaderValue::from(self.reset_secs));
+        if !self.allowed {
+            headers.insert(header::RETRY_AFTER, HeaderValue::from(self.reset_secs));
+        }
+    }
+}
+
 impl RateLimiter {
     pub fn new(max_requests: u32, window_secs: u64) -> Self {
         Self {
@@ -111,6 +146,11 @@ impl RateLimiter {
     }
 
     pub async fn check(&self, key: &str) -> bool {
+        self.acquire(key).await.allowed
+    }
+
+    /// Count a request against `key` and report what is left of its window
+    pub async fn acquire(&self, key: &str) -> RateLimitStatus {
         let mut requests = self.requests.write().await;
         let now = Instant::now();
         
@@ -125,12 +165,20 @@ impl RateLimiter {
             entry.window_start = now;
         }
 
-        if entry.count >= self.max_requests {
-            return false;
+        let allowed = entry.count < self.max_requests;
+        if allowed {
+            entry.count += 1;
         }
 
-        entry.count += 1;
-        true
+        // Rounded up so a client waiting `reset_secs` lands in the next window
+        let elapsed = now.duration_since(entry.window_start);
+        let reset = std::time::Duration::from_secs(self.window_secs).saturating_sub(elapsed);
+        RateLimitStatus {
+            allowed,
+            limit: self.max_requests,
+            remaining: self.max_requests - entry.count,
+            reset_secs: reset.as_secs() + u64::from(reset.subsec_nanos() > 0),
+        }
     }
 
     pub async fn cleanup(&self) {
@@ -141,6 +189,84 @@ impl RateLimiter {
             now.duration_since(entry.window_start).as_secs() < self.window_secs * 2
         });
     }
+
+    /// Run [`cleanup`](Self::cleanup) once per window until the returned
+    /// task is aborted, so callers that stop sending are forgotten
+    pub fn spawn_cleanup(&self) -> tokio::task::JoinHandle<()> {
+        let limiter = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(limiter.window_secs.max(1)));
+            loop {
+                interval.tick().await;
+                limiter.cleanup().await;
+            }
+        })
+    }
+
+    /// Number of callers with a tracked window
+    pub async fn len(&self) -> usize {
+        self.requests.read().await.len()
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.len().await == 0
+    }
+}
+
+/// Add an `X-Budget-Remaining: <budget id>=<remaining>` header for each
+/// budget `principal` declared, as the tracker now stands
+pub fn apply_budget_headers(headers: &mut HeaderMap, tracker: &BudgetTracker, principal: &str, budgets: &[Budget]) {
+    for budget in budgets {
+        let remaining = tracker.remaining(principal, budget);
+        if let Ok(value) = HeaderValue::from_str(&format!("{}={}", budget.id, remaining)) {
+            headers.append("x-budget-remaining", value);
+        }
+    }
+}
+
+/// Rate limiting middleware - counts each request against its caller when
+/// `GatewayConfig::rate_limit` is set and reports the caller's standing in
+/// `X-RateLimit-*` headers
+///
+/// Callers are told apart by a configured bearer API key, then verified
+/// client certificate subject, then peer IP address. Unrecognised keys are
+/// ignored, so inventing keys does not buy a fresh allowance.
+pub async fn rate_limit(State(state): State<Arc<AppState>>, request: Request, next: Next) -> Response {
+    let Some(limiter) = &state.rate_limiter else {
+        return next.run(request).await;
+    };
+
+    let status = limiter.acquire(&rate_limit_key(&state, &request)).await;
+    let mut response = if status.allowed {
+        next.run(request).await
+    } else {
+        warn!(limit = status.limit, reset_secs = status.reset_secs, "Rate limit exceeded");
+        GatewayError::RateLimited { retry_after_secs: status.reset_secs }.into_response()
+    };
+    status.apply_headers(response.headers_mut());
+    response
+}
+
+/// Who a request is counted against
+fn rate_limit_key(state: &AppState, request: &Request) -> String {
+    let config = &state.config;
+    let key = request.headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .filter(|key| config.api_key_principals.contains_key(*key) || config.api_key_namespaces.contains_key(*key));
+    if let Some(key) = key {
+        return format!("key:{}", key);
+    }
+
+    if let Some(cert) = request.extensions().get::<PeerIdentity>().and_then(|peer| peer.certificate.as_ref()) {
+        return format!("cert:{}", cert.subject);
+    }
+
+    match request.extensions().get::<ConnectInfo<SocketAddr>>() {
+        Some(ConnectInfo(addr)) => format!("ip:{}", addr.ip()),
+        None => "anonymous".to_string(),
+    }
 }
 
 /// Compression middleware
@@ -0,0 +1,120 @@
+//! Namespace isolation for read endpoints
+//!
+//! When `GatewayConfig::api_key_namespaces` is non-empty, callers must send
+//! `Authorization: Bearer <api key>` and may only read VĀKYAs whose resource
+//! namespace is within one of the namespaces bound to their key. Records
+//! outside the caller's namespaces are reported as not found. A key bound
+//! to [`ALL_NAMESPACES`] is unrestricted, as every caller is when no keys
+//! are bound, and may use the operator endpoints.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use axum::extract::FromRequestParts;
+use axum::http::{header, request::Parts};
+
+use aapi_core::types::Namespace;
+use aapi_core::validation::NamespaceValidator;
+use aapi_indexdb::VakyaRecord;
+
+use crate::error::{GatewayError, GatewayResult};
+use crate::state::AppState;
+
+/// Namespace binding that gives an API key an unrestricted scope
+pub const ALL_NAMESPACES: &str = "*";
+
+/// Namespaces the calling client may read
+pub struct CallerScope {
+    validator: Option<NamespaceValidator>,
+}
+
+impl CallerScope {
+    /// Scope for callers when namespace binding is disabled
+    pub fn unrestricted() -> Self {
+        Self { validator: None }
+    }
+
+    /// Scope limited to the given namespaces
+    pub fn restricted(namespaces: Vec<Namespace>) -> Self {
+        Self { validator: Some(NamespaceValidator::new(namespaces)) }
+    }
+
+    pub fn is_restricted(&self) -> bool {
+        self.validator.is_some()
+    }
+
+    /// Whether a record in namespace `ns` is visible; records without a
+    /// namespace are only visible to unrestricted callers
+    pub fn can_read(&self, ns: Option<&str>) -> bool {
+        match &self.validator {
+            None => true,
+            Some(validator) => ns.is_some_and(|ns| validator.is_allowed(&Namespace::new(ns))),
+        }
+    }
+
+    /// Check that a stored VĀKYA is visible, hiding it otherwise
+    pub fn check_record(&self, record: &VakyaRecord) -> GatewayResult<()> {
+        if self.can_read(record.karma_ns.as_deref()) {
+            Ok(())
+        } else {
+            Err(GatewayError::NotFound(format!("VĀKYA not found: {}", record.vakya_id)))
+        }
+    }
+
+    /// Check a query's namespace filter; restricted callers must filter to
+    /// one of their namespaces
+    pub fn check_query(&self, ns: Option<&str>) -> GatewayResult<()> {
+        if !self.is_restricted() || (ns.is_some() && self.can_read(ns)) {
+            Ok(())
+        } else {
+            Err(GatewayError::AuthorizationDenied(
+                "Query must be limited to a namespace bound to the caller".to_string(),
+            ))
+        }
+    }
+}
+
+#[async_trait]
+impl FromRequestParts<Arc<AppState>> for CallerScope {
+    type Rejection = GatewayError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &Arc<AppState>) -> Result<Self, Self::Rejection> {
+        let bindings = &state.config.api_key_namespaces;
+        if bindings.is_empty() {
+            return Ok(Self::unrestricted());
+        }
+
+        let key = parts.headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .ok_or_else(|| GatewayError::AuthorizationDenied("Missing API key".to_string()))?;
+
+        let namespaces = bindings.get(key)
+            .ok_or_else(|| GatewayError::AuthorizationDenied("Unknown API key".to_string()))?;
+        if namespaces.iter().any(|ns| ns == ALL_NAMESPACES) {
+            return Ok(Self::unrestricted());
+        }
+
+        Ok(Self::restricted(namespaces.iter().map(Namespace::new).collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_caller_scope() {
+        let scope = CallerScope::restricted(vec![Namespace::new("org.acme")]);
+        assert!(scope.can_read(Some("org.acme.billing")));
+        assert!(!scope.can_read(Some("org.other")));
+        assert!(!scope.can_read(None));
+        assert!(scope.check_query(None).is_err());
+        assert!(scope.check_query(Some("org.acme")).is_ok());
+
+        let open = CallerScope::unrestricted();
+        assert!(open.can_read(None));
+        assert!(open.check_query(None).is_ok());
+    }
+}
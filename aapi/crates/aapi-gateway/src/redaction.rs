@@ -0,0 +1,253 @@
+//! Redaction of sensitive values in the gateway's operational logs
+//!
+//! Handlers log VĀKYA IDs, actions, actors and resource identifiers; some
+//! `rid`s carry paths or URLs that must not reach log storage. A
+//! [`LogRedaction`] lists regular expressions for such values, and
+//! [`fmt_layer`] builds a `tracing` formatting layer that masks or hashes
+//! every match in event and span fields before they are written.
+//!
+//! This only concerns what is logged; VĀKYAs, receipts and effects are
+//! stored unchanged.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::sync::Arc;
+
+use regex::Regex;
+use tracing::field::{Field, Visit};
+use tracing::Subscriber;
+use tracing_subscriber::field::{MakeVisitor, VisitFmt, VisitOutput};
+use tracing_subscriber::fmt::format::{DefaultFields, DefaultVisitor, Writer};
+use tracing_subscriber::registry::LookupSpan;
+
+use aapi_core::hash_bytes;
+
+use crate::error::{GatewayError, GatewayResult};
+
+/// Text a masked value is replaced with
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Hex digits of the SHA-256 kept when hashing a value
+const HASH_PREFIX_LEN: usize = 12;
+
+/// How a matched value is rewritten
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RedactionMode {
+    /// Replace the match with [`REDACTED`]
+    #[default]
+    Mask,
+    /// Replace the match with a short SHA-256 prefix, so log lines about
+    /// the same resource can still be correlated
+    Hash,
+}
+
+/// Values to keep out of the logs; nothing is redacted without patterns
+#[derive(Debug, Clone, Default)]
+pub struct LogRedaction {
+    /// Regular expressions matched against every logged field value
+    pub patterns: Vec<String>,
+    pub mode: RedactionMode,
+}
+
+impl LogRedaction {
+    pub fn new(mode: RedactionMode) -> Self {
+        Self {
+            patterns: Vec::new(),
+            mode,
+        }
+    }
+
+    pub fn with_pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.patterns.push(pattern.into());
+        self
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.patterns.is_empty()
+    }
+
+    /// Compile the patterns, failing on the first invalid one
+    pub fn compile(&self) -> GatewayResult<Redactor> {
+        let patterns = self.patterns.iter()
+            .map(|p| {
+                Regex::new(p).map_err(|e| {
+                    GatewayError::Validation(format!("Invalid log redaction pattern '{}': {}", p, e))
+                })
+            })
+            .collect::<GatewayResult<Vec<_>>>()?;
+        Ok(Redactor {
+            patterns,
+            mode: self.mode,
+        })
+    }
+}
+
+/// Compiled [`LogRedaction`]
+#[derive(Debug, Clone)]
+pub struct Redactor {
+    patterns: Vec<Regex>,
+    mode: RedactionMode,
+}
+
+impl Redactor {
+    /// `value` with every pattern match rewritten, borrowed when nothing
+    /// matched
+    pub fn redact<'a>(&self, value: &'a str) -> Cow<'a, str> {
+        let mut value = Cow::Borrowed(value);
+        for pattern in &self.patterns {
+            if let Cow::Owned(replaced) = pattern.replace_all(&value, |m: &regex::Captures| self.replacement(&m[0])) {
+                value = Cow::Owned(replaced);
+            }
+        }
+        value
+    }
+
+    fn replacement(&self, matched: &str) -> String {
+        match self.mode {
+            RedactionMode::Mask => REDACTED.to_string(),
+            RedactionMode::Hash => {
+                let hash = hash_bytes(matched.as_bytes());
+                format!("[sha256:{}]", &hash.value[..HASH_PREFIX_LEN])
+            }
+        }
+    }
+}
+
+/// Field formatter applying a [`Redactor`] before the default formatting
+#[derive(Debug, Clone)]
+pub struct RedactingFields {
+    redactor: Arc<Redactor>,
+}
+
+impl<'a> MakeVisitor<Writer<'a>> for RedactingFields {
+    type Visitor = RedactingVisitor<'a>;
+
+    fn make_visitor(&self, target: Writer<'a>) -> Self::Visitor {
+        RedactingVisitor {
+            inner: DefaultFields::new().make_visitor(target),
+            redactor: Arc::clone(&self.redactor),
+        }
+    }
+}
+
+/// Visitor behind [`RedactingFields`]
+pub struct RedactingVisitor<'a> {
+    inner: DefaultVisitor<'a>,
+    redactor: Arc<Redactor>,
+}
+
+impl Visit for RedactingVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        let value = self.redactor.redact(value);
+        self.inner.record_str(field, &value);
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        let formatted = format!("{:?}", value);
+        match self.redactor.redact(&formatted) {
+            Cow::Borrowed(_) => self.inner.record_debug(field, value),
+            Cow::Owned(redacted) => self.inner.record_debug(field, &format_args!("{}", redacted)),
+        }
+    }
+}
+
+impl VisitOutput<fmt::Result> for RedactingVisitor<'_> {
+    fn finish(self) -> fmt::Result {
+        self.inner.finish()
+    }
+}
+
+impl VisitFmt for RedactingVisitor<'_> {
+    fn writer(&mut self) -> &mut dyn fmt::Write {
+        self.inner.writer()
+    }
+}
+
+/// The gateway's log formatting layer, redacting as `redaction` configures
+///
+/// Without patterns, output is the same as `tracing_subscriber::fmt::layer()`.
+pub fn fmt_layer<S>(redaction: &LogRedaction) -> GatewayResult<tracing_subscriber::fmt::Layer<S, RedactingFields>>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    let redactor = Arc::new(redaction.compile()?);
+    Ok(tracing_subscriber::fmt::layer().fmt_fields(RedactingFields { redactor }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+    use std::sync::Mutex;
+    use tracing::info;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    /// Log output captured in memory
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn log_with(redaction: &LogRedaction) -> String {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let layer = fmt_layer(redaction).unwrap()
+            .with_ansi(false)
+            .with_writer(move || writer.clone());
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            let rid = "file:/home/alice/payroll.csv";
+            info!(vakya_id = %"vakya-1", rid = %rid, action = "file.read", "Executing {}", rid);
+        });
+        let output = captured.0.lock().unwrap().clone();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_no_patterns_logs_unchanged() {
+        let output = log_with(&LogRedaction::default());
+        assert!(output.contains("Executing file:/home/alice/payroll.csv"));
+        assert!(output.contains("rid=file:/home/alice/payroll.csv"));
+        assert!(output.contains("action=\"file.read\""));
+    }
+
+    #[test]
+    fn test_matches_are_masked_in_fields_and_messages() {
+        let redaction = LogRedaction::new(RedactionMode::Mask).with_pattern(r"/home/[^\s]+");
+        let output = log_with(&redaction);
+        assert!(!output.contains("alice"), "{}", output);
+        assert!(output.contains("Executing file:[REDACTED]"));
+        assert!(output.contains("rid=file:[REDACTED]"));
+        assert!(output.contains("vakya_id=vakya-1"));
+    }
+
+    #[test]
+    fn test_hashed_matches_stay_correlatable() {
+        let redaction = LogRedaction::new(RedactionMode::Hash).with_pattern(r"/home/[^\s]+");
+        let output = log_with(&redaction);
+        assert!(!output.contains("alice"), "{}", output);
+
+        let redactor = redaction.compile().unwrap();
+        let hashed = redactor.redact("file:/home/alice/payroll.csv");
+        assert!(hashed.starts_with("file:[sha256:"));
+        assert!(output.contains(&format!("rid={}", hashed)));
+        assert_eq!(hashed, redactor.redact("file:/home/alice/payroll.csv"));
+        assert_ne!(hashed, redactor.redact("file:/home/bob/payroll.csv"));
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_rejected() {
+        let redaction = LogRedaction::default().with_pattern("(unclosed");
+        assert!(matches!(redaction.compile(), Err(GatewayError::Validation(_))));
+    }
+}
@@ -0,0 +1,270 @@
+//! Replay of stored VĀKYAs against the current adapters
+//!
+//! The replayer reconstructs historical VĀKYAs from IndexDB, dispatches them
+//! in dry-run mode and compares the outcome and would-be effects with what was
+//! recorded when they originally ran. Only VĀKYAs that reached an adapter
+//! (receipt `Success` or `AdapterError`) are replayed; denied or pending
+//! requests are skipped.
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use aapi_adapters::{CapturedEffect, Dispatcher, ExecutionContext};
+use aapi_core::error::ReasonCode;
+use aapi_core::types::EffectBucket;
+use aapi_core::Vakya;
+use aapi_indexdb::{EffectRecord, IndexDbStore, OrderBy, OrderDirection, VakyaRecord};
+
+use crate::error::{GatewayError, GatewayResult};
+use crate::handlers::SimulationQuery;
+use crate::state::AppState;
+
+/// Replays stored VĀKYAs through a dispatcher in dry-run mode
+pub struct Replayer<'a> {
+    index_db: &'a dyn IndexDbStore,
+    dispatcher: &'a Dispatcher,
+}
+
+impl<'a> Replayer<'a> {
+    pub fn new(index_db: &'a dyn IndexDbStore, dispatcher: &'a Dispatcher) -> Self {
+        Self { index_db, dispatcher }
+    }
+
+    /// Replay against the gateway's own store and adapters
+    pub fn from_state(state: &'a AppState) -> Self {
+        Self::new(state.index_db.as_ref(), &state.dispatcher)
+    }
+
+    /// Replay every stored VĀKYA matching the filter, oldest first
+    pub async fn replay(&self, filter: &SimulationQuery) -> GatewayResult<ReplayReport> {
+        let mut query = filter.to_vakya_query();
+        query.order_by = Some(OrderBy::CreatedAt);
+        query.order_dir = Some(OrderDirection::Asc);
+
+        let records = self.index_db.query_vakyas(&query).await
+            .map_err(|e| GatewayError::Database(e.to_string()))?;
+
+        let mut report = ReplayReport::default();
+        for record in records {
+            let outcome = self.replay_record(record).await?;
+            match outcome.status {
+                ReplayStatus::Matched => report.matched += 1,
+                ReplayStatus::Diverged => report.diverged += 1,
+                ReplayStatus::Skipped => report.skipped += 1,
+            }
+            report.results.push(outcome);
+        }
+        report.replayed = report.matched + report.diverged;
+
+        Ok(report)
+    }
+
+    /// Replay a single stored VĀKYA
+    pub async fn replay_record(&self, record: VakyaRecord) -> GatewayResult<ReplayOutcome> {
+        let receipt = self.index_db.get_receipt(&record.vakya_id).await
+            .map_err(|e| GatewayError::Database(e.to_string()))?;
+        let original_reason_code = receipt.map(|r| r.reason_code);
+
+        let mut outcome = ReplayOutcome {
+            vakya_id: record.vakya_id.clone(),
+            action: record.kriya_action.clone(),
+            resource: record.karma_rid.clone(),
+            status: ReplayStatus::Skipped,
+            original_reason_code,
+            replay_success: None,
+            replay_error: None,
+            effects_compared: false,
+            divergences: Vec::new(),
+        };
+
+        let original_success = match original_reason_code {
+            Some(ReasonCode::Success) => true,
+            Some(ReasonCode::AdapterError) => false,
+            _ => {
+                outcome.replay_error = Some("Not executed originally".to_string());
+                return Ok(outcome);
+            }
+        };
+
+        let vakya: Vakya = match serde_json::from_value(record.vakya_json) {
+            Ok(vakya) => vakya,
+            Err(e) => {
+                warn!(vakya_id = %record.vakya_id, error = %e, "Skipping unreadable VĀKYA in replay");
+                outcome.replay_error = Some(format!("Stored VĀKYA could not be reconstructed: {}", e));
+                return Ok(outcome);
+            }
+        };
+
+        let original_effects = self.index_db.get_effects(&record.vakya_id).await
+            .map_err(|e| GatewayError::Database(e.to_string()))?;
+
+        let mut exec_ctx = ExecutionContext::new(format!("replay:{}", record.vakya_id)).dry_run();
+        exec_ctx.capture_state = true;
+
+        let (replay_success, replayed_effects) = match self.dispatcher.dispatch(&vakya, &exec_ctx).await {
+            Ok(result) => {
+                outcome.replay_error = result.error;
+                (result.success, result.effects)
+            }
+            Err(e) => {
+                outcome.replay_error = Some(e.to_string());
+                (false, Vec::new())
+            }
+        };
+        outcome.replay_success = Some(replay_success);
+
+        if replay_success != original_success {
+            outcome.divergences.push(Divergence::Outcome {
+                original_success,
+                replay_success,
+            });
+        }
+
+        // Adapters that skip side effects in dry-run report no would-be
+        // effects; there is nothing to compare against in that case.
+        outcome.effects_compared = !replayed_effects.is_empty() || original_effects.is_empty();
+        if outcome.effects_compared {
+            outcome.divergences.extend(compare_effects(&original_effects, &replayed_effects));
+        }
+
+        outcome.status = if outcome.divergences.is_empty() {
+            ReplayStatus::Matched
+        } else {
+            ReplayStatus::Diverged
+        };
+
+        debug!(vakya_id = %outcome.vakya_id, status = ?outcome.status, "Replayed VĀKYA");
+        Ok(outcome)
+    }
+}
+
+/// Pair recorded and replayed effects by bucket and target, in order
+fn compare_effects(original: &[EffectRecord], replayed: &[CapturedEffect]) -> Vec<Divergence> {
+    let mut divergences = Vec::new();
+    let mut unmatched: Vec<&CapturedEffect> = replayed.iter().collect();
+
+    for recorded in original {
+        let position = unmatched.iter()
+            .position(|e| e.bucket == recorded.effect_bucket && e.target == recorded.target_rid);
+
+        match position {
+            Some(i) => {
+                let effect = unmatched.remove(i);
+                let replayed_hash = effect.after.as_ref().map(|s| s.hash.clone());
+                if replayed_hash != recorded.after_hash {
+                    divergences.push(Divergence::AfterState {
+                        target: recorded.target_rid.clone(),
+                        original_hash: recorded.after_hash.clone(),
+                        replayed_hash,
+                    });
+                }
+            }
+            None => divergences.push(Divergence::MissingEffect {
+                bucket: recorded.effect_bucket,
+                target: recorded.target_rid.clone(),
+            }),
+        }
+    }
+
+    for effect in unmatched {
+        divergences.push(Divergence::UnexpectedEffect {
+            bucket: effect.bucket,
+            target: effect.target.clone(),
+        });
+    }
+
+    divergences
+}
+
+/// Replay result for a single VĀKYA
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayOutcome {
+    pub vakya_id: String,
+    pub action: String,
+    pub resource: String,
+    pub status: ReplayStatus,
+    /// Reason code of the original receipt
+    pub original_reason_code: Option<ReasonCode>,
+    /// Whether the dry-run dispatch succeeded; `None` when skipped
+    pub replay_success: Option<bool>,
+    pub replay_error: Option<String>,
+    /// False when the adapter reported no would-be effects in dry-run
+    pub effects_compared: bool,
+    pub divergences: Vec<Divergence>,
+}
+
+/// Replay status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplayStatus {
+    Matched,
+    Diverged,
+    Skipped,
+}
+
+/// A difference between the recorded and replayed execution
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Divergence {
+    /// The adapter now succeeds where it failed, or vice versa
+    Outcome {
+        original_success: bool,
+        replay_success: bool,
+    },
+    /// A recorded effect the replay would not produce
+    MissingEffect {
+        bucket: EffectBucket,
+        target: String,
+    },
+    /// A would-be effect that was not recorded originally
+    UnexpectedEffect {
+        bucket: EffectBucket,
+        target: String,
+    },
+    /// Same effect, different resulting state
+    AfterState {
+        target: String,
+        original_hash: Option<String>,
+        replayed_hash: Option<String>,
+    },
+}
+
+/// Summary of a replay run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReplayReport {
+    /// VĀKYAs dispatched (matched + diverged)
+    pub replayed: usize,
+    pub matched: usize,
+    pub diverged: usize,
+    pub skipped: usize,
+    pub results: Vec<ReplayOutcome>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aapi_adapters::StateSnapshot;
+
+    #[test]
+    fn test_compare_effects() {
+        let mut recorded = EffectRecord::new("v1".to_string(), EffectBucket::Read, "file:/a".to_string());
+        recorded.after_hash = Some(StateSnapshot::from_bytes(b"a").hash);
+
+        let mut same = CapturedEffect::new("v1", EffectBucket::Read, "file:/a");
+        same.after = Some(StateSnapshot::from_bytes(b"a"));
+        assert!(compare_effects(std::slice::from_ref(&recorded), &[same]).is_empty());
+
+        let mut changed = CapturedEffect::new("v1", EffectBucket::Read, "file:/a");
+        changed.after = Some(StateSnapshot::from_bytes(b"b"));
+        let extra = CapturedEffect::new("v1", EffectBucket::Create, "file:/b");
+        let divergences = compare_effects(std::slice::from_ref(&recorded), &[changed, extra]);
+        assert!(matches!(divergences[0], Divergence::AfterState { .. }));
+        assert_eq!(divergences[1], Divergence::UnexpectedEffect {
+            bucket: EffectBucket::Create,
+            target: "file:/b".to_string(),
+        });
+
+        let divergences = compare_effects(std::slice::from_ref(&recorded), &[]);
+        assert!(matches!(divergences[0], Divergence::MissingEffect { .. }));
+    }
+}
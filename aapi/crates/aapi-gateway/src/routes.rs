@@ -1,12 +1,14 @@
 //! Route definitions for the Gateway
 
 use axum::{
+    middleware,
     routing::{get, post},
     Router,
 };
 use std::sync::Arc;
 
 use crate::handlers::*;
+use crate::middleware::rate_limit;
 use crate::state::AppState;
 
 /// Create the main router with all routes
@@ -15,20 +17,50 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         // Health and status
         .route("/health", get(health_check))
         .route("/metrics", get(get_metrics))
+        .route("/metrics/prometheus", get(get_metrics_prometheus))
         
         // VĀKYA operations
-        .route("/v1/vakya", post(submit_vakya))
+        .route("/v1/vakya", post(submit_vakya_encoded))
+        .route("/v1/vakya/stream", post(submit_vakya_stream))
+        .route("/v1/vakya/plan", post(plan_vakya))
         .route("/v1/vakya/:vakya_id", get(get_vakya))
         .route("/v1/vakya/:vakya_id/receipt", get(get_receipt))
         .route("/v1/vakya/:vakya_id/effects", get(get_effects))
+        .route("/v1/vakya/:vakya_id/diff", get(get_vakya_diff))
+        .route("/v1/vakya/:vakya_id/proof", get(get_vakya_proof))
+        .route("/v1/vakya/:vakya_id/receipt/proof", get(get_receipt_proof))
+        .route("/v1/effects/:effect_id/proof", get(get_effect_proof))
+        
+        // Approvals
+        .route("/v1/approvals/:approval_id", get(get_approval).post(vote_approval))
+        
+        // Capabilities
+        .route("/v1/capabilities/:token_id", get(get_capability))
+        .route("/v1/capabilities/:token_id/revoke", post(revoke_capability))
+        
+        // Policy
+        .route("/v1/policy/simulate", post(simulate_policy))
+        .route("/v1/policy/reload", post(reload_policies))
+        
+        // Replay
+        .route("/v1/replay", post(replay_vakyas))
         
         // Transparency log
         .route("/v1/merkle/root", get(get_merkle_root))
         .route("/v1/merkle/proof", get(get_inclusion_proof))
+        .route("/v1/merkle/consistency", get(get_consistency_proof))
+        .route("/v1/export", get(export_evidence))
+        .route("/v1/export/bundle", post(export_bundle))
+        .route("/v1/audit", get(get_audit_log))
+        .route("/v1/keys", get(list_public_keys))
+        .route("/v1/keys/:key_id", get(get_public_key))
+        .route("/.well-known/jwks.json", get(get_jwks))
         
         // Adapters
         .route("/v1/adapters", get(list_adapters))
         
+        .layer(middleware::from_fn_with_state(Arc::clone(&state), rate_limit))
+        
         // State
         .with_state(state)
 }
@@ -92,6 +124,13 @@ async fn openapi_spec() -> axum::Json<serde_json::Value> {
                                 "schema": {
                                     "$ref": "#/components/schemas/SubmitVakyaRequest"
                                 }
+                            },
+                            "application/protobuf": {
+                                "schema": {
+                                    "type": "string",
+                                    "format": "binary",
+                                    "description": "aapi.v1.SubmitVakyaRequest message (proto/vakya.proto in aapi-core)"
+                                }
                             }
                         }
                     },
@@ -115,6 +154,61 @@ async fn openapi_spec() -> axum::Json<serde_json::Value> {
                     }
                 }
             },
+            "/v1/vakya/stream": {
+                "post": {
+                    "summary": "Submit a VĀKYA and stream its execution",
+                    "description": "Runs the same pipeline as POST /v1/vakya and streams server-sent events: a `progress` event per adapter update, then a `result` event with the submission response or an `error` event if the submission was refused",
+                    "operationId": "submitVakyaStream",
+                    "tags": ["VĀKYA"],
+                    "requestBody": {
+                        "required": true,
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "$ref": "#/components/schemas/SubmitVakyaRequest"
+                                }
+                            }
+                        }
+                    },
+                    "responses": {
+                        "200": {
+                            "description": "Event stream",
+                            "content": {
+                                "text/event-stream": {}
+                            }
+                        }
+                    }
+                }
+            },
+            "/v1/vakya/plan": {
+                "post": {
+                    "summary": "Plan a VĀKYA without executing it",
+                    "description": "Runs validation, policy and an adapter dry run, and reports the resolved adapter, predicted effect buckets and whether each effect is reversible; nothing is stored and no receipt is issued",
+                    "operationId": "planVakya",
+                    "tags": ["VĀKYA"],
+                    "requestBody": {
+                        "required": true,
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "$ref": "#/components/schemas/SubmitVakyaRequest"
+                                }
+                            }
+                        }
+                    },
+                    "responses": {
+                        "200": {
+                            "description": "Policy decision, predicted effects and dry-run output"
+                        },
+                        "400": {
+                            "description": "Validation error"
+                        },
+                        "403": {
+                            "description": "Authorization denied"
+                        }
+                    }
+                }
+            },
             "/v1/vakya/{vakya_id}": {
                 "get": {
                     "summary": "Get a VĀKYA by ID",
@@ -187,50 +281,101 @@ async fn openapi_spec() -> axum::Json<serde_json::Value> {
                     }
                 }
             },
-            "/v1/merkle/root": {
+            "/v1/vakya/{vakya_id}/diff": {
                 "get": {
-                    "summary": "Get Merkle tree root",
-                    "operationId": "getMerkleRoot",
+                    "summary": "Get the state changes made by a VĀKYA",
+                    "description": "Combines the per-effect deltas (change type, JSON patch and one-line summary) into a change report",
+                    "operationId": "getVakyaDiff",
+                    "tags": ["VĀKYA"],
+                    "parameters": [
+                        {
+                            "name": "vakya_id",
+                            "in": "path",
+                            "required": true,
+                            "schema": {
+                                "type": "string"
+                            }
+                        }
+                    ],
+                    "responses": {
+                        "200": {
+                            "description": "Change report",
+                            "content": {
+                                "application/json": {
+                                    "schema": {
+                                        "$ref": "#/components/schemas/VakyaDiffResponse"
+                                    }
+                                }
+                            }
+                        },
+                        "404": {
+                            "description": "VĀKYA not found"
+                        }
+                    }
+                }
+            },
+            "/v1/vakya/{vakya_id}/proof": {
+                "get": {
+                    "summary": "Get inclusion proof for a VĀKYA",
+                    "operationId": "getVakyaProof",
                     "tags": ["Transparency"],
                     "parameters": [
                         {
-                            "name": "tree_type",
-                            "in": "query",
+                            "name": "vakya_id",
+                            "in": "path",
                             "required": true,
                             "schema": {
-                                "type": "string",
-                                "enum": ["vakya", "effect", "receipt"]
+                                "type": "string"
                             }
                         }
                     ],
                     "responses": {
                         "200": {
-                            "description": "Merkle root"
+                            "description": "Inclusion proof"
+                        },
+                        "404": {
+                            "description": "VĀKYA not found or not yet in the tree"
                         }
                     }
                 }
             },
-            "/v1/merkle/proof": {
+            "/v1/vakya/{vakya_id}/receipt/proof": {
                 "get": {
-                    "summary": "Get inclusion proof",
-                    "operationId": "getInclusionProof",
+                    "summary": "Get inclusion proof for a VĀKYA's receipt",
+                    "operationId": "getReceiptProof",
                     "tags": ["Transparency"],
                     "parameters": [
                         {
-                            "name": "tree_type",
-                            "in": "query",
+                            "name": "vakya_id",
+                            "in": "path",
                             "required": true,
                             "schema": {
-                                "type": "string",
-                                "enum": ["vakya", "effect", "receipt"]
+                                "type": "string"
                             }
+                        }
+                    ],
+                    "responses": {
+                        "200": {
+                            "description": "Inclusion proof"
                         },
+                        "404": {
+                            "description": "Receipt not found or not yet in the tree"
+                        }
+                    }
+                }
+            },
+            "/v1/effects/{effect_id}/proof": {
+                "get": {
+                    "summary": "Get inclusion proof for an effect",
+                    "operationId": "getEffectProof",
+                    "tags": ["Transparency"],
+                    "parameters": [
                         {
-                            "name": "leaf_index",
-                            "in": "query",
+                            "name": "effect_id",
+                            "in": "path",
                             "required": true,
                             "schema": {
-                                "type": "integer"
+                                "type": "string"
                             }
                         }
                     ],
@@ -239,65 +384,716 @@ async fn openapi_spec() -> axum::Json<serde_json::Value> {
                             "description": "Inclusion proof"
                         },
                         "404": {
-                            "description": "Proof not found"
+                            "description": "Effect not found or not yet in the tree"
                         }
                     }
                 }
             },
-            "/v1/adapters": {
+            "/v1/approvals/{approval_id}": {
                 "get": {
-                    "summary": "List registered adapters",
-                    "operationId": "listAdapters",
-                    "tags": ["Adapters"],
+                    "summary": "Get an approval by ID",
+                    "operationId": "getApproval",
+                    "tags": ["Approvals"],
+                    "parameters": [
+                        {
+                            "name": "approval_id",
+                            "in": "path",
+                            "required": true,
+                            "schema": {
+                                "type": "string"
+                            }
+                        }
+                    ],
                     "responses": {
                         "200": {
-                            "description": "List of adapters"
+                            "description": "Approval state"
+                        },
+                        "404": {
+                            "description": "Approval not found"
+                        }
+                    }
+                },
+                "post": {
+                    "summary": "Vote on a pending approval",
+                    "description": "The approver is the caller's principal, from a bound API key or the verified client certificate; submitters cannot vote on their own VĀKYAs",
+                    "operationId": "voteApproval",
+                    "tags": ["Approvals"],
+                    "parameters": [
+                        {
+                            "name": "approval_id",
+                            "in": "path",
+                            "required": true,
+                            "schema": {
+                                "type": "string"
+                            }
+                        }
+                    ],
+                    "requestBody": {
+                        "required": true,
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "$ref": "#/components/schemas/ApprovalVoteRequest"
+                                }
+                            }
+                        }
+                    },
+                    "responses": {
+                        "200": {
+                            "description": "Updated approval state; includes the receipt once resolved"
+                        },
+                        "403": {
+                            "description": "Caller not authenticated, not a listed approver, or the submitter"
+                        },
+                        "404": {
+                            "description": "Approval not found"
+                        },
+                        "409": {
+                            "description": "Approval already resolved or approver already voted"
                         }
                     }
                 }
             },
-            "/metrics": {
+            "/v1/capabilities/{token_id}": {
                 "get": {
-                    "summary": "Get gateway metrics",
-                    "operationId": "getMetrics",
-                    "tags": ["System"],
+                    "summary": "Get the revocation status of a capability token",
+                    "operationId": "getCapability",
+                    "tags": ["Capabilities"],
+                    "parameters": [
+                        {
+                            "name": "token_id",
+                            "in": "path",
+                            "required": true,
+                            "schema": {
+                                "type": "string"
+                            }
+                        }
+                    ],
                     "responses": {
                         "200": {
-                            "description": "Gateway metrics"
+                            "description": "Revocation status, with the revocation record if revoked",
+                            "content": {
+                                "application/json": {
+                                    "schema": {
+                                        "$ref": "#/components/schemas/CapabilityStatusResponse"
+                                    }
+                                }
+                            }
+                        },
+                        "403": {
+                            "description": "Caller is not a capability operator, or is limited to namespaces"
                         }
                     }
                 }
-            }
-        },
-        "components": {
-            "schemas": {
-                "HealthResponse": {
-                    "type": "object",
-                    "properties": {
-                        "status": { "type": "string" },
-                        "gateway_id": { "type": "string" },
-                        "version": { "type": "string" },
-                        "timestamp": { "type": "string", "format": "date-time" }
+            },
+            "/v1/capabilities/{token_id}/revoke": {
+                "post": {
+                    "summary": "Revoke a capability token",
+                    "description": "Persists the revocation and rejects the token, and optionally every token delegated from it, on every later verification; the calling operator is recorded as the revoker",
+                    "operationId": "revokeCapability",
+                    "tags": ["Capabilities"],
+                    "parameters": [
+                        {
+                            "name": "token_id",
+                            "in": "path",
+                            "required": true,
+                            "schema": {
+                                "type": "string"
+                            }
+                        }
+                    ],
+                    "requestBody": {
+                        "required": false,
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "$ref": "#/components/schemas/RevokeCapabilityRequest"
+                                }
+                            }
+                        }
+                    },
+                    "responses": {
+                        "200": {
+                            "description": "Token revoked",
+                            "content": {
+                                "application/json": {
+                                    "schema": {
+                                        "$ref": "#/components/schemas/CapabilityStatusResponse"
+                                    }
+                                }
+                            }
+                        },
+                        "403": {
+                            "description": "Caller is not a capability operator, or is limited to namespaces"
+                        }
                     }
-                },
-                "SubmitVakyaRequest": {
-                    "type": "object",
-                    "required": ["vakya"],
-                    "properties": {
-                        "vakya": { "$ref": "#/components/schemas/Vakya" },
-                        "signature": { "type": "string" },
-                        "key_id": { "type": "string" }
+                }
+            },
+            "/v1/policy/simulate": {
+                "post": {
+                    "summary": "Simulate a candidate policy set",
+                    "description": "Evaluates the given policies against ad-hoc or stored VĀKYAs using a temporary engine; live policies are not modified",
+                    "operationId": "simulatePolicy",
+                    "tags": ["Policy"],
+                    "requestBody": {
+                        "required": true,
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "$ref": "#/components/schemas/PolicySimulationRequest"
+                                }
+                            }
+                        }
+                    },
+                    "responses": {
+                        "200": {
+                            "description": "Simulated decisions and matched rules"
+                        },
+                        "400": {
+                            "description": "Invalid policy or missing input"
+                        }
                     }
-                },
-                "SubmitVakyaResponse": {
-                    "type": "object",
+                }
+            },
+            "/v1/policy/reload": {
+                "post": {
+                    "summary": "Reload policies from the policy directory",
+                    "description": "Re-reads the gateway's configured policy directory and atomically swaps in the new policy set; on failure the previous set stays active",
+                    "operationId": "reloadPolicies",
+                    "tags": ["Policy"],
+                    "responses": {
+                        "200": {
+                            "description": "Policies reloaded",
+                            "content": {
+                                "application/json": {
+                                    "schema": {
+                                        "$ref": "#/components/schemas/PolicyReloadResponse"
+                                    }
+                                }
+                            }
+                        },
+                        "400": {
+                            "description": "No policy directory configured, or the policy files are invalid"
+                        }
+                    }
+                }
+            },
+            "/v1/replay": {
+                "post": {
+                    "summary": "Replay stored VĀKYAs against current adapters",
+                    "description": "Re-dispatches matching VĀKYAs in dry-run mode and compares outcome and would-be effects with the recorded ones",
+                    "operationId": "replayVakyas",
+                    "tags": ["Replay"],
+                    "requestBody": {
+                        "required": true,
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "$ref": "#/components/schemas/ReplayRequest"
+                                }
+                            }
+                        }
+                    },
+                    "responses": {
+                        "200": {
+                            "description": "Per-VĀKYA replay outcome and divergences"
+                        }
+                    }
+                }
+            },
+            "/v1/merkle/root": {
+                "get": {
+                    "summary": "Get Merkle tree root",
+                    "operationId": "getMerkleRoot",
+                    "tags": ["Transparency"],
+                    "parameters": [
+                        {
+                            "name": "tree_type",
+                            "in": "query",
+                            "required": true,
+                            "schema": {
+                                "type": "string",
+                                "enum": ["vakya", "effect", "receipt"]
+                            }
+                        }
+                    ],
+                    "responses": {
+                        "200": {
+                            "description": "Merkle root"
+                        }
+                    }
+                }
+            },
+            "/v1/merkle/proof": {
+                "get": {
+                    "summary": "Get inclusion proof",
+                    "operationId": "getInclusionProof",
+                    "tags": ["Transparency"],
+                    "parameters": [
+                        {
+                            "name": "tree_type",
+                            "in": "query",
+                            "required": true,
+                            "schema": {
+                                "type": "string",
+                                "enum": ["vakya", "effect", "receipt"]
+                            }
+                        },
+                        {
+                            "name": "leaf_index",
+                            "in": "query",
+                            "required": true,
+                            "schema": {
+                                "type": "integer"
+                            }
+                        }
+                    ],
+                    "responses": {
+                        "200": {
+                            "description": "Inclusion proof"
+                        },
+                        "404": {
+                            "description": "Proof not found"
+                        }
+                    }
+                }
+            },
+            "/v1/merkle/consistency": {
+                "get": {
+                    "summary": "Get consistency proof between two tree sizes",
+                    "operationId": "getConsistencyProof",
+                    "tags": ["Transparency"],
+                    "parameters": [
+                        {
+                            "name": "tree_type",
+                            "in": "query",
+                            "required": true,
+                            "schema": {
+                                "type": "string",
+                                "enum": ["vakya", "effect", "receipt"]
+                            }
+                        },
+                        {
+                            "name": "first_size",
+                            "in": "query",
+                            "required": true,
+                            "schema": {
+                                "type": "integer"
+                            }
+                        },
+                        {
+                            "name": "second_size",
+                            "in": "query",
+                            "required": true,
+                            "schema": {
+                                "type": "integer"
+                            }
+                        }
+                    ],
+                    "responses": {
+                        "200": {
+                            "description": "RFC 6962 consistency proof with the roots at both sizes"
+                        },
+                        "400": {
+                            "description": "Invalid tree type or sizes"
+                        },
+                        "404": {
+                            "description": "Tree is smaller than second_size"
+                        }
+                    }
+                }
+            },
+            "/v1/export": {
+                "get": {
+                    "summary": "Stream the evidence log as JSON lines",
+                    "operationId": "exportEvidence",
+                    "tags": ["Transparency"],
+                    "parameters": [
+                        {
+                            "name": "from",
+                            "in": "query",
+                            "required": false,
+                            "schema": {
+                                "type": "string",
+                                "format": "date-time"
+                            }
+                        },
+                        {
+                            "name": "to",
+                            "in": "query",
+                            "required": false,
+                            "schema": {
+                                "type": "string",
+                                "format": "date-time"
+                            }
+                        },
+                        {
+                            "name": "types",
+                            "in": "query",
+                            "required": false,
+                            "description": "Comma-separated record types (vakya, effect, receipt)",
+                            "schema": {
+                                "type": "string"
+                            }
+                        }
+                    ],
+                    "responses": {
+                        "200": {
+                            "description": "One record per line in created_at order, followed by a Merkle checkpoint per tree",
+                            "content": {
+                                "application/x-ndjson": {}
+                            }
+                        },
+                        "400": {
+                            "description": "Invalid record type"
+                        }
+                    }
+                }
+            },
+            "/v1/export/bundle": {
+                "post": {
+                    "summary": "Build a signed evidence bundle for offline verification",
+                    "description": "The selected VĀKYA records with their effects and receipts, an inclusion proof for each, signed tree heads and the gateway's public keys",
+                    "operationId": "exportBundle",
+                    "tags": ["Transparency"],
+                    "requestBody": {
+                        "required": true,
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "$ref": "#/components/schemas/ExportBundleRequest"
+                                }
+                            }
+                        }
+                    },
+                    "responses": {
+                        "200": {
+                            "description": "Export bundle"
+                        },
+                        "400": {
+                            "description": "No VĀKYA IDs, or too many"
+                        },
+                        "404": {
+                            "description": "Unknown VĀKYA ID"
+                        }
+                    }
+                }
+            },
+            "/v1/audit": {
+                "get": {
+                    "summary": "Query the audit log of policy denials and executions, newest first",
+                    "operationId": "getAuditLog",
+                    "tags": ["Transparency"],
+                    "parameters": [
+                        {
+                            "name": "event_type",
+                            "in": "query",
+                            "required": false,
+                            "description": "e.g. vakya_denied or vakya_executed",
+                            "schema": {
+                                "type": "string"
+                            }
+                        },
+                        {
+                            "name": "actor",
+                            "in": "query",
+                            "required": false,
+                            "schema": {
+                                "type": "string"
+                            }
+                        },
+                        {
+                            "name": "from",
+                            "in": "query",
+                            "required": false,
+                            "schema": {
+                                "type": "string",
+                                "format": "date-time"
+                            }
+                        },
+                        {
+                            "name": "to",
+                            "in": "query",
+                            "required": false,
+                            "schema": {
+                                "type": "string",
+                                "format": "date-time"
+                            }
+                        },
+                        {
+                            "name": "offset",
+                            "in": "query",
+                            "required": false,
+                            "schema": {
+                                "type": "integer",
+                                "default": 0
+                            }
+                        },
+                        {
+                            "name": "limit",
+                            "in": "query",
+                            "required": false,
+                            "schema": {
+                                "type": "integer",
+                                "default": 100,
+                                "maximum": 1000
+                            }
+                        }
+                    ],
+                    "responses": {
+                        "200": {
+                            "description": "A page of audit entries with the total match count"
+                        },
+                        "403": {
+                            "description": "Caller is restricted to namespaces"
+                        }
+                    }
+                }
+            },
+            "/v1/keys": {
+                "get": {
+                    "summary": "List the gateway's public keys",
+                    "operationId": "listPublicKeys",
+                    "tags": ["Transparency"],
+                    "responses": {
+                        "200": {
+                            "description": "Public key information for every key"
+                        }
+                    }
+                }
+            },
+            "/.well-known/jwks.json": {
+                "get": {
+                    "summary": "Get the gateway's public keys as a JWKS document",
+                    "description": "Ed25519 keys as OKP JWKs with the key ID as kid and the key purpose as a custom purpose member",
+                    "operationId": "getJwks",
+                    "tags": ["Transparency"],
+                    "responses": {
+                        "200": {
+                            "description": "JSON Web Key Set"
+                        }
+                    }
+                }
+            },
+            "/v1/keys/{key_id}": {
+                "get": {
+                    "summary": "Get a gateway public key, e.g. the receipt-signing key named by a receipt's key_id",
+                    "operationId": "getPublicKey",
+                    "tags": ["Transparency"],
+                    "parameters": [
+                        {
+                            "name": "key_id",
+                            "in": "path",
+                            "required": true,
+                            "schema": {
+                                "type": "string"
+                            }
+                        }
+                    ],
+                    "responses": {
+                        "200": {
+                            "description": "Public key information"
+                        },
+                        "404": {
+                            "description": "Key not found"
+                        }
+                    }
+                }
+            },
+            "/v1/adapters": {
+                "get": {
+                    "summary": "List registered adapters",
+                    "operationId": "listAdapters",
+                    "tags": ["Adapters"],
+                    "responses": {
+                        "200": {
+                            "description": "List of adapters"
+                        }
+                    }
+                }
+            },
+            "/metrics": {
+                "get": {
+                    "summary": "Get gateway metrics",
+                    "operationId": "getMetrics",
+                    "tags": ["System"],
+                    "responses": {
+                        "200": {
+                            "description": "Gateway metrics, including latency percentiles and the recent request rate"
+                        }
+                    }
+                }
+            },
+            "/metrics/prometheus": {
+                "get": {
+                    "summary": "Get gateway metrics in Prometheus text format",
+                    "operationId": "getMetricsPrometheus",
+                    "tags": ["System"],
+                    "responses": {
+                        "200": {
+                            "description": "Prometheus text exposition",
+                            "content": {
+                                "text/plain": {}
+                            }
+                        }
+                    }
+                }
+            }
+        },
+        "components": {
+            "schemas": {
+                "HealthResponse": {
+                    "type": "object",
+                    "properties": {
+                        "status": { "type": "string" },
+                        "gateway_id": { "type": "string" },
+                        "version": { "type": "string" },
+                        "timestamp": { "type": "string", "format": "date-time" }
+                    }
+                },
+                "SubmitVakyaRequest": {
+                    "type": "object",
+                    "required": ["vakya"],
+                    "properties": {
+                        "vakya": { "$ref": "#/components/schemas/Vakya" },
+                        "signature": { "type": "string" },
+                        "key_id": { "type": "string" }
+                    }
+                },
+                "SubmitVakyaResponse": {
+                    "type": "object",
                     "properties": {
                         "vakya_id": { "type": "string" },
                         "vakya_hash": { "type": "string" },
-                        "status": { "type": "string" },
+                        "status": {
+                            "type": "string",
+                            "description": "`accepted`, `partial` when some items of a multi-item action failed (listed in the receipt's `item_errors`), `failed`, `denied`, `pending_approval` or `deferred`"
+                        },
                         "receipt": { "$ref": "#/components/schemas/Receipt" },
                         "merkle_root": { "type": "string" },
-                        "leaf_index": { "type": "integer" }
+                        "leaf_index": { "type": "integer" },
+                        "deduplicated": { "type": "boolean" },
+                        "warnings": {
+                            "type": "array",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "path": { "type": "string" },
+                                    "code": { "type": "string" },
+                                    "message": { "type": "string" }
+                                }
+                            }
+                        }
+                    }
+                },
+                "ApprovalVoteRequest": {
+                    "type": "object",
+                    "required": ["decision"],
+                    "properties": {
+                        "decision": { "type": "string", "enum": ["approve", "deny"] },
+                        "comment": { "type": "string" }
+                    }
+                },
+                "RevokeCapabilityRequest": {
+                    "type": "object",
+                    "properties": {
+                        "include_delegations": { "type": "boolean" },
+                        "reason": { "type": "string" }
+                    }
+                },
+                "CapabilityStatusResponse": {
+                    "type": "object",
+                    "required": ["token_id", "revoked"],
+                    "properties": {
+                        "token_id": { "type": "string" },
+                        "revoked": { "type": "boolean" },
+                        "revocation": {
+                            "type": "object",
+                            "properties": {
+                                "token_id": { "type": "string" },
+                                "include_delegations": { "type": "boolean" },
+                                "reason": { "type": "string" },
+                                "revoked_by": { "type": "string" },
+                                "revoked_at": { "type": "string", "format": "date-time" }
+                            }
+                        }
+                    }
+                },
+                "PolicySimulationRequest": {
+                    "type": "object",
+                    "required": ["policies"],
+                    "properties": {
+                        "policies": { "type": "array", "items": { "type": "object" } },
+                        "default_allow": { "type": "boolean" },
+                        "explain": { "type": "boolean" },
+                        "vakyas": { "type": "array", "items": { "$ref": "#/components/schemas/Vakya" } },
+                        "query": {
+                            "type": "object",
+                            "properties": {
+                                "actor": { "type": "string" },
+                                "action": { "type": "string" },
+                                "resource_prefix": { "type": "string" },
+                                "namespace": { "type": "string" },
+                                "from": { "type": "string", "format": "date-time" },
+                                "to": { "type": "string", "format": "date-time" },
+                                "limit": { "type": "integer" }
+                            }
+                        }
+                    }
+                },
+                "VakyaDiffResponse": {
+                    "type": "object",
+                    "properties": {
+                        "vakya_id": { "type": "string" },
+                        "summary": { "type": "string" },
+                        "effects": {
+                            "type": "array",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "effect_id": { "type": "string" },
+                                    "target": { "type": "string" },
+                                    "bucket": { "type": "string" },
+                                    "change_type": { "type": "string", "enum": ["created", "modified", "deleted", "unchanged"] },
+                                    "summary": { "type": "string" },
+                                    "json_patch": { "type": "array", "items": { "type": "object" } }
+                                }
+                            }
+                        }
+                    }
+                },
+                "PolicyReloadResponse": {
+                    "type": "object",
+                    "properties": {
+                        "version": { "type": "integer" },
+                        "policies": { "type": "integer" }
+                    }
+                },
+                "ReplayRequest": {
+                    "type": "object",
+                    "properties": {
+                        "filter": {
+                            "type": "object",
+                            "properties": {
+                                "actor": { "type": "string" },
+                                "action": { "type": "string" },
+                                "resource_prefix": { "type": "string" },
+                                "namespace": { "type": "string" },
+                                "from": { "type": "string", "format": "date-time" },
+                                "to": { "type": "string", "format": "date-time" },
+                                "limit": { "type": "integer" }
+                            }
+                        }
+                    }
+                },
+                "ExportBundleRequest": {
+                    "type": "object",
+                    "required": ["vakya_ids"],
+                    "properties": {
+                        "vakya_ids": {
+                            "type": "array",
+                            "items": { "type": "string" }
+                        }
                     }
                 },
                 "Vakya": {
@@ -313,6 +1109,9 @@ async fn openapi_spec() -> axum::Json<serde_json::Value> {
         "tags": [
             { "name": "System", "description": "System operations" },
             { "name": "VĀKYA", "description": "VĀKYA submission and retrieval" },
+            { "name": "Approvals", "description": "Human approval workflow" },
+            { "name": "Policy", "description": "Policy simulation" },
+            { "name": "Replay", "description": "Dry-run replay of stored VĀKYAs" },
             { "name": "Transparency", "description": "Transparency log operations" },
             { "name": "Adapters", "description": "Adapter management" }
         ]
@@ -1,13 +1,19 @@
 //! Gateway server implementation
 
 use axum::middleware;
+use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::net::TcpListener;
-use tracing::{info, error};
+use tracing::{info, error, Subscriber};
+use tracing_subscriber::registry::LookupSpan;
 
-use crate::middleware::{cors_layer, compression_layer, logging, request_id};
+use crate::error::GatewayResult;
+use crate::handlers::resume_deferred_executions;
+use crate::middleware::{cors_layer, compression_layer, logging, request_id, RateLimiter};
+use crate::redaction::{fmt_layer, RedactingFields};
 use crate::routes::create_router_with_docs;
 use crate::state::{AppState, GatewayConfig};
+use crate::tls::{serve_tls, TlsConfig};
 
 /// AAPI Gateway Server
 pub struct GatewayServer {
@@ -18,6 +24,7 @@ impl GatewayServer {
     /// Create a new gateway server with the given configuration
     pub async fn new(config: GatewayConfig) -> Result<Self, Box<dyn std::error::Error>> {
         let state = Arc::new(AppState::new(config).await?);
+        resume_deferred_executions(&state).await?;
         Ok(Self { state })
     }
 
@@ -32,6 +39,14 @@ impl GatewayServer {
         Arc::clone(&self.state)
     }
 
+    /// Log formatting layer applying the configured `log_redaction`
+    pub fn tracing_layer<S>(&self) -> GatewayResult<tracing_subscriber::fmt::Layer<S, RedactingFields>>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        fmt_layer(&self.state.config.log_redaction)
+    }
+
     /// Build the router with all middleware
     pub fn router(&self) -> axum::Router {
         create_router_with_docs(Arc::clone(&self.state))
@@ -43,19 +58,7 @@ impl GatewayServer {
 
     /// Run the server
     pub async fn run(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let addr = self.state.config.bind_address();
-        let router = self.router();
-
-        info!(address = %addr, "Starting AAPI Gateway");
-
-        let listener = TcpListener::bind(&addr).await?;
-        
-        axum::serve(listener, router)
-            .await
-            .map_err(|e| {
-                error!(error = %e, "Server error");
-                Box::new(e) as Box<dyn std::error::Error>
-            })
+        self.run_with_shutdown(std::future::pending()).await
     }
 
     /// Run the server with graceful shutdown
@@ -64,19 +67,38 @@ impl GatewayServer {
         shutdown_signal: impl std::future::Future<Output = ()> + Send + 'static,
     ) -> Result<(), Box<dyn std::error::Error>> {
         let addr = self.state.config.bind_address();
-        let router = self.router();
 
-        info!(address = %addr, "Starting AAPI Gateway with graceful shutdown");
+        info!(address = %addr, tls = self.state.config.tls.is_some(), "Starting AAPI Gateway with graceful shutdown");
 
         let listener = TcpListener::bind(&addr).await?;
-        
-        axum::serve(listener, router)
-            .with_graceful_shutdown(shutdown_signal)
-            .await
-            .map_err(|e| {
-                error!(error = %e, "Server error");
-                Box::new(e) as Box<dyn std::error::Error>
-            })
+        self.serve_with_shutdown(listener, shutdown_signal).await
+    }
+
+    /// Serve on an already bound listener, over TLS when configured
+    pub async fn serve_with_shutdown(
+        &self,
+        listener: TcpListener,
+        shutdown_signal: impl std::future::Future<Output = ()> + Send + 'static,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let router = self.router();
+        let cleanup = self.state.rate_limiter.as_ref().map(RateLimiter::spawn_cleanup);
+
+        let tls_config = self.state.config.tls.as_ref().map(TlsConfig::server_config).transpose()?;
+
+        let result = match tls_config {
+            Some(tls_config) => serve_tls(listener, router, tls_config, shutdown_signal).await,
+            None => axum::serve(listener, router.into_make_service_with_connect_info::<SocketAddr>())
+                .with_graceful_shutdown(shutdown_signal)
+                .await,
+        };
+        if let Some(cleanup) = cleanup {
+            cleanup.abort();
+        }
+
+        result.map_err(|e| {
+            error!(error = %e, "Server error");
+            Box::new(e) as Box<dyn std::error::Error>
+        })
     }
 }
 
@@ -143,6 +165,121 @@ impl GatewayServerBuilder {
         self
     }
 
+    pub fn policy_dir(mut self, dir: impl Into<std::path::PathBuf>) -> Self {
+        self.config.policy_dir = Some(dir.into());
+        self
+    }
+
+    /// Restrict callers presenting `api_key` to reading `namespace`; binding
+    /// [`ALL_NAMESPACES`](crate::namespace::ALL_NAMESPACES) leaves them unrestricted
+    pub fn bind_api_key_namespace(mut self, api_key: impl Into<String>, namespace: impl Into<String>) -> Self {
+        self.config.api_key_namespaces
+            .entry(api_key.into())
+            .or_default()
+            .push(namespace.into());
+        self
+    }
+
+    /// Allow `principal` to inspect and revoke capability tokens
+    pub fn capability_operator(mut self, principal: impl Into<String>) -> Self {
+        self.config.capability_operators.push(principal.into());
+        self
+    }
+
+    /// Allow `principal` to reload policies through the API
+    pub fn policy_operator(mut self, principal: impl Into<String>) -> Self {
+        self.config.policy_operators.push(principal.into());
+        self
+    }
+
+    /// Authenticate callers presenting `api_key` as `principal`
+    pub fn bind_api_key_principal(mut self, api_key: impl Into<String>, principal: impl Into<String>) -> Self {
+        self.config.api_key_principals.insert(api_key.into(), principal.into());
+        self
+    }
+
+    /// Terminate TLS with the given settings
+    pub fn tls(mut self, tls: TlsConfig) -> Self {
+        self.config.tls = Some(tls);
+        self
+    }
+
+    /// Sandbox file operations and HTTP downloads to `dir`
+    pub fn file_base_dir(mut self, dir: impl Into<std::path::PathBuf>) -> Self {
+        self.config.file_base_dir = dir.into();
+        self
+    }
+
+    /// Permission bits applied to the file sandbox directory at startup
+    pub fn file_base_dir_mode(mut self, mode: u32) -> Self {
+        self.config.file_base_dir_mode = Some(mode);
+        self
+    }
+
+    /// Restrict the HTTP adapter to these hosts
+    pub fn http_allowed_hosts(mut self, hosts: Vec<String>) -> Self {
+        self.config.http_allowed_hosts = hosts;
+        self
+    }
+
+    /// Never let the HTTP adapter reach these hosts
+    pub fn http_denied_hosts(mut self, hosts: Vec<String>) -> Self {
+        self.config.http_denied_hosts = hosts;
+        self
+    }
+
+    /// Refuse HTTP requests to loopback, private and link-local addresses
+    pub fn http_block_private(mut self, block: bool) -> Self {
+        self.config.http_block_private = block;
+        self
+    }
+
+    /// Fail executions whose effects diverge from the declared `expected_effect`
+    pub fn strict_effect_matching(mut self, strict: bool) -> Self {
+        self.config.strict_effect_matching = strict;
+        self
+    }
+
+    /// Cache VĀKYA, receipt and effect reads in memory
+    pub fn record_cache(mut self, cache: aapi_indexdb::CacheConfig) -> Self {
+        self.config.record_cache = Some(cache);
+        self
+    }
+
+    /// Envelope-encrypt stored effect state under `key`
+    pub fn encrypt_state(mut self, key: aapi_crypto::MasterKey) -> Self {
+        self.config.encrypt_state = Some(key);
+        self
+    }
+
+    /// Bound concurrent executions, waiting up to `queue_wait_ms` for a slot
+    pub fn max_concurrent_executions(mut self, max: usize, queue_wait_ms: u64) -> Self {
+        self.config.max_concurrent_executions = max;
+        self.config.execution_queue_wait_ms = queue_wait_ms;
+        self
+    }
+
+    /// Bound how many deferred VĀKYAs may wait for their `not_before` time
+    pub fn max_deferred_executions(mut self, max: usize) -> Self {
+        self.config.max_deferred_executions = max;
+        self
+    }
+
+    /// Values to mask or hash in the logs; see [`crate::redaction`]
+    pub fn log_redaction(mut self, redaction: crate::redaction::LogRedaction) -> Self {
+        self.config.log_redaction = redaction;
+        self
+    }
+
+    /// Log formatting layer applying the configured `log_redaction`, for
+    /// installing before the server is built so startup is redacted too
+    pub fn tracing_layer<S>(&self) -> GatewayResult<tracing_subscriber::fmt::Layer<S, RedactingFields>>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        fmt_layer(&self.config.log_redaction)
+    }
+
     pub async fn build(self) -> Result<GatewayServer, Box<dyn std::error::Error>> {
         GatewayServer::new(self.config).await
     }
@@ -178,4 +315,21 @@ mod tests {
         let _router = server.router();
         // Router created successfully
     }
+
+    #[tokio::test]
+    async fn test_tracing_layer_follows_config() {
+        use crate::redaction::LogRedaction;
+        use tracing_subscriber::Registry;
+
+        let invalid = GatewayServerBuilder::new().log_redaction(LogRedaction::default().with_pattern("(unclosed"));
+        assert!(invalid.tracing_layer::<Registry>().is_err());
+
+        let server = GatewayServerBuilder::new()
+            .log_redaction(LogRedaction::default().with_pattern(r"/home/\S+"))
+            .build_in_memory()
+            .await
+            .unwrap();
+        assert!(server.tracing_layer::<Registry>().is_ok());
+        assert!(server.state.config.log_redaction.is_enabled());
+    }
 }
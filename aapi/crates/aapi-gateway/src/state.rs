@@ -1,13 +1,47 @@
 //! Gateway application state
 
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{OwnedSemaphorePermit, RwLock, Semaphore};
 use tracing::info;
 
-use aapi_adapters::{Dispatcher, RegistryBuilder};
-use aapi_crypto::{KeyStore, CapabilityVerifier, VakyaSigner, VakyaVerifier};
-use aapi_indexdb::{SqliteIndexDb, IndexDbStore};
-use aapi_metarules::{PolicyEngine, Policy, Rule, Condition, ConditionType, Operator};
+use aapi_core::error::ReasonCode;
+use aapi_core::{BudgetTracker, CostEstimator, EffectCostEstimator, VakyaValidator};
+use aapi_adapters::{ActionFilter, AdapterRegistry, Dispatcher, FileAdapter, HttpAdapter, RegistryBuilder};
+use aapi_crypto::{KeyId, KeyStore, CapabilityVerifier, MasterKey, RevocationList, SignedTreeHead, VakyaSigner, VakyaVerifier};
+use aapi_indexdb::{CacheConfig, CachingStore, DbConfig, ReceiptRecord, SqliteIndexDb, IndexDbStore};
+
+use crate::dedup::DedupIndex;
+use crate::error::{GatewayError, GatewayResult};
+
+use crate::metrics::{LatencyHistogram, LatencyPercentiles, RateWindow, RATE_WINDOW};
+use crate::middleware::RateLimiter;
+use crate::redaction::LogRedaction;
+use crate::tls::TlsConfig;
+use aapi_metarules::{PolicyEngine, PolicyWatcher, Policy, Rule, Condition, ConditionType, Operator};
+
+/// Default sandbox directory for file operations
+pub const DEFAULT_FILE_BASE_DIR: &str = "/tmp/aapi";
+
+/// Default number of submissions executing at once
+pub const DEFAULT_MAX_CONCURRENT_EXECUTIONS: usize = 256;
+
+/// Default wait for an execution slot before refusing a submission
+pub const DEFAULT_EXECUTION_QUEUE_WAIT_MS: u64 = 100;
+
+/// Default number of deferred VĀKYAs waiting for their `not_before` time
+pub const DEFAULT_MAX_DEFERRED_EXECUTIONS: usize = 1024;
+
+/// `Retry-After` sent when a submission is refused for lack of a slot
+pub const OVERLOAD_RETRY_AFTER_SECS: u64 = 1;
+
+/// Requests each caller may make per fixed window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitConfig {
+    pub max_requests: u32,
+    pub window_secs: u64,
+}
 
 /// Gateway configuration
 #[derive(Debug, Clone)]
@@ -32,6 +66,80 @@ pub struct GatewayConfig {
     pub max_body_size: usize,
     /// Request timeout in seconds
     pub request_timeout_secs: u64,
+    /// Directory of JSON policy files; replaces the built-in policies and is
+    /// watched for changes when set
+    pub policy_dir: Option<std::path::PathBuf>,
+    /// API key -> namespaces the caller may read; when non-empty, read
+    /// endpoints require `Authorization: Bearer <api key>`. A key bound to
+    /// `"*"` may read everything and reach the operator endpoints
+    pub api_key_namespaces: HashMap<String, Vec<String>>,
+    /// Principals allowed to inspect and revoke capability tokens; nobody
+    /// may when empty
+    pub capability_operators: Vec<String>,
+    /// Principals allowed to reload policies through the API; nobody may
+    /// when empty
+    pub policy_operators: Vec<String>,
+    /// API key -> principal a caller presenting it as
+    /// `Authorization: Bearer <api key>` acts as, e.g. when voting on approvals
+    pub api_key_principals: HashMap<String, String>,
+    /// Recompute stored VĀKYA hashes on read to detect tampering
+    /// (enforced in production mode)
+    pub verify_record_hashes: bool,
+    /// Terminate TLS (and optionally verify client certificates) in the
+    /// gateway itself
+    pub tls: Option<TlsConfig>,
+    /// Sandbox directory for the file adapter and HTTP downloads
+    pub file_base_dir: PathBuf,
+    /// Unix permission bits applied to `file_base_dir` at startup; `None`
+    /// leaves them to the process umask
+    pub file_base_dir_mode: Option<u32>,
+    /// Hosts the HTTP adapter may reach; empty allows any host outside
+    /// production mode and none in it
+    pub http_allowed_hosts: Vec<String>,
+    /// Hosts the HTTP adapter must never reach; checked before the allow-list
+    pub http_denied_hosts: Vec<String>,
+    /// Refuse HTTP requests to loopback, private and link-local addresses
+    /// (enforced in production mode)
+    pub http_block_private: bool,
+    /// Fail executions whose effects diverge from the VĀKYA's declared
+    /// `expected_effect`; otherwise the divergence is only noted in the receipt
+    pub strict_effect_matching: bool,
+    /// Cache VĀKYA, receipt and effect reads in memory
+    pub record_cache: Option<CacheConfig>,
+    /// Submissions allowed to execute at once; beyond this a submission
+    /// waits up to `execution_queue_wait_ms` for a slot and is then refused
+    pub max_concurrent_executions: usize,
+    /// How long a submission waits for an execution slot, in milliseconds
+    pub execution_queue_wait_ms: u64,
+    /// Deferred VĀKYAs allowed to wait at once; further deferred
+    /// submissions are refused as overloaded
+    pub max_deferred_executions: usize,
+    /// Envelope-encrypt stored effect state under this master key; reads
+    /// decrypt transparently while the key is configured
+    pub encrypt_state: Option<MasterKey>,
+    /// Limit requests per caller, keyed by configured API key, then client
+    /// certificate subject, then peer IP address; unlimited when unset
+    pub rate_limit: Option<RateLimitConfig>,
+    /// Reject JSON submissions whose VĀKYA has top-level or slot fields the
+    /// gateway does not recognise, instead of ignoring them
+    pub reject_unknown_fields: bool,
+    /// Refuse capability tokens delegated more times than this, whatever
+    /// limit their issuer set
+    pub max_delegation_depth: Option<u32>,
+    /// Answer a VĀKYA whose content and actor match one accepted within
+    /// this many seconds with the earlier receipt, even under a new
+    /// `vakya_id`; off when `None`
+    pub dedup_window_secs: Option<u64>,
+    /// Refuse submissions that only draw validation warnings, such as a
+    /// nearly expired TTL; otherwise warnings are returned with the response
+    pub strict_validation: bool,
+    /// Actions each adapter domain is limited to, or barred from, whatever
+    /// the adapter supports; disabled actions are refused as unsupported
+    pub action_filters: HashMap<String, ActionFilter>,
+    /// Values masked or hashed in log output by the layer from
+    /// [`GatewayServer::tracing_layer`](crate::GatewayServer::tracing_layer);
+    /// nothing is redacted by default
+    pub log_redaction: LogRedaction,
 }
 
 impl Default for GatewayConfig {
@@ -47,6 +155,31 @@ impl Default for GatewayConfig {
             default_deny: false,
             max_body_size: 10 * 1024 * 1024, // 10MB
             request_timeout_secs: 30,
+            policy_dir: None,
+            api_key_namespaces: HashMap::new(),
+            capability_operators: vec![],
+            policy_operators: vec![],
+            api_key_principals: HashMap::new(),
+            verify_record_hashes: false,
+            tls: None,
+            file_base_dir: PathBuf::from(DEFAULT_FILE_BASE_DIR),
+            file_base_dir_mode: None,
+            http_allowed_hosts: vec![],
+            http_denied_hosts: vec![],
+            http_block_private: false,
+            strict_effect_matching: false,
+            record_cache: None,
+            max_concurrent_executions: DEFAULT_MAX_CONCURRENT_EXECUTIONS,
+            execution_queue_wait_ms: DEFAULT_EXECUTION_QUEUE_WAIT_MS,
+            max_deferred_executions: DEFAULT_MAX_DEFERRED_EXECUTIONS,
+            encrypt_state: None,
+            rate_limit: None,
+            reject_unknown_fields: false,
+            max_delegation_depth: None,
+            dedup_window_secs: None,
+            strict_validation: false,
+            action_filters: HashMap::new(),
+            log_redaction: LogRedaction::default(),
         }
     }
 }
@@ -65,6 +198,31 @@ impl GatewayConfig {
             default_deny: true,
             max_body_size: 10 * 1024 * 1024,
             request_timeout_secs: 30,
+            policy_dir: None,
+            api_key_namespaces: HashMap::new(),
+            capability_operators: vec![],
+            policy_operators: vec![],
+            api_key_principals: HashMap::new(),
+            verify_record_hashes: true,
+            tls: None,
+            file_base_dir: PathBuf::from(DEFAULT_FILE_BASE_DIR),
+            file_base_dir_mode: Some(0o700),
+            http_allowed_hosts: vec![],
+            http_denied_hosts: vec![],
+            http_block_private: true,
+            strict_effect_matching: false,
+            record_cache: None,
+            max_concurrent_executions: DEFAULT_MAX_CONCURRENT_EXECUTIONS,
+            execution_queue_wait_ms: DEFAULT_EXECUTION_QUEUE_WAIT_MS,
+            max_deferred_executions: DEFAULT_MAX_DEFERRED_EXECUTIONS,
+            encrypt_state: None,
+            rate_limit: None,
+            reject_unknown_fields: false,
+            max_delegation_depth: None,
+            dedup_window_secs: None,
+            strict_validation: false,
+            action_filters: HashMap::new(),
+            log_redaction: LogRedaction::default(),
         }
     }
 
@@ -78,6 +236,41 @@ impl GatewayConfig {
         self.require_capabilities || self.production_mode
     }
 
+    /// IndexDB settings derived from this configuration
+    pub fn db_config(&self) -> DbConfig {
+        let mut config = DbConfig::default();
+        if self.verify_record_hashes || self.production_mode {
+            config = config.with_hash_verification();
+        }
+        if let Some(key) = &self.encrypt_state {
+            config = config.with_state_encryption(key.clone());
+        }
+        config
+    }
+
+    /// Validator every submitted VĀKYA is checked with
+    ///
+    /// Inline capabilities are left to `cap_verifier`, which refuses them
+    /// as an authorization failure.
+    pub fn validator(&self) -> VakyaValidator {
+        let validator = VakyaValidator::new().defer_capability_checks();
+        if self.strict_validation {
+            validator.strict()
+        } else {
+            validator
+        }
+    }
+
+    /// Check if HTTP to private networks is blocked (explicit or via production mode)
+    pub fn blocks_private_networks(&self) -> bool {
+        self.http_block_private || self.production_mode
+    }
+
+    /// Check if HTTP hosts must be explicitly allow-listed (production mode)
+    pub fn http_allow_list_required(&self) -> bool {
+        self.production_mode
+    }
+
     /// Check if default-deny is enabled (explicit or via production mode)
     pub fn is_default_deny(&self) -> bool {
         self.default_deny || self.production_mode
@@ -98,8 +291,12 @@ pub struct AppState {
     pub config: GatewayConfig,
     /// Key store for signing/verification
     pub key_store: KeyStore,
+    /// Key used to sign PRAMĀṆA receipts
+    pub receipt_key_id: KeyId,
     /// IndexDB store
     pub index_db: Arc<dyn IndexDbStore>,
+    /// Cache in front of `index_db`, when `config.record_cache` is set
+    pub record_cache: Option<Arc<CachingStore>>,
     /// VĀKYA signer
     pub signer: VakyaSigner,
     /// VĀKYA verifier
@@ -112,100 +309,277 @@ pub struct AppState {
     pub dispatcher: Dispatcher,
     /// Policy engine for MetaRules enforcement
     pub policy_engine: PolicyEngine,
+    /// Watcher reloading `policy_engine` from `config.policy_dir`
+    pub policy_watcher: Option<PolicyWatcher>,
     /// Metrics collector
     pub metrics: Arc<RwLock<GatewayMetrics>>,
+    /// Admission control for executions, sized by
+    /// `config.max_concurrent_executions`
+    pub execution_slots: Arc<Semaphore>,
+    /// Places for deferred VĀKYAs waiting to run, sized by
+    /// `config.max_deferred_executions`
+    pub deferred_slots: Arc<Semaphore>,
+    /// Per-caller request limiter, when `config.rate_limit` is set
+    pub rate_limiter: Option<RateLimiter>,
+    /// Prices each execution in the resources VĀKYA budgets are declared in
+    pub cost_estimator: Arc<dyn CostEstimator>,
+    /// Usage of declared budgets, charged before each execution
+    pub budget_tracker: BudgetTracker,
+    /// Recently accepted submissions by content, when
+    /// `config.dedup_window_secs` is set
+    pub dedup: Option<DedupIndex>,
 }
 
 impl AppState {
+    /// Replace the default effect-based cost estimator
+    pub fn with_cost_estimator(mut self, estimator: Arc<dyn CostEstimator>) -> Self {
+        self.cost_estimator = estimator;
+        self
+    }
+
     /// Create new application state with SQLite backend
     pub async fn new(config: GatewayConfig) -> Result<Self, Box<dyn std::error::Error>> {
         let key_store = KeyStore::new();
         
         // Generate gateway signing key
-        let _gateway_key = key_store.generate_key(aapi_crypto::KeyPurpose::ReceiptSigning)?;
+        let receipt_key_id = key_store.generate_key(aapi_crypto::KeyPurpose::ReceiptSigning)?;
         
-        let index_db: Arc<dyn IndexDbStore> = Arc::new(
-            SqliteIndexDb::new(&config.database_url).await?
-        );
+        let (index_db, record_cache) = with_record_cache(&config, Arc::new(
+            SqliteIndexDb::with_config(&config.database_url, config.db_config()).await?
+        ));
         
         let signer = VakyaSigner::new(key_store.clone());
         let verifier = VakyaVerifier::new(key_store.clone());
-        let cap_verifier = CapabilityVerifier::new(key_store.clone());
-
-        // Default adapter registry
-        let file_base_dir = std::path::PathBuf::from("/tmp/aapi");
-        let _ = tokio::fs::create_dir_all(&file_base_dir).await;
-        info!(base_dir = %file_base_dir.display(), "Initializing adapter registry with file sandbox");
-
-        let exec_registry = RegistryBuilder::new()
-            .with_file_adapter_config(aapi_adapters::FileAdapter::new().with_base_dir(&file_base_dir))
-            .with_http_adapter()
-            .build();
-        let adapters = Arc::new(RwLock::new(exec_registry));
-        let dispatcher = Dispatcher::from_arc(Arc::clone(&adapters));
-
-        // Initialize policy engine with default policies
-        let policy_engine = create_default_policy_engine(config.is_default_deny()).await;
-        
+        let cap_verifier = capability_verifier(&config, &key_store, index_db.as_ref()).await?;
+
+        let (adapters, dispatcher) = init_adapters(&config).await?;
+
+        let (policy_engine, policy_watcher) = init_policy_engine(&config).await?;
+        let execution_slots = Arc::new(Semaphore::new(config.max_concurrent_executions.max(1)));
+        let deferred_slots = Arc::new(Semaphore::new(config.max_deferred_executions));
+        let rate_limiter = config.rate_limit.map(|limit| RateLimiter::new(limit.max_requests, limit.window_secs));
+        let dedup = config.dedup_window_secs.map(DedupIndex::new);
+
         Ok(Self {
             config,
             key_store,
+            receipt_key_id,
             index_db,
+            record_cache,
             signer,
             verifier,
             cap_verifier,
             adapters,
             dispatcher,
             policy_engine,
+            policy_watcher,
             metrics: Arc::new(RwLock::new(GatewayMetrics::new())),
+            execution_slots,
+            deferred_slots,
+            rate_limiter,
+            cost_estimator: Arc::new(EffectCostEstimator::default()),
+            budget_tracker: BudgetTracker::new(),
+            dedup,
         })
     }
 
     /// Create state with in-memory database (for testing)
     pub async fn in_memory(config: GatewayConfig) -> Result<Self, Box<dyn std::error::Error>> {
         let key_store = KeyStore::new();
-        let _gateway_key = key_store.generate_key(aapi_crypto::KeyPurpose::ReceiptSigning)?;
+        let receipt_key_id = key_store.generate_key(aapi_crypto::KeyPurpose::ReceiptSigning)?;
         
-        let index_db: Arc<dyn IndexDbStore> = Arc::new(
-            SqliteIndexDb::in_memory().await?
-        );
+        let (index_db, record_cache) = with_record_cache(&config, Arc::new(
+            SqliteIndexDb::with_config("sqlite::memory:", config.db_config()).await?
+        ));
         
         let signer = VakyaSigner::new(key_store.clone());
         let verifier = VakyaVerifier::new(key_store.clone());
-        let cap_verifier = CapabilityVerifier::new(key_store.clone());
-
-        // Default adapter registry
-        let file_base_dir = std::path::PathBuf::from("/tmp/aapi");
-        let _ = tokio::fs::create_dir_all(&file_base_dir).await;
-        info!(base_dir = %file_base_dir.display(), "Initializing adapter registry with file sandbox");
-
-        let exec_registry = RegistryBuilder::new()
-            .with_file_adapter_config(aapi_adapters::FileAdapter::new().with_base_dir(&file_base_dir))
-            .with_http_adapter()
-            .build();
-        let adapters = Arc::new(RwLock::new(exec_registry));
-        let dispatcher = Dispatcher::from_arc(Arc::clone(&adapters));
-
-        // Initialize policy engine with default policies
-        let policy_engine = create_default_policy_engine(config.is_default_deny()).await;
-        
+        let cap_verifier = capability_verifier(&config, &key_store, index_db.as_ref()).await?;
+
+        let (adapters, dispatcher) = init_adapters(&config).await?;
+
+        let (policy_engine, policy_watcher) = init_policy_engine(&config).await?;
+        let execution_slots = Arc::new(Semaphore::new(config.max_concurrent_executions.max(1)));
+        let deferred_slots = Arc::new(Semaphore::new(config.max_deferred_executions));
+        let rate_limiter = config.rate_limit.map(|limit| RateLimiter::new(limit.max_requests, limit.window_secs));
+        let dedup = config.dedup_window_secs.map(DedupIndex::new);
+
         Ok(Self {
             config,
             key_store,
+            receipt_key_id,
             index_db,
+            record_cache,
             signer,
             verifier,
             cap_verifier,
             adapters,
             dispatcher,
             policy_engine,
+            policy_watcher,
             metrics: Arc::new(RwLock::new(GatewayMetrics::new())),
+            execution_slots,
+            deferred_slots,
+            rate_limiter,
+            cost_estimator: Arc::new(EffectCostEstimator::default()),
+            budget_tracker: BudgetTracker::new(),
+            dedup,
         })
     }
 }
 
+impl AppState {
+    /// Take an execution slot, waiting up to `execution_queue_wait_ms`
+    ///
+    /// Fails with `GatewayError::Overloaded` when every slot stays busy; the
+    /// rejection is counted in the metrics.
+    pub async fn acquire_execution_slot(&self) -> GatewayResult<OwnedSemaphorePermit> {
+        let wait = std::time::Duration::from_millis(self.config.execution_queue_wait_ms);
+        match tokio::time::timeout(wait, Arc::clone(&self.execution_slots).acquire_owned()).await {
+            Ok(Ok(permit)) => Ok(permit),
+            _ => {
+                self.metrics.write().await.record_backpressure_rejection();
+                Err(GatewayError::Overloaded { retry_after_secs: OVERLOAD_RETRY_AFTER_SECS })
+            }
+        }
+    }
+
+    /// Take a place in the deferred queue without waiting
+    ///
+    /// Fails with `GatewayError::Overloaded` when `max_deferred_executions`
+    /// VĀKYAs are already waiting; the rejection is counted in the metrics.
+    pub async fn reserve_deferred_slot(&self) -> GatewayResult<OwnedSemaphorePermit> {
+        match Arc::clone(&self.deferred_slots).try_acquire_owned() {
+            Ok(permit) => Ok(permit),
+            Err(_) => {
+                self.metrics.write().await.record_backpressure_rejection();
+                Err(GatewayError::Overloaded { retry_after_secs: OVERLOAD_RETRY_AFTER_SECS })
+            }
+        }
+    }
+
+    /// Sign a receipt with the gateway's receipt key before it is stored
+    pub fn sign_receipt(&self, mut receipt: ReceiptRecord) -> GatewayResult<ReceiptRecord> {
+        let key_pair = self.key_store.get_key(&self.receipt_key_id)
+            .map_err(|e| GatewayError::Internal(e.to_string()))?;
+
+        receipt.key_id = Some(self.receipt_key_id.0.clone());
+        receipt.signature = None;
+        let signature = aapi_crypto::sign_receipt(&key_pair, &serde_json::to_value(&receipt)?)
+            .map_err(|e| GatewayError::Internal(format!("Failed to sign receipt: {}", e)))?;
+        receipt.signature = Some(signature);
+        Ok(receipt)
+    }
+
+    /// Sign a tree head with the receipt signing key
+    pub fn sign_tree_head(&self, head: SignedTreeHead) -> GatewayResult<SignedTreeHead> {
+        let key_pair = self.key_store.get_key(&self.receipt_key_id)
+            .map_err(|e| GatewayError::Internal(e.to_string()))?;
+        head.sign(&key_pair)
+            .map_err(|e| GatewayError::Internal(format!("Failed to sign tree head: {}", e)))
+    }
+}
+
+/// Capability verifier applying the configured delegation limit and the
+/// revocations persisted in the IndexDB
+async fn capability_verifier(
+    config: &GatewayConfig,
+    key_store: &KeyStore,
+    index_db: &dyn IndexDbStore,
+) -> Result<CapabilityVerifier, Box<dyn std::error::Error>> {
+    let revocations = RevocationList::new();
+    for revocation in index_db.list_capability_revocations().await? {
+        revocations.revoke(revocation.token_id, revocation.include_delegations);
+    }
+    for (token_id, parent_token_id) in index_db.list_capability_delegations().await? {
+        revocations.record_delegation(token_id, parent_token_id);
+    }
+
+    let mut verifier = CapabilityVerifier::new(key_store.clone()).with_revocations(revocations);
+    if let Some(depth) = config.max_delegation_depth {
+        verifier = verifier.with_max_delegation_depth(depth);
+    }
+    Ok(verifier)
+}
+
+/// Wrap `store` in a `CachingStore` when `record_cache` is configured
+fn with_record_cache(
+    config: &GatewayConfig,
+    store: Arc<dyn IndexDbStore>,
+) -> (Arc<dyn IndexDbStore>, Option<Arc<CachingStore>>) {
+    match &config.record_cache {
+        Some(cache_config) => {
+            info!(capacity = cache_config.capacity, ttl_secs = cache_config.ttl.as_secs(), "Caching IndexDB record reads");
+            let cache = Arc::new(CachingStore::new(store, cache_config.clone()));
+            (Arc::clone(&cache) as Arc<dyn IndexDbStore>, Some(cache))
+        }
+        None => (store, None),
+    }
+}
+
+/// Create the adapter registry sandboxed to `file_base_dir`
+async fn init_adapters(
+    config: &GatewayConfig,
+) -> Result<(Arc<RwLock<AdapterRegistry>>, Dispatcher), Box<dyn std::error::Error>> {
+    let base_dir = &config.file_base_dir;
+    create_sandbox_dir(base_dir, config.file_base_dir_mode).await
+        .map_err(|e| format!("Failed to prepare file sandbox {}: {}", base_dir.display(), e))?;
+    info!(base_dir = %base_dir.display(), "Initializing adapter registry with file sandbox");
+
+    let mut registry = RegistryBuilder::new()
+        .with_file_adapter_config(FileAdapter::new().with_base_dir(base_dir))
+        .with_http_adapter_config(
+            HttpAdapter::new()
+                .with_download_dir(base_dir)
+                .with_upload_dir(base_dir)
+                .with_allowed_hosts(config.http_allowed_hosts.clone())
+                .with_denied_hosts(config.http_denied_hosts.clone())
+                .with_allow_list_required(config.http_allow_list_required())
+                .with_private_networks_blocked(config.blocks_private_networks()),
+        )
+        .build();
+    for (domain, filter) in &config.action_filters {
+        registry.set_action_filter(domain.clone(), filter.clone());
+    }
+    let adapters = Arc::new(RwLock::new(registry));
+    let dispatcher = Dispatcher::from_arc(Arc::clone(&adapters));
+
+    Ok((adapters, dispatcher))
+}
+
+async fn create_sandbox_dir(dir: &Path, mode: Option<u32>) -> std::io::Result<()> {
+    tokio::fs::create_dir_all(dir).await?;
+
+    #[cfg(unix)]
+    if let Some(mode) = mode {
+        use std::os::unix::fs::PermissionsExt;
+        tokio::fs::set_permissions(dir, std::fs::Permissions::from_mode(mode)).await?;
+    }
+    #[cfg(not(unix))]
+    let _ = mode;
+
+    Ok(())
+}
+
+/// Create the policy engine, from `policy_dir` when configured
+async fn init_policy_engine(config: &GatewayConfig) -> Result<(PolicyEngine, Option<PolicyWatcher>), Box<dyn std::error::Error>> {
+    let engine = create_default_policy_engine(config.is_default_deny(), &config.file_base_dir).await;
+
+    let Some(ref dir) = config.policy_dir else {
+        return Ok((engine, None));
+    };
+
+    let count = engine.reload_from_dir(dir).await?;
+    info!(dir = %dir.display(), policies = count, "Loaded policies from directory");
+    let watcher = PolicyWatcher::watch(engine.clone(), dir)?;
+
+    Ok((engine, Some(watcher)))
+}
+
 /// Create default policy engine with sample policies
-async fn create_default_policy_engine(default_deny: bool) -> PolicyEngine {
+async fn create_default_policy_engine(default_deny: bool, sandbox: &Path) -> PolicyEngine {
+    let sandbox = sandbox.to_string_lossy();
+    let sandbox = sandbox.trim_end_matches('/');
     let engine = if default_deny {
         PolicyEngine::new()
     } else {
@@ -217,7 +591,7 @@ async fn create_default_policy_engine(default_deny: bool) -> PolicyEngine {
         .with_description("Deny file.delete actions outside the sandbox")
         .with_priority(100)
         .with_rule(
-            Rule::deny("rule:deny-delete-outside-sandbox", "Deny delete outside /tmp/aapi")
+            Rule::deny("rule:deny-delete-outside-sandbox", format!("Deny delete outside {}", sandbox))
                 .with_condition(Condition {
                     condition_type: ConditionType::Action,
                     field: "action".to_string(),
@@ -227,10 +601,9 @@ async fn create_default_policy_engine(default_deny: bool) -> PolicyEngine {
                 .with_condition(Condition {
                     condition_type: ConditionType::Resource,
                     field: "rid".to_string(),
-                    operator: Operator::StartsWith,
-                    value: serde_json::json!("file:/tmp/aapi"),
-                    // This rule denies if NOT starting with sandbox - we need to negate
-                    // For now, we'll use a simpler approach: deny all deletes by default
+                    operator: Operator::NotStartsWith,
+                    // Trailing slash so siblings like /tmp/aapi-other stay outside
+                    value: serde_json::json!(format!("file:{}/", sandbox)),
                 })
                 .with_priority(100),
         )
@@ -257,20 +630,20 @@ async fn create_default_policy_engine(default_deny: bool) -> PolicyEngine {
         .with_description("Allow file operations within the sandbox")
         .with_priority(10)
         .with_rule(
-            Rule::allow("rule:allow-sandbox-files", "Allow files in /tmp/aapi")
+            Rule::allow("rule:allow-sandbox-files", format!("Allow files in {}", sandbox))
                 .with_condition(Condition {
                     condition_type: ConditionType::Resource,
                     field: "rid".to_string(),
                     operator: Operator::StartsWith,
-                    value: serde_json::json!("file:/tmp/aapi"),
+                    value: serde_json::json!(format!("file:{}", sandbox)),
                 })
                 .with_priority(10),
         )
         .with_default_allow();
 
-    engine.add_policy(deny_dangerous_delete).await;
-    engine.add_policy(require_approval_http).await;
-    engine.add_policy(allow_sandbox_files).await;
+    engine.add_policy(deny_dangerous_delete).await.expect("default policy is valid");
+    engine.add_policy(require_approval_http).await.expect("default policy is valid");
+    engine.add_policy(allow_sandbox_files).await.expect("default policy is valid");
 
     info!("Policy engine initialized with {} default policies", 3);
     engine
@@ -286,12 +659,22 @@ pub struct GatewayMetrics {
     pub requests_failed: u64,
     /// Authorization denials
     pub auth_denials: u64,
+    /// Submissions refused because no execution slot was free
+    pub backpressure_rejections: u64,
     /// Average latency in milliseconds
     pub avg_latency_ms: f64,
     /// Requests by action
     pub requests_by_action: std::collections::HashMap<String, u64>,
     /// Requests by actor
     pub requests_by_actor: std::collections::HashMap<String, u64>,
+    /// Latency distribution across all requests
+    pub latency: LatencyHistogram,
+    /// Latency distribution per action
+    pub latency_by_action: std::collections::HashMap<String, LatencyHistogram>,
+    /// Outcomes by reason code
+    pub requests_by_reason: std::collections::HashMap<ReasonCode, u64>,
+    /// Requests received within the recent rate window
+    pub recent_requests: RateWindow,
 }
 
 impl GatewayMetrics {
@@ -301,9 +684,14 @@ impl GatewayMetrics {
             requests_success: 0,
             requests_failed: 0,
             auth_denials: 0,
+            backpressure_rejections: 0,
             avg_latency_ms: 0.0,
             requests_by_action: std::collections::HashMap::new(),
             requests_by_actor: std::collections::HashMap::new(),
+            latency: LatencyHistogram::new(),
+            latency_by_action: std::collections::HashMap::new(),
+            requests_by_reason: std::collections::HashMap::new(),
+            recent_requests: RateWindow::new(RATE_WINDOW),
         }
     }
 
@@ -321,11 +709,33 @@ impl GatewayMetrics {
 
         *self.requests_by_action.entry(action.to_string()).or_insert(0) += 1;
         *self.requests_by_actor.entry(actor.to_string()).or_insert(0) += 1;
+
+        self.latency.record(latency_ms);
+        self.latency_by_action.entry(action.to_string()).or_default().record(latency_ms);
+        self.recent_requests.record();
+    }
+
+    /// Latency percentiles across all requests
+    pub fn latency_percentiles(&self) -> LatencyPercentiles {
+        self.latency.percentiles()
+    }
+
+    /// Requests per second over the recent rate window
+    pub fn request_rate(&self) -> f64 {
+        self.recent_requests.rate()
     }
 
     pub fn record_auth_denial(&mut self) {
         self.auth_denials += 1;
     }
+
+    pub fn record_backpressure_rejection(&mut self) {
+        self.backpressure_rejections += 1;
+    }
+
+    pub fn record_reason(&mut self, reason: ReasonCode) {
+        *self.requests_by_reason.entry(reason).or_insert(0) += 1;
+    }
 }
 
 impl Default for GatewayMetrics {
@@ -0,0 +1,293 @@
+//! TLS termination with optional client-certificate (mTLS) verification
+//!
+//! When `GatewayConfig::tls` is set the server terminates TLS itself. With a
+//! client CA bundle configured, client certificates are verified against it
+//! during the handshake; if `require_client_cert` is set, handshakes without
+//! a valid certificate are rejected. The verified certificate's identity is
+//! attached to every request on the connection as a [`PeerIdentity`].
+
+use std::convert::Infallible;
+use std::io::BufReader;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use axum::extract::{ConnectInfo, FromRequestParts};
+use axum::http::request::Parts;
+use axum::http::Request;
+use axum::Router;
+use hyper::body::Incoming;
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::conn::auto;
+use tokio::net::TcpListener;
+use tokio_rustls::rustls::crypto::ring::default_provider;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::server::WebPkiClientVerifier;
+use tokio_rustls::rustls::{RootCertStore, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+use tower::Service;
+use tracing::{debug, warn};
+use x509_parser::extensions::GeneralName;
+
+use crate::error::{GatewayError, GatewayResult};
+
+/// TLS settings for the gateway listener
+#[derive(Debug, Clone)]
+pub struct TlsConfig {
+    /// PEM certificate chain presented by the gateway
+    pub cert_path: PathBuf,
+    /// PEM private key for `cert_path`
+    pub key_path: PathBuf,
+    /// PEM bundle of CAs trusted to issue client certificates; enables mTLS
+    pub client_ca_path: Option<PathBuf>,
+    /// Reject handshakes without a valid client certificate
+    pub require_client_cert: bool,
+    /// Require `v1_karta.pid` of submitted VĀKYAs to match the client
+    /// certificate's common name or a subject alternative name
+    pub match_principal: bool,
+}
+
+impl TlsConfig {
+    pub fn new(cert_path: impl Into<PathBuf>, key_path: impl Into<PathBuf>) -> Self {
+        Self {
+            cert_path: cert_path.into(),
+            key_path: key_path.into(),
+            client_ca_path: None,
+            require_client_cert: false,
+            match_principal: false,
+        }
+    }
+
+    /// Verify client certificates against the given CA bundle
+    pub fn with_client_ca(mut self, ca_path: impl Into<PathBuf>) -> Self {
+        self.client_ca_path = Some(ca_path.into());
+        self
+    }
+
+    pub fn require_client_cert(mut self) -> Self {
+        self.require_client_cert = true;
+        self
+    }
+
+    pub fn match_principal(mut self) -> Self {
+        self.match_principal = true;
+        self
+    }
+
+    /// Build the rustls server configuration
+    pub fn server_config(&self) -> Result<Arc<ServerConfig>, Box<dyn std::error::Error>> {
+        let provider = Arc::new(default_provider());
+        let certs = load_certs(&self.cert_path)?;
+        let key = load_key(&self.key_path)?;
+
+        let builder = ServerConfig::builder_with_provider(Arc::clone(&provider))
+            .with_safe_default_protocol_versions()?;
+
+        let builder = match &self.client_ca_path {
+            Some(ca_path) => {
+                let mut roots = RootCertStore::empty();
+                for cert in load_certs(ca_path)? {
+                    roots.add(cert)?;
+                }
+                let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider);
+                let verifier = if self.require_client_cert {
+                    verifier.build()?
+                } else {
+                    verifier.allow_unauthenticated().build()?
+                };
+                builder.with_client_cert_verifier(verifier)
+            }
+            None if self.require_client_cert => {
+                return Err("require_client_cert needs a client CA bundle".into());
+            }
+            None => builder.with_no_client_auth(),
+        };
+
+        let mut config = builder.with_single_cert(certs, key)?;
+        config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+        Ok(Arc::new(config))
+    }
+}
+
+fn load_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>, Box<dyn std::error::Error>> {
+    let mut reader = BufReader::new(std::fs::File::open(path)?);
+    let certs = rustls_pemfile::certs(&mut reader).collect::<Result<Vec<_>, _>>()?;
+    if certs.is_empty() {
+        return Err(format!("No certificates found in {}", path.display()).into());
+    }
+    Ok(certs)
+}
+
+fn load_key(path: &Path) -> Result<PrivateKeyDer<'static>, Box<dyn std::error::Error>> {
+    let mut reader = BufReader::new(std::fs::File::open(path)?);
+    rustls_pemfile::private_key(&mut reader)?
+        .ok_or_else(|| format!("No private key found in {}", path.display()).into())
+}
+
+/// Identity taken from a verified client certificate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientCertificate {
+    /// Full subject distinguished name, e.g. `CN=agent:billing, O=Acme`
+    pub subject: String,
+    pub common_name: Option<String>,
+    /// DNS, URI and email subject alternative names
+    pub subject_alt_names: Vec<String>,
+}
+
+impl ClientCertificate {
+    /// Parse the identity out of a DER certificate
+    pub fn from_der(der: &[u8]) -> Result<Self, String> {
+        let (_, cert) = x509_parser::parse_x509_certificate(der).map_err(|e| e.to_string())?;
+
+        let common_name = cert.subject()
+            .iter_common_name()
+            .next()
+            .and_then(|cn| cn.as_str().ok())
+            .map(String::from);
+
+        let subject_alt_names = cert.subject_alternative_name()
+            .ok()
+            .flatten()
+            .map(|san| {
+                san.value.general_names.iter()
+                    .filter_map(|name| match name {
+                        GeneralName::DNSName(s) | GeneralName::URI(s) | GeneralName::RFC822Name(s) => {
+                            Some(s.to_string())
+                        }
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(Self {
+            subject: cert.subject().to_string(),
+            common_name,
+            subject_alt_names,
+        })
+    }
+
+    /// Whether the certificate names the given principal
+    pub fn matches_principal(&self, pid: &str) -> bool {
+        self.common_name.as_deref() == Some(pid) || self.subject_alt_names.iter().any(|san| san == pid)
+    }
+}
+
+/// Client certificate and remote address of the connection a request
+/// arrived on, where known
+#[derive(Debug, Clone, Default)]
+pub struct PeerIdentity {
+    pub certificate: Option<ClientCertificate>,
+    pub addr: Option<SocketAddr>,
+}
+
+impl PeerIdentity {
+    /// Check a submitted principal against the client certificate when
+    /// `match_principal` is enabled. Requests without a certificate are only
+    /// possible when client certificates are optional and pass unchecked.
+    pub fn check_principal(&self, tls: Option<&TlsConfig>, pid: &str) -> GatewayResult<()> {
+        let enforce = tls.is_some_and(|tls| tls.match_principal);
+        match &self.certificate {
+            Some(cert) if enforce && !cert.matches_principal(pid) => Err(GatewayError::AuthorizationDenied(format!(
+                "Principal {} does not match client certificate {}",
+                pid, cert.subject
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for PeerIdentity {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let mut peer = parts.extensions.get::<PeerIdentity>().cloned().unwrap_or_default();
+        if peer.addr.is_none() {
+            peer.addr = parts.extensions.get::<ConnectInfo<SocketAddr>>().map(|ConnectInfo(addr)| *addr);
+        }
+        Ok(peer)
+    }
+}
+
+/// Longest a client may take to complete the TLS handshake
+pub const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Pause after a failed `accept` before trying again
+const ACCEPT_ERROR_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Accept TLS connections and serve `router` on them until `shutdown`
+/// resolves. Connections already accepted run to completion.
+pub async fn serve_tls(
+    listener: TcpListener,
+    router: Router,
+    config: Arc<ServerConfig>,
+    shutdown: impl std::future::Future<Output = ()> + Send + 'static,
+) -> std::io::Result<()> {
+    let acceptor = TlsAcceptor::from(config);
+    tokio::pin!(shutdown);
+
+    loop {
+        let accepted = tokio::select! {
+            accepted = listener.accept() => accepted,
+            _ = &mut shutdown => return Ok(()),
+        };
+        // Accept errors (out of file descriptors, aborted connections) are
+        // transient; the listener keeps serving once they clear
+        let (stream, peer) = match accepted {
+            Ok(accepted) => accepted,
+            Err(e) => {
+                warn!(error = %e, "Failed to accept connection");
+                tokio::select! {
+                    _ = tokio::time::sleep(ACCEPT_ERROR_BACKOFF) => continue,
+                    _ = &mut shutdown => return Ok(()),
+                }
+            }
+        };
+
+        let acceptor = acceptor.clone();
+        let router = router.clone();
+        tokio::spawn(async move {
+            let stream = match tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                Ok(Ok(stream)) => stream,
+                Ok(Err(e)) => {
+                    debug!(peer = %peer, error = %e, "TLS handshake failed");
+                    return;
+                }
+                Err(_) => {
+                    debug!(peer = %peer, "TLS handshake timed out");
+                    return;
+                }
+            };
+
+            let identity = match stream.get_ref().1.peer_certificates().and_then(|certs| certs.first()) {
+                Some(cert) => match ClientCertificate::from_der(cert.as_ref()) {
+                    Ok(cert) => Some(cert),
+                    Err(e) => {
+                        warn!(peer = %peer, error = %e, "Unreadable client certificate");
+                        return;
+                    }
+                },
+                None => None,
+            };
+            debug!(peer = %peer, client = ?identity.as_ref().map(|c| &c.subject), "TLS connection established");
+
+            let identity = PeerIdentity { certificate: identity, addr: Some(peer) };
+            let service = hyper::service::service_fn(move |mut request: Request<Incoming>| {
+                request.extensions_mut().insert(identity.clone());
+                request.extensions_mut().insert(ConnectInfo(peer));
+                // Router is always ready, so it can be called without poll_ready
+                router.clone().call(request)
+            });
+
+            if let Err(e) = auto::Builder::new(TokioExecutor::new())
+                .serve_connection_with_upgrades(TokioIo::new(stream), service)
+                .await
+            {
+                debug!(peer = %peer, error = %e, "Connection closed with error");
+            }
+        });
+    }
+}
@@ -0,0 +1,58 @@
+//! Fixtures shared by the gateway integration tests
+
+use aapi_core::{
+    ActorType,
+    Adhikarana,
+    ApprovalLane,
+    CapabilityRef,
+    Karta,
+    Karma,
+    Kriya,
+    PrincipalId,
+    ResourceId,
+    Vakya,
+};
+
+/// A VĀKYA from `actor` performing `action` (`domain.verb`) on `rid`
+///
+/// It carries a reference capability and no TTL, budgets or approval lane;
+/// tests needing more set the fields on the result.
+pub fn build_vakya(actor: &str, action: &str, rid: &str) -> Vakya {
+    let (domain, verb) = action.split_once('.').expect("action must be domain.verb");
+
+    Vakya::builder()
+        .karta(Karta {
+            pid: PrincipalId::new(actor),
+            role: None,
+            realm: None,
+            key_id: None,
+            actor_type: ActorType::Agent,
+            delegation_chain: vec![],
+        })
+        .karma(Karma {
+            rid: ResourceId::new(rid),
+            kind: Some(domain.to_string()),
+            ns: None,
+            version: None,
+            labels: std::collections::HashMap::new(),
+        })
+        .kriya(Kriya::new(domain, verb))
+        .adhikarana(Adhikarana {
+            cap: CapabilityRef::Reference {
+                cap_ref: "cap:test:123".to_string(),
+            },
+            policy_ref: None,
+            ttl: None,
+            budgets: vec![],
+            approval_lane: ApprovalLane::None,
+            scopes: vec![],
+            context: None,
+            delegation_chain_cid: None,
+            execution_constraints: None,
+            port_id: None,
+            required_phase: None,
+            required_role: None,
+        })
+        .build()
+        .expect("vakya build")
+}
@@ -0,0 +1,145 @@
+use std::sync::Arc;
+
+use axum::extract::{Query, State};
+use axum::Json;
+
+use aapi_core::{
+    Namespace,
+    Vakya,
+};
+use aapi_indexdb::AuditEventType;
+
+use aapi_gateway::error::GatewayError;
+use aapi_gateway::handlers::{get_audit_log, submit_vakya, AuditQuery, SubmitVakyaRequest};
+use aapi_gateway::namespace::CallerScope;
+use aapi_gateway::state::{AppState, GatewayConfig};
+use aapi_gateway::tls::PeerIdentity;
+
+mod common;
+
+fn build_vakya(action: &str, rid: &str) -> Vakya {
+    common::build_vakya("agent:reviewed", action, rid)
+}
+
+fn audit_query(event_type: Option<AuditEventType>) -> AuditQuery {
+    AuditQuery {
+        event_type,
+        actor: Some("agent:reviewed".to_string()),
+        from: None,
+        to: None,
+        offset: None,
+        limit: None,
+    }
+}
+
+#[tokio::test]
+async fn policy_denials_and_executions_are_queryable() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+
+    let mut statuses = Vec::new();
+    for (action, rid) in [
+        ("file.exists", "file:/tmp/aapi/audit.txt"),
+        ("file.delete", "file:/etc/audit"),
+    ] {
+        let response = submit_vakya(
+            State(Arc::clone(&state)),
+            PeerIdentity::default(),
+            Json(SubmitVakyaRequest { vakya: build_vakya(action, rid), signature: None, key_id: None }),
+        )
+        .await
+        .expect("submit")
+        .0;
+        statuses.push(response.status);
+    }
+    assert_eq!(statuses, vec!["accepted", "denied"]);
+
+    let all = get_audit_log(State(Arc::clone(&state)), CallerScope::unrestricted(), Query(audit_query(None)))
+        .await
+        .expect("audit log")
+        .0;
+    assert_eq!(all.total, Some(2));
+    assert_eq!(all.items[0].event_type, AuditEventType::VakyaDenied);
+
+    let denied = get_audit_log(
+        State(Arc::clone(&state)),
+        CallerScope::unrestricted(),
+        Query(audit_query(Some(AuditEventType::VakyaDenied))),
+    )
+    .await
+    .expect("denials")
+    .0;
+    assert_eq!(denied.items.len(), 1);
+    assert_eq!(denied.items[0].target.as_deref(), Some("file:/etc/audit"));
+    assert!(denied.items[0].details["reason"].is_string());
+
+    let scoped = get_audit_log(
+        State(state),
+        CallerScope::restricted(vec![Namespace::new("team-a")]),
+        Query(audit_query(None)),
+    )
+    .await
+    .expect_err("scoped caller");
+    assert!(matches!(scoped, GatewayError::AuthorizationDenied(_)));
+}
+
+#[tokio::test]
+async fn approval_requests_are_audited() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+
+    let response = submit_vakya(
+        State(Arc::clone(&state)),
+        PeerIdentity::default(),
+        Json(SubmitVakyaRequest {
+            vakya: build_vakya("http.post", "http:https://example.com/api"),
+            signature: None,
+            key_id: None,
+        }),
+    )
+    .await
+    .expect("submit")
+    .0;
+    assert_eq!(response.status, "pending_approval");
+    let approval_id = response.policy_decision.and_then(|d| d.approval_id).expect("approval id");
+
+    let entries = get_audit_log(
+        State(state),
+        CallerScope::unrestricted(),
+        Query(audit_query(Some(AuditEventType::ApprovalRequested))),
+    )
+    .await
+    .expect("audit log")
+    .0;
+    assert_eq!(entries.items.len(), 1);
+    assert_eq!(entries.items[0].details["approval_id"], approval_id.as_str());
+    assert_eq!(entries.items[0].target.as_deref(), Some("http:https://example.com/api"));
+}
+
+#[tokio::test]
+async fn unsigned_submissions_are_audited_when_signatures_are_required() {
+    let config = GatewayConfig {
+        require_signatures: true,
+        ..GatewayConfig::default()
+    };
+    let state = Arc::new(AppState::in_memory(config).await.expect("state"));
+
+    let err = submit_vakya(
+        State(Arc::clone(&state)),
+        PeerIdentity::default(),
+        Json(SubmitVakyaRequest {
+            vakya: build_vakya("file.exists", "file:/tmp/aapi/unsigned.txt"),
+            signature: None,
+            key_id: None,
+        }),
+    )
+    .await
+    .expect_err("unsigned");
+    assert!(matches!(err, GatewayError::AuthorizationDenied(_)));
+
+    let entries = get_audit_log(State(state), CallerScope::unrestricted(), Query(audit_query(None)))
+        .await
+        .expect("audit log")
+        .0;
+    assert_eq!(entries.items.len(), 1);
+    assert_eq!(entries.items[0].event_type, AuditEventType::AuthorizationFailed);
+    assert_eq!(entries.items[0].details["reason"], "Signature required in production mode");
+}
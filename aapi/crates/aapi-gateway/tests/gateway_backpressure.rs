@@ -0,0 +1,150 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::http::{header, StatusCode};
+use axum::response::IntoResponse;
+use axum::Json;
+
+use aapi_core::Vakya;
+
+use aapi_gateway::error::GatewayError;
+use aapi_gateway::handlers::{plan_vakya, submit_vakya, vote_approval, ApprovalVoteRequest, SubmitVakyaRequest};
+use aapi_gateway::identity::Caller;
+use aapi_gateway::namespace::CallerScope;
+use aapi_gateway::state::{AppState, GatewayConfig, OVERLOAD_RETRY_AFTER_SECS};
+use aapi_gateway::tls::PeerIdentity;
+use aapi_indexdb::{ApprovalRecordStatus, VoteDecision};
+use aapi_metarules::{ApprovalConfig, ApprovalType, Condition, ConditionType, Operator, Policy, Rule};
+
+mod common;
+
+fn build_vakya(rid: &str) -> Vakya {
+    let mut vakya = common::build_vakya("agent:busy", "file.write", rid);
+    vakya.body = serde_json::json!({ "content": "busy" });
+    vakya
+}
+
+async fn submit(state: &Arc<AppState>, rid: &str) -> Result<String, GatewayError> {
+    submit_vakya(
+        State(Arc::clone(state)),
+        PeerIdentity::default(),
+        Json(SubmitVakyaRequest { vakya: build_vakya(rid), signature: None, key_id: None }),
+    )
+    .await
+    .map(|response| response.0.status)
+}
+
+#[tokio::test]
+async fn saturated_pool_refuses_with_retry_after() {
+    let config = GatewayConfig {
+        max_concurrent_executions: 1,
+        execution_queue_wait_ms: 20,
+        ..GatewayConfig::default()
+    };
+    let state = Arc::new(AppState::in_memory(config).await.expect("state"));
+    tokio::fs::create_dir_all("/tmp/aapi").await.expect("mkdir");
+    let rid = format!("file:/tmp/aapi/busy-{}.txt", uuid::Uuid::new_v4());
+
+    let held = Arc::clone(&state.execution_slots).acquire_owned().await.expect("slot");
+    let refused = submit(&state, &rid).await.unwrap_err();
+    assert!(matches!(refused, GatewayError::Overloaded { retry_after_secs: OVERLOAD_RETRY_AFTER_SECS }));
+    assert_eq!(state.metrics.read().await.backpressure_rejections, 1);
+
+    let response = refused.into_response();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(response.headers()[header::RETRY_AFTER], OVERLOAD_RETRY_AFTER_SECS.to_string().as_str());
+
+    drop(held);
+    assert_eq!(submit(&state, &rid).await.expect("submit"), "accepted");
+    assert_eq!(state.execution_slots.available_permits(), 1);
+    assert_eq!(state.metrics.read().await.backpressure_rejections, 1);
+}
+
+#[tokio::test]
+async fn approved_executions_take_an_execution_slot() {
+    let config = GatewayConfig {
+        max_concurrent_executions: 1,
+        execution_queue_wait_ms: 20,
+        ..GatewayConfig::default()
+    };
+    let state = Arc::new(AppState::in_memory(config).await.expect("state"));
+    state
+        .policy_engine
+        .add_policy(
+            Policy::new("policy:approve-writes", "Approve Writes")
+                .with_priority(200)
+                .with_rule(
+                    Rule::require_approval("rule:approve-writes", "Writes need a reviewer")
+                        .with_condition(Condition {
+                            condition_type: ConditionType::Action,
+                            field: "action".to_string(),
+                            operator: Operator::Eq,
+                            value: serde_json::json!("file.write"),
+                        })
+                        .with_approval_config(
+                            ApprovalConfig::new(ApprovalType::Human)
+                                .with_approvers(vec!["user:reviewer".to_string()])
+                                .with_timeout(3600),
+                        ),
+                ),
+        )
+        .await
+        .expect("policy");
+    tokio::fs::create_dir_all("/tmp/aapi").await.expect("mkdir");
+    let rid = format!("file:/tmp/aapi/busy-{}.txt", uuid::Uuid::new_v4());
+
+    let submitted = submit_vakya(
+        State(Arc::clone(&state)),
+        PeerIdentity::default(),
+        Json(SubmitVakyaRequest { vakya: build_vakya(&rid), signature: None, key_id: None }),
+    )
+    .await
+    .expect("submit")
+    .0;
+    assert_eq!(submitted.status, "pending_approval");
+    let approval_id = submitted.policy_decision.and_then(|d| d.approval_id).expect("approval id");
+
+    let approve = || vote_approval(
+        State(Arc::clone(&state)),
+        CallerScope::unrestricted(),
+        Path(approval_id.clone()),
+        Caller::authenticated("user:reviewer"),
+        Json(ApprovalVoteRequest { decision: VoteDecision::Approve, comment: None }),
+    );
+
+    // With every slot busy the vote is refused and stays open for a retry
+    let held = Arc::clone(&state.execution_slots).acquire_owned().await.expect("slot");
+    let refused = approve().await.unwrap_err();
+    assert!(matches!(refused, GatewayError::Overloaded { .. }));
+    let pending = state.index_db.get_approval(&approval_id).await.expect("get").expect("approval");
+    assert_eq!(pending.status, ApprovalRecordStatus::Pending);
+    assert!(pending.votes.is_empty());
+
+    drop(held);
+    let approved = approve().await.expect("vote").0;
+    assert_eq!(approved.status, ApprovalRecordStatus::Approved);
+    assert_eq!(state.execution_slots.available_permits(), 1);
+}
+
+#[tokio::test]
+async fn dry_run_plans_take_an_execution_slot() {
+    let config = GatewayConfig {
+        max_concurrent_executions: 1,
+        execution_queue_wait_ms: 20,
+        ..GatewayConfig::default()
+    };
+    let state = Arc::new(AppState::in_memory(config).await.expect("state"));
+    let rid = format!("file:/tmp/aapi/plan-{}.txt", uuid::Uuid::new_v4());
+    let plan = || plan_vakya(
+        State(Arc::clone(&state)),
+        PeerIdentity::default(),
+        Json(SubmitVakyaRequest { vakya: build_vakya(&rid), signature: None, key_id: None }),
+    );
+
+    let held = Arc::clone(&state.execution_slots).acquire_owned().await.expect("slot");
+    assert!(matches!(plan().await.unwrap_err(), GatewayError::Overloaded { .. }));
+
+    drop(held);
+    assert!(plan().await.expect("plan").0.dry_run.is_some());
+    assert_eq!(state.execution_slots.available_permits(), 1);
+}
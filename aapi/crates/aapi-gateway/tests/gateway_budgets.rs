@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::extract::{Request, State};
+use axum::Json;
+
+use aapi_core::error::ReasonCode;
+use aapi_core::{
+    Budget,
+    CostEstimator,
+    Vakya,
+};
+
+use aapi_gateway::handlers::{submit_vakya, submit_vakya_encoded, SubmitVakyaRequest, SubmitVakyaResponse};
+use aapi_gateway::state::{AppState, GatewayConfig};
+use aapi_gateway::tls::PeerIdentity;
+
+mod common;
+
+fn build_vakya(budgets: Vec<Budget>) -> Vakya {
+    let rid = format!("file:/tmp/aapi/budget-{}.txt", uuid::Uuid::new_v4());
+    let mut vakya = common::build_vakya("agent:metered", "file.write", &rid);
+    vakya.v7_adhikarana.budgets = budgets;
+    vakya.body = serde_json::json!({ "content": "metered" });
+    vakya
+}
+
+async fn submit(state: &Arc<AppState>, vakya: Vakya) -> SubmitVakyaResponse {
+    submit_vakya(
+        State(Arc::clone(state)),
+        PeerIdentity::default(),
+        Json(SubmitVakyaRequest { vakya, signature: None, key_id: None }),
+    )
+    .await
+    .expect("submit")
+    .0
+}
+
+#[tokio::test]
+async fn exhausted_budget_stops_execution() {
+    tokio::fs::create_dir_all("/tmp/aapi").await.expect("mkdir");
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+    let budgets = vec![Budget::new("daily-calls", "api_calls", 2)];
+
+    for _ in 0..2 {
+        let response = submit(&state, build_vakya(budgets.clone())).await;
+        assert_eq!(response.status, "accepted");
+    }
+
+    let response = submit(&state, build_vakya(budgets.clone())).await;
+    assert_eq!(response.status, "failed");
+    let receipt = response.receipt.expect("receipt");
+    assert_eq!(receipt.reason_code, ReasonCode::BudgetExceeded);
+    assert!(receipt.effect_ids.is_empty());
+
+    let stored = state.index_db.get_receipt(&response.vakya_id).await.unwrap().unwrap();
+    assert_eq!(stored.receipt_json["costs"]["api_calls"], 1);
+    assert_eq!(state.budget_tracker.remaining("agent:metered", &budgets[0]), 0);
+}
+
+#[tokio::test]
+async fn responses_report_remaining_budget() {
+    tokio::fs::create_dir_all("/tmp/aapi").await.expect("mkdir");
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+    let budgets = vec![
+        Budget::new("daily-calls", "api_calls", 1),
+        Budget::new("weekly-calls", "api_calls", 5),
+    ];
+
+    let remaining = |headers: &axum::http::HeaderMap| -> Vec<String> {
+        headers.get_all("x-budget-remaining").iter()
+            .map(|v| v.to_str().unwrap().to_string())
+            .collect()
+    };
+    let request = |vakya: Vakya| {
+        let body = serde_json::to_vec(&serde_json::json!({ "vakya": vakya })).unwrap();
+        Request::builder()
+            .method("POST")
+            .uri("/v1/vakya")
+            .header("content-type", "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    };
+
+    let headers = submit_vakya_encoded(State(Arc::clone(&state)), PeerIdentity::default(), request(build_vakya(budgets.clone())))
+        .await
+        .expect("submit")
+        .0;
+    assert_eq!(remaining(&headers), vec!["daily-calls=0", "weekly-calls=4"]);
+
+    // Still reported when the budget refuses the execution
+    let headers = submit_vakya_encoded(State(Arc::clone(&state)), PeerIdentity::default(), request(build_vakya(budgets.clone())))
+        .await
+        .expect("submit")
+        .0;
+    assert_eq!(remaining(&headers), vec!["daily-calls=0", "weekly-calls=4"]);
+}
+
+#[tokio::test]
+async fn unrecorded_executions_refund_their_budget() {
+    tokio::fs::create_dir_all("/tmp/aapi").await.expect("mkdir");
+    let dir = tempfile::tempdir().expect("tempdir");
+    let database_url = format!("sqlite:{}?mode=rwc", dir.path().join("gateway.db").display());
+    let state = Arc::new(
+        AppState::new(GatewayConfig { database_url: database_url.clone(), ..GatewayConfig::default() })
+            .await
+            .expect("state"),
+    );
+    let budgets = vec![Budget::new("daily-calls", "api_calls", 1)];
+
+    // Fail every effect insert, as a full disk would
+    let pool = sqlx::SqlitePool::connect(&database_url).await.expect("pool");
+    sqlx::query("CREATE TRIGGER fail_effects BEFORE INSERT ON effect_records BEGIN SELECT RAISE(ABORT, 'disk full'); END")
+        .execute(&pool)
+        .await
+        .expect("trigger");
+
+    let result = submit_vakya(
+        State(Arc::clone(&state)),
+        PeerIdentity::default(),
+        Json(SubmitVakyaRequest { vakya: build_vakya(budgets.clone()), signature: None, key_id: None }),
+    )
+    .await;
+    assert!(result.is_err());
+    assert_eq!(state.budget_tracker.remaining("agent:metered", &budgets[0]), 1);
+
+    sqlx::query("DROP TRIGGER fail_effects").execute(&pool).await.expect("drop trigger");
+    let response = submit(&state, build_vakya(budgets.clone())).await;
+    assert_eq!(response.status, "accepted");
+    assert_eq!(state.budget_tracker.remaining("agent:metered", &budgets[0]), 0);
+}
+
+/// Prices every action at a flat number of API dollars
+struct FlatPrice(u64);
+
+impl CostEstimator for FlatPrice {
+    fn estimate(&self, _vakya: &Vakya) -> HashMap<String, u64> {
+        HashMap::from([("api_dollars".to_string(), self.0)])
+    }
+}
+
+#[tokio::test]
+async fn registered_estimator_prices_executions() {
+    tokio::fs::create_dir_all("/tmp/aapi").await.expect("mkdir");
+    let state = Arc::new(
+        AppState::in_memory(GatewayConfig::default()).await.expect("state")
+            .with_cost_estimator(Arc::new(FlatPrice(40))),
+    );
+    let budgets = vec![Budget::new("spend", "api_dollars", 100)];
+
+    let response = submit(&state, build_vakya(budgets.clone())).await;
+    let stored = state.index_db.get_receipt(&response.vakya_id).await.unwrap().unwrap();
+    assert_eq!(stored.receipt_json["budgets"][0]["used"], 40);
+    assert_eq!(stored.receipt_json["budgets"][0]["remaining"], 60);
+
+    submit(&state, build_vakya(budgets.clone())).await;
+    let response = submit(&state, build_vakya(budgets)).await;
+    assert_eq!(response.receipt.expect("receipt").reason_code, ReasonCode::BudgetExceeded);
+}
@@ -0,0 +1,157 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::Json;
+
+use aapi_core::{Namespace, PrincipalId};
+use aapi_crypto::{CapabilityIssuer, CapabilityToken, CapabilityTokenBuilder, KeyPurpose, TokenAttenuation};
+use aapi_indexdb::AuditEventType;
+
+use aapi_gateway::error::GatewayError;
+use aapi_gateway::handlers::{get_audit_log, get_capability, revoke_capability, AuditQuery, RevokeCapabilityRequest};
+use aapi_gateway::identity::Caller;
+use aapi_gateway::namespace::CallerScope;
+use aapi_gateway::state::{AppState, GatewayConfig};
+
+const OPERATOR: &str = "ops:alice";
+
+fn operator_config() -> GatewayConfig {
+    GatewayConfig { capability_operators: vec![OPERATOR.to_string()], ..GatewayConfig::default() }
+}
+
+fn issue_tokens(state: &AppState) -> (CapabilityToken, CapabilityToken) {
+    let key_id = state.key_store.generate_key(KeyPurpose::CapabilitySigning).expect("key");
+    let key_pair = state.key_store.get_key(&key_id).expect("key pair");
+
+    let parent = CapabilityTokenBuilder::new()
+        .issuer(PrincipalId::new("issuer:gateway"))
+        .subject(PrincipalId::new("agent:parent"))
+        .action("file.read")
+        .resource("**")
+        .ttl_seconds(3600)
+        .max_delegation_depth(2)
+        .build_and_sign(&key_pair)
+        .expect("sign token");
+    let child = CapabilityIssuer::new(state.key_store.clone(), key_id, PrincipalId::new("issuer:gateway"))
+        .attenuate(&parent, PrincipalId::new("agent:child"), TokenAttenuation::default())
+        .expect("delegate token");
+    (parent, child)
+}
+
+async fn revoke(state: &Arc<AppState>, token_id: &str, include_delegations: bool) {
+    let response = revoke_capability(
+        State(Arc::clone(state)),
+        CallerScope::unrestricted(),
+        Caller::authenticated(OPERATOR),
+        Path(token_id.to_string()),
+        Some(Json(RevokeCapabilityRequest {
+            include_delegations,
+            reason: Some("key leaked".to_string()),
+        })),
+    )
+    .await
+    .expect("revoke");
+    assert!(response.0.revoked);
+}
+
+#[tokio::test]
+async fn revoked_capabilities_fail_verification() {
+    let state = Arc::new(AppState::in_memory(operator_config()).await.expect("state"));
+    let (parent, child) = issue_tokens(&state);
+
+    let status = get_capability(
+        State(Arc::clone(&state)),
+        CallerScope::unrestricted(),
+        Caller::authenticated(OPERATOR),
+        Path(parent.token_id.clone()),
+    )
+        .await
+        .expect("status");
+    assert!(!status.0.revoked);
+    assert!(state.cap_verifier.verify(&parent).unwrap().valid);
+
+    revoke(&state, &parent.token_id, false).await;
+    assert!(!state.cap_verifier.verify(&parent).unwrap().valid);
+    assert!(state.cap_verifier.verify(&child).unwrap().valid);
+
+    revoke(&state, &parent.token_id, true).await;
+    assert!(!state.cap_verifier.verify(&child).unwrap().valid);
+
+    let status = get_capability(
+        State(Arc::clone(&state)),
+        CallerScope::unrestricted(),
+        Caller::authenticated(OPERATOR),
+        Path(parent.token_id.clone()),
+    )
+        .await
+        .expect("status");
+    let revocation = status.0.revocation.expect("revocation record");
+    assert!(revocation.include_delegations);
+    assert_eq!(revocation.reason.as_deref(), Some("key leaked"));
+    assert_eq!(revocation.revoked_by.as_deref(), Some(OPERATOR));
+
+    let audit = get_audit_log(
+        State(Arc::clone(&state)),
+        CallerScope::unrestricted(),
+        Query(AuditQuery {
+            event_type: Some(AuditEventType::CapabilityRevoked),
+            actor: None,
+            from: None,
+            to: None,
+            offset: None,
+            limit: None,
+        }),
+    )
+    .await
+    .expect("audit log");
+    assert_eq!(audit.0.items.len(), 2);
+    assert_eq!(audit.0.items[0].target.as_deref(), Some(parent.token_id.as_str()));
+    assert_eq!(audit.0.items[0].actor.as_deref(), Some(OPERATOR));
+}
+
+#[tokio::test]
+async fn revocations_survive_a_restart() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let config = GatewayConfig {
+        database_url: format!("sqlite:{}?mode=rwc", dir.path().join("gateway.db").display()),
+        ..operator_config()
+    };
+
+    let state = Arc::new(AppState::new(config.clone()).await.expect("state"));
+    let (parent, _) = issue_tokens(&state);
+    revoke(&state, &parent.token_id, true).await;
+    drop(state);
+
+    let restarted = AppState::new(config).await.expect("restarted state");
+    assert!(restarted.cap_verifier.revocations().is_revoked(&parent.token_id));
+}
+
+#[tokio::test]
+async fn only_operators_can_revoke() {
+    let state = Arc::new(AppState::in_memory(operator_config()).await.expect("state"));
+
+    let callers = [
+        (CallerScope::restricted(vec![Namespace::new("team-a")]), Caller::authenticated(OPERATOR)),
+        (CallerScope::unrestricted(), Caller::anonymous()),
+        (CallerScope::unrestricted(), Caller::authenticated("agent:mallory")),
+    ];
+    for (scope, caller) in callers {
+        let err = revoke_capability(State(Arc::clone(&state)), scope, caller, Path("cap-1".to_string()), None)
+            .await
+            .expect_err("not an operator");
+        assert!(matches!(err, GatewayError::AuthorizationDenied(_)), "{:?}", err);
+    }
+    assert!(!state.cap_verifier.revocations().is_revoked("cap-1"));
+
+    // Nobody administers capabilities unless operators are configured
+    let unconfigured = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+    let err = get_capability(
+        State(unconfigured),
+        CallerScope::unrestricted(),
+        Caller::authenticated(OPERATOR),
+        Path("cap-1".to_string()),
+    )
+    .await
+    .expect_err("no operators configured");
+    assert!(matches!(err, GatewayError::AuthorizationDenied(_)), "{:?}", err);
+}
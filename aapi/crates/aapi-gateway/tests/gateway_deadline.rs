@@ -0,0 +1,120 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use axum::extract::State;
+use axum::Json;
+
+use aapi_adapters::{
+    Adapter, AdapterResult, CapturedEffect, ExecutionContext, ExecutionResult, HealthStatus,
+};
+use aapi_core::error::ReasonCode;
+use aapi_core::types::Timestamp;
+use aapi_core::{
+    TtlConstraint,
+    Vakya,
+};
+
+use aapi_gateway::handlers::{submit_vakya, SubmitVakyaRequest};
+use aapi_gateway::state::{AppState, GatewayConfig};
+use aapi_gateway::tls::PeerIdentity;
+
+mod common;
+
+/// Sleeps for a second, recording the budget it was given
+struct SlowAdapter {
+    seen_timeout_ms: Arc<Mutex<Option<u64>>>,
+}
+
+#[async_trait]
+impl Adapter for SlowAdapter {
+    fn domain(&self) -> &str {
+        "slow"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn supported_actions(&self) -> Vec<&str> {
+        vec!["slow.run"]
+    }
+
+    async fn execute(&self, _vakya: &Vakya, context: &ExecutionContext) -> AdapterResult<ExecutionResult> {
+        *self.seen_timeout_ms.lock().unwrap() = context.timeout_ms;
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        Ok(ExecutionResult::success(serde_json::json!({}), vec![], 1000))
+    }
+
+    fn can_rollback(&self, _action: &str) -> bool {
+        false
+    }
+
+    async fn rollback(&self, _effect: &CapturedEffect) -> AdapterResult<()> {
+        Ok(())
+    }
+
+    async fn health_check(&self) -> AdapterResult<HealthStatus> {
+        Ok(HealthStatus::healthy())
+    }
+}
+
+fn build_vakya(ttl: TtlConstraint) -> Vakya {
+    let mut vakya = common::build_vakya("agent:hurried", "slow.run", "slow:job");
+    vakya.v7_adhikarana.ttl = Some(ttl);
+    vakya
+}
+
+#[tokio::test]
+async fn ttl_max_duration_bounds_execution() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+    let seen_timeout_ms = Arc::new(Mutex::new(None));
+    state.adapters.write().await.register(SlowAdapter { seen_timeout_ms: Arc::clone(&seen_timeout_ms) });
+
+    let vakya = build_vakya(TtlConstraint {
+        expires_at: Timestamp(chrono::Utc::now() + chrono::Duration::minutes(5)),
+        max_duration_ms: Some(50),
+        not_before: None,
+    });
+
+    let started = std::time::Instant::now();
+    let response = submit_vakya(
+        State(Arc::clone(&state)),
+        PeerIdentity::default(),
+        Json(SubmitVakyaRequest { vakya, signature: None, key_id: None }),
+    )
+    .await
+    .expect("submit")
+    .0;
+
+    assert!(started.elapsed() < Duration::from_millis(900), "took {:?}", started.elapsed());
+    assert_eq!(*seen_timeout_ms.lock().unwrap(), Some(50));
+    assert_eq!(response.status, "failed");
+    assert_eq!(response.receipt.expect("receipt").reason_code, ReasonCode::Timeout);
+}
+
+#[tokio::test]
+async fn near_expiry_shrinks_execution_budget() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+    let seen_timeout_ms = Arc::new(Mutex::new(None));
+    state.adapters.write().await.register(SlowAdapter { seen_timeout_ms: Arc::clone(&seen_timeout_ms) });
+
+    let vakya = build_vakya(TtlConstraint {
+        expires_at: Timestamp(chrono::Utc::now() + chrono::Duration::milliseconds(400)),
+        max_duration_ms: None,
+        not_before: None,
+    });
+
+    let response = submit_vakya(
+        State(Arc::clone(&state)),
+        PeerIdentity::default(),
+        Json(SubmitVakyaRequest { vakya, signature: None, key_id: None }),
+    )
+    .await
+    .expect("submit")
+    .0;
+
+    let budget = seen_timeout_ms.lock().unwrap().expect("timeout set");
+    assert!(budget <= 400, "budget {}ms", budget);
+    assert_eq!(response.receipt.expect("receipt").reason_code, ReasonCode::Timeout);
+}
@@ -0,0 +1,125 @@
+use std::sync::Arc;
+
+use aapi_core::Vakya;
+
+use aapi_gateway::engine::{Engine, SubmissionContext};
+use aapi_gateway::state::{AppState, GatewayConfig};
+
+mod common;
+
+fn build_vakya(actor: &str, rid: &str) -> Vakya {
+    common::build_vakya(actor, "file.exists", rid)
+}
+
+async fn engine(dedup_window_secs: Option<u64>) -> Engine {
+    let config = GatewayConfig { dedup_window_secs, ..GatewayConfig::default() };
+    Engine::new(Arc::new(AppState::in_memory(config).await.expect("state")))
+}
+
+#[tokio::test]
+async fn retries_with_a_new_id_return_the_earlier_receipt() {
+    let engine = engine(Some(60)).await;
+    let first = build_vakya("agent:retry", "file:/tmp/aapi/dedup.txt");
+    let retry = build_vakya("agent:retry", "file:/tmp/aapi/dedup.txt");
+    assert_ne!(first.vakya_id, retry.vakya_id);
+
+    let original = engine.execute(first.clone(), SubmissionContext::default()).await.expect("execute");
+    assert!(!original.deduplicated);
+
+    let repeated = engine.execute(retry.clone(), SubmissionContext::default()).await.expect("retry");
+    assert!(repeated.deduplicated);
+    assert_eq!(repeated.vakya_id, first.vakya_id.0);
+    assert_eq!(repeated.status, original.status);
+    assert_eq!(repeated.leaf_index, original.leaf_index);
+    assert_eq!(
+        repeated.receipt.expect("receipt").created_at,
+        original.receipt.expect("receipt").created_at
+    );
+
+    // Nothing was recorded for the retry itself
+    let index_db = &engine.state().index_db;
+    assert!(index_db.get_vakya(&retry.vakya_id.0).await.expect("query").is_none());
+
+    // The same content from another actor is its own submission
+    let other = engine
+        .execute(build_vakya("agent:other", "file:/tmp/aapi/dedup.txt"), SubmissionContext::default())
+        .await
+        .expect("other actor");
+    assert!(!other.deduplicated);
+}
+
+#[tokio::test]
+async fn deduplication_is_off_by_default() {
+    let engine = engine(None).await;
+    assert!(engine.state().dedup.is_none());
+
+    for _ in 0..2 {
+        let response = engine
+            .execute(build_vakya("agent:retry", "file:/tmp/aapi/dedup-off.txt"), SubmissionContext::default())
+            .await
+            .expect("execute");
+        assert!(!response.deduplicated);
+    }
+}
+
+#[tokio::test]
+async fn entries_expire_with_the_window() {
+    let engine = engine(Some(0)).await;
+
+    engine
+        .execute(build_vakya("agent:retry", "file:/tmp/aapi/dedup-expired.txt"), SubmissionContext::default())
+        .await
+        .expect("execute");
+    let retry = engine
+        .execute(build_vakya("agent:retry", "file:/tmp/aapi/dedup-expired.txt"), SubmissionContext::default())
+        .await
+        .expect("retry");
+    assert!(!retry.deduplicated);
+}
+
+#[tokio::test]
+async fn concurrent_retries_execute_once() {
+    let engine = engine(Some(60)).await;
+    let first = build_vakya("agent:retry", "file:/tmp/aapi/dedup-concurrent.txt");
+    let retry = build_vakya("agent:retry", "file:/tmp/aapi/dedup-concurrent.txt");
+
+    let (a, b) = tokio::join!(
+        engine.execute(first.clone(), SubmissionContext::default()),
+        engine.execute(retry.clone(), SubmissionContext::default()),
+    );
+
+    // The loser either sees the winner in progress or gets its receipt
+    let executed = [&a, &b].iter().filter(|r| matches!(r, Ok(response) if !response.deduplicated)).count();
+    assert_eq!(executed, 1);
+
+    let index_db = &engine.state().index_db;
+    let recorded = [
+        index_db.get_vakya(&first.vakya_id.0).await.expect("query").is_some(),
+        index_db.get_vakya(&retry.vakya_id.0).await.expect("query").is_some(),
+    ];
+    assert_eq!(recorded.iter().filter(|r| **r).count(), 1);
+}
+
+#[tokio::test]
+async fn failed_submissions_release_their_claim() {
+    let config = GatewayConfig {
+        dedup_window_secs: Some(60),
+        max_concurrent_executions: 1,
+        ..GatewayConfig::default()
+    };
+    let engine = Engine::new(Arc::new(AppState::in_memory(config).await.expect("state")));
+
+    let slot = Arc::clone(&engine.state().execution_slots).acquire_owned().await.expect("slot");
+    let refused = engine
+        .execute(build_vakya("agent:retry", "file:/tmp/aapi/dedup-released.txt"), SubmissionContext::default())
+        .await;
+    assert!(refused.is_err());
+    assert!(engine.state().dedup.as_ref().expect("dedup").is_empty());
+    drop(slot);
+
+    let retry = engine
+        .execute(build_vakya("agent:retry", "file:/tmp/aapi/dedup-released.txt"), SubmissionContext::default())
+        .await
+        .expect("retry");
+    assert!(!retry.deduplicated);
+}
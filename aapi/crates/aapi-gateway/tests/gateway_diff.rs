@@ -0,0 +1,54 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::Json;
+
+use aapi_adapters::ChangeType;
+use aapi_gateway::error::GatewayError;
+use aapi_gateway::handlers::{get_vakya_diff, submit_vakya, SubmitVakyaRequest};
+use aapi_gateway::namespace::CallerScope;
+use aapi_gateway::state::{AppState, GatewayConfig};
+use aapi_gateway::tls::PeerIdentity;
+
+mod common;
+use common::build_vakya;
+
+async fn write_file(state: &Arc<AppState>, rid: &str, content: serde_json::Value) -> String {
+    let mut vakya = build_vakya("agent:diff", "file.write", rid);
+    vakya.body = serde_json::json!({ "content": content.to_string() });
+    let response = submit_vakya(
+        State(Arc::clone(state)),
+        PeerIdentity::default(),
+        Json(SubmitVakyaRequest { vakya, signature: None, key_id: None }),
+    )
+    .await
+    .expect("submit")
+    .0;
+    assert_eq!(response.status, "accepted");
+    response.vakya_id
+}
+
+#[tokio::test]
+async fn diff_reports_per_effect_changes() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+
+    tokio::fs::create_dir_all("/tmp/aapi").await.expect("mkdir");
+    let rid = format!("file:/tmp/aapi/diff-{}.json", uuid::Uuid::new_v4());
+
+    let created = write_file(&state, &rid, serde_json::json!({"a": 1, "b": 2})).await;
+    let diff = get_vakya_diff(State(Arc::clone(&state)), CallerScope::unrestricted(), Path(created)).await.expect("diff").0;
+    assert_eq!(diff.effects.len(), 1);
+    assert_eq!(diff.effects[0].change_type, Some(ChangeType::Created));
+    assert_eq!(diff.summary, "1 effect: 1 created");
+
+    let modified = write_file(&state, &rid, serde_json::json!({"a": 1, "b": 3, "c": 4})).await;
+    let diff = get_vakya_diff(State(Arc::clone(&state)), CallerScope::unrestricted(), Path(modified)).await.expect("diff").0;
+    let effect = &diff.effects[0];
+    assert_eq!(effect.change_type, Some(ChangeType::Modified));
+    assert_eq!(effect.json_patch.as_ref().map(Vec::len), Some(2));
+    assert!(effect.summary.as_deref().unwrap().starts_with("modified 2 keys, +"));
+    assert_eq!(diff.summary, "1 effect: 1 modified");
+
+    let missing = get_vakya_diff(State(state), CallerScope::unrestricted(), Path("missing".to_string())).await.unwrap_err();
+    assert!(matches!(missing, GatewayError::NotFound(_)));
+}
@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use axum::extract::{Query, State};
+use axum::Json;
+
+use aapi_core::Vakya;
+
+use aapi_gateway::error::GatewayError;
+use aapi_gateway::handlers::{get_merkle_root, submit_vakya, MerkleRootQuery, SubmitVakyaRequest};
+use aapi_gateway::state::{AppState, GatewayConfig};
+use aapi_gateway::tls::PeerIdentity;
+
+mod common;
+
+fn build_vakya(rid: &str) -> Vakya {
+    common::build_vakya("agent:retry", "file.exists", rid)
+}
+
+async fn vakya_root(state: &Arc<AppState>) -> Option<String> {
+    get_merkle_root(
+        State(Arc::clone(state)),
+        Query(MerkleRootQuery { tree_type: "vakya".to_string() }),
+    )
+    .await
+    .expect("root")
+    .0
+    .root_hash
+}
+
+#[tokio::test]
+async fn resubmitted_vakya_id_is_a_conflict() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+    let vakya = build_vakya("file:/tmp/aapi/duplicate.txt");
+
+    let submit = |vakya: Vakya| {
+        submit_vakya(
+            State(Arc::clone(&state)),
+            PeerIdentity::default(),
+            Json(SubmitVakyaRequest { vakya, signature: None, key_id: None }),
+        )
+    };
+
+    let first = submit(vakya.clone()).await.expect("first submit").0;
+    assert_eq!(first.status, "accepted");
+    let root = vakya_root(&state).await;
+
+    let retry = submit(vakya).await.expect_err("duplicate submit");
+    assert!(matches!(retry, GatewayError::Conflict(_)), "{:?}", retry);
+    assert_eq!(vakya_root(&state).await, root);
+}
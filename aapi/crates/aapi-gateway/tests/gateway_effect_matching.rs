@@ -0,0 +1,118 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::Json;
+
+use aapi_core::error::ReasonCode;
+use aapi_core::types::EffectBucket;
+use aapi_core::{
+    ActorType,
+    Adhikarana,
+    ApprovalLane,
+    CapabilityRef,
+    Karta,
+    Karma,
+    Kriya,
+    PrincipalId,
+    ResourceId,
+    Vakya,
+};
+
+use aapi_gateway::handlers::{submit_vakya, SubmitVakyaRequest, SubmitVakyaResponse};
+use aapi_gateway::state::{AppState, GatewayConfig};
+use aapi_gateway::tls::PeerIdentity;
+
+fn build_write(expected_effect: EffectBucket) -> Vakya {
+    let mut kriya = Kriya::new("file", "write");
+    kriya.expected_effect = expected_effect;
+
+    let mut vakya = Vakya::builder()
+        .karta(Karta {
+            pid: PrincipalId::new("agent:declared"),
+            role: None,
+            realm: None,
+            key_id: None,
+            actor_type: ActorType::Agent,
+            delegation_chain: vec![],
+        })
+        .karma(Karma {
+            rid: ResourceId::new(format!("file:/tmp/aapi/effect-{}.txt", uuid::Uuid::new_v4())),
+            kind: Some("file".to_string()),
+            ns: None,
+            version: None,
+            labels: std::collections::HashMap::new(),
+        })
+        .kriya(kriya)
+        .adhikarana(Adhikarana {
+            cap: CapabilityRef::Reference {
+                cap_ref: "cap:test:123".to_string(),
+            },
+            policy_ref: None,
+            ttl: None,
+            budgets: vec![],
+            approval_lane: ApprovalLane::None,
+            scopes: vec![],
+            context: None,
+            delegation_chain_cid: None,
+            execution_constraints: None,
+            port_id: None,
+            required_phase: None,
+            required_role: None,
+        })
+        .build()
+        .expect("vakya build");
+    vakya.body = serde_json::json!({ "content": "declared" });
+    vakya
+}
+
+async fn submit(state: &Arc<AppState>, vakya: Vakya) -> (SubmitVakyaResponse, serde_json::Value) {
+    tokio::fs::create_dir_all("/tmp/aapi").await.expect("mkdir");
+    let response = submit_vakya(
+        State(Arc::clone(state)),
+        PeerIdentity::default(),
+        Json(SubmitVakyaRequest { vakya, signature: None, key_id: None }),
+    )
+    .await
+    .expect("submit")
+    .0;
+    let receipt = state.index_db.get_receipt(&response.vakya_id).await.expect("lookup").expect("receipt");
+    (response, receipt.receipt_json)
+}
+
+#[tokio::test]
+async fn divergent_effects_are_noted_in_the_receipt() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+
+    let (response, receipt) = submit(&state, build_write(EffectBucket::Create)).await;
+    assert_eq!(response.status, "accepted");
+    assert!(receipt.get("effect_warnings").is_none());
+
+    // A write declared as a read
+    let (response, receipt) = submit(&state, build_write(EffectBucket::Read)).await;
+    assert_eq!(response.status, "accepted");
+    let warnings = receipt["effect_warnings"].as_array().expect("warnings");
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].as_str().unwrap().starts_with("Create on file:/tmp/aapi/effect-"));
+
+    // Undeclared expectations are not checked
+    let (_, receipt) = submit(&state, build_write(EffectBucket::None)).await;
+    assert!(receipt.get("effect_warnings").is_none());
+}
+
+#[tokio::test]
+async fn strict_matching_fails_divergent_executions() {
+    let config = GatewayConfig { strict_effect_matching: true, ..GatewayConfig::default() };
+    let state = Arc::new(AppState::in_memory(config).await.expect("state"));
+
+    let (response, _) = submit(&state, build_write(EffectBucket::Create)).await;
+    assert_eq!(response.status, "accepted");
+
+    let (response, receipt) = submit(&state, build_write(EffectBucket::Read)).await;
+    assert_eq!(response.status, "failed");
+    let receipt_response = response.receipt.expect("receipt");
+    assert_eq!(receipt_response.reason_code, ReasonCode::ScopeViolation);
+    assert!(receipt_response.message.unwrap().contains("diverge from declared Read"));
+    // The effect still happened and stays on record
+    assert_eq!(receipt_response.effect_ids.len(), 1);
+    assert_eq!(receipt["status"], "failed");
+}
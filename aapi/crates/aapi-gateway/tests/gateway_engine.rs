@@ -0,0 +1,55 @@
+use std::sync::Arc;
+
+use aapi_core::{
+    canonicalize,
+    Vakya,
+};
+
+use aapi_gateway::engine::{Engine, SubmissionContext};
+use aapi_gateway::error::GatewayError;
+use aapi_gateway::state::{AppState, GatewayConfig};
+
+mod common;
+
+fn build_vakya(rid: &str) -> Vakya {
+    common::build_vakya("agent:embedded", "file.exists", rid)
+}
+
+#[tokio::test]
+async fn engine_runs_the_pipeline_without_http() {
+    let engine = Engine::new(Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state")));
+
+    let vakya = build_vakya("file:/tmp/aapi/embedded.txt");
+    let expected_hash = canonicalize(&vakya).expect("canonicalize").vakya_hash.value;
+
+    let response = engine.execute(vakya.clone(), SubmissionContext::default()).await.expect("execute");
+    assert_eq!(response.status, "accepted");
+    assert_eq!(response.vakya_hash, expected_hash);
+    assert!(response.leaf_index.is_some());
+
+    let receipt = engine.state()
+        .index_db
+        .get_receipt(&vakya.vakya_id.0)
+        .await
+        .expect("receipt query")
+        .expect("stored receipt");
+    assert!(receipt.reason_code.is_success());
+
+    // Persistence is shared with the HTTP path, so a replay is refused
+    let err = engine.execute(vakya, SubmissionContext::default()).await.expect_err("duplicate");
+    assert!(matches!(err, GatewayError::Conflict(_)), "{:?}", err);
+}
+
+#[tokio::test]
+async fn engine_enforces_signatures_from_the_context() {
+    let engine = Engine::new(Arc::new(AppState::in_memory(GatewayConfig {
+        require_signatures: true,
+        ..GatewayConfig::default()
+    }).await.expect("state")));
+
+    let err = engine
+        .execute(build_vakya("file:/tmp/aapi/unsigned.txt"), SubmissionContext::default())
+        .await
+        .expect_err("unsigned");
+    assert!(matches!(err, GatewayError::AuthorizationDenied(_)), "{:?}", err);
+}
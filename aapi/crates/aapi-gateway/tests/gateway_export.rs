@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use axum::extract::{Query, State};
+use axum::Json;
+
+use aapi_gateway::error::GatewayError;
+use aapi_gateway::handlers::{export_evidence, submit_vakya, ExportQuery, SubmitVakyaRequest};
+use aapi_gateway::namespace::CallerScope;
+use aapi_gateway::state::{AppState, GatewayConfig};
+use aapi_gateway::tls::PeerIdentity;
+use aapi_indexdb::{ExportRecord, TreeType};
+
+mod common;
+use common::build_vakya;
+
+#[tokio::test]
+async fn export_streams_records_and_checkpoints_as_jsonl() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+
+    let vakya = build_vakya("agent:exporter", "file.delete", "file:/etc/export.txt");
+    let vakya_id = vakya.vakya_id.0.clone();
+    let submitted = submit_vakya(
+        State(Arc::clone(&state)),
+        PeerIdentity::default(),
+        Json(SubmitVakyaRequest { vakya, signature: None, key_id: None }),
+    )
+    .await
+    .expect("submit")
+    .0;
+    assert_eq!(submitted.status, "denied");
+
+    let response = export_evidence(
+        State(Arc::clone(&state)),
+        CallerScope::unrestricted(),
+        Query(ExportQuery { from: None, to: None, types: Some("vakya,receipt".to_string()) }),
+    )
+    .await
+    .expect("export");
+
+    assert_eq!(response.headers()["content-type"], "application/x-ndjson");
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.expect("body");
+    let lines: Vec<ExportRecord> = std::str::from_utf8(&body)
+        .expect("utf8")
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("json line"))
+        .collect();
+
+    assert_eq!(lines.len(), 4);
+    assert!(matches!(&lines[0], ExportRecord::Vakya(v) if v.vakya_id == vakya_id && v.leaf_index == Some(0)));
+    assert!(matches!(&lines[1], ExportRecord::Receipt(r) if r.vakya_id == vakya_id));
+    assert!(matches!(&lines[2], ExportRecord::Checkpoint(c) if c.tree_type == TreeType::Vakya && c.tree_size == 1));
+    assert!(matches!(&lines[3], ExportRecord::Checkpoint(c) if c.tree_type == TreeType::Receipt));
+}
+
+#[tokio::test]
+async fn export_rejects_unknown_record_type() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+
+    let result = export_evidence(
+        State(state),
+        CallerScope::unrestricted(),
+        Query(ExportQuery { from: None, to: None, types: Some("packet".to_string()) }),
+    )
+    .await;
+
+    assert!(matches!(result, Err(GatewayError::Validation(_))));
+}
@@ -0,0 +1,106 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::Json;
+
+use aapi_core::{
+    Namespace,
+    Vakya,
+};
+use aapi_sdk::verify_bundle;
+
+use aapi_gateway::error::GatewayError;
+use aapi_gateway::handlers::{export_bundle, submit_vakya, ExportBundleRequest, SubmitVakyaRequest};
+use aapi_gateway::namespace::CallerScope;
+use aapi_gateway::state::{AppState, GatewayConfig};
+use aapi_gateway::tls::PeerIdentity;
+
+mod common;
+
+fn build_vakya(action: &str, rid: &str) -> Vakya {
+    common::build_vakya("agent:audited", action, rid)
+}
+
+async fn submit(state: &Arc<AppState>, vakya: Vakya) -> String {
+    submit_vakya(
+        State(Arc::clone(state)),
+        PeerIdentity::default(),
+        Json(SubmitVakyaRequest { vakya, signature: None, key_id: None }),
+    )
+    .await
+    .expect("submit")
+    .0
+    .vakya_id
+}
+
+#[tokio::test]
+async fn exported_bundle_verifies_offline() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+
+    tokio::fs::create_dir_all("/tmp/aapi").await.expect("mkdir");
+    let mut write = build_vakya("file.write", &format!("file:/tmp/aapi/bundle-{}.txt", uuid::Uuid::new_v4()));
+    write.body = serde_json::json!({ "content": "evidence" });
+    let written = submit(&state, write).await;
+    let denied = submit(&state, build_vakya("file.delete", "file:/etc/hosts")).await;
+
+    let bundle = export_bundle(
+        State(Arc::clone(&state)),
+        CallerScope::unrestricted(),
+        Json(ExportBundleRequest { vakya_ids: vec![written, denied] }),
+    )
+    .await
+    .expect("export")
+    .0;
+
+    assert_eq!(bundle.vakyas.len(), 2);
+    assert_eq!(bundle.effects.len(), 1);
+    assert_eq!(bundle.receipts.len(), 2);
+    assert_eq!(bundle.tree_heads.len(), 3);
+    let result = verify_bundle(&bundle);
+    assert!(result.is_valid(), "{:?}", result);
+
+    // Survives a round trip through the file an auditor would receive
+    let reloaded = serde_json::from_str(&serde_json::to_string(&bundle).unwrap()).unwrap();
+    assert!(verify_bundle(&reloaded).is_valid());
+
+    let mut tampered = bundle.clone();
+    tampered.receipts[1].record["reason_code"] = serde_json::json!("SUCCESS");
+    assert!(!verify_bundle(&tampered).is_valid());
+
+    let mut tampered = bundle;
+    tampered.effects[0].record["id"] = serde_json::json!(uuid::Uuid::new_v4().to_string());
+    assert!(!verify_bundle(&tampered).is_valid());
+}
+
+#[tokio::test]
+async fn export_rejects_unknown_and_scoped_requests() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+    let known = submit(&state, build_vakya("file.exists", "file:/tmp/aapi/bundle-probe")).await;
+
+    let missing = export_bundle(
+        State(Arc::clone(&state)),
+        CallerScope::unrestricted(),
+        Json(ExportBundleRequest { vakya_ids: vec![known.clone(), "missing".to_string()] }),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(missing, GatewayError::NotFound(_)));
+
+    let empty = export_bundle(
+        State(Arc::clone(&state)),
+        CallerScope::unrestricted(),
+        Json(ExportBundleRequest { vakya_ids: vec![] }),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(empty, GatewayError::Validation(_)));
+
+    let scoped = export_bundle(
+        State(state),
+        CallerScope::restricted(vec![Namespace::new("team-a")]),
+        Json(ExportBundleRequest { vakya_ids: vec![known] }),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(scoped, GatewayError::AuthorizationDenied(_)));
+}
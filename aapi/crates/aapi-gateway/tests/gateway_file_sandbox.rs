@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::Json;
+
+use aapi_adapters::ActionFilter;
+use aapi_core::Vakya;
+
+use aapi_gateway::handlers::{submit_vakya, SubmitVakyaRequest};
+use aapi_gateway::state::{AppState, GatewayConfig};
+use aapi_gateway::tls::PeerIdentity;
+
+mod common;
+
+fn build_vakya(action: &str, rid: &str, body: serde_json::Value) -> Vakya {
+    let mut vakya = common::build_vakya("agent:sandboxed", action, rid);
+    vakya.body = body;
+    vakya
+}
+
+async fn submit(state: &Arc<AppState>, vakya: Vakya) -> String {
+    submit_vakya(
+        State(Arc::clone(state)),
+        PeerIdentity::default(),
+        Json(SubmitVakyaRequest { vakya, signature: None, key_id: None }),
+    )
+    .await
+    .expect("submit")
+    .0
+    .status
+}
+
+#[tokio::test]
+async fn file_adapter_uses_configured_base_dir() {
+    let root = tempfile::tempdir().expect("tempdir");
+    let sandbox = root.path().join("sandbox");
+
+    let config = GatewayConfig {
+        file_base_dir: sandbox.clone(),
+        file_base_dir_mode: Some(0o750),
+        ..GatewayConfig::default()
+    };
+    let state = Arc::new(AppState::in_memory(config).await.expect("state"));
+
+    assert!(sandbox.is_dir());
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&sandbox).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o750);
+    }
+
+    let target = sandbox.join("report.txt");
+    let status = submit(
+        &state,
+        build_vakya(
+            "file.write",
+            &format!("file:{}", target.display()),
+            serde_json::json!({ "content": "persisted" }),
+        ),
+    )
+    .await;
+    assert_eq!(status, "accepted");
+    assert_eq!(std::fs::read_to_string(&target).unwrap(), "persisted");
+
+    // Deletes are allowed inside the configured sandbox, not the default one
+    let status = submit(
+        &state,
+        build_vakya("file.delete", &format!("file:{}", target.display()), serde_json::json!({})),
+    )
+    .await;
+    assert_eq!(status, "accepted");
+    assert!(!target.exists());
+
+    let status = submit(
+        &state,
+        build_vakya("file.delete", "file:/tmp/aapi/outside-sandbox.txt", serde_json::json!({})),
+    )
+    .await;
+    assert_eq!(status, "denied");
+}
+
+#[tokio::test]
+async fn disabled_file_actions_never_reach_the_adapter() {
+    let root = tempfile::tempdir().expect("tempdir");
+    let config = GatewayConfig {
+        file_base_dir: root.path().to_path_buf(),
+        action_filters: [("file".to_string(), ActionFilter::deny(["file.delete"]))].into(),
+        ..GatewayConfig::default()
+    };
+    let state = Arc::new(AppState::in_memory(config).await.expect("state"));
+
+    let target = root.path().join("keep.txt");
+    std::fs::write(&target, "kept").unwrap();
+    let status = submit(
+        &state,
+        build_vakya("file.delete", &format!("file:{}", target.display()), serde_json::json!({})),
+    )
+    .await;
+    assert_eq!(status, "failed");
+    assert!(target.exists());
+
+    let info = state.dispatcher.adapter_info().await;
+    let file = info.iter().find(|a| a.domain == "file").expect("file adapter");
+    assert!(!file.actions.iter().any(|a| a == "file.delete"));
+    assert!(file.actions.iter().any(|a| a == "file.write"));
+}
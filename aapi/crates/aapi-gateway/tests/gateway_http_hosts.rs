@@ -0,0 +1,56 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::Json;
+
+use aapi_core::Vakya;
+
+use aapi_gateway::handlers::{submit_vakya, SubmitVakyaRequest, SubmitVakyaResponse};
+use aapi_gateway::state::{AppState, GatewayConfig};
+use aapi_gateway::tls::PeerIdentity;
+
+mod common;
+
+fn build_vakya(url: &str) -> Vakya {
+    common::build_vakya("agent:fetcher", "http.get", url)
+}
+
+async fn submit(state: &Arc<AppState>, url: &str) -> SubmitVakyaResponse {
+    submit_vakya(
+        State(Arc::clone(state)),
+        PeerIdentity::default(),
+        Json(SubmitVakyaRequest { vakya: build_vakya(url), signature: None, key_id: None }),
+    )
+    .await
+    .expect("submit")
+    .0
+}
+
+#[tokio::test]
+async fn gateway_blocks_private_http_destinations() {
+    let config = GatewayConfig {
+        http_block_private: true,
+        http_denied_hosts: vec!["blocked.example".to_string()],
+        ..GatewayConfig::default()
+    };
+    let state = Arc::new(AppState::in_memory(config).await.expect("state"));
+
+    for url in ["http://169.254.169.254/latest/meta-data/", "http://localhost:9/", "http://api.blocked.example/"] {
+        let response = submit(&state, url).await;
+        assert_eq!(response.status, "failed", "{}", url);
+        let message = response.receipt.expect("receipt").message.unwrap_or_default();
+        assert!(message.contains("denied") || message.contains("private"), "{}: {}", url, message);
+    }
+}
+
+#[test]
+fn production_restricts_http_by_default() {
+    let config = GatewayConfig::production();
+    assert!(config.blocks_private_networks());
+    assert!(config.http_allow_list_required());
+    assert!(config.http_allowed_hosts.is_empty());
+
+    let config = GatewayConfig::default();
+    assert!(!config.blocks_private_networks());
+    assert!(!config.http_allow_list_required());
+}
@@ -0,0 +1,203 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::Json;
+
+use aapi_core::{
+    CapabilityRef,
+    PrincipalId,
+    Vakya,
+};
+use aapi_crypto::{CapabilityIssuer, CapabilityTokenBuilder, Caveat, CaveatType, KeyPair, KeyPurpose, TokenAttenuation};
+
+use aapi_gateway::error::GatewayError;
+use aapi_gateway::handlers::{revoke_capability, submit_vakya, RevokeCapabilityRequest, SubmitVakyaRequest};
+use aapi_gateway::identity::Caller;
+use aapi_gateway::namespace::CallerScope;
+use aapi_gateway::state::{AppState, GatewayConfig};
+use aapi_gateway::tls::PeerIdentity;
+
+mod common;
+
+fn build_vakya(actor: &str, action: &str, rid: &str, cap: CapabilityRef) -> Vakya {
+    let mut vakya = common::build_vakya(actor, action, rid);
+    vakya.v7_adhikarana.cap = cap;
+    vakya
+}
+
+const INLINE_RID: &str = "file:/tmp/aapi/inline-cap.txt";
+
+fn inline_cap(issuer: &KeyPair, subject: &str, ttl_seconds: i64) -> CapabilityRef {
+    let token = CapabilityTokenBuilder::new()
+        .issuer(PrincipalId::new("issuer:gateway"))
+        .subject(PrincipalId::new(subject))
+        .action("file.exists")
+        .resource(INLINE_RID)
+        .ttl_seconds(ttl_seconds)
+        .build_and_sign(issuer)
+        .expect("sign token");
+    CapabilityRef::Inline(Box::new((&token).into()))
+}
+
+async fn submit(state: &Arc<AppState>, vakya: Vakya) -> Result<String, GatewayError> {
+    submit_from(state, PeerIdentity::default(), vakya).await
+}
+
+async fn submit_from(state: &Arc<AppState>, peer: PeerIdentity, vakya: Vakya) -> Result<String, GatewayError> {
+    submit_vakya(
+        State(Arc::clone(state)),
+        peer,
+        Json(SubmitVakyaRequest { vakya, signature: None, key_id: None }),
+    )
+    .await
+    .map(|response| response.0.status)
+}
+
+#[tokio::test]
+async fn inline_capability_is_verified_before_execution() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+    let issuer = KeyPair::generate(KeyPurpose::CapabilitySigning);
+    state.key_store.store_public_key(issuer.to_public_info()).expect("trust issuer");
+
+    // Token survives a JSON round trip through the request body
+    let vakya = build_vakya("agent:inline", "file.exists", INLINE_RID, inline_cap(&issuer, "agent:inline", 600));
+    let vakya: Vakya = serde_json::from_value(serde_json::to_value(&vakya).unwrap()).unwrap();
+    assert_eq!(submit(&state, vakya).await.expect("valid token"), "accepted");
+
+    let expired = build_vakya("agent:inline", "file.exists", INLINE_RID, inline_cap(&issuer, "agent:inline", -60));
+    let err = submit(&state, expired).await.expect_err("expired token");
+    assert!(matches!(err, GatewayError::AuthorizationDenied(ref reason) if reason.contains("expired")), "{:?}", err);
+
+    let stolen = build_vakya("agent:other", "file.exists", INLINE_RID, inline_cap(&issuer, "agent:inline", 600));
+    let err = submit(&state, stolen).await.expect_err("subject mismatch");
+    assert!(matches!(err, GatewayError::AuthorizationDenied(_)), "{:?}", err);
+
+    let out_of_scope = build_vakya("agent:inline", "file.exists", "file:/etc/hosts", inline_cap(&issuer, "agent:inline", 600));
+    let err = submit(&state, out_of_scope).await.expect_err("resource not covered");
+    assert!(matches!(err, GatewayError::AuthorizationDenied(_)), "{:?}", err);
+
+    let untrusted = KeyPair::generate(KeyPurpose::CapabilitySigning);
+    let forged = build_vakya("agent:inline", "file.exists", INLINE_RID, inline_cap(&untrusted, "agent:inline", 600));
+    let err = submit(&state, forged).await.expect_err("unknown issuer key");
+    assert!(matches!(err, GatewayError::AuthorizationDenied(_)), "{:?}", err);
+}
+
+#[tokio::test]
+async fn ip_address_caveats_are_checked_against_the_peer() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+    let issuer = KeyPair::generate(KeyPurpose::CapabilitySigning);
+    state.key_store.store_public_key(issuer.to_public_info()).expect("trust issuer");
+
+    let token = CapabilityTokenBuilder::new()
+        .issuer(PrincipalId::new("issuer:gateway"))
+        .subject(PrincipalId::new("agent:inline"))
+        .action("file.exists")
+        .resource(INLINE_RID)
+        .ttl_seconds(600)
+        .caveat(Caveat {
+            caveat_type: CaveatType::IpAddress,
+            value: serde_json::json!("10.0.0.0/8"),
+            description: Some("internal only".to_string()),
+        })
+        .build_and_sign(&issuer)
+        .expect("sign token");
+    let vakya = || build_vakya("agent:inline", "file.exists", INLINE_RID, CapabilityRef::Inline(Box::new((&token).into())));
+    let from = |addr: &str| PeerIdentity { certificate: None, addr: Some(addr.parse().unwrap()) };
+
+    let err = submit_from(&state, from("192.168.1.5:40000"), vakya()).await.expect_err("outside the range");
+    assert!(
+        matches!(err, GatewayError::AuthorizationDenied(ref reason) if reason.contains("ip_address caveat")),
+        "{:?}",
+        err
+    );
+
+    // A peer address the gateway does not know cannot satisfy the caveat
+    let err = submit(&state, vakya()).await.expect_err("no peer address");
+    assert!(matches!(err, GatewayError::AuthorizationDenied(_)), "{:?}", err);
+
+    assert_eq!(submit_from(&state, from("10.4.2.1:40000"), vakya()).await.expect("inside the range"), "accepted");
+}
+
+#[tokio::test]
+async fn delegation_beyond_the_gateway_limit_is_refused() {
+    let config = GatewayConfig { max_delegation_depth: Some(0), ..GatewayConfig::default() };
+    let state = Arc::new(AppState::in_memory(config).await.expect("state"));
+    let key_id = state.key_store.generate_key(KeyPurpose::CapabilitySigning).expect("key");
+    let issuer_key = state.key_store.get_key(&key_id).expect("key pair");
+
+    let root = CapabilityTokenBuilder::new()
+        .issuer(PrincipalId::new("issuer:gateway"))
+        .subject(PrincipalId::new("agent:lead"))
+        .action("file.exists")
+        .resource(INLINE_RID)
+        .ttl_seconds(600)
+        .max_delegation_depth(3)
+        .build_and_sign(&issuer_key)
+        .expect("sign token");
+    let delegated = CapabilityIssuer::new(state.key_store.clone(), key_id, PrincipalId::new("issuer:gateway"))
+        .attenuate(&root, PrincipalId::new("agent:helper"), TokenAttenuation::default())
+        .expect("delegate");
+
+    let direct = build_vakya("agent:lead", "file.exists", INLINE_RID, CapabilityRef::Inline(Box::new((&root).into())));
+    assert_eq!(submit(&state, direct).await.expect("root token"), "accepted");
+
+    let vakya = build_vakya("agent:helper", "file.exists", INLINE_RID, CapabilityRef::Inline(Box::new((&delegated).into())));
+    let err = submit(&state, vakya).await.expect_err("too deep");
+    assert!(
+        matches!(err, GatewayError::AuthorizationDenied(ref reason) if reason.contains("Delegation depth 1 exceeds")),
+        "{:?}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn revoking_a_root_reaches_grandchildren_across_a_restart() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let config = GatewayConfig {
+        database_url: format!("sqlite:{}?mode=rwc", dir.path().join("gateway.db").display()),
+        capability_operators: vec!["ops:alice".to_string()],
+        ..GatewayConfig::default()
+    };
+    let state = Arc::new(AppState::new(config.clone()).await.expect("state"));
+    let key_id = state.key_store.generate_key(KeyPurpose::CapabilitySigning).expect("key");
+    let issuer_key = state.key_store.get_key(&key_id).expect("key pair");
+
+    let root = CapabilityTokenBuilder::new()
+        .issuer(PrincipalId::new("issuer:gateway"))
+        .subject(PrincipalId::new("agent:lead"))
+        .action("file.exists")
+        .resource(INLINE_RID)
+        .ttl_seconds(600)
+        .max_delegation_depth(3)
+        .build_and_sign(&issuer_key)
+        .expect("sign token");
+    let issuer = CapabilityIssuer::new(state.key_store.clone(), key_id, PrincipalId::new("issuer:gateway"));
+    let child = issuer.attenuate(&root, PrincipalId::new("agent:helper"), TokenAttenuation::default()).expect("delegate");
+    let grandchild = issuer.attenuate(&child, PrincipalId::new("agent:sub"), TokenAttenuation::default()).expect("delegate");
+
+    // Only the child is ever presented before the restart
+    let vakya = build_vakya("agent:helper", "file.exists", INLINE_RID, CapabilityRef::Inline(Box::new((&child).into())));
+    assert_eq!(submit(&state, vakya).await.expect("child token"), "accepted");
+    drop(state);
+
+    let state = Arc::new(AppState::new(config).await.expect("restarted state"));
+    state.key_store.store_public_key(issuer_key.to_public_info()).expect("trust issuer");
+    let response = revoke_capability(
+        State(Arc::clone(&state)),
+        CallerScope::unrestricted(),
+        Caller::authenticated("ops:alice"),
+        Path(root.token_id.clone()),
+        Some(Json(RevokeCapabilityRequest { include_delegations: true, reason: None })),
+    )
+    .await
+    .expect("revoke root");
+    assert!(response.0.revoked);
+
+    let vakya = build_vakya("agent:sub", "file.exists", INLINE_RID, CapabilityRef::Inline(Box::new((&grandchild).into())));
+    let err = submit(&state, vakya).await.expect_err("grandchild of a revoked root");
+    assert!(
+        matches!(err, GatewayError::AuthorizationDenied(ref reason) if reason.contains(&root.token_id)),
+        "{:?}",
+        err
+    );
+}
@@ -0,0 +1,35 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::Json;
+
+use aapi_gateway::handlers::{get_vakya, submit_vakya, SubmitVakyaRequest};
+use aapi_gateway::namespace::CallerScope;
+use aapi_gateway::state::{AppState, GatewayConfig};
+use aapi_gateway::tls::PeerIdentity;
+
+mod common;
+use common::build_vakya;
+
+#[tokio::test]
+async fn submitted_vakyas_pass_hash_verification() {
+    let config = GatewayConfig { verify_record_hashes: true, ..GatewayConfig::default() };
+    let state = Arc::new(AppState::in_memory(config).await.expect("state"));
+
+    let mut vakya = build_vakya("agent:audit", "file.write", "file:/tmp/aapi/integrity.json");
+    vakya.body = serde_json::json!({ "content": { "ratio": 0.1, "tags": ["a", "b"] } });
+    let submitted = submit_vakya(
+        State(Arc::clone(&state)),
+        PeerIdentity::default(),
+        Json(SubmitVakyaRequest { vakya, signature: None, key_id: None }),
+    )
+    .await
+    .expect("submit")
+    .0;
+
+    let record = get_vakya(State(state), CallerScope::unrestricted(), Path(submitted.vakya_id.clone()))
+        .await
+        .expect("verified read")
+        .0;
+    assert_eq!(record.vakya_hash, submitted.vakya_hash);
+}
@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+
+use aapi_crypto::KeyPurpose;
+use aapi_gateway::handlers::{get_jwks, list_public_keys};
+use aapi_gateway::state::{AppState, GatewayConfig};
+
+#[tokio::test]
+async fn public_keys_are_listed_and_published_as_jwks() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+    let extra = state.key_store.generate_key(KeyPurpose::CapabilitySigning).expect("generate");
+
+    let keys = list_public_keys(State(Arc::clone(&state))).await.expect("list").0;
+    let receipt_key = keys.iter().find(|k| k.key_id == state.receipt_key_id).expect("receipt key listed");
+    assert_eq!(receipt_key.purpose, KeyPurpose::ReceiptSigning);
+    assert!(keys.iter().any(|k| k.key_id == extra));
+
+    let jwks = get_jwks(State(Arc::clone(&state))).await.expect("jwks").0;
+    assert_eq!(jwks.keys.len(), keys.len());
+    let jwk = jwks.keys.iter().find(|k| k.kid == state.receipt_key_id.0).expect("receipt key in jwks");
+    assert_eq!(jwk, &receipt_key.to_jwk().expect("jwk"));
+
+    let document = serde_json::to_value(&jwks).expect("serialize");
+    let entry = document["keys"].as_array().expect("keys")
+        .iter()
+        .find(|k| k["kid"] == state.receipt_key_id.0.as_str())
+        .expect("receipt key entry");
+    assert_eq!(entry["kty"], "OKP");
+    assert_eq!(entry["crv"], "Ed25519");
+    assert_eq!(entry["use"], "sig");
+    assert_eq!(entry["purpose"], "receipt_signing");
+}
@@ -0,0 +1,116 @@
+use std::path::Path;
+
+use rcgen::{
+    BasicConstraints, Certificate, CertificateParams, DnType, ExtendedKeyUsagePurpose, IsCa, KeyPair,
+};
+
+use aapi_gateway::server::GatewayServer;
+use aapi_gateway::state::GatewayConfig;
+use aapi_gateway::tls::TlsConfig;
+
+mod common;
+use common::build_vakya;
+
+struct Pki {
+    ca: Certificate,
+    ca_key: KeyPair,
+}
+
+impl Pki {
+    fn new() -> Self {
+        let ca_key = KeyPair::generate().expect("ca key");
+        let mut params = CertificateParams::new(Vec::<String>::new()).expect("ca params");
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        params.distinguished_name.push(DnType::CommonName, "AAPI Test CA");
+        let ca = params.self_signed(&ca_key).expect("ca cert");
+        Self { ca, ca_key }
+    }
+
+    /// Issue a leaf certificate; returns (cert PEM, key PEM)
+    fn issue(&self, cn: &str, sans: &[&str], usage: ExtendedKeyUsagePurpose) -> (String, String) {
+        let key = KeyPair::generate().expect("leaf key");
+        let sans: Vec<String> = sans.iter().map(|s| s.to_string()).collect();
+        let mut params = CertificateParams::new(sans).expect("leaf params");
+        params.distinguished_name.push(DnType::CommonName, cn);
+        params.extended_key_usages = vec![usage];
+        let cert = params.signed_by(&key, &self.ca, &self.ca_key).expect("leaf cert");
+        (cert.pem(), key.serialize_pem())
+    }
+}
+
+async fn start_gateway(dir: &Path, pki: &Pki) -> (std::net::SocketAddr, tokio::sync::oneshot::Sender<()>) {
+    let (cert, key) = pki.issue("localhost", &["localhost"], ExtendedKeyUsagePurpose::ServerAuth);
+    std::fs::write(dir.join("server.pem"), cert).unwrap();
+    std::fs::write(dir.join("server.key"), key).unwrap();
+    std::fs::write(dir.join("ca.pem"), pki.ca.pem()).unwrap();
+
+    let tls = TlsConfig::new(dir.join("server.pem"), dir.join("server.key"))
+        .with_client_ca(dir.join("ca.pem"))
+        .require_client_cert()
+        .match_principal();
+    let config = GatewayConfig { tls: Some(tls), ..GatewayConfig::default() };
+    let server = GatewayServer::in_memory(config).await.expect("server");
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.expect("bind");
+    let addr = listener.local_addr().unwrap();
+    let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+    tokio::spawn(async move {
+        server
+            .serve_with_shutdown(listener, async { stopped.await.ok(); })
+            .await
+            .expect("serve");
+    });
+    (addr, stop)
+}
+
+fn client(addr: std::net::SocketAddr, pki: &Pki, identity: Option<(String, String)>) -> reqwest::Client {
+    let mut builder = reqwest::Client::builder()
+        .use_rustls_tls()
+        .tls_built_in_root_certs(false)
+        .add_root_certificate(reqwest::Certificate::from_pem(pki.ca.pem().as_bytes()).unwrap())
+        .resolve("localhost", addr);
+    if let Some((cert, key)) = identity {
+        let pem = format!("{}{}", key, cert);
+        builder = builder.identity(reqwest::Identity::from_pem(pem.as_bytes()).unwrap());
+    }
+    builder.build().unwrap()
+}
+
+#[tokio::test]
+async fn mtls_requires_client_cert_and_binds_principal() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let pki = Pki::new();
+    let (addr, _stop) = start_gateway(dir.path(), &pki).await;
+    let base = format!("https://localhost:{}", addr.port());
+
+    // No client certificate: the handshake is rejected
+    let anonymous = client(addr, &pki, None);
+    assert!(anonymous.get(format!("{}/health", base)).send().await.is_err());
+
+    let identity = pki.issue("agent:billing", &[], ExtendedKeyUsagePurpose::ClientAuth);
+    let billing = client(addr, &pki, Some(identity));
+    let health = billing.get(format!("{}/health", base)).send().await.expect("health");
+    assert!(health.status().is_success());
+
+    let submit = |actor: &str| {
+        let vakya = build_vakya(actor, "file.read", "file:/tmp/aapi/mtls.txt");
+        billing.post(format!("{}/v1/vakya", base)).json(&serde_json::json!({ "vakya": vakya })).send()
+    };
+
+    let own = submit("agent:billing").await.expect("submit");
+    assert_ne!(own.status(), reqwest::StatusCode::FORBIDDEN);
+
+    let impersonated = submit("agent:payroll").await.expect("submit");
+    assert_eq!(impersonated.status(), reqwest::StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn client_certs_from_other_cas_are_rejected() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let pki = Pki::new();
+    let (addr, _stop) = start_gateway(dir.path(), &pki).await;
+
+    let rogue = Pki::new().issue("agent:billing", &[], ExtendedKeyUsagePurpose::ClientAuth);
+    let client = client(addr, &pki, Some(rogue));
+    assert!(client.get(format!("https://localhost:{}/health", addr.port())).send().await.is_err());
+}
@@ -0,0 +1,241 @@
+use std::sync::Arc;
+
+use axum::extract::{FromRequestParts, Path, Query, State};
+use axum::http::Request;
+use axum::Json;
+
+use aapi_core::{
+    Namespace,
+    Vakya,
+};
+
+use aapi_gateway::error::GatewayError;
+use aapi_gateway::handlers::{
+    export_evidence, get_approval, get_vakya, reload_policies, replay_vakyas, revoke_capability, submit_vakya,
+    vote_approval, ApprovalVoteRequest, ExportQuery, ReplayRequest, SimulationQuery, SubmitVakyaRequest,
+};
+use aapi_gateway::identity::Caller;
+use aapi_gateway::namespace::{CallerScope, ALL_NAMESPACES};
+use aapi_gateway::state::{AppState, GatewayConfig};
+use aapi_gateway::tls::PeerIdentity;
+use aapi_indexdb::{ApprovalRecordStatus, VoteDecision};
+use aapi_metarules::{ApprovalConfig, ApprovalType, Condition, ConditionType, Operator, Policy, Rule};
+
+mod common;
+
+fn build_vakya(actor: &str, action: &str, rid: &str, ns: &str) -> Vakya {
+    let mut vakya = common::build_vakya(actor, action, rid);
+    vakya.v2_karma.ns = Some(Namespace::new(ns));
+    vakya
+}
+
+async fn submit_in(state: &Arc<AppState>, ns: &str) -> String {
+    let rid = format!("file:/tmp/aapi/ns-{}-{}.txt", ns, uuid::Uuid::new_v4());
+    let mut vakya = build_vakya("agent:tenant", "file.write", &rid, ns);
+    vakya.body = serde_json::json!({ "content": "tenant data" });
+    submit_vakya(
+        State(Arc::clone(state)),
+        PeerIdentity::default(),
+        Json(SubmitVakyaRequest { vakya, signature: None, key_id: None }),
+    )
+    .await
+    .expect("submit")
+    .0
+    .vakya_id
+}
+
+async fn extract_for<T>(state: &Arc<AppState>, api_key: Option<&str>) -> Result<T, GatewayError>
+where
+    T: FromRequestParts<Arc<AppState>, Rejection = GatewayError>,
+{
+    let mut request = Request::builder();
+    if let Some(key) = api_key {
+        request = request.header("authorization", format!("Bearer {}", key));
+    }
+    let (mut parts, _) = request.body(()).expect("request").into_parts();
+    T::from_request_parts(&mut parts, state).await
+}
+
+async fn scope_for(state: &Arc<AppState>, api_key: Option<&str>) -> Result<CallerScope, GatewayError> {
+    extract_for(state, api_key).await
+}
+
+async fn namespaced_state() -> Arc<AppState> {
+    let mut config = GatewayConfig::default();
+    config.api_key_namespaces.insert("acme-key".to_string(), vec!["org.acme".to_string()]);
+    Arc::new(AppState::in_memory(config).await.expect("state"))
+}
+
+#[tokio::test]
+async fn scoped_key_only_reads_its_namespace() {
+    let state = namespaced_state().await;
+    let own = submit_in(&state, "org.acme.billing").await;
+    let other = submit_in(&state, "org.other").await;
+
+    let record = get_vakya(
+        State(Arc::clone(&state)),
+        scope_for(&state, Some("acme-key")).await.expect("scope"),
+        Path(own.clone()),
+    )
+    .await
+    .expect("own record")
+    .0;
+    assert_eq!(record.karma_ns.as_deref(), Some("org.acme.billing"));
+
+    let hidden = get_vakya(
+        State(Arc::clone(&state)),
+        scope_for(&state, Some("acme-key")).await.expect("scope"),
+        Path(other),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(hidden, GatewayError::NotFound(_)));
+
+    assert!(matches!(scope_for(&state, None).await, Err(GatewayError::AuthorizationDenied(_))));
+    assert!(matches!(scope_for(&state, Some("stolen")).await, Err(GatewayError::AuthorizationDenied(_))));
+}
+
+#[tokio::test]
+async fn scoped_queries_must_stay_in_namespace() {
+    let state = namespaced_state().await;
+    submit_in(&state, "org.acme").await;
+    submit_in(&state, "org.other").await;
+
+    let replay = |namespace: Option<&str>| {
+        let state = Arc::clone(&state);
+        let namespace = namespace.map(String::from);
+        async move {
+            let scope = scope_for(&state, Some("acme-key")).await.expect("scope");
+            replay_vakyas(
+                State(state),
+                scope,
+                Json(ReplayRequest {
+                    filter: SimulationQuery { namespace, ..Default::default() },
+                }),
+            )
+            .await
+        }
+    };
+
+    let report = replay(Some("org.acme")).await.expect("replay").0;
+    assert_eq!(report.results.len(), 1);
+    assert!(matches!(replay(None).await, Err(GatewayError::AuthorizationDenied(_))));
+    assert!(matches!(replay(Some("org.other")).await, Err(GatewayError::AuthorizationDenied(_))));
+
+    let export = export_evidence(
+        State(Arc::clone(&state)),
+        scope_for(&state, Some("acme-key")).await.expect("scope"),
+        Query(ExportQuery { from: None, to: None, types: None }),
+    )
+    .await;
+    assert!(matches!(export, Err(GatewayError::AuthorizationDenied(_))));
+}
+
+#[tokio::test]
+async fn scoped_key_cannot_see_or_vote_on_other_approvals() {
+    let state = namespaced_state().await;
+    state
+        .policy_engine
+        .add_policy(
+            Policy::new("policy:approve-writes", "Approve Writes").with_priority(200).with_rule(
+                Rule::require_approval("rule:approve-writes", "Writes need sign-off")
+                    .with_condition(Condition {
+                        condition_type: ConditionType::Action,
+                        field: "action".to_string(),
+                        operator: Operator::Eq,
+                        value: serde_json::json!("file.write"),
+                    })
+                    .with_approval_config(
+                        ApprovalConfig::new(ApprovalType::Human).with_approvers(vec!["user:reviewer".to_string()]),
+                    ),
+            ),
+        )
+        .await
+        .expect("policy");
+
+    let mut vakya = build_vakya("agent:tenant", "file.write", "file:/tmp/aapi/ns-approval.txt", "org.other");
+    vakya.body = serde_json::json!({ "content": "tenant data" });
+    let approval_id = submit_vakya(
+        State(Arc::clone(&state)),
+        PeerIdentity::default(),
+        Json(SubmitVakyaRequest { vakya, signature: None, key_id: None }),
+    )
+    .await
+    .expect("submit")
+    .0
+    .policy_decision
+    .and_then(|d| d.approval_id)
+    .expect("approval id");
+
+    let hidden = get_approval(
+        State(Arc::clone(&state)),
+        scope_for(&state, Some("acme-key")).await.expect("scope"),
+        Path(approval_id.clone()),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(hidden, GatewayError::NotFound(_)));
+
+    let refused = vote_approval(
+        State(Arc::clone(&state)),
+        scope_for(&state, Some("acme-key")).await.expect("scope"),
+        Path(approval_id.clone()),
+        Caller::authenticated("user:reviewer"),
+        Json(ApprovalVoteRequest { decision: VoteDecision::Approve, comment: None }),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(refused, GatewayError::NotFound(_)));
+
+    let approval = state.index_db.get_approval(&approval_id).await.expect("get").expect("approval");
+    assert_eq!(approval.status, ApprovalRecordStatus::Pending);
+    assert!(approval.votes.is_empty());
+}
+
+#[tokio::test]
+async fn admin_key_reaches_operator_endpoints() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let policies = vec![Policy::new("policy:base", "Base").with_default_allow()];
+    std::fs::write(dir.path().join("policies.json"), serde_json::to_vec(&policies).unwrap()).unwrap();
+
+    let mut config = GatewayConfig {
+        policy_dir: Some(dir.path().to_path_buf()),
+        capability_operators: vec!["ops:admin".to_string()],
+        policy_operators: vec!["ops:admin".to_string()],
+        ..GatewayConfig::default()
+    };
+    config.api_key_namespaces.insert("acme-key".to_string(), vec!["org.acme".to_string()]);
+    config.api_key_namespaces.insert("admin-key".to_string(), vec![ALL_NAMESPACES.to_string()]);
+    // Even an operator principal is refused through a tenant key
+    config.api_key_principals.insert("acme-key".to_string(), "ops:admin".to_string());
+    config.api_key_principals.insert("admin-key".to_string(), "ops:admin".to_string());
+    let state = Arc::new(AppState::in_memory(config).await.expect("state"));
+
+    let as_key = |key: &'static str| {
+        let state = Arc::clone(&state);
+        async move {
+            let scope: CallerScope = extract_for(&state, Some(key)).await.expect("scope");
+            let caller: Caller = extract_for(&state, Some(key)).await.expect("caller");
+            (scope, caller)
+        }
+    };
+
+    let (scope, caller) = as_key("acme-key").await;
+    let refused = revoke_capability(State(Arc::clone(&state)), scope, caller, Path("cap-1".to_string()), None).await;
+    assert!(matches!(refused, Err(GatewayError::AuthorizationDenied(_))));
+    let (scope, caller) = as_key("acme-key").await;
+    let refused = reload_policies(State(Arc::clone(&state)), scope, caller).await;
+    assert!(matches!(refused, Err(GatewayError::AuthorizationDenied(_))));
+
+    let (scope, caller) = as_key("admin-key").await;
+    assert!(!scope.is_restricted());
+    let revoked = revoke_capability(State(Arc::clone(&state)), scope, caller, Path("cap-1".to_string()), None)
+        .await
+        .expect("admin revokes")
+        .0;
+    assert!(revoked.revoked);
+    assert!(state.cap_verifier.revocations().is_revoked("cap-1"));
+    let (scope, caller) = as_key("admin-key").await;
+    let reloaded = reload_policies(State(Arc::clone(&state)), scope, caller).await.expect("admin reloads").0;
+    assert_eq!(reloaded.policies, 1);
+}
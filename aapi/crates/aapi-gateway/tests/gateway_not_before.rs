@@ -0,0 +1,208 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use axum::extract::State;
+use axum::Json;
+
+use aapi_adapters::{
+    Adapter, AdapterResult, CapturedEffect, ExecutionContext, ExecutionResult, HealthStatus,
+};
+use aapi_core::error::ReasonCode;
+use aapi_core::types::Timestamp;
+use aapi_core::{
+    ApprovalLane,
+    TtlConstraint,
+    Vakya,
+};
+
+use aapi_gateway::error::{GatewayError, GatewayResult};
+use aapi_gateway::handlers::{resume_deferred_executions, submit_vakya, SubmitVakyaRequest, SubmitVakyaResponse};
+use aapi_gateway::state::{AppState, GatewayConfig};
+use aapi_gateway::tls::PeerIdentity;
+
+mod common;
+
+/// Records when it was asked to run
+struct ClockAdapter {
+    ran_at: Arc<Mutex<Option<chrono::DateTime<chrono::Utc>>>>,
+}
+
+#[async_trait]
+impl Adapter for ClockAdapter {
+    fn domain(&self) -> &str {
+        "clock"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn supported_actions(&self) -> Vec<&str> {
+        vec!["clock.tick"]
+    }
+
+    async fn execute(&self, _vakya: &Vakya, _context: &ExecutionContext) -> AdapterResult<ExecutionResult> {
+        *self.ran_at.lock().unwrap() = Some(chrono::Utc::now());
+        Ok(ExecutionResult::success(serde_json::json!({}), vec![], 0))
+    }
+
+    fn can_rollback(&self, _action: &str) -> bool {
+        false
+    }
+
+    async fn rollback(&self, _effect: &CapturedEffect) -> AdapterResult<()> {
+        Ok(())
+    }
+
+    async fn health_check(&self) -> AdapterResult<HealthStatus> {
+        Ok(HealthStatus::healthy())
+    }
+}
+
+fn build_vakya(approval_lane: ApprovalLane, not_before: Timestamp) -> Vakya {
+    let mut vakya = common::build_vakya("agent:scheduler", "clock.tick", "clock:job");
+    vakya.v7_adhikarana.ttl = Some(TtlConstraint {
+        expires_at: Timestamp(chrono::Utc::now() + chrono::Duration::minutes(5)),
+        max_duration_ms: None,
+        not_before: Some(not_before),
+    });
+    vakya.v7_adhikarana.approval_lane = approval_lane;
+    vakya
+}
+
+async fn submit_deferred(state: &Arc<AppState>, not_before: chrono::DateTime<chrono::Utc>) -> GatewayResult<SubmitVakyaResponse> {
+    submit_vakya(
+        State(Arc::clone(state)),
+        PeerIdentity::default(),
+        Json(SubmitVakyaRequest {
+            vakya: build_vakya(ApprovalLane::Sync, Timestamp(not_before)),
+            signature: None,
+            key_id: None,
+        }),
+    )
+    .await
+    .map(|response| response.0)
+}
+
+#[tokio::test]
+async fn immediate_vakya_with_future_not_before_is_rejected() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+    let ran_at = Arc::new(Mutex::new(None));
+    state.adapters.write().await.register(ClockAdapter { ran_at: Arc::clone(&ran_at) });
+
+    let not_before = Timestamp(chrono::Utc::now() + chrono::Duration::minutes(1));
+    let mut vakya = build_vakya(ApprovalLane::Sync, not_before);
+    vakya.v7_adhikarana.approval_lane = ApprovalLane::None;
+
+    let result = submit_vakya(
+        State(Arc::clone(&state)),
+        PeerIdentity::default(),
+        Json(SubmitVakyaRequest { vakya, signature: None, key_id: None }),
+    )
+    .await;
+
+    assert!(matches!(result, Err(GatewayError::InvalidVakya(_))));
+    assert!(ran_at.lock().unwrap().is_none());
+}
+
+#[tokio::test]
+async fn deferred_vakya_waits_for_not_before() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+    let ran_at = Arc::new(Mutex::new(None));
+    state.adapters.write().await.register(ClockAdapter { ran_at: Arc::clone(&ran_at) });
+
+    let not_before = chrono::Utc::now() + chrono::Duration::milliseconds(300);
+    let vakya = build_vakya(ApprovalLane::Sync, Timestamp(not_before));
+    let vakya_id = vakya.vakya_id.0.clone();
+
+    let response = submit_vakya(
+        State(Arc::clone(&state)),
+        PeerIdentity::default(),
+        Json(SubmitVakyaRequest { vakya, signature: None, key_id: None }),
+    )
+    .await
+    .expect("submit")
+    .0;
+
+    assert_eq!(response.status, "deferred");
+    assert_eq!(response.receipt.expect("receipt").reason_code, ReasonCode::Deferred);
+    assert!(ran_at.lock().unwrap().is_none());
+
+    tokio::time::sleep(Duration::from_millis(800)).await;
+
+    let ran_at = ran_at.lock().unwrap().expect("deferred execution ran");
+    assert!(ran_at >= not_before, "ran at {} before {}", ran_at, not_before);
+    let receipt = state.index_db.get_receipt(&vakya_id).await.expect("get").expect("receipt");
+    assert_eq!(receipt.reason_code, ReasonCode::Success);
+    assert!(receipt.signature.is_some());
+}
+
+#[tokio::test]
+async fn full_deferred_queue_refuses_submissions() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig {
+        max_deferred_executions: 1,
+        ..GatewayConfig::default()
+    }).await.expect("state"));
+    let later = chrono::Utc::now() + chrono::Duration::minutes(1);
+
+    assert_eq!(submit_deferred(&state, later).await.expect("first").status, "deferred");
+    let err = submit_deferred(&state, later).await.expect_err("queue is full");
+    assert!(matches!(err, GatewayError::Overloaded { .. }), "{:?}", err);
+}
+
+#[tokio::test]
+async fn deferred_execution_waits_for_an_execution_slot() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig {
+        max_concurrent_executions: 1,
+        ..GatewayConfig::default()
+    }).await.expect("state"));
+    let ran_at = Arc::new(Mutex::new(None));
+    state.adapters.write().await.register(ClockAdapter { ran_at: Arc::clone(&ran_at) });
+
+    let response = submit_deferred(&state, chrono::Utc::now() + chrono::Duration::milliseconds(200)).await.expect("submit");
+    assert_eq!(response.status, "deferred");
+
+    let busy = Arc::clone(&state.execution_slots).acquire_owned().await.expect("slot");
+    tokio::time::sleep(Duration::from_millis(600)).await;
+    assert!(ran_at.lock().unwrap().is_none(), "ran while every slot was busy");
+
+    drop(busy);
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert!(ran_at.lock().unwrap().is_some());
+}
+
+#[test]
+fn deferred_vakyas_resume_after_a_restart() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let config = GatewayConfig {
+        database_url: format!("sqlite:{}?mode=rwc", dir.path().join("gateway.db").display()),
+        ..GatewayConfig::default()
+    };
+
+    // Shutting the runtime down drops the waiting task, as a restart would.
+    // The delay leaves room for a slow start so the task cannot run first.
+    let first = tokio::runtime::Runtime::new().expect("runtime");
+    let (vakya_id, not_before) = first.block_on(async {
+        let state = Arc::new(AppState::new(config.clone()).await.expect("state"));
+        let not_before = chrono::Utc::now() + chrono::Duration::milliseconds(1500);
+        (submit_deferred(&state, not_before).await.expect("submit").vakya_id, not_before)
+    });
+    drop(first);
+
+    let second = tokio::runtime::Runtime::new().expect("runtime");
+    second.block_on(async {
+        let state = Arc::new(AppState::new(config).await.expect("restarted state"));
+        let ran_at = Arc::new(Mutex::new(None));
+        state.adapters.write().await.register(ClockAdapter { ran_at: Arc::clone(&ran_at) });
+
+        assert_eq!(resume_deferred_executions(&state).await.expect("resume"), 1);
+        let wait = (not_before - chrono::Utc::now()).to_std().unwrap_or_default();
+        tokio::time::sleep(wait + Duration::from_millis(500)).await;
+
+        let ran_at = ran_at.lock().unwrap().expect("resumed execution ran");
+        assert!(ran_at >= not_before);
+        let receipt = state.index_db.get_receipt(&vakya_id).await.expect("get").expect("receipt");
+        assert_eq!(receipt.reason_code, ReasonCode::Success);
+    });
+}
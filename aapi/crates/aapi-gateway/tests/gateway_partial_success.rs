@@ -0,0 +1,169 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use axum::extract::State;
+use axum::Json;
+
+use aapi_adapters::{
+    Adapter, AdapterResult, CapturedEffect, ExecutionContext, ExecutionResult, HealthStatus, ItemError,
+};
+use aapi_core::error::ReasonCode;
+use aapi_core::{
+    ActorType,
+    Adhikarana,
+    ApprovalLane,
+    CapabilityRef,
+    Karta,
+    Karma,
+    Kriya,
+    PrincipalId,
+    ResourceId,
+    Vakya,
+};
+
+use aapi_gateway::handlers::{submit_vakya, SubmitVakyaRequest, SubmitVakyaResponse};
+use aapi_gateway::state::{AppState, GatewayConfig};
+use aapi_gateway::tls::PeerIdentity;
+
+/// Pushes each of the body's `items`, failing the ones named `locked`
+struct SyncAdapter;
+
+#[async_trait]
+impl Adapter for SyncAdapter {
+    fn domain(&self) -> &str {
+        "sync"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn supported_actions(&self) -> Vec<&str> {
+        vec!["sync.push"]
+    }
+
+    async fn execute(&self, vakya: &Vakya, _context: &ExecutionContext) -> AdapterResult<ExecutionResult> {
+        let items: Vec<&str> = vakya.body["items"].as_array().unwrap().iter().filter_map(|i| i.as_str()).collect();
+        let (failed, pushed): (Vec<&str>, Vec<&str>) = items.into_iter().partition(|item| item.starts_with("locked"));
+        if pushed.is_empty() {
+            return Ok(ExecutionResult::failure("nothing was pushed", 0));
+        }
+        let item_errors = failed.into_iter().map(|item| ItemError::new(item, "item is locked")).collect();
+        Ok(ExecutionResult::partial(serde_json::json!({ "pushed": pushed }), item_errors, vec![], 0))
+    }
+
+    fn can_rollback(&self, _action: &str) -> bool {
+        false
+    }
+
+    async fn rollback(&self, _effect: &CapturedEffect) -> AdapterResult<()> {
+        Ok(())
+    }
+
+    async fn health_check(&self) -> AdapterResult<HealthStatus> {
+        Ok(HealthStatus::healthy())
+    }
+}
+
+fn build_push(items: &[&str]) -> Vakya {
+    let mut vakya = Vakya::builder()
+        .karta(Karta {
+            pid: PrincipalId::new("agent:sync"),
+            role: None,
+            realm: None,
+            key_id: None,
+            actor_type: ActorType::Agent,
+            delegation_chain: vec![],
+        })
+        .karma(Karma {
+            rid: ResourceId::new("sync:remote"),
+            kind: Some("sync".to_string()),
+            ns: None,
+            version: None,
+            labels: std::collections::HashMap::new(),
+        })
+        .kriya(Kriya::new("sync", "push"))
+        .adhikarana(Adhikarana {
+            cap: CapabilityRef::Reference {
+                cap_ref: "cap:test:123".to_string(),
+            },
+            policy_ref: None,
+            ttl: None,
+            budgets: vec![],
+            approval_lane: ApprovalLane::None,
+            scopes: vec![],
+            context: None,
+            delegation_chain_cid: None,
+            execution_constraints: None,
+            port_id: None,
+            required_phase: None,
+            required_role: None,
+        })
+        .build()
+        .expect("vakya build");
+    vakya.body = serde_json::json!({ "items": items });
+    vakya
+}
+
+async fn gateway(config: GatewayConfig) -> Arc<AppState> {
+    let state = Arc::new(AppState::in_memory(config).await.expect("state"));
+    state.adapters.write().await.register(SyncAdapter);
+    state
+}
+
+async fn submit(state: &Arc<AppState>, vakya: Vakya) -> (SubmitVakyaResponse, serde_json::Value) {
+    let response = submit_vakya(
+        State(Arc::clone(state)),
+        PeerIdentity::default(),
+        Json(SubmitVakyaRequest { vakya, signature: None, key_id: None }),
+    )
+    .await
+    .expect("submit")
+    .0;
+    let receipt = state.index_db.get_receipt(&response.vakya_id).await.expect("lookup").expect("receipt");
+    (response, receipt.receipt_json)
+}
+
+#[tokio::test]
+async fn partial_results_are_distinct_from_success_and_failure() {
+    let state = gateway(GatewayConfig::default()).await;
+
+    let (response, receipt) = submit(&state, build_push(&["a", "b"])).await;
+    assert_eq!(response.status, "accepted");
+    assert_eq!(response.receipt.expect("receipt").reason_code, ReasonCode::Success);
+    assert_eq!(receipt["status"], "success");
+    assert!(receipt.get("item_errors").is_none());
+
+    let (response, receipt) = submit(&state, build_push(&["a", "locked-1", "b", "locked-2"])).await;
+    assert_eq!(response.status, "partial");
+    let receipt_response = response.receipt.expect("receipt");
+    assert_eq!(receipt_response.reason_code, ReasonCode::PartialSuccess);
+    assert_eq!(receipt_response.message.as_deref(), Some("2 item(s) failed"));
+    assert_eq!(receipt["status"], "partial");
+    assert_eq!(receipt["result"]["pushed"], serde_json::json!(["a", "b"]));
+    assert_eq!(
+        receipt["item_errors"],
+        serde_json::json!([
+            { "item": "locked-1", "error": "item is locked" },
+            { "item": "locked-2", "error": "item is locked" },
+        ])
+    );
+
+    let (response, receipt) = submit(&state, build_push(&["locked-1"])).await;
+    assert_eq!(response.status, "failed");
+    assert_eq!(response.receipt.expect("receipt").reason_code, ReasonCode::AdapterError);
+    assert_eq!(receipt["status"], "failed");
+}
+
+#[tokio::test]
+async fn deduplicated_partial_submissions_stay_partial() {
+    let state = gateway(GatewayConfig { dedup_window_secs: Some(60), ..GatewayConfig::default() }).await;
+
+    let (first, _) = submit(&state, build_push(&["a", "locked-1"])).await;
+    assert_eq!(first.status, "partial");
+
+    let (retry, _) = submit(&state, build_push(&["a", "locked-1"])).await;
+    assert!(retry.deduplicated);
+    assert_eq!(retry.vakya_id, first.vakya_id);
+    assert_eq!(retry.status, "partial");
+}
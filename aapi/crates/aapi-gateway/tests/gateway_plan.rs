@@ -0,0 +1,138 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use axum::extract::State;
+use axum::Json;
+
+use aapi_adapters::{
+    Adapter, AdapterResult, CapturedEffect, ExecutionContext, ExecutionResult, HealthStatus,
+};
+use aapi_core::types::EffectBucket;
+use aapi_core::Vakya;
+use aapi_metarules::{templates, DecisionType, Policy};
+
+use aapi_gateway::handlers::{plan_vakya, SubmitVakyaRequest, VakyaPlanResponse};
+use aapi_gateway::state::{AppState, GatewayConfig};
+use aapi_gateway::tls::PeerIdentity;
+
+mod common;
+
+/// Sends notifications, which cannot be recalled
+struct NotifyAdapter;
+
+#[async_trait]
+impl Adapter for NotifyAdapter {
+    fn domain(&self) -> &str {
+        "notify"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn supported_actions(&self) -> Vec<&str> {
+        vec!["notify.send"]
+    }
+
+    async fn execute(&self, _vakya: &Vakya, context: &ExecutionContext) -> AdapterResult<ExecutionResult> {
+        assert!(context.dry_run, "planning must not send");
+        Ok(ExecutionResult::success(serde_json::json!({ "dry_run": true, "would_send": 1 }), vec![], 0))
+    }
+
+    fn can_rollback(&self, _action: &str) -> bool {
+        false
+    }
+
+    async fn rollback(&self, _effect: &CapturedEffect) -> AdapterResult<()> {
+        Ok(())
+    }
+
+    async fn health_check(&self) -> AdapterResult<HealthStatus> {
+        Ok(HealthStatus::healthy())
+    }
+}
+
+fn build_vakya(action: &str, rid: &str) -> Vakya {
+    common::build_vakya("agent:planner", action, rid)
+}
+
+async fn plan(state: &Arc<AppState>, vakya: Vakya) -> VakyaPlanResponse {
+    plan_vakya(
+        State(Arc::clone(state)),
+        PeerIdentity::default(),
+        Json(SubmitVakyaRequest { vakya, signature: None, key_id: None }),
+    )
+    .await
+    .expect("plan")
+    .0
+}
+
+#[tokio::test]
+async fn planning_a_delete_reports_reversibility_without_side_effects() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+
+    tokio::fs::create_dir_all("/tmp/aapi").await.expect("mkdir");
+    let path = format!("/tmp/aapi/plan-{}.txt", uuid::Uuid::new_v4());
+    tokio::fs::write(&path, "keep me").await.expect("write");
+
+    let vakya = build_vakya("file.delete", &format!("file:{}", path));
+    let vakya_id = vakya.vakya_id.0.clone();
+    let plan = plan(&state, vakya).await;
+
+    assert_eq!(plan.decision, DecisionType::Allow);
+    assert_eq!(plan.adapter.as_ref().expect("adapter").domain, "file");
+    assert_eq!(plan.effects.len(), 1);
+    assert_eq!(plan.effects[0].bucket, EffectBucket::Delete);
+    assert!(plan.reversible);
+    assert_eq!(plan.dry_run.expect("dry run")["would_delete"], path.as_str());
+
+    // Nothing was executed or recorded
+    assert_eq!(tokio::fs::read_to_string(&path).await.expect("still there"), "keep me");
+    assert!(state.index_db.get_vakya(&vakya_id).await.expect("get").is_none());
+    assert!(state.index_db.get_receipt(&vakya_id).await.expect("get").is_none());
+}
+
+#[tokio::test]
+async fn irreversible_and_denied_actions_are_flagged() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+    state.adapters.write().await.register(NotifyAdapter);
+
+    let mut vakya = build_vakya("notify.send", "notify:ops");
+    vakya.v3_kriya.expected_effect = EffectBucket::External;
+    let notify = plan(&state, vakya).await;
+    assert_eq!(notify.effects[0].bucket, EffectBucket::External);
+    assert!(!notify.effects[0].reversible);
+    assert!(!notify.reversible);
+    assert_eq!(notify.dry_run.expect("dry run")["would_send"], 1);
+
+    let denied = plan(&state, build_vakya("file.delete", "file:/etc/hosts")).await;
+    assert_eq!(denied.decision, DecisionType::Deny);
+    assert!(denied.dry_run.is_none());
+    assert!(denied.matched_rules.iter().any(|r| r.rule_id == "rule:deny-delete-outside-sandbox"));
+}
+
+#[tokio::test]
+async fn actions_awaiting_approval_are_not_dry_run() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+    state.adapters.write().await.register(NotifyAdapter);
+    state
+        .policy_engine
+        .add_policy(
+            Policy::new("policy:irreversible", "Irreversible Actions")
+                .with_priority(200)
+                .with_rule(templates::require_approval_for_irreversible()),
+        )
+        .await
+        .expect("policy");
+
+    let mut vakya = build_vakya("notify.send", "notify:ops");
+    vakya.v3_kriya.expected_effect = EffectBucket::External;
+    let pending = plan(&state, vakya).await;
+
+    assert_eq!(pending.decision, DecisionType::PendingApproval);
+    assert!(!pending.required_approvals.is_empty());
+    assert!(!pending.reversible);
+    // The adapter was never called
+    assert!(pending.dry_run.is_none());
+    assert!(pending.dry_run_error.is_none());
+}
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use axum::extract::State;
+use axum::extract::{Path, State};
 use axum::Json;
 
 use aapi_core::{
@@ -16,8 +16,20 @@ use aapi_core::{
     Vakya,
 };
 
-use aapi_gateway::handlers::{submit_vakya, SubmitVakyaRequest};
+use aapi_core::error::ReasonCode;
+use aapi_gateway::error::GatewayError;
+use aapi_gateway::handlers::{
+    get_approval, submit_vakya, vote_approval, ApprovalVoteRequest, SubmitVakyaRequest,
+};
+use aapi_indexdb::{ApprovalRecordStatus, VoteDecision};
+use aapi_metarules::{
+    ApprovalConfig, ApprovalType, Condition, ConditionType, DecisionType, EvaluationContext, Operator, Policy, Rule,
+    templates,
+};
 use aapi_gateway::state::{AppState, GatewayConfig};
+use aapi_gateway::identity::Caller;
+use aapi_gateway::namespace::CallerScope;
+use aapi_gateway::tls::PeerIdentity;
 
 fn test_adhikarana() -> Adhikarana {
     Adhikarana {
@@ -68,7 +80,7 @@ async fn deny_decision_blocks_execution_and_stores_no_effects() {
     let config = GatewayConfig::default();
     let state = Arc::new(AppState::in_memory(config).await.expect("state"));
 
-    let vakya = build_vakya("file.delete", "file:/tmp/aapi/should-deny.txt");
+    let vakya = build_vakya("file.delete", "file:/etc/hosts");
     let vakya_id = vakya.vakya_id.0.clone();
 
     let request = SubmitVakyaRequest {
@@ -77,7 +89,7 @@ async fn deny_decision_blocks_execution_and_stores_no_effects() {
         key_id: None,
     };
 
-    let response = submit_vakya(State(Arc::clone(&state)), Json(request))
+    let response = submit_vakya(State(Arc::clone(&state)), PeerIdentity::default(), Json(request))
         .await
         .expect("handler ok")
         .0;
@@ -105,6 +117,23 @@ async fn deny_decision_blocks_execution_and_stores_no_effects() {
     assert_eq!(stored_receipt.reason_code, aapi_core::error::ReasonCode::PolicyDenied);
 }
 
+#[tokio::test]
+async fn default_policy_denies_deletes_outside_sandbox_only() {
+    let state = AppState::in_memory(GatewayConfig::default()).await.expect("state");
+
+    let delete_of = |rid: &str| EvaluationContext::new(build_vakya("file.delete", rid));
+
+    let outside = state.policy_engine.evaluate(&delete_of("file:/etc/hosts")).await.expect("evaluate");
+    assert_eq!(outside.decision, DecisionType::Deny);
+    assert!(outside.matched_rules.iter().any(|r| r.rule_id == "rule:deny-delete-outside-sandbox"));
+
+    let sibling = state.policy_engine.evaluate(&delete_of("file:/tmp/aapi-other/x")).await.expect("evaluate");
+    assert_eq!(sibling.decision, DecisionType::Deny);
+
+    let inside = state.policy_engine.evaluate(&delete_of("file:/tmp/aapi/x")).await.expect("evaluate");
+    assert!(inside.allowed, "{}", inside.reason);
+}
+
 #[tokio::test]
 async fn pending_approval_blocks_execution_and_stores_no_effects() {
     let config = GatewayConfig::default();
@@ -119,7 +148,7 @@ async fn pending_approval_blocks_execution_and_stores_no_effects() {
         key_id: None,
     };
 
-    let response = submit_vakya(State(Arc::clone(&state)), Json(request))
+    let response = submit_vakya(State(Arc::clone(&state)), PeerIdentity::default(), Json(request))
         .await
         .expect("handler ok")
         .0;
@@ -150,3 +179,394 @@ async fn pending_approval_blocks_execution_and_stores_no_effects() {
         .expect("stored receipt");
     assert_eq!(stored_receipt.reason_code, aapi_core::error::ReasonCode::ApprovalRequired);
 }
+
+async fn submit_for_approval(state: &Arc<AppState>, vakya: Vakya) -> String {
+    let request = SubmitVakyaRequest {
+        vakya,
+        signature: None,
+        key_id: None,
+    };
+
+    let response = submit_vakya(State(Arc::clone(state)), PeerIdentity::default(), Json(request))
+        .await
+        .expect("handler ok")
+        .0;
+    assert_eq!(response.status, "pending_approval");
+
+    response
+        .policy_decision
+        .and_then(|d| d.approval_id)
+        .expect("approval_id")
+}
+
+fn vote(decision: VoteDecision) -> Json<ApprovalVoteRequest> {
+    Json(ApprovalVoteRequest {
+        decision,
+        comment: None,
+    })
+}
+
+/// Route `action` to approval by the listed principals
+async fn require_approvers(state: &Arc<AppState>, action: &str, approvers: &[&str]) {
+    state
+        .policy_engine
+        .add_policy(
+            Policy::new(format!("policy:approvers:{}", action), "Listed Approvers")
+                .with_priority(200)
+                .with_rule(
+                    Rule::require_approval(format!("rule:approvers:{}", action), "Listed approvers sign off")
+                        .with_condition(Condition {
+                            condition_type: ConditionType::Action,
+                            field: "action".to_string(),
+                            operator: Operator::Eq,
+                            value: serde_json::json!(action),
+                        })
+                        .with_approval_config(
+                            ApprovalConfig::new(ApprovalType::Human)
+                                .with_approvers(approvers.iter().map(|a| a.to_string()).collect())
+                                .with_timeout(3600),
+                        ),
+                ),
+        )
+        .await
+        .expect("policy");
+}
+
+#[tokio::test]
+async fn approval_is_persisted_and_deny_vote_rejects() {
+    let config = GatewayConfig::default();
+    let state = Arc::new(AppState::in_memory(config).await.expect("state"));
+    require_approvers(&state, "http.post", &["user:reviewer"]).await;
+
+    let vakya = build_vakya("http.post", "http:https://example.com/api");
+    let vakya_id = vakya.vakya_id.0.clone();
+    let approval_id = submit_for_approval(&state, vakya).await;
+
+    let stored = state
+        .index_db
+        .get_approval(&approval_id)
+        .await
+        .expect("approval query")
+        .expect("stored approval");
+    assert_eq!(stored.vakya_id, vakya_id);
+    assert_eq!(stored.status, ApprovalRecordStatus::Pending);
+
+    let response = vote_approval(
+        State(Arc::clone(&state)),
+        CallerScope::unrestricted(),
+        Path(approval_id.clone()),
+        Caller::authenticated("user:reviewer"),
+        vote(VoteDecision::Deny),
+    )
+    .await
+    .expect("vote ok")
+    .0;
+    assert_eq!(response.status, ApprovalRecordStatus::Rejected);
+    assert_eq!(response.receipt.expect("receipt").reason_code, ReasonCode::PolicyDenied);
+
+    let stored_receipt = state
+        .index_db
+        .get_receipt(&vakya_id)
+        .await
+        .expect("receipt query")
+        .expect("stored receipt");
+    assert_eq!(stored_receipt.reason_code, ReasonCode::PolicyDenied);
+
+    // Resolved approvals do not accept further votes
+    let err = vote_approval(
+        State(Arc::clone(&state)),
+        CallerScope::unrestricted(),
+        Path(approval_id),
+        Caller::authenticated("user:reviewer"),
+        vote(VoteDecision::Approve),
+    )
+    .await
+    .expect_err("vote on resolved approval");
+    assert!(matches!(err, GatewayError::Conflict(_)));
+}
+
+#[tokio::test]
+async fn votes_require_an_authenticated_listed_approver_other_than_the_submitter() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+    require_approvers(&state, "file.exists", &["agent:test", "user:alice"]).await;
+    let approval_id = submit_for_approval(&state, build_vakya("file.exists", "file:/tmp/aapi/self-approval.txt")).await;
+
+    let err = vote_approval(
+        State(Arc::clone(&state)),
+        CallerScope::unrestricted(),
+        Path(approval_id.clone()),
+        Caller::anonymous(),
+        vote(VoteDecision::Approve),
+    )
+    .await
+    .expect_err("anonymous vote");
+    assert!(matches!(err, GatewayError::AuthorizationDenied(_)));
+
+    // agent:test submitted the VĀKYA, so its listing does not let it approve
+    let err = vote_approval(
+        State(Arc::clone(&state)),
+        CallerScope::unrestricted(),
+        Path(approval_id.clone()),
+        Caller::authenticated("agent:test"),
+        vote(VoteDecision::Approve),
+    )
+    .await
+    .expect_err("self-approval");
+    assert!(matches!(err, GatewayError::AuthorizationDenied(_)));
+
+    let response = vote_approval(
+        State(Arc::clone(&state)),
+        CallerScope::unrestricted(),
+        Path(approval_id),
+        Caller::authenticated("user:alice"),
+        vote(VoteDecision::Approve),
+    )
+    .await
+    .expect("listed approver")
+    .0;
+    assert_eq!(response.status, ApprovalRecordStatus::Approved);
+    assert_eq!(response.votes[0].approver, "user:alice");
+}
+
+#[tokio::test]
+async fn approvals_without_listed_approvers_take_no_votes() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+    require_approvers(&state, "file.exists", &[]).await;
+    let approval_id = submit_for_approval(&state, build_vakya("file.exists", "file:/tmp/aapi/no-approvers.txt")).await;
+
+    let err = vote_approval(
+        State(Arc::clone(&state)),
+        CallerScope::unrestricted(),
+        Path(approval_id),
+        Caller::authenticated("user:anyone"),
+        vote(VoteDecision::Approve),
+    )
+    .await
+    .expect_err("nobody is listed");
+    assert!(matches!(err, GatewayError::AuthorizationDenied(_)));
+}
+
+#[tokio::test]
+async fn approval_quorum_executes_vakya_and_updates_receipt() {
+    let config = GatewayConfig::default();
+    let state = Arc::new(AppState::in_memory(config).await.expect("state"));
+
+    state
+        .policy_engine
+        .add_policy(
+            Policy::new("policy:exists-approval", "Approve Existence Checks")
+                .with_priority(200)
+                .with_rule(
+                    Rule::require_approval("rule:exists-approval", "Two approvers for file.exists")
+                        .with_condition(Condition {
+                            condition_type: ConditionType::Action,
+                            field: "action".to_string(),
+                            operator: Operator::Eq,
+                            value: serde_json::json!("file.exists"),
+                        })
+                        .with_approval_config(
+                            ApprovalConfig::new(ApprovalType::Human)
+                                .with_approvers(vec!["user:alice".to_string(), "user:bob".to_string()])
+                                .with_min_approvals(2)
+                                .with_timeout(3600),
+                        ),
+                ),
+        )
+        .await
+        .expect("policy");
+
+    let vakya = build_vakya("file.exists", "file:/tmp/aapi/approval-quorum.txt");
+    let vakya_id = vakya.vakya_id.0.clone();
+    let approval_id = submit_for_approval(&state, vakya).await;
+
+    let err = vote_approval(
+        State(Arc::clone(&state)),
+        CallerScope::unrestricted(),
+        Path(approval_id.clone()),
+        Caller::authenticated("user:mallory"),
+        vote(VoteDecision::Approve),
+    )
+    .await
+    .expect_err("unlisted approver");
+    assert!(matches!(err, GatewayError::AuthorizationDenied(_)));
+
+    let response = vote_approval(
+        State(Arc::clone(&state)),
+        CallerScope::unrestricted(),
+        Path(approval_id.clone()),
+        Caller::authenticated("user:alice"),
+        vote(VoteDecision::Approve),
+    )
+    .await
+    .expect("first vote")
+    .0;
+    assert_eq!(response.status, ApprovalRecordStatus::Pending);
+    assert_eq!(response.approvals_received, 1);
+    assert!(response.receipt.is_none());
+
+    let response = vote_approval(
+        State(Arc::clone(&state)),
+        CallerScope::unrestricted(),
+        Path(approval_id.clone()),
+        Caller::authenticated("user:bob"),
+        vote(VoteDecision::Approve),
+    )
+    .await
+    .expect("second vote")
+    .0;
+    assert_eq!(response.status, ApprovalRecordStatus::Approved);
+    assert_eq!(response.receipt.expect("receipt").reason_code, ReasonCode::Success);
+
+    let stored_receipt = state
+        .index_db
+        .get_receipt(&vakya_id)
+        .await
+        .expect("receipt query")
+        .expect("stored receipt");
+    assert_eq!(stored_receipt.reason_code, ReasonCode::Success);
+
+    let fetched = get_approval(State(Arc::clone(&state)), CallerScope::unrestricted(), Path(approval_id))
+        .await
+        .expect("get approval")
+        .0;
+    assert_eq!(fetched.status, ApprovalRecordStatus::Approved);
+    assert_eq!(fetched.votes.len(), 2);
+}
+
+#[tokio::test]
+async fn weighted_approval_resolves_on_total_weight() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+
+    state
+        .policy_engine
+        .add_policy(
+            Policy::new("policy:weighted-approval", "Weighted Sign-off")
+                .with_priority(200)
+                .with_rule(
+                    Rule::require_approval("rule:weighted-approval", "Manager or two peers for file.exists")
+                        .with_condition(Condition {
+                            condition_type: ConditionType::Action,
+                            field: "action".to_string(),
+                            operator: Operator::Eq,
+                            value: serde_json::json!("file.exists"),
+                        })
+                        .with_approval_config(
+                            ApprovalConfig::new(ApprovalType::MultiParty)
+                                .with_weighted_approvers(vec![
+                                    ("user:manager".to_string(), 2),
+                                    ("user:peer".to_string(), 1),
+                                ])
+                                .with_required_weight(3)
+                                .with_timeout(3600),
+                        ),
+                ),
+        )
+        .await
+        .expect("policy");
+
+    let approval_id = submit_for_approval(&state, build_vakya("file.exists", "file:/tmp/aapi/weighted.txt")).await;
+
+    let response = vote_approval(
+        State(Arc::clone(&state)),
+        CallerScope::unrestricted(),
+        Path(approval_id.clone()),
+        Caller::authenticated("user:manager"),
+        vote(VoteDecision::Approve),
+    )
+    .await
+    .expect("manager vote")
+    .0;
+    assert_eq!(response.status, ApprovalRecordStatus::Pending);
+    assert_eq!(response.approval_weight, Some(2));
+    assert_eq!(response.required_weight, Some(3));
+
+    let response = vote_approval(
+        State(Arc::clone(&state)),
+        CallerScope::unrestricted(),
+        Path(approval_id.clone()),
+        Caller::authenticated("user:peer"),
+        vote(VoteDecision::Approve),
+    )
+    .await
+    .expect("peer vote")
+    .0;
+    assert_eq!(response.status, ApprovalRecordStatus::Approved);
+    assert_eq!(response.approvals_received, 2);
+    assert_eq!(response.approval_weight, Some(3));
+    assert_eq!(response.receipt.expect("receipt").reason_code, ReasonCode::Success);
+}
+
+#[tokio::test]
+async fn regex_capture_annotates_receipt() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+
+    state
+        .policy_engine
+        .add_policy(
+            Policy::new("policy:object-ids", "Record Object IDs")
+                .with_priority(200)
+                .with_rule(
+                    Rule::allow("rule:object-id", "Capture the checked object")
+                        .with_condition(Condition::resource(
+                            Operator::RegexCapture,
+                            r"^file:/tmp/aapi/(?P<object_id>[^/]+)\.txt$",
+                        )),
+                ),
+        )
+        .await
+        .expect("policy");
+
+    let vakya = build_vakya("file.exists", "file:/tmp/aapi/invoice-42.txt");
+    let vakya_id = vakya.vakya_id.0.clone();
+    let response = submit_vakya(
+        State(Arc::clone(&state)),
+        PeerIdentity::default(),
+        Json(SubmitVakyaRequest { vakya, signature: None, key_id: None }),
+    )
+    .await
+    .expect("submit")
+    .0;
+    assert_eq!(response.status, "accepted");
+
+    let receipt = state.index_db.get_receipt(&vakya_id).await.expect("get").expect("receipt");
+    assert_eq!(receipt.receipt_json["annotations"]["object_id"], "invoice-42");
+    assert!(receipt.signature.is_some());
+}
+
+#[tokio::test]
+async fn irreversible_actions_require_approval() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+    state
+        .policy_engine
+        .add_policy(
+            Policy::new("policy:irreversible", "Irreversible Actions")
+                .with_priority(200)
+                .with_rule(templates::require_approval_for_irreversible()),
+        )
+        .await
+        .expect("policy");
+    tokio::fs::create_dir_all("/tmp/aapi").await.expect("mkdir");
+
+    let submit = |vakya: Vakya| {
+        let state = Arc::clone(&state);
+        async move {
+            submit_vakya(State(state), PeerIdentity::default(), Json(SubmitVakyaRequest { vakya, signature: None, key_id: None }))
+                .await
+                .expect("submit")
+                .0
+        }
+    };
+
+    // The file adapter can roll writes back
+    let mut write = build_vakya("file.write", &format!("file:/tmp/aapi/undo-{}.txt", uuid::Uuid::new_v4()));
+    write.body = serde_json::json!({ "content": "draft" });
+    assert_eq!(submit(write).await.status, "accepted");
+
+    // but not probes, so they are held for approval
+    let response = submit(build_vakya("file.exists", "file:/tmp/aapi/undo.txt")).await;
+    assert_eq!(response.status, "pending_approval");
+    assert_eq!(
+        response.policy_decision.expect("decision").matched_rules,
+        Some(vec!["Require Approval for Irreversible Actions".to_string()])
+    );
+}
@@ -0,0 +1,97 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+
+use aapi_gateway::error::{GatewayError, GatewayResult};
+use aapi_gateway::handlers::{reload_policies, PolicyReloadResponse};
+use aapi_gateway::identity::Caller;
+use aapi_gateway::namespace::CallerScope;
+use aapi_gateway::state::{AppState, GatewayConfig};
+use aapi_metarules::{Condition, Operator, Policy, Rule};
+
+const OPERATOR: &str = "ops:policy";
+
+fn deny_policy(id: &str, action: &str) -> Policy {
+    Policy::new(id, id)
+        .with_rule(
+            Rule::deny(format!("{}:rule", id), "Deny")
+                .with_condition(Condition::action(Operator::Eq, action)),
+        )
+        .with_default_allow()
+}
+
+async fn reload_as(state: &Arc<AppState>, caller: Caller) -> GatewayResult<Json<PolicyReloadResponse>> {
+    reload_policies(State(Arc::clone(state)), CallerScope::unrestricted(), caller).await
+}
+
+fn write_policies(dir: &std::path::Path, policies: &[Policy]) {
+    std::fs::write(dir.join("policies.json"), serde_json::to_vec(policies).unwrap()).unwrap();
+}
+
+#[tokio::test]
+async fn reload_swaps_policies_from_directory() {
+    let dir = tempfile::TempDir::new().unwrap();
+    write_policies(dir.path(), &[deny_policy("policy:a", "file.delete")]);
+
+    let config = GatewayConfig {
+        policy_dir: Some(dir.path().to_path_buf()),
+        policy_operators: vec![OPERATOR.to_string()],
+        ..GatewayConfig::default()
+    };
+    let state = Arc::new(AppState::in_memory(config).await.expect("state"));
+
+    // Directory policies replace the built-in defaults
+    assert!(state.policy_engine.get_policy("policy:a").await.is_some());
+    assert!(state.policy_engine.get_policy("policy:allow-sandbox").await.is_none());
+    assert!(state.policy_watcher.is_some());
+
+    write_policies(dir.path(), &[
+        deny_policy("policy:a", "file.delete"),
+        deny_policy("policy:b", "http.post"),
+    ]);
+    let before = state.policy_engine.version();
+    let response = reload_as(&state, Caller::authenticated(OPERATOR)).await.expect("reload").0;
+    assert_eq!(response.policies, 2);
+    assert!(response.version > before);
+    assert!(state.policy_engine.get_policy("policy:b").await.is_some());
+
+    // A broken file is rejected and the previous set stays active
+    std::fs::write(dir.path().join("policies.json"), "[{").unwrap();
+    let err = reload_as(&state, Caller::authenticated(OPERATOR)).await.unwrap_err();
+    assert!(matches!(err, GatewayError::Validation(_)));
+    assert!(state.policy_engine.get_policy("policy:b").await.is_some());
+}
+
+#[tokio::test]
+async fn reload_requires_policy_dir() {
+    let config = GatewayConfig { policy_operators: vec![OPERATOR.to_string()], ..GatewayConfig::default() };
+    let state = Arc::new(AppState::in_memory(config).await.expect("state"));
+    assert!(state.policy_watcher.is_none());
+
+    let err = reload_as(&state, Caller::authenticated(OPERATOR)).await.unwrap_err();
+    assert!(matches!(err, GatewayError::Validation(_)));
+}
+
+#[tokio::test]
+async fn only_operators_can_reload() {
+    let dir = tempfile::TempDir::new().unwrap();
+    write_policies(dir.path(), &[deny_policy("policy:a", "file.delete")]);
+    let config = GatewayConfig {
+        policy_dir: Some(dir.path().to_path_buf()),
+        policy_operators: vec![OPERATOR.to_string()],
+        ..GatewayConfig::default()
+    };
+    let state = Arc::new(AppState::in_memory(config).await.expect("state"));
+    let before = state.policy_engine.version();
+
+    let err = reload_as(&state, Caller::anonymous()).await.unwrap_err();
+    assert_eq!(err.into_response().status(), StatusCode::FORBIDDEN);
+    let err = reload_as(&state, Caller::authenticated("agent:mallory")).await.unwrap_err();
+    assert!(matches!(err, GatewayError::AuthorizationDenied(_)), "{:?}", err);
+    assert_eq!(state.policy_engine.version(), before);
+
+    assert!(reload_as(&state, Caller::authenticated(OPERATOR)).await.is_ok());
+}
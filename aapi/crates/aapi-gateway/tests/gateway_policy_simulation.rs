@@ -0,0 +1,102 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::Json;
+
+use aapi_core::error::ReasonCode;
+
+use aapi_gateway::error::GatewayError;
+use aapi_gateway::handlers::{
+    simulate_policy, submit_vakya, PolicySimulationRequest, SimulationQuery, SubmitVakyaRequest,
+};
+use aapi_gateway::namespace::CallerScope;
+use aapi_gateway::state::{AppState, GatewayConfig};
+use aapi_gateway::tls::PeerIdentity;
+use aapi_metarules::{DecisionType, Operator, Policy, Rule, Condition};
+
+mod common;
+use common::build_vakya;
+
+fn deny_reads_policy() -> Policy {
+    Policy::new("policy:deny-reads", "Deny Reads")
+        .with_rule(
+            Rule::deny("rule:deny-reads", "Deny file reads")
+                .with_condition(Condition::action(Operator::Eq, "file.read")),
+        )
+        .with_default_allow()
+}
+
+#[tokio::test]
+async fn simulation_replays_stored_traffic_without_touching_live_engine() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+
+    // Live policies deny this delete; the receipt records the denial
+    let vakya = build_vakya("agent:sim", "file.delete", "file:/etc/sim-delete.txt");
+    let submitted = submit_vakya(
+        State(Arc::clone(&state)),
+        PeerIdentity::default(),
+        Json(SubmitVakyaRequest { vakya, signature: None, key_id: None }),
+    )
+    .await
+    .expect("submit")
+    .0;
+    assert_eq!(submitted.status, "denied");
+
+    let live_policies = state.policy_engine.list_policies().await.len();
+
+    let response = simulate_policy(
+        State(Arc::clone(&state)),
+        CallerScope::unrestricted(),
+        Json(PolicySimulationRequest {
+            policies: vec![deny_reads_policy()],
+            default_allow: true,
+            vakyas: vec![build_vakya("agent:other", "file.read", "file:/tmp/aapi/report.txt")],
+            query: Some(SimulationQuery {
+                actor: Some("agent:sim".to_string()),
+                ..Default::default()
+            }),
+            explain: true,
+        }),
+    )
+    .await
+    .expect("simulate")
+    .0;
+
+    assert_eq!(response.evaluated, 2);
+    assert_eq!(response.denied, 1);
+    assert_eq!(response.allowed, 1);
+
+    let read = &response.results[0];
+    assert_eq!(read.decision, DecisionType::Deny);
+    assert_eq!(read.matched_rules[0].rule_id, "rule:deny-reads");
+    assert!(read.recorded_reason_code.is_none());
+    assert!(read.trace.as_ref().expect("trace").rules[0].matched);
+
+    let replayed = &response.results[1];
+    assert_eq!(replayed.action, "file.delete");
+    assert_eq!(replayed.decision, DecisionType::Allow);
+    assert_eq!(replayed.recorded_reason_code, Some(ReasonCode::PolicyDenied));
+
+    assert_eq!(state.policy_engine.list_policies().await.len(), live_policies);
+    assert!(state.policy_engine.get_policy("policy:deny-reads").await.is_none());
+}
+
+#[tokio::test]
+async fn simulation_requires_input() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+
+    let err = simulate_policy(
+        State(state),
+        CallerScope::unrestricted(),
+        Json(PolicySimulationRequest {
+            policies: vec![deny_reads_policy()],
+            default_allow: false,
+            vakyas: vec![],
+            query: None,
+            explain: false,
+        }),
+    )
+    .await
+    .expect_err("no input");
+    assert!(matches!(err, GatewayError::Validation(_)));
+}
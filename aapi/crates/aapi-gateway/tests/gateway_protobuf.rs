@@ -0,0 +1,94 @@
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::extract::{Request, State};
+use axum::http::header;
+use prost::Message;
+
+use aapi_core::proto::pb;
+use aapi_core::{
+    canonicalize,
+    Vakya,
+};
+
+use aapi_gateway::handlers::{submit_vakya_encoded, PROTOBUF_CONTENT_TYPE};
+use aapi_gateway::state::{AppState, GatewayConfig};
+use aapi_gateway::tls::PeerIdentity;
+
+mod common;
+
+fn build_vakya(rid: &str) -> Vakya {
+    common::build_vakya("agent:bulk", "file.exists", rid)
+}
+
+fn request(content_type: &str, body: Vec<u8>) -> Request {
+    Request::builder()
+        .method("POST")
+        .uri("/v1/vakya")
+        .header(header::CONTENT_TYPE, content_type)
+        .body(Body::from(body))
+        .expect("request")
+}
+
+#[tokio::test]
+async fn protobuf_and_json_submissions_hash_identically() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+
+    let vakya = build_vakya("file:/tmp/aapi/protobuf-ingest.txt");
+    let expected_hash = canonicalize(&vakya).expect("canonicalize").vakya_hash.value;
+
+    let message = pb::SubmitVakyaRequest {
+        vakya: Some(pb::Vakya::try_from(&vakya).expect("encode")),
+        signature: None,
+        key_id: None,
+    };
+    let response = submit_vakya_encoded(
+        State(Arc::clone(&state)),
+        PeerIdentity::default(),
+        request(PROTOBUF_CONTENT_TYPE, message.encode_to_vec()),
+    )
+    .await
+    .expect("protobuf submit")
+    .1
+    .0;
+    assert_eq!(response.status, "accepted");
+    assert_eq!(response.vakya_hash, expected_hash);
+
+    let stored = state
+        .index_db
+        .get_vakya(&vakya.vakya_id.0)
+        .await
+        .expect("vakya query")
+        .expect("stored vakya");
+    assert_eq!(stored.vakya_hash, expected_hash);
+
+    // The same VĀKYA content as JSON yields the same hash
+    let mut resubmitted = vakya.clone();
+    resubmitted.vakya_id = aapi_core::VakyaId::new();
+    let json_hash = canonicalize(&resubmitted).expect("canonicalize").vakya_hash.value;
+    let body = serde_json::to_vec(&serde_json::json!({ "vakya": resubmitted })).unwrap();
+    let response = submit_vakya_encoded(
+        State(Arc::clone(&state)),
+        PeerIdentity::default(),
+        request("application/json", body),
+    )
+    .await
+    .expect("json submit")
+    .1
+    .0;
+    assert_eq!(response.vakya_hash, json_hash);
+}
+
+#[tokio::test]
+async fn malformed_protobuf_is_rejected() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+
+    let rejection = submit_vakya_encoded(
+        State(state),
+        PeerIdentity::default(),
+        request("application/x-protobuf", vec![0xff, 0xff, 0xff]),
+    )
+    .await
+    .expect_err("malformed body");
+    assert_eq!(rejection.status(), axum::http::StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::{header, StatusCode};
+
+use aapi_gateway::middleware::RateLimiter;
+use aapi_gateway::routes::create_router;
+use aapi_gateway::state::{AppState, GatewayConfig, RateLimitConfig};
+
+/// Serve the gateway routes on a local port, returning the health URL
+async fn start_gateway(config: GatewayConfig) -> String {
+    let router = create_router(Arc::new(AppState::in_memory(config).await.expect("state")));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.expect("bind");
+    let addr = listener.local_addr().expect("addr");
+    tokio::spawn(async move {
+        axum::serve(listener, router.into_make_service_with_connect_info::<SocketAddr>()).await
+    });
+    format!("http://{}/health", addr)
+}
+
+fn keyed_config(max_requests: u32) -> GatewayConfig {
+    GatewayConfig {
+        rate_limit: Some(RateLimitConfig { max_requests, window_secs: 60 }),
+        api_key_principals: HashMap::from([
+            ("alice".to_string(), "user:alice".to_string()),
+            ("bob".to_string(), "user:bob".to_string()),
+        ]),
+        ..GatewayConfig::default()
+    }
+}
+
+async fn health(url: &str, api_key: Option<&str>) -> reqwest::Response {
+    let mut request = reqwest::Client::new().get(url);
+    if let Some(key) = api_key {
+        request = request.bearer_auth(key);
+    }
+    request.send().await.expect("request")
+}
+
+fn header_u64(response: &reqwest::Response, name: impl header::AsHeaderName) -> u64 {
+    response.headers().get(name).expect("header").to_str().unwrap().parse().unwrap()
+}
+
+#[tokio::test]
+async fn responses_report_rate_limit_and_429_says_when_to_retry() {
+    let url = start_gateway(keyed_config(2)).await;
+
+    let first = health(&url, Some("alice")).await;
+    assert_eq!(first.status(), StatusCode::OK);
+    assert_eq!(header_u64(&first, "x-ratelimit-limit"), 2);
+    assert_eq!(header_u64(&first, "x-ratelimit-remaining"), 1);
+    assert!((1..=60).contains(&header_u64(&first, "x-ratelimit-reset")));
+    assert!(first.headers().get(header::RETRY_AFTER).is_none());
+
+    let second = health(&url, Some("alice")).await;
+    assert_eq!(second.status(), StatusCode::OK);
+    assert_eq!(header_u64(&second, "x-ratelimit-remaining"), 0);
+
+    let denied = health(&url, Some("alice")).await;
+    assert_eq!(denied.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(header_u64(&denied, "x-ratelimit-remaining"), 0);
+    let retry_after = header_u64(&denied, header::RETRY_AFTER);
+    assert!((1..=60).contains(&retry_after));
+    assert_eq!(retry_after, header_u64(&denied, "x-ratelimit-reset"));
+
+    // Each API key has its own allowance
+    let other = health(&url, Some("bob")).await;
+    assert_eq!(other.status(), StatusCode::OK);
+    assert_eq!(header_u64(&other, "x-ratelimit-remaining"), 1);
+}
+
+#[tokio::test]
+async fn unconfigured_keys_share_the_client_address_allowance() {
+    let url = start_gateway(keyed_config(2)).await;
+
+    assert_eq!(health(&url, Some("made-up-1")).await.status(), StatusCode::OK);
+    assert_eq!(health(&url, None).await.status(), StatusCode::OK);
+    // A fresh invented key does not reset the allowance
+    let denied = health(&url, Some("made-up-2")).await;
+    assert_eq!(denied.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    // A configured key is still counted on its own
+    assert_eq!(health(&url, Some("alice")).await.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn cleanup_task_forgets_idle_callers() {
+    let limiter = RateLimiter::new(5, 1);
+    let cleanup = limiter.spawn_cleanup();
+
+    limiter.acquire("ip:127.0.0.1").await;
+    assert_eq!(limiter.len().await, 1);
+
+    tokio::time::sleep(Duration::from_millis(3500)).await;
+    assert!(limiter.is_empty().await);
+    cleanup.abort();
+}
+
+#[tokio::test]
+async fn no_rate_limit_headers_without_a_limit() {
+    let url = start_gateway(GatewayConfig::default()).await;
+
+    for _ in 0..5 {
+        let response = health(&url, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().get("x-ratelimit-limit").is_none());
+    }
+}
@@ -0,0 +1,92 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::Json;
+
+use aapi_core::Vakya;
+use aapi_crypto::{merkle_leaf_hash, verify_inclusion, verify_receipt};
+
+use aapi_gateway::handlers::{
+    get_inclusion_proof, get_merkle_root, get_public_key, get_receipt, submit_vakya,
+    InclusionProofQuery, MerkleRootQuery, SubmitVakyaRequest,
+};
+use aapi_gateway::namespace::CallerScope;
+use aapi_gateway::state::{AppState, GatewayConfig};
+use aapi_gateway::tls::PeerIdentity;
+
+mod common;
+
+fn build_vakya(action: &str, rid: &str) -> Vakya {
+    common::build_vakya("agent:auditor", action, rid)
+}
+
+#[tokio::test]
+async fn stored_receipts_are_signed_and_logged() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+
+    // One executed and one denied VĀKYA, so the tree has more than one leaf
+    let mut ids = Vec::new();
+    for (action, rid) in [
+        ("file.exists", "file:/tmp/aapi/receipt-verify.txt"),
+        ("file.delete", "file:/etc/receipt-verify"),
+    ] {
+        let vakya = build_vakya(action, rid);
+        ids.push(vakya.vakya_id.0.clone());
+        let _ = submit_vakya(
+            State(Arc::clone(&state)),
+            PeerIdentity::default(),
+            Json(SubmitVakyaRequest { vakya, signature: None, key_id: None }),
+        )
+        .await
+        .expect("submit");
+    }
+
+    let root = get_merkle_root(
+        State(Arc::clone(&state)),
+        Query(MerkleRootQuery { tree_type: "receipt".to_string() }),
+    )
+    .await
+    .expect("root")
+    .0
+    .root_hash
+    .expect("non-empty receipt tree");
+
+    for vakya_id in ids {
+        let receipt = get_receipt(State(Arc::clone(&state)), CallerScope::unrestricted(), Path(vakya_id))
+            .await
+            .expect("receipt")
+            .0;
+        let key_id = receipt.key_id.clone().expect("receipt key id");
+        let public_key = get_public_key(State(Arc::clone(&state)), Path(key_id))
+            .await
+            .expect("public key")
+            .0;
+
+        let mut value = serde_json::to_value(&receipt).unwrap();
+        assert!(verify_receipt(&public_key, &value).expect("signature present"));
+
+        value["message"] = serde_json::json!("forged");
+        assert!(!verify_receipt(&public_key, &value).unwrap());
+
+        let proof = get_inclusion_proof(
+            State(Arc::clone(&state)),
+            Query(InclusionProofQuery {
+                tree_type: "receipt".to_string(),
+                leaf_index: receipt.leaf_index.expect("leaf index"),
+            }),
+        )
+        .await
+        .expect("proof")
+        .0;
+
+        let leaf_hash = proof["leaf_hash"].as_str().unwrap();
+        assert_eq!(leaf_hash, merkle_leaf_hash(receipt.vakya_hash.as_bytes()));
+        let path: Vec<(String, bool)> = proof["proof_hashes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|node| (node["hash"].as_str().unwrap().to_string(), node["position"] == "right"))
+            .collect();
+        assert!(verify_inclusion(leaf_hash, &path, &root));
+    }
+}
@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use axum::body::to_bytes;
+use axum::extract::{Path, State};
+use axum::Json;
+
+use aapi_core::Vakya;
+use aapi_indexdb::CacheConfig;
+
+use aapi_gateway::handlers::{get_metrics, get_metrics_prometheus, get_vakya, submit_vakya, SubmitVakyaRequest};
+use aapi_gateway::namespace::CallerScope;
+use aapi_gateway::state::{AppState, GatewayConfig};
+use aapi_gateway::tls::PeerIdentity;
+
+mod common;
+
+fn build_vakya() -> Vakya {
+    common::build_vakya("agent:dashboard", "file.exists", "file:/tmp/aapi/cached")
+}
+
+#[tokio::test]
+async fn repeated_reads_are_served_from_the_cache() {
+    let config = GatewayConfig { record_cache: Some(CacheConfig::default()), ..GatewayConfig::default() };
+    let state = Arc::new(AppState::in_memory(config).await.expect("state"));
+
+    let vakya_id = submit_vakya(
+        State(Arc::clone(&state)),
+        PeerIdentity::default(),
+        Json(SubmitVakyaRequest { vakya: build_vakya(), signature: None, key_id: None }),
+    )
+    .await
+    .expect("submit")
+    .0
+    .vakya_id;
+
+    for _ in 0..3 {
+        let record = get_vakya(State(Arc::clone(&state)), CallerScope::unrestricted(), Path(vakya_id.clone()))
+            .await
+            .expect("get")
+            .0;
+        assert_eq!(record.vakya_id, vakya_id);
+    }
+
+    let stats = get_metrics(State(Arc::clone(&state))).await.0.record_cache.expect("cache stats");
+    assert_eq!(stats.misses, 1);
+    assert_eq!(stats.hits, 2);
+
+    let response = get_metrics_prometheus(State(state)).await;
+    let body = to_bytes(response.into_body(), usize::MAX).await.expect("body");
+    let body = String::from_utf8(body.to_vec()).expect("utf8");
+    assert!(body.contains("aapi_record_cache_hits_total 2"), "{}", body);
+}
+
+#[tokio::test]
+async fn cache_stats_are_absent_when_disabled() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+    assert!(state.record_cache.is_none());
+    assert!(get_metrics(State(state)).await.0.record_cache.is_none());
+}
@@ -0,0 +1,91 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::Json;
+
+use aapi_core::Vakya;
+
+use aapi_gateway::error::GatewayError;
+use aapi_gateway::handlers::{
+    get_effect_proof, get_receipt_proof, get_vakya_proof, submit_vakya, SubmitVakyaRequest,
+};
+use aapi_gateway::namespace::CallerScope;
+use aapi_gateway::state::{AppState, GatewayConfig};
+use aapi_gateway::tls::PeerIdentity;
+use aapi_indexdb::{ProofPosition, TreeType};
+
+mod common;
+
+fn build_vakya(rid: &str) -> Vakya {
+    let mut vakya = common::build_vakya("agent:prover", "file.write", rid);
+    vakya.body = serde_json::json!({ "content": "proof" });
+    vakya
+}
+
+async fn assert_proves(state: &AppState, tree_type: TreeType, proof: &aapi_indexdb::InclusionProof) {
+    let root = state.index_db.get_merkle_root(tree_type).await.expect("root").expect("non-empty tree");
+    let path: Vec<(String, bool)> = proof.proof_hashes.iter()
+        .map(|node| (node.hash.clone(), node.position == ProofPosition::Right))
+        .collect();
+    assert_eq!(proof.root_hash, root);
+    assert!(aapi_crypto::verify_inclusion(&proof.leaf_hash, &path, &root));
+}
+
+#[tokio::test]
+async fn proofs_resolve_leaf_index_from_record_ids() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+
+    tokio::fs::create_dir_all("/tmp/aapi").await.expect("mkdir");
+    let rid = format!("file:/tmp/aapi/proof-{}.txt", uuid::Uuid::new_v4());
+    let response = submit_vakya(
+        State(Arc::clone(&state)),
+        PeerIdentity::default(),
+        Json(SubmitVakyaRequest { vakya: build_vakya(&rid), signature: None, key_id: None }),
+    )
+    .await
+    .expect("submit")
+    .0;
+    assert_eq!(response.status, "accepted");
+    let vakya_id = response.vakya_id;
+
+    let proof = get_vakya_proof(State(Arc::clone(&state)), CallerScope::unrestricted(), Path(vakya_id.clone()))
+        .await
+        .expect("vakya proof")
+        .0;
+    assert_eq!(Some(proof.leaf_index), response.leaf_index);
+    assert_proves(&state, TreeType::Vakya, &proof).await;
+
+    let proof = get_receipt_proof(State(Arc::clone(&state)), CallerScope::unrestricted(), Path(vakya_id.clone()))
+        .await
+        .expect("receipt proof")
+        .0;
+    assert_proves(&state, TreeType::Receipt, &proof).await;
+
+    let effect = state.index_db.get_effects(&vakya_id).await.expect("effects").remove(0);
+    let proof = get_effect_proof(State(Arc::clone(&state)), CallerScope::unrestricted(), Path(effect.id.to_string()))
+        .await
+        .expect("effect proof")
+        .0;
+    assert_eq!(Some(proof.leaf_index), effect.leaf_index);
+    assert_proves(&state, TreeType::Effect, &proof).await;
+}
+
+#[tokio::test]
+async fn unknown_records_have_no_proof() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+
+    let missing = get_vakya_proof(State(Arc::clone(&state)), CallerScope::unrestricted(), Path("missing".to_string()))
+        .await
+        .unwrap_err();
+    assert!(matches!(missing, GatewayError::NotFound(_)));
+
+    let missing = get_receipt_proof(State(Arc::clone(&state)), CallerScope::unrestricted(), Path("missing".to_string()))
+        .await
+        .unwrap_err();
+    assert!(matches!(missing, GatewayError::NotFound(_)));
+
+    let missing = get_effect_proof(State(state), CallerScope::unrestricted(), Path("missing".to_string()))
+        .await
+        .unwrap_err();
+    assert!(matches!(missing, GatewayError::NotFound(_)));
+}
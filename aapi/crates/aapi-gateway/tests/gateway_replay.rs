@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::Json;
+
+use aapi_gateway::handlers::{replay_vakyas, submit_vakya, ReplayRequest, SimulationQuery, SubmitVakyaRequest};
+use aapi_gateway::namespace::CallerScope;
+use aapi_gateway::replay::{Divergence, ReplayStatus};
+use aapi_gateway::state::{AppState, GatewayConfig};
+use aapi_gateway::tls::PeerIdentity;
+
+mod common;
+use common::build_vakya;
+
+async fn replay_actor(state: &Arc<AppState>, actor: &str) -> aapi_gateway::replay::ReplayReport {
+    replay_vakyas(
+        State(Arc::clone(state)),
+        CallerScope::unrestricted(),
+        Json(ReplayRequest {
+            filter: SimulationQuery {
+                actor: Some(actor.to_string()),
+                ..Default::default()
+            },
+        }),
+    )
+    .await
+    .expect("replay")
+    .0
+}
+
+#[tokio::test]
+async fn replay_matches_until_resource_state_changes() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+
+    let path = format!("/tmp/aapi/replay-{}.txt", uuid::Uuid::new_v4());
+    tokio::fs::create_dir_all("/tmp/aapi").await.expect("mkdir");
+    tokio::fs::write(&path, "original").await.expect("write");
+
+    let actor = "agent:replay";
+    let read = build_vakya(actor, "file.read", &format!("file:{}", path));
+    let submitted = submit_vakya(
+        State(Arc::clone(&state)),
+        PeerIdentity::default(),
+        Json(SubmitVakyaRequest { vakya: read, signature: None, key_id: None }),
+    )
+    .await
+    .expect("submit")
+    .0;
+    assert_eq!(submitted.status, "accepted");
+
+    // Denied requests never reached an adapter and are not replayed
+    let delete = build_vakya(actor, "file.delete", "file:/etc/replay-denied.txt");
+    let denied = submit_vakya(
+        State(Arc::clone(&state)),
+        PeerIdentity::default(),
+        Json(SubmitVakyaRequest { vakya: delete, signature: None, key_id: None }),
+    )
+    .await
+    .expect("submit denied")
+    .0;
+    assert_eq!(denied.status, "denied");
+
+    let report = replay_actor(&state, actor).await;
+    assert_eq!(report.replayed, 1);
+    assert_eq!(report.matched, 1);
+    assert_eq!(report.skipped, 1);
+    assert!(report.results[0].effects_compared);
+
+    tokio::fs::write(&path, "changed").await.expect("rewrite");
+
+    let report = replay_actor(&state, actor).await;
+    assert_eq!(report.diverged, 1);
+    let outcome = &report.results[0];
+    assert_eq!(outcome.status, ReplayStatus::Diverged);
+    assert!(matches!(outcome.divergences[0], Divergence::AfterState { .. }));
+
+    tokio::fs::remove_file(&path).await.ok();
+}
@@ -0,0 +1,44 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::Json;
+
+use aapi_core::Vakya;
+
+use aapi_crypto::MasterKey;
+use aapi_gateway::handlers::{submit_vakya, SubmitVakyaRequest};
+use aapi_gateway::state::{AppState, GatewayConfig};
+use aapi_gateway::tls::PeerIdentity;
+
+mod common;
+
+fn build_vakya(rid: &str) -> Vakya {
+    let mut vakya = common::build_vakya("agent:records", "file.write", rid);
+    vakya.body = serde_json::json!({ "content": "dob=1970-01-01" });
+    vakya
+}
+
+#[tokio::test]
+async fn effect_state_is_encrypted_at_rest_and_read_back() {
+    let config = GatewayConfig { encrypt_state: Some(MasterKey::generate("kek-test")), ..GatewayConfig::default() };
+    let state = Arc::new(AppState::in_memory(config).await.expect("state"));
+
+    tokio::fs::create_dir_all("/tmp/aapi").await.expect("mkdir");
+    let rid = format!("file:/tmp/aapi/encrypted-{}.txt", uuid::Uuid::new_v4());
+    let response = submit_vakya(
+        State(Arc::clone(&state)),
+        PeerIdentity::default(),
+        Json(SubmitVakyaRequest { vakya: build_vakya(&rid), signature: None, key_id: None }),
+    )
+    .await
+    .expect("submit")
+    .0;
+    assert_eq!(response.status, "accepted");
+
+    let effects = state.index_db.get_effects(&response.vakya_id).await.expect("effects");
+    let effect = effects.first().expect("effect");
+    let after_state = effect.after_state.as_ref().expect("after state decrypted");
+    // The file adapter captures content as base64 of "dob=1970-01-01"
+    assert_eq!(after_state["_data"], "ZG9iPTE5NzAtMDEtMDE=");
+    assert!(effect.after_hash.is_some());
+}
@@ -0,0 +1,132 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::stream;
+use reqwest::header;
+
+use aapi_adapters::{
+    Adapter, AdapterResult, CapturedEffect, ExecutionContext, ExecutionEvent, ExecutionResult, ExecutionStream,
+    HealthStatus,
+};
+use aapi_core::{
+    PrincipalId,
+    Vakya,
+};
+
+use aapi_gateway::routes::create_router;
+use aapi_gateway::state::{AppState, GatewayConfig};
+
+mod common;
+
+/// Tails a log, reporting each line before finishing
+struct TailAdapter;
+
+#[async_trait]
+impl Adapter for TailAdapter {
+    fn domain(&self) -> &str {
+        "log"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn supported_actions(&self) -> Vec<&str> {
+        vec!["log.tail"]
+    }
+
+    async fn execute(&self, _vakya: &Vakya, _context: &ExecutionContext) -> AdapterResult<ExecutionResult> {
+        Ok(ExecutionResult::success(serde_json::json!({ "lines": 2 }), vec![], 0))
+    }
+
+    fn execute_streaming<'a>(&'a self, vakya: &'a Vakya, context: &'a ExecutionContext) -> ExecutionStream<'a> {
+        let lines = stream::iter(["starting", "ready"].map(|line| Ok(ExecutionEvent::progress(line))));
+        let done = stream::once(async move { self.execute(vakya, context).await.map(ExecutionEvent::Completed) });
+        Box::pin(futures::StreamExt::chain(lines, done))
+    }
+
+    fn can_rollback(&self, _action: &str) -> bool {
+        false
+    }
+
+    async fn rollback(&self, _effect: &CapturedEffect) -> AdapterResult<()> {
+        Ok(())
+    }
+
+    async fn health_check(&self) -> AdapterResult<HealthStatus> {
+        Ok(HealthStatus::healthy())
+    }
+}
+
+fn build_vakya(action: &str, rid: &str) -> Vakya {
+    common::build_vakya("agent:tail", action, rid)
+}
+
+/// Serve the gateway routes on a local port, returning the streaming URL
+async fn start_gateway(state: Arc<AppState>) -> String {
+    let router = create_router(state);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.expect("bind");
+    let addr = listener.local_addr().expect("addr");
+    tokio::spawn(async move { axum::serve(listener, router).await });
+    format!("http://{}/v1/vakya/stream", addr)
+}
+
+/// Submit a VĀKYA and collect the SSE events as (name, data) pairs
+async fn stream_events(url: &str, vakya: &Vakya) -> Vec<(String, serde_json::Value)> {
+    let body = serde_json::json!({ "vakya": vakya }).to_string();
+    let response = reqwest::Client::new()
+        .post(url)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(body)
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "text/event-stream");
+
+    let text = response.text().await.expect("body");
+    text.split("\n\n")
+        .filter_map(|block| {
+            let field = |name: &str| block.lines().find_map(|l| l.strip_prefix(name)).map(str::to_string);
+            Some((field("event: ")?, serde_json::from_str(&field("data: ")?).expect("json data")))
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn progress_is_streamed_before_the_result() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+    state.adapters.write().await.register(TailAdapter);
+    let url = start_gateway(Arc::clone(&state)).await;
+
+    let vakya = build_vakya("log.tail", "log:app");
+    let events = stream_events(&url, &vakya).await;
+
+    let names: Vec<&str> = events.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["progress", "progress", "result"]);
+    assert_eq!(events[0].1["message"], "starting");
+    assert_eq!(events[1].1["message"], "ready");
+    assert_eq!(events[2].1["status"], "accepted");
+
+    // The streamed execution is recorded like any other
+    let receipt = state.index_db.get_receipt(&vakya.vakya_id.0).await.expect("get").expect("receipt");
+    assert!(receipt.reason_code.is_success());
+}
+
+#[tokio::test]
+async fn adapters_without_streaming_report_only_the_result() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+    let url = start_gateway(state).await;
+
+    tokio::fs::create_dir_all("/tmp/aapi").await.expect("mkdir");
+    let path = format!("/tmp/aapi/stream-{}.txt", uuid::Uuid::new_v4());
+    let events = stream_events(&url, &build_vakya("file.exists", &format!("file:{}", path))).await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].0, "result");
+
+    // Refused submissions end the stream with an error event
+    let mut invalid = build_vakya("file.exists", &format!("file:{}", path));
+    invalid.v1_karta.pid = PrincipalId::new("");
+    let events = stream_events(&url, &invalid).await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].0, "error");
+}
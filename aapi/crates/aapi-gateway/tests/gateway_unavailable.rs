@@ -0,0 +1,77 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use axum::extract::State;
+use axum::Json;
+
+use aapi_adapters::{
+    Adapter, AdapterError, AdapterResult, CapturedEffect, ExecutionContext, ExecutionResult, HealthStatus,
+};
+use aapi_core::error::ReasonCode;
+use aapi_core::Vakya;
+
+use aapi_gateway::handlers::{get_metrics, submit_vakya, SubmitVakyaRequest};
+use aapi_gateway::state::{AppState, GatewayConfig};
+use aapi_gateway::tls::PeerIdentity;
+
+mod common;
+
+/// Target whose backend cannot be reached
+struct DownAdapter;
+
+#[async_trait]
+impl Adapter for DownAdapter {
+    fn domain(&self) -> &str {
+        "down"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn supported_actions(&self) -> Vec<&str> {
+        vec!["down.call"]
+    }
+
+    async fn execute(&self, _vakya: &Vakya, _context: &ExecutionContext) -> AdapterResult<ExecutionResult> {
+        Err(AdapterError::Unavailable("connection refused".to_string()))
+    }
+
+    fn can_rollback(&self, _action: &str) -> bool {
+        false
+    }
+
+    async fn rollback(&self, _effect: &CapturedEffect) -> AdapterResult<()> {
+        Ok(())
+    }
+
+    async fn health_check(&self) -> AdapterResult<HealthStatus> {
+        Ok(HealthStatus::unhealthy("connection refused"))
+    }
+}
+
+fn build_vakya() -> Vakya {
+    common::build_vakya("agent:caller", "down.call", "down:service")
+}
+
+#[tokio::test]
+async fn unreachable_target_is_reported_as_unavailable() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+    state.adapters.write().await.register(DownAdapter);
+
+    let response = submit_vakya(
+        State(Arc::clone(&state)),
+        PeerIdentity::default(),
+        Json(SubmitVakyaRequest { vakya: build_vakya(), signature: None, key_id: None }),
+    )
+    .await
+    .expect("submit")
+    .0;
+
+    assert_eq!(response.status, "failed");
+    assert_eq!(response.receipt.expect("receipt").reason_code, ReasonCode::Unavailable);
+
+    let metrics = get_metrics(State(Arc::clone(&state))).await.0;
+    assert_eq!(metrics.requests_by_reason.get("UNAVAILABLE"), Some(&1));
+    assert_eq!(metrics.requests_by_reason.get("ADAPTER_ERROR"), None);
+}
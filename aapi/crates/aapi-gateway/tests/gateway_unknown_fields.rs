@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::extract::{Request, State};
+use axum::http::{header, StatusCode};
+
+use aapi_core::Vakya;
+
+use aapi_gateway::handlers::submit_vakya_encoded;
+use aapi_gateway::state::{AppState, GatewayConfig};
+use aapi_gateway::tls::PeerIdentity;
+
+mod common;
+
+fn build_vakya(rid: &str) -> Vakya {
+    common::build_vakya("agent:strict", "file.exists", rid)
+}
+
+fn json_request(body: &serde_json::Value) -> Request {
+    Request::builder()
+        .method("POST")
+        .uri("/v1/vakya")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(serde_json::to_vec(body).unwrap()))
+        .expect("request")
+}
+
+/// A submission whose VĀKYA has one unknown top-level and one unknown slot field
+fn submission_with_unknown_fields() -> serde_json::Value {
+    let mut vakya = serde_json::to_value(build_vakya("file:/tmp/aapi/strict.txt")).unwrap();
+    vakya["priority"] = serde_json::json!("urgent");
+    vakya["v7_adhikarana"]["sudo"] = serde_json::json!(true);
+    serde_json::json!({ "vakya": vakya })
+}
+
+async fn error_message(response: axum::response::Response) -> String {
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.expect("body");
+    let json: serde_json::Value = serde_json::from_slice(&body).expect("json");
+    json.to_string()
+}
+
+#[tokio::test]
+async fn unknown_fields_are_ignored_by_default() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig::default()).await.expect("state"));
+
+    let response = submit_vakya_encoded(
+        State(state),
+        PeerIdentity::default(),
+        json_request(&submission_with_unknown_fields()),
+    )
+    .await
+    .expect("lenient submit")
+    .1
+    .0;
+    assert_eq!(response.status, "accepted");
+}
+
+#[tokio::test]
+async fn strict_mode_rejects_unknown_fields_by_path() {
+    let state = Arc::new(AppState::in_memory(GatewayConfig {
+        reject_unknown_fields: true,
+        ..GatewayConfig::default()
+    }).await.expect("state"));
+
+    let rejection = submit_vakya_encoded(
+        State(Arc::clone(&state)),
+        PeerIdentity::default(),
+        json_request(&submission_with_unknown_fields()),
+    )
+    .await
+    .expect_err("unknown fields");
+    assert_eq!(rejection.status(), StatusCode::BAD_REQUEST);
+    let message = error_message(rejection).await;
+    assert!(message.contains("SchemaViolation"), "{}", message);
+    assert!(message.contains("priority"), "{}", message);
+    assert!(message.contains("v7_adhikarana.sudo"), "{}", message);
+
+    // A VĀKYA with only known fields is still accepted
+    let clean = serde_json::json!({ "vakya": build_vakya("file:/tmp/aapi/strict-clean.txt") });
+    let response = submit_vakya_encoded(State(state), PeerIdentity::default(), json_request(&clean))
+        .await
+        .expect("strict submit")
+        .1
+        .0;
+    assert_eq!(response.status, "accepted");
+}
@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use reqwest::StatusCode;
+
+use aapi_core::{
+    CapabilityRef,
+    ResourceId,
+    Vakya,
+};
+
+use aapi_gateway::routes::create_router;
+use aapi_gateway::state::{AppState, GatewayConfig};
+
+mod common;
+
+fn build_vakya(actor: &str) -> Vakya {
+    common::build_vakya(actor, "file.exists", "file:/tmp/aapi/validation.txt")
+}
+
+/// Serve the gateway routes on a local port, returning the submission URL
+async fn start_gateway(config: GatewayConfig) -> String {
+    let router = create_router(Arc::new(AppState::in_memory(config).await.expect("state")));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.expect("bind");
+    let addr = listener.local_addr().expect("addr");
+    tokio::spawn(async move { axum::serve(listener, router).await });
+    format!("http://{}/v1/vakya", addr)
+}
+
+async fn submit(url: &str, vakya: &Vakya) -> (StatusCode, serde_json::Value) {
+    let response = reqwest::Client::new()
+        .post(url)
+        .json(&serde_json::json!({ "vakya": vakya }))
+        .send()
+        .await
+        .expect("request");
+    (response.status(), response.json().await.expect("json body"))
+}
+
+#[tokio::test]
+async fn every_validation_error_is_reported() {
+    let url = start_gateway(GatewayConfig::default()).await;
+
+    let mut vakya = build_vakya("agent:validation");
+    vakya.v2_karma.rid = ResourceId::new("");
+    vakya.v3_kriya.action = "exists".to_string();
+    vakya.v7_adhikarana.cap = CapabilityRef::Reference { cap_ref: String::new() };
+
+    let (status, body) = submit(&url, &vakya).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "VALIDATION_ERROR");
+
+    let errors = body["details"]["errors"].as_array().expect("errors");
+    let reported: Vec<(&str, &str)> = errors
+        .iter()
+        .map(|e| (e["path"].as_str().unwrap(), e["code"].as_str().unwrap()))
+        .collect();
+    assert_eq!(reported, [
+        ("v2_karma.rid", "MissingRequired"),
+        ("v3_kriya.action", "InvalidFormat"),
+        ("v7_adhikarana.cap.cap_ref", "MissingRequired"),
+    ]);
+    assert!(body["message"].as_str().unwrap().contains("v2_karma.rid: Resource ID is required"));
+}
+
+#[tokio::test]
+async fn warnings_are_returned_unless_validation_is_strict() {
+    // Principal IDs are expected in type:id form
+    let vakya = build_vakya("validation");
+
+    let url = start_gateway(GatewayConfig::default()).await;
+    let (status, body) = submit(&url, &vakya).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "accepted");
+    assert_eq!(body["warnings"][0]["path"], "v1_karta.pid");
+    assert_eq!(body["warnings"][0]["code"], "DeprecatedField");
+
+    // Clean submissions carry no warnings field
+    let (_, body) = submit(&url, &build_vakya("agent:validation")).await;
+    assert!(body.get("warnings").is_none());
+
+    let strict = start_gateway(GatewayConfig { strict_validation: true, ..GatewayConfig::default() }).await;
+    let (status, body) = submit(&strict, &vakya).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["details"]["errors"][0]["path"], "v1_karta.pid");
+    assert!(body["details"]["warnings"].as_array().unwrap().is_empty());
+}
@@ -0,0 +1,485 @@
+//! Read-through cache for IndexDB records
+//!
+//! `CachingStore` wraps any `IndexDbStore` and keeps recently read VĀKYA
+//! records, receipts and effect lists in bounded LRU caches. Records are
+//! append-only, so an entry only goes stale when this store writes for the
+//! same VĀKYA; those writes invalidate it. The TTL bounds staleness when
+//! another process writes to the same database.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWrite;
+
+use crate::error::IndexDbResult;
+use crate::merkle::SignedTreeHead;
+use crate::models::*;
+use crate::query::{AuditFilter, ExportFilter, Page, QueryResult, VakyaQuery};
+use crate::store::IndexDbStore;
+
+/// Size and freshness limits for each record cache
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheConfig {
+    /// Entries kept per record kind before the least recently used is evicted
+    pub capacity: usize,
+    /// How long an entry is served before it is read again
+    pub ttl: Duration,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 10_000,
+            ttl: Duration::from_secs(300),
+        }
+    }
+}
+
+impl CacheConfig {
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+}
+
+/// Cache counters, summed over all record kinds
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Entries dropped to stay within capacity
+    pub evictions: u64,
+    /// Entries currently cached
+    pub entries: u64,
+}
+
+impl CacheStats {
+    /// Fraction of lookups served from the cache
+    pub fn hit_ratio(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+struct CacheEntry<V> {
+    value: V,
+    inserted_at: Instant,
+    /// Key into `LruCache::recency`
+    last_used: u64,
+}
+
+/// LRU map with per-entry expiry
+struct LruCache<V> {
+    entries: HashMap<String, CacheEntry<V>>,
+    /// Use tick -> key, oldest first
+    recency: BTreeMap<u64, String>,
+    tick: u64,
+}
+
+impl<V: Clone> LruCache<V> {
+    fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            tick: 0,
+        }
+    }
+
+    fn get(&mut self, key: &str, ttl: Duration) -> Option<V> {
+        let entry = self.entries.get_mut(key)?;
+        if entry.inserted_at.elapsed() >= ttl {
+            self.remove(key);
+            return None;
+        }
+
+        self.tick += 1;
+        self.recency.remove(&entry.last_used);
+        self.recency.insert(self.tick, key.to_string());
+        entry.last_used = self.tick;
+        Some(entry.value.clone())
+    }
+
+    /// Insert `value`, returning how many entries were evicted
+    fn insert(&mut self, key: String, value: V, capacity: usize) -> u64 {
+        self.remove(&key);
+        if capacity == 0 {
+            return 0;
+        }
+
+        let mut evicted = 0;
+        while self.entries.len() >= capacity {
+            let Some((_, oldest)) = self.recency.pop_first() else { break };
+            self.entries.remove(&oldest);
+            evicted += 1;
+        }
+
+        self.tick += 1;
+        self.recency.insert(self.tick, key.clone());
+        self.entries.insert(key, CacheEntry {
+            value,
+            inserted_at: Instant::now(),
+            last_used: self.tick,
+        });
+        evicted
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.recency.remove(&entry.last_used);
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+/// `IndexDbStore` decorator caching `get_vakya`, `get_receipt` and
+/// `get_effects`; every other call goes straight to the inner store
+pub struct CachingStore {
+    inner: Arc<dyn IndexDbStore>,
+    config: CacheConfig,
+    vakyas: Mutex<LruCache<VakyaRecord>>,
+    receipts: Mutex<LruCache<ReceiptRecord>>,
+    effects: Mutex<LruCache<Vec<EffectRecord>>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+}
+
+impl CachingStore {
+    pub fn new(inner: Arc<dyn IndexDbStore>, config: CacheConfig) -> Self {
+        Self {
+            inner,
+            config,
+            vakyas: Mutex::new(LruCache::new()),
+            receipts: Mutex::new(LruCache::new()),
+            effects: Mutex::new(LruCache::new()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+        }
+    }
+
+    /// Current hit, miss and eviction counts
+    pub fn stats(&self) -> CacheStats {
+        let entries = self.vakyas.lock().unwrap().len()
+            + self.receipts.lock().unwrap().len()
+            + self.effects.lock().unwrap().len();
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+            entries: entries as u64,
+        }
+    }
+
+    fn lookup<V: Clone>(&self, cache: &Mutex<LruCache<V>>, key: &str) -> Option<V> {
+        let value = cache.lock().unwrap().get(key, self.config.ttl);
+        let counter = if value.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        value
+    }
+
+    fn remember<V: Clone>(&self, cache: &Mutex<LruCache<V>>, key: &str, value: V) {
+        let evicted = cache.lock().unwrap().insert(key.to_string(), value, self.config.capacity);
+        self.evictions.fetch_add(evicted, Ordering::Relaxed);
+    }
+}
+
+#[async_trait]
+impl IndexDbStore for CachingStore {
+    async fn store_vakya(&self, record: VakyaRecord) -> IndexDbResult<VakyaRecord> {
+        let vakya_id = record.vakya_id.clone();
+        let stored = self.inner.store_vakya(record).await;
+        self.vakyas.lock().unwrap().remove(&vakya_id);
+        stored
+    }
+
+    async fn get_vakya(&self, vakya_id: &str) -> IndexDbResult<Option<VakyaRecord>> {
+        if let Some(record) = self.lookup(&self.vakyas, vakya_id) {
+            return Ok(Some(record));
+        }
+        let record = self.inner.get_vakya(vakya_id).await?;
+        if let Some(ref record) = record {
+            self.remember(&self.vakyas, vakya_id, record.clone());
+        }
+        Ok(record)
+    }
+
+    async fn query_vakyas(&self, query: &VakyaQuery) -> IndexDbResult<Vec<VakyaRecord>> {
+        self.inner.query_vakyas(query).await
+    }
+
+    async fn store_effect(&self, record: EffectRecord) -> IndexDbResult<EffectRecord> {
+        let vakya_id = record.vakya_id.clone();
+        let stored = self.inner.store_effect(record).await;
+        self.effects.lock().unwrap().remove(&vakya_id);
+        stored
+    }
+
+    async fn store_effects(&self, records: Vec<EffectRecord>) -> IndexDbResult<Vec<EffectRecord>> {
+        let vakya_ids: Vec<String> = records.iter().map(|r| r.vakya_id.clone()).collect();
+        let stored = self.inner.store_effects(records).await;
+        let mut effects = self.effects.lock().unwrap();
+        for vakya_id in &vakya_ids {
+            effects.remove(vakya_id);
+        }
+        stored
+    }
+
+    async fn get_effects(&self, vakya_id: &str) -> IndexDbResult<Vec<EffectRecord>> {
+        if let Some(effects) = self.lookup(&self.effects, vakya_id) {
+            return Ok(effects);
+        }
+        let effects = self.inner.get_effects(vakya_id).await?;
+        self.remember(&self.effects, vakya_id, effects.clone());
+        Ok(effects)
+    }
+
+    async fn get_effect(&self, effect_id: &str) -> IndexDbResult<Option<EffectRecord>> {
+        self.inner.get_effect(effect_id).await
+    }
+
+    async fn store_receipt(&self, record: ReceiptRecord) -> IndexDbResult<ReceiptRecord> {
+        let vakya_id = record.vakya_id.clone();
+        let stored = self.inner.store_receipt(record).await;
+        self.receipts.lock().unwrap().remove(&vakya_id);
+        stored
+    }
+
+    async fn get_receipt(&self, vakya_id: &str) -> IndexDbResult<Option<ReceiptRecord>> {
+        if let Some(receipt) = self.lookup(&self.receipts, vakya_id) {
+            return Ok(Some(receipt));
+        }
+        let receipt = self.inner.get_receipt(vakya_id).await?;
+        if let Some(ref receipt) = receipt {
+            self.remember(&self.receipts, vakya_id, receipt.clone());
+        }
+        Ok(receipt)
+    }
+
+    async fn update_receipt(&self, record: ReceiptRecord) -> IndexDbResult<ReceiptRecord> {
+        let vakya_id = record.vakya_id.clone();
+        let updated = self.inner.update_receipt(record).await;
+        self.receipts.lock().unwrap().remove(&vakya_id);
+        updated
+    }
+
+    async fn receipts_with_reason(&self, reason_code: aapi_core::error::ReasonCode) -> IndexDbResult<Vec<ReceiptRecord>> {
+        self.inner.receipts_with_reason(reason_code).await
+    }
+
+    async fn get_actor_chain_head(&self, karta_pid: &str) -> IndexDbResult<Option<ActorChainHead>> {
+        self.inner.get_actor_chain_head(karta_pid).await
+    }
+
+    async fn verify_actor_chain(&self, karta_pid: &str) -> IndexDbResult<ActorChainHead> {
+        self.inner.verify_actor_chain(karta_pid).await
+    }
+
+    async fn store_approval(&self, record: ApprovalRecord) -> IndexDbResult<ApprovalRecord> {
+        self.inner.store_approval(record).await
+    }
+
+    async fn get_approval(&self, approval_id: &str) -> IndexDbResult<Option<ApprovalRecord>> {
+        self.inner.get_approval(approval_id).await
+    }
+
+    async fn update_approval(&self, record: ApprovalRecord) -> IndexDbResult<ApprovalRecord> {
+        self.inner.update_approval(record).await
+    }
+
+    async fn revoke_capability(&self, revocation: CapabilityRevocation) -> IndexDbResult<CapabilityRevocation> {
+        self.inner.revoke_capability(revocation).await
+    }
+
+    async fn get_capability_revocation(&self, token_id: &str) -> IndexDbResult<Option<CapabilityRevocation>> {
+        self.inner.get_capability_revocation(token_id).await
+    }
+
+    async fn list_capability_revocations(&self) -> IndexDbResult<Vec<CapabilityRevocation>> {
+        self.inner.list_capability_revocations().await
+    }
+
+    async fn record_capability_delegation(&self, token_id: &str, parent_token_id: &str) -> IndexDbResult<()> {
+        self.inner.record_capability_delegation(token_id, parent_token_id).await
+    }
+
+    async fn list_capability_delegations(&self) -> IndexDbResult<Vec<(String, String)>> {
+        self.inner.list_capability_delegations().await
+    }
+
+    async fn store_packet(&self, record: MemPacketRecord) -> IndexDbResult<MemPacketRecord> {
+        self.inner.store_packet(record).await
+    }
+
+    async fn get_packet(&self, packet_cid: &str) -> IndexDbResult<Option<MemPacketRecord>> {
+        self.inner.get_packet(packet_cid).await
+    }
+
+    async fn get_packets_by_pipeline(&self, pipeline_id: &str) -> IndexDbResult<Vec<MemPacketRecord>> {
+        self.inner.get_packets_by_pipeline(pipeline_id).await
+    }
+
+    async fn get_packets_by_subject(&self, subject_id: &str) -> IndexDbResult<Vec<MemPacketRecord>> {
+        self.inner.get_packets_by_subject(subject_id).await
+    }
+
+    async fn get_packets_by_type(&self, subject_id: &str, packet_type: &str) -> IndexDbResult<Vec<MemPacketRecord>> {
+        self.inner.get_packets_by_type(subject_id, packet_type).await
+    }
+
+    async fn store_session(&self, session: SessionRecord) -> IndexDbResult<SessionRecord> {
+        self.inner.store_session(session).await
+    }
+
+    async fn get_session(&self, session_id: &str) -> IndexDbResult<Option<SessionRecord>> {
+        self.inner.get_session(session_id).await
+    }
+
+    async fn get_active_sessions(&self, agent_id: &str) -> IndexDbResult<Vec<SessionRecord>> {
+        self.inner.get_active_sessions(agent_id).await
+    }
+
+    async fn store_action_record(&self, record: ActionRecordEntry) -> IndexDbResult<ActionRecordEntry> {
+        self.inner.store_action_record(record).await
+    }
+
+    async fn get_action_record(&self, record_id: &str) -> IndexDbResult<Option<ActionRecordEntry>> {
+        self.inner.get_action_record(record_id).await
+    }
+
+    async fn store_kernel_audit(&self, entry: KernelAuditRecord) -> IndexDbResult<KernelAuditRecord> {
+        self.inner.store_kernel_audit(entry).await
+    }
+
+    async fn get_kernel_audits_by_agent(&self, agent_pid: &str, limit: u32) -> IndexDbResult<Vec<KernelAuditRecord>> {
+        self.inner.get_kernel_audits_by_agent(agent_pid, limit).await
+    }
+
+    async fn store_audit_log(&self, entry: AuditLogEntry) -> IndexDbResult<()> {
+        self.inner.store_audit_log(entry).await
+    }
+
+    async fn get_audit_log(&self, filter: &AuditFilter, page: Page) -> IndexDbResult<QueryResult<AuditLogEntry>> {
+        self.inner.get_audit_log(filter, page).await
+    }
+
+    async fn get_merkle_root(&self, tree_type: TreeType) -> IndexDbResult<Option<String>> {
+        self.inner.get_merkle_root(tree_type).await
+    }
+
+    async fn store_merkle_checkpoint(&self, checkpoint: MerkleCheckpoint) -> IndexDbResult<()> {
+        self.inner.store_merkle_checkpoint(checkpoint).await
+    }
+
+    async fn get_inclusion_proof(&self, tree_type: TreeType, leaf_index: i64) -> IndexDbResult<Option<InclusionProof>> {
+        self.inner.get_inclusion_proof(tree_type, leaf_index).await
+    }
+
+    async fn get_inclusion_proofs(&self, tree_type: TreeType, leaf_indices: &[i64]) -> IndexDbResult<(SignedTreeHead, Vec<InclusionProof>)> {
+        self.inner.get_inclusion_proofs(tree_type, leaf_indices).await
+    }
+
+    async fn get_consistency_proof(&self, tree_type: TreeType, first_size: i64, second_size: i64) -> IndexDbResult<Option<ConsistencyProof>> {
+        self.inner.get_consistency_proof(tree_type, first_size, second_size).await
+    }
+
+    async fn export_jsonl(&self, filter: &ExportFilter, writer: &mut (dyn AsyncWrite + Unpin + Send)) -> IndexDbResult<u64> {
+        self.inner.export_jsonl(filter, writer).await
+    }
+
+    async fn maintenance(&self) -> IndexDbResult<MaintenanceReport> {
+        self.inner.maintenance().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aapi_core::error::ReasonCode;
+    use crate::store::SqliteIndexDb;
+
+    async fn store(config: CacheConfig) -> CachingStore {
+        let inner = SqliteIndexDb::new("sqlite::memory:").await.unwrap();
+        CachingStore::new(Arc::new(inner), config)
+    }
+
+    fn vakya(vakya_id: &str) -> VakyaRecord {
+        VakyaRecord::new(
+            vakya_id.to_string(),
+            format!("hash-{}", vakya_id),
+            "agent:test".to_string(),
+            "file:/tmp/a".to_string(),
+            "file.read".to_string(),
+            serde_json::json!({ "vakya_id": vakya_id }),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_reads_are_cached_and_writes_invalidate() {
+        let store = store(CacheConfig::default()).await;
+        store.store_vakya(vakya("v1")).await.unwrap();
+
+        assert!(store.get_vakya("v1").await.unwrap().is_some());
+        assert!(store.get_vakya("v1").await.unwrap().is_some());
+        assert_eq!(store.stats().hits, 1);
+        assert_eq!(store.stats().misses, 1);
+
+        // An empty effect list is cached, then dropped when an effect lands
+        assert!(store.get_effects("v1").await.unwrap().is_empty());
+        store.store_effect(EffectRecord::new("v1".to_string(), aapi_core::types::EffectBucket::Read, "file:/tmp/a".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(store.get_effects("v1").await.unwrap().len(), 1);
+
+        // A missing receipt is not cached
+        assert!(store.get_receipt("v1").await.unwrap().is_none());
+        let receipt = ReceiptRecord::new("v1".to_string(), "hash-v1".to_string(), ReasonCode::Success, "gw".to_string(), serde_json::json!({}));
+        store.store_receipt(receipt).await.unwrap();
+        let mut receipt = store.get_receipt("v1").await.unwrap().unwrap();
+        receipt.message = Some("amended".to_string());
+        store.update_receipt(receipt).await.unwrap();
+        assert_eq!(store.get_receipt("v1").await.unwrap().unwrap().message.as_deref(), Some("amended"));
+    }
+
+    #[tokio::test]
+    async fn test_capacity_and_ttl() {
+        let store = store(CacheConfig::default().with_capacity(2)).await;
+        for id in ["v1", "v2", "v3"] {
+            store.store_vakya(vakya(id)).await.unwrap();
+        }
+
+        store.get_vakya("v1").await.unwrap();
+        store.get_vakya("v2").await.unwrap();
+        store.get_vakya("v1").await.unwrap(); // v2 is now least recently used
+        store.get_vakya("v3").await.unwrap();
+        assert_eq!(store.stats().evictions, 1);
+        assert_eq!(store.stats().entries, 2);
+
+        let before = store.stats().hits;
+        store.get_vakya("v1").await.unwrap();
+        store.get_vakya("v2").await.unwrap();
+        assert_eq!(store.stats().hits, before + 1);
+
+        let store = self::store(CacheConfig::default().with_ttl(Duration::ZERO)).await;
+        store.store_vakya(vakya("v1")).await.unwrap();
+        store.get_vakya("v1").await.unwrap();
+        store.get_vakya("v1").await.unwrap();
+        assert_eq!(store.stats().hits, 0);
+    }
+}
@@ -14,6 +14,12 @@ pub enum IndexDbError {
     #[error("Duplicate record: {0}")]
     Duplicate(String),
 
+    #[error("Duplicate VĀKYA: {0}")]
+    DuplicateVakya(String),
+
+    #[error("Conflicting update: {0}")]
+    Conflict(String),
+
     #[error("Invalid record: {0}")]
     InvalidRecord(String),
 
@@ -32,8 +38,18 @@ pub enum IndexDbError {
     #[error("Integrity violation: {0}")]
     IntegrityViolation(String),
 
+    #[error("Integrity mismatch for {vakya_id}: stored hash {stored}, computed {computed}")]
+    IntegrityMismatch {
+        vakya_id: String,
+        stored: String,
+        computed: String,
+    },
+
     #[error("Connection error: {0}")]
     Connection(String),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
 }
 
 pub type IndexDbResult<T> = Result<T, IndexDbError>;
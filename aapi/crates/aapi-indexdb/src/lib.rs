@@ -5,15 +5,18 @@
 //! - Merkle tree indexing for transparency proofs
 //! - Query capabilities for audit and replay
 //! - Support for SQLite (embedded) and PostgreSQL (enterprise)
+//! - An optional LRU cache for hot record reads
 
 pub mod store;
 pub mod models;
 pub mod merkle;
 pub mod query;
 pub mod error;
+pub mod cache;
 
 pub use store::*;
 pub use models::*;
 pub use merkle::*;
 pub use query::*;
 pub use error::*;
+pub use cache::*;
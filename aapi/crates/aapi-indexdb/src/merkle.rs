@@ -1,15 +1,56 @@
 //! Merkle tree implementation for transparency logs
 
-use sha2::{Sha256, Digest};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
-/// In-memory Merkle tree for append-only logs
+use aapi_core::types::HashAlgorithm;
+use aapi_crypto::merkle::{consistency_proof_with, merkle_leaf_hash_with, merkle_node_hash_with, verify_consistency};
+
+use crate::error::IndexDbError;
+
+pub use aapi_crypto::merkle::SignedTreeHead;
+
+/// Append-only Merkle tree holding only its right edge in memory
+///
+/// Appends and the current root need just the frontier: at each level, the
+/// complete node still waiting for a right sibling. Completed nodes are
+/// kept until they have been persisted (see [`MerkleTree::unpersisted`]);
+/// proofs and past roots then read the few nodes they need back from
+/// storage, found with [`MerkleTree::proof_nodes`] and friends.
 #[derive(Debug, Clone)]
 pub struct MerkleTree {
-    /// Leaf hashes
-    leaves: Vec<String>,
-    /// Cached internal nodes: (level, index) -> hash
-    nodes: HashMap<(usize, usize), String>,
+    /// Number of leaves
+    size: usize,
+    /// `frontier[l]` is the node at level `l` and index `(size >> l) - 1`
+    /// when bit `l` of `size` is set
+    frontier: Vec<Option<String>>,
+    /// Hash of the most recent leaf
+    last_leaf: Option<String>,
+    /// Completed nodes not yet persisted, by level and index
+    unpersisted: BTreeMap<(usize, usize), String>,
+    /// Hash algorithm for leaves and internal nodes
+    algorithm: HashAlgorithm,
+}
+
+/// A stored tree node
+///
+/// Leaves are level 0; the node at `level` and `index` covers leaves
+/// `index * 2^level .. (index + 1) * 2^level`. Only complete nodes exist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleNode {
+    pub level: usize,
+    pub index: usize,
+    pub hash: String,
+}
+
+/// Node hashes read back from storage, by level and index
+pub type StoredNodes = HashMap<(usize, usize), String>;
+
+/// A tree state to return to with [`MerkleTree::rollback`]
+#[derive(Debug, Clone)]
+pub struct TreeMark {
+    size: usize,
+    frontier: Vec<Option<String>>,
+    last_leaf: Option<String>,
 }
 
 impl Default for MerkleTree {
@@ -19,63 +60,200 @@ impl Default for MerkleTree {
 }
 
 impl MerkleTree {
-    /// Create a new empty Merkle tree
+    /// Create a new empty SHA-256 Merkle tree
     pub fn new() -> Self {
+        Self::new_with(HashAlgorithm::Sha256)
+    }
+
+    /// Create a new empty Merkle tree using the given hash algorithm
+    pub fn new_with(algorithm: HashAlgorithm) -> Self {
         Self {
-            leaves: Vec::new(),
-            nodes: HashMap::new(),
+            size: 0,
+            frontier: Vec::new(),
+            last_leaf: None,
+            unpersisted: BTreeMap::new(),
+            algorithm,
         }
     }
 
+    /// Nodes [`resume`](Self::resume) needs for a tree of `size` leaves:
+    /// the frontier and the last leaf
+    pub fn frontier_nodes(size: usize) -> Vec<(usize, usize)> {
+        let mut nodes: Vec<(usize, usize)> = (0..height(size) + 1)
+            .filter(|level| size >> level & 1 == 1)
+            .map(|level| (level, (size >> level) - 1))
+            .collect();
+        // An odd-sized tree's last leaf is already on the frontier
+        if size > 0 && size % 2 == 0 {
+            nodes.push((0, size - 1));
+        }
+        nodes
+    }
+
+    /// Resume a persisted tree of `size` leaves from its
+    /// [`frontier_nodes`](Self::frontier_nodes)
+    ///
+    /// Further leaves can then be appended at O(log n) each; nothing else
+    /// of the tree is loaded.
+    pub fn resume(algorithm: HashAlgorithm, size: usize, stored: &StoredNodes) -> Result<Self, IndexDbError> {
+        let mut tree = Self::new_with(algorithm);
+        let fetch = |level: usize, index: usize| {
+            stored.get(&(level, index)).cloned().ok_or_else(|| {
+                IndexDbError::MerkleError(format!("Missing stored node {} at level {}", index, level))
+            })
+        };
+
+        for level in (0..height(size) + 1).filter(|level| size >> level & 1 == 1) {
+            if tree.frontier.len() <= level {
+                tree.frontier.resize(level + 1, None);
+            }
+            tree.frontier[level] = Some(fetch(level, (size >> level) - 1)?);
+        }
+        if size > 0 {
+            tree.last_leaf = Some(fetch(0, size - 1)?);
+        }
+        tree.size = size;
+        Ok(tree)
+    }
+
+    /// Completed nodes not yet persisted
+    pub fn unpersisted(&self) -> Vec<MerkleNode> {
+        self.unpersisted.iter()
+            .map(|(&(level, index), hash)| MerkleNode { level, index, hash: hash.clone() })
+            .collect()
+    }
+
+    /// Forget the nodes returned by [`unpersisted`](Self::unpersisted) once
+    /// they are stored
+    pub fn clear_unpersisted(&mut self) {
+        self.unpersisted.clear();
+    }
+
+    /// Hash algorithm used by this tree
+    pub fn algorithm(&self) -> HashAlgorithm {
+        self.algorithm
+    }
+
     /// Append a new leaf and return its index
     pub fn append(&mut self, data: &str) -> usize {
-        let leaf_hash = self.hash_leaf(data.as_bytes());
-        let index = self.leaves.len();
-        self.leaves.push(leaf_hash);
-        
-        // Invalidate cached nodes (simple approach - rebuild on demand)
-        self.nodes.clear();
-        
+        let leaf_hash = self.leaf_hash(data);
+        let index = self.size;
+        self.unpersisted.insert((0, index), leaf_hash.clone());
+        self.last_leaf = Some(leaf_hash.clone());
+
+        // Each completed pair finishes a node one level up, like a binary carry
+        let (mut level, mut i, mut node) = (0, index, leaf_hash);
+        while i % 2 == 1 {
+            let left = self.frontier[level].take().expect("left sibling is on the frontier");
+            node = self.hash_internal(&left, &node);
+            level += 1;
+            i /= 2;
+            self.unpersisted.insert((level, i), node.clone());
+        }
+        if self.frontier.len() <= level {
+            self.frontier.resize(level + 1, None);
+        }
+        self.frontier[level] = Some(node);
+        self.size += 1;
+
         index
     }
 
+    /// Remember the current state, to undo appends with [`rollback`](Self::rollback)
+    pub fn mark(&self) -> TreeMark {
+        TreeMark {
+            size: self.size,
+            frontier: self.frontier.clone(),
+            last_leaf: self.last_leaf.clone(),
+        }
+    }
+
+    /// Drop every leaf appended since `mark`
+    ///
+    /// Used to undo an append whose record could not be stored.
+    pub fn rollback(&mut self, mark: TreeMark) {
+        self.unpersisted.retain(|&(level, index), _| (index + 1) << level <= mark.size);
+        self.size = mark.size;
+        self.frontier = mark.frontier;
+        self.last_leaf = mark.last_leaf;
+    }
+
+    /// Leaf hash this tree computes for `data`
+    pub fn leaf_hash(&self, data: &str) -> String {
+        self.hash_leaf(data.as_bytes())
+    }
+
     /// Get the number of leaves
     pub fn size(&self) -> usize {
-        self.leaves.len()
+        self.size
     }
 
     /// Check if the tree is empty
     pub fn is_empty(&self) -> bool {
-        self.leaves.is_empty()
+        self.size == 0
     }
 
-    /// Get the root hash
+    /// Hash of the most recent leaf
+    pub fn last_leaf(&self) -> Option<&String> {
+        self.last_leaf.as_ref()
+    }
+
+    /// Get the root hash, labeled with the tree's algorithm
     pub fn root(&self) -> Option<String> {
-        if self.leaves.is_empty() {
-            return None;
-        }
-        
-        if self.leaves.len() == 1 {
-            return Some(self.leaves[0].clone());
+        // The frontier, smallest first, folds into the root
+        let parts: Vec<&str> = self.frontier.iter().rev().flatten().map(String::as_str).collect();
+        self.fold(&parts).map(|hash| self.algorithm.label(&hash))
+    }
+
+    /// Stored nodes [`root_at`](Self::root_at) needs for `size` leaves
+    pub fn root_nodes(&self, size: usize) -> Vec<(usize, usize)> {
+        if size == 0 || size > self.size {
+            return Vec::new();
         }
+        self.not_held(subtree_parts(height(size), 0, size))
+    }
 
-        Some(self.compute_root(&self.leaves))
+    /// Get the root hash the tree had when it contained `size` leaves
+    pub fn root_at(&self, size: usize, stored: &StoredNodes) -> Option<String> {
+        if size == 0 || size > self.size {
+            return None;
+        }
+        self.subtree_hash(height(size), 0, size, stored).map(|hash| self.algorithm.label(&hash))
     }
 
-    /// Get a leaf hash by index
-    pub fn get_leaf(&self, index: usize) -> Option<&String> {
-        self.leaves.get(index)
+    /// Stored nodes [`get_proof`](Self::get_proof) needs for `leaf_index`
+    pub fn proof_nodes(&self, leaf_index: usize) -> Vec<(usize, usize)> {
+        if leaf_index >= self.size {
+            return Vec::new();
+        }
+        let mut nodes = vec![(0, leaf_index)];
+        for level in 0..height(self.size) {
+            nodes.extend(subtree_parts(level, (leaf_index >> level) ^ 1, self.size));
+        }
+        self.not_held(nodes)
     }
 
-    /// Get an inclusion proof for a leaf
-    pub fn get_proof(&self, leaf_index: usize) -> Option<MerkleProof> {
-        if leaf_index >= self.leaves.len() {
+    /// Get an inclusion proof for a leaf against the current root
+    ///
+    /// Returns `None` if the leaf does not exist or `stored` lacks one of
+    /// its [`proof_nodes`](Self::proof_nodes).
+    pub fn get_proof(&self, leaf_index: usize, stored: &StoredNodes) -> Option<MerkleProof> {
+        if leaf_index >= self.size {
             return None;
         }
 
-        let leaf_hash = self.leaves[leaf_index].clone();
-        let path = self.compute_proof_path(leaf_index);
-        
+        let leaf_hash = self.lookup(0, leaf_index, stored)?.to_string();
+        let mut path = Vec::new();
+        for level in 0..height(self.size) {
+            let index = leaf_index >> level;
+            let start = (index ^ 1) << level;
+            if start >= self.size {
+                continue;
+            }
+            let sibling = self.subtree_hash(level, index ^ 1, self.size, stored)?;
+            path.push((sibling, index % 2 == 0));
+        }
+
         Some(MerkleProof {
             leaf_hash,
             leaf_index,
@@ -91,89 +269,84 @@ impl MerkleTree {
         };
 
         let computed_root = self.compute_root_from_proof(proof);
-        computed_root == root
+        self.algorithm.label(&computed_root) == root
+    }
+
+    /// Stored leaves [`get_consistency_proof`](Self::get_consistency_proof)
+    /// needs up to `second_size`
+    pub fn consistency_nodes(&self, second_size: usize) -> Vec<(usize, usize)> {
+        self.not_held((0..second_size.min(self.size)).map(|index| (0, index)))
+    }
+
+    /// Get an RFC 6962 consistency proof between two tree sizes
+    pub fn get_consistency_proof(&self, first_size: usize, second_size: usize, stored: &StoredNodes) -> Option<ConsistencyProof> {
+        if first_size > second_size || second_size > self.size {
+            return None;
+        }
+
+        let leaves = (0..second_size)
+            .map(|index| self.lookup(0, index, stored).map(str::to_string))
+            .collect::<Option<Vec<_>>>()?;
+        let proof_hashes = consistency_proof_with(self.algorithm, &leaves, first_size)?;
+
+        Some(ConsistencyProof {
+            first_size,
+            second_size,
+            proof_hashes,
+        })
     }
 
     /// Hash a leaf (with 0x00 prefix to distinguish from internal nodes)
     fn hash_leaf(&self, data: &[u8]) -> String {
-        let mut hasher = Sha256::new();
-        hasher.update(&[0x00]); // Leaf prefix
-        hasher.update(data);
-        hex::encode(hasher.finalize())
+        merkle_leaf_hash_with(self.algorithm, data)
     }
 
     /// Hash an internal node (with 0x01 prefix)
     fn hash_internal(&self, left: &str, right: &str) -> String {
-        let mut hasher = Sha256::new();
-        hasher.update(&[0x01]); // Internal node prefix
-        hasher.update(hex::decode(left).unwrap_or_default());
-        hasher.update(hex::decode(right).unwrap_or_default());
-        hex::encode(hasher.finalize())
-    }
-
-    /// Compute the root hash from leaves
-    fn compute_root(&self, leaves: &[String]) -> String {
-        if leaves.len() == 1 {
-            return leaves[0].clone();
-        }
-
-        let mut current_level: Vec<String> = leaves.to_vec();
-
-        while current_level.len() > 1 {
-            let mut next_level = Vec::new();
-            let mut i = 0;
-
-            while i < current_level.len() {
-                if i + 1 < current_level.len() {
-                    let hash = self.hash_internal(&current_level[i], &current_level[i + 1]);
-                    next_level.push(hash);
-                    i += 2;
-                } else {
-                    // Odd node: promote to next level
-                    next_level.push(current_level[i].clone());
-                    i += 1;
-                }
-            }
+        merkle_node_hash_with(self.algorithm, left, right)
+    }
 
-            current_level = next_level;
+    /// A complete node held in memory
+    fn held(&self, level: usize, index: usize) -> Option<&str> {
+        if let Some(hash) = self.unpersisted.get(&(level, index)) {
+            return Some(hash);
+        }
+        if level == 0 && index + 1 == self.size {
+            return self.last_leaf.as_deref();
+        }
+        match self.frontier.get(level) {
+            Some(Some(hash)) if (self.size >> level) - 1 == index => Some(hash),
+            _ => None,
         }
-
-        current_level[0].clone()
     }
 
-    /// Compute the proof path for a leaf
-    fn compute_proof_path(&self, leaf_index: usize) -> Vec<(String, bool)> {
-        let mut path = Vec::new();
-        let mut current_level = self.leaves.clone();
-        let mut index = leaf_index;
-
-        while current_level.len() > 1 {
-            let sibling_index = if index % 2 == 0 { index + 1 } else { index - 1 };
-            
-            if sibling_index < current_level.len() {
-                let is_right = index % 2 == 0;
-                path.push((current_level[sibling_index].clone(), is_right));
-            }
+    /// A complete node, from memory or from `stored`
+    fn lookup<'a>(&'a self, level: usize, index: usize, stored: &'a StoredNodes) -> Option<&'a str> {
+        self.held(level, index).or_else(|| stored.get(&(level, index)).map(String::as_str))
+    }
 
-            // Move to next level
-            let mut next_level = Vec::new();
-            let mut i = 0;
-            while i < current_level.len() {
-                if i + 1 < current_level.len() {
-                    let hash = self.hash_internal(&current_level[i], &current_level[i + 1]);
-                    next_level.push(hash);
-                    i += 2;
-                } else {
-                    next_level.push(current_level[i].clone());
-                    i += 1;
-                }
-            }
+    fn not_held(&self, nodes: impl IntoIterator<Item = (usize, usize)>) -> Vec<(usize, usize)> {
+        nodes.into_iter()
+            .filter(|&(level, index)| self.held(level, index).is_none())
+            .collect()
+    }
 
-            current_level = next_level;
-            index /= 2;
-        }
+    /// Hash of the subtree at `level`/`index` in a tree of `size` leaves
+    ///
+    /// Subtrees on the right edge may be incomplete; a node missing its
+    /// right child takes its left child's hash, as in the pairwise
+    /// construction, which folds the complete parts right to left.
+    fn subtree_hash(&self, level: usize, index: usize, size: usize, stored: &StoredNodes) -> Option<String> {
+        let parts = subtree_parts(level, index, size).into_iter()
+            .map(|(level, index)| self.lookup(level, index, stored))
+            .collect::<Option<Vec<_>>>()?;
+        self.fold(&parts)
+    }
 
-        path
+    /// Combine complete subtree hashes, largest first, into one
+    fn fold(&self, parts: &[&str]) -> Option<String> {
+        let (last, rest) = parts.split_last()?;
+        Some(rest.iter().rev().fold(last.to_string(), |acc, part| self.hash_internal(part, &acc)))
     }
 
     /// Compute root from a proof
@@ -190,32 +363,27 @@ impl MerkleTree {
 
         current
     }
+}
 
-    /// Get a consistency proof between two tree sizes
-    pub fn get_consistency_proof(&self, first_size: usize, second_size: usize) -> Option<ConsistencyProof> {
-        if first_size > second_size || second_size > self.leaves.len() {
-            return None;
-        }
-
-        if first_size == 0 {
-            return Some(ConsistencyProof {
-                first_size,
-                second_size,
-                proof_hashes: vec![],
-            });
+/// Complete nodes making up the subtree at `level`/`index` in a tree of
+/// `size` leaves, largest first; empty for subtrees entirely past `size`
+fn subtree_parts(level: usize, index: usize, size: usize) -> Vec<(usize, usize)> {
+    let start = index << level;
+    let end = size.min(start + (1 << level));
+    let mut parts = Vec::new();
+    let mut pos = start;
+    for part_level in (0..=level).rev() {
+        if pos + (1 << part_level) <= end {
+            parts.push((part_level, pos >> part_level));
+            pos += 1 << part_level;
         }
-
-        let first_root = self.compute_root(&self.leaves[..first_size]);
-        let second_root = self.compute_root(&self.leaves[..second_size]);
-
-        // Simplified consistency proof - just include the roots
-        // A full implementation would include the minimal set of nodes
-        Some(ConsistencyProof {
-            first_size,
-            second_size,
-            proof_hashes: vec![first_root, second_root],
-        })
     }
+    parts
+}
+
+/// Number of levels above the leaves in a tree of `size` leaves
+fn height(size: usize) -> usize {
+    (usize::BITS - size.saturating_sub(1).leading_zeros()) as usize
 }
 
 /// Merkle inclusion proof
@@ -231,8 +399,12 @@ pub struct MerkleProof {
 
 impl MerkleProof {
     /// Verify the proof against a known root
+    ///
+    /// The hash algorithm is taken from the root's label; unlabeled roots
+    /// are SHA-256.
     pub fn verify(&self, expected_root: &str) -> bool {
-        let tree = MerkleTree::new();
+        let (algorithm, expected_root) = HashAlgorithm::split_labeled(expected_root);
+        let tree = MerkleTree::new_with(algorithm);
         let computed = tree.compute_root_from_proof(self);
         computed == expected_root
     }
@@ -246,40 +418,16 @@ pub struct ConsistencyProof {
     pub proof_hashes: Vec<String>,
 }
 
-/// Signed tree head for checkpointing
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct SignedTreeHead {
-    /// Tree size (number of leaves)
-    pub tree_size: u64,
-    /// Root hash
-    pub root_hash: String,
-    /// Timestamp
-    pub timestamp: chrono::DateTime<chrono::Utc>,
-    /// Signature over (tree_size || root_hash || timestamp)
-    pub signature: Option<String>,
-    /// Key ID used for signing
-    pub key_id: Option<String>,
-}
-
-impl SignedTreeHead {
-    /// Create a new unsigned tree head
-    pub fn new(tree_size: u64, root_hash: String) -> Self {
-        Self {
-            tree_size,
-            root_hash,
-            timestamp: chrono::Utc::now(),
-            signature: None,
-            key_id: None,
-        }
-    }
-
-    /// Get the bytes to sign
-    pub fn signing_bytes(&self) -> Vec<u8> {
-        let mut bytes = Vec::new();
-        bytes.extend_from_slice(&self.tree_size.to_be_bytes());
-        bytes.extend_from_slice(self.root_hash.as_bytes());
-        bytes.extend_from_slice(&self.timestamp.timestamp().to_be_bytes());
-        bytes
+impl ConsistencyProof {
+    /// Verify the proof against the roots of the two tree states
+    pub fn verify(&self, first_root: &str, second_root: &str) -> bool {
+        verify_consistency(
+            self.first_size as u64,
+            self.second_size as u64,
+            first_root,
+            second_root,
+            &self.proof_hashes,
+        )
     }
 }
 
@@ -330,6 +478,32 @@ mod tests {
         assert_ne!(root1, root2);
     }
 
+    #[test]
+    fn test_rollback_undoes_appends() {
+        let mut tree = MerkleTree::new();
+        for i in 0..5 {
+            tree.append(&format!("leaf{}", i));
+        }
+        let root = tree.root();
+        let mark = tree.mark();
+
+        for i in 5..9 {
+            tree.append(&format!("other{}", i));
+        }
+        tree.rollback(mark);
+        assert_eq!(tree.size(), 5);
+        assert_eq!(tree.root(), root);
+
+        // Appending after a rollback matches a tree that never diverged
+        let mut fresh = MerkleTree::new();
+        for i in 0..6 {
+            fresh.append(&format!("leaf{}", i));
+        }
+        tree.append("leaf5");
+        assert_eq!(tree.root(), fresh.root());
+        assert_eq!(tree.unpersisted(), fresh.unpersisted());
+    }
+
     #[test]
     fn test_inclusion_proof() {
         let mut tree = MerkleTree::new();
@@ -338,7 +512,7 @@ mod tests {
         tree.append("leaf2");
         tree.append("leaf3");
         
-        let proof = tree.get_proof(1).unwrap();
+        let proof = tree.get_proof(1, &StoredNodes::new()).unwrap();
         assert!(tree.verify_proof(&proof));
     }
 
@@ -350,7 +524,7 @@ mod tests {
         }
         
         for i in 0..8 {
-            let proof = tree.get_proof(i).unwrap();
+            let proof = tree.get_proof(i, &StoredNodes::new()).unwrap();
             assert!(tree.verify_proof(&proof), "Proof failed for leaf {}", i);
         }
     }
@@ -361,7 +535,7 @@ mod tests {
         tree.append("leaf0");
         tree.append("leaf1");
         
-        let mut proof = tree.get_proof(0).unwrap();
+        let mut proof = tree.get_proof(0, &StoredNodes::new()).unwrap();
         proof.leaf_hash = "tampered".to_string();
         
         assert!(!tree.verify_proof(&proof));
@@ -373,7 +547,7 @@ mod tests {
         tree.append("leaf0");
         tree.append("leaf1");
         
-        let proof = tree.get_consistency_proof(1, 2);
+        let proof = tree.get_consistency_proof(1, 2, &StoredNodes::new());
         assert!(proof.is_some());
         
         let proof = proof.unwrap();
@@ -381,6 +555,135 @@ mod tests {
         assert_eq!(proof.second_size, 2);
     }
 
+    #[test]
+    fn test_consistency_proof_verifies_for_all_sizes() {
+        let mut tree = MerkleTree::new();
+        for i in 0..11 {
+            tree.append(&format!("leaf{}", i));
+        }
+
+        for second in 1..=tree.size() {
+            for first in 1..=second {
+                let proof = tree.get_consistency_proof(first, second, &StoredNodes::new()).unwrap();
+                let first_root = tree.root_at(first, &StoredNodes::new()).unwrap();
+                let second_root = tree.root_at(second, &StoredNodes::new()).unwrap();
+                assert!(proof.verify(&first_root, &second_root), "Consistency failed for {} -> {}", first, second);
+            }
+        }
+
+        let proof = tree.get_consistency_proof(3, 11, &StoredNodes::new()).unwrap();
+        assert!(!proof.verify(&tree.root_at(4, &StoredNodes::new()).unwrap(), &tree.root().unwrap()));
+        assert!(tree.get_consistency_proof(3, 12, &StoredNodes::new()).is_none());
+    }
+
+    #[test]
+    fn test_blake3_tree() {
+        let mut sha = MerkleTree::new();
+        let mut blake = MerkleTree::new_with(HashAlgorithm::Blake3);
+        for i in 0..6 {
+            sha.append(&format!("leaf{}", i));
+            blake.append(&format!("leaf{}", i));
+        }
+
+        let root = blake.root().unwrap();
+        assert!(root.starts_with("blake3:"));
+        assert_ne!(sha.root(), blake.root());
+
+        let proof = blake.get_proof(4, &StoredNodes::new()).unwrap();
+        assert!(blake.verify_proof(&proof));
+        assert!(proof.verify(&root));
+        assert!(!proof.verify(&sha.root().unwrap()));
+
+        let consistency = blake.get_consistency_proof(2, 6, &StoredNodes::new()).unwrap();
+        assert!(consistency.verify(&blake.root_at(2, &StoredNodes::new()).unwrap(), &root));
+    }
+
+    #[test]
+    fn test_incremental_nodes_match_full_recomputation() {
+        use aapi_crypto::merkle::merkle_root_with;
+
+        let mut tree = MerkleTree::new();
+        let mut leaves = Vec::new();
+        for i in 0..40 {
+            leaves.push(tree.leaf_hash(&format!("leaf{}", i)));
+            tree.append(&format!("leaf{}", i));
+            let size = tree.size();
+            assert_eq!(tree.root(), merkle_root_with(HashAlgorithm::Sha256, &leaves), "size {}", size);
+
+            let root = tree.root().unwrap();
+            for leaf in 0..size {
+                assert!(tree.get_proof(leaf, &StoredNodes::new()).unwrap().verify(&root), "leaf {} of {}", leaf, size);
+            }
+        }
+        for size in 1..=40 {
+            assert_eq!(tree.root_at(size, &StoredNodes::new()), merkle_root_with(HashAlgorithm::Sha256, &leaves[..size]));
+        }
+    }
+
+    #[test]
+    fn test_proofs_read_persisted_nodes() {
+        let mut tree = MerkleTree::new();
+        let mut stored = StoredNodes::new();
+        for i in 0..23 {
+            tree.append(&format!("leaf{}", i));
+            if i % 4 == 0 {
+                stored.extend(tree.unpersisted().into_iter().map(|n| ((n.level, n.index), n.hash)));
+                tree.clear_unpersisted();
+            }
+        }
+        let root = tree.root().unwrap();
+
+        for leaf in 0..tree.size() {
+            let needed = tree.proof_nodes(leaf);
+            assert!(needed.len() <= 2 * height(tree.size()) + 1);
+            assert!(tree.get_proof(leaf, &StoredNodes::new()).is_none() || needed.is_empty());
+
+            let fetched: StoredNodes = needed.iter().map(|key| (*key, stored[key].clone())).collect();
+            let proof = tree.get_proof(leaf, &fetched).unwrap();
+            assert!(proof.verify(&root), "leaf {}", leaf);
+        }
+
+        let leaves: StoredNodes = tree.consistency_nodes(tree.size()).iter().map(|key| (*key, stored[key].clone())).collect();
+        for size in 1..=tree.size() {
+            let fetched: StoredNodes = tree.root_nodes(size).iter().map(|key| (*key, stored[key].clone())).collect();
+            assert!(fetched.len() <= height(size) + 1);
+            let consistency = tree.get_consistency_proof(size, tree.size(), &leaves).unwrap();
+            assert!(consistency.verify(&tree.root_at(size, &fetched).unwrap(), &root), "size {}", size);
+        }
+    }
+
+    #[test]
+    fn test_resume_from_persisted_nodes() {
+        let mut tree = MerkleTree::new_with(HashAlgorithm::Blake3);
+        let mut stored = StoredNodes::new();
+        for i in 0..13 {
+            tree.append(&format!("leaf{}", i));
+        }
+        stored.extend(tree.unpersisted().into_iter().map(|n| ((n.level, n.index), n.hash)));
+
+        // Only the right edge is read back
+        let frontier: StoredNodes = MerkleTree::frontier_nodes(13).iter().map(|key| (*key, stored[key].clone())).collect();
+        assert_eq!(frontier.len(), 3);
+        let mut restored = MerkleTree::resume(HashAlgorithm::Blake3, 13, &frontier).unwrap();
+        assert_eq!(restored.size(), 13);
+        assert_eq!(restored.root(), tree.root());
+        assert_eq!(restored.last_leaf(), tree.last_leaf());
+        assert!(restored.unpersisted().is_empty());
+
+        let fetched: StoredNodes = restored.proof_nodes(6).iter().map(|key| (*key, stored[key].clone())).collect();
+        assert_eq!(restored.get_proof(6, &fetched).unwrap().path, tree.get_proof(6, &stored).unwrap().path);
+
+        restored.append("leaf13");
+        tree.append("leaf13");
+        assert_eq!(restored.root(), tree.root());
+
+        assert!(MerkleTree::resume(HashAlgorithm::Sha256, 0, &StoredNodes::new()).unwrap().is_empty());
+        assert!(matches!(
+            MerkleTree::resume(HashAlgorithm::Blake3, 14, &frontier),
+            Err(IndexDbError::MerkleError(_))
+        ));
+    }
+
     #[test]
     fn test_deterministic_hashing() {
         let mut tree1 = MerkleTree::new();
@@ -4,7 +4,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use aapi_core::types::{ContentHash, EffectBucket, PrincipalId, ResourceId};
+use aapi_core::types::{ContentHash, EffectBucket, HashAlgorithm, PrincipalId, ResourceId};
 use aapi_core::error::ReasonCode;
 
 /// Stored VĀKYA record
@@ -24,6 +24,9 @@ pub struct VakyaRecord {
     pub karma_rid: String,
     /// Resource kind
     pub karma_kind: Option<String>,
+    /// Resource namespace
+    #[serde(default)]
+    pub karma_ns: Option<String>,
     /// Action performed
     pub kriya_action: String,
     /// Expected effect bucket
@@ -67,6 +70,7 @@ impl VakyaRecord {
             karta_type: "human".to_string(),
             karma_rid,
             karma_kind: None,
+            karma_ns: None,
             kriya_action,
             expected_effect: EffectBucket::None,
             cap_ref: String::new(),
@@ -171,6 +175,18 @@ pub struct ReceiptRecord {
     pub receipt_json: serde_json::Value,
     /// Merkle leaf index
     pub leaf_index: Option<i64>,
+    /// Actor whose chain this receipt extends, set at store time
+    #[serde(default)]
+    pub karta_pid: Option<String>,
+    /// Position in the actor's chain, from 0
+    #[serde(default)]
+    pub chain_seq: Option<i64>,
+    /// `chain_hash` of the actor's previous receipt; `None` for the first
+    #[serde(default)]
+    pub prev_receipt_hash: Option<String>,
+    /// Hash linking this receipt to `prev_receipt_hash`
+    #[serde(default)]
+    pub chain_hash: Option<String>,
 }
 
 impl ReceiptRecord {
@@ -195,6 +211,10 @@ impl ReceiptRecord {
             created_at: Utc::now(),
             receipt_json,
             leaf_index: None,
+            karta_pid: None,
+            chain_seq: None,
+            prev_receipt_hash: None,
+            chain_hash: None,
         }
     }
 
@@ -203,6 +223,239 @@ impl ReceiptRecord {
     }
 }
 
+/// Link hash of a receipt in its actor's chain
+///
+/// Covers only what a receipt update cannot change (the VĀKYA it answers and
+/// that VĀKYA's hash), so resolving a pending receipt keeps the chain valid.
+pub fn receipt_chain_hash(
+    algorithm: HashAlgorithm,
+    prev_receipt_hash: Option<&str>,
+    vakya_id: &str,
+    vakya_hash: &str,
+) -> String {
+    let data = format!(
+        "aapi.receipt-chain.v1\n{}\n{}\n{}",
+        prev_receipt_hash.unwrap_or(""),
+        vakya_id,
+        vakya_hash
+    );
+    algorithm.digest_hex(data.as_bytes())
+}
+
+/// Latest receipt in an actor's receipt chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActorChainHead {
+    /// Actor principal
+    pub karta_pid: String,
+    /// `chain_hash` of the latest receipt
+    pub head_hash: String,
+    /// VĀKYA answered by the latest receipt
+    pub head_vakya_id: String,
+    /// Receipts in the chain
+    pub length: i64,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Revocation of a capability token
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapabilityRevocation {
+    /// Revoked token ID
+    pub token_id: String,
+    /// Whether tokens delegated from this one are revoked too
+    pub include_delegations: bool,
+    /// Why the token was revoked
+    pub reason: Option<String>,
+    /// Who revoked the token
+    pub revoked_by: Option<String>,
+    pub revoked_at: DateTime<Utc>,
+}
+
+impl CapabilityRevocation {
+    pub fn new(token_id: impl Into<String>) -> Self {
+        Self {
+            token_id: token_id.into(),
+            include_delegations: false,
+            reason: None,
+            revoked_by: None,
+            revoked_at: Utc::now(),
+        }
+    }
+}
+
+/// Outcome of `IndexDbStore::maintenance`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MaintenanceReport {
+    /// Problems the integrity check found; empty when the database is intact
+    pub integrity_errors: Vec<String>,
+    /// Trees whose latest stored checkpoint does not match the log
+    pub checkpoint_mismatches: Vec<String>,
+    /// Database size in bytes before and after compaction
+    pub size_before: u64,
+    pub size_after: u64,
+    pub duration_ms: u64,
+}
+
+impl MaintenanceReport {
+    /// Whether the database passed every check
+    pub fn is_healthy(&self) -> bool {
+        self.integrity_errors.is_empty() && self.checkpoint_mismatches.is_empty()
+    }
+}
+
+/// Stored approval request for a VĀKYA awaiting human sign-off
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalRecord {
+    /// Unique record ID
+    pub id: Uuid,
+    /// Approval ID returned to the submitter
+    pub approval_id: String,
+    /// VĀKYA awaiting approval
+    pub vakya_id: String,
+    /// VĀKYA hash
+    pub vakya_hash: String,
+    /// Rule that required the approval
+    pub rule_id: Option<String>,
+    /// Approval type (human, manager, security, ...)
+    pub approval_type: String,
+    /// Principals allowed to vote; with neither this nor
+    /// `weighted_approvers` set, nobody may vote
+    pub approvers: Vec<String>,
+    /// Approvals needed to execute
+    pub min_approvals: u32,
+    /// Voting weight per approver; when non-empty the request resolves on
+    /// total weight rather than the number of approvals
+    #[serde(default)]
+    pub weighted_approvers: Vec<(String, u32)>,
+    /// Total weight needed when `weighted_approvers` is set; defaults to
+    /// `min_approvals`
+    #[serde(default)]
+    pub required_weight: Option<u32>,
+    /// Current state
+    pub status: ApprovalRecordStatus,
+    /// Votes cast so far
+    pub votes: Vec<ApprovalVote>,
+    /// Reason approval was required
+    pub reason: String,
+    /// Creation timestamp
+    pub created_at: DateTime<Utc>,
+    /// Deadline after which the request times out
+    pub expires_at: Option<DateTime<Utc>>,
+    /// When the request left the pending state
+    pub resolved_at: Option<DateTime<Utc>>,
+    /// Number of updates applied; an update only succeeds against the
+    /// version it was read at
+    #[serde(default)]
+    pub version: u32,
+}
+
+impl ApprovalRecord {
+    pub fn new(
+        approval_id: String,
+        vakya_id: String,
+        vakya_hash: String,
+        min_approvals: u32,
+        reason: String,
+    ) -> Self {
+        Self {
+            id: Uuid::now_v7(),
+            approval_id,
+            vakya_id,
+            vakya_hash,
+            rule_id: None,
+            approval_type: "human".to_string(),
+            approvers: vec![],
+            min_approvals,
+            weighted_approvers: vec![],
+            required_weight: None,
+            status: ApprovalRecordStatus::Pending,
+            votes: vec![],
+            reason,
+            created_at: Utc::now(),
+            expires_at: None,
+            resolved_at: None,
+            version: 0,
+        }
+    }
+
+    /// Number of approve votes received
+    pub fn approval_count(&self) -> u32 {
+        self.votes.iter().filter(|v| v.decision == VoteDecision::Approve).count() as u32
+    }
+
+    /// Voting weight of a principal: its listed weight, otherwise 1
+    pub fn weight_of(&self, approver: &str) -> u32 {
+        self.weighted_approvers.iter()
+            .find(|(principal, _)| principal == approver)
+            .map(|(_, weight)| *weight)
+            .unwrap_or(1)
+    }
+
+    /// Summed weight of the distinct principals that approved
+    pub fn approval_weight(&self) -> u32 {
+        let mut seen = std::collections::HashSet::new();
+        self.votes.iter()
+            .filter(|v| v.decision == VoteDecision::Approve && seen.insert(v.approver.as_str()))
+            .map(|v| self.weight_of(&v.approver))
+            .fold(0u32, u32::saturating_add)
+    }
+
+    /// Check whether enough approvals (or approval weight) have been cast
+    pub fn is_satisfied(&self) -> bool {
+        if self.weighted_approvers.is_empty() {
+            self.approval_count() >= self.min_approvals
+        } else {
+            self.approval_weight() >= self.required_weight.unwrap_or(self.min_approvals)
+        }
+    }
+
+    /// Check whether a principal is listed as an approver of this request
+    pub fn is_authorized_approver(&self, approver: &str) -> bool {
+        self.approvers.iter().any(|a| a == approver)
+            || self.weighted_approvers.iter().any(|(a, _)| a == approver)
+    }
+
+    /// Check whether a principal has already voted
+    pub fn has_voted(&self, approver: &str) -> bool {
+        self.votes.iter().any(|v| v.approver == approver)
+    }
+
+    /// Check whether the approval deadline has passed
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.map(|t| Utc::now() > t).unwrap_or(false)
+    }
+}
+
+/// State of an approval request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalRecordStatus {
+    Pending,
+    Approved,
+    Rejected,
+    TimedOut,
+}
+
+/// A single approver's vote
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalVote {
+    /// Approver principal
+    pub approver: String,
+    /// Vote cast
+    pub decision: VoteDecision,
+    /// Optional comment
+    pub comment: Option<String>,
+    /// Vote timestamp
+    pub voted_at: DateTime<Utc>,
+}
+
+/// Approve or deny
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VoteDecision {
+    Approve,
+    Deny,
+}
+
 /// Merkle tree checkpoint
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MerkleCheckpoint {
@@ -222,6 +475,29 @@ pub struct MerkleCheckpoint {
     pub signature: Option<String>,
 }
 
+/// One line of a JSONL evidence export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "record_type", content = "record", rename_all = "snake_case")]
+pub enum ExportRecord {
+    Vakya(VakyaRecord),
+    Effect(EffectRecord),
+    Receipt(ReceiptRecord),
+    /// Tree head written after the records, so exported leaf indices can be
+    /// checked against inclusion proofs for that size
+    Checkpoint(MerkleCheckpoint),
+}
+
+impl ExportRecord {
+    pub fn created_at(&self) -> DateTime<Utc> {
+        match self {
+            ExportRecord::Vakya(r) => r.created_at,
+            ExportRecord::Effect(r) => r.created_at,
+            ExportRecord::Receipt(r) => r.created_at,
+            ExportRecord::Checkpoint(c) => c.created_at,
+        }
+    }
+}
+
 /// Type of Merkle tree
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -548,6 +824,31 @@ pub struct AuditLogEntry {
     pub user_agent: Option<String>,
 }
 
+impl AuditLogEntry {
+    pub fn new(event_type: AuditEventType, details: serde_json::Value) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            event_type,
+            actor: None,
+            target: None,
+            details,
+            created_at: Utc::now(),
+            source_ip: None,
+            user_agent: None,
+        }
+    }
+
+    pub fn with_actor(mut self, actor: impl Into<String>) -> Self {
+        self.actor = Some(actor.into());
+        self
+    }
+
+    pub fn with_target(mut self, target: impl Into<String>) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+}
+
 /// Types of audit events
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -558,6 +859,10 @@ pub enum AuditEventType {
     VakyaExecuted,
     /// VĀKYA denied
     VakyaDenied,
+    /// VĀKYA held for approval
+    ApprovalRequested,
+    /// Signature or capability check failed
+    AuthorizationFailed,
     /// Effect captured
     EffectCaptured,
     /// Receipt issued
@@ -680,4 +985,74 @@ mod tests {
         );
         assert!(!failure.is_success());
     }
+
+    #[test]
+    fn test_approval_record_votes() {
+        let mut approval = ApprovalRecord::new(
+            "approval-1".to_string(),
+            "vakya-123".to_string(),
+            "hash-abc".to_string(),
+            2,
+            "Delete requires approval".to_string(),
+        );
+        approval.approvers = vec!["user:alice".to_string(), "user:bob".to_string()];
+
+        assert!(approval.is_authorized_approver("user:alice"));
+        assert!(!approval.is_authorized_approver("user:mallory"));
+
+        // Without listed approvers nobody may vote
+        let unlisted = ApprovalRecord { approvers: vec![], ..approval.clone() };
+        assert!(!unlisted.is_authorized_approver("user:alice"));
+        assert!(!approval.is_expired());
+
+        approval.votes.push(ApprovalVote {
+            approver: "user:alice".to_string(),
+            decision: VoteDecision::Approve,
+            comment: None,
+            voted_at: Utc::now(),
+        });
+        assert!(approval.has_voted("user:alice"));
+        assert_eq!(approval.approval_count(), 1);
+        assert!(!approval.is_satisfied());
+    }
+
+    #[test]
+    fn test_weighted_approval_resolution() {
+        let mut approval = ApprovalRecord::new(
+            "approval-2".to_string(),
+            "vakya-123".to_string(),
+            "hash-abc".to_string(),
+            1,
+            "Deploy requires sign-off".to_string(),
+        );
+        approval.weighted_approvers = vec![
+            ("user:manager".to_string(), 2),
+            ("user:peer-a".to_string(), 1),
+            ("user:peer-b".to_string(), 1),
+        ];
+        approval.required_weight = Some(2);
+        assert!(approval.is_authorized_approver("user:peer-a"));
+        assert!(!approval.is_authorized_approver("user:mallory"));
+
+        let vote = |approver: &str, decision| ApprovalVote {
+            approver: approver.to_string(),
+            decision,
+            comment: None,
+            voted_at: Utc::now(),
+        };
+
+        // One peer is not enough, and repeated votes do not add weight
+        approval.votes.push(vote("user:peer-a", VoteDecision::Approve));
+        approval.votes.push(vote("user:peer-a", VoteDecision::Approve));
+        assert_eq!(approval.approval_weight(), 1);
+        assert!(!approval.is_satisfied());
+
+        approval.votes.push(vote("user:peer-b", VoteDecision::Approve));
+        assert!(approval.is_satisfied());
+
+        // The manager alone carries the required weight
+        approval.votes = vec![vote("user:manager", VoteDecision::Approve)];
+        assert_eq!(approval.approval_weight(), 2);
+        assert!(approval.is_satisfied());
+    }
 }
@@ -16,6 +16,8 @@ pub struct VakyaQuery {
     pub karta_type: Option<String>,
     /// Filter by resource ID (prefix match)
     pub karma_rid_prefix: Option<String>,
+    /// Filter by resource namespace, including child namespaces
+    pub karma_ns: Option<String>,
     /// Filter by action (exact or prefix)
     pub kriya_action: Option<String>,
     /// Filter by trace ID
@@ -49,6 +51,11 @@ impl VakyaQuery {
         self
     }
 
+    pub fn by_namespace(mut self, ns: impl Into<String>) -> Self {
+        self.karma_ns = Some(ns.into());
+        self
+    }
+
     pub fn by_action(mut self, action: impl Into<String>) -> Self {
         self.kriya_action = Some(action.into());
         self
@@ -105,6 +112,12 @@ impl VakyaQuery {
             params.push(format!("{}%", rid));
         }
 
+        // Same prefix semantics as `Namespace::contains`
+        if let Some(ref ns) = self.karma_ns {
+            conditions.push("karma_ns LIKE ?".to_string());
+            params.push(format!("{}%", ns));
+        }
+
         if let Some(ref action) = self.kriya_action {
             if action.ends_with('*') {
                 conditions.push("kriya_action LIKE ?".to_string());
@@ -422,6 +435,175 @@ pub enum RiskLevel {
     Critical,
 }
 
+/// Filter for streaming the evidence log out as JSON lines
+#[derive(Debug, Clone, Default)]
+pub struct ExportFilter {
+    /// Record types to export; empty means VĀKYA, effect and receipt records
+    pub record_types: Vec<TreeType>,
+    /// Filter by time range start
+    pub from_time: Option<DateTime<Utc>>,
+    /// Filter by time range end
+    pub to_time: Option<DateTime<Utc>>,
+}
+
+impl ExportFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_type(mut self, tree_type: TreeType) -> Self {
+        self.record_types.push(tree_type);
+        self
+    }
+
+    pub fn from(mut self, time: DateTime<Utc>) -> Self {
+        self.from_time = Some(time);
+        self
+    }
+
+    pub fn to(mut self, time: DateTime<Utc>) -> Self {
+        self.to_time = Some(time);
+        self
+    }
+
+    /// Record types to export, in a stable order
+    pub fn effective_record_types(&self) -> Vec<TreeType> {
+        if self.record_types.is_empty() {
+            return vec![TreeType::Vakya, TreeType::Effect, TreeType::Receipt];
+        }
+
+        let mut types = Vec::new();
+        for tree_type in [TreeType::Vakya, TreeType::Effect, TreeType::Receipt, TreeType::Packet] {
+            if self.record_types.contains(&tree_type) {
+                types.push(tree_type);
+            }
+        }
+        types
+    }
+
+    /// Build SQL WHERE clause
+    pub fn build_where_clause(&self) -> (String, Vec<String>) {
+        let mut conditions = Vec::new();
+        let mut params = Vec::new();
+
+        if let Some(ref from) = self.from_time {
+            conditions.push("created_at >= ?".to_string());
+            params.push(from.to_rfc3339());
+        }
+
+        if let Some(ref to) = self.to_time {
+            conditions.push("created_at < ?".to_string());
+            params.push(to.to_rfc3339());
+        }
+
+        let where_clause = if conditions.is_empty() {
+            "1=1".to_string()
+        } else {
+            conditions.join(" AND ")
+        };
+
+        (where_clause, params)
+    }
+}
+
+/// Filter for reading the audit log back
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    /// Filter by event type
+    pub event_type: Option<AuditEventType>,
+    /// Filter by actor
+    pub actor: Option<String>,
+    /// Filter by time range start
+    pub from_time: Option<DateTime<Utc>>,
+    /// Filter by time range end
+    pub to_time: Option<DateTime<Utc>>,
+}
+
+impl AuditFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn by_event_type(mut self, event_type: AuditEventType) -> Self {
+        self.event_type = Some(event_type);
+        self
+    }
+
+    pub fn by_actor(mut self, actor: impl Into<String>) -> Self {
+        self.actor = Some(actor.into());
+        self
+    }
+
+    pub fn from(mut self, time: DateTime<Utc>) -> Self {
+        self.from_time = Some(time);
+        self
+    }
+
+    pub fn to(mut self, time: DateTime<Utc>) -> Self {
+        self.to_time = Some(time);
+        self
+    }
+
+    /// Build SQL WHERE clause
+    pub fn build_where_clause(&self) -> (String, Vec<String>) {
+        let mut conditions = Vec::new();
+        let mut params = Vec::new();
+
+        if let Some(ref event_type) = self.event_type {
+            // Event types are stored in their JSON form
+            conditions.push("event_type = ?".to_string());
+            params.push(serde_json::to_string(event_type).unwrap_or_default());
+        }
+
+        if let Some(ref actor) = self.actor {
+            conditions.push("actor = ?".to_string());
+            params.push(actor.clone());
+        }
+
+        if let Some(ref from) = self.from_time {
+            conditions.push("created_at >= ?".to_string());
+            params.push(from.to_rfc3339());
+        }
+
+        if let Some(ref to) = self.to_time {
+            conditions.push("created_at < ?".to_string());
+            params.push(to.to_rfc3339());
+        }
+
+        let where_clause = if conditions.is_empty() {
+            "1=1".to_string()
+        } else {
+            conditions.join(" AND ")
+        };
+
+        (where_clause, params)
+    }
+}
+
+/// A page of results, by offset and limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Page {
+    pub offset: u32,
+    pub limit: u32,
+}
+
+impl Default for Page {
+    fn default() -> Self {
+        Self { offset: 0, limit: 100 }
+    }
+}
+
+impl Page {
+    pub fn new(offset: u32, limit: u32) -> Self {
+        Self { offset, limit }
+    }
+
+    /// Build LIMIT/OFFSET clause
+    pub fn build_limit_clause(&self) -> String {
+        format!("LIMIT {} OFFSET {}", self.limit, self.offset)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2,15 +2,24 @@
 
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use futures::stream::BoxStream;
+use futures::TryStreamExt;
 use sqlx::{Pool, Sqlite, SqlitePool, Row};
 use std::sync::Arc;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
-use aapi_core::types::EffectBucket;
-use crate::error::IndexDbResult;
+use aapi_core::types::{EffectBucket, HashAlgorithm};
+use aapi_core::sandhi::hash_value;
+use aapi_crypto::{DataKey, MasterKey, WrappedKey};
+use crate::error::{IndexDbError, IndexDbResult};
 use crate::models::*;
-use crate::merkle::MerkleTree;
+use crate::merkle::{MerkleNode, MerkleProof, MerkleTree, SignedTreeHead, StoredNodes};
+use crate::query::{AuditFilter, ExportFilter, Page, QueryResult, VakyaQuery};
 
 /// Storage trait for IndexDB backends
 #[async_trait]
@@ -21,18 +30,84 @@ pub trait IndexDbStore: Send + Sync {
     /// Get a VĀKYA record by ID
     async fn get_vakya(&self, vakya_id: &str) -> IndexDbResult<Option<VakyaRecord>>;
     
+    /// Query VĀKYA records by actor, action, resource and time range
+    async fn query_vakyas(&self, query: &VakyaQuery) -> IndexDbResult<Vec<VakyaRecord>>;
+    
     /// Store an effect record
     async fn store_effect(&self, record: EffectRecord) -> IndexDbResult<EffectRecord>;
+
+    /// Store the effects of one execution together
+    ///
+    /// Backends with transactions keep either every effect, with its Merkle
+    /// leaf, or none of them. The default stores them one by one and may
+    /// leave the effects before a failure in place.
+    async fn store_effects(&self, records: Vec<EffectRecord>) -> IndexDbResult<Vec<EffectRecord>> {
+        let mut stored = Vec::with_capacity(records.len());
+        for record in records {
+            stored.push(self.store_effect(record).await?);
+        }
+        Ok(stored)
+    }
     
     /// Get effects for a VĀKYA
     async fn get_effects(&self, vakya_id: &str) -> IndexDbResult<Vec<EffectRecord>>;
     
+    /// Get an effect record by effect ID
+    async fn get_effect(&self, effect_id: &str) -> IndexDbResult<Option<EffectRecord>>;
+    
     /// Store a receipt record
     async fn store_receipt(&self, record: ReceiptRecord) -> IndexDbResult<ReceiptRecord>;
     
     /// Get a receipt by VĀKYA ID
     async fn get_receipt(&self, vakya_id: &str) -> IndexDbResult<Option<ReceiptRecord>>;
     
+    /// Replace the receipt for a VĀKYA, keeping its Merkle leaf
+    async fn update_receipt(&self, record: ReceiptRecord) -> IndexDbResult<ReceiptRecord>;
+
+    /// Every receipt currently carrying `reason_code`, oldest first
+    async fn receipts_with_reason(&self, reason_code: aapi_core::error::ReasonCode) -> IndexDbResult<Vec<ReceiptRecord>>;
+
+    /// Latest receipt in an actor's receipt chain
+    async fn get_actor_chain_head(&self, karta_pid: &str) -> IndexDbResult<Option<ActorChainHead>>;
+
+    /// Walk an actor's receipt chain from the first receipt, checking every
+    /// link and that it ends at the stored head
+    ///
+    /// Fails with `IntegrityViolation` if a receipt is missing, out of order
+    /// or altered.
+    async fn verify_actor_chain(&self, karta_pid: &str) -> IndexDbResult<ActorChainHead>;
+    
+    /// Store an approval request
+    async fn store_approval(&self, record: ApprovalRecord) -> IndexDbResult<ApprovalRecord>;
+    
+    /// Get an approval request by approval ID
+    async fn get_approval(&self, approval_id: &str) -> IndexDbResult<Option<ApprovalRecord>>;
+    
+    /// Update the votes and status of a pending approval request
+    ///
+    /// Fails with [`IndexDbError::Conflict`] when the request is no longer
+    /// pending or was updated since `record` was read.
+    async fn update_approval(&self, record: ApprovalRecord) -> IndexDbResult<ApprovalRecord>;
+
+    /// Record a capability revocation, returning the stored revocation
+    ///
+    /// Revoking a token again keeps the original record, widened to
+    /// include delegations if the new revocation does.
+    async fn revoke_capability(&self, revocation: CapabilityRevocation) -> IndexDbResult<CapabilityRevocation>;
+
+    /// Get the revocation of a capability token, if it is revoked
+    async fn get_capability_revocation(&self, token_id: &str) -> IndexDbResult<Option<CapabilityRevocation>>;
+
+    /// All capability revocations, oldest first
+    async fn list_capability_revocations(&self) -> IndexDbResult<Vec<CapabilityRevocation>>;
+
+    /// Record that a verified capability token was delegated from
+    /// `parent_token_id`; a token's first recorded parent is kept
+    async fn record_capability_delegation(&self, token_id: &str, parent_token_id: &str) -> IndexDbResult<()>;
+
+    /// Every recorded delegation as `(token_id, parent_token_id)`
+    async fn list_capability_delegations(&self) -> IndexDbResult<Vec<(String, String)>>;
+    
     /// Store a MemPacket record (3D envelope)
     async fn store_packet(&self, record: MemPacketRecord) -> IndexDbResult<MemPacketRecord>;
     
@@ -71,6 +146,9 @@ pub trait IndexDbStore: Send + Sync {
     
     /// Store an audit log entry
     async fn store_audit_log(&self, entry: AuditLogEntry) -> IndexDbResult<()>;
+
+    /// Get audit log entries matching the filter, newest first
+    async fn get_audit_log(&self, filter: &AuditFilter, page: Page) -> IndexDbResult<QueryResult<AuditLogEntry>>;
     
     /// Get the current Merkle root for a tree type
     async fn get_merkle_root(&self, tree_type: TreeType) -> IndexDbResult<Option<String>>;
@@ -80,6 +158,132 @@ pub trait IndexDbStore: Send + Sync {
     
     /// Get inclusion proof for a record
     async fn get_inclusion_proof(&self, tree_type: TreeType, leaf_index: i64) -> IndexDbResult<Option<InclusionProof>>;
+
+    /// Get inclusion proofs for several records against one tree size,
+    /// with the (unsigned) head of the tree at that size
+    async fn get_inclusion_proofs(&self, tree_type: TreeType, leaf_indices: &[i64]) -> IndexDbResult<(SignedTreeHead, Vec<InclusionProof>)>;
+
+    /// Get the inclusion proof for a VĀKYA by ID; `None` if the record is
+    /// unknown or has no leaf index yet
+    async fn inclusion_proof_for_vakya(&self, vakya_id: &str) -> IndexDbResult<Option<InclusionProof>> {
+        match self.get_vakya(vakya_id).await?.and_then(|r| r.leaf_index) {
+            Some(leaf_index) => self.get_inclusion_proof(TreeType::Vakya, leaf_index).await,
+            None => Ok(None),
+        }
+    }
+
+    /// Get the inclusion proof for the receipt of a VĀKYA; `None` if there
+    /// is no receipt or it has no leaf index yet
+    async fn inclusion_proof_for_receipt(&self, vakya_id: &str) -> IndexDbResult<Option<InclusionProof>> {
+        match self.get_receipt(vakya_id).await?.and_then(|r| r.leaf_index) {
+            Some(leaf_index) => self.get_inclusion_proof(TreeType::Receipt, leaf_index).await,
+            None => Ok(None),
+        }
+    }
+
+    /// Get the inclusion proof for an effect by effect ID; `None` if the
+    /// record is unknown or has no leaf index yet
+    async fn inclusion_proof_for_effect(&self, effect_id: &str) -> IndexDbResult<Option<InclusionProof>> {
+        match self.get_effect(effect_id).await?.and_then(|r| r.leaf_index) {
+            Some(leaf_index) => self.get_inclusion_proof(TreeType::Effect, leaf_index).await,
+            None => Ok(None),
+        }
+    }
+    
+    /// Get a consistency proof between two tree sizes
+    async fn get_consistency_proof(&self, tree_type: TreeType, first_size: i64, second_size: i64) -> IndexDbResult<Option<ConsistencyProof>>;
+    
+    /// Stream records matching the filter to `writer` as JSON lines in
+    /// `created_at` order, followed by a checkpoint per exported tree.
+    /// Returns the number of lines written.
+    async fn export_jsonl(&self, filter: &ExportFilter, writer: &mut (dyn AsyncWrite + Unpin + Send)) -> IndexDbResult<u64>;
+
+    /// Check the database for corruption, reconcile the Merkle trees with
+    /// their latest checkpoints and compact the storage
+    async fn maintenance(&self) -> IndexDbResult<MaintenanceReport>;
+}
+
+/// Records hashed per page when catching a restored tree up with the log
+const MERKLE_RESTORE_PAGE: i64 = 4096;
+
+/// Nodes looked up per `merkle_nodes` query, keeping under SQLite's
+/// bound-parameter limit
+const MERKLE_NODE_CHUNK: usize = 256;
+
+/// Connection pool and timeout settings for `SqliteIndexDb`
+#[derive(Debug, Clone)]
+pub struct DbConfig {
+    /// Maximum pooled connections
+    pub max_connections: u32,
+    /// How long to wait for a free pooled connection
+    pub acquire_timeout: Duration,
+    /// How long SQLite retries a locked database before failing
+    pub busy_timeout: Duration,
+    /// Upper bound on the time a connection spends running statements per
+    /// checkout from the pool; `None` disables it. Long exports hold their
+    /// connections for the whole stream, so size this accordingly.
+    pub statement_timeout: Option<Duration>,
+    /// Hash algorithm for the Merkle trees
+    pub hash_algorithm: HashAlgorithm,
+    /// Recompute each VĀKYA's hash from its stored JSON when it is read and
+    /// fail with `IntegrityMismatch` if it differs. Costs a canonicalization
+    /// per record.
+    pub verify_hashes: bool,
+    /// Envelope-encrypt effect `before_state`, `after_state` and `delta`
+    /// under a per-record data key wrapped by this master key. Hashes are
+    /// still taken over the plaintext, so proofs are unaffected.
+    pub state_key: Option<MasterKey>,
+}
+
+impl Default for DbConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: 10,
+            acquire_timeout: Duration::from_secs(30),
+            busy_timeout: Duration::from_secs(5),
+            statement_timeout: None,
+            hash_algorithm: HashAlgorithm::Sha256,
+            verify_hashes: false,
+            state_key: None,
+        }
+    }
+}
+
+impl DbConfig {
+    pub fn with_max_connections(mut self, max_connections: u32) -> Self {
+        self.max_connections = max_connections;
+        self
+    }
+
+    pub fn with_acquire_timeout(mut self, timeout: Duration) -> Self {
+        self.acquire_timeout = timeout;
+        self
+    }
+
+    pub fn with_busy_timeout(mut self, timeout: Duration) -> Self {
+        self.busy_timeout = timeout;
+        self
+    }
+
+    pub fn with_statement_timeout(mut self, timeout: Duration) -> Self {
+        self.statement_timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_hash_algorithm(mut self, algorithm: HashAlgorithm) -> Self {
+        self.hash_algorithm = algorithm;
+        self
+    }
+
+    pub fn with_hash_verification(mut self) -> Self {
+        self.verify_hashes = true;
+        self
+    }
+
+    pub fn with_state_encryption(mut self, key: MasterKey) -> Self {
+        self.state_key = Some(key);
+        self
+    }
 }
 
 /// SQLite-based IndexDB store
@@ -89,21 +293,42 @@ pub struct SqliteIndexDb {
     effect_tree: Arc<RwLock<MerkleTree>>,
     receipt_tree: Arc<RwLock<MerkleTree>>,
     packet_tree: Arc<RwLock<MerkleTree>>,
+    verify_hashes: bool,
+    state_key: Option<MasterKey>,
+    hash_algorithm: HashAlgorithm,
+    /// Serializes receipt inserts so each actor's chain grows one link at a time
+    chain_lock: tokio::sync::Mutex<()>,
 }
 
 impl SqliteIndexDb {
     /// Create a new SQLite IndexDB
     pub async fn new(database_url: &str) -> IndexDbResult<Self> {
-        let pool = SqlitePool::connect(database_url).await?;
+        Self::new_with(database_url, HashAlgorithm::Sha256).await
+    }
+
+    /// Create a new SQLite IndexDB whose Merkle trees use the given hash algorithm
+    ///
+    /// Trees are restored from stored nodes and records on startup, so the algorithm
+    /// must stay the same across restarts for published roots to remain valid.
+    pub async fn new_with(database_url: &str, algorithm: HashAlgorithm) -> IndexDbResult<Self> {
+        Self::with_config(database_url, DbConfig::default().with_hash_algorithm(algorithm)).await
+    }
+
+    /// Create a new SQLite IndexDB with explicit pool and timeout settings
+    ///
+    /// The database is opened in WAL mode so readers do not block the writer.
+    pub async fn with_config(database_url: &str, config: DbConfig) -> IndexDbResult<Self> {
+        let algorithm = config.hash_algorithm;
+        let pool = Self::connect_pool(database_url, &config).await?;
         
         // Run migrations
         Self::run_migrations(&pool).await?;
         
         // Initialize Merkle trees
-        let vakya_tree = Arc::new(RwLock::new(MerkleTree::new()));
-        let effect_tree = Arc::new(RwLock::new(MerkleTree::new()));
-        let receipt_tree = Arc::new(RwLock::new(MerkleTree::new()));
-        let packet_tree = Arc::new(RwLock::new(MerkleTree::new()));
+        let vakya_tree = Arc::new(RwLock::new(MerkleTree::new_with(algorithm)));
+        let effect_tree = Arc::new(RwLock::new(MerkleTree::new_with(algorithm)));
+        let receipt_tree = Arc::new(RwLock::new(MerkleTree::new_with(algorithm)));
+        let packet_tree = Arc::new(RwLock::new(MerkleTree::new_with(algorithm)));
         
         let store = Self {
             pool,
@@ -111,6 +336,10 @@ impl SqliteIndexDb {
             effect_tree,
             receipt_tree,
             packet_tree,
+            verify_hashes: config.verify_hashes,
+            state_key: config.state_key,
+            hash_algorithm: algorithm,
+            chain_lock: tokio::sync::Mutex::new(()),
         };
         
         // Rebuild Merkle trees from existing data
@@ -120,6 +349,30 @@ impl SqliteIndexDb {
         Ok(store)
     }
 
+    async fn connect_pool(database_url: &str, config: &DbConfig) -> IndexDbResult<SqlitePool> {
+        let options = SqliteConnectOptions::from_str(database_url)?
+            .journal_mode(SqliteJournalMode::Wal)
+            .busy_timeout(config.busy_timeout);
+
+        let mut pool_options = SqlitePoolOptions::new()
+            .max_connections(config.max_connections)
+            .acquire_timeout(config.acquire_timeout);
+
+        if let Some(timeout) = config.statement_timeout {
+            // SQLite has no statement timeout; interrupt long-running work
+            // through the progress handler, re-armed on every checkout.
+            pool_options = pool_options.before_acquire(move |conn, _| {
+                Box::pin(async move {
+                    let deadline = Instant::now() + timeout;
+                    conn.lock_handle().await?.set_progress_handler(1000, move || Instant::now() < deadline);
+                    Ok(true)
+                })
+            });
+        }
+
+        Ok(pool_options.connect_with(options).await?)
+    }
+
     /// Create an in-memory SQLite IndexDB (for testing)
     pub async fn in_memory() -> IndexDbResult<Self> {
         Self::new("sqlite::memory:").await
@@ -136,6 +389,7 @@ impl SqliteIndexDb {
                 karta_type TEXT NOT NULL,
                 karma_rid TEXT NOT NULL,
                 karma_kind TEXT,
+                karma_ns TEXT,
                 kriya_action TEXT NOT NULL,
                 expected_effect TEXT NOT NULL,
                 cap_ref TEXT,
@@ -167,6 +421,7 @@ impl SqliteIndexDb {
                 reversal_instructions TEXT,
                 created_at TEXT NOT NULL,
                 leaf_index INTEGER,
+                state_key TEXT,
                 FOREIGN KEY (vakya_id) REFERENCES vakya_records(vakya_id)
             )
         "#).execute(pool).await?;
@@ -190,6 +445,57 @@ impl SqliteIndexDb {
             )
         "#).execute(pool).await?;
 
+        sqlx::query(r#"
+            CREATE TABLE IF NOT EXISTS actor_chain_heads (
+                karta_pid TEXT PRIMARY KEY,
+                head_hash TEXT NOT NULL,
+                head_vakya_id TEXT NOT NULL,
+                length INTEGER NOT NULL,
+                updated_at TEXT NOT NULL
+            )
+        "#).execute(pool).await?;
+
+        sqlx::query(r#"
+            CREATE TABLE IF NOT EXISTS approvals (
+                id TEXT PRIMARY KEY,
+                approval_id TEXT UNIQUE NOT NULL,
+                vakya_id TEXT NOT NULL,
+                vakya_hash TEXT NOT NULL,
+                rule_id TEXT,
+                approval_type TEXT NOT NULL,
+                approvers TEXT NOT NULL DEFAULT '[]',
+                min_approvals INTEGER NOT NULL DEFAULT 1,
+                weighted_approvers TEXT NOT NULL DEFAULT '[]',
+                required_weight INTEGER,
+                status TEXT NOT NULL,
+                votes TEXT NOT NULL DEFAULT '[]',
+                reason TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                expires_at TEXT,
+                resolved_at TEXT,
+                version INTEGER NOT NULL DEFAULT 0,
+                FOREIGN KEY (vakya_id) REFERENCES vakya_records(vakya_id)
+            )
+        "#).execute(pool).await?;
+
+        sqlx::query(r#"
+            CREATE TABLE IF NOT EXISTS capability_revocations (
+                token_id TEXT PRIMARY KEY,
+                include_delegations INTEGER NOT NULL DEFAULT 0,
+                reason TEXT,
+                revoked_by TEXT,
+                revoked_at TEXT NOT NULL
+            )
+        "#).execute(pool).await?;
+
+        sqlx::query(r#"
+            CREATE TABLE IF NOT EXISTS capability_delegations (
+                token_id TEXT PRIMARY KEY,
+                parent_token_id TEXT NOT NULL,
+                recorded_at TEXT NOT NULL
+            )
+        "#).execute(pool).await?;
+
         sqlx::query(r#"
             CREATE TABLE IF NOT EXISTS merkle_checkpoints (
                 id TEXT PRIMARY KEY,
@@ -324,9 +630,22 @@ impl SqliteIndexDb {
             )
         "#).execute(pool).await?;
 
+        // Columns added after the initial schema
+        Self::add_column_if_missing(pool, "vakya_records", "karma_ns", "TEXT").await?;
+        Self::add_column_if_missing(pool, "effect_records", "state_key", "TEXT").await?;
+        Self::add_column_if_missing(pool, "approvals", "weighted_approvers", "TEXT NOT NULL DEFAULT '[]'").await?;
+        Self::add_column_if_missing(pool, "approvals", "required_weight", "INTEGER").await?;
+        Self::add_column_if_missing(pool, "approvals", "version", "INTEGER NOT NULL DEFAULT 0").await?;
+        Self::add_column_if_missing(pool, "receipt_records", "karta_pid", "TEXT").await?;
+        Self::add_column_if_missing(pool, "receipt_records", "chain_seq", "INTEGER").await?;
+        Self::add_column_if_missing(pool, "receipt_records", "prev_receipt_hash", "TEXT").await?;
+        Self::add_column_if_missing(pool, "receipt_records", "chain_hash", "TEXT").await?;
+
         // Create indexes
         sqlx::query("CREATE INDEX IF NOT EXISTS idx_vakya_karta ON vakya_records(karta_pid)")
             .execute(pool).await?;
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_vakya_ns ON vakya_records(karma_ns)")
+            .execute(pool).await?;
         sqlx::query("CREATE INDEX IF NOT EXISTS idx_vakya_karma ON vakya_records(karma_rid)")
             .execute(pool).await?;
         sqlx::query("CREATE INDEX IF NOT EXISTS idx_vakya_action ON vakya_records(kriya_action)")
@@ -339,6 +658,14 @@ impl SqliteIndexDb {
             .execute(pool).await?;
         sqlx::query("CREATE INDEX IF NOT EXISTS idx_receipt_vakya ON receipt_records(vakya_id)")
             .execute(pool).await?;
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_receipt_chain ON receipt_records(karta_pid, chain_seq)")
+            .execute(pool).await?;
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_receipt_reason ON receipt_records(reason_code)")
+            .execute(pool).await?;
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_approval_vakya ON approvals(vakya_id)")
+            .execute(pool).await?;
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_approval_status ON approvals(status)")
+            .execute(pool).await?;
         sqlx::query("CREATE INDEX IF NOT EXISTS idx_audit_type ON audit_log(event_type)")
             .execute(pool).await?;
         sqlx::query("CREATE INDEX IF NOT EXISTS idx_audit_created ON audit_log(created_at)")
@@ -390,55 +717,523 @@ impl SqliteIndexDb {
         Ok(())
     }
 
-    /// Rebuild Merkle trees from existing data
+    /// Add a column to a table created by an older schema version
+    async fn add_column_if_missing(pool: &SqlitePool, table: &str, column: &str, decl: &str) -> IndexDbResult<()> {
+        let columns = sqlx::query(&format!("PRAGMA table_info({})", table))
+            .fetch_all(pool)
+            .await?;
+        if columns.iter().any(|row| row.get::<String, _>("name") == column) {
+            return Ok(());
+        }
+
+        sqlx::query(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, decl))
+            .execute(pool)
+            .await?;
+        info!(table, column, "Added missing column");
+        Ok(())
+    }
+
+    /// Restore Merkle trees from persisted nodes and catch up on new records
     async fn rebuild_merkle_trees(&self) -> IndexDbResult<()> {
-        // Rebuild VĀKYA tree
-        let vakya_hashes: Vec<(i64, String)> = sqlx::query_as(
-            "SELECT leaf_index, vakya_hash FROM vakya_records WHERE leaf_index IS NOT NULL ORDER BY leaf_index"
-        ).fetch_all(&self.pool).await?;
-        
-        let mut vakya_tree = self.vakya_tree.write().await;
-        for (_, hash) in vakya_hashes {
-            vakya_tree.append(&hash);
+        for tree_type in [TreeType::Vakya, TreeType::Effect, TreeType::Receipt, TreeType::Packet] {
+            self.restore_tree(tree_type).await?;
         }
-        drop(vakya_tree);
 
-        // Rebuild effect tree
-        let effect_hashes: Vec<(i64, String)> = sqlx::query_as(
-            "SELECT leaf_index, id FROM effect_records WHERE leaf_index IS NOT NULL ORDER BY leaf_index"
-        ).fetch_all(&self.pool).await?;
-        
-        let mut effect_tree = self.effect_tree.write().await;
-        for (_, id) in effect_hashes {
-            effect_tree.append(&id);
+        info!("Merkle trees rebuilt from existing data");
+        Ok(())
+    }
+
+    /// Resume one tree from the right edge stored in `merkle_nodes`,
+    /// append records logged since its nodes were last persisted, and
+    /// persist the new nodes
+    ///
+    /// Only the frontier is read back, and new leaves are hashed a page at a
+    /// time, so neither startup cost nor memory grows with the size of the log.
+    async fn restore_tree(&self, tree_type: TreeType) -> IndexDbResult<()> {
+        let (table, column) = leaf_source(tree_type);
+        let algorithm = self.get_tree(tree_type).read().await.algorithm();
+
+        let (count, size): (i64, i64) = sqlx::query_as(
+            "SELECT COUNT(*), COALESCE(MAX(index_in_level) + 1, 0) FROM merkle_nodes WHERE tree_type = ? AND level = 0"
+        )
+        .bind(tree_type.to_string())
+        .fetch_one(&self.pool)
+        .await?;
+        let resumed = if count == size {
+            let stored = self.load_merkle_nodes(tree_type, &MerkleTree::frontier_nodes(size as usize)).await?;
+            MerkleTree::resume(algorithm, size as usize, &stored)
+        } else {
+            Err(IndexDbError::MerkleError(format!("{} of {} leaves are stored", count, size)))
+        };
+
+        let mut tree = match resumed {
+            Ok(tree) if self.last_leaf_matches(&tree, table, column).await? => tree,
+            Ok(_) => {
+                warn!(tree_type = %tree_type, "Persisted Merkle nodes do not match the log; rebuilding");
+                self.clear_merkle_nodes(tree_type).await?;
+                MerkleTree::new_with(algorithm)
+            }
+            Err(e) => {
+                warn!(tree_type = %tree_type, error = %e, "Persisted Merkle nodes are unusable; rebuilding");
+                self.clear_merkle_nodes(tree_type).await?;
+                MerkleTree::new_with(algorithm)
+            }
+        };
+
+        let persisted = tree.size();
+        let mut last_index = persisted as i64 - 1;
+        loop {
+            let page: Vec<(i64, String)> = sqlx::query_as(&format!(
+                "SELECT leaf_index, {} FROM {} WHERE leaf_index > ? ORDER BY leaf_index LIMIT ?",
+                column, table
+            ))
+            .bind(last_index)
+            .bind(MERKLE_RESTORE_PAGE)
+            .fetch_all(&self.pool)
+            .await?;
+            let Some((last, _)) = page.last() else { break };
+            last_index = *last;
+
+            for (_, data) in &page {
+                tree.append(data);
+            }
+            self.flush_tree(tree_type, &mut tree).await?;
         }
-        drop(effect_tree);
+        debug!(tree_type = %tree_type, persisted, appended = tree.size() - persisted, "Restored Merkle tree");
 
-        // Rebuild receipt tree
-        let receipt_hashes: Vec<(i64, String)> = sqlx::query_as(
-            "SELECT leaf_index, vakya_hash FROM receipt_records WHERE leaf_index IS NOT NULL ORDER BY leaf_index"
-        ).fetch_all(&self.pool).await?;
-        
-        let mut receipt_tree = self.receipt_tree.write().await;
-        for (_, hash) in receipt_hashes {
-            receipt_tree.append(&hash);
+        *self.get_tree(tree_type).write().await = tree;
+        Ok(())
+    }
+
+    /// Whether the restored tree's last leaf is the record logged at that index
+    async fn last_leaf_matches(&self, tree: &MerkleTree, table: &str, column: &str) -> IndexDbResult<bool> {
+        let Some(last) = tree.size().checked_sub(1) else {
+            return Ok(true);
+        };
+        let data: Option<String> = sqlx::query_scalar(&format!(
+            "SELECT {} FROM {} WHERE leaf_index = ?",
+            column, table
+        ))
+        .bind(last as i64)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(data.is_some_and(|data| tree.last_leaf() == Some(&tree.leaf_hash(&data))))
+    }
+
+    /// Persist tree nodes completed since they were last written
+    ///
+    /// Nodes are written after every append; this retries any a failed
+    /// write left behind, and runs whenever a checkpoint is stored.
+    pub async fn persist_merkle_nodes(&self, tree_type: TreeType) -> IndexDbResult<()> {
+        let mut tree = self.get_tree(tree_type).write().await;
+        self.flush_tree(tree_type, &mut tree).await
+    }
+
+    /// Write a tree's unpersisted nodes in one transaction
+    async fn flush_tree(&self, tree_type: TreeType, tree: &mut MerkleTree) -> IndexDbResult<()> {
+        self.write_merkle_nodes(tree_type, &tree.unpersisted()).await?;
+        tree.clear_unpersisted();
+        Ok(())
+    }
+
+    /// Flush after an append without failing it
+    ///
+    /// Nodes that fail to write stay in memory and go out with the next flush.
+    async fn flush_after_append(&self, tree_type: TreeType, tree: &mut MerkleTree) {
+        if let Err(e) = self.flush_tree(tree_type, tree).await {
+            warn!(tree_type = %tree_type, error = %e, "Failed to persist Merkle nodes; retrying on the next write");
         }
+    }
 
-        // Rebuild packet tree
-        let packet_hashes: Vec<(i64, String)> = sqlx::query_as(
-            "SELECT leaf_index, packet_cid FROM packet_records WHERE leaf_index IS NOT NULL ORDER BY leaf_index"
-        ).fetch_all(&self.pool).await?;
-        
-        let mut packet_tree = self.packet_tree.write().await;
-        for (_, cid) in packet_hashes {
-            packet_tree.append(&cid);
+    async fn write_merkle_nodes(&self, tree_type: TreeType, nodes: &[MerkleNode]) -> IndexDbResult<()> {
+        if nodes.is_empty() {
+            return Ok(());
         }
-        drop(packet_tree);
 
-        info!("Merkle trees rebuilt from existing data");
+        let tree_type = tree_type.to_string();
+        let mut tx = self.pool.begin().await?;
+        for node in nodes {
+            sqlx::query(
+                "INSERT OR IGNORE INTO merkle_nodes (tree_type, level, index_in_level, hash) VALUES (?, ?, ?, ?)"
+            )
+            .bind(&tree_type)
+            .bind(node.level as i64)
+            .bind(node.index as i64)
+            .bind(&node.hash)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
         Ok(())
     }
 
+    /// Read the given nodes of a tree back from `merkle_nodes`
+    async fn load_merkle_nodes(&self, tree_type: TreeType, nodes: &[(usize, usize)]) -> IndexDbResult<StoredNodes> {
+        let mut stored = StoredNodes::new();
+        for chunk in nodes.chunks(MERKLE_NODE_CHUNK) {
+            let sql = format!(
+                "SELECT level, index_in_level, hash FROM merkle_nodes WHERE tree_type = ? AND (level, index_in_level) IN (VALUES {})",
+                vec!["(?, ?)"; chunk.len()].join(", ")
+            );
+            let mut query = sqlx::query_as::<_, (i64, i64, String)>(&sql).bind(tree_type.to_string());
+            for &(level, index) in chunk {
+                query = query.bind(level as i64).bind(index as i64);
+            }
+            for (level, index, hash) in query.fetch_all(&self.pool).await? {
+                stored.insert((level as usize, index as usize), hash);
+            }
+        }
+        Ok(stored)
+    }
+
+    /// Read the first `size` leaf hashes of a tree back from `merkle_nodes`
+    async fn load_merkle_leaves(&self, tree_type: TreeType, size: usize) -> IndexDbResult<StoredNodes> {
+        let rows: Vec<(i64, String)> = sqlx::query_as(
+            "SELECT index_in_level, hash FROM merkle_nodes WHERE tree_type = ? AND level = 0 AND index_in_level < ?"
+        )
+        .bind(tree_type.to_string())
+        .bind(size as i64)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(|(index, hash)| ((0, index as usize), hash)).collect())
+    }
+
+    /// Inclusion proof for one leaf, with its path nodes read from storage
+    async fn inclusion_proof(&self, tree_type: TreeType, tree: &MerkleTree, leaf_index: usize) -> IndexDbResult<Option<MerkleProof>> {
+        let stored = self.load_merkle_nodes(tree_type, &tree.proof_nodes(leaf_index)).await?;
+        Ok(tree.get_proof(leaf_index, &stored))
+    }
+
+    /// Root a tree had at `size` leaves, from nodes read from storage
+    async fn root_at(&self, tree_type: TreeType, tree: &MerkleTree, size: usize) -> IndexDbResult<Option<String>> {
+        let stored = self.load_merkle_nodes(tree_type, &tree.root_nodes(size)).await?;
+        Ok(tree.root_at(size, &stored))
+    }
+
+    async fn clear_merkle_nodes(&self, tree_type: TreeType) -> IndexDbResult<()> {
+        sqlx::query("DELETE FROM merkle_nodes WHERE tree_type = ?")
+            .bind(tree_type.to_string())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Check a record's stored hash against its JSON when verification is on
+    fn verified(&self, record: VakyaRecord) -> IndexDbResult<VakyaRecord> {
+        if !self.verify_hashes {
+            return Ok(record);
+        }
+
+        let computed = hash_value(&record.vakya_json)
+            .map_err(|e| IndexDbError::InvalidRecord(e.to_string()))?
+            .value;
+        if computed != record.vakya_hash {
+            warn!(vakya_id = %record.vakya_id, "Stored VĀKYA hash does not match its content");
+            return Err(IndexDbError::IntegrityMismatch {
+                vakya_id: record.vakya_id,
+                stored: record.vakya_hash,
+                computed,
+            });
+        }
+        Ok(record)
+    }
+
+    /// Convert a SQLite row to a VakyaRecord
+    fn row_to_vakya_record(row: &sqlx::sqlite::SqliteRow) -> IndexDbResult<VakyaRecord> {
+        let effect_str: String = row.get("expected_effect");
+        let vakya_json_str: String = row.get("vakya_json");
+
+        Ok(VakyaRecord {
+            id: row.get::<String, _>("id").parse().unwrap_or_default(),
+            vakya_id: row.get("vakya_id"),
+            vakya_hash: row.get("vakya_hash"),
+            karta_pid: row.get("karta_pid"),
+            karta_type: row.get("karta_type"),
+            karma_rid: row.get("karma_rid"),
+            karma_kind: row.get("karma_kind"),
+            karma_ns: row.get("karma_ns"),
+            kriya_action: row.get("kriya_action"),
+            expected_effect: serde_json::from_str(&effect_str).unwrap_or(EffectBucket::None),
+            cap_ref: row.get("cap_ref"),
+            vakya_json: serde_json::from_str(&vakya_json_str).unwrap_or_default(),
+            signature: row.get("signature"),
+            key_id: row.get("key_id"),
+            trace_id: row.get("trace_id"),
+            span_id: row.get("span_id"),
+            parent_span_id: row.get("parent_span_id"),
+            created_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+            leaf_index: row.get("leaf_index"),
+            merkle_root: row.get("merkle_root"),
+        })
+    }
+
+    /// Convert a SQLite row to an EffectRecord
+    ///
+    /// Encrypted state is decrypted with `state_key`; without the wrapping
+    /// key it is left out of the record.
+    fn row_to_effect_record(row: &sqlx::sqlite::SqliteRow, state_key: Option<&MasterKey>) -> IndexDbResult<EffectRecord> {
+        let id: String = row.get("id");
+        let effect_str: String = row.get("effect_bucket");
+        let mut before_state_str: Option<String> = row.get("before_state");
+        let mut after_state_str: Option<String> = row.get("after_state");
+        let mut delta_str: Option<String> = row.get("delta");
+        let reversal_str: Option<String> = row.get("reversal_instructions");
+        let wrapped_str: Option<String> = row.get("state_key");
+
+        if let Some(wrapped_str) = wrapped_str {
+            let wrapped: WrappedKey = serde_json::from_str(&wrapped_str)?;
+            match state_key.filter(|key| key.key_id() == wrapped.kid) {
+                Some(key) => {
+                    let data_key = key.unwrap_key(&wrapped).map_err(|e| {
+                        IndexDbError::IntegrityViolation(format!("Cannot unwrap state key for effect {}: {}", id, e))
+                    })?;
+                    before_state_str = Self::open_state(&data_key, &id, "before_state", before_state_str)?;
+                    after_state_str = Self::open_state(&data_key, &id, "after_state", after_state_str)?;
+                    delta_str = Self::open_state(&data_key, &id, "delta", delta_str)?;
+                }
+                None => {
+                    debug!(effect_id = %id, kid = %wrapped.kid, "State key unavailable, omitting encrypted state");
+                    before_state_str = None;
+                    after_state_str = None;
+                    delta_str = None;
+                }
+            }
+        }
+
+        Ok(EffectRecord {
+            id: id.parse().unwrap_or_default(),
+            vakya_id: row.get("vakya_id"),
+            effect_bucket: serde_json::from_str(&effect_str).unwrap_or(EffectBucket::None),
+            target_rid: row.get("target_rid"),
+            target_kind: row.get("target_kind"),
+            before_hash: row.get("before_hash"),
+            after_hash: row.get("after_hash"),
+            before_state: before_state_str.and_then(|s| serde_json::from_str(&s).ok()),
+            after_state: after_state_str.and_then(|s| serde_json::from_str(&s).ok()),
+            delta: delta_str.and_then(|s| serde_json::from_str(&s).ok()),
+            reversible: row.get("reversible"),
+            reversal_instructions: reversal_str.and_then(|s| serde_json::from_str(&s).ok()),
+            created_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+            leaf_index: row.get("leaf_index"),
+        })
+    }
+
+    /// Encrypt a state column, bound to its effect and column name
+    fn seal_state(data_key: &DataKey, effect_id: &str, column: &str, value: Option<String>) -> IndexDbResult<Option<String>> {
+        value.map(|v| {
+            data_key.seal(v.as_bytes(), format!("{}:{}", effect_id, column).as_bytes())
+                .map_err(|e| IndexDbError::InvalidRecord(format!("Cannot encrypt {}: {}", column, e)))
+        })
+        .transpose()
+    }
+
+    /// Decrypt a state column sealed by `seal_state`
+    fn open_state(data_key: &DataKey, effect_id: &str, column: &str, value: Option<String>) -> IndexDbResult<Option<String>> {
+        value.map(|v| {
+            let plaintext = data_key.open(&v, format!("{}:{}", effect_id, column).as_bytes())
+                .map_err(|e| IndexDbError::IntegrityViolation(format!("Cannot decrypt {} of effect {}: {}", column, effect_id, e)))?;
+            String::from_utf8(plaintext)
+                .map_err(|e| IndexDbError::IntegrityViolation(format!("Decrypted {} is not UTF-8: {}", column, e)))
+        })
+        .transpose()
+    }
+
+    /// Convert a SQLite row to a ReceiptRecord
+    fn row_to_receipt_record(row: &sqlx::sqlite::SqliteRow) -> IndexDbResult<ReceiptRecord> {
+        let reason_code_str: String = row.get("reason_code");
+        let effect_ids_str: String = row.get("effect_ids");
+        let receipt_json_str: String = row.get("receipt_json");
+
+        Ok(ReceiptRecord {
+            id: row.get::<String, _>("id").parse().unwrap_or_default(),
+            vakya_id: row.get("vakya_id"),
+            vakya_hash: row.get("vakya_hash"),
+            reason_code: serde_json::from_str(&reason_code_str).unwrap_or(aapi_core::error::ReasonCode::InternalError),
+            message: row.get("message"),
+            duration_ms: row.get("duration_ms"),
+            effect_ids: serde_json::from_str(&effect_ids_str).unwrap_or_default(),
+            executor_id: row.get("executor_id"),
+            signature: row.get("signature"),
+            key_id: row.get("key_id"),
+            created_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+            receipt_json: serde_json::from_str(&receipt_json_str).unwrap_or_default(),
+            leaf_index: row.get("leaf_index"),
+            karta_pid: row.get("karta_pid"),
+            chain_seq: row.get("chain_seq"),
+            prev_receipt_hash: row.get("prev_receipt_hash"),
+            chain_hash: row.get("chain_hash"),
+        })
+    }
+
+    /// Convert a SQLite row to an ActorChainHead
+    fn row_to_chain_head(row: &sqlx::sqlite::SqliteRow) -> ActorChainHead {
+        ActorChainHead {
+            karta_pid: row.get("karta_pid"),
+            head_hash: row.get("head_hash"),
+            head_vakya_id: row.get("head_vakya_id"),
+            length: row.get("length"),
+            updated_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("updated_at"))
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+        }
+    }
+
+    /// Write a receipt row and advance its actor's chain head
+    async fn insert_receipt(&self, record: &ReceiptRecord) -> IndexDbResult<()> {
+        let reason_code_str = serde_json::to_string(&record.reason_code)?;
+        let effect_ids_str = serde_json::to_string(&record.effect_ids)?;
+        let receipt_json_str = serde_json::to_string(&record.receipt_json)?;
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(r#"
+            INSERT INTO receipt_records (
+                id, vakya_id, vakya_hash, reason_code, message, duration_ms,
+                effect_ids, executor_id, signature, key_id, created_at, receipt_json, leaf_index,
+                karta_pid, chain_seq, prev_receipt_hash, chain_hash
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#)
+        .bind(record.id.to_string())
+        .bind(&record.vakya_id)
+        .bind(&record.vakya_hash)
+        .bind(&reason_code_str)
+        .bind(&record.message)
+        .bind(record.duration_ms)
+        .bind(&effect_ids_str)
+        .bind(&record.executor_id)
+        .bind(&record.signature)
+        .bind(&record.key_id)
+        .bind(record.created_at.to_rfc3339())
+        .bind(&receipt_json_str)
+        .bind(record.leaf_index)
+        .bind(&record.karta_pid)
+        .bind(record.chain_seq)
+        .bind(&record.prev_receipt_hash)
+        .bind(&record.chain_hash)
+        .execute(&mut *tx)
+        .await?;
+
+        if let (Some(karta_pid), Some(chain_hash), Some(seq)) = (&record.karta_pid, &record.chain_hash, record.chain_seq) {
+            sqlx::query(r#"
+                INSERT INTO actor_chain_heads (karta_pid, head_hash, head_vakya_id, length, updated_at)
+                VALUES (?, ?, ?, ?, ?)
+                ON CONFLICT(karta_pid) DO UPDATE SET
+                    head_hash = excluded.head_hash,
+                    head_vakya_id = excluded.head_vakya_id,
+                    length = excluded.length,
+                    updated_at = excluded.updated_at
+            "#)
+            .bind(karta_pid)
+            .bind(chain_hash)
+            .bind(&record.vakya_id)
+            .bind(seq + 1)
+            .bind(Utc::now().to_rfc3339())
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Append each effect to the tree and insert it, all in one transaction
+    async fn insert_effects(&self, tree: &mut MerkleTree, records: &mut [EffectRecord]) -> IndexDbResult<()> {
+        let mut tx = self.pool.begin().await?;
+        for record in records.iter_mut() {
+            record.leaf_index = Some(tree.append(&record.id.to_string()) as i64);
+
+            let effect_bucket_str = serde_json::to_string(&record.effect_bucket)?;
+            let mut before_state_str = record.before_state.as_ref().map(serde_json::to_string).transpose()?;
+            let mut after_state_str = record.after_state.as_ref().map(serde_json::to_string).transpose()?;
+            let mut delta_str = record.delta.as_ref().map(serde_json::to_string).transpose()?;
+            let reversal_str = record.reversal_instructions.as_ref().map(serde_json::to_string).transpose()?;
+
+            let mut wrapped_str = None;
+            if let Some(key) = &self.state_key {
+                let (data_key, wrapped) = key.new_data_key()
+                    .map_err(|e| IndexDbError::InvalidRecord(format!("Cannot create state key: {}", e)))?;
+                let effect_id = record.id.to_string();
+                before_state_str = Self::seal_state(&data_key, &effect_id, "before_state", before_state_str)?;
+                after_state_str = Self::seal_state(&data_key, &effect_id, "after_state", after_state_str)?;
+                delta_str = Self::seal_state(&data_key, &effect_id, "delta", delta_str)?;
+                wrapped_str = Some(serde_json::to_string(&wrapped)?);
+            }
+
+            sqlx::query(r#"
+                INSERT INTO effect_records (
+                    id, vakya_id, effect_bucket, target_rid, target_kind,
+                    before_hash, after_hash, before_state, after_state, delta,
+                    reversible, reversal_instructions, created_at, leaf_index, state_key
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#)
+            .bind(record.id.to_string())
+            .bind(&record.vakya_id)
+            .bind(&effect_bucket_str)
+            .bind(&record.target_rid)
+            .bind(&record.target_kind)
+            .bind(&record.before_hash)
+            .bind(&record.after_hash)
+            .bind(&before_state_str)
+            .bind(&after_state_str)
+            .bind(&delta_str)
+            .bind(record.reversible)
+            .bind(&reversal_str)
+            .bind(record.created_at.to_rfc3339())
+            .bind(record.leaf_index)
+            .bind(&wrapped_str)
+            .execute(&mut *tx)
+            .await?;
+
+            debug!(effect_id = %record.id, vakya_id = %record.vakya_id, "Stored effect record");
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Size of the database file in bytes
+    async fn database_size(&self) -> IndexDbResult<u64> {
+        let pages: i64 = sqlx::query_scalar("PRAGMA page_count").fetch_one(&self.pool).await?;
+        let page_size: i64 = sqlx::query_scalar("PRAGMA page_size").fetch_one(&self.pool).await?;
+        Ok((pages * page_size) as u64)
+    }
+
+    /// Convert a SQLite row to a CapabilityRevocation
+    fn row_to_revocation(row: &sqlx::sqlite::SqliteRow) -> CapabilityRevocation {
+        CapabilityRevocation {
+            token_id: row.get("token_id"),
+            include_delegations: row.get("include_delegations"),
+            reason: row.get("reason"),
+            revoked_by: row.get("revoked_by"),
+            revoked_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("revoked_at"))
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+        }
+    }
+
+    /// Convert a SQLite row to an AuditLogEntry
+    fn row_to_audit_entry(row: &sqlx::sqlite::SqliteRow) -> IndexDbResult<AuditLogEntry> {
+        let event_type_str: String = row.get("event_type");
+        let details_str: String = row.get("details");
+
+        Ok(AuditLogEntry {
+            id: row.get::<String, _>("id").parse().unwrap_or_default(),
+            event_type: serde_json::from_str(&event_type_str)?,
+            actor: row.get("actor"),
+            target: row.get("target"),
+            details: serde_json::from_str(&details_str).unwrap_or_default(),
+            created_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+            source_ip: row.get("source_ip"),
+            user_agent: row.get("user_agent"),
+        })
+    }
+
     /// Convert a SQLite row to a SessionRecord
     fn row_to_session_record(row: &sqlx::sqlite::SqliteRow) -> IndexDbResult<SessionRecord> {
         let metadata_str: String = row.get("metadata");
@@ -508,6 +1303,65 @@ impl SqliteIndexDb {
         })
     }
 
+    /// Convert a SQLite row to an ApprovalRecord
+    fn row_to_approval_record(row: &sqlx::sqlite::SqliteRow) -> IndexDbResult<ApprovalRecord> {
+        let approvers_str: String = row.get("approvers");
+        let weighted_str: String = row.get("weighted_approvers");
+        let status_str: String = row.get("status");
+        let votes_str: String = row.get("votes");
+        let expires_at_str: Option<String> = row.get("expires_at");
+        let resolved_at_str: Option<String> = row.get("resolved_at");
+
+        Ok(ApprovalRecord {
+            id: row.get::<String, _>("id").parse().unwrap_or_default(),
+            approval_id: row.get("approval_id"),
+            vakya_id: row.get("vakya_id"),
+            vakya_hash: row.get("vakya_hash"),
+            rule_id: row.get("rule_id"),
+            approval_type: row.get("approval_type"),
+            approvers: serde_json::from_str(&approvers_str).unwrap_or_default(),
+            min_approvals: row.get::<i64, _>("min_approvals") as u32,
+            weighted_approvers: serde_json::from_str(&weighted_str).unwrap_or_default(),
+            required_weight: row.get::<Option<i64>, _>("required_weight").map(|w| w as u32),
+            status: serde_json::from_str(&status_str)?,
+            votes: serde_json::from_str(&votes_str).unwrap_or_default(),
+            reason: row.get("reason"),
+            created_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+            expires_at: expires_at_str.and_then(|s| DateTime::parse_from_rfc3339(&s).ok().map(|dt| dt.with_timezone(&Utc))),
+            resolved_at: resolved_at_str.and_then(|s| DateTime::parse_from_rfc3339(&s).ok().map(|dt| dt.with_timezone(&Utc))),
+            version: row.get::<i64, _>("version") as u32,
+        })
+    }
+
+    /// Pull the next record from an export cursor
+    async fn next_export_record(
+        tree_type: TreeType,
+        state_key: Option<&MasterKey>,
+        cursor: &mut BoxStream<'_, Result<sqlx::sqlite::SqliteRow, sqlx::Error>>,
+    ) -> IndexDbResult<Option<ExportRecord>> {
+        let Some(row) = cursor.try_next().await? else {
+            return Ok(None);
+        };
+
+        let record = match tree_type {
+            TreeType::Vakya => ExportRecord::Vakya(Self::row_to_vakya_record(&row)?),
+            TreeType::Effect => ExportRecord::Effect(Self::row_to_effect_record(&row, state_key)?),
+            TreeType::Receipt => ExportRecord::Receipt(Self::row_to_receipt_record(&row)?),
+            TreeType::Packet => return Err(IndexDbError::Query("Packet records cannot be exported".to_string())),
+        };
+        Ok(Some(record))
+    }
+
+    /// Write one JSONL line
+    async fn write_export_line(writer: &mut (dyn AsyncWrite + Unpin + Send), record: &ExportRecord) -> IndexDbResult<()> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+        writer.write_all(&line).await?;
+        Ok(())
+    }
+
     /// Get the Merkle tree for a given type
     fn get_tree(&self, tree_type: TreeType) -> &Arc<RwLock<MerkleTree>> {
         match tree_type {
@@ -519,27 +1373,38 @@ impl SqliteIndexDb {
     }
 }
 
+/// Table and column whose value each tree's leaves hash
+fn leaf_source(tree_type: TreeType) -> (&'static str, &'static str) {
+    match tree_type {
+        TreeType::Vakya => ("vakya_records", "vakya_hash"),
+        TreeType::Effect => ("effect_records", "id"),
+        TreeType::Receipt => ("receipt_records", "vakya_hash"),
+        TreeType::Packet => ("packet_records", "packet_cid"),
+    }
+}
+
 #[async_trait]
 impl IndexDbStore for SqliteIndexDb {
     async fn store_vakya(&self, mut record: VakyaRecord) -> IndexDbResult<VakyaRecord> {
-        // Add to Merkle tree
+        let effect_bucket_str = serde_json::to_string(&record.expected_effect)?;
+        let vakya_json_str = serde_json::to_string(&record.vakya_json)?;
+
+        // Add to Merkle tree, holding the lock until the row is in so a
+        // failed insert can take its leaf back out
         let mut tree = self.vakya_tree.write().await;
+        let mark = tree.mark();
         let leaf_index = tree.append(&record.vakya_hash);
-        let merkle_root = tree.root().map(|h| h.to_string());
-        drop(tree);
 
         record.leaf_index = Some(leaf_index as i64);
-        record.merkle_root = merkle_root;
+        record.merkle_root = tree.root().map(|h| h.to_string());
 
-        let effect_bucket_str = serde_json::to_string(&record.expected_effect)?;
-        let vakya_json_str = serde_json::to_string(&record.vakya_json)?;
-
-        sqlx::query(r#"
+        let inserted = sqlx::query(r#"
             INSERT INTO vakya_records (
-                id, vakya_id, vakya_hash, karta_pid, karta_type, karma_rid, karma_kind,
+                id, vakya_id, vakya_hash, karta_pid, karta_type, karma_rid, karma_kind, karma_ns,
                 kriya_action, expected_effect, cap_ref, vakya_json, signature, key_id,
                 trace_id, span_id, parent_span_id, created_at, leaf_index, merkle_root
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(vakya_id) DO NOTHING
         "#)
         .bind(record.id.to_string())
         .bind(&record.vakya_id)
@@ -548,6 +1413,7 @@ impl IndexDbStore for SqliteIndexDb {
         .bind(&record.karta_type)
         .bind(&record.karma_rid)
         .bind(&record.karma_kind)
+        .bind(&record.karma_ns)
         .bind(&record.kriya_action)
         .bind(&effect_bucket_str)
         .bind(&record.cap_ref)
@@ -561,7 +1427,21 @@ impl IndexDbStore for SqliteIndexDb {
         .bind(record.leaf_index)
         .bind(&record.merkle_root)
         .execute(&self.pool)
-        .await?;
+        .await;
+
+        match inserted {
+            Ok(result) if result.rows_affected() > 0 => {}
+            Ok(_) => {
+                tree.rollback(mark);
+                return Err(IndexDbError::DuplicateVakya(record.vakya_id));
+            }
+            Err(e) => {
+                tree.rollback(mark);
+                return Err(e.into());
+            }
+        }
+        self.flush_after_append(TreeType::Vakya, &mut tree).await;
+        drop(tree);
 
         debug!(vakya_id = %record.vakya_id, "Stored VĀKYA record");
         Ok(record)
@@ -576,78 +1456,49 @@ impl IndexDbStore for SqliteIndexDb {
         .await?;
 
         match row {
-            Some(row) => {
-                let effect_str: String = row.get("expected_effect");
-                let vakya_json_str: String = row.get("vakya_json");
-                
-                Ok(Some(VakyaRecord {
-                    id: row.get::<String, _>("id").parse().unwrap_or_default(),
-                    vakya_id: row.get("vakya_id"),
-                    vakya_hash: row.get("vakya_hash"),
-                    karta_pid: row.get("karta_pid"),
-                    karta_type: row.get("karta_type"),
-                    karma_rid: row.get("karma_rid"),
-                    karma_kind: row.get("karma_kind"),
-                    kriya_action: row.get("kriya_action"),
-                    expected_effect: serde_json::from_str(&effect_str).unwrap_or(EffectBucket::None),
-                    cap_ref: row.get("cap_ref"),
-                    vakya_json: serde_json::from_str(&vakya_json_str).unwrap_or_default(),
-                    signature: row.get("signature"),
-                    key_id: row.get("key_id"),
-                    trace_id: row.get("trace_id"),
-                    span_id: row.get("span_id"),
-                    parent_span_id: row.get("parent_span_id"),
-                    created_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))
-                        .map(|dt| dt.with_timezone(&Utc))
-                        .unwrap_or_else(|_| Utc::now()),
-                    leaf_index: row.get("leaf_index"),
-                    merkle_root: row.get("merkle_root"),
-                }))
-            }
+            Some(row) => Ok(Some(self.verified(Self::row_to_vakya_record(&row)?)?)),
             None => Ok(None),
         }
     }
 
-    async fn store_effect(&self, mut record: EffectRecord) -> IndexDbResult<EffectRecord> {
-        // Add to Merkle tree
-        let mut tree = self.effect_tree.write().await;
-        let leaf_index = tree.append(&record.id.to_string());
-        drop(tree);
+    async fn query_vakyas(&self, query: &VakyaQuery) -> IndexDbResult<Vec<VakyaRecord>> {
+        let (where_clause, params) = query.build_where_clause();
+        let sql = format!(
+            "SELECT * FROM vakya_records WHERE {} ORDER BY {} {}",
+            where_clause,
+            query.build_order_clause(),
+            query.build_limit_clause(),
+        );
 
-        record.leaf_index = Some(leaf_index as i64);
+        let mut q = sqlx::query(&sql);
+        for param in params {
+            q = q.bind(param);
+        }
+        let rows = q.fetch_all(&self.pool).await?;
 
-        let effect_bucket_str = serde_json::to_string(&record.effect_bucket)?;
-        let before_state_str = record.before_state.as_ref().map(|v| serde_json::to_string(v)).transpose()?;
-        let after_state_str = record.after_state.as_ref().map(|v| serde_json::to_string(v)).transpose()?;
-        let delta_str = record.delta.as_ref().map(|v| serde_json::to_string(v)).transpose()?;
-        let reversal_str = record.reversal_instructions.as_ref().map(|v| serde_json::to_string(v)).transpose()?;
+        rows.iter()
+            .map(|row| self.verified(Self::row_to_vakya_record(row)?))
+            .collect()
+    }
 
-        sqlx::query(r#"
-            INSERT INTO effect_records (
-                id, vakya_id, effect_bucket, target_rid, target_kind,
-                before_hash, after_hash, before_state, after_state, delta,
-                reversible, reversal_instructions, created_at, leaf_index
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-        "#)
-        .bind(record.id.to_string())
-        .bind(&record.vakya_id)
-        .bind(&effect_bucket_str)
-        .bind(&record.target_rid)
-        .bind(&record.target_kind)
-        .bind(&record.before_hash)
-        .bind(&record.after_hash)
-        .bind(&before_state_str)
-        .bind(&after_state_str)
-        .bind(&delta_str)
-        .bind(record.reversible)
-        .bind(&reversal_str)
-        .bind(record.created_at.to_rfc3339())
-        .bind(record.leaf_index)
-        .execute(&self.pool)
-        .await?;
+    async fn store_effect(&self, record: EffectRecord) -> IndexDbResult<EffectRecord> {
+        let mut stored = self.store_effects(vec![record]).await?;
+        Ok(stored.remove(0))
+    }
 
-        debug!(effect_id = %record.id, vakya_id = %record.vakya_id, "Stored effect record");
-        Ok(record)
+    async fn store_effects(&self, mut records: Vec<EffectRecord>) -> IndexDbResult<Vec<EffectRecord>> {
+        // Hold the tree until the batch commits so a failed batch can take
+        // its leaves back out
+        let mut tree = self.effect_tree.write().await;
+        let mark = tree.mark();
+        if let Err(e) = self.insert_effects(&mut tree, &mut records).await {
+            tree.rollback(mark);
+            return Err(e);
+        }
+        self.flush_after_append(TreeType::Effect, &mut tree).await;
+        drop(tree);
+
+        Ok(records)
     }
 
     async fn get_effects(&self, vakya_id: &str) -> IndexDbResult<Vec<EffectRecord>> {
@@ -658,58 +1509,95 @@ impl IndexDbStore for SqliteIndexDb {
         .fetch_all(&self.pool)
         .await?;
 
-        let mut effects = Vec::with_capacity(rows.len());
-        for row in rows {
-            let effect_str: String = row.get("effect_bucket");
-            let before_state_str: Option<String> = row.get("before_state");
-            let after_state_str: Option<String> = row.get("after_state");
-            let delta_str: Option<String> = row.get("delta");
-            let reversal_str: Option<String> = row.get("reversal_instructions");
+        rows.iter().map(|row| Self::row_to_effect_record(row, self.state_key.as_ref())).collect()
+    }
 
-            effects.push(EffectRecord {
-                id: row.get::<String, _>("id").parse().unwrap_or_default(),
-                vakya_id: row.get("vakya_id"),
-                effect_bucket: serde_json::from_str(&effect_str).unwrap_or(EffectBucket::None),
-                target_rid: row.get("target_rid"),
-                target_kind: row.get("target_kind"),
-                before_hash: row.get("before_hash"),
-                after_hash: row.get("after_hash"),
-                before_state: before_state_str.and_then(|s| serde_json::from_str(&s).ok()),
-                after_state: after_state_str.and_then(|s| serde_json::from_str(&s).ok()),
-                delta: delta_str.and_then(|s| serde_json::from_str(&s).ok()),
-                reversible: row.get("reversible"),
-                reversal_instructions: reversal_str.and_then(|s| serde_json::from_str(&s).ok()),
-                created_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))
-                    .map(|dt| dt.with_timezone(&Utc))
-                    .unwrap_or_else(|_| Utc::now()),
-                leaf_index: row.get("leaf_index"),
-            });
-        }
+    async fn get_effect(&self, effect_id: &str) -> IndexDbResult<Option<EffectRecord>> {
+        let row = sqlx::query("SELECT * FROM effect_records WHERE id = ?")
+            .bind(effect_id)
+            .fetch_optional(&self.pool)
+            .await?;
 
-        Ok(effects)
+        row.as_ref().map(|row| Self::row_to_effect_record(row, self.state_key.as_ref())).transpose()
     }
 
     async fn store_receipt(&self, mut record: ReceiptRecord) -> IndexDbResult<ReceiptRecord> {
-        // Add to Merkle tree
+        let _chain = self.chain_lock.lock().await;
+
+        // Extend the actor's chain; receipts for unknown VĀKYAs stay unchained
+        let karta_pid: Option<String> = sqlx::query_scalar("SELECT karta_pid FROM vakya_records WHERE vakya_id = ?")
+            .bind(&record.vakya_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        let head = match &karta_pid {
+            Some(karta_pid) => self.get_actor_chain_head(karta_pid).await?,
+            None => None,
+        };
+        if let Some(karta_pid) = karta_pid {
+            let prev = head.as_ref().map(|h| h.head_hash.clone());
+            record.chain_hash = Some(receipt_chain_hash(
+                self.hash_algorithm,
+                prev.as_deref(),
+                &record.vakya_id,
+                &record.vakya_hash,
+            ));
+            record.chain_seq = Some(head.as_ref().map_or(0, |h| h.length));
+            record.prev_receipt_hash = prev;
+            record.karta_pid = Some(karta_pid);
+        }
+
+        // Add to Merkle tree, holding the lock until the row is in so a
+        // failed insert can take its leaf back out
         let mut tree = self.receipt_tree.write().await;
+        let mark = tree.mark();
         let leaf_index = tree.append(&record.vakya_hash);
+        record.leaf_index = Some(leaf_index as i64);
+
+        if let Err(e) = self.insert_receipt(&record).await {
+            tree.rollback(mark);
+            return Err(e);
+        }
+        self.flush_after_append(TreeType::Receipt, &mut tree).await;
         drop(tree);
 
-        record.leaf_index = Some(leaf_index as i64);
+        debug!(vakya_id = %record.vakya_id, "Stored receipt record");
+        Ok(record)
+    }
+
+    async fn get_receipt(&self, vakya_id: &str) -> IndexDbResult<Option<ReceiptRecord>> {
+        let row = sqlx::query(
+            "SELECT * FROM receipt_records WHERE vakya_id = ?"
+        )
+        .bind(vakya_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.as_ref().map(Self::row_to_receipt_record).transpose()
+    }
+
+    async fn update_receipt(&self, mut record: ReceiptRecord) -> IndexDbResult<ReceiptRecord> {
+        let existing = self.get_receipt(&record.vakya_id).await?
+            .ok_or_else(|| IndexDbError::NotFound(format!("Receipt not found for: {}", record.vakya_id)))?;
+
+        // The receipt tree leaf and chain link cover only the VĀKYA and its
+        // hash, so they stay valid across updates
+        record.id = existing.id;
+        record.leaf_index = existing.leaf_index;
+        record.karta_pid = existing.karta_pid;
+        record.chain_seq = existing.chain_seq;
+        record.prev_receipt_hash = existing.prev_receipt_hash;
+        record.chain_hash = existing.chain_hash;
 
         let reason_code_str = serde_json::to_string(&record.reason_code)?;
         let effect_ids_str = serde_json::to_string(&record.effect_ids)?;
         let receipt_json_str = serde_json::to_string(&record.receipt_json)?;
 
         sqlx::query(r#"
-            INSERT INTO receipt_records (
-                id, vakya_id, vakya_hash, reason_code, message, duration_ms,
-                effect_ids, executor_id, signature, key_id, created_at, receipt_json, leaf_index
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            UPDATE receipt_records SET
+                reason_code = ?, message = ?, duration_ms = ?, effect_ids = ?, executor_id = ?,
+                signature = ?, key_id = ?, created_at = ?, receipt_json = ?
+            WHERE vakya_id = ?
         "#)
-        .bind(record.id.to_string())
-        .bind(&record.vakya_id)
-        .bind(&record.vakya_hash)
         .bind(&reason_code_str)
         .bind(&record.message)
         .bind(record.duration_ms)
@@ -719,55 +1607,233 @@ impl IndexDbStore for SqliteIndexDb {
         .bind(&record.key_id)
         .bind(record.created_at.to_rfc3339())
         .bind(&receipt_json_str)
-        .bind(record.leaf_index)
+        .bind(&record.vakya_id)
         .execute(&self.pool)
         .await?;
 
-        debug!(vakya_id = %record.vakya_id, "Stored receipt record");
+        debug!(vakya_id = %record.vakya_id, "Updated receipt record");
         Ok(record)
     }
 
-    async fn get_receipt(&self, vakya_id: &str) -> IndexDbResult<Option<ReceiptRecord>> {
+    async fn receipts_with_reason(&self, reason_code: aapi_core::error::ReasonCode) -> IndexDbResult<Vec<ReceiptRecord>> {
+        let rows = sqlx::query(
+            "SELECT * FROM receipt_records WHERE reason_code = ? ORDER BY created_at"
+        )
+        .bind(serde_json::to_string(&reason_code)?)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(Self::row_to_receipt_record).collect()
+    }
+
+    async fn get_actor_chain_head(&self, karta_pid: &str) -> IndexDbResult<Option<ActorChainHead>> {
+        let row = sqlx::query("SELECT * FROM actor_chain_heads WHERE karta_pid = ?")
+            .bind(karta_pid)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.as_ref().map(Self::row_to_chain_head))
+    }
+
+    async fn verify_actor_chain(&self, karta_pid: &str) -> IndexDbResult<ActorChainHead> {
+        let head = self.get_actor_chain_head(karta_pid).await?
+            .ok_or_else(|| IndexDbError::NotFound(format!("No receipt chain for: {}", karta_pid)))?;
+
+        let rows = sqlx::query(
+            "SELECT vakya_id, vakya_hash, chain_seq, prev_receipt_hash, chain_hash FROM receipt_records WHERE karta_pid = ? ORDER BY chain_seq"
+        )
+        .bind(karta_pid)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let broken = |reason: String| IndexDbError::IntegrityViolation(format!(
+            "Receipt chain for {} is broken: {}", karta_pid, reason
+        ));
+
+        let mut prev: Option<String> = None;
+        for (expected_seq, row) in rows.iter().enumerate() {
+            let vakya_id: String = row.get("vakya_id");
+            let seq: Option<i64> = row.get("chain_seq");
+            if seq != Some(expected_seq as i64) {
+                return Err(broken(format!(
+                    "receipt {} is at position {:?}, expected {}", vakya_id, seq, expected_seq
+                )));
+            }
+            let prev_receipt_hash: Option<String> = row.get("prev_receipt_hash");
+            if prev_receipt_hash != prev {
+                return Err(broken(format!("receipt {} does not link to its predecessor", vakya_id)));
+            }
+            let chain_hash: Option<String> = row.get("chain_hash");
+            let computed = receipt_chain_hash(
+                self.hash_algorithm,
+                prev.as_deref(),
+                &vakya_id,
+                &row.get::<String, _>("vakya_hash"),
+            );
+            if chain_hash.as_deref() != Some(computed.as_str()) {
+                return Err(broken(format!("receipt {} hash does not match its contents", vakya_id)));
+            }
+            prev = chain_hash;
+        }
+
+        if rows.len() as i64 != head.length || prev.as_deref() != Some(head.head_hash.as_str()) {
+            return Err(broken(format!(
+                "{} receipts found but the head records {} ending at {}",
+                rows.len(), head.length, head.head_vakya_id
+            )));
+        }
+        Ok(head)
+    }
+
+    async fn store_approval(&self, record: ApprovalRecord) -> IndexDbResult<ApprovalRecord> {
+        let approvers_str = serde_json::to_string(&record.approvers)?;
+        let weighted_str = serde_json::to_string(&record.weighted_approvers)?;
+        let status_str = serde_json::to_string(&record.status)?;
+        let votes_str = serde_json::to_string(&record.votes)?;
+
+        sqlx::query(r#"
+            INSERT INTO approvals (
+                id, approval_id, vakya_id, vakya_hash, rule_id, approval_type, approvers,
+                min_approvals, weighted_approvers, required_weight, status, votes, reason,
+                created_at, expires_at, resolved_at, version
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#)
+        .bind(record.id.to_string())
+        .bind(&record.approval_id)
+        .bind(&record.vakya_id)
+        .bind(&record.vakya_hash)
+        .bind(&record.rule_id)
+        .bind(&record.approval_type)
+        .bind(&approvers_str)
+        .bind(record.min_approvals as i64)
+        .bind(&weighted_str)
+        .bind(record.required_weight.map(|w| w as i64))
+        .bind(&status_str)
+        .bind(&votes_str)
+        .bind(&record.reason)
+        .bind(record.created_at.to_rfc3339())
+        .bind(record.expires_at.map(|t| t.to_rfc3339()))
+        .bind(record.resolved_at.map(|t| t.to_rfc3339()))
+        .bind(record.version as i64)
+        .execute(&self.pool)
+        .await?;
+
+        debug!(approval_id = %record.approval_id, vakya_id = %record.vakya_id, "Stored approval record");
+        Ok(record)
+    }
+
+    async fn get_approval(&self, approval_id: &str) -> IndexDbResult<Option<ApprovalRecord>> {
         let row = sqlx::query(
-            "SELECT * FROM receipt_records WHERE vakya_id = ?"
+            "SELECT * FROM approvals WHERE approval_id = ?"
         )
-        .bind(vakya_id)
+        .bind(approval_id)
         .fetch_optional(&self.pool)
         .await?;
 
         match row {
-            Some(row) => {
-                let reason_code_str: String = row.get("reason_code");
-                let effect_ids_str: String = row.get("effect_ids");
-                let receipt_json_str: String = row.get("receipt_json");
-
-                Ok(Some(ReceiptRecord {
-                    id: row.get::<String, _>("id").parse().unwrap_or_default(),
-                    vakya_id: row.get("vakya_id"),
-                    vakya_hash: row.get("vakya_hash"),
-                    reason_code: serde_json::from_str(&reason_code_str).unwrap_or(aapi_core::error::ReasonCode::InternalError),
-                    message: row.get("message"),
-                    duration_ms: row.get("duration_ms"),
-                    effect_ids: serde_json::from_str(&effect_ids_str).unwrap_or_default(),
-                    executor_id: row.get("executor_id"),
-                    signature: row.get("signature"),
-                    key_id: row.get("key_id"),
-                    created_at: DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))
-                        .map(|dt| dt.with_timezone(&Utc))
-                        .unwrap_or_else(|_| Utc::now()),
-                    receipt_json: serde_json::from_str(&receipt_json_str).unwrap_or_default(),
-                    leaf_index: row.get("leaf_index"),
-                }))
-            }
+            Some(row) => Ok(Some(Self::row_to_approval_record(&row)?)),
             None => Ok(None),
         }
     }
 
+    async fn update_approval(&self, mut record: ApprovalRecord) -> IndexDbResult<ApprovalRecord> {
+        let status_str = serde_json::to_string(&record.status)?;
+        let votes_str = serde_json::to_string(&record.votes)?;
+        let pending_str = serde_json::to_string(&ApprovalRecordStatus::Pending)?;
+
+        // Compare-and-set: concurrent votes read the same version, and only
+        // the first to write it wins
+        let result = sqlx::query(
+            "UPDATE approvals SET status = ?, votes = ?, resolved_at = ?, version = version + 1 \
+             WHERE approval_id = ? AND status = ? AND version = ?"
+        )
+        .bind(&status_str)
+        .bind(&votes_str)
+        .bind(record.resolved_at.map(|t| t.to_rfc3339()))
+        .bind(&record.approval_id)
+        .bind(&pending_str)
+        .bind(record.version as i64)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return match self.get_approval(&record.approval_id).await? {
+                Some(_) => Err(IndexDbError::Conflict(format!(
+                    "Approval {} was resolved or updated concurrently",
+                    record.approval_id
+                ))),
+                None => Err(IndexDbError::NotFound(format!("Approval not found: {}", record.approval_id))),
+            };
+        }
+        record.version += 1;
+
+        debug!(approval_id = %record.approval_id, status = ?record.status, "Updated approval record");
+        Ok(record)
+    }
+
+    async fn revoke_capability(&self, revocation: CapabilityRevocation) -> IndexDbResult<CapabilityRevocation> {
+        sqlx::query(r#"
+            INSERT INTO capability_revocations (token_id, include_delegations, reason, revoked_by, revoked_at)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT(token_id) DO UPDATE SET
+                include_delegations = MAX(include_delegations, excluded.include_delegations)
+        "#)
+        .bind(&revocation.token_id)
+        .bind(revocation.include_delegations)
+        .bind(&revocation.reason)
+        .bind(&revocation.revoked_by)
+        .bind(revocation.revoked_at.to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+
+        debug!(token_id = %revocation.token_id, "Stored capability revocation");
+        self.get_capability_revocation(&revocation.token_id).await?
+            .ok_or_else(|| IndexDbError::NotFound(format!("Revocation not found: {}", revocation.token_id)))
+    }
+
+    async fn get_capability_revocation(&self, token_id: &str) -> IndexDbResult<Option<CapabilityRevocation>> {
+        let row = sqlx::query("SELECT * FROM capability_revocations WHERE token_id = ?")
+            .bind(token_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.as_ref().map(Self::row_to_revocation))
+    }
+
+    async fn list_capability_revocations(&self) -> IndexDbResult<Vec<CapabilityRevocation>> {
+        let rows = sqlx::query("SELECT * FROM capability_revocations ORDER BY revoked_at, token_id")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.iter().map(Self::row_to_revocation).collect())
+    }
+
+    async fn record_capability_delegation(&self, token_id: &str, parent_token_id: &str) -> IndexDbResult<()> {
+        sqlx::query(
+            "INSERT OR IGNORE INTO capability_delegations (token_id, parent_token_id, recorded_at) VALUES (?, ?, ?)"
+        )
+        .bind(token_id)
+        .bind(parent_token_id)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn list_capability_delegations(&self) -> IndexDbResult<Vec<(String, String)>> {
+        let rows = sqlx::query("SELECT token_id, parent_token_id FROM capability_delegations")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.iter().map(|row| (row.get("token_id"), row.get("parent_token_id"))).collect())
+    }
+
     async fn store_packet(&self, mut record: MemPacketRecord) -> IndexDbResult<MemPacketRecord> {
-        // Add to Merkle tree
+        // Add to Merkle tree, holding the lock until the row is in so a
+        // failed insert can take its leaf back out
         let mut tree = self.packet_tree.write().await;
+        let mark = tree.mark();
         let leaf_index = tree.append(&record.packet_cid);
-        drop(tree);
 
         record.leaf_index = Some(leaf_index as i64);
 
@@ -776,7 +1842,7 @@ impl IndexDbStore for SqliteIndexDb {
         let evidence_cids_str = serde_json::to_string(&record.evidence_cids)?;
         let payload_json_str = serde_json::to_string(&record.payload_json)?;
 
-        sqlx::query(r#"
+        let inserted = sqlx::query(r#"
             INSERT INTO packet_records (
                 id, packet_cid, packet_type, pipeline_id, subject_id,
                 payload_cid, payload_json, entities, tags,
@@ -815,7 +1881,13 @@ impl IndexDbStore for SqliteIndexDb {
         .bind(record.leaf_index)
         .bind(record.created_at.to_rfc3339())
         .execute(&self.pool)
-        .await?;
+        .await;
+        if let Err(e) = inserted {
+            tree.rollback(mark);
+            return Err(e.into());
+        }
+        self.flush_after_append(TreeType::Packet, &mut tree).await;
+        drop(tree);
 
         debug!(packet_cid = %record.packet_cid, packet_type = %record.packet_type, "Stored MemPacket record");
         Ok(record)
@@ -1094,6 +2166,34 @@ impl IndexDbStore for SqliteIndexDb {
         Ok(())
     }
 
+    async fn get_audit_log(&self, filter: &AuditFilter, page: Page) -> IndexDbResult<QueryResult<AuditLogEntry>> {
+        let (where_clause, params) = filter.build_where_clause();
+
+        let count_sql = format!("SELECT COUNT(*) FROM audit_log WHERE {}", where_clause);
+        let mut count = sqlx::query_scalar::<_, i64>(&count_sql);
+        for param in &params {
+            count = count.bind(param);
+        }
+        let total = count.fetch_one(&self.pool).await?;
+
+        let sql = format!(
+            "SELECT * FROM audit_log WHERE {} ORDER BY created_at DESC, id {}",
+            where_clause,
+            page.build_limit_clause(),
+        );
+        let mut q = sqlx::query(&sql);
+        for param in params {
+            q = q.bind(param);
+        }
+        let rows = q.fetch_all(&self.pool).await?;
+
+        let entries = rows.iter()
+            .map(Self::row_to_audit_entry)
+            .collect::<IndexDbResult<Vec<_>>>()?;
+
+        Ok(QueryResult::new(entries, page.offset, page.limit).with_total(total as u64))
+    }
+
     async fn get_merkle_root(&self, tree_type: TreeType) -> IndexDbResult<Option<String>> {
         let tree = self.get_tree(tree_type).read().await;
         Ok(tree.root().map(|h| h.to_string()))
@@ -1117,14 +2217,19 @@ impl IndexDbStore for SqliteIndexDb {
         .execute(&self.pool)
         .await?;
 
+        self.persist_merkle_nodes(checkpoint.tree_type).await?;
+
         info!(tree_type = %tree_type_str, root = %checkpoint.root_hash, "Stored Merkle checkpoint");
         Ok(())
     }
 
     async fn get_inclusion_proof(&self, tree_type: TreeType, leaf_index: i64) -> IndexDbResult<Option<InclusionProof>> {
+        let Ok(index) = usize::try_from(leaf_index) else {
+            return Ok(None);
+        };
         let tree = self.get_tree(tree_type).read().await;
-        
-        if let Some(proof) = tree.get_proof(leaf_index as usize) {
+
+        if let Some(proof) = self.inclusion_proof(tree_type, &tree, index).await? {
             let root = tree.root().unwrap_or_default();
             
             Ok(Some(InclusionProof {
@@ -1143,12 +2248,198 @@ impl IndexDbStore for SqliteIndexDb {
             Ok(None)
         }
     }
+
+    async fn get_inclusion_proofs(&self, tree_type: TreeType, leaf_indices: &[i64]) -> IndexDbResult<(SignedTreeHead, Vec<InclusionProof>)> {
+        let tree = self.get_tree(tree_type).read().await;
+        let root = tree.root().unwrap_or_default();
+
+        let mut proofs = Vec::with_capacity(leaf_indices.len());
+        for &leaf_index in leaf_indices {
+            let proof = match usize::try_from(leaf_index) {
+                Ok(index) => self.inclusion_proof(tree_type, &tree, index).await?,
+                Err(_) => None,
+            };
+            let proof = proof.ok_or_else(|| IndexDbError::NotFound(format!("No leaf {} in the {} tree", leaf_index, tree_type)))?;
+            proofs.push(InclusionProof {
+                leaf_hash: proof.leaf_hash,
+                leaf_index,
+                tree_size: tree.size() as i64,
+                proof_hashes: proof.path.into_iter().map(|(hash, is_right)| {
+                    ProofNode {
+                        hash,
+                        position: if is_right { ProofPosition::Right } else { ProofPosition::Left },
+                    }
+                }).collect(),
+                root_hash: root.clone(),
+            });
+        }
+
+        Ok((SignedTreeHead::new(tree.size() as u64, root), proofs))
+    }
+
+    async fn get_consistency_proof(&self, tree_type: TreeType, first_size: i64, second_size: i64) -> IndexDbResult<Option<ConsistencyProof>> {
+        if first_size < 0 || second_size < 0 {
+            return Ok(None);
+        }
+
+        let (first, second) = (first_size as usize, second_size as usize);
+        let tree = self.get_tree(tree_type).read().await;
+        if first > second || second > tree.size() {
+            return Ok(None);
+        }
+
+        // Consistency proofs are built from the leaf hashes, read on demand
+        let leaves = if tree.consistency_nodes(second).is_empty() {
+            StoredNodes::new()
+        } else {
+            self.load_merkle_leaves(tree_type, second).await?
+        };
+        if let Some(proof) = tree.get_consistency_proof(first, second, &leaves) {
+            Ok(Some(ConsistencyProof {
+                first_size,
+                second_size,
+                first_root: self.root_at(tree_type, &tree, first).await?.unwrap_or_default(),
+                second_root: self.root_at(tree_type, &tree, second).await?.unwrap_or_default(),
+                proof_hashes: proof.proof_hashes,
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn export_jsonl(&self, filter: &ExportFilter, writer: &mut (dyn AsyncWrite + Unpin + Send)) -> IndexDbResult<u64> {
+        let record_types = filter.effective_record_types();
+        let (where_clause, params) = filter.build_where_clause();
+
+        let mut sqls = Vec::with_capacity(record_types.len());
+        for tree_type in &record_types {
+            let table = match tree_type {
+                TreeType::Vakya => "vakya_records",
+                TreeType::Effect => "effect_records",
+                TreeType::Receipt => "receipt_records",
+                TreeType::Packet => return Err(IndexDbError::Query("Packet records cannot be exported".to_string())),
+            };
+            sqls.push(format!("SELECT * FROM {} WHERE {} ORDER BY created_at", table, where_clause));
+        }
+
+        // One ordered cursor per table, merged on created_at so memory stays bounded
+        let mut cursors: Vec<(TreeType, BoxStream<'_, Result<sqlx::sqlite::SqliteRow, sqlx::Error>>)> = Vec::new();
+        for (tree_type, sql) in record_types.iter().zip(&sqls) {
+            let mut query = sqlx::query(sql);
+            for param in &params {
+                query = query.bind(param);
+            }
+            cursors.push((*tree_type, query.fetch(&self.pool)));
+        }
+
+        let mut heads = Vec::with_capacity(cursors.len());
+        for (tree_type, cursor) in cursors.iter_mut() {
+            heads.push(Self::next_export_record(*tree_type, self.state_key.as_ref(), cursor).await?);
+        }
+
+        let mut written = 0u64;
+        loop {
+            let next = heads.iter()
+                .enumerate()
+                .filter_map(|(i, head)| head.as_ref().map(|r| (i, r.created_at())))
+                .min_by_key(|(_, created_at)| *created_at)
+                .map(|(i, _)| i);
+
+            let Some(i) = next else { break };
+            if let Some(record) = heads[i].take() {
+                Self::write_export_line(writer, &record).await?;
+                written += 1;
+            }
+
+            let (tree_type, cursor) = &mut cursors[i];
+            heads[i] = Self::next_export_record(*tree_type, self.state_key.as_ref(), cursor).await?;
+        }
+        drop(cursors);
+
+        for tree_type in record_types {
+            let tree = self.get_tree(tree_type).read().await;
+            let Some(root_hash) = tree.root() else { continue };
+
+            let checkpoint = MerkleCheckpoint {
+                id: uuid::Uuid::new_v4(),
+                tree_type,
+                tree_size: tree.size() as i64,
+                root_hash,
+                created_at: Utc::now(),
+                previous_id: None,
+                signature: None,
+            };
+            drop(tree);
+
+            Self::write_export_line(writer, &ExportRecord::Checkpoint(checkpoint)).await?;
+            written += 1;
+        }
+
+        writer.flush().await?;
+        Ok(written)
+    }
+
+    async fn maintenance(&self) -> IndexDbResult<MaintenanceReport> {
+        let start = std::time::Instant::now();
+        let mut report = MaintenanceReport {
+            size_before: self.database_size().await?,
+            ..Default::default()
+        };
+
+        let results: Vec<String> = sqlx::query_scalar("PRAGMA integrity_check")
+            .fetch_all(&self.pool)
+            .await?;
+        report.integrity_errors = results.into_iter().filter(|r| r != "ok").collect();
+
+        for tree_type in [TreeType::Vakya, TreeType::Effect, TreeType::Receipt, TreeType::Packet] {
+            let checkpoint: Option<(i64, String)> = sqlx::query_as(
+                "SELECT tree_size, root_hash FROM merkle_checkpoints WHERE tree_type = ? ORDER BY tree_size DESC, created_at DESC LIMIT 1"
+            )
+            .bind(tree_type.to_string())
+            .fetch_optional(&self.pool)
+            .await?;
+            let Some((size, root_hash)) = checkpoint else { continue };
+
+            let tree = self.get_tree(tree_type).read().await;
+            let root = self.root_at(tree_type, &tree, size as usize).await?;
+            drop(tree);
+            if root.as_deref() != Some(root_hash.as_str()) {
+                report.checkpoint_mismatches.push(format!(
+                    "{} tree root at size {} is {}, checkpoint says {}",
+                    tree_type,
+                    size,
+                    root.as_deref().unwrap_or("missing"),
+                    root_hash
+                ));
+            }
+        }
+
+        // Compacting a damaged database could make it worse
+        if report.integrity_errors.is_empty() {
+            sqlx::query("PRAGMA optimize").execute(&self.pool).await?;
+            sqlx::query("VACUUM").execute(&self.pool).await?;
+        }
+
+        report.size_after = self.database_size().await?;
+        report.duration_ms = start.elapsed().as_millis() as u64;
+        if report.is_healthy() {
+            info!(size_before = report.size_before, size_after = report.size_after, "Database maintenance complete");
+        } else {
+            warn!(
+                integrity_errors = report.integrity_errors.len(),
+                checkpoint_mismatches = report.checkpoint_mismatches.len(),
+                "Database maintenance found problems"
+            );
+        }
+        Ok(report)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use aapi_core::types::EffectBucket;
+    use aapi_core::error::ReasonCode;
 
     #[tokio::test]
     async fn test_sqlite_store_vakya() {
@@ -1172,6 +2463,39 @@ mod tests {
         assert_eq!(retrieved.unwrap().vakya_id, "vakya-test-1");
     }
 
+    #[tokio::test]
+    async fn test_query_vakyas() {
+        let store = SqliteIndexDb::in_memory().await.unwrap();
+
+        for (id, actor, action) in [
+            ("vakya-q-1", "user:alice", "file.read"),
+            ("vakya-q-2", "user:alice", "file.write"),
+            ("vakya-q-3", "user:bob", "http.get"),
+        ] {
+            let record = VakyaRecord::new(
+                id.to_string(),
+                format!("hash-{}", id),
+                actor.to_string(),
+                "file:/data.txt".to_string(),
+                action.to_string(),
+                serde_json::json!({}),
+            );
+            store.store_vakya(record).await.unwrap();
+        }
+
+        let all = store.query_vakyas(&VakyaQuery::new()).await.unwrap();
+        assert_eq!(all.len(), 3);
+
+        let alice_files = store
+            .query_vakyas(&VakyaQuery::new().by_actor("user:alice").by_action("file.*"))
+            .await
+            .unwrap();
+        assert_eq!(alice_files.len(), 2);
+
+        let limited = store.query_vakyas(&VakyaQuery::new().limit(1)).await.unwrap();
+        assert_eq!(limited.len(), 1);
+    }
+
     #[tokio::test]
     async fn test_sqlite_store_effect() {
         let store = SqliteIndexDb::in_memory().await.unwrap();
@@ -1203,6 +2527,499 @@ mod tests {
         assert_eq!(effects.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_failed_effect_batch_is_rolled_back() {
+        let store = SqliteIndexDb::in_memory().await.unwrap();
+        store.store_vakya(VakyaRecord::new(
+            "vakya-batch".to_string(),
+            "hash-batch".to_string(),
+            "user:alice".to_string(),
+            "file:/batch".to_string(),
+            "file.write".to_string(),
+            serde_json::json!({}),
+        )).await.unwrap();
+        let effect = |target: &str| EffectRecord::new("vakya-batch".to_string(), EffectBucket::Create, target.to_string());
+
+        store.store_effect(effect("file:/first")).await.unwrap();
+        let root = store.get_merkle_root(TreeType::Effect).await.unwrap();
+
+        // The repeated effect ID fails the third insert after two succeeded
+        let second = effect("file:/second");
+        let batch = vec![effect("file:/third"), second.clone(), second];
+        assert!(store.store_effects(batch).await.is_err());
+
+        assert_eq!(store.get_effects("vakya-batch").await.unwrap().len(), 1);
+        assert_eq!(store.get_merkle_root(TreeType::Effect).await.unwrap(), root);
+
+        let stored = store.store_effects(vec![effect("file:/fourth"), effect("file:/fifth")]).await.unwrap();
+        assert_eq!(stored.iter().map(|e| e.leaf_index).collect::<Vec<_>>(), [Some(1), Some(2)]);
+        assert_eq!(store.get_effects("vakya-batch").await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_failed_receipt_insert_is_rolled_back() {
+        let store = SqliteIndexDb::in_memory().await.unwrap();
+        for id in ["vakya-r1", "vakya-r2"] {
+            store.store_vakya(VakyaRecord::new(
+                id.to_string(),
+                format!("hash-{}", id),
+                "user:alice".to_string(),
+                "file:/receipts".to_string(),
+                "file.write".to_string(),
+                serde_json::json!({}),
+            )).await.unwrap();
+        }
+        let receipt = |id: &str| ReceiptRecord::new(
+            id.to_string(),
+            format!("hash-{}", id),
+            ReasonCode::Success,
+            "gateway-1".to_string(),
+            serde_json::json!({}),
+        );
+
+        store.store_receipt(receipt("vakya-r1")).await.unwrap();
+        let root = store.get_merkle_root(TreeType::Receipt).await.unwrap();
+
+        // A second receipt for the same VĀKYA violates the unique constraint
+        assert!(store.store_receipt(receipt("vakya-r1")).await.is_err());
+        assert_eq!(store.get_merkle_root(TreeType::Receipt).await.unwrap(), root);
+
+        let stored = store.store_receipt(receipt("vakya-r2")).await.unwrap();
+        assert_eq!(stored.leaf_index, Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_effect_state_encryption() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let url = format!("sqlite:{}?mode=rwc", dir.path().join("enc.db").display());
+        let master = MasterKey::generate("kek-1");
+        let store = SqliteIndexDb::with_config(&url, DbConfig::default().with_state_encryption(master.clone()))
+            .await
+            .unwrap();
+
+        let vakya = VakyaRecord::new(
+            "vakya-enc".to_string(),
+            "hash-enc".to_string(),
+            "user:carol".to_string(),
+            "file:/patients.json".to_string(),
+            "file.write".to_string(),
+            serde_json::json!({}),
+        );
+        store.store_vakya(vakya).await.unwrap();
+
+        let mut effect = EffectRecord::new(
+            "vakya-enc".to_string(),
+            EffectBucket::Update,
+            "file:/patients.json".to_string(),
+        );
+        effect.before_hash = Some("before-hash".to_string());
+        effect.after_hash = Some("after-hash".to_string());
+        effect.before_state = Some(serde_json::json!({ "ssn": "123-45-6789" }));
+        effect.after_state = Some(serde_json::json!({ "ssn": "987-65-4321" }));
+        effect.delta = Some(serde_json::json!({ "changed": ["ssn"] }));
+        let stored = store.store_effect(effect.clone()).await.unwrap();
+        let root = store.get_merkle_root(TreeType::Effect).await.unwrap();
+
+        // Only ciphertext and the wrapped data key reach the database
+        let row = sqlx::query("SELECT before_state, after_state, delta, state_key FROM effect_records")
+            .fetch_one(&store.pool)
+            .await
+            .unwrap();
+        let raw: Vec<Option<String>> = (0..4).map(|i| row.get(i)).collect();
+        assert!(raw.iter().all(|column| column.as_deref().is_some_and(|v| !v.contains("ssn"))));
+        let wrapped: WrappedKey = serde_json::from_str(raw[3].as_deref().unwrap()).unwrap();
+        assert_eq!(wrapped.kid, "kek-1");
+
+        let effects = store.get_effects("vakya-enc").await.unwrap();
+        assert_eq!(effects[0].before_state, effect.before_state);
+        assert_eq!(effects[0].after_state, effect.after_state);
+        assert_eq!(effects[0].delta, effect.delta);
+        assert_eq!(effects[0].before_hash.as_deref(), Some("before-hash"));
+        let by_id = store.get_effect(&stored.id.to_string()).await.unwrap().unwrap();
+        assert_eq!(by_id.after_state, effect.after_state);
+        drop(store);
+
+        // Without the master key the state is withheld; the tree is unchanged
+        let store = SqliteIndexDb::with_config(&url, DbConfig::default()).await.unwrap();
+        let effects = store.get_effects("vakya-enc").await.unwrap();
+        assert!(effects[0].before_state.is_none());
+        assert!(effects[0].after_state.is_none());
+        assert!(effects[0].delta.is_none());
+        assert_eq!(effects[0].after_hash.as_deref(), Some("after-hash"));
+        assert_eq!(store.get_merkle_root(TreeType::Effect).await.unwrap(), root);
+        drop(store);
+
+        // A different key under the same ID is detected rather than ignored
+        let wrong = DbConfig::default().with_state_encryption(MasterKey::generate("kek-1"));
+        let store = SqliteIndexDb::with_config(&url, wrong).await.unwrap();
+        assert!(matches!(
+            store.get_effects("vakya-enc").await,
+            Err(IndexDbError::IntegrityViolation(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_approval_roundtrip_and_receipt_update() {
+        let store = SqliteIndexDb::in_memory().await.unwrap();
+
+        let vakya = VakyaRecord::new(
+            "vakya-approval".to_string(),
+            "hash-approval".to_string(),
+            "user:carol".to_string(),
+            "file:/data.json".to_string(),
+            "file.delete".to_string(),
+            serde_json::json!({}),
+        );
+        store.store_vakya(vakya).await.unwrap();
+
+        let mut approval = ApprovalRecord::new(
+            "approval-1".to_string(),
+            "vakya-approval".to_string(),
+            "hash-approval".to_string(),
+            1,
+            "Delete requires approval".to_string(),
+        );
+        approval.approvers = vec!["user:alice".to_string()];
+        store.store_approval(approval).await.unwrap();
+
+        let mut loaded = store.get_approval("approval-1").await.unwrap().unwrap();
+        assert_eq!(loaded.status, ApprovalRecordStatus::Pending);
+        assert_eq!(loaded.approvers, vec!["user:alice".to_string()]);
+
+        loaded.votes.push(ApprovalVote {
+            approver: "user:alice".to_string(),
+            decision: VoteDecision::Approve,
+            comment: Some("ok".to_string()),
+            voted_at: Utc::now(),
+        });
+        loaded.status = ApprovalRecordStatus::Approved;
+        loaded.resolved_at = Some(Utc::now());
+        store.update_approval(loaded).await.unwrap();
+
+        let reloaded = store.get_approval("approval-1").await.unwrap().unwrap();
+        assert_eq!(reloaded.status, ApprovalRecordStatus::Approved);
+        assert_eq!(reloaded.approval_count(), 1);
+        assert!(reloaded.resolved_at.is_some());
+
+        let pending = ReceiptRecord::new(
+            "vakya-approval".to_string(),
+            "hash-approval".to_string(),
+            aapi_core::error::ReasonCode::ApprovalRequired,
+            "gw".to_string(),
+            serde_json::json!({"status": "pending_approval"}),
+        );
+        let stored = store.store_receipt(pending).await.unwrap();
+
+        let final_receipt = ReceiptRecord::new(
+            "vakya-approval".to_string(),
+            "hash-approval".to_string(),
+            aapi_core::error::ReasonCode::Success,
+            "gw".to_string(),
+            serde_json::json!({"status": "success"}),
+        );
+        let updated = store.update_receipt(final_receipt).await.unwrap();
+        assert_eq!(updated.leaf_index, stored.leaf_index);
+
+        let receipt = store.get_receipt("vakya-approval").await.unwrap().unwrap();
+        assert_eq!(receipt.reason_code, aapi_core::error::ReasonCode::Success);
+        assert!(store.receipts_with_reason(aapi_core::error::ReasonCode::ApprovalRequired).await.unwrap().is_empty());
+        let succeeded = store.receipts_with_reason(aapi_core::error::ReasonCode::Success).await.unwrap();
+        assert_eq!(succeeded.len(), 1);
+        assert_eq!(succeeded[0].vakya_id, "vakya-approval");
+    }
+
+    #[tokio::test]
+    async fn test_stale_approval_updates_conflict() {
+        let store = SqliteIndexDb::in_memory().await.unwrap();
+        store.store_vakya(VakyaRecord::new(
+            "vakya-race".to_string(),
+            "hash-race".to_string(),
+            "user:carol".to_string(),
+            "file:/data.json".to_string(),
+            "file.delete".to_string(),
+            serde_json::json!({}),
+        )).await.unwrap();
+        let mut approval = ApprovalRecord::new(
+            "approval-race".to_string(),
+            "vakya-race".to_string(),
+            "hash-race".to_string(),
+            2,
+            "Delete requires approval".to_string(),
+        );
+        approval.approvers = vec!["user:alice".to_string(), "user:bob".to_string()];
+        store.store_approval(approval).await.unwrap();
+
+        let vote = |approver: &str| ApprovalVote {
+            approver: approver.to_string(),
+            decision: VoteDecision::Approve,
+            comment: None,
+            voted_at: Utc::now(),
+        };
+
+        // Both voters read the same version; the second write loses
+        let mut first = store.get_approval("approval-race").await.unwrap().unwrap();
+        let mut second = first.clone();
+        first.votes.push(vote("user:alice"));
+        let first = store.update_approval(first).await.unwrap();
+        assert_eq!(first.version, 1);
+        second.votes.push(vote("user:bob"));
+        assert!(matches!(store.update_approval(second).await, Err(IndexDbError::Conflict(_))));
+        assert_eq!(store.get_approval("approval-race").await.unwrap().unwrap().approval_count(), 1);
+
+        // A resolved approval takes no further updates, even at its version
+        let mut resolved = store.get_approval("approval-race").await.unwrap().unwrap();
+        resolved.votes.push(vote("user:bob"));
+        resolved.status = ApprovalRecordStatus::Approved;
+        let mut resolved = store.update_approval(resolved).await.unwrap();
+        resolved.status = ApprovalRecordStatus::Rejected;
+        assert!(matches!(store.update_approval(resolved).await, Err(IndexDbError::Conflict(_))));
+
+        let mut missing = ApprovalRecord::new("nope".into(), "vakya-race".into(), "hash-race".into(), 1, "x".into());
+        missing.status = ApprovalRecordStatus::Rejected;
+        assert!(matches!(store.update_approval(missing).await, Err(IndexDbError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_maintenance_reconciles_checkpoints() {
+        let dir = tempfile::tempdir().unwrap();
+        let url = format!("sqlite:{}?mode=rwc", dir.path().join("maint.db").display());
+        let store = SqliteIndexDb::new(&url).await.unwrap();
+
+        for i in 0..3 {
+            store.store_vakya(VakyaRecord::new(
+                format!("vakya-maint-{}", i),
+                format!("hash-maint-{}", i),
+                "user:ops".to_string(),
+                "file:/maint".to_string(),
+                "file.read".to_string(),
+                serde_json::json!({}),
+            )).await.unwrap();
+        }
+        let checkpoint = |root_hash: String| MerkleCheckpoint {
+            id: uuid::Uuid::new_v4(),
+            tree_type: TreeType::Vakya,
+            tree_size: 3,
+            root_hash,
+            created_at: Utc::now(),
+            previous_id: None,
+            signature: None,
+        };
+        let root = store.get_merkle_root(TreeType::Vakya).await.unwrap().unwrap();
+        store.store_merkle_checkpoint(checkpoint(root)).await.unwrap();
+
+        let report = store.maintenance().await.unwrap();
+        assert!(report.is_healthy(), "{:?}", report);
+        assert!(report.size_after > 0);
+
+        // A checkpoint the log cannot reproduce is reported
+        store.store_merkle_checkpoint(checkpoint("sha256:forged".to_string())).await.unwrap();
+        let report = store.maintenance().await.unwrap();
+        assert!(report.integrity_errors.is_empty());
+        assert_eq!(report.checkpoint_mismatches.len(), 1);
+        assert!(report.checkpoint_mismatches[0].contains("sha256:forged"));
+    }
+
+    #[tokio::test]
+    async fn test_capability_revocations() {
+        let store = SqliteIndexDb::in_memory().await.unwrap();
+        assert!(store.get_capability_revocation("cap-1").await.unwrap().is_none());
+
+        let mut revocation = CapabilityRevocation::new("cap-1");
+        revocation.reason = Some("leaked".to_string());
+        let stored = store.revoke_capability(revocation).await.unwrap();
+        assert!(!stored.include_delegations);
+
+        // Revoking again widens the revocation but keeps the original record
+        let mut again = CapabilityRevocation::new("cap-1");
+        again.include_delegations = true;
+        let widened = store.revoke_capability(again).await.unwrap();
+        assert!(widened.include_delegations);
+        assert_eq!(widened.reason.as_deref(), Some("leaked"));
+        assert_eq!(widened.revoked_at.timestamp(), stored.revoked_at.timestamp());
+
+        store.revoke_capability(CapabilityRevocation::new("cap-2")).await.unwrap();
+        let all = store.list_capability_revocations().await.unwrap();
+        assert_eq!(all.iter().map(|r| r.token_id.as_str()).collect::<Vec<_>>(), ["cap-1", "cap-2"]);
+
+        // A token's first recorded parent sticks
+        store.record_capability_delegation("cap-3", "cap-1").await.unwrap();
+        store.record_capability_delegation("cap-3", "cap-2").await.unwrap();
+        store.record_capability_delegation("cap-4", "cap-3").await.unwrap();
+        let mut delegations = store.list_capability_delegations().await.unwrap();
+        delegations.sort();
+        assert_eq!(delegations, [
+            ("cap-3".to_string(), "cap-1".to_string()),
+            ("cap-4".to_string(), "cap-3".to_string()),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_actor_receipt_chain() {
+        let store = SqliteIndexDb::in_memory().await.unwrap();
+
+        for (i, actor) in ["user:alice", "user:bob", "user:alice", "user:alice"].iter().enumerate() {
+            let vakya_id = format!("vakya-chain-{}", i);
+            let hash = format!("hash-chain-{}", i);
+            store.store_vakya(VakyaRecord::new(
+                vakya_id.clone(),
+                hash.clone(),
+                actor.to_string(),
+                "file:/chain.txt".to_string(),
+                "file.write".to_string(),
+                serde_json::json!({}),
+            )).await.unwrap();
+            store.store_receipt(ReceiptRecord::new(
+                vakya_id,
+                hash,
+                aapi_core::error::ReasonCode::Success,
+                "gw".to_string(),
+                serde_json::json!({}),
+            )).await.unwrap();
+        }
+
+        let first = store.get_receipt("vakya-chain-0").await.unwrap().unwrap();
+        let second = store.get_receipt("vakya-chain-2").await.unwrap().unwrap();
+        assert_eq!(first.prev_receipt_hash, None);
+        assert_eq!(second.prev_receipt_hash, first.chain_hash);
+        assert_eq!(second.chain_seq, Some(1));
+
+        let head = store.verify_actor_chain("user:alice").await.unwrap();
+        assert_eq!(head.length, 3);
+        assert_eq!(head.head_vakya_id, "vakya-chain-3");
+        assert_eq!(store.verify_actor_chain("user:bob").await.unwrap().length, 1);
+
+        // Resolving a receipt keeps its link
+        store.update_receipt(ReceiptRecord::new(
+            "vakya-chain-2".to_string(),
+            "hash-chain-2".to_string(),
+            aapi_core::error::ReasonCode::PolicyDenied,
+            "gw".to_string(),
+            serde_json::json!({}),
+        )).await.unwrap();
+        assert!(store.verify_actor_chain("user:alice").await.is_ok());
+
+        // Omitting one of alice's actions breaks her chain
+        sqlx::query("DELETE FROM receipt_records WHERE vakya_id = 'vakya-chain-2'")
+            .execute(&store.pool).await.unwrap();
+        assert!(matches!(
+            store.verify_actor_chain("user:alice").await,
+            Err(IndexDbError::IntegrityViolation(_))
+        ));
+        assert!(store.verify_actor_chain("user:bob").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_query_by_namespace() {
+        let store = SqliteIndexDb::in_memory().await.unwrap();
+
+        for (id, ns) in [("v1", Some("org.acme")), ("v2", Some("org.acme.billing")), ("v3", Some("org.other")), ("v4", None)] {
+            let mut record = VakyaRecord::new(
+                id.to_string(),
+                format!("hash-{}", id),
+                "user:alice".to_string(),
+                "file:/x".to_string(),
+                "file.read".to_string(),
+                serde_json::json!({}),
+            );
+            record.karma_ns = ns.map(String::from);
+            store.store_vakya(record).await.unwrap();
+        }
+
+        let results = store.query_vakyas(&VakyaQuery::new().by_namespace("org.acme")).await.unwrap();
+        let mut ids: Vec<_> = results.iter().map(|r| r.vakya_id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["v1", "v2"]);
+
+        let stored = store.get_vakya("v2").await.unwrap().unwrap();
+        assert_eq!(stored.karma_ns.as_deref(), Some("org.acme.billing"));
+    }
+
+    #[tokio::test]
+    async fn test_db_config_applies_pool_settings() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let url = format!("sqlite:{}?mode=rwc", dir.path().join("pool.db").display());
+        let config = DbConfig::default()
+            .with_max_connections(2)
+            .with_busy_timeout(Duration::from_millis(1500))
+            .with_statement_timeout(Duration::from_millis(100));
+        let store = SqliteIndexDb::with_config(&url, config).await.unwrap();
+
+        let mode: String = sqlx::query_scalar("PRAGMA journal_mode").fetch_one(&store.pool).await.unwrap();
+        assert_eq!(mode, "wal");
+        let busy: i64 = sqlx::query_scalar("PRAGMA busy_timeout").fetch_one(&store.pool).await.unwrap();
+        assert_eq!(busy, 1500);
+        assert_eq!(store.pool.options().get_max_connections(), 2);
+
+        // A runaway query is interrupted instead of holding the connection
+        let started = Instant::now();
+        let runaway = sqlx::query_scalar::<_, i64>(
+            "WITH RECURSIVE c(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM c) SELECT count(*) FROM c",
+        )
+        .fetch_one(&store.pool)
+        .await;
+        assert!(runaway.is_err());
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        // The connection is usable again on the next checkout
+        store.store_vakya(VakyaRecord::new(
+            "v1".to_string(), "h1".to_string(), "u1".to_string(),
+            "r1".to_string(), "a.b".to_string(), serde_json::json!({}),
+        )).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_hash_verification_detects_tampering() {
+        let config = DbConfig::default().with_hash_verification();
+        let store = SqliteIndexDb::with_config("sqlite::memory:", config).await.unwrap();
+
+        let vakya_json = serde_json::json!({"vakya_id": "v1", "body": {"amount": 10}});
+        let hash = hash_value(&vakya_json).unwrap().value;
+        store.store_vakya(VakyaRecord::new(
+            "v1".to_string(), hash.clone(), "u1".to_string(),
+            "r1".to_string(), "a.b".to_string(), vakya_json,
+        )).await.unwrap();
+        assert!(store.get_vakya("v1").await.unwrap().is_some());
+
+        sqlx::query("UPDATE vakya_records SET vakya_json = ? WHERE vakya_id = 'v1'")
+            .bind(r#"{"vakya_id":"v1","body":{"amount":10000}}"#)
+            .execute(&store.pool)
+            .await
+            .unwrap();
+
+        let err = store.get_vakya("v1").await.unwrap_err();
+        assert!(matches!(err, IndexDbError::IntegrityMismatch { ref stored, .. } if *stored == hash));
+        assert!(store.query_vakyas(&VakyaQuery::new()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_migration_adds_namespace_column() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let url = format!("sqlite:{}?mode=rwc", dir.path().join("old.db").display());
+
+        // Table as created before `karma_ns` existed
+        let pool = SqlitePool::connect(&url).await.unwrap();
+        sqlx::query(r#"
+            CREATE TABLE vakya_records (
+                id TEXT PRIMARY KEY, vakya_id TEXT UNIQUE NOT NULL, vakya_hash TEXT NOT NULL,
+                karta_pid TEXT NOT NULL, karta_type TEXT NOT NULL, karma_rid TEXT NOT NULL,
+                karma_kind TEXT, kriya_action TEXT NOT NULL, expected_effect TEXT NOT NULL,
+                cap_ref TEXT, vakya_json TEXT NOT NULL, signature TEXT, key_id TEXT,
+                trace_id TEXT, span_id TEXT, parent_span_id TEXT, created_at TEXT NOT NULL,
+                leaf_index INTEGER, merkle_root TEXT
+            )
+        "#).execute(&pool).await.unwrap();
+        pool.close().await;
+
+        let store = SqliteIndexDb::new(&url).await.unwrap();
+        let mut record = VakyaRecord::new(
+            "v1".to_string(), "h1".to_string(), "u1".to_string(),
+            "r1".to_string(), "a.b".to_string(), serde_json::json!({}),
+        );
+        record.karma_ns = Some("org.acme".to_string());
+        store.store_vakya(record).await.unwrap();
+        assert_eq!(store.get_vakya("v1").await.unwrap().unwrap().karma_ns.as_deref(), Some("org.acme"));
+    }
+
     #[tokio::test]
     async fn test_merkle_root_updates() {
         let store = SqliteIndexDb::in_memory().await.unwrap();
@@ -1237,4 +3054,321 @@ mod tests {
         assert!(root3.is_some());
         assert_ne!(root2, root3); // Root should change
     }
+
+    #[tokio::test]
+    async fn test_audit_log_query() {
+        let store = SqliteIndexDb::in_memory().await.unwrap();
+        let start = Utc::now();
+
+        for i in 0..5 {
+            let event_type = if i % 2 == 0 { AuditEventType::VakyaDenied } else { AuditEventType::VakyaExecuted };
+            let mut entry = AuditLogEntry::new(event_type, serde_json::json!({"n": i}))
+                .with_actor(if i < 3 { "user:alice" } else { "user:bob" })
+                .with_target(format!("file:/tmp/{}", i));
+            entry.created_at = start + chrono::Duration::seconds(i);
+            store.store_audit_log(entry).await.unwrap();
+        }
+
+        let all = store.get_audit_log(&AuditFilter::new(), Page::default()).await.unwrap();
+        assert_eq!(all.total, Some(5));
+        let order: Vec<i64> = all.items.iter().map(|e| e.details["n"].as_i64().unwrap()).collect();
+        assert_eq!(order, vec![4, 3, 2, 1, 0]);
+
+        let denied = AuditFilter::new().by_event_type(AuditEventType::VakyaDenied);
+        let page = store.get_audit_log(&denied, Page::new(0, 2)).await.unwrap();
+        assert_eq!(page.total, Some(3));
+        assert_eq!(page.items.len(), 2);
+        assert!(page.has_more);
+        let rest = store.get_audit_log(&denied, Page::new(2, 2)).await.unwrap();
+        assert_eq!(rest.items.len(), 1);
+        assert!(!rest.has_more);
+        assert_eq!(rest.items[0].event_type, AuditEventType::VakyaDenied);
+
+        let bob_recent = AuditFilter::new()
+            .by_actor("user:bob")
+            .from(start + chrono::Duration::seconds(4));
+        let entries = store.get_audit_log(&bob_recent, Page::default()).await.unwrap().items;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].target.as_deref(), Some("file:/tmp/4"));
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_vakya_leaves_tree_untouched() {
+        let store = SqliteIndexDb::in_memory().await.unwrap();
+        let record = |hash: &str| VakyaRecord::new(
+            "v-dup".to_string(),
+            hash.to_string(),
+            "u1".to_string(),
+            "r1".to_string(),
+            "a.b".to_string(),
+            serde_json::json!({}),
+        );
+
+        store.store_vakya(record("h1")).await.unwrap();
+        let root = store.get_merkle_root(TreeType::Vakya).await.unwrap();
+
+        let err = store.store_vakya(record("h2")).await.unwrap_err();
+        assert!(matches!(err, IndexDbError::DuplicateVakya(ref id) if id == "v-dup"));
+        assert_eq!(store.get_merkle_root(TreeType::Vakya).await.unwrap(), root);
+
+        // The next VĀKYA takes the leaf the duplicate did not
+        let mut next = record("h3");
+        next.vakya_id = "v-next".to_string();
+        assert_eq!(store.store_vakya(next).await.unwrap().leaf_index, Some(1));
+        assert_eq!(store.get_vakya("v-dup").await.unwrap().unwrap().vakya_hash, "h1");
+    }
+
+    #[tokio::test]
+    async fn test_inclusion_proofs_share_tree_head() {
+        let store = SqliteIndexDb::in_memory().await.unwrap();
+        for i in 0..5 {
+            store.store_vakya(VakyaRecord::new(
+                format!("v{}", i), format!("h{}", i), "u1".to_string(),
+                "r1".to_string(), "a.b".to_string(), serde_json::json!({}),
+            )).await.unwrap();
+        }
+
+        let (head, proofs) = store.get_inclusion_proofs(TreeType::Vakya, &[1, 3]).await.unwrap();
+        assert_eq!(head.tree_size, 5);
+        assert_eq!(Some(head.root_hash.clone()), store.get_merkle_root(TreeType::Vakya).await.unwrap());
+        for proof in &proofs {
+            let path: Vec<(String, bool)> = proof.proof_hashes.iter()
+                .map(|node| (node.hash.clone(), node.position == ProofPosition::Right))
+                .collect();
+            assert!(aapi_crypto::verify_inclusion(&proof.leaf_hash, &path, &head.root_hash));
+        }
+
+        assert!(matches!(
+            store.get_inclusion_proofs(TreeType::Vakya, &[5]).await,
+            Err(IndexDbError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_inclusion_proofs_by_record_id() {
+        let store = SqliteIndexDb::in_memory().await.unwrap();
+        for i in 0..3 {
+            store.store_vakya(VakyaRecord::new(
+                format!("v{}", i), format!("h{}", i), "u1".to_string(),
+                "r1".to_string(), "a.b".to_string(), serde_json::json!({}),
+            )).await.unwrap();
+        }
+        let effect = store.store_effect(EffectRecord::new(
+            "v2".to_string(), EffectBucket::Update, "r1".to_string(),
+        )).await.unwrap();
+        store.store_receipt(ReceiptRecord::new(
+            "v2".to_string(), "h2".to_string(), aapi_core::error::ReasonCode::Success,
+            "gw".to_string(), serde_json::json!({}),
+        )).await.unwrap();
+
+        let proof = store.inclusion_proof_for_vakya("v2").await.unwrap().unwrap();
+        assert_eq!(proof.leaf_index, 2);
+        assert_eq!(Some(proof.root_hash), store.get_merkle_root(TreeType::Vakya).await.unwrap());
+
+        let proof = store.inclusion_proof_for_receipt("v2").await.unwrap().unwrap();
+        assert_eq!(proof.leaf_index, 0);
+        assert_eq!(Some(proof.root_hash), store.get_merkle_root(TreeType::Receipt).await.unwrap());
+
+        let proof = store.inclusion_proof_for_effect(&effect.id.to_string()).await.unwrap().unwrap();
+        assert_eq!(proof.leaf_index, effect.leaf_index.unwrap());
+        assert_eq!(Some(proof.root_hash), store.get_merkle_root(TreeType::Effect).await.unwrap());
+
+        assert!(store.inclusion_proof_for_vakya("missing").await.unwrap().is_none());
+        assert!(store.inclusion_proof_for_receipt("v0").await.unwrap().is_none());
+        assert!(store.inclusion_proof_for_effect("missing").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_merkle_trees_restore_from_persisted_nodes() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let url = format!("sqlite:{}?mode=rwc", dir.path().join("log.db").display());
+        let record = |i: usize| VakyaRecord::new(
+            format!("v{}", i), format!("h{}", i), "u1".to_string(),
+            "r1".to_string(), "a.b".to_string(), serde_json::json!({}),
+        );
+        let node_count = |store: &SqliteIndexDb| {
+            let pool = store.pool.clone();
+            async move {
+                sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM merkle_nodes WHERE tree_type = 'vakya'")
+                    .fetch_one(&pool)
+                    .await
+                    .unwrap()
+            }
+        };
+
+        let store = SqliteIndexDb::new(&url).await.unwrap();
+        for i in 0..5 {
+            store.store_vakya(record(i)).await.unwrap();
+        }
+        let root = store.get_merkle_root(TreeType::Vakya).await.unwrap().unwrap();
+        store.store_merkle_checkpoint(MerkleCheckpoint {
+            id: uuid::Uuid::new_v4(),
+            tree_type: TreeType::Vakya,
+            tree_size: 5,
+            root_hash: root.clone(),
+            created_at: Utc::now(),
+            previous_id: None,
+            signature: None,
+        }).await.unwrap();
+        // 5 leaves plus the complete nodes above them
+        assert_eq!(node_count(&store).await, 5 + 2 + 1);
+        for i in 5..7 {
+            store.store_vakya(record(i)).await.unwrap();
+        }
+        let root = store.get_merkle_root(TreeType::Vakya).await.unwrap();
+
+        // Appends persist their nodes as they go, so a restart only reads
+        // the right edge back and proofs come from the stored nodes
+        assert_eq!(node_count(&store).await, 7 + 3 + 1);
+        drop(store);
+        let store = SqliteIndexDb::new(&url).await.unwrap();
+        assert_eq!(store.get_merkle_root(TreeType::Vakya).await.unwrap(), root);
+        assert_eq!(node_count(&store).await, 7 + 3 + 1);
+        let root = root.unwrap();
+        for i in 0..7 {
+            let proof = store.get_inclusion_proof(TreeType::Vakya, i).await.unwrap().unwrap();
+            let path: Vec<(String, bool)> = proof.proof_hashes.iter()
+                .map(|node| (node.hash.clone(), node.position == ProofPosition::Right))
+                .collect();
+            assert_eq!(proof.root_hash, root);
+            assert!(aapi_crypto::verify_inclusion(&proof.leaf_hash, &path, &root), "leaf {}", i);
+        }
+        let consistency = store.get_consistency_proof(TreeType::Vakya, 5, 7).await.unwrap().unwrap();
+        assert_eq!(consistency.second_root, root);
+        assert!(aapi_crypto::verify_consistency(5, 7, &consistency.first_root, &root, &consistency.proof_hashes));
+        let stored = store.store_vakya(record(7)).await.unwrap();
+        assert_eq!(stored.leaf_index, Some(7));
+
+        // Leaves logged while their nodes were not persisted are caught up
+        sqlx::query("DELETE FROM merkle_nodes WHERE tree_type = 'vakya' AND (level, index_in_level) IN ((0, 7), (1, 3), (2, 1), (3, 0))")
+            .execute(&store.pool)
+            .await
+            .unwrap();
+        let root = store.get_merkle_root(TreeType::Vakya).await.unwrap();
+        drop(store);
+        let store = SqliteIndexDb::new(&url).await.unwrap();
+        assert_eq!(store.get_merkle_root(TreeType::Vakya).await.unwrap(), root);
+        assert_eq!(node_count(&store).await, 8 + 4 + 2 + 1);
+
+        // A right edge that disagrees with the log is discarded and rebuilt
+        sqlx::query("UPDATE merkle_nodes SET hash = 'bogus' WHERE tree_type = 'vakya' AND level = 0 AND index_in_level = 7")
+            .execute(&store.pool)
+            .await
+            .unwrap();
+        let root = store.get_merkle_root(TreeType::Vakya).await.unwrap();
+        drop(store);
+        let store = SqliteIndexDb::new(&url).await.unwrap();
+        assert_eq!(store.get_merkle_root(TreeType::Vakya).await.unwrap(), root);
+    }
+
+    #[tokio::test]
+    async fn test_consistency_proof() {
+        let store = SqliteIndexDb::in_memory().await.unwrap();
+        let mut roots = Vec::new();
+
+        for i in 0..5 {
+            let record = VakyaRecord::new(
+                format!("v{}", i),
+                format!("h{}", i),
+                "u1".to_string(),
+                "r1".to_string(),
+                "a.b".to_string(),
+                serde_json::json!({}),
+            );
+            store.store_vakya(record).await.unwrap();
+            roots.push(store.get_merkle_root(TreeType::Vakya).await.unwrap().unwrap());
+        }
+
+        let proof = store.get_consistency_proof(TreeType::Vakya, 3, 5).await.unwrap().unwrap();
+        assert_eq!(proof.first_root, roots[2]);
+        assert_eq!(proof.second_root, roots[4]);
+        assert!(aapi_crypto::verify_consistency(3, 5, &roots[2], &roots[4], &proof.proof_hashes));
+
+        assert!(store.get_consistency_proof(TreeType::Vakya, 3, 6).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_blake3_merkle_trees() {
+        let store = SqliteIndexDb::new_with("sqlite::memory:", HashAlgorithm::Blake3).await.unwrap();
+
+        for i in 0..4 {
+            let record = VakyaRecord::new(
+                format!("v{}", i),
+                format!("h{}", i),
+                "u1".to_string(),
+                "r1".to_string(),
+                "a.b".to_string(),
+                serde_json::json!({}),
+            );
+            let stored = store.store_vakya(record).await.unwrap();
+            assert!(stored.merkle_root.unwrap().starts_with("blake3:"));
+        }
+
+        let proof = store.get_consistency_proof(TreeType::Vakya, 1, 4).await.unwrap().unwrap();
+        assert!(aapi_crypto::verify_consistency(1, 4, &proof.first_root, &proof.second_root, &proof.proof_hashes));
+    }
+
+    #[tokio::test]
+    async fn test_export_jsonl() {
+        let store = SqliteIndexDb::in_memory().await.unwrap();
+        let start = Utc::now();
+
+        for i in 0..3 {
+            let vakya_id = format!("vakya-export-{}", i);
+            let vakya = VakyaRecord::new(
+                vakya_id.clone(),
+                format!("hash-{}", i),
+                "user:dave".to_string(),
+                "file:/export.txt".to_string(),
+                "file.write".to_string(),
+                serde_json::json!({}),
+            );
+            store.store_vakya(vakya).await.unwrap();
+
+            let effect = EffectRecord::new(vakya_id.clone(), EffectBucket::Update, "file:/export.txt".to_string());
+            store.store_effect(effect).await.unwrap();
+
+            let receipt = ReceiptRecord::new(
+                vakya_id,
+                format!("hash-{}", i),
+                aapi_core::error::ReasonCode::Success,
+                "gateway-1".to_string(),
+                serde_json::json!({}),
+            );
+            store.store_receipt(receipt).await.unwrap();
+        }
+
+        let mut out = Vec::new();
+        let written = store.export_jsonl(&ExportFilter::new().from(start), &mut out).await.unwrap();
+        // 9 records plus a checkpoint per tree
+        assert_eq!(written, 12);
+
+        let lines: Vec<ExportRecord> = String::from_utf8(out).unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 12);
+
+        let records = &lines[..9];
+        assert!(records.windows(2).all(|w| w[0].created_at() <= w[1].created_at()));
+        match &records[0] {
+            ExportRecord::Vakya(v) => {
+                assert_eq!(v.leaf_index, Some(0));
+                assert!(v.merkle_root.is_some());
+            }
+            other => panic!("expected vakya first, got {:?}", other),
+        }
+        match &lines[11] {
+            ExportRecord::Checkpoint(c) => {
+                assert_eq!(c.tree_type, TreeType::Receipt);
+                assert_eq!(c.tree_size, 3);
+            }
+            other => panic!("expected checkpoint, got {:?}", other),
+        }
+
+        let mut out = Vec::new();
+        let filter = ExportFilter::new().record_type(TreeType::Effect).to(start);
+        let written = store.export_jsonl(&filter, &mut out).await.unwrap();
+        assert_eq!(written, 1); // only the effect tree checkpoint
+    }
 }
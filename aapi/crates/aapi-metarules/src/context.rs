@@ -1,6 +1,7 @@
 //! Evaluation context for MetaRules
 
 use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::net::IpAddr;
@@ -8,6 +9,8 @@ use std::net::IpAddr;
 use aapi_core::Vakya;
 use aapi_core::types::PrincipalId;
 
+use crate::error::{MetaRulesError, MetaRulesResult};
+
 /// Context for policy evaluation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EvaluationContext {
@@ -15,6 +18,9 @@ pub struct EvaluationContext {
     pub vakya: Vakya,
     /// Current timestamp
     pub timestamp: DateTime<Utc>,
+    /// Timezone Time conditions are evaluated in (IANA name, default UTC)
+    #[serde(default = "default_timezone")]
+    pub timezone: Tz,
     /// Request source IP
     pub source_ip: Option<String>,
     /// Geographic location
@@ -25,6 +31,13 @@ pub struct EvaluationContext {
     pub environment: String,
     /// Custom attributes
     pub attributes: HashMap<String, serde_json::Value>,
+    /// Record a `DecisionTrace` during evaluation
+    #[serde(default)]
+    pub explain: bool,
+    /// What the adapter the action resolves to declares about undoing it;
+    /// `None` when no adapter was resolved
+    #[serde(default)]
+    pub reversibility: Option<Reversibility>,
 }
 
 impl EvaluationContext {
@@ -32,14 +45,36 @@ impl EvaluationContext {
         Self {
             vakya,
             timestamp: Utc::now(),
+            timezone: default_timezone(),
             source_ip: None,
             geo: None,
             session: None,
             environment: "production".to_string(),
             attributes: HashMap::new(),
+            explain: false,
+            reversibility: None,
         }
     }
 
+    /// Enable explain mode; the decision will carry a `DecisionTrace`
+    pub fn with_explain(mut self) -> Self {
+        self.explain = true;
+        self
+    }
+
+    /// Evaluate Time conditions in the given IANA timezone, e.g. `Europe/Berlin`
+    pub fn with_timezone(mut self, timezone: &str) -> MetaRulesResult<Self> {
+        self.timezone = timezone.parse().map_err(|_| {
+            MetaRulesError::ContextError(format!("Unknown timezone: {}", timezone))
+        })?;
+        Ok(self)
+    }
+
+    /// The evaluation timestamp in the context's timezone
+    pub fn local_time(&self) -> DateTime<Tz> {
+        self.timestamp.with_timezone(&self.timezone)
+    }
+
     pub fn with_source_ip(mut self, ip: impl Into<String>) -> Self {
         self.source_ip = Some(ip.into());
         self
@@ -65,6 +100,11 @@ impl EvaluationContext {
         self
     }
 
+    pub fn with_reversibility(mut self, reversibility: Reversibility) -> Self {
+        self.reversibility = Some(reversibility);
+        self
+    }
+
     /// Get the actor principal ID
     pub fn actor(&self) -> &PrincipalId {
         &self.vakya.v1_karta.pid
@@ -91,6 +131,10 @@ impl EvaluationContext {
     }
 }
 
+fn default_timezone() -> Tz {
+    Tz::UTC
+}
+
 /// Geographic context
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GeoContext {
@@ -204,6 +248,31 @@ impl SessionContext {
     }
 }
 
+/// Whether an action can be undone, as resolved before execution
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Reversibility {
+    /// The adapter supports rolling the action back
+    pub can_rollback: bool,
+    /// The `reversible` flag of the adapter's action descriptor, if it
+    /// publishes one
+    pub declared_reversible: Option<bool>,
+}
+
+impl Reversibility {
+    pub fn new(can_rollback: bool, declared_reversible: Option<bool>) -> Self {
+        Self {
+            can_rollback,
+            declared_reversible,
+        }
+    }
+
+    /// The adapter can roll the action back and does not declare it
+    /// irreversible
+    pub fn reversible(&self) -> bool {
+        self.can_rollback && self.declared_reversible.unwrap_or(true)
+    }
+}
+
 /// Rate limit context
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RateLimitContext {
@@ -276,6 +345,7 @@ mod tests {
                 ttl: Some(TtlConstraint {
                     expires_at: Timestamp(chrono::Utc::now() + chrono::Duration::hours(1)),
                     max_duration_ms: None,
+                    not_before: None,
                 }),
                 budgets: vec![],
                 approval_lane: ApprovalLane::None,
@@ -303,6 +373,19 @@ mod tests {
         assert!(!ctx.is_production());
     }
 
+    #[test]
+    fn test_context_timezone() {
+        let mut ctx = EvaluationContext::new(create_test_vakya());
+        ctx.timestamp = "2024-03-01T20:00:00Z".parse().unwrap();
+        assert_eq!(ctx.timezone, Tz::UTC);
+        assert_eq!(ctx.local_time().format("%H").to_string(), "20");
+
+        let ctx = ctx.with_timezone("Asia/Tokyo").unwrap();
+        assert_eq!(ctx.local_time().format("%Y-%m-%d %H").to_string(), "2024-03-02 05");
+
+        assert!(EvaluationContext::new(create_test_vakya()).with_timezone("Mars/Olympus").is_err());
+    }
+
     #[test]
     fn test_rate_limit_context() {
         let mut ctx = RateLimitContext::new("user:test", 100, 60);
@@ -4,6 +4,8 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::rules::{ConditionType, Operator};
+
 /// Result of policy evaluation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PolicyDecision {
@@ -25,6 +27,9 @@ pub struct PolicyDecision {
     pub timestamp: DateTime<Utc>,
     /// Decision ID for audit
     pub decision_id: String,
+    /// Per-rule evaluation trace, present when explain mode is on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace: Option<DecisionTrace>,
 }
 
 impl PolicyDecision {
@@ -40,6 +45,7 @@ impl PolicyDecision {
             advice: vec![],
             timestamp: Utc::now(),
             decision_id: uuid::Uuid::new_v4().to_string(),
+            trace: None,
         }
     }
 
@@ -55,6 +61,7 @@ impl PolicyDecision {
             advice: vec![],
             timestamp: Utc::now(),
             decision_id: uuid::Uuid::new_v4().to_string(),
+            trace: None,
         }
     }
 
@@ -70,6 +77,7 @@ impl PolicyDecision {
             advice: vec![],
             timestamp: Utc::now(),
             decision_id: uuid::Uuid::new_v4().to_string(),
+            trace: None,
         }
     }
 
@@ -85,6 +93,12 @@ impl PolicyDecision {
         self
     }
 
+    /// Attach an evaluation trace
+    pub fn with_trace(mut self, trace: DecisionTrace) -> Self {
+        self.trace = Some(trace);
+        self
+    }
+
     /// Add advice
     pub fn with_advice(mut self, advice: impl Into<String>) -> Self {
         self.advice.push(advice.into());
@@ -100,6 +114,17 @@ impl PolicyDecision {
     pub fn has_obligations(&self) -> bool {
         !self.obligations.is_empty()
     }
+
+    /// Values from `Annotate` obligations, keyed by annotation name
+    pub fn annotations(&self) -> serde_json::Map<String, serde_json::Value> {
+        self.obligations.iter()
+            .filter(|o| o.obligation_type == ObligationType::Annotate)
+            .filter_map(|o| {
+                let name = o.parameters.get("name")?.as_str()?;
+                Some((name.to_string(), o.parameters.get("value")?.clone()))
+            })
+            .collect()
+    }
 }
 
 /// Type of decision
@@ -133,6 +158,51 @@ pub struct MatchedRule {
     pub matched_conditions: Vec<String>,
 }
 
+/// Explanation of how a decision was reached
+///
+/// Rules are listed in evaluation order. Conditions are AND-ed and
+/// short-circuit, so each rule lists its conditions up to and including the
+/// first one that failed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DecisionTrace {
+    /// Every rule that was evaluated
+    pub rules: Vec<RuleTrace>,
+}
+
+/// Evaluation record for a single rule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleTrace {
+    /// Policy containing the rule
+    pub policy_id: String,
+    /// Rule ID
+    pub rule_id: String,
+    /// Rule name
+    pub rule_name: String,
+    /// Rule effect
+    pub effect: RuleEffect,
+    /// Whether all conditions passed
+    pub matched: bool,
+    /// Evaluated conditions
+    pub conditions: Vec<ConditionTrace>,
+}
+
+/// Evaluation record for a single condition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConditionTrace {
+    /// Condition type
+    pub condition_type: ConditionType,
+    /// Field looked up in the context
+    pub field: String,
+    /// Comparison operator
+    pub operator: Operator,
+    /// Value from the rule
+    pub expected: serde_json::Value,
+    /// Value found in the context (the boolean result for expressions)
+    pub actual: serde_json::Value,
+    /// Whether the condition held
+    pub passed: bool,
+}
+
 /// Rule effect
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -153,6 +223,13 @@ pub struct ApprovalRequirement {
     pub approvers: Vec<String>,
     /// Minimum approvals needed
     pub min_approvals: u32,
+    /// Approvers with a voting weight; empty means every approval counts as 1
+    #[serde(default)]
+    pub weighted_approvers: Vec<(String, u32)>,
+    /// Total weight needed when `weighted_approvers` is set; defaults to
+    /// `min_approvals`
+    #[serde(default)]
+    pub required_weight: Option<u32>,
     /// Approval timeout in seconds
     pub timeout_secs: Option<u64>,
     /// Reason for requiring approval
@@ -166,6 +243,8 @@ impl ApprovalRequirement {
             approval_type,
             approvers: vec![],
             min_approvals: 1,
+            weighted_approvers: vec![],
+            required_weight: None,
             timeout_secs: None,
             reason: reason.into(),
         }
@@ -181,6 +260,16 @@ impl ApprovalRequirement {
         self
     }
 
+    pub fn with_weighted_approvers(mut self, approvers: Vec<(String, u32)>) -> Self {
+        self.weighted_approvers = approvers;
+        self
+    }
+
+    pub fn with_required_weight(mut self, weight: u32) -> Self {
+        self.required_weight = Some(weight);
+        self
+    }
+
     pub fn with_timeout(mut self, timeout_secs: u64) -> Self {
         self.timeout_secs = Some(timeout_secs);
         self
@@ -254,6 +343,8 @@ pub enum ObligationType {
     Redact,
     /// Rate limit
     RateLimit,
+    /// Record a named value in the receipt
+    Annotate,
     /// Custom obligation
     Custom(String),
 }
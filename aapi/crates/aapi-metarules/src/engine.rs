@@ -1,18 +1,36 @@
 //! Policy evaluation engine
 
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
+use aapi_core::{Namespace, NamespaceValidator};
+
 use crate::context::EvaluationContext;
-use crate::decision::{PolicyDecision, DecisionType, MatchedRule, RuleEffect};
+use crate::decision::{
+    PolicyDecision, DecisionType, MatchedRule, RuleEffect,
+    DecisionTrace, RuleTrace, ConditionTrace,
+    Obligation, ObligationType, ObligationTiming,
+};
 use crate::error::{MetaRulesError, MetaRulesResult};
+use crate::expression::CompiledExpression;
 use crate::rules::{Policy, Rule, Condition, ConditionType, Operator};
 
 /// Policy evaluation engine
+///
+/// Clones share the same policy set, so a clone handed to a reload task
+/// updates the engine used for evaluation.
+#[derive(Clone)]
 pub struct PolicyEngine {
     policies: Arc<RwLock<HashMap<String, Policy>>>,
+    /// Compiled CEL programs keyed by source text
+    expressions: Arc<RwLock<HashMap<String, CompiledExpression>>>,
+    /// Compiled `RegexCapture` patterns keyed by source text
+    patterns: Arc<RwLock<HashMap<String, regex::Regex>>>,
+    /// Bumped on every change to the policy set
+    version: Arc<AtomicU64>,
     /// Default decision when no policies match
     default_decision: DecisionType,
 }
@@ -27,6 +45,9 @@ impl PolicyEngine {
     pub fn new() -> Self {
         Self {
             policies: Arc::new(RwLock::new(HashMap::new())),
+            expressions: Arc::new(RwLock::new(HashMap::new())),
+            patterns: Arc::new(RwLock::new(HashMap::new())),
+            version: Arc::new(AtomicU64::new(0)),
             default_decision: DecisionType::Deny,
         }
     }
@@ -36,17 +57,86 @@ impl PolicyEngine {
         self
     }
 
+    /// Policy set version
+    ///
+    /// Incremented whenever policies are added, removed or replaced; anything
+    /// caching decisions should treat a change as invalidation.
+    pub fn version(&self) -> u64 {
+        self.version.load(Ordering::SeqCst)
+    }
+
     /// Add a policy
-    pub async fn add_policy(&self, policy: Policy) {
+    ///
+    /// Expression conditions and `RegexCapture` patterns are compiled here;
+    /// a policy containing an invalid CEL program or pattern is rejected and
+    /// not added.
+    pub async fn add_policy(&self, policy: Policy) -> MetaRulesResult<()> {
+        validate_ranges(&policy)?;
+        let captures = compile_captures(&policy)?;
+        let compiled = compile_expressions(&policy)?;
+
         let mut policies = self.policies.write().await;
         info!(policy_id = %policy.id, policy_name = %policy.name, "Adding policy");
-        policies.insert(policy.id.clone(), policy);
+        let replaced = policies.insert(policy.id.clone(), policy).is_some();
+
+        let mut expressions = self.expressions.write().await;
+        let mut patterns = self.patterns.write().await;
+        for expr in compiled {
+            expressions.insert(expr.source().to_string(), expr);
+        }
+        for regex in captures {
+            patterns.insert(regex.as_str().to_string(), regex);
+        }
+        // The policy it replaces may have used programs nothing else does
+        if replaced {
+            retain_referenced(&policies, &mut expressions, &mut patterns);
+        }
+        self.version.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Atomically replace the whole policy set
+    ///
+    /// Every policy is compiled before anything is swapped in; if any is
+    /// invalid the current set is left untouched and the error returned.
+    pub async fn replace_policies(&self, new_policies: Vec<Policy>) -> MetaRulesResult<()> {
+        let mut new_expressions = HashMap::new();
+        let mut new_patterns = HashMap::new();
+        for policy in &new_policies {
+            validate_ranges(policy)?;
+            for regex in compile_captures(policy)? {
+                new_patterns.insert(regex.as_str().to_string(), regex);
+            }
+            for expr in compile_expressions(policy)? {
+                new_expressions.insert(expr.source().to_string(), expr);
+            }
+        }
+
+        let mut policies = self.policies.write().await;
+        let mut expressions = self.expressions.write().await;
+        let mut patterns = self.patterns.write().await;
+        *policies = new_policies.into_iter().map(|p| (p.id.clone(), p)).collect();
+        *expressions = new_expressions;
+        *patterns = new_patterns;
+        let version = self.version.fetch_add(1, Ordering::SeqCst) + 1;
+
+        info!(policies = policies.len(), version, "Replaced policy set");
+        Ok(())
     }
 
     /// Remove a policy
     pub async fn remove_policy(&self, policy_id: &str) -> Option<Policy> {
         let mut policies = self.policies.write().await;
-        policies.remove(policy_id)
+        let removed = policies.remove(policy_id);
+
+        if removed.is_some() {
+            let mut expressions = self.expressions.write().await;
+            let mut patterns = self.patterns.write().await;
+            retain_referenced(&policies, &mut expressions, &mut patterns);
+            self.version.fetch_add(1, Ordering::SeqCst);
+        }
+
+        removed
     }
 
     /// Get a policy by ID
@@ -64,6 +154,8 @@ impl PolicyEngine {
     /// Evaluate a context against all policies
     pub async fn evaluate(&self, context: &EvaluationContext) -> MetaRulesResult<PolicyDecision> {
         let policies = self.policies.read().await;
+        let expressions = self.expressions.read().await;
+        let patterns = self.patterns.read().await;
         
         // Sort policies by priority (higher first)
         let mut sorted_policies: Vec<&Policy> = policies.values()
@@ -72,7 +164,9 @@ impl PolicyEngine {
         sorted_policies.sort_by(|a, b| b.priority.cmp(&a.priority));
 
         let mut matched_rules = Vec::new();
+        let mut annotations = Vec::new();
         let mut final_decision: Option<PolicyDecision> = None;
+        let mut trace = context.explain.then(DecisionTrace::default);
 
         for policy in sorted_policies {
             debug!(policy_id = %policy.id, "Evaluating policy");
@@ -84,7 +178,27 @@ impl PolicyEngine {
             sorted_rules.sort_by(|a, b| b.priority.cmp(&a.priority));
 
             for rule in sorted_rules {
-                if self.evaluate_rule(rule, context)? {
+                let mut conditions = Vec::new();
+                let matched = self.evaluate_rule(
+                    rule,
+                    context,
+                    &expressions,
+                    &patterns,
+                    trace.is_some().then_some(&mut conditions),
+                )?;
+
+                if let Some(ref mut trace) = trace {
+                    trace.rules.push(RuleTrace {
+                        policy_id: policy.id.clone(),
+                        rule_id: rule.id.clone(),
+                        rule_name: rule.name.clone(),
+                        effect: rule.effect,
+                        matched,
+                        conditions,
+                    });
+                }
+
+                if matched {
                     debug!(rule_id = %rule.id, effect = ?rule.effect, "Rule matched");
                     
                     matched_rules.push(MatchedRule {
@@ -96,6 +210,7 @@ impl PolicyEngine {
                             .map(|c| format!("{:?}", c.condition_type))
                             .collect(),
                     });
+                    annotations.extend(self.capture_annotations(rule, context, &patterns)?);
 
                     // First matching rule with Deny or RequireApproval takes precedence
                     if rule.effect == RuleEffect::Deny {
@@ -133,36 +248,116 @@ impl PolicyEngine {
         }
 
         // Return final decision or default
-        Ok(final_decision.unwrap_or_else(|| {
+        let mut decision = final_decision.unwrap_or_else(|| {
             match self.default_decision {
                 DecisionType::Allow => PolicyDecision::allow("No matching rules, default allow"),
                 _ => PolicyDecision::deny("No matching rules, default deny"),
             }
-        }))
+        });
+        decision.obligations.extend(annotations);
+
+        Ok(match trace {
+            Some(trace) => decision.with_trace(trace),
+            None => decision,
+        })
     }
 
     /// Evaluate a single rule against context
-    fn evaluate_rule(&self, rule: &Rule, context: &EvaluationContext) -> MetaRulesResult<bool> {
+    fn evaluate_rule(
+        &self,
+        rule: &Rule,
+        context: &EvaluationContext,
+        expressions: &HashMap<String, CompiledExpression>,
+        patterns: &HashMap<String, regex::Regex>,
+        mut trace: Option<&mut Vec<ConditionTrace>>,
+    ) -> MetaRulesResult<bool> {
         // All conditions must match (AND logic)
         for condition in &rule.conditions {
-            if !self.evaluate_condition(condition, context)? {
+            let (passed, actual) = self.evaluate_condition(condition, context, expressions, patterns)?;
+
+            if let Some(trace) = trace.as_deref_mut() {
+                trace.push(ConditionTrace {
+                    condition_type: condition.condition_type.clone(),
+                    field: condition.field.clone(),
+                    operator: condition.operator.clone(),
+                    expected: condition.value.clone(),
+                    actual,
+                    passed,
+                });
+            }
+
+            if !passed {
                 return Ok(false);
             }
         }
         Ok(true)
     }
 
-    /// Evaluate a single condition
-    fn evaluate_condition(&self, condition: &Condition, context: &EvaluationContext) -> MetaRulesResult<bool> {
+    /// `Annotate` obligations for the named groups of a matched rule's
+    /// `RegexCapture` conditions
+    fn capture_annotations(
+        &self,
+        rule: &Rule,
+        context: &EvaluationContext,
+        patterns: &HashMap<String, regex::Regex>,
+    ) -> MetaRulesResult<Vec<Obligation>> {
+        let mut obligations = Vec::new();
+        for condition in rule.conditions.iter().filter(|c| c.operator == Operator::RegexCapture) {
+            let actual = self.get_field_value(condition, context)?;
+            let Some(text) = actual.as_str() else { continue };
+            let regex = compiled_pattern(condition, patterns)?;
+            let Some(captures) = regex.captures(text) else { continue };
+
+            for name in regex.capture_names().flatten() {
+                if let Some(value) = captures.name(name) {
+                    obligations.push(
+                        Obligation::new(ObligationType::Annotate, ObligationTiming::After)
+                            .with_parameter("name", serde_json::json!(name))
+                            .with_parameter("value", serde_json::json!(value.as_str()))
+                            .with_parameter("rule_id", serde_json::json!(rule.id)),
+                    );
+                }
+            }
+        }
+        Ok(obligations)
+    }
+
+    /// Evaluate a single condition, returning the outcome and the value it was checked against
+    fn evaluate_condition(
+        &self,
+        condition: &Condition,
+        context: &EvaluationContext,
+        expressions: &HashMap<String, CompiledExpression>,
+        patterns: &HashMap<String, regex::Regex>,
+    ) -> MetaRulesResult<(bool, serde_json::Value)> {
+        if condition.condition_type == ConditionType::Expression {
+            let source = condition.value.as_str().unwrap_or_default();
+            let expr = expressions.get(source).ok_or_else(|| {
+                MetaRulesError::EvaluationFailed(format!("Expression not compiled: {}", source))
+            })?;
+            let result = expr.evaluate(context)?;
+            return Ok((result, serde_json::Value::Bool(result)));
+        }
+
         let actual_value = self.get_field_value(condition, context)?;
-        
+        let passed = self.apply_operator(condition, &actual_value, patterns)?;
+        Ok((passed, actual_value))
+    }
+
+    /// Apply a condition's operator to the value found in the context
+    fn apply_operator(
+        &self,
+        condition: &Condition,
+        actual_value: &serde_json::Value,
+        patterns: &HashMap<String, regex::Regex>,
+    ) -> MetaRulesResult<bool> {
         match condition.operator {
-            Operator::Eq => Ok(actual_value == condition.value),
-            Operator::Ne => Ok(actual_value != condition.value),
-            Operator::Gt => self.compare_values(&actual_value, &condition.value, |a, b| a > b),
-            Operator::Gte => self.compare_values(&actual_value, &condition.value, |a, b| a >= b),
-            Operator::Lt => self.compare_values(&actual_value, &condition.value, |a, b| a < b),
-            Operator::Lte => self.compare_values(&actual_value, &condition.value, |a, b| a <= b),
+            Operator::Eq => Ok(*actual_value == condition.value),
+            Operator::Ne => Ok(*actual_value != condition.value),
+            Operator::Gt => self.compare_values(actual_value, &condition.value, |a, b| a > b),
+            Operator::Gte => self.compare_values(actual_value, &condition.value, |a, b| a >= b),
+            Operator::Lt => self.compare_values(actual_value, &condition.value, |a, b| a < b),
+            Operator::Lte => self.compare_values(actual_value, &condition.value, |a, b| a <= b),
             Operator::Contains => {
                 if let (Some(haystack), Some(needle)) = (actual_value.as_str(), condition.value.as_str()) {
                     Ok(haystack.contains(needle))
@@ -179,6 +374,13 @@ impl PolicyEngine {
                     Ok(false)
                 }
             }
+            Operator::NotStartsWith => {
+                if let (Some(s), Some(prefix)) = (actual_value.as_str(), condition.value.as_str()) {
+                    Ok(!s.starts_with(prefix))
+                } else {
+                    Ok(true)
+                }
+            }
             Operator::EndsWith => {
                 if let (Some(s), Some(suffix)) = (actual_value.as_str(), condition.value.as_str()) {
                     Ok(s.ends_with(suffix))
@@ -194,22 +396,48 @@ impl PolicyEngine {
                     Ok(false)
                 }
             }
+            Operator::RegexCapture => {
+                match actual_value.as_str() {
+                    Some(s) => Ok(compiled_pattern(condition, patterns)?.is_match(s)),
+                    None => Ok(false),
+                }
+            }
             Operator::In => {
                 if let Some(arr) = condition.value.as_array() {
-                    Ok(arr.contains(&actual_value))
+                    Ok(arr.contains(actual_value))
                 } else {
                     Ok(false)
                 }
             }
             Operator::NotIn => {
-                if let Some(arr) = condition.value.as_array() {
-                    Ok(!arr.contains(&actual_value))
+                if actual_value.is_null() {
+                    Ok(false)
+                } else if let Some(arr) = condition.value.as_array() {
+                    Ok(!arr.contains(actual_value))
                 } else {
                     Ok(true)
                 }
             }
             Operator::Exists => Ok(!actual_value.is_null()),
             Operator::NotExists => Ok(actual_value.is_null()),
+            Operator::Between | Operator::NotBetween => {
+                let (low, high) = range_bounds(&condition.value).ok_or_else(|| {
+                    MetaRulesError::EvaluationFailed(format!(
+                        "{:?} on {} needs a [low, high] value",
+                        condition.operator, condition.field
+                    ))
+                })?;
+                let within = in_range(actual_value, low, high);
+                Ok(match condition.operator {
+                    Operator::Between => within == Some(true),
+                    _ => within == Some(false),
+                })
+            }
+            Operator::Within | Operator::NotWithin => {
+                let within = actual_value.as_str()
+                    .is_some_and(|ns| namespace_within(ns, &condition.value));
+                Ok(within == (condition.operator == Operator::Within))
+            }
         }
     }
 
@@ -230,6 +458,11 @@ impl PolicyEngine {
                     "action" => Ok(serde_json::json!(context.vakya.v3_kriya.action)),
                     "domain" => Ok(serde_json::json!(context.vakya.v3_kriya.domain)),
                     "verb" => Ok(serde_json::json!(context.vakya.v3_kriya.verb)),
+                    "reversible" => Ok(serde_json::json!(context.reversibility.map(|r| r.reversible()))),
+                    "can_rollback" => Ok(serde_json::json!(context.reversibility.map(|r| r.can_rollback))),
+                    "declared_reversible" => Ok(serde_json::json!(
+                        context.reversibility.and_then(|r| r.declared_reversible)
+                    )),
                     _ => Ok(serde_json::Value::Null),
                 }
             }
@@ -241,8 +474,14 @@ impl PolicyEngine {
                     _ => Ok(serde_json::Value::Null),
                 }
             }
+            ConditionType::Namespace => {
+                match condition.field.as_str() {
+                    "ns" => Ok(serde_json::json!(context.vakya.v2_karma.ns.as_ref().map(|n| &n.0))),
+                    _ => Ok(serde_json::Value::Null),
+                }
+            }
             ConditionType::Time => {
-                let now = context.timestamp;
+                let now = context.local_time();
                 match condition.field.as_str() {
                     "hour" => Ok(serde_json::json!(now.format("%H").to_string())),
                     "minute" => Ok(serde_json::json!(now.format("%M").to_string())),
@@ -287,6 +526,20 @@ impl PolicyEngine {
                     .cloned()
                     .unwrap_or(serde_json::Value::Null))
             }
+            ConditionType::Hetu => {
+                let hetu = context.vakya.meta.hetu.as_ref();
+                match condition.field.as_str() {
+                    "reason" => Ok(serde_json::json!(hetu
+                        .map(|h| h.reason.trim())
+                        .filter(|reason| !reason.is_empty()))),
+                    "confidence" => Ok(serde_json::json!(hetu.and_then(|h| h.confidence).unwrap_or(0.0))),
+                    "chain_length" => Ok(serde_json::json!(hetu.map_or(0, |h| h.chain.len()))),
+                    "evidence_count" => Ok(serde_json::json!(hetu.map_or(0, |h| h.evidence_cids.len()))),
+                    _ => Ok(serde_json::Value::Null),
+                }
+            }
+            // Expressions are evaluated as a whole, not via field lookup
+            ConditionType::Expression => Ok(serde_json::Value::Null),
         }
     }
 
@@ -311,6 +564,146 @@ impl PolicyEngine {
     }
 }
 
+/// Drop cached programs and patterns no longer referenced by any policy
+fn retain_referenced(
+    policies: &HashMap<String, Policy>,
+    expressions: &mut HashMap<String, CompiledExpression>,
+    patterns: &mut HashMap<String, regex::Regex>,
+) {
+    let referenced = |source: &str, is_kind: fn(&Condition) -> bool| {
+        policies.values().any(|p| {
+            p.rules.iter().flat_map(|r| &r.conditions).any(|c| {
+                is_kind(c) && c.value.as_str() == Some(source)
+            })
+        })
+    };
+    expressions.retain(|source, _| referenced(source, |c| c.condition_type == ConditionType::Expression));
+    patterns.retain(|source, _| referenced(source, |c| c.operator == Operator::RegexCapture));
+}
+
+/// Check that every range condition in a policy has a `[low, high]` value
+fn validate_ranges(policy: &Policy) -> MetaRulesResult<()> {
+    let conditions = policy.rules.iter()
+        .flat_map(|rule| rule.conditions.iter().map(move |c| (rule, c)))
+        .filter(|(_, c)| matches!(c.operator, Operator::Between | Operator::NotBetween));
+
+    for (rule, condition) in conditions {
+        let (low, high) = range_bounds(&condition.value).ok_or_else(|| MetaRulesError::InvalidRule(format!(
+            "Rule {} in policy {}: {:?} value must be a [low, high] array",
+            rule.id, policy.id, condition.operator
+        )))?;
+        if in_range(low, low, high).is_none() {
+            return Err(MetaRulesError::InvalidRule(format!(
+                "Rule {} in policy {}: range bounds must both be numbers or both be strings",
+                rule.id, policy.id
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Compile the `RegexCapture` patterns of a policy, rejecting any that does
+/// not compile or has no named group to capture
+fn compile_captures(policy: &Policy) -> MetaRulesResult<Vec<regex::Regex>> {
+    let conditions = policy.rules.iter()
+        .flat_map(|rule| rule.conditions.iter().map(move |c| (rule, c)))
+        .filter(|(_, c)| c.operator == Operator::RegexCapture);
+
+    let mut compiled = Vec::new();
+    for (rule, condition) in conditions {
+        let regex = capture_regex(condition).map_err(|e| MetaRulesError::InvalidRule(format!(
+            "Rule {} in policy {}: {}",
+            rule.id, policy.id, e
+        )))?;
+        if regex.capture_names().flatten().next().is_none() {
+            return Err(MetaRulesError::InvalidRule(format!(
+                "Rule {} in policy {}: RegexCapture pattern has no named group",
+                rule.id, policy.id
+            )));
+        }
+        compiled.push(regex);
+    }
+    Ok(compiled)
+}
+
+/// The compiled pattern of a `RegexCapture` condition
+fn compiled_pattern<'a>(
+    condition: &Condition,
+    patterns: &'a HashMap<String, regex::Regex>,
+) -> MetaRulesResult<&'a regex::Regex> {
+    let pattern = condition.value.as_str().unwrap_or_default();
+    patterns.get(pattern).ok_or_else(|| {
+        MetaRulesError::EvaluationFailed(format!("Pattern not compiled: {}", pattern))
+    })
+}
+
+/// Compile the pattern of a `RegexCapture` condition
+fn capture_regex(condition: &Condition) -> MetaRulesResult<regex::Regex> {
+    let pattern = condition.value.as_str().ok_or_else(|| MetaRulesError::EvaluationFailed(format!(
+        "RegexCapture on {} needs a pattern string",
+        condition.field
+    )))?;
+    regex::Regex::new(pattern)
+        .map_err(|e| MetaRulesError::EvaluationFailed(format!("Invalid pattern {}: {}", pattern, e)))
+}
+
+/// Whether `ns` is within the namespace, or any of the list of namespaces,
+/// in `value`
+fn namespace_within(ns: &str, value: &serde_json::Value) -> bool {
+    let parents = match value {
+        serde_json::Value::Array(values) => values.iter().filter_map(|v| v.as_str()).map(Namespace::new).collect(),
+        serde_json::Value::String(parent) => vec![Namespace::new(parent.as_str())],
+        _ => vec![],
+    };
+    NamespaceValidator::new(parents).is_allowed(&Namespace::new(ns))
+}
+
+/// Split a two-element `[low, high]` array
+fn range_bounds(value: &serde_json::Value) -> Option<(&serde_json::Value, &serde_json::Value)> {
+    match value.as_array()?.as_slice() {
+        [low, high] => Some((low, high)),
+        _ => None,
+    }
+}
+
+/// Whether `value` lies in the inclusive range, or `None` if the three
+/// values cannot be compared
+///
+/// Numeric strings compare as numbers so that time fields such as `"09"`
+/// work against `[9, 17]`; other strings compare lexicographically, which
+/// orders `YYYY-MM-DD` dates and zero-padded `HH:MM` times correctly.
+fn in_range(value: &serde_json::Value, low: &serde_json::Value, high: &serde_json::Value) -> Option<bool> {
+    let number = |v: &serde_json::Value| v.as_f64().or_else(|| v.as_str()?.trim().parse::<f64>().ok());
+    if let (Some(v), Some(lo), Some(hi)) = (number(value), number(low), number(high)) {
+        return Some(lo <= v && v <= hi);
+    }
+    match (value.as_str(), low.as_str(), high.as_str()) {
+        (Some(v), Some(lo), Some(hi)) => Some(lo <= v && v <= hi),
+        _ => None,
+    }
+}
+
+/// Compile every expression condition in a policy
+fn compile_expressions(policy: &Policy) -> MetaRulesResult<Vec<CompiledExpression>> {
+    policy.rules.iter()
+        .flat_map(|rule| rule.conditions.iter().map(move |c| (rule, c)))
+        .filter(|(_, c)| c.condition_type == ConditionType::Expression)
+        .map(|(rule, c)| {
+            let source = c.value.as_str().ok_or_else(|| MetaRulesError::InvalidRule(format!(
+                "Rule {} in policy {}: expression value must be a string",
+                rule.id, policy.id
+            )))?;
+            CompiledExpression::compile(source).map_err(|e| match e {
+                MetaRulesError::InvalidRule(msg) => MetaRulesError::InvalidRule(format!(
+                    "Rule {} in policy {}: {}",
+                    rule.id, policy.id, msg
+                )),
+                other => other,
+            })
+        })
+        .collect()
+}
+
 /// Simple glob matching
 fn glob_match(pattern: &str, value: &str) -> bool {
     if pattern == "*" {
@@ -370,18 +763,19 @@ impl PolicyEngineBuilder {
         self
     }
 
-    pub async fn build(self) -> PolicyEngine {
+    pub async fn build(self) -> MetaRulesResult<PolicyEngine> {
         for policy in self.policies {
-            self.engine.add_policy(policy).await;
+            self.engine.add_policy(policy).await?;
         }
-        self.engine
+        Ok(self.engine)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::rules::{Rule, Condition, Operator};
+    use crate::rules::{templates, Rule, Condition, Operator};
+    use crate::context::Reversibility;
     use aapi_core::*;
 
     fn create_test_vakya(action: &str) -> Vakya {
@@ -411,6 +805,7 @@ mod tests {
                 ttl: Some(TtlConstraint {
                     expires_at: Timestamp(chrono::Utc::now() + chrono::Duration::hours(1)),
                     max_duration_ms: None,
+                    not_before: None,
                 }),
                 budgets: vec![],
                 approval_lane: ApprovalLane::None,
@@ -436,7 +831,7 @@ mod tests {
                     .with_condition(Condition::action(Operator::EndsWith, ".read"))
             );
         
-        engine.add_policy(policy).await;
+        engine.add_policy(policy).await.unwrap();
 
         let vakya = create_test_vakya("file.read");
         let context = EvaluationContext::new(vakya);
@@ -455,7 +850,7 @@ mod tests {
                     .with_condition(Condition::action(Operator::EndsWith, ".delete"))
             );
         
-        engine.add_policy(policy).await;
+        engine.add_policy(policy).await.unwrap();
 
         let vakya = create_test_vakya("file.delete");
         let context = EvaluationContext::new(vakya);
@@ -486,6 +881,358 @@ mod tests {
         assert!(decision.allowed);
     }
 
+    #[tokio::test]
+    async fn test_expression_condition() {
+        let engine = PolicyEngine::new();
+
+        let policy = Policy::new("test", "Test Policy")
+            .with_rule(
+                Rule::deny("deny-admin-delete", "Deny admin deletes")
+                    .with_condition(Condition::expression(
+                        r#"actor.role == "admin" && action.verb == "delete""#,
+                    ))
+            );
+
+        engine.add_policy(policy).await.unwrap();
+
+        let decision = engine.evaluate(&EvaluationContext::new(create_test_vakya("file.delete"))).await.unwrap();
+        assert!(!decision.allowed);
+        assert_eq!(decision.matched_rules.len(), 1);
+
+        let decision = engine.evaluate(&EvaluationContext::new(create_test_vakya("file.read"))).await.unwrap();
+        assert!(decision.matched_rules.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_time_condition_uses_context_timezone() {
+        let engine = PolicyEngine::new().with_default_allow();
+        engine.add_policy(
+            Policy::new("evening", "Evening Freeze")
+                .with_rule(Rule::deny("after-18", "Deny after 18:00")
+                    .with_condition(Condition::time("hour", Operator::Gte, "18")))
+        ).await.unwrap();
+
+        let mut context = EvaluationContext::new(create_test_vakya("file.write"));
+        context.timestamp = "2024-03-01T20:00:00Z".parse().unwrap();
+        assert!(!engine.evaluate(&context).await.unwrap().allowed);
+
+        // 20:00 UTC is 05:00 the next morning in Tokyo
+        let context = context.with_timezone("Asia/Tokyo").unwrap();
+        assert!(engine.evaluate(&context).await.unwrap().allowed);
+    }
+
+    #[tokio::test]
+    async fn test_namespace_within() {
+        let engine = PolicyEngine::new().with_default_allow();
+        engine.add_policy(
+            Policy::new("tenant", "Tenant Isolation")
+                .with_rule(Rule::deny("outside-tenant", "Deny anything outside org.example")
+                    .with_condition(Condition::namespace(Operator::NotWithin, "org.example")))
+        ).await.unwrap();
+
+        let evaluate = |ns: Option<&str>| {
+            let mut vakya = create_test_vakya("file.read");
+            vakya.v2_karma.ns = ns.map(Namespace::new);
+            let engine = engine.clone();
+            async move { engine.evaluate(&EvaluationContext::new(vakya)).await.unwrap().allowed }
+        };
+
+        assert!(evaluate(Some("org.example")).await);
+        assert!(evaluate(Some("org.example.billing")).await);
+        // Membership follows the hierarchy, not the string prefix
+        assert!(!evaluate(Some("org.examples")).await);
+        assert!(!evaluate(Some("org.other")).await);
+        assert!(!evaluate(None).await);
+
+        let within_any = Condition::new(
+            ConditionType::Namespace,
+            "ns",
+            Operator::Within,
+            serde_json::json!(["org.a", "org.b"]),
+        );
+        assert!(engine.apply_operator(&within_any, &serde_json::json!("org.b.team"), &HashMap::new()).unwrap());
+        assert!(!engine.apply_operator(&within_any, &serde_json::json!("org.c"), &HashMap::new()).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_actor_role_in_and_not_in() {
+        let engine = PolicyEngine::new();
+        let policy = Policy::new("rbac", "RBAC")
+            .with_rule(
+                Rule::allow("allow-staff", "Allow staff roles")
+                    .with_condition(Condition::actor_role(Operator::In, ["admin", "operator"]))
+                    .with_condition(Condition::actor_type(Operator::In, [ActorType::Human]))
+            )
+            .with_rule(
+                Rule::deny("deny-outsiders", "Deny other realms")
+                    .with_condition(Condition::actor_realm(Operator::NotIn, ["corp"]))
+            );
+        engine.add_policy(policy).await.unwrap();
+
+        // Role admin, no realm: the NotIn realm rule must not fire
+        let decision = engine.evaluate(&EvaluationContext::new(create_test_vakya("file.read"))).await.unwrap();
+        assert!(decision.allowed);
+
+        let mut vakya = create_test_vakya("file.read");
+        vakya.v1_karta.realm = Some("partner".to_string());
+        let decision = engine.evaluate(&EvaluationContext::new(vakya)).await.unwrap();
+        assert!(!decision.allowed);
+
+        let mut vakya = create_test_vakya("file.read");
+        vakya.v1_karta.role = Some("guest".to_string());
+        let decision = engine.evaluate(&EvaluationContext::new(vakya)).await.unwrap();
+        assert!(!decision.allowed);
+
+        // No role at all matches neither In nor NotIn
+        let mut vakya = create_test_vakya("file.read");
+        vakya.v1_karta.role = None;
+        assert!(!engine.evaluate(&EvaluationContext::new(vakya)).await.unwrap().allowed);
+        let not_in = Condition::actor_role(Operator::NotIn, ["guest"]);
+        assert!(!engine.apply_operator(&not_in, &serde_json::Value::Null, &HashMap::new()).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_hetu_requirements() {
+        let engine = PolicyEngine::new();
+        let policy = Policy::new("accountability", "Accountability")
+            .with_rule(templates::require_hetu_for_delete())
+            .with_rule(templates::approve_low_confidence(0.8))
+            .with_rule(Rule::allow("allow-all", "Allow all"));
+        engine.add_policy(policy).await.unwrap();
+
+        let with_hetu = |action: &str, reason: &str, confidence: Option<f64>| {
+            let mut vakya = create_test_vakya(action);
+            vakya.meta.hetu = Some(Hetu {
+                reason: reason.to_string(),
+                chain: vec![],
+                confidence,
+                evidence_cids: vec![],
+            });
+            EvaluationContext::new(vakya)
+        };
+
+        // No hetu, or a blank reason, is denied for deletes
+        let decision = engine.evaluate(&EvaluationContext::new(create_test_vakya("file.delete"))).await.unwrap();
+        assert_eq!(decision.decision, DecisionType::Deny);
+        let decision = engine.evaluate(&with_hetu("file.delete", "  ", Some(0.9))).await.unwrap();
+        assert_eq!(decision.decision, DecisionType::Deny);
+
+        let decision = engine.evaluate(&with_hetu("file.delete", "stale cache entry", Some(0.9))).await.unwrap();
+        assert!(decision.allowed);
+
+        // Unstated confidence counts as below the minimum
+        let decision = engine.evaluate(&with_hetu("file.delete", "stale cache entry", Some(0.5))).await.unwrap();
+        assert_eq!(decision.decision, DecisionType::PendingApproval);
+        let decision = engine.evaluate(&with_hetu("file.read", "report", None)).await.unwrap();
+        assert_eq!(decision.decision, DecisionType::PendingApproval);
+    }
+
+    #[tokio::test]
+    async fn test_reversibility_conditions() {
+        let engine = PolicyEngine::new();
+        let policy = Policy::new("undo", "Undo")
+            .with_rule(templates::require_approval_for_irreversible())
+            .with_rule(
+                Rule::deny("no-undeclared", "No undeclared actions")
+                    .with_condition(Condition::expression("action.can_rollback && action.declared_reversible == null")),
+            )
+            .with_rule(Rule::allow("allow-all", "Allow all"));
+        engine.add_policy(policy).await.unwrap();
+
+        let with = |reversibility: Reversibility| {
+            EvaluationContext::new(create_test_vakya("file.write")).with_reversibility(reversibility)
+        };
+
+        let decision = engine.evaluate(&with(Reversibility::new(true, Some(true)))).await.unwrap();
+        assert!(decision.allowed);
+        // Rollback support is not enough when the descriptor says otherwise
+        let decision = engine.evaluate(&with(Reversibility::new(true, Some(false)))).await.unwrap();
+        assert_eq!(decision.decision, DecisionType::PendingApproval);
+        let decision = engine.evaluate(&with(Reversibility::new(false, None))).await.unwrap();
+        assert_eq!(decision.decision, DecisionType::PendingApproval);
+        let decision = engine.evaluate(&with(Reversibility::new(true, None))).await.unwrap();
+        assert_eq!(decision.decision, DecisionType::Deny);
+
+        // Unresolved reversibility matches neither value
+        let decision = engine.evaluate(&EvaluationContext::new(create_test_vakya("file.write"))).await.unwrap();
+        assert!(decision.allowed);
+    }
+
+    #[tokio::test]
+    async fn test_between_numeric_range() {
+        let engine = PolicyEngine::new().with_default_allow();
+        engine.add_policy(
+            Policy::new("amounts", "Mid-size Amounts")
+                .with_rule(Rule::require_approval("review", "Review mid-size amounts")
+                    .with_condition(Condition::attribute("amount", Operator::Between, serde_json::json!([10, 100]))))
+                .with_rule(Rule::deny("outliers", "Deny out-of-range amounts")
+                    .with_condition(Condition::attribute("amount", Operator::NotBetween, serde_json::json!([0, 1000]))))
+        ).await.unwrap();
+
+        let evaluate = |amount: serde_json::Value| {
+            let context = EvaluationContext::new(create_test_vakya("payment.send")).with_attribute("amount", amount);
+            let engine = engine.clone();
+            async move { engine.evaluate(&context).await.unwrap() }
+        };
+
+        assert_eq!(evaluate(serde_json::json!(5)).await.decision, DecisionType::Allow);
+        // Bounds are inclusive
+        assert_eq!(evaluate(serde_json::json!(10)).await.decision, DecisionType::PendingApproval);
+        assert_eq!(evaluate(serde_json::json!("100")).await.decision, DecisionType::PendingApproval);
+        assert_eq!(evaluate(serde_json::json!(100.5)).await.decision, DecisionType::Allow);
+        assert_eq!(evaluate(serde_json::json!(-1)).await.decision, DecisionType::Deny);
+        // Values that cannot be compared satisfy neither operator
+        assert_eq!(evaluate(serde_json::json!("lots")).await.decision, DecisionType::Allow);
+    }
+
+    #[tokio::test]
+    async fn test_not_between_time_range() {
+        let engine = PolicyEngine::new().with_default_allow();
+        engine.add_policy(
+            Policy::new("hours", "Business Hours Only")
+                .with_rule(Rule::deny("outside-hours", "Deny outside 09:00-17:59")
+                    .with_condition(Condition::new(
+                        ConditionType::Time,
+                        "hour",
+                        Operator::NotBetween,
+                        serde_json::json!([9, 17]),
+                    )))
+                .with_rule(Rule::deny("freeze", "Deny during the March freeze")
+                    .with_condition(Condition::new(
+                        ConditionType::Time,
+                        "date",
+                        Operator::Between,
+                        serde_json::json!(["2024-03-10", "2024-03-20"]),
+                    )))
+        ).await.unwrap();
+
+        let at = |timestamp: &str| {
+            let mut context = EvaluationContext::new(create_test_vakya("file.write"));
+            context.timestamp = timestamp.parse().unwrap();
+            context
+        };
+
+        assert!(engine.evaluate(&at("2024-03-01T09:00:00Z")).await.unwrap().allowed);
+        assert!(engine.evaluate(&at("2024-03-01T17:59:00Z")).await.unwrap().allowed);
+        assert!(!engine.evaluate(&at("2024-03-01T18:00:00Z")).await.unwrap().allowed);
+        assert!(!engine.evaluate(&at("2024-03-01T02:00:00Z")).await.unwrap().allowed);
+        assert!(!engine.evaluate(&at("2024-03-15T12:00:00Z")).await.unwrap().allowed);
+        assert!(engine.evaluate(&at("2024-03-21T12:00:00Z")).await.unwrap().allowed);
+    }
+
+    #[tokio::test]
+    async fn test_regex_capture_annotates_decision() {
+        let engine = PolicyEngine::new();
+        engine.add_policy(
+            Policy::new("orders", "Order Access").with_rule(
+                Rule::allow("read-order", "Read orders")
+                    .with_condition(Condition::resource(Operator::RegexCapture, r"^http:/api/orders/(?P<order_id>\d+)$")),
+            ),
+        ).await.unwrap();
+
+        let mut vakya = create_test_vakya("http.get");
+        vakya.v2_karma.rid = aapi_core::ResourceId::new("http:/api/orders/4711");
+        let decision = engine.evaluate(&EvaluationContext::new(vakya.clone())).await.unwrap();
+        assert!(decision.allowed);
+        assert_eq!(decision.annotations().get("order_id"), Some(&serde_json::json!("4711")));
+        assert_eq!(decision.obligations[0].parameters["rule_id"], "read-order");
+
+        vakya.v2_karma.rid = aapi_core::ResourceId::new("http:/api/users/1");
+        let decision = engine.evaluate(&EvaluationContext::new(vakya)).await.unwrap();
+        assert!(!decision.allowed);
+        assert!(decision.annotations().is_empty());
+
+        for pattern in ["(unclosed", r"^orders/\d+$"] {
+            let policy = Policy::new("bad", "Bad Capture").with_rule(
+                Rule::allow("r1", "Broken").with_condition(Condition::resource(Operator::RegexCapture, pattern)),
+            );
+            assert!(matches!(engine.add_policy(policy).await, Err(MetaRulesError::InvalidRule(_))));
+        }
+
+        // Patterns are compiled once at load and dropped with their policy
+        assert_eq!(engine.patterns.read().await.len(), 1);
+        engine.remove_policy("orders").await;
+        assert!(engine.patterns.read().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_replacing_policy_drops_its_old_expressions() {
+        let engine = PolicyEngine::new();
+        let policy = |condition: &str| Policy::new("roles", "Role Access")
+            .with_rule(Rule::allow("r1", "By role").with_condition(Condition::expression(condition)));
+
+        engine.add_policy(policy("actor.role == \"admin\"")).await.unwrap();
+        engine.add_policy(policy("actor.role == \"operator\"")).await.unwrap();
+
+        let expressions = engine.expressions.read().await;
+        assert_eq!(expressions.keys().collect::<Vec<_>>(), vec!["actor.role == \"operator\""]);
+    }
+
+    #[tokio::test]
+    async fn test_malformed_range_rejected_at_load() {
+        let engine = PolicyEngine::new();
+
+        for value in [serde_json::json!(10), serde_json::json!([1, 2, 3]), serde_json::json!([1, "z"])] {
+            let policy = Policy::new("bad", "Bad Range").with_rule(
+                Rule::deny("r1", "Broken")
+                    .with_condition(Condition::attribute("amount", Operator::Between, value)),
+            );
+            assert!(matches!(engine.add_policy(policy).await, Err(MetaRulesError::InvalidRule(_))));
+        }
+        assert!(engine.get_policy("bad").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_expression_rejected_at_load() {
+        let engine = PolicyEngine::new();
+
+        let policy = Policy::new("bad", "Bad Policy")
+            .with_rule(Rule::allow("r1", "Broken").with_condition(Condition::expression("actor.role ==")));
+
+        assert!(matches!(engine.add_policy(policy).await, Err(MetaRulesError::InvalidRule(_))));
+        assert!(engine.get_policy("bad").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_explain_trace() {
+        let engine = PolicyEngine::new();
+
+        let policy = Policy::new("test", "Test Policy")
+            .with_rule(
+                Rule::deny("deny-guest-delete", "Deny Guest Delete")
+                    .with_condition(Condition::action(Operator::EndsWith, ".delete"))
+                    .with_condition(Condition::new(ConditionType::Actor, "role", Operator::Eq, serde_json::json!("guest")))
+                    .with_priority(10)
+            )
+            .with_rule(
+                Rule::allow("allow-delete", "Allow Delete")
+                    .with_condition(Condition::action(Operator::EndsWith, ".delete"))
+            );
+        engine.add_policy(policy).await.unwrap();
+
+        let vakya = create_test_vakya("file.delete");
+
+        let decision = engine.evaluate(&EvaluationContext::new(vakya.clone())).await.unwrap();
+        assert!(decision.trace.is_none());
+
+        let decision = engine.evaluate(&EvaluationContext::new(vakya).with_explain()).await.unwrap();
+        assert!(decision.allowed);
+
+        let trace = decision.trace.unwrap();
+        assert_eq!(trace.rules.len(), 2);
+
+        let skipped = &trace.rules[0];
+        assert_eq!(skipped.rule_id, "deny-guest-delete");
+        assert!(!skipped.matched);
+        assert_eq!(skipped.conditions.len(), 2);
+        assert!(skipped.conditions[0].passed);
+        assert!(!skipped.conditions[1].passed);
+        assert_eq!(skipped.conditions[1].actual, serde_json::json!("admin"));
+        assert_eq!(skipped.conditions[1].expected, serde_json::json!("guest"));
+
+        assert!(trace.rules[1].matched);
+    }
+
     #[test]
     fn test_glob_match() {
         assert!(glob_match("*", "anything"));
@@ -29,6 +29,9 @@ pub enum MetaRulesError {
     #[error("Budget exceeded: {0}")]
     BudgetExceeded(String),
 
+    #[error("Policy load error: {0}")]
+    PolicyLoad(String),
+
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
 }
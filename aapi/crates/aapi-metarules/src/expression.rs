@@ -0,0 +1,203 @@
+//! CEL expression conditions
+//!
+//! `ConditionType::Expression` conditions carry a [CEL](https://github.com/google/cel-spec)
+//! program in their `value` field. The condition matches when the program
+//! evaluates to `true`; `field` and `operator` are ignored.
+//!
+//! Programs are compiled when a policy is added to the [`PolicyEngine`](crate::PolicyEngine)
+//! and cached by source text, so syntax errors are reported at load time
+//! rather than on the first request.
+//!
+//! # Variable bindings
+//!
+//! | Variable     | Type   | Fields |
+//! |--------------|--------|--------|
+//! | `actor`      | map    | `pid`, `role`, `realm`, `key_id`, `actor_type` |
+//! | `action`     | map    | `action`, `domain`, `verb`, `reversible`, `can_rollback`, `declared_reversible` |
+//! | `resource`   | map    | `rid`, `kind`, `ns`, `version`, `labels` (map) |
+//! | `time`       | map    | `hour`, `minute`, `day_of_week` (1 = Monday), `date` (`YYYY-MM-DD`), `timestamp` (RFC 3339), `timezone` |
+//! | `env`        | string | Deployment environment, e.g. `"production"` |
+//! | `attributes` | map    | Custom attributes from the evaluation context |
+//!
+//! Optional fields that are unset are bound to `null`. `time` fields are
+//! computed in the context's timezone (UTC unless set).
+//!
+//! ```text
+//! actor.role == "admin" && action.verb in ["delete", "purge"] && env != "sandbox"
+//! time.hour >= 9 && time.hour < 18 && !(resource.rid.startsWith("file:/etc"))
+//! ```
+
+use std::sync::Arc;
+
+use cel_interpreter::{Context, Program, Value};
+use chrono::{Datelike, Timelike};
+
+use crate::context::EvaluationContext;
+use crate::error::{MetaRulesError, MetaRulesResult};
+
+/// A compiled CEL program ready for evaluation
+#[derive(Debug, Clone)]
+pub struct CompiledExpression {
+    source: String,
+    program: Arc<Program>,
+}
+
+impl CompiledExpression {
+    /// Compile a CEL source string
+    pub fn compile(source: &str) -> MetaRulesResult<Self> {
+        let program = Program::compile(source).map_err(|e| {
+            MetaRulesError::InvalidRule(format!("Invalid CEL expression `{}`: {}", source, e))
+        })?;
+
+        Ok(Self {
+            source: source.to_string(),
+            program: Arc::new(program),
+        })
+    }
+
+    /// Original source text
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Evaluate against a policy context; the result must be a boolean
+    pub fn evaluate(&self, context: &EvaluationContext) -> MetaRulesResult<bool> {
+        let cel_context = bind_context(context)?;
+
+        match self.program.execute(&cel_context) {
+            Ok(Value::Bool(result)) => Ok(result),
+            Ok(other) => Err(MetaRulesError::EvaluationFailed(format!(
+                "CEL expression `{}` returned {:?}, expected bool",
+                self.source, other
+            ))),
+            Err(e) => Err(MetaRulesError::EvaluationFailed(format!(
+                "CEL expression `{}` failed: {}",
+                self.source, e
+            ))),
+        }
+    }
+}
+
+/// Build the CEL variable bindings for an evaluation context
+fn bind_context(context: &EvaluationContext) -> MetaRulesResult<Context<'static>> {
+    let vakya = &context.vakya;
+    let now = context.local_time();
+
+    let bindings = [
+        ("actor", serde_json::json!({
+            "pid": vakya.v1_karta.pid.0,
+            "role": vakya.v1_karta.role,
+            "realm": vakya.v1_karta.realm,
+            "key_id": vakya.v1_karta.key_id,
+            "actor_type": format!("{:?}", vakya.v1_karta.actor_type),
+        })),
+        ("action", serde_json::json!({
+            "action": vakya.v3_kriya.action,
+            "domain": vakya.v3_kriya.domain,
+            "verb": vakya.v3_kriya.verb,
+            "reversible": context.reversibility.map(|r| r.reversible()),
+            "can_rollback": context.reversibility.map(|r| r.can_rollback),
+            "declared_reversible": context.reversibility.and_then(|r| r.declared_reversible),
+        })),
+        ("resource", serde_json::json!({
+            "rid": vakya.v2_karma.rid.0,
+            "kind": vakya.v2_karma.kind,
+            "ns": vakya.v2_karma.ns.as_ref().map(|n| &n.0),
+            "version": vakya.v2_karma.version,
+            "labels": vakya.v2_karma.labels,
+        })),
+        ("time", serde_json::json!({
+            "hour": now.hour(),
+            "minute": now.minute(),
+            "day_of_week": now.weekday().number_from_monday(),
+            "date": now.format("%Y-%m-%d").to_string(),
+            "timestamp": now.to_rfc3339(),
+            "timezone": context.timezone.name(),
+        })),
+        ("env", serde_json::json!(context.environment)),
+        ("attributes", serde_json::json!(context.attributes)),
+    ];
+
+    let mut cel_context = Context::default();
+    for (name, value) in bindings {
+        cel_context.add_variable(name, value).map_err(|e| {
+            MetaRulesError::ContextError(format!("Failed to bind `{}`: {}", name, e))
+        })?;
+    }
+
+    Ok(cel_context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aapi_core::*;
+
+    fn create_test_context() -> EvaluationContext {
+        let vakya = Vakya::builder()
+            .karta(Karta {
+                pid: PrincipalId::new("user:alice"),
+                role: Some("admin".to_string()),
+                realm: None,
+                key_id: None,
+                actor_type: ActorType::Human,
+                delegation_chain: vec![],
+            })
+            .karma(Karma {
+                rid: ResourceId::new("file:/data/report.csv"),
+                kind: Some("file".to_string()),
+                ns: None,
+                version: None,
+                labels: std::collections::HashMap::new(),
+            })
+            .kriya(Kriya::new("file", "delete"))
+            .adhikarana(Adhikarana {
+                cap: CapabilityRef::Reference { cap_ref: "cap:test".to_string() },
+                policy_ref: None,
+                ttl: None,
+                budgets: vec![],
+                approval_lane: ApprovalLane::None,
+                scopes: vec![],
+                context: None,
+                delegation_chain_cid: None,
+                execution_constraints: None,
+                port_id: None,
+                required_phase: None,
+                required_role: None,
+            })
+            .build()
+            .unwrap();
+
+        EvaluationContext::new(vakya)
+            .with_environment("staging")
+            .with_attribute("risk_score", serde_json::json!(42))
+    }
+
+    #[test]
+    fn test_compile_rejects_invalid_syntax() {
+        assert!(CompiledExpression::compile("actor.role == ").is_err());
+    }
+
+    #[test]
+    fn test_evaluate_bindings() {
+        let context = create_test_context();
+
+        let expr = CompiledExpression::compile(
+            r#"actor.role == "admin" && action.verb == "delete" && resource.rid.startsWith("file:/data")"#,
+        ).unwrap();
+        assert!(expr.evaluate(&context).unwrap());
+
+        let expr = CompiledExpression::compile(r#"env == "staging" && attributes.risk_score > 40"#).unwrap();
+        assert!(expr.evaluate(&context).unwrap());
+
+        let expr = CompiledExpression::compile("time.hour >= 0 && time.hour < 24").unwrap();
+        assert!(expr.evaluate(&context).unwrap());
+    }
+
+    #[test]
+    fn test_non_boolean_result_is_error() {
+        let context = create_test_context();
+        let expr = CompiledExpression::compile("actor.pid").unwrap();
+        assert!(expr.evaluate(&context).is_err());
+    }
+}
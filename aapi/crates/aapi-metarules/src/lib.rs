@@ -4,16 +4,22 @@
 //! - Policy-based authorization for VĀKYA requests
 //! - Human-in-the-loop approval workflows
 //! - Rate limiting and budget enforcement
+//! - CEL expression conditions for complex predicates
+//! - Hot reload of policies from a directory
 //! - Audit logging of policy decisions
 
 pub mod engine;
 pub mod rules;
 pub mod context;
 pub mod decision;
+pub mod expression;
+pub mod reload;
 pub mod error;
 
 pub use engine::*;
 pub use rules::*;
 pub use context::*;
 pub use decision::*;
+pub use expression::*;
+pub use reload::*;
 pub use error::*;
@@ -0,0 +1,205 @@
+//! Loading policies from disk and hot-reloading them on change
+//!
+//! A policy directory holds `*.json` files, each containing either a single
+//! [`Policy`] or an array of them. Other files are ignored. A reload parses
+//! and compiles the whole directory before swapping it into the engine, so a
+//! bad edit never leaves the engine with a partial or empty policy set.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Deserialize;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use crate::engine::PolicyEngine;
+use crate::error::{MetaRulesError, MetaRulesResult};
+use crate::rules::Policy;
+
+/// How long to wait for a burst of file events to settle before reloading
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(250);
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PolicyFile {
+    One(Box<Policy>),
+    Many(Vec<Policy>),
+}
+
+/// Read every policy file in a directory, in file name order
+pub async fn load_policy_dir(dir: impl AsRef<Path>) -> MetaRulesResult<Vec<Policy>> {
+    let dir = dir.as_ref();
+    let mut entries = tokio::fs::read_dir(dir).await.map_err(|e| {
+        MetaRulesError::PolicyLoad(format!("{}: {}", dir.display(), e))
+    })?;
+
+    let mut paths = Vec::new();
+    while let Some(entry) = entries.next_entry().await.map_err(|e| {
+        MetaRulesError::PolicyLoad(format!("{}: {}", dir.display(), e))
+    })? {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    let mut policies = Vec::new();
+    for path in paths {
+        let data = tokio::fs::read(&path).await.map_err(|e| {
+            MetaRulesError::PolicyLoad(format!("{}: {}", path.display(), e))
+        })?;
+        let file: PolicyFile = serde_json::from_slice(&data).map_err(|e| {
+            MetaRulesError::PolicyLoad(format!("{}: {}", path.display(), e))
+        })?;
+        match file {
+            PolicyFile::One(policy) => policies.push(*policy),
+            PolicyFile::Many(many) => policies.extend(many),
+        }
+    }
+
+    Ok(policies)
+}
+
+impl PolicyEngine {
+    /// Replace the policy set with the contents of a policy directory
+    ///
+    /// Returns the number of policies loaded. On error the previous set stays
+    /// in place.
+    pub async fn reload_from_dir(&self, dir: impl AsRef<Path>) -> MetaRulesResult<usize> {
+        let policies = load_policy_dir(dir).await?;
+        let count = policies.len();
+        self.replace_policies(policies).await?;
+        Ok(count)
+    }
+}
+
+/// Watches a policy directory and reloads the engine when it changes
+///
+/// Watching stops when the watcher is dropped.
+pub struct PolicyWatcher {
+    dir: PathBuf,
+    _watcher: RecommendedWatcher,
+}
+
+impl PolicyWatcher {
+    /// Start watching `dir`, reloading `engine` on every change
+    ///
+    /// Must be called from within a Tokio runtime. Failed reloads are logged
+    /// and the previous policy set is kept.
+    pub fn watch(engine: PolicyEngine, dir: impl Into<PathBuf>) -> MetaRulesResult<Self> {
+        let dir = dir.into();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            match res {
+                // Reading the directory during a reload generates access
+                // events; reacting to them would loop forever.
+                Ok(event) if matches!(event.kind, EventKind::Access(_)) => {}
+                Ok(_) => {
+                    let _ = tx.send(());
+                }
+                Err(e) => warn!(error = %e, "Policy watcher error"),
+            }
+        })
+        .map_err(|e| MetaRulesError::PolicyLoad(format!("Failed to create watcher: {}", e)))?;
+
+        watcher.watch(&dir, RecursiveMode::NonRecursive).map_err(|e| {
+            MetaRulesError::PolicyLoad(format!("Failed to watch {}: {}", dir.display(), e))
+        })?;
+
+        let reload_dir = dir.clone();
+        tokio::spawn(async move {
+            while rx.recv().await.is_some() {
+                tokio::time::sleep(RELOAD_DEBOUNCE).await;
+                while rx.try_recv().is_ok() {}
+
+                match engine.reload_from_dir(&reload_dir).await {
+                    Ok(count) => info!(
+                        dir = %reload_dir.display(),
+                        policies = count,
+                        version = engine.version(),
+                        "Reloaded policies"
+                    ),
+                    Err(e) => warn!(
+                        dir = %reload_dir.display(),
+                        error = %e,
+                        "Policy reload failed, keeping previous policies"
+                    ),
+                }
+            }
+        });
+
+        info!(dir = %dir.display(), "Watching policy directory");
+        Ok(Self { dir, _watcher: watcher })
+    }
+
+    /// The watched directory
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{Condition, Operator, Rule};
+
+    fn write_policy(dir: &Path, name: &str, policy: &Policy) {
+        std::fs::write(dir.join(name), serde_json::to_vec(policy).unwrap()).unwrap();
+    }
+
+    fn deny_action(id: &str, action: &str) -> Policy {
+        Policy::new(id, id).with_rule(
+            Rule::deny(format!("{}-rule", id), "Deny").with_condition(Condition::action(Operator::Eq, action)),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_reload_keeps_previous_set_on_error() {
+        let dir = tempfile::TempDir::new().unwrap();
+        write_policy(dir.path(), "a.json", &deny_action("a", "file.delete"));
+        std::fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+
+        let engine = PolicyEngine::new();
+        assert_eq!(engine.reload_from_dir(dir.path()).await.unwrap(), 1);
+        let version = engine.version();
+
+        std::fs::write(dir.path().join("b.json"), "{ not json").unwrap();
+        assert!(matches!(engine.reload_from_dir(dir.path()).await, Err(MetaRulesError::PolicyLoad(_))));
+
+        std::fs::write(
+            dir.path().join("b.json"),
+            serde_json::to_vec(&Policy::new("b", "b").with_rule(
+                Rule::allow("b-rule", "Broken").with_condition(Condition::expression("actor.role ==")),
+            )).unwrap(),
+        ).unwrap();
+        assert!(matches!(engine.reload_from_dir(dir.path()).await, Err(MetaRulesError::InvalidRule(_))));
+
+        assert_eq!(engine.version(), version);
+        assert!(engine.get_policy("a").await.is_some());
+        assert!(engine.get_policy("b").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_watcher_reloads_on_change() {
+        let dir = tempfile::TempDir::new().unwrap();
+        write_policy(dir.path(), "a.json", &deny_action("a", "file.delete"));
+
+        let engine = PolicyEngine::new();
+        engine.reload_from_dir(dir.path()).await.unwrap();
+        let _watcher = PolicyWatcher::watch(engine.clone(), dir.path()).unwrap();
+
+        let policies = vec![deny_action("a", "file.delete"), deny_action("b", "http.post")];
+        std::fs::write(dir.path().join("a.json"), serde_json::to_vec(&policies).unwrap()).unwrap();
+
+        for _ in 0..50 {
+            if engine.get_policy("b").await.is_some() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        panic!("Policy change was not picked up");
+    }
+}
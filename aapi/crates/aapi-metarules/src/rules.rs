@@ -1,5 +1,6 @@
 //! Rule definitions for MetaRules
 
+use aapi_core::ActorType;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -148,6 +149,15 @@ pub struct ApprovalConfig {
     pub approvers: Vec<String>,
     /// Minimum approvals needed
     pub min_approvals: u32,
+    /// Approvers with a voting weight; when set, resolution sums the weights
+    /// of distinct approvers instead of counting votes. Listed approvers
+    /// without a weight count as 1.
+    #[serde(default)]
+    pub weighted_approvers: Vec<(String, u32)>,
+    /// Total weight needed when `weighted_approvers` is set; defaults to
+    /// `min_approvals`
+    #[serde(default)]
+    pub required_weight: Option<u32>,
     /// Timeout in seconds
     pub timeout_secs: u64,
     /// Reason template
@@ -160,6 +170,8 @@ impl ApprovalConfig {
             approval_type,
             approvers: vec![],
             min_approvals: 1,
+            weighted_approvers: vec![],
+            required_weight: None,
             timeout_secs: 3600,
             reason_template: "Approval required".to_string(),
         }
@@ -175,6 +187,16 @@ impl ApprovalConfig {
         self
     }
 
+    pub fn with_weighted_approvers(mut self, approvers: Vec<(String, u32)>) -> Self {
+        self.weighted_approvers = approvers;
+        self
+    }
+
+    pub fn with_required_weight(mut self, weight: u32) -> Self {
+        self.required_weight = Some(weight);
+        self
+    }
+
     pub fn with_timeout(mut self, timeout_secs: u64) -> Self {
         self.timeout_secs = timeout_secs;
         self
@@ -187,10 +209,13 @@ impl ApprovalConfig {
 
     /// Convert to ApprovalRequirement
     pub fn to_requirement(&self) -> ApprovalRequirement {
-        ApprovalRequirement::new(self.approval_type, &self.reason_template)
+        let mut requirement = ApprovalRequirement::new(self.approval_type, &self.reason_template)
             .with_approvers(self.approvers.clone())
             .with_min_approvals(self.min_approvals)
-            .with_timeout(self.timeout_secs)
+            .with_weighted_approvers(self.weighted_approvers.clone())
+            .with_timeout(self.timeout_secs);
+        requirement.required_weight = self.required_weight;
+        requirement
     }
 }
 
@@ -227,6 +252,30 @@ impl Condition {
         )
     }
 
+    /// Actor role condition, for `In`/`NotIn` against a list of roles
+    ///
+    /// An actor without a role matches neither `In` nor `NotIn`.
+    pub fn actor_role<S: Into<String>>(operator: Operator, roles: impl IntoIterator<Item = S>) -> Self {
+        Self::new(ConditionType::Actor, "role", operator, string_list(roles))
+    }
+
+    /// Actor realm condition, for `In`/`NotIn` against a list of realms
+    ///
+    /// An actor without a realm matches neither `In` nor `NotIn`.
+    pub fn actor_realm<S: Into<String>>(operator: Operator, realms: impl IntoIterator<Item = S>) -> Self {
+        Self::new(ConditionType::Actor, "realm", operator, string_list(realms))
+    }
+
+    /// Actor type condition, for `In`/`NotIn` against a list of types
+    pub fn actor_type(operator: Operator, types: impl IntoIterator<Item = ActorType>) -> Self {
+        Self::new(
+            ConditionType::Actor,
+            "actor_type",
+            operator,
+            string_list(types.into_iter().map(|t| format!("{:?}", t))),
+        )
+    }
+
     /// Action condition
     pub fn action(operator: Operator, value: impl Into<String>) -> Self {
         Self::new(
@@ -237,6 +286,18 @@ impl Condition {
         )
     }
 
+    /// Matches actions whose reversibility is `reversible`
+    ///
+    /// Actions without resolved reversibility match neither value.
+    pub fn reversible(reversible: bool) -> Self {
+        Self::new(
+            ConditionType::Action,
+            "reversible",
+            Operator::Eq,
+            serde_json::json!(reversible),
+        )
+    }
+
     /// Resource condition
     pub fn resource(operator: Operator, value: impl Into<String>) -> Self {
         Self::new(
@@ -247,6 +308,16 @@ impl Condition {
         )
     }
 
+    /// Resource namespace condition
+    pub fn namespace(operator: Operator, value: impl Into<String>) -> Self {
+        Self::new(
+            ConditionType::Namespace,
+            "ns",
+            operator,
+            serde_json::json!(value.into()),
+        )
+    }
+
     /// Time condition
     pub fn time(field: impl Into<String>, operator: Operator, value: impl Into<String>) -> Self {
         Self::new(
@@ -271,6 +342,39 @@ impl Condition {
     pub fn attribute(field: impl Into<String>, operator: Operator, value: serde_json::Value) -> Self {
         Self::new(ConditionType::Attribute, field, operator, value)
     }
+
+    /// Condition on the VĀKYA's stated justification (`meta.hetu`)
+    ///
+    /// Fields are `reason`, `confidence`, `chain_length` and
+    /// `evidence_count`; see [`ConditionType::Hetu`].
+    pub fn hetu(field: impl Into<String>, operator: Operator, value: serde_json::Value) -> Self {
+        Self::new(ConditionType::Hetu, field, operator, value)
+    }
+
+    /// Matches VĀKYAs without a hetu, or whose hetu reason is blank
+    pub fn hetu_reason_missing() -> Self {
+        Self::hetu("reason", Operator::NotExists, serde_json::Value::Null)
+    }
+
+    /// Matches VĀKYAs whose hetu confidence is below `min`, including
+    /// those stating no confidence or no hetu at all
+    pub fn hetu_confidence_below(min: f64) -> Self {
+        Self::hetu("confidence", Operator::Lt, serde_json::json!(min))
+    }
+
+    /// CEL expression condition (see [`crate::expression`] for bindings)
+    pub fn expression(source: impl Into<String>) -> Self {
+        Self::new(
+            ConditionType::Expression,
+            "expression",
+            Operator::Eq,
+            serde_json::json!(source.into()),
+        )
+    }
+}
+
+fn string_list<S: Into<String>>(values: impl IntoIterator<Item = S>) -> serde_json::Value {
+    serde_json::Value::Array(values.into_iter().map(|v| serde_json::Value::String(v.into())).collect())
 }
 
 /// Type of condition
@@ -279,10 +383,14 @@ impl Condition {
 pub enum ConditionType {
     /// Condition on actor
     Actor,
-    /// Condition on action
+    /// Condition on action: `action`, `domain`, `verb`, and from the
+    /// context's reversibility `reversible`, `can_rollback` and
+    /// `declared_reversible` (absent when no adapter was resolved)
     Action,
     /// Condition on resource
     Resource,
+    /// Condition on the resource namespace hierarchy (`Karma.ns`)
+    Namespace,
     /// Condition on time
     Time,
     /// Condition on environment
@@ -293,6 +401,12 @@ pub enum ConditionType {
     Session,
     /// Condition on custom attribute
     Attribute,
+    /// Condition on the justification in `meta.hetu`: `reason` (absent
+    /// when there is no hetu or the reason is blank), `confidence` (0 when
+    /// not stated), `chain_length` and `evidence_count`
+    Hetu,
+    /// CEL expression evaluated against the whole context
+    Expression,
 }
 
 /// Comparison operator
@@ -315,18 +429,36 @@ pub enum Operator {
     Contains,
     /// Starts with
     StartsWith,
+    /// Does not start with (also true for non-string values)
+    NotStartsWith,
     /// Ends with
     EndsWith,
     /// Matches regex
     Matches,
+    /// Matches a regex; when the rule matches, each named capture group is
+    /// attached to the decision as an `Annotate` obligation
+    RegexCapture,
     /// In list
     In,
-    /// Not in list
+    /// Not in list; false when the field is absent, so a missing value
+    /// never passes as "not one of these"
     NotIn,
     /// Exists (field is present)
     Exists,
     /// Not exists
     NotExists,
+    /// Within an inclusive `[low, high]` range, compared numerically when
+    /// all three values are numbers (or numeric strings) and as strings
+    /// otherwise
+    Between,
+    /// Outside an inclusive `[low, high]` range
+    NotBetween,
+    /// Namespace is the given namespace, or one of a list, or nested
+    /// under it
+    Within,
+    /// Namespace is not within any of the given namespaces (also true when
+    /// there is no namespace)
+    NotWithin,
 }
 
 /// Predefined rule templates
@@ -359,12 +491,53 @@ pub mod templates {
             )
     }
 
+    /// Require approval for actions that cannot be undone
+    pub fn require_approval_for_irreversible() -> Rule {
+        Rule::require_approval("approve-irreversible", "Require Approval for Irreversible Actions")
+            .with_description("Require human approval for actions the adapter cannot roll back")
+            .with_condition(Condition::reversible(false))
+            .with_approval_config(
+                ApprovalConfig::new(ApprovalType::Human)
+                    .with_min_approvals(1)
+                    .with_reason("Action cannot be rolled back"),
+            )
+    }
+
+    /// Deny delete actions that do not say why they are being taken
+    pub fn require_hetu_for_delete() -> Rule {
+        Rule::deny("require-hetu-delete", "Require Justification for Delete")
+            .with_description("Deny delete actions without a hetu reason")
+            .with_condition(Condition::action(Operator::EndsWith, ".delete"))
+            .with_condition(Condition::hetu_reason_missing())
+            .with_priority(100)
+    }
+
+    /// Require approval for actions whose stated confidence is below
+    /// `min_confidence`
+    pub fn approve_low_confidence(min_confidence: f64) -> Rule {
+        Rule::require_approval("approve-low-confidence", "Approve Low-Confidence Actions")
+            .with_description(format!("Require approval when hetu confidence is below {}", min_confidence))
+            .with_condition(Condition::hetu_confidence_below(min_confidence))
+            .with_approval_config(
+                ApprovalConfig::new(ApprovalType::Human)
+                    .with_min_approvals(1)
+                    .with_reason("Agent confidence is below the required minimum"),
+            )
+    }
+
     /// Deny actions outside business hours
+    ///
+    /// The range is inclusive of whole hours, so `[9, 17]` allows 09:00
+    /// through 17:59. Hours are in the evaluation context's timezone.
     pub fn business_hours_only() -> Rule {
         Rule::deny("business-hours", "Business Hours Only")
             .with_description("Deny actions outside business hours (9 AM - 6 PM)")
-            .with_condition(Condition::time("hour", Operator::Lt, "9"))
-            .with_condition(Condition::time("hour", Operator::Gte, "18"))
+            .with_condition(Condition::new(
+                ConditionType::Time,
+                "hour",
+                Operator::NotBetween,
+                serde_json::json!([9, 17]),
+            ))
     }
 
     /// Allow read-only actions for all users
@@ -444,6 +617,26 @@ mod tests {
         let requirement = config.to_requirement();
         assert_eq!(requirement.min_approvals, 2);
         assert_eq!(requirement.timeout_secs, Some(7200));
+        assert!(requirement.weighted_approvers.is_empty());
+        assert_eq!(requirement.required_weight, None);
+
+        let weighted = ApprovalConfig::new(ApprovalType::MultiParty)
+            .with_weighted_approvers(vec![("user:manager".to_string(), 2), ("user:peer".to_string(), 1)])
+            .with_required_weight(2)
+            .to_requirement();
+        assert_eq!(weighted.weighted_approvers.len(), 2);
+        assert_eq!(weighted.required_weight, Some(2));
+
+        // Policies written before weights existed still load
+        let legacy: ApprovalConfig = serde_json::from_value(serde_json::json!({
+            "approval_type": "human",
+            "approvers": [],
+            "min_approvals": 1,
+            "timeout_secs": 60,
+            "reason_template": "Approval required",
+        }))
+        .unwrap();
+        assert!(legacy.weighted_approvers.is_empty());
     }
 
     #[test]
@@ -21,6 +21,7 @@ use aapi_metarules::{
     Operator,
     DecisionType,
     ApprovalType,
+    templates,
 };
 
 fn test_adhikarana() -> Adhikarana {
@@ -83,7 +84,7 @@ async fn policy_engine_denies_matching_rule() {
         )
         .with_default_allow();
 
-    engine.add_policy(policy).await;
+    engine.add_policy(policy).await.unwrap();
 
     let vakya = build_vakya("file.delete", "file:/tmp/aapi/test.txt");
     let ctx = EvaluationContext::new(vakya);
@@ -117,7 +118,7 @@ async fn policy_engine_requires_approval() {
         )
         .with_default_allow();
 
-    engine.add_policy(policy).await;
+    engine.add_policy(policy).await.unwrap();
 
     let vakya = build_vakya("http.post", "http:https://example.com/api");
     let ctx = EvaluationContext::new(vakya);
@@ -139,3 +140,28 @@ async fn policy_engine_allows_when_no_rules_match_and_default_allow() {
     assert_eq!(decision.decision, DecisionType::Allow);
     assert!(decision.allowed);
 }
+
+#[tokio::test]
+async fn business_hours_template_denies_outside_hours() {
+    let engine = PolicyEngine::new().with_default_allow();
+    engine.add_policy(
+        Policy::new("hours", "Business hours")
+            .with_rule(templates::business_hours_only())
+            .with_default_allow(),
+    ).await.unwrap();
+
+    let mut ctx = EvaluationContext::new(build_vakya("file.write", "file:/tmp/aapi/report.txt"));
+
+    ctx.timestamp = "2024-03-01T20:00:00Z".parse().unwrap();
+    let decision = engine.evaluate(&ctx).await.expect("evaluate");
+    assert_eq!(decision.decision, DecisionType::Deny);
+
+    ctx.timestamp = "2024-03-01T14:00:00Z".parse().unwrap();
+    let decision = engine.evaluate(&ctx).await.expect("evaluate");
+    assert_eq!(decision.decision, DecisionType::Allow);
+
+    // 14:00 UTC is 23:00 in Tokyo
+    let ctx = ctx.with_timezone("Asia/Tokyo").unwrap();
+    let decision = engine.evaluate(&ctx).await.expect("evaluate");
+    assert_eq!(decision.decision, DecisionType::Deny);
+}
@@ -45,6 +45,7 @@ fn make_test_vakya(domain: &str, verb: &str, agent_pid: &str) -> Vakya {
             ttl: Some(TtlConstraint {
                 expires_at: Timestamp(chrono::Utc::now() + chrono::Duration::hours(1)),
                 max_duration_ms: None,
+                not_before: None,
             }),
             budgets: vec![],
             approval_lane: ApprovalLane::None,
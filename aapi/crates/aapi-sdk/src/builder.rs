@@ -170,6 +170,7 @@ impl VakyaRequestBuilder {
         let ttl = self.ttl_secs.map(|secs| TtlConstraint {
             expires_at: Timestamp(Utc::now() + Duration::seconds(secs)),
             max_duration_ms: Some((secs * 1000) as u64),
+            not_before: None,
         });
 
         let cap_ref = self.capability_ref.unwrap_or_else(|| "cap:default".to_string());
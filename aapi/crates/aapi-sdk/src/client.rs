@@ -3,10 +3,11 @@
 use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
+use tokio::time::Instant;
 use tracing::{debug, info};
 
 use aapi_core::Vakya;
-use aapi_crypto::{KeyStore, KeyId, VakyaSigner, SignedVakya};
+use aapi_crypto::{ExportBundle, KeyStore, KeyId, PublicKeyInfo, VakyaSigner, SignedVakya};
 
 use crate::error::{SdkError, SdkResult};
 
@@ -127,6 +128,64 @@ impl AapiClient {
         self.handle_response(response).await
     }
 
+    /// Submit a VĀKYA and, if it is held for approval, wait until the
+    /// approval resolves
+    ///
+    /// Polls the approval with backoff (250 ms up to 2 s) until it is
+    /// approved, rejected or times out on the gateway, then fetches the
+    /// final receipt, so callers get one result whichever path the request
+    /// took. Fails with `SdkError::Timeout` if `timeout` passes while the
+    /// approval is still pending; the request stays pending on the gateway.
+    pub async fn submit_and_wait(&self, vakya: Vakya, timeout: Duration) -> SdkResult<SubmitOutcome> {
+        let deadline = Instant::now() + timeout;
+        let submitted = tokio::time::timeout(timeout, self.submit(vakya))
+            .await
+            .map_err(|_| SdkError::Timeout)??;
+
+        let approval_id = match submitted.approval_id() {
+            Some(approval_id) if submitted.status == "pending_approval" => approval_id.to_string(),
+            _ => {
+                let receipt = submitted.receipt.clone();
+                return Ok(SubmitOutcome { submitted, approval: None, receipt });
+            }
+        };
+
+        debug!(vakya_id = %submitted.vakya_id, approval_id = %approval_id, "Waiting for approval");
+        let mut interval = Duration::from_millis(250);
+        let approval = loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let approval = tokio::time::timeout(remaining, self.get_approval(&approval_id))
+                .await
+                .map_err(|_| SdkError::Timeout)??;
+            if approval.status != "pending" {
+                break approval;
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(SdkError::Timeout);
+            }
+            tokio::time::sleep(interval.min(remaining)).await;
+            interval = (interval * 2).min(Duration::from_secs(2));
+        };
+
+        info!(vakya_id = %submitted.vakya_id, approval_id = %approval_id, status = %approval.status, "Approval resolved");
+        let receipt = match self.get_receipt(&submitted.vakya_id).await {
+            Ok(receipt) => Some(receipt),
+            Err(SdkError::NotFound(_)) => approval.receipt.clone(),
+            Err(e) => return Err(e),
+        };
+        Ok(SubmitOutcome { submitted, approval: Some(approval), receipt })
+    }
+
+    /// Get an approval request by ID
+    pub async fn get_approval(&self, approval_id: &str) -> SdkResult<ApprovalResponse> {
+        let url = format!("{}/v1/approvals/{}", self.config.gateway_url, approval_id);
+        
+        let response = self.http_client.get(&url).send().await?;
+        self.handle_response(response).await
+    }
+
     /// Get a VĀKYA by ID
     pub async fn get_vakya(&self, vakya_id: &str) -> SdkResult<VakyaResponse> {
         let url = format!("{}/v1/vakya/{}", self.config.gateway_url, vakya_id);
@@ -143,6 +202,38 @@ impl AapiClient {
         self.handle_response(response).await
     }
 
+    /// Get the stored receipt for a VĀKYA, including its signature
+    ///
+    /// Returned as the gateway's JSON so the signature can be checked over
+    /// exactly the fields the gateway signed.
+    pub async fn get_signed_receipt(&self, vakya_id: &str) -> SdkResult<serde_json::Value> {
+        let url = format!("{}/v1/vakya/{}/receipt", self.config.gateway_url, vakya_id);
+        
+        let response = self.http_client.get(&url).send().await?;
+        self.handle_response(response).await
+    }
+
+    /// Get a gateway public key, e.g. the one named by a receipt's `key_id`
+    pub async fn get_public_key(&self, key_id: &str) -> SdkResult<PublicKeyInfo> {
+        let url = format!("{}/v1/keys/{}", self.config.gateway_url, key_id);
+        
+        let response = self.http_client.get(&url).send().await?;
+        self.handle_response(response).await
+    }
+
+    /// Build a signed evidence bundle for the given VĀKYA requests; check it
+    /// with [`crate::verify_bundle`]
+    pub async fn export_bundle(&self, vakya_ids: &[String]) -> SdkResult<ExportBundle> {
+        let url = format!("{}/v1/export/bundle", self.config.gateway_url);
+
+        let response = self.http_client
+            .post(&url)
+            .json(&serde_json::json!({ "vakya_ids": vakya_ids }))
+            .send()
+            .await?;
+        self.handle_response(response).await
+    }
+
     /// Get effects for a VĀKYA
     pub async fn get_effects(&self, vakya_id: &str) -> SdkResult<Vec<EffectResponse>> {
         let url = format!("{}/v1/vakya/{}/effects", self.config.gateway_url, vakya_id);
@@ -170,6 +261,22 @@ impl AapiClient {
         self.handle_response(response).await
     }
 
+    /// Get a consistency proof between two tree sizes
+    pub async fn get_consistency_proof(
+        &self,
+        tree_type: &str,
+        first_size: i64,
+        second_size: i64,
+    ) -> SdkResult<ConsistencyProofResponse> {
+        let url = format!(
+            "{}/v1/merkle/consistency?tree_type={}&first_size={}&second_size={}",
+            self.config.gateway_url, tree_type, first_size, second_size
+        );
+        
+        let response = self.http_client.get(&url).send().await?;
+        self.handle_response(response).await
+    }
+
     /// Health check
     pub async fn health(&self) -> SdkResult<HealthResponse> {
         let url = format!("{}/health", self.config.gateway_url);
@@ -223,6 +330,65 @@ pub struct SubmitResponse {
     pub receipt: Option<ReceiptResponse>,
     pub merkle_root: Option<String>,
     pub leaf_index: Option<i64>,
+    /// Present when policy denied the request or held it for approval
+    #[serde(default)]
+    pub policy_decision: Option<PolicyDecisionResponse>,
+}
+
+impl SubmitResponse {
+    /// Approval to wait on when the request is pending approval
+    pub fn approval_id(&self) -> Option<&str> {
+        self.policy_decision.as_ref()?.approval_id.as_deref()
+    }
+}
+
+/// Policy decision reported on submission
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyDecisionResponse {
+    pub decision: String,
+    pub message: String,
+    #[serde(default)]
+    pub matched_rules: Option<Vec<String>>,
+    #[serde(default)]
+    pub approval_id: Option<String>,
+}
+
+/// Approval request state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalResponse {
+    pub approval_id: String,
+    pub vakya_id: String,
+    /// `pending`, `approved`, `rejected` or `timed_out`
+    pub status: String,
+    pub approvals_received: u32,
+    pub min_approvals: u32,
+    pub expires_at: Option<String>,
+    /// Receipt written when the approval was resolved
+    pub receipt: Option<ReceiptResponse>,
+}
+
+/// Final result of `submit_and_wait`
+#[derive(Debug, Clone)]
+pub struct SubmitOutcome {
+    /// Response to the submission itself
+    pub submitted: SubmitResponse,
+    /// Resolved approval, when the request was held for one
+    pub approval: Option<ApprovalResponse>,
+    /// Final receipt, whose reason code and message give the outcome
+    pub receipt: Option<ReceiptResponse>,
+}
+
+impl SubmitOutcome {
+    /// Reason code of the final receipt, e.g. `SUCCESS`, `POLICY_DENIED`
+    /// or `TIMEOUT`
+    pub fn reason_code(&self) -> Option<&str> {
+        self.receipt.as_ref().map(|r| r.reason_code.as_str())
+    }
+
+    /// Whether the action ran and succeeded
+    pub fn is_success(&self) -> bool {
+        self.reason_code() == Some("SUCCESS")
+    }
 }
 
 /// VĀKYA record response
@@ -237,6 +403,9 @@ pub struct VakyaResponse {
     pub created_at: String,
     pub leaf_index: Option<i64>,
     pub merkle_root: Option<String>,
+    /// Full VĀKYA as submitted
+    #[serde(default)]
+    pub vakya_json: serde_json::Value,
 }
 
 /// Receipt response
@@ -290,6 +459,18 @@ pub struct ProofNode {
     pub position: String,
 }
 
+/// Consistency proof response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsistencyProofResponse {
+    pub first_size: i64,
+    pub second_size: i64,
+    /// Root the gateway reports for the first tree size
+    pub first_root: String,
+    /// Root the gateway reports for the second tree size
+    pub second_root: String,
+    pub proof_hashes: Vec<String>,
+}
+
 /// Health response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HealthResponse {
@@ -325,4 +506,109 @@ mod tests {
         let client = AapiClient::new(config);
         assert!(client.is_ok());
     }
+
+    fn receipt_json(vakya_id: &str, reason_code: &str) -> serde_json::Value {
+        serde_json::json!({
+            "vakya_id": vakya_id,
+            "vakya_hash": "hash",
+            "reason_code": reason_code,
+            "message": null,
+            "duration_ms": 1,
+            "effect_ids": [],
+            "executor_id": "gateway",
+            "created_at": "2024-01-01T00:00:00Z",
+        })
+    }
+
+    fn approval_json(vakya_id: &str, status: &str) -> serde_json::Value {
+        serde_json::json!({
+            "approval_id": "appr-1",
+            "vakya_id": vakya_id,
+            "status": status,
+            "approvals_received": if status == "approved" { 1 } else { 0 },
+            "min_approvals": 1,
+            "votes": [],
+            "expires_at": null,
+            "receipt": null,
+        })
+    }
+
+    async fn pending_gateway(vakya: &Vakya) -> wiremock::MockServer {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let vakya_id = vakya.vakya_id.0.clone();
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/vakya"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "vakya_id": vakya_id,
+                "vakya_hash": "hash",
+                "status": "pending_approval",
+                "receipt": receipt_json(&vakya_id, "APPROVAL_REQUIRED"),
+                "merkle_root": null,
+                "leaf_index": 0,
+                "policy_decision": {
+                    "decision": "pending_approval",
+                    "message": "needs a human",
+                    "approval_id": "appr-1",
+                },
+            })))
+            .mount(&server)
+            .await;
+        server
+    }
+
+    #[tokio::test]
+    async fn test_submit_and_wait_follows_approval() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, ResponseTemplate};
+
+        let vakya = crate::FileActionBuilder::read("user:bob", "/data/report.csv").build().unwrap();
+        let vakya_id = vakya.vakya_id.0.clone();
+        let server = pending_gateway(&vakya).await;
+        Mock::given(method("GET"))
+            .and(path("/v1/approvals/appr-1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(approval_json(&vakya_id, "pending")))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/approvals/appr-1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(approval_json(&vakya_id, "approved")))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/v1/vakya/{}/receipt", vakya_id)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(receipt_json(&vakya_id, "SUCCESS")))
+            .mount(&server)
+            .await;
+
+        let client = AapiClient::new(ClientConfig::new(server.uri())).unwrap();
+        let outcome = client.submit_and_wait(vakya, Duration::from_secs(5)).await.unwrap();
+        assert_eq!(outcome.submitted.status, "pending_approval");
+        assert_eq!(outcome.approval.as_ref().unwrap().status, "approved");
+        assert!(outcome.is_success());
+    }
+
+    #[tokio::test]
+    async fn test_submit_and_wait_respects_timeout() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, ResponseTemplate};
+
+        let vakya = crate::FileActionBuilder::read("user:bob", "/data/report.csv").build().unwrap();
+        let vakya_id = vakya.vakya_id.0.clone();
+        let server = pending_gateway(&vakya).await;
+        Mock::given(method("GET"))
+            .and(path("/v1/approvals/appr-1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(approval_json(&vakya_id, "pending")))
+            .mount(&server)
+            .await;
+
+        let client = AapiClient::new(ClientConfig::new(server.uri())).unwrap();
+        let started = std::time::Instant::now();
+        let result = client.submit_and_wait(vakya, Duration::from_millis(600)).await;
+        assert!(matches!(result, Err(SdkError::Timeout)));
+        assert!(started.elapsed() < Duration::from_secs(3));
+    }
 }
@@ -4,15 +4,20 @@
 //! - Easy-to-use client for submitting VĀKYA requests
 //! - Automatic signing and capability management
 //! - Response handling and effect tracking
+//! - Local verification of transparency log consistency proofs,
+//!   signed, logged receipts and evidence bundles
 
 pub mod client;
 pub mod builder;
 pub mod error;
+pub mod transparency;
 
 pub use client::*;
 pub use builder::*;
 pub use error::*;
+pub use transparency::*;
 
 // Re-export core types for convenience
 pub use aapi_core::{Vakya, VakyaId, Karta, Karma, Kriya, Adhikarana};
 pub use aapi_core::types::{PrincipalId, ResourceId, Namespace, Timestamp};
+pub use aapi_crypto::{BundleEntry, ExportBundle, ProofStep, SignedTreeHead};
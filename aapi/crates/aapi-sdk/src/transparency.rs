@@ -0,0 +1,413 @@
+//! Client-side verification of transparency log proofs
+//!
+//! Uses the same RFC 6962 routines as the gateway's IndexDB, so a monitor
+//! holding earlier tree heads can check that the log only ever grew, and an
+//! auditor can check that a receipt or a whole evidence bundle is genuine
+//! and logged.
+
+use aapi_core::types::HashAlgorithm;
+use aapi_crypto::{merkle_leaf_hash_with, verify_receipt, BundleEntry, ExportBundle, PublicKeyInfo, SignedTreeHead};
+use serde::{Deserialize, Serialize};
+
+use crate::client::{ConsistencyProofResponse, InclusionProofResponse};
+
+/// Verify that `new_sth` is an append-only extension of `old_sth`.
+///
+/// The roots are taken from the tree heads the caller already trusts, not
+/// from the roots echoed back in the proof response.
+pub fn verify_consistency(
+    old_sth: &SignedTreeHead,
+    new_sth: &SignedTreeHead,
+    proof: &ConsistencyProofResponse,
+) -> bool {
+    if proof.first_size != old_sth.tree_size as i64 || proof.second_size != new_sth.tree_size as i64 {
+        return false;
+    }
+
+    aapi_crypto::verify_consistency(
+        old_sth.tree_size,
+        new_sth.tree_size,
+        &old_sth.root_hash,
+        &new_sth.root_hash,
+        &proof.proof_hashes,
+    )
+}
+
+/// Verify that `leaf_data` is included in the tree with root `trusted_root`
+///
+/// The leaf hash is recomputed from `leaf_data` rather than taken from the
+/// proof, and the root is the caller's, not the one echoed in the proof.
+pub fn verify_inclusion(proof: &InclusionProofResponse, leaf_data: &[u8], trusted_root: &str) -> bool {
+    let (algorithm, _) = HashAlgorithm::split_labeled(trusted_root);
+    if merkle_leaf_hash_with(algorithm, leaf_data) != proof.leaf_hash {
+        return false;
+    }
+
+    let mut path = Vec::with_capacity(proof.proof_hashes.len());
+    for node in &proof.proof_hashes {
+        let sibling_on_right = match node.position.as_str() {
+            "right" => true,
+            "left" => false,
+            _ => return false,
+        };
+        path.push((node.hash.clone(), sibling_on_right));
+    }
+
+    aapi_crypto::verify_inclusion(&proof.leaf_hash, &path, trusted_root)
+}
+
+/// Everything needed to audit a receipt without contacting the gateway
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReceiptBundle {
+    /// Stored receipt as returned by the gateway
+    pub receipt: serde_json::Value,
+    /// Inclusion proof for the receipt's leaf in the receipt tree
+    pub proof: InclusionProofResponse,
+    /// Receipt tree root the proof is checked against
+    pub root_hash: String,
+    /// Key named by the receipt's `key_id`
+    pub public_key: PublicKeyInfo,
+}
+
+/// One check performed while verifying a receipt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationStep {
+    pub check: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+/// Outcome of verifying a [`ReceiptBundle`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReceiptVerification {
+    pub vakya_id: String,
+    pub steps: Vec<VerificationStep>,
+}
+
+impl ReceiptVerification {
+    /// Whether every check passed
+    pub fn is_valid(&self) -> bool {
+        !self.steps.is_empty() && self.steps.iter().all(|step| step.passed)
+    }
+
+    fn check(&mut self, check: &str, passed: bool, detail: impl Into<String>) {
+        self.steps.push(VerificationStep {
+            check: check.to_string(),
+            passed,
+            detail: detail.into(),
+        });
+    }
+}
+
+impl ReceiptBundle {
+    /// Check the receipt signature and its inclusion in the receipt tree
+    pub fn verify(&self) -> ReceiptVerification {
+        let field = |name: &str| self.receipt.get(name).and_then(serde_json::Value::as_str).unwrap_or_default();
+        let mut result = ReceiptVerification {
+            vakya_id: field("vakya_id").to_string(),
+            steps: Vec::new(),
+        };
+
+        let key_id = field("key_id");
+        result.check(
+            "signing key",
+            !key_id.is_empty() && key_id == self.public_key.key_id.0,
+            format!("receipt key '{}', public key '{}'", key_id, self.public_key.key_id.0),
+        );
+
+        match verify_receipt(&self.public_key, &self.receipt) {
+            Ok(true) => result.check("signature", true, "Ed25519 signature over the receipt is valid"),
+            Ok(false) => result.check("signature", false, "signature does not match the receipt contents"),
+            Err(e) => result.check("signature", false, e.to_string()),
+        }
+
+        let leaf_index = self.receipt.get("leaf_index").and_then(serde_json::Value::as_i64);
+        result.check(
+            "leaf index",
+            leaf_index == Some(self.proof.leaf_index),
+            format!(
+                "receipt leaf {}, proof leaf {}",
+                leaf_index.map_or_else(|| "none".to_string(), |i| i.to_string()),
+                self.proof.leaf_index
+            ),
+        );
+
+        let included = verify_inclusion(&self.proof, field("vakya_hash").as_bytes(), &self.root_hash);
+        result.check(
+            "inclusion",
+            included,
+            format!(
+                "leaf {} of {} {} root {}",
+                self.proof.leaf_index,
+                self.proof.tree_size,
+                if included { "hashes to" } else { "does not hash to" },
+                self.root_hash
+            ),
+        );
+
+        result
+    }
+}
+
+/// Outcome of verifying an [`ExportBundle`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleVerification {
+    pub gateway_id: String,
+    pub steps: Vec<VerificationStep>,
+}
+
+impl BundleVerification {
+    /// Whether every check passed
+    pub fn is_valid(&self) -> bool {
+        !self.steps.is_empty() && self.steps.iter().all(|step| step.passed)
+    }
+
+    fn check(&mut self, check: impl Into<String>, passed: bool, detail: impl Into<String>) {
+        self.steps.push(VerificationStep {
+            check: check.into(),
+            passed,
+            detail: detail.into(),
+        });
+    }
+}
+
+/// Check an evidence bundle using only what it carries: tree head
+/// signatures, every record's inclusion proof, VĀKYA hashes and receipt
+/// signatures.
+///
+/// The bundled keys are taken at face value; pin them against keys obtained
+/// out of band before trusting the result.
+pub fn verify_bundle(bundle: &ExportBundle) -> BundleVerification {
+    let mut result = BundleVerification {
+        gateway_id: bundle.gateway_id.clone(),
+        steps: Vec::new(),
+    };
+
+    for (tree, head) in &bundle.tree_heads {
+        let key = head.key_id.as_deref().and_then(|key_id| bundle.public_key(key_id));
+        match key.map(|key| head.verify(key)) {
+            Some(Ok(true)) => result.check(format!("{} tree head", tree), true, format!("size {} root {}", head.tree_size, head.root_hash)),
+            Some(Ok(false)) => result.check(format!("{} tree head", tree), false, "signature does not match the tree head"),
+            Some(Err(e)) => result.check(format!("{} tree head", tree), false, e.to_string()),
+            None => result.check(format!("{} tree head", tree), false, "no bundled key for the tree head"),
+        }
+    }
+
+    for entry in &bundle.vakyas {
+        let field = |name: &str| entry.record.get(name).and_then(serde_json::Value::as_str).unwrap_or_default();
+        let vakya_id = field("vakya_id");
+        let computed = entry.record.get("vakya_json")
+            .and_then(|vakya| aapi_core::hash_value(vakya).ok())
+            .map(|hash| hash.value);
+        result.check(
+            format!("vakya {} hash", vakya_id),
+            computed.as_deref() == Some(field("vakya_hash")),
+            format!("stored {}, computed {}", field("vakya_hash"), computed.as_deref().unwrap_or("none")),
+        );
+        check_entry(&mut result, bundle, "vakya", entry, field("vakya_hash"), vakya_id);
+    }
+
+    for entry in &bundle.effects {
+        let id = entry.record.get("id").and_then(serde_json::Value::as_str).unwrap_or_default();
+        check_entry(&mut result, bundle, "effect", entry, id, id);
+    }
+
+    for entry in &bundle.receipts {
+        let field = |name: &str| entry.record.get(name).and_then(serde_json::Value::as_str).unwrap_or_default();
+        let vakya_id = field("vakya_id");
+        match bundle.public_key(field("key_id")).map(|key| verify_receipt(key, &entry.record)) {
+            Some(Ok(true)) => result.check(format!("receipt {} signature", vakya_id), true, "Ed25519 signature over the receipt is valid"),
+            Some(Ok(false)) => result.check(format!("receipt {} signature", vakya_id), false, "signature does not match the receipt contents"),
+            Some(Err(e)) => result.check(format!("receipt {} signature", vakya_id), false, e.to_string()),
+            None => result.check(format!("receipt {} signature", vakya_id), false, format!("no bundled key '{}'", field("key_id"))),
+        }
+        check_entry(&mut result, bundle, "receipt", entry, field("vakya_hash"), vakya_id);
+    }
+
+    result
+}
+
+/// Check that `leaf_data` sits at the entry's leaf under the bundled head of `tree`
+fn check_entry(
+    result: &mut BundleVerification,
+    bundle: &ExportBundle,
+    tree: &str,
+    entry: &BundleEntry,
+    leaf_data: &str,
+    label: &str,
+) {
+    let check = format!("{} {} inclusion", tree, label);
+    let Some(head) = bundle.tree_heads.get(tree) else {
+        result.check(check, false, format!("bundle has no {} tree head", tree));
+        return;
+    };
+
+    let (algorithm, _) = HashAlgorithm::split_labeled(&head.root_hash);
+    let leaf_hash = merkle_leaf_hash_with(algorithm, leaf_data.as_bytes());
+    let path: Vec<(String, bool)> = entry.proof.iter()
+        .map(|step| (step.hash.clone(), step.sibling_on_right))
+        .collect();
+    let included = entry.leaf_index < head.tree_size
+        && aapi_crypto::verify_inclusion(&leaf_hash, &path, &head.root_hash);
+    result.check(
+        check,
+        included,
+        format!(
+            "leaf {} of {} {} root {}",
+            entry.leaf_index,
+            head.tree_size,
+            if included { "hashes to" } else { "does not hash to" },
+            head.root_hash
+        ),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aapi_crypto::{consistency_proof, merkle_leaf_hash, merkle_root};
+
+    fn response(leaves: &[String], first_size: usize) -> ConsistencyProofResponse {
+        ConsistencyProofResponse {
+            first_size: first_size as i64,
+            second_size: leaves.len() as i64,
+            first_root: merkle_root(&leaves[..first_size]).unwrap(),
+            second_root: merkle_root(leaves).unwrap(),
+            proof_hashes: consistency_proof(leaves, first_size).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_verify_consistency() {
+        let leaves: Vec<String> = (0..6).map(|i| merkle_leaf_hash(&[i])).collect();
+        let proof = response(&leaves, 3);
+
+        let old_sth = SignedTreeHead::new(3, proof.first_root.clone());
+        let new_sth = SignedTreeHead::new(6, proof.second_root.clone());
+        assert!(verify_consistency(&old_sth, &new_sth, &proof));
+
+        // A checkpoint the gateway's log no longer extends
+        let forked = SignedTreeHead::new(3, merkle_leaf_hash(b"other"));
+        assert!(!verify_consistency(&forked, &new_sth, &proof));
+
+        // Proof for different sizes than the tree heads
+        let stale = SignedTreeHead::new(2, merkle_root(&leaves[..2]).unwrap());
+        assert!(!verify_consistency(&stale, &new_sth, &proof));
+    }
+
+    #[test]
+    fn test_receipt_bundle_verification() {
+        use aapi_crypto::{sign_receipt, KeyPair, KeyPurpose};
+        use crate::client::ProofNode;
+
+        let key_pair = KeyPair::generate(KeyPurpose::ReceiptSigning);
+        let hashes = ["h0", "h1", "h2"];
+        let leaves: Vec<String> = hashes.iter().map(|h| merkle_leaf_hash(h.as_bytes())).collect();
+        let root = merkle_root(&leaves).unwrap();
+
+        let mut receipt = serde_json::json!({
+            "vakya_id": "vakya-2",
+            "vakya_hash": "h2",
+            "reason_code": "SUCCESS",
+            "key_id": key_pair.key_id.0,
+        });
+        receipt["signature"] = serde_json::json!(sign_receipt(&key_pair, &receipt).unwrap());
+        receipt["leaf_index"] = serde_json::json!(2);
+
+        // Leaf 2 of 3: its sibling is the subtree of leaves 0 and 1, on the left
+        let proof = InclusionProofResponse {
+            leaf_hash: leaves[2].clone(),
+            leaf_index: 2,
+            tree_size: 3,
+            proof_hashes: vec![ProofNode {
+                hash: merkle_root(&leaves[..2]).unwrap(),
+                position: "left".to_string(),
+            }],
+            root_hash: root.clone(),
+        };
+
+        let bundle = ReceiptBundle {
+            receipt,
+            proof,
+            root_hash: root,
+            public_key: key_pair.to_public_info(),
+        };
+        let result = bundle.verify();
+        assert!(result.is_valid(), "{:?}", result);
+        assert_eq!(result.vakya_id, "vakya-2");
+
+        // A receipt for a different VĀKYA cannot borrow the proof
+        let mut tampered = bundle.clone();
+        tampered.receipt["vakya_hash"] = serde_json::json!("h1");
+        let result = tampered.verify();
+        assert!(!result.is_valid());
+        assert!(result.steps.iter().filter(|s| !s.passed).count() >= 2);
+
+        // Root the operator does not trust
+        let mut forked = bundle.clone();
+        forked.root_hash = merkle_leaf_hash(b"other");
+        assert!(!forked.verify().is_valid());
+    }
+
+    #[test]
+    fn test_verify_bundle() {
+        use aapi_crypto::{sign_receipt, KeyPair, KeyPurpose, ProofStep};
+        use std::collections::BTreeMap;
+
+        let key_pair = KeyPair::generate(KeyPurpose::ReceiptSigning);
+        let vakya_json = serde_json::json!({ "vakya_id": "vakya-1", "action": "file.read" });
+        let vakya_hash = aapi_core::hash_value(&vakya_json).unwrap().value;
+
+        // The VĀKYA is leaf 1 of 2 in both the vakya and the receipt tree;
+        // its only sibling is leaf 0, on the left
+        let leaves = vec![merkle_leaf_hash(b"earlier"), merkle_leaf_hash(vakya_hash.as_bytes())];
+        let root = merkle_root(&leaves).unwrap();
+        let entry = |record: serde_json::Value| BundleEntry {
+            record,
+            leaf_index: 1,
+            proof: vec![ProofStep { hash: leaves[0].clone(), sibling_on_right: false }],
+        };
+
+        let mut receipt = serde_json::json!({
+            "vakya_id": "vakya-1",
+            "vakya_hash": vakya_hash,
+            "reason_code": "SUCCESS",
+            "key_id": key_pair.key_id.0,
+        });
+        receipt["signature"] = serde_json::json!(sign_receipt(&key_pair, &receipt).unwrap());
+
+        let head = SignedTreeHead::new(2, root).sign(&key_pair).unwrap();
+        let bundle = ExportBundle {
+            gateway_id: "gateway-1".to_string(),
+            created_at: chrono::Utc::now(),
+            vakyas: vec![entry(serde_json::json!({
+                "vakya_id": "vakya-1",
+                "vakya_hash": vakya_hash,
+                "vakya_json": vakya_json,
+            }))],
+            effects: vec![],
+            receipts: vec![entry(receipt)],
+            tree_heads: BTreeMap::from([("vakya".to_string(), head.clone()), ("receipt".to_string(), head)]),
+            public_keys: vec![key_pair.to_public_info()],
+        };
+        let result = verify_bundle(&bundle);
+        assert!(result.is_valid(), "{:?}", result);
+
+        // Editing the stored request breaks its hash
+        let mut tampered = bundle.clone();
+        tampered.vakyas[0].record["vakya_json"]["action"] = serde_json::json!("file.delete");
+        assert!(!verify_bundle(&tampered).is_valid());
+
+        // A head re-rooted after signing
+        let mut forged = bundle.clone();
+        forged.tree_heads.get_mut("receipt").unwrap().root_hash = merkle_leaf_hash(b"other");
+        let result = verify_bundle(&forged);
+        assert!(!result.is_valid());
+        assert!(result.steps.iter().any(|s| s.check == "receipt tree head" && !s.passed));
+
+        // Without its key nothing signed can be checked
+        let mut keyless = bundle;
+        keyless.public_keys.clear();
+        assert!(!verify_bundle(&keyless).is_valid());
+    }
+}
@@ -0,0 +1,225 @@
+//! AAPI WASM - Capability verification and VĀKYA signing for browser agents
+//!
+//! Exposes `CapabilityVerifier` to JavaScript so agents can check the tokens
+//! they are handed before acting, using the same time, signature, caveat and
+//! budget checks as the gateway. VĀKYAs are canonicalized with the gateway's
+//! own canonicalizer, so the bytes a browser signs are exactly the bytes the
+//! gateway verifies. Keys cross the boundary as hex; canonical bytes and
+//! signatures as base64, the encoding the gateway accepts.
+//!
+//! ```js
+//! import init, { verify_capability, canonicalize_vakya, sign_ed25519 } from "@connector-oss/aapi-wasm";
+//! await init();
+//! const { valid, errors } = verify_capability(tokenJson, issuerPublicKeyHex);
+//! const { hash, canonical_bytes_base64 } = canonicalize_vakya(vakyaJson);
+//! const signature = sign_ed25519(canonical_bytes_base64, secretKeyHex);
+//! ```
+
+use base64::Engine;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use aapi_core::{canonicalize, Vakya};
+use aapi_crypto::{sign_bytes, CapabilityToken, CapabilityVerifier, KeyPair, KeyPurpose, SecretKeyEncoding};
+
+/// Verification result handed back to JavaScript
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TokenVerification {
+    pub valid: bool,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl TokenVerification {
+    fn rejected(error: String) -> Self {
+        Self {
+            valid: false,
+            errors: vec![error],
+            warnings: vec![],
+        }
+    }
+}
+
+/// Verify a capability token (JSON) against the issuer's Ed25519 public key
+///
+/// Returns `{ valid, errors, warnings }`. Malformed input is reported through
+/// `errors` rather than thrown, so callers only need to check `valid`.
+#[wasm_bindgen]
+pub fn verify_capability(token_json: &str, public_key_hex: &str) -> Result<JsValue, JsError> {
+    let verification = verify_capability_json(token_json, public_key_hex);
+    serde_wasm_bindgen::to_value(&verification).map_err(|e| JsError::new(&e.to_string()))
+}
+
+/// Host-side implementation of [`verify_capability`]
+pub fn verify_capability_json(token_json: &str, public_key_hex: &str) -> TokenVerification {
+    let token: CapabilityToken = match serde_json::from_str(token_json) {
+        Ok(token) => token,
+        Err(e) => return TokenVerification::rejected(format!("Invalid token JSON: {}", e)),
+    };
+
+    let verifier = match CapabilityVerifier::from_public_key_hex(token.key_id.clone(), public_key_hex) {
+        Ok(verifier) => verifier,
+        Err(e) => return TokenVerification::rejected(format!("Invalid public key: {}", e)),
+    };
+
+    match verifier.verify(&token) {
+        Ok(result) => TokenVerification {
+            valid: result.valid,
+            errors: result.errors,
+            warnings: result.warnings,
+        },
+        Err(e) => TokenVerification::rejected(e.to_string()),
+    }
+}
+
+/// Canonical form of a VĀKYA handed back to JavaScript
+#[derive(Debug, Clone, Serialize)]
+pub struct CanonicalVakya {
+    /// SHA-256 of the canonical bytes, as the gateway records `vakya_hash`
+    pub hash: String,
+    /// RFC 8785 canonical JSON, the bytes a VĀKYA signature covers
+    pub canonical_bytes_base64: String,
+}
+
+/// Canonicalize a VĀKYA (JSON) exactly as the gateway does
+///
+/// Returns `{ hash, canonical_bytes_base64 }`; throws if the JSON is not a
+/// valid VĀKYA.
+#[wasm_bindgen]
+pub fn canonicalize_vakya(vakya_json: &str) -> Result<JsValue, JsError> {
+    let canonical = canonicalize_vakya_json(vakya_json).map_err(|e| JsError::new(&e))?;
+    serde_wasm_bindgen::to_value(&canonical).map_err(|e| JsError::new(&e.to_string()))
+}
+
+/// Host-side implementation of [`canonicalize_vakya`]
+pub fn canonicalize_vakya_json(vakya_json: &str) -> Result<CanonicalVakya, String> {
+    let vakya: Vakya = serde_json::from_str(vakya_json)
+        .map_err(|e| format!("Invalid VĀKYA JSON: {}", e))?;
+    let sandhi = canonicalize(&vakya).map_err(|e| e.to_string())?;
+
+    Ok(CanonicalVakya {
+        hash: sandhi.vakya_hash.value,
+        canonical_bytes_base64: base64::engine::general_purpose::STANDARD.encode(&sandhi.canonical_bytes),
+    })
+}
+
+/// Sign canonical bytes (base64) with an Ed25519 secret key (hex)
+///
+/// Returns the base64 signature to submit alongside the VĀKYA.
+#[wasm_bindgen]
+pub fn sign_ed25519(canonical_bytes_base64: &str, secret_key_hex: &str) -> Result<String, JsError> {
+    sign_ed25519_base64(canonical_bytes_base64, secret_key_hex).map_err(|e| JsError::new(&e))
+}
+
+/// Host-side implementation of [`sign_ed25519`]
+pub fn sign_ed25519_base64(canonical_bytes_base64: &str, secret_key_hex: &str) -> Result<String, String> {
+    let bytes = base64::engine::general_purpose::STANDARD.decode(canonical_bytes_base64)
+        .map_err(|e| format!("Invalid canonical bytes: {}", e))?;
+    let key_pair = KeyPair::import_secret(secret_key_hex, SecretKeyEncoding::Hex, KeyPurpose::VakyaSigning)
+        .map_err(|e| format!("Invalid secret key: {}", e))?;
+    sign_bytes(&key_pair, &bytes).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aapi_crypto::{CapabilityTokenBuilder, KeyPair, KeyPurpose};
+    use aapi_core::types::PrincipalId;
+
+    #[test]
+    fn test_verify_capability_json() {
+        let key_pair = KeyPair::generate(KeyPurpose::CapabilitySigning);
+        let token = CapabilityTokenBuilder::new()
+            .issuer(PrincipalId::new("issuer:gateway"))
+            .subject(PrincipalId::new("agent:browser"))
+            .action("http.get")
+            .resource("**")
+            .ttl_seconds(600)
+            .build_and_sign(&key_pair)
+            .unwrap();
+        let token_json = serde_json::to_string(&token).unwrap();
+
+        let result = verify_capability_json(&token_json, &key_pair.public_key_hex());
+        assert!(result.valid, "{:?}", result.errors);
+
+        let other = KeyPair::generate(KeyPurpose::CapabilitySigning);
+        let result = verify_capability_json(&token_json, &other.public_key_hex());
+        assert!(!result.valid);
+        assert_eq!(result.errors, vec!["Invalid signature".to_string()]);
+
+        let result = verify_capability_json(&token_json, "not-hex");
+        assert!(result.errors[0].starts_with("Invalid public key"));
+
+        let result = verify_capability_json("{}", &key_pair.public_key_hex());
+        assert!(result.errors[0].starts_with("Invalid token JSON"));
+    }
+
+    #[test]
+    fn test_canonicalize_and_sign_match_gateway() {
+        use aapi_core::vakya::*;
+        use aapi_core::types::{ApprovalLane, ResourceId};
+        use aapi_crypto::{KeyStore, SignedVakya, VakyaSignature, VakyaVerifier, SignatureAlgorithm};
+
+        let vakya = Vakya::builder()
+            .karta(Karta {
+                pid: PrincipalId::new("agent:browser"),
+                role: None,
+                realm: None,
+                key_id: None,
+                actor_type: ActorType::Agent,
+                delegation_chain: vec![],
+            })
+            .karma(Karma {
+                rid: ResourceId::new("file:/tmp/aapi/report.txt"),
+                kind: Some("file".to_string()),
+                ns: None,
+                version: None,
+                labels: std::collections::HashMap::new(),
+            })
+            .kriya(Kriya::new("file", "read"))
+            .adhikarana(Adhikarana {
+                cap: CapabilityRef::Reference { cap_ref: "cap:browser".to_string() },
+                policy_ref: None,
+                ttl: None,
+                budgets: vec![],
+                approval_lane: ApprovalLane::None,
+                scopes: vec![],
+                context: None,
+                delegation_chain_cid: None,
+                execution_constraints: None,
+                port_id: None,
+                required_phase: None,
+                required_role: None,
+            })
+            .body(serde_json::json!({ "encoding": "utf-8", "offset": 0 }))
+            .build()
+            .unwrap();
+
+        // Key order in the submitted JSON does not change the canonical form
+        let json = serde_json::to_value(&vakya).unwrap();
+        let canonical = canonicalize_vakya_json(&json.to_string()).unwrap();
+        assert_eq!(canonical.hash, canonicalize(&vakya).unwrap().vakya_hash.value);
+
+        let key_pair = KeyPair::generate(KeyPurpose::VakyaSigning);
+        let secret_hex = hex::encode(key_pair.signing_key().to_bytes());
+        let signature = sign_ed25519_base64(&canonical.canonical_bytes_base64, &secret_hex).unwrap();
+
+        // Verified the way the gateway verifies a submission
+        let store = KeyStore::new();
+        store.store_key(key_pair.clone()).unwrap();
+        let signed = SignedVakya {
+            vakya,
+            vakya_hash: canonical.hash,
+            signature: VakyaSignature {
+                key_id: key_pair.key_id.clone(),
+                algorithm: SignatureAlgorithm::Ed25519,
+                value: signature,
+                signed_at: chrono::Utc::now(),
+            },
+        };
+        assert!(VakyaVerifier::new(store).verify(&signed).unwrap().valid);
+
+        assert!(canonicalize_vakya_json("{}").unwrap_err().starts_with("Invalid VĀKYA JSON"));
+        assert!(sign_ed25519_base64(&canonical.canonical_bytes_base64, "abcd").unwrap_err().starts_with("Invalid secret key"));
+    }
+}
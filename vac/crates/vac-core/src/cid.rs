@@ -2,6 +2,7 @@
 //!
 //! Uses CIDv1 + DAG-CBOR + SHA2-256 as specified in arch.md
 
+use cid::multibase::Base;
 use cid::Cid;
 use multihash::Multihash;
 use serde::Serialize;
@@ -15,22 +16,110 @@ const DAG_CBOR_CODE: u64 = 0x71;
 /// SHA2-256 multihash code
 const SHA256_CODE: u64 = 0x12;
 
+/// DAG-PB multicodec code, implied by every CIDv0
+const DAG_PB_CODE: u64 = 0x70;
+
+/// SHA2-512 multihash code
+const SHA512_CODE: u64 = 0x13;
+
+/// How `compute_cid` addresses objects and how CIDs are rendered as strings
+///
+/// The default (CIDv1, SHA2-256, base32) is what VAC stores and compares.
+/// Other settings exist for interop with IPLD tools that expect a different
+/// form; a CID computed under one configuration never equals one computed
+/// under another, even for the same object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CidConfig {
+    /// CID version. CIDv0 requires SHA2-256 and always renders as base58btc.
+    pub version: cid::Version,
+    /// Multihash code: SHA2-256 (0x12) or SHA2-512 (0x13)
+    pub hash: u64,
+    /// Multibase for the string form of CIDv1
+    pub base: Base,
+}
+
+impl Default for CidConfig {
+    fn default() -> Self {
+        Self {
+            version: cid::Version::V1,
+            hash: SHA256_CODE,
+            base: Base::Base32Lower,
+        }
+    }
+}
+
+impl CidConfig {
+    /// Address `bytes` under this configuration
+    pub fn cid_of(&self, bytes: &[u8]) -> VacResult<Cid> {
+        let mh = match self.hash {
+            SHA256_CODE => Multihash::<64>::wrap(SHA256_CODE, &sha256(bytes)),
+            SHA512_CODE => Multihash::<64>::wrap(SHA512_CODE, &sha2::Sha512::digest(bytes)),
+            other => return Err(VacError::CidError(format!("unsupported multihash code {:#x}", other))),
+        }
+        .map_err(|e| VacError::CidError(e.to_string()))?;
+        
+        match self.version {
+            cid::Version::V1 => Ok(Cid::new_v1(DAG_CBOR_CODE, mh)),
+            cid::Version::V0 => Cid::new(cid::Version::V0, DAG_PB_CODE, mh)
+                .map_err(|e| VacError::CidError(e.to_string())),
+        }
+    }
+    
+    /// Render `cid` in this configuration's multibase
+    pub fn format(&self, cid: &Cid) -> VacResult<String> {
+        cid.to_string_base(self.base)
+    }
+}
+
+/// String forms of a CID beyond the default `to_string`
+pub trait CidExt {
+    /// Render in `base`; CIDv0 only has a base58btc form
+    fn to_string_base(&self, base: Base) -> VacResult<String>;
+}
+
+impl CidExt for Cid {
+    fn to_string_base(&self, base: Base) -> VacResult<String> {
+        match self.version() {
+            cid::Version::V0 if base == Base::Base58Btc => Ok(self.to_string()),
+            cid::Version::V0 => Err(VacError::CidError(format!(
+                "CIDv0 can only be rendered as base58btc, not {:?}",
+                base
+            ))),
+            cid::Version::V1 => self
+                .to_string_of_base(base)
+                .map_err(|e| VacError::CidError(e.to_string())),
+        }
+    }
+}
+
+/// Parse a CID string in any multibase, or a bare base58btc CIDv0
+pub fn parse_cid(s: &str) -> VacResult<Cid> {
+    Cid::try_from(s).map_err(|e| VacError::CidError(format!("{}: {}", s, e)))
+}
+
 /// Compute CIDv1 for any serializable object using DAG-CBOR + SHA2-256
 pub fn compute_cid<T: Serialize>(obj: &T) -> VacResult<Cid> {
-    // Serialize to DAG-CBOR
+    compute_cid_with(obj, &CidConfig::default())
+}
+
+/// Compute the CID of an object's DAG-CBOR encoding under `config`
+pub fn compute_cid_with<T: Serialize>(obj: &T, config: &CidConfig) -> VacResult<Cid> {
     let bytes = to_dag_cbor(obj)?;
-    
-    // Compute SHA2-256 hash
-    let hash_bytes = sha256(&bytes);
-    
-    // Create multihash (SHA2-256 = 0x12, 32 bytes)
-    let mh = Multihash::<64>::wrap(SHA256_CODE, &hash_bytes)
+    config.cid_of(&bytes)
+}
+
+/// Compute the default CID without buffering the encoding
+///
+/// Matches `compute_cid` but hashes the DAG-CBOR stream as it is written,
+/// so memory stays flat however large the object's encoding is.
+pub fn compute_cid_streaming<T: Serialize>(obj: &T) -> VacResult<Cid> {
+    let mut writer = crate::codec::HashingWriter::sink();
+    ciborium::into_writer(obj, &mut writer)
+        .map_err(|e| VacError::CodecError(e.to_string()))?;
+    let (digest, _) = writer.finish();
+    let mh = Multihash::<64>::wrap(SHA256_CODE, &digest)
         .map_err(|e| VacError::CidError(e.to_string()))?;
-    
-    // Create CIDv1
-    let cid = Cid::new_v1(DAG_CBOR_CODE, mh);
-    
-    Ok(cid)
+    Ok(Cid::new_v1(DAG_CBOR_CODE, mh))
 }
 
 /// Serialize object to DAG-CBOR bytes
@@ -218,6 +307,48 @@ mod tests {
         assert_eq!(cid.codec(), DAG_CBOR_CODE);
     }
     
+    #[test]
+    fn test_default_config_matches_v1_sha256() {
+        let cid = compute_cid(&"hello").unwrap();
+        assert_eq!(cid.version(), cid::Version::V1);
+        assert_eq!(cid.hash().code(), SHA256_CODE);
+        assert_eq!(CidConfig::default().format(&cid).unwrap(), cid.to_string());
+    }
+    
+    #[test]
+    fn test_roundtrip_base32_and_base58btc() {
+        let cid = compute_cid(&"hello").unwrap();
+        
+        let base32 = cid.to_string_base(Base::Base32Lower).unwrap();
+        assert!(base32.starts_with('b'));
+        assert_eq!(parse_cid(&base32).unwrap(), cid);
+        
+        let base58 = cid.to_string_base(Base::Base58Btc).unwrap();
+        assert!(base58.starts_with('z'));
+        assert_eq!(parse_cid(&base58).unwrap(), cid);
+        assert_ne!(base32, base58);
+    }
+    
+    #[test]
+    fn test_cid_v0_and_sha512() {
+        let v0 = CidConfig { version: cid::Version::V0, ..CidConfig::default() };
+        let cid = compute_cid_with(&"hello", &v0).unwrap();
+        assert_eq!(cid.version(), cid::Version::V0);
+        let s = cid.to_string_base(Base::Base58Btc).unwrap();
+        assert!(s.starts_with("Qm"));
+        assert_eq!(parse_cid(&s).unwrap(), cid);
+        assert!(cid.to_string_base(Base::Base32Lower).is_err());
+        
+        let sha512 = CidConfig { hash: SHA512_CODE, ..CidConfig::default() };
+        let cid = compute_cid_with(&"hello", &sha512).unwrap();
+        assert_eq!(cid.hash().digest().len(), 64);
+        assert_ne!(cid, compute_cid(&"hello").unwrap());
+        
+        let v0_sha512 = CidConfig { hash: SHA512_CODE, ..v0 };
+        assert!(compute_cid_with(&"hello", &v0_sha512).is_err());
+        assert!(parse_cid("not a cid").is_err());
+    }
+    
     #[test]
     fn test_sha256() {
         let hash = sha256(b"hello world");
@@ -1,19 +1,31 @@
 //! Codec traits and implementations for VAC objects
 
+use std::io::Write;
+
 use cid::Cid;
 use serde::{de::DeserializeOwned, Serialize};
+use sha2::{Digest, Sha256};
 
 use crate::error::{VacError, VacResult};
 
 /// Trait for objects that can be content-addressed
 pub trait ContentAddressable: Serialize + DeserializeOwned {
+    /// Check the object is well-formed; run before it is addressed or encoded
+    fn validate(&self) -> VacResult<()> {
+        Ok(())
+    }
+    
     /// Compute the CID for this object
+    ///
+    /// The encoding is hashed as it is produced and never buffered.
     fn cid(&self) -> VacResult<Cid> {
-        crate::cid::compute_cid(self)
+        self.validate()?;
+        crate::cid::compute_cid_streaming(self)
     }
     
     /// Serialize to DAG-CBOR bytes
     fn to_bytes(&self) -> VacResult<Vec<u8>> {
+        self.validate()?;
         crate::cid::to_dag_cbor(self)
     }
     
@@ -24,8 +36,73 @@ pub trait ContentAddressable: Serialize + DeserializeOwned {
     }
 }
 
+/// Encode an object as DAG-CBOR straight into `writer`
+pub fn encode_to_writer<T: ContentAddressable, W: Write>(obj: &T, writer: &mut W) -> VacResult<()> {
+    obj.validate()?;
+    ciborium::into_writer(obj, writer)
+        .map_err(|e| VacError::CodecError(e.to_string()))
+}
+
+/// Encode an object as DAG-CBOR into a buffer
+pub fn encode<T: ContentAddressable>(obj: &T) -> VacResult<Vec<u8>> {
+    let mut bytes = Vec::new();
+    encode_to_writer(obj, &mut bytes)?;
+    Ok(bytes)
+}
+
+/// Writer that SHA2-256 hashes everything passing through to `inner`
+pub struct HashingWriter<W> {
+    inner: W,
+    hasher: Sha256,
+    written: u64,
+}
+
+impl HashingWriter<std::io::Sink> {
+    /// Hash without keeping the bytes anywhere
+    pub fn sink() -> Self {
+        Self::new(std::io::sink())
+    }
+}
+
+impl<W: Write> HashingWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            hasher: Sha256::new(),
+            written: 0,
+        }
+    }
+    
+    /// Bytes written so far
+    pub fn written(&self) -> u64 {
+        self.written
+    }
+    
+    /// Digest of everything written, and the inner writer
+    pub fn finish(self) -> ([u8; 32], W) {
+        (self.hasher.finalize().into(), self.inner)
+    }
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.hasher.update(&buf[..n]);
+        self.written += n as u64;
+        Ok(n)
+    }
+    
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
 // Implement ContentAddressable for all core types
-impl ContentAddressable for crate::types::Event {}
+impl ContentAddressable for crate::types::Event {
+    fn validate(&self) -> VacResult<()> {
+        crate::types::Event::validate(self).map_err(VacError::from)
+    }
+}
 impl ContentAddressable for crate::types::ClaimBundle {}
 impl ContentAddressable for crate::types::Bracket {}
 impl ContentAddressable for crate::types::Node {}
@@ -73,4 +150,36 @@ mod tests {
         // Same content should produce same CID
         assert_eq!(event.cid().unwrap(), event2.cid().unwrap());
     }
+    
+    #[test]
+    fn test_streaming_cid_matches_buffered() {
+        let source = Source {
+            kind: SourceKind::User,
+            principal_id: "did:key:z6Mk...".to_string(),
+        };
+        let mut event = Event::new(1706764800000, Cid::default(), source);
+        event.feature_sketch = (0..Event::MAX_SKETCH_BYTES).map(|i| i as u8).collect();
+        
+        let buffered = encode(&event).unwrap();
+        assert_eq!(buffered, event.to_bytes().unwrap());
+        assert_eq!(event.cid().unwrap(), crate::cid::compute_cid(&event).unwrap());
+        
+        let mut writer = HashingWriter::sink();
+        encode_to_writer(&event, &mut writer).unwrap();
+        assert_eq!(writer.written(), buffered.len() as u64);
+        let (digest, _) = writer.finish();
+        assert_eq!(digest, crate::cid::sha256(&buffered));
+    }
+    
+    #[test]
+    fn test_invalid_event_has_no_cid() {
+        let source = Source {
+            kind: SourceKind::User,
+            principal_id: String::new(),
+        };
+        let event = Event::new(1706764800000, Cid::default(), source);
+        
+        assert!(matches!(event.cid(), Err(VacError::InvalidEvent(_))));
+        assert!(matches!(event.to_bytes(), Err(VacError::InvalidEvent(_))));
+    }
 }
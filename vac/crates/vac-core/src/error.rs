@@ -30,6 +30,37 @@ pub enum VacError {
     
     #[error("Invalid state: {0}")]
     InvalidState(String),
+    
+    #[error("Invalid event: {0}")]
+    InvalidEvent(#[from] EventError),
+}
+
+/// Reasons an `Event` is rejected before it is content-addressed
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum EventError {
+    #[error("missing required field `{0}`")]
+    MissingField(&'static str),
+    
+    #[error("invalid link `{name}`: {reason}")]
+    InvalidLink { name: String, reason: String },
+    
+    #[error("field `{field}` has size {size}, limit is {limit}")]
+    TooLarge { field: &'static str, size: usize, limit: usize },
+    
+    #[error("field `{field}` is out of range: {reason}")]
+    OutOfRange { field: &'static str, reason: String },
+}
+
+impl EventError {
+    /// Stable machine-readable code for bindings and logs
+    pub fn code(&self) -> &'static str {
+        match self {
+            EventError::MissingField(_) => "MISSING_FIELD",
+            EventError::InvalidLink { .. } => "INVALID_LINK",
+            EventError::TooLarge { .. } => "TOO_LARGE",
+            EventError::OutOfRange { .. } => "OUT_OF_RANGE",
+        }
+    }
 }
 
 pub type VacResult<T> = Result<T, VacError>;
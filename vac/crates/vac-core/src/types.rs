@@ -6,6 +6,8 @@ use cid::Cid;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
+use crate::error::EventError;
+
 /// Source of an event or claim
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Source {
@@ -108,6 +110,92 @@ impl Event {
             metadata: BTreeMap::new(),
         }
     }
+    
+    /// Largest number of actors, tags, entities, links or metadata entries
+    pub const MAX_LIST_LEN: usize = 1024;
+    /// Largest feature sketch in bytes
+    pub const MAX_SKETCH_BYTES: usize = 64 * 1024;
+    /// Largest string field in bytes
+    pub const MAX_STRING_BYTES: usize = 4096;
+    
+    /// Check the event is well-formed before it is content-addressed
+    ///
+    /// Once an event has a CID it is immutable, so malformed events must be
+    /// rejected here rather than discovered downstream. Links must be CIDv1
+    /// SHA2-256 references as produced by `compute_cid`.
+    pub fn validate(&self) -> Result<(), EventError> {
+        if self.type_.is_empty() {
+            return Err(EventError::MissingField("type"));
+        }
+        if self.version == 0 {
+            return Err(EventError::MissingField("version"));
+        }
+        if self.source.principal_id.is_empty() {
+            return Err(EventError::MissingField("source.principal_id"));
+        }
+        if self.ts < 0 {
+            return Err(EventError::OutOfRange {
+                field: "ts",
+                reason: format!("{} is before the epoch", self.ts),
+            });
+        }
+        for (field, value) in [("entropy", self.entropy), ("importance", self.importance)] {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(EventError::OutOfRange {
+                    field,
+                    reason: format!("{} is not within [0, 1]", value),
+                });
+            }
+        }
+        
+        check_size("feature_sketch", self.feature_sketch.len(), Self::MAX_SKETCH_BYTES)?;
+        for (field, list) in [("actors", &self.actors), ("tags", &self.tags), ("entities", &self.entities)] {
+            check_size(field, list.len(), Self::MAX_LIST_LEN)?;
+            for item in list {
+                check_size(field, item.len(), Self::MAX_STRING_BYTES)?;
+            }
+        }
+        if let Some(hint) = &self.chapter_hint {
+            check_size("chapter_hint", hint.len(), Self::MAX_STRING_BYTES)?;
+        }
+        check_size("metadata", self.metadata.len(), Self::MAX_LIST_LEN)?;
+        
+        check_size("links", self.links.len(), Self::MAX_LIST_LEN)?;
+        for (name, cid) in &self.links {
+            if name.is_empty() {
+                return Err(EventError::InvalidLink {
+                    name: name.clone(),
+                    reason: "empty link name".to_string(),
+                });
+            }
+            check_size("links", name.len(), Self::MAX_STRING_BYTES)?;
+            if cid.version() != cid::Version::V1 {
+                return Err(EventError::InvalidLink {
+                    name: name.clone(),
+                    reason: format!("expected CIDv1, got {:?}", cid.version()),
+                });
+            }
+            let hash = cid.hash();
+            if hash.code() != 0x12 || hash.digest().len() != 32 {
+                return Err(EventError::InvalidLink {
+                    name: name.clone(),
+                    reason: format!(
+                        "expected a 32-byte SHA2-256 multihash, got code {:#x} with {} bytes",
+                        hash.code(),
+                        hash.digest().len()
+                    ),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+fn check_size(field: &'static str, size: usize, limit: usize) -> Result<(), EventError> {
+    if size > limit {
+        return Err(EventError::TooLarge { field, size, limit });
+    }
+    Ok(())
 }
 
 /// Epistemic status for claims
@@ -3291,6 +3379,34 @@ mod tests {
         assert_eq!(event.trust_tier, 1);
     }
     
+    #[test]
+    fn test_event_validate() {
+        let source = Source {
+            kind: SourceKind::User,
+            principal_id: "did:key:z6Mk...".to_string(),
+        };
+        let mut event = Event::new(1706764800000, Cid::default(), source);
+        let target = crate::cid::compute_cid(&"target").unwrap();
+        event.links.insert("parent".to_string(), target);
+        assert!(event.validate().is_ok());
+        
+        let mut missing = event.clone();
+        missing.source.principal_id.clear();
+        assert_eq!(missing.validate(), Err(EventError::MissingField("source.principal_id")));
+        
+        let mut bad_link = event.clone();
+        bad_link.links.insert("broken".to_string(), Cid::default());
+        assert!(matches!(bad_link.validate(), Err(EventError::InvalidLink { name, .. }) if name == "broken"));
+        
+        let mut oversized = event.clone();
+        oversized.feature_sketch = vec![0; Event::MAX_SKETCH_BYTES + 1];
+        assert_eq!(oversized.validate().unwrap_err().code(), "TOO_LARGE");
+        
+        let mut out_of_range = event;
+        out_of_range.importance = f32::NAN;
+        assert!(matches!(out_of_range.validate(), Err(EventError::OutOfRange { field: "importance", .. })));
+    }
+    
     #[test]
     fn test_claim_bundle_creation() {
         let source = Source {
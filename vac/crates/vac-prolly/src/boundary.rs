@@ -4,6 +4,7 @@
 
 use sha2::{Sha256, Digest};
 
+use crate::config::ProllyConfig;
 use crate::{BOUNDARY_THRESHOLD, DEFAULT_Q};
 
 /// Check if a key is a boundary key (starts a new chunk)
@@ -15,6 +16,16 @@ pub fn is_boundary(key: &[u8]) -> bool {
     hash < BOUNDARY_THRESHOLD
 }
 
+/// Check if `keys[index]` is a boundary key under `config`
+///
+/// The hash covers the `config.window` keys ending at `index` (fewer at the
+/// start of `keys`). With the default configuration this agrees with
+/// `is_boundary`.
+pub fn is_boundary_at(config: &ProllyConfig, keys: &[Vec<u8>], index: usize) -> bool {
+    let start = (index + 1).saturating_sub(config.window);
+    window_hash(config.seed, &keys[start..=index]) < config.threshold()
+}
+
 /// Hash a key to a u32 for boundary detection
 fn hash_key(key: &[u8]) -> u32 {
     let mut hasher = Sha256::new();
@@ -25,6 +36,25 @@ fn hash_key(key: &[u8]) -> u32 {
     u32::from_be_bytes([result[0], result[1], result[2], result[3]])
 }
 
+/// Hash a window of keys to a u32 for boundary detection
+///
+/// A lone key with no seed hashes exactly as `hash_key`; otherwise the seed
+/// and length-prefixed keys are hashed together.
+fn window_hash(seed: u64, keys: &[Vec<u8>]) -> u32 {
+    if let ([key], 0) = (keys, seed) {
+        return hash_key(key);
+    }
+
+    let mut hasher = Sha256::new();
+    hasher.update(seed.to_be_bytes());
+    for key in keys {
+        hasher.update((key.len() as u32).to_be_bytes());
+        hasher.update(key);
+    }
+    let result = hasher.finalize();
+    u32::from_be_bytes([result[0], result[1], result[2], result[3]])
+}
+
 /// Compute the boundary probability for a given Q
 pub fn boundary_probability(q: usize) -> f64 {
     1.0 / q as f64
@@ -67,4 +97,34 @@ mod tests {
         assert!(ratio > expected * 0.5);
         assert!(ratio < expected * 1.5);
     }
+
+    #[test]
+    fn test_default_config_matches_is_boundary() {
+        let config = ProllyConfig::default();
+        let keys: Vec<Vec<u8>> = (0..1000).map(|i| format!("key_{}", i).into_bytes()).collect();
+        for (i, key) in keys.iter().enumerate() {
+            assert_eq!(is_boundary_at(&config, &keys, i), is_boundary(key));
+        }
+    }
+
+    #[test]
+    fn test_configured_boundaries() {
+        let keys: Vec<Vec<u8>> = (0..10000).map(|i| format!("key_{}", i).into_bytes()).collect();
+        let count = |config: &ProllyConfig| {
+            (0..keys.len()).filter(|&i| is_boundary_at(config, &keys, i)).count()
+        };
+
+        // A larger Q gives proportionally fewer boundaries
+        let coarse = ProllyConfig { target_q: 128, window: 3, seed: 42 };
+        let ratio = count(&coarse) as f64 / keys.len() as f64;
+        assert!(ratio > 0.5 / 128.0);
+        assert!(ratio < 1.5 / 128.0);
+
+        // The seed moves the boundaries
+        let default = ProllyConfig::default();
+        let seeded = ProllyConfig { seed: 1, ..default };
+        assert!((0..keys.len()).any(|i| {
+            is_boundary_at(&default, &keys, i) != is_boundary_at(&seeded, &keys, i)
+        }));
+    }
 }
@@ -0,0 +1,125 @@
+//! Chunking configuration for Prolly trees
+//!
+//! The configuration decides where chunk boundaries fall, so it is part of a
+//! tree's identity: the same entries chunked under two configurations give
+//! different node structure and different CIDs, and diffs between such trees
+//! are meaningless. A non-default configuration is recorded as a fingerprint
+//! in the root node so trees built with different configurations are never
+//! mistaken for one another.
+
+use serde::{Deserialize, Serialize};
+
+use vac_core::{sha256_domain, VacError, VacResult};
+
+use crate::DEFAULT_Q;
+
+/// Largest number of keys a boundary window may span
+pub const MAX_WINDOW: usize = 64;
+
+/// Rolling-hash parameters for content-defined chunking
+///
+/// Larger `target_q` gives fewer, bigger chunks (cheaper storage, coarser
+/// diffs); smaller gives finer diffs at the cost of more nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProllyConfig {
+    /// Target entries per chunk; a key is a boundary with probability ~1/Q
+    pub target_q: usize,
+    /// Number of consecutive keys, ending at the candidate, hashed to decide
+    /// whether it is a boundary
+    pub window: usize,
+    /// Seed mixed into the boundary hash
+    pub seed: u64,
+}
+
+impl Default for ProllyConfig {
+    fn default() -> Self {
+        Self {
+            target_q: DEFAULT_Q,
+            window: 1,
+            seed: 0,
+        }
+    }
+}
+
+impl ProllyConfig {
+    /// Check the parameters are usable
+    pub fn validate(&self) -> VacResult<()> {
+        if self.target_q < 2 || self.target_q > u32::MAX as usize {
+            return Err(VacError::InvalidState(format!(
+                "Prolly target_q must be at least 2, got {}",
+                self.target_q
+            )));
+        }
+        if self.window == 0 || self.window > MAX_WINDOW {
+            return Err(VacError::InvalidState(format!(
+                "Prolly window must be between 1 and {}, got {}",
+                MAX_WINDOW, self.window
+            )));
+        }
+        Ok(())
+    }
+
+    /// Boundary hashes below this threshold start a new chunk
+    pub fn threshold(&self) -> u32 {
+        u32::MAX / self.target_q as u32
+    }
+
+    /// Hard limit on entries per chunk (4×Q), see `ProllyNode::MAX_CHUNK_SIZE`
+    pub fn max_chunk_size(&self) -> usize {
+        self.target_q * 4
+    }
+
+    /// Stable digest of the parameters
+    pub fn fingerprint(&self) -> [u8; 32] {
+        let mut data = Vec::with_capacity(24);
+        data.extend_from_slice(&(self.target_q as u64).to_be_bytes());
+        data.extend_from_slice(&(self.window as u64).to_be_bytes());
+        data.extend_from_slice(&self.seed.to_be_bytes());
+        sha256_domain(b"vac.prolly.config.v1", &data)
+    }
+
+    /// Fingerprint recorded in a root node
+    ///
+    /// The default configuration records none, so trees built before the
+    /// configuration was tunable keep their CIDs.
+    pub fn root_fingerprint(&self) -> Option<[u8; 32]> {
+        if *self == Self::default() {
+            None
+        } else {
+            Some(self.fingerprint())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_matches_constants() {
+        let config = ProllyConfig::default();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.threshold(), crate::BOUNDARY_THRESHOLD);
+        assert_eq!(config.max_chunk_size(), crate::ProllyNode::MAX_CHUNK_SIZE);
+        assert!(config.root_fingerprint().is_none());
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_parameters() {
+        let bad_q = ProllyConfig { target_q: 1, ..Default::default() };
+        assert!(bad_q.validate().is_err());
+        let no_window = ProllyConfig { window: 0, ..Default::default() };
+        assert!(no_window.validate().is_err());
+        let wide_window = ProllyConfig { window: MAX_WINDOW + 1, ..Default::default() };
+        assert!(wide_window.validate().is_err());
+    }
+
+    #[test]
+    fn test_fingerprint_distinguishes_configs() {
+        let a = ProllyConfig { target_q: 64, window: 4, seed: 7 };
+        let b = ProllyConfig { seed: 8, ..a };
+        assert_eq!(a.fingerprint(), a.fingerprint());
+        assert_ne!(a.fingerprint(), b.fingerprint());
+        assert_eq!(a.root_fingerprint(), Some(a.fingerprint()));
+    }
+}
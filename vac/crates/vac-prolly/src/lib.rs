@@ -4,17 +4,24 @@
 //! - History-independent structure
 //! - O(log n) lookup, insert, delete
 //! - Efficient diff/sync via Merkle proofs
-//! - Branching factor Q = 32
+//! - Branching factor Q = 32 by default, tunable through [`ProllyConfig`]
+//!
+//! The chunking configuration is part of a tree's identity: changing it
+//! changes the tree's node structure and therefore its CIDs. Trees
+//! built with a non-default configuration record its fingerprint in their
+//! root, and refuse to be opened or extended under another configuration.
 
 pub mod tree;
 pub mod node;
 pub mod proof;
 pub mod boundary;
+pub mod config;
 
 pub use tree::*;
 pub use node::*;
 pub use proof::*;
 pub use boundary::*;
+pub use config::*;
 
 /// Default branching factor
 pub const DEFAULT_Q: usize = 32;
@@ -4,6 +4,8 @@ use cid::Cid;
 use serde::{Deserialize, Serialize};
 use vac_core::{compute_prolly_node_hash, ContentAddressable, VacResult};
 
+use crate::config::ProllyConfig;
+
 /// A node in the Prolly tree
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProllyNode {
@@ -13,6 +15,10 @@ pub struct ProllyNode {
     pub keys: Vec<Vec<u8>>,
     /// Values (CIDs to data for leaves, CIDs to child nodes for internal)
     pub values: Vec<Cid>,
+    /// Fingerprint of the tree's `ProllyConfig`, on the root of a tree built
+    /// with a non-default configuration; absent otherwise
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_fingerprint: Option<[u8; 32]>,
     /// Cached node hash
     #[serde(skip)]
     cached_hash: Option<[u8; 32]>,
@@ -26,6 +32,7 @@ impl ProllyNode {
             level: 0,
             keys,
             values,
+            config_fingerprint: None,
             cached_hash: None,
         }
     }
@@ -38,6 +45,7 @@ impl ProllyNode {
             level,
             keys,
             values: children,
+            config_fingerprint: None,
             cached_hash: None,
         }
     }
@@ -111,6 +119,7 @@ impl ProllyNode {
             level: self.level,
             keys,
             values,
+            config_fingerprint: self.config_fingerprint,
             cached_hash: None,
         }
     }
@@ -128,6 +137,7 @@ impl ProllyNode {
                     level: self.level,
                     keys,
                     values,
+                    config_fingerprint: self.config_fingerprint,
                     cached_hash: None,
                 })
             }
@@ -146,24 +156,32 @@ impl ProllyNode {
     /// 1. Probabilistic: `hash(key) < THRESHOLD` (~1/Q probability)
     /// 2. Hard limit: chunk exceeds `MAX_CHUNK_SIZE` entries (prevents DoS)
     pub fn split_at_boundaries(&self) -> Vec<Self> {
-        use crate::boundary::is_boundary;
+        self.split_at_boundaries_with(&ProllyConfig::default())
+    }
+
+    /// Split node at the boundaries `config` places, capping chunks at
+    /// `config.max_chunk_size()` entries
+    pub fn split_at_boundaries_with(&self, config: &ProllyConfig) -> Vec<Self> {
+        use crate::boundary::is_boundary_at;
         
         if self.is_empty() {
             return vec![];
         }
         
+        let max_chunk_size = config.max_chunk_size();
         let mut chunks = Vec::new();
         let mut current_keys = Vec::new();
         let mut current_values = Vec::new();
         
         for (i, key) in self.keys.iter().enumerate() {
             // Force boundary if chunk exceeds max size OR probabilistic boundary hit
-            let force_split = current_keys.len() >= Self::MAX_CHUNK_SIZE;
-            if (is_boundary(key) || force_split) && !current_keys.is_empty() {
+            let force_split = current_keys.len() >= max_chunk_size;
+            if (is_boundary_at(config, &self.keys, i) || force_split) && !current_keys.is_empty() {
                 chunks.push(Self {
                     level: self.level,
                     keys: std::mem::take(&mut current_keys),
                     values: std::mem::take(&mut current_values),
+                    config_fingerprint: None,
                     cached_hash: None,
                 });
             }
@@ -178,6 +196,7 @@ impl ProllyNode {
                 level: self.level,
                 keys: current_keys,
                 values: current_values,
+                config_fingerprint: None,
                 cached_hash: None,
             });
         }
@@ -276,6 +295,28 @@ mod tests {
         assert!(chunks.len() >= 2, "Should have split into multiple chunks");
     }
 
+    #[test]
+    fn test_split_with_config() {
+        let keys: Vec<Vec<u8>> = (0..500u32)
+            .map(|i| format!("key_{:06}", i).into_bytes())
+            .collect();
+        let values: Vec<Cid> = (0..500).map(|_| Cid::default()).collect();
+        let node = ProllyNode::new_leaf(keys, values);
+
+        let fine = ProllyConfig { target_q: 4, window: 2, seed: 9 };
+        let chunks = node.split_at_boundaries_with(&fine);
+        assert!(chunks.len() > node.split_at_boundaries().len());
+        assert!(chunks.iter().all(|c| c.len() <= fine.max_chunk_size()));
+        assert_eq!(chunks.iter().map(|c| c.len()).sum::<usize>(), 500);
+
+        // Same input and config, same chunks
+        let again = node.split_at_boundaries_with(&fine);
+        assert_eq!(
+            chunks.iter().map(|c| c.keys.clone()).collect::<Vec<_>>(),
+            again.iter().map(|c| c.keys.clone()).collect::<Vec<_>>()
+        );
+    }
+
     #[test]
     fn test_h3_small_node_not_force_split() {
         // A node smaller than MAX_CHUNK_SIZE should not be force-split
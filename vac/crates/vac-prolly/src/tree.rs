@@ -10,6 +10,7 @@ use std::future::Future;
 
 use vac_core::{VacError, VacResult};
 
+use crate::config::ProllyConfig;
 use crate::node::ProllyNode;
 use crate::proof::{ProllyProof, ProofStep};
 
@@ -59,17 +60,36 @@ impl NodeStore for MemoryNodeStore {
 pub struct ProllyTree<S: NodeStore> {
     store: S,
     root: Option<Cid>,
+    config: ProllyConfig,
 }
 
 impl<S: NodeStore> ProllyTree<S> {
     /// Create a new empty tree
     pub fn new(store: S) -> Self {
-        Self { store, root: None }
+        Self { store, root: None, config: ProllyConfig::default() }
+    }
+    
+    /// Create a new empty tree with custom chunking parameters
+    ///
+    /// The configuration fixes the tree's structure and CIDs; see
+    /// [`ProllyConfig`].
+    pub fn with_config(store: S, config: ProllyConfig) -> VacResult<Self> {
+        config.validate()?;
+        Ok(Self { store, root: None, config })
     }
     
     /// Create a tree with an existing root
     pub fn with_root(store: S, root: Cid) -> Self {
-        Self { store, root: Some(root) }
+        Self { store, root: Some(root), config: ProllyConfig::default() }
+    }
+    
+    /// Open an existing tree, checking it was built with `config`
+    pub async fn open(store: S, root: Cid, config: ProllyConfig) -> VacResult<Self> {
+        config.validate()?;
+        let node = store.get(&root).await?;
+        let tree = Self { store, root: Some(root), config };
+        tree.check_config(&node)?;
+        Ok(tree)
     }
     
     /// Get the root CID
@@ -77,6 +97,21 @@ impl<S: NodeStore> ProllyTree<S> {
         self.root.as_ref()
     }
     
+    /// Get the chunking configuration
+    pub fn config(&self) -> &ProllyConfig {
+        &self.config
+    }
+    
+    /// Fail unless `root` records this tree's configuration
+    fn check_config(&self, root: &ProllyNode) -> VacResult<()> {
+        if root.config_fingerprint != self.config.root_fingerprint() {
+            return Err(VacError::InvalidState(
+                "Prolly root was built with a different chunking configuration".to_string(),
+            ));
+        }
+        Ok(())
+    }
+    
     /// Get a value by key (iterative to avoid async recursion)
     pub async fn get(&self, key: &[u8]) -> VacResult<Option<Cid>> {
         let mut current_cid = match &self.root {
@@ -109,7 +144,8 @@ impl<S: NodeStore> ProllyTree<S> {
             }
             None => {
                 // Create new leaf node
-                let node = ProllyNode::new_leaf(vec![key], vec![value]);
+                let mut node = ProllyNode::new_leaf(vec![key], vec![value]);
+                node.config_fingerprint = self.config.root_fingerprint();
                 self.store.put(&node).await?
             }
         };
@@ -121,6 +157,7 @@ impl<S: NodeStore> ProllyTree<S> {
     /// Insert into leaf (simplified - no tree balancing for v0.1)
     async fn insert_into_leaf(&self, node_cid: &Cid, key: Vec<u8>, value: Cid) -> VacResult<Cid> {
         let node = self.store.get(node_cid).await?;
+        self.check_config(&node)?;
         let new_node = node.insert(key, value);
         self.store.put(&new_node).await
     }
@@ -256,4 +293,29 @@ mod tests {
         let proof = tree.prove(b"nonexistent").await.unwrap();
         assert!(proof.is_none());
     }
+    
+    #[tokio::test]
+    async fn test_config_is_part_of_tree_identity() {
+        let config = ProllyConfig { target_q: 64, window: 4, seed: 7 };
+        assert!(ProllyTree::with_config(MemoryNodeStore::default(), ProllyConfig { window: 0, ..config }).is_err());
+        
+        let mut default_tree = ProllyTree::new(MemoryNodeStore::default());
+        let mut tuned_tree = ProllyTree::with_config(MemoryNodeStore::default(), config).unwrap();
+        for tree in [&mut default_tree, &mut tuned_tree] {
+            tree.insert(b"key1".to_vec(), Cid::default()).await.unwrap();
+            tree.insert(b"key2".to_vec(), Cid::default()).await.unwrap();
+        }
+        assert_ne!(default_tree.root(), tuned_tree.root());
+        assert_eq!(tuned_tree.get(b"key2").await.unwrap(), Some(Cid::default()));
+        
+        // Reopening needs the configuration the tree was built with
+        let root = *tuned_tree.root().unwrap();
+        let reopened = ProllyTree::open(tuned_tree.store, root, config).await.unwrap();
+        assert_eq!(reopened.config(), &config);
+        
+        let mut wrong = ProllyTree::with_root(reopened.store, root);
+        assert!(wrong.insert(b"key3".to_vec(), Cid::default()).await.is_err());
+        let result = ProllyTree::open(wrong.store, root, ProllyConfig::default()).await;
+        assert!(matches!(result, Err(VacError::InvalidState(_))));
+    }
 }
@@ -0,0 +1,51 @@
+//! Observation throughput: batched vs per-call
+//!
+//! Run with `cargo bench -p vac-red --bench observe`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use vac_red::{hash_to_dim, RedEngine, SparseVector};
+
+const VECTORS: usize = 1_000;
+
+fn build_vectors() -> Vec<SparseVector> {
+    (0..VECTORS)
+        .map(|i| {
+            let mut vector = SparseVector::new();
+            for j in 0..16 {
+                vector.add(hash_to_dim(&format!("feature:{}:{}", i, j), vector.dims), 1.0);
+            }
+            vector
+        })
+        .collect()
+}
+
+fn observe(c: &mut Criterion) {
+    let vectors = build_vectors();
+
+    let mut group = c.benchmark_group("observe");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(VECTORS as u64));
+
+    group.bench_with_input(BenchmarkId::new("per_call", VECTORS), &vectors, |b, vectors| {
+        b.iter(|| {
+            let mut engine = RedEngine::new();
+            for vector in vectors {
+                engine.observe(black_box(vector));
+            }
+            engine.total_observations
+        })
+    });
+    group.bench_with_input(BenchmarkId::new("batch", VECTORS), &vectors, |b, vectors| {
+        b.iter(|| {
+            let mut engine = RedEngine::new();
+            engine.observe_batch(black_box(vectors));
+            engine.total_observations
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, observe);
+criterion_main!(benches);
@@ -0,0 +1,248 @@
+//! Approximate nearest-neighbour index over sparse vectors
+//!
+//! Sign random projection LSH: each of `tables` hash tables buckets a vector
+//! by the signs of its dot products with `bits` random hyperplanes, so
+//! vectors at a small angle share a bucket with high probability. A query
+//! scores only the vectors in its buckets by cosine similarity.
+//!
+//! Hyperplane coefficients are derived from the seed by hashing rather than
+//! stored, so the index costs nothing per dimension. Only the config and the
+//! indexed vectors are serialized; buckets are rebuilt on load.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::vector::SparseVector;
+
+/// Tuning for the ANN index
+///
+/// More `tables` raise recall at the cost of more candidates per query;
+/// more `bits` make buckets more selective, lowering both.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnnConfig {
+    /// Number of hash tables (at least 1)
+    pub tables: usize,
+    /// Hyperplanes per table, 1 to 64
+    pub bits: usize,
+    /// Seed for the hyperplanes
+    pub seed: u64,
+    /// Corpora smaller than this are scanned exactly
+    pub exact_below: usize,
+}
+
+impl Default for AnnConfig {
+    fn default() -> Self {
+        Self {
+            tables: 8,
+            bits: 12,
+            seed: 0,
+            exact_below: 256,
+        }
+    }
+}
+
+/// LSH index of identified sparse vectors
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "AnnState", into = "AnnState")]
+pub struct AnnIndex {
+    config: AnnConfig,
+    entries: Vec<(String, SparseVector)>,
+    /// Per table: signature -> entry positions
+    buckets: Vec<HashMap<u64, Vec<usize>>>,
+}
+
+/// Serialized form of an `AnnIndex`
+#[derive(Serialize, Deserialize)]
+struct AnnState {
+    config: AnnConfig,
+    entries: Vec<(String, SparseVector)>,
+}
+
+impl From<AnnState> for AnnIndex {
+    fn from(state: AnnState) -> Self {
+        let mut index = AnnIndex::new(state.config);
+        for (id, vector) in state.entries {
+            index.insert(id, vector);
+        }
+        index
+    }
+}
+
+impl From<AnnIndex> for AnnState {
+    fn from(index: AnnIndex) -> Self {
+        Self { config: index.config, entries: index.entries }
+    }
+}
+
+impl AnnIndex {
+    /// Create an empty index; out-of-range `tables` and `bits` are clamped
+    pub fn new(config: AnnConfig) -> Self {
+        let config = AnnConfig {
+            tables: config.tables.max(1),
+            bits: config.bits.clamp(1, 64),
+            ..config
+        };
+        Self {
+            config,
+            entries: Vec::new(),
+            buckets: vec![HashMap::new(); config.tables],
+        }
+    }
+
+    pub fn config(&self) -> &AnnConfig {
+        &self.config
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Add a vector under `id`
+    pub fn insert(&mut self, id: impl Into<String>, vector: SparseVector) {
+        let position = self.entries.len();
+        for table in 0..self.config.tables {
+            let signature = self.signature(table, &vector);
+            self.buckets[table].entry(signature).or_default().push(position);
+        }
+        self.entries.push((id.into(), vector));
+    }
+
+    /// Up to `k` indexed vectors most similar to `query`, best first
+    ///
+    /// Corpora smaller than `exact_below` are scanned exactly. Larger ones
+    /// rank only the query's bucket-mates, so fewer than `k` results may come
+    /// back for a query unlike anything indexed.
+    pub fn top_k(&self, query: &SparseVector, k: usize) -> Vec<(String, f64)> {
+        if k == 0 {
+            return Vec::new();
+        }
+        if self.entries.len() < self.config.exact_below {
+            return self.rank(0..self.entries.len(), query, k);
+        }
+        self.rank(self.candidates(query).into_iter(), query, k)
+    }
+
+    /// Positions of the entries sharing a bucket with `query` in any table
+    fn candidates(&self, query: &SparseVector) -> Vec<usize> {
+        let mut candidates: Vec<usize> = (0..self.config.tables)
+            .filter_map(|table| self.buckets[table].get(&self.signature(table, query)))
+            .flatten()
+            .copied()
+            .collect();
+        candidates.sort_unstable();
+        candidates.dedup();
+        candidates
+    }
+
+    fn rank(&self, positions: impl Iterator<Item = usize>, query: &SparseVector, k: usize) -> Vec<(String, f64)> {
+        let mut scored: Vec<(usize, f64)> = positions
+            .map(|i| (i, self.entries[i].1.cosine_similarity(query)))
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        scored.truncate(k);
+        scored.into_iter()
+            .map(|(i, score)| (self.entries[i].0.clone(), score))
+            .collect()
+    }
+
+    /// Signs of the vector's projections onto one table's hyperplanes
+    fn signature(&self, table: usize, vector: &SparseVector) -> u64 {
+        let mut signature = 0u64;
+        for bit in 0..self.config.bits {
+            let plane = (table * self.config.bits + bit) as u64;
+            let projection: f64 = vector.nonzero()
+                .map(|(dim, weight)| weight * plane_coefficient(self.config.seed, plane, dim))
+                .sum();
+            if projection >= 0.0 {
+                signature |= 1 << bit;
+            }
+        }
+        signature
+    }
+}
+
+/// ±1 coefficient of hyperplane `plane` at dimension `dim`
+fn plane_coefficient(seed: u64, plane: u64, dim: usize) -> f64 {
+    let h = splitmix64(seed ^ splitmix64(plane ^ splitmix64(dim as u64)));
+    if h & 1 == 0 { 1.0 } else { -1.0 }
+}
+
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn corpus(n: usize) -> Vec<SparseVector> {
+        (0..n)
+            .map(|i| {
+                let mut vector = SparseVector::new();
+                for j in 0..20 {
+                    let dim = splitmix64((i * 20 + j) as u64) as usize % vector.dims;
+                    vector.add(dim, 1.0 + (j % 3) as f64);
+                }
+                vector
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_small_corpus_is_exact() {
+        let mut index = AnnIndex::new(AnnConfig::default());
+        let vectors = corpus(50);
+        for (i, vector) in vectors.iter().enumerate() {
+            index.insert(format!("v{}", i), vector.clone());
+        }
+
+        let top = index.top_k(&vectors[7], 3);
+        assert_eq!(top.len(), 3);
+        assert_eq!(top[0].0, "v7");
+        assert!((top[0].1 - 1.0).abs() < 1e-9);
+        assert!(top[1].1 <= top[0].1 && top[2].1 <= top[1].1);
+    }
+
+    #[test]
+    fn test_lsh_finds_near_duplicates_sublinearly() {
+        let mut index = AnnIndex::new(AnnConfig { exact_below: 0, ..AnnConfig::default() });
+        let vectors = corpus(2_000);
+        for (i, vector) in vectors.iter().enumerate() {
+            index.insert(format!("v{}", i), vector.clone());
+        }
+
+        let mut found = 0;
+        for i in (0..2_000).step_by(100) {
+            let mut query = vectors[i].clone();
+            query.add(*query.entries.keys().next().unwrap(), 0.1);
+            assert!(index.candidates(&query).len() < 2_000 / 4);
+            if index.top_k(&query, 5).iter().any(|(id, _)| *id == format!("v{}", i)) {
+                found += 1;
+            }
+        }
+        assert!(found >= 18, "recall {}/20", found);
+    }
+
+    #[test]
+    fn test_buckets_rebuilt_after_roundtrip() {
+        let mut index = AnnIndex::new(AnnConfig { exact_below: 0, seed: 3, ..AnnConfig::default() });
+        let vectors = corpus(300);
+        for (i, vector) in vectors.iter().enumerate() {
+            index.insert(format!("v{}", i), vector.clone());
+        }
+
+        let json = serde_json::to_string(&index).unwrap();
+        assert!(!json.contains("buckets"));
+        let restored: AnnIndex = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.config(), index.config());
+        assert_eq!(restored.top_k(&vectors[42], 5), index.top_k(&vectors[42], 5));
+    }
+}
@@ -5,10 +5,14 @@
 //! - KL divergence as information gain
 //! - Free energy minimization
 
+use serde::{Deserialize, Serialize};
+
+use crate::ann::{AnnConfig, AnnIndex};
 use crate::vector::SparseVector;
 use crate::{DEFAULT_DIMS, DEFAULT_ETA};
 
 /// Regressive Entropic Displacement engine
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RedEngine {
     /// Number of dimensions
     pub dims: usize,
@@ -24,6 +28,9 @@ pub struct RedEngine {
     pub total_observations: u64,
     /// Total retrievals
     pub total_retrievals: u64,
+    /// Nearest-neighbour index over vectors passed to `observe_indexed`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ann: Option<AnnIndex>,
 }
 
 impl RedEngine {
@@ -43,9 +50,17 @@ impl RedEngine {
             cumulative_loss: vec![0.0; dims],
             total_observations: 0,
             total_retrievals: 0,
+            ann: None,
         }
     }
     
+    /// Keep an approximate nearest-neighbour index of observed vectors,
+    /// enabling `top_k`
+    pub fn with_ann(mut self, config: AnnConfig) -> Self {
+        self.ann = Some(AnnIndex::new(config));
+        self
+    }
+    
     /// Update belief distribution when new event is observed
     /// This is the "perception" step in free energy minimization
     pub fn observe(&mut self, vector: &SparseVector) {
@@ -60,6 +75,34 @@ impl RedEngine {
         self.normalize_posterior();
     }
     
+    /// Observe an event and, when the ANN index is enabled, index its
+    /// vector under `id` for `top_k`
+    pub fn observe_indexed(&mut self, id: impl Into<String>, vector: &SparseVector) {
+        self.observe(vector);
+        if let Some(ann) = &mut self.ann {
+            ann.insert(id, vector.clone());
+        }
+    }
+    
+    /// The `k` indexed events most similar to `query` by cosine similarity,
+    /// best first; empty unless the engine was built `with_ann`
+    pub fn top_k(&self, query: &SparseVector, k: usize) -> Vec<(String, f64)> {
+        self.ann.as_ref()
+            .map(|ann| ann.top_k(query, k))
+            .unwrap_or_default()
+    }
+    
+    /// Observe many events at once
+    ///
+    /// Applies the same per-vector update and renormalization as calling
+    /// `observe` on each vector in order, so the result is identical; it
+    /// exists so bindings can cross the language boundary once per batch.
+    pub fn observe_batch(&mut self, vectors: &[SparseVector]) {
+        for vector in vectors {
+            self.observe(vector);
+        }
+    }
+    
     /// Update weights based on retrieval outcome
     /// Uses multiplicative weights update (Hedge algorithm)
     pub fn retrieval_feedback(&mut self, vector: &SparseVector, was_useful: bool) {
@@ -207,6 +250,58 @@ mod tests {
         assert!(engine.posterior[0] > engine.posterior[50]);
     }
     
+    #[test]
+    fn test_observe_batch_matches_sequential() {
+        let vectors: Vec<SparseVector> = (0..50)
+            .map(|i| {
+                let mut vector = SparseVector::with_dims(100);
+                vector.add(i % 100, 1.0);
+                vector.add((i * 7) % 100, 0.25);
+                vector
+            })
+            .collect();
+        
+        let mut sequential = RedEngine::with_params(100, 0.1);
+        for vector in &vectors {
+            sequential.observe(vector);
+        }
+        let mut batched = RedEngine::with_params(100, 0.1);
+        batched.observe_batch(&vectors);
+        
+        assert_eq!(batched.total_observations, 50);
+        assert_eq!(batched.posterior, sequential.posterior);
+    }
+    
+    #[test]
+    fn test_top_k_with_ann() {
+        let mut plain = RedEngine::with_params(100, 0.1);
+        let mut engine = RedEngine::with_params(100, 0.1).with_ann(AnnConfig::default());
+        for i in 0..20 {
+            let mut vector = SparseVector::with_dims(100);
+            vector.add(i, 1.0);
+            vector.add(i + 1, 0.5);
+            plain.observe_indexed(format!("event-{}", i), &vector);
+            engine.observe_indexed(format!("event-{}", i), &vector);
+        }
+        assert_eq!(engine.total_observations, 20);
+        assert_eq!(engine.posterior, plain.posterior);
+        
+        let mut query = SparseVector::with_dims(100);
+        query.add(5, 1.0);
+        query.add(6, 0.5);
+        assert!(plain.top_k(&query, 3).is_empty());
+        let top = engine.top_k(&query, 3);
+        assert_eq!(top[0].0, "event-5");
+        
+        // The index travels with the engine's state
+        let json = serde_json::to_string(&engine).unwrap();
+        let restored: RedEngine = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.top_k(&query, 3), top);
+        for (a, b) in restored.posterior.iter().zip(&engine.posterior) {
+            assert!((a - b).abs() < 1e-12);
+        }
+    }
+    
     #[test]
     fn test_retrieval_feedback() {
         let mut engine = RedEngine::with_params(100, 0.1);
@@ -9,10 +9,12 @@
 pub mod vector;
 pub mod displacement;
 pub mod entropy;
+pub mod ann;
 
 pub use vector::*;
 pub use displacement::*;
 pub use entropy::*;
+pub use ann::*;
 
 /// Default number of dimensions for feature vectors
 pub const DEFAULT_DIMS: usize = 65536; // 2^16
@@ -7,10 +7,12 @@ use std::collections::BTreeMap;
 use std::hash::{Hash, Hasher};
 use std::collections::hash_map::DefaultHasher;
 
+use serde::{Deserialize, Serialize};
+
 use crate::DEFAULT_DIMS;
 
 /// A sparse vector for feature representation
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SparseVector {
     /// Number of dimensions
     pub dims: usize,
@@ -10,7 +10,7 @@ use sha2::{Sha256, Digest};
 use tokio::sync::RwLock;
 use tracing::debug;
 
-use vac_sync::protocol::{SyncableVault, SyncResult};
+use vac_sync::protocol::{BlockVerifier, SyncableVault, SyncResult};
 
 use crate::error::{ReplicateError, ReplicateResult};
 use crate::peer::{PeerInfo, PeerRegistry};
@@ -102,23 +102,29 @@ impl<S: SyncableVault> MerkleSync<S> {
     /// Sync with a specific peer using `vac_sync::sync()`.
     ///
     /// `peer_vault` is the remote vault accessor (could be over network).
+    /// Only blocks signed with the peer's registered public key are accepted.
     pub async fn sync_with_peer<T: SyncableVault>(
         &self,
         peer_cell_id: &str,
         peer_vault: &T,
     ) -> ReplicateResult<SyncResult> {
-        // Check peer exists
-        {
+        // Check peer exists and has a key to verify its blocks against
+        let verifier = {
             let peers = self.peers.read().await;
-            if peers.get(peer_cell_id).is_none() {
-                return Err(ReplicateError::PeerNotFound(peer_cell_id.to_string()));
-            }
-        }
+            let peer = peers.get(peer_cell_id)
+                .ok_or_else(|| ReplicateError::PeerNotFound(peer_cell_id.to_string()))?;
+            let public_key: [u8; 32] = peer.public_key.as_slice().try_into()
+                .map_err(|_| ReplicateError::SyncFailed {
+                    peer_id: peer_cell_id.to_string(),
+                    reason: "peer has no Ed25519 public key registered".to_string(),
+                })?;
+            BlockVerifier::from_public_keys([public_key])
+        };
 
         debug!(peer = %peer_cell_id, "Starting sync with peer");
 
         // Delegate to vac_sync::sync() — block-verified replication
-        let result = vac_sync::sync(peer_vault, self.local.as_ref())
+        let result = vac_sync::sync(peer_vault, self.local.as_ref(), &verifier)
             .await
             .map_err(|e| ReplicateError::SyncFailed {
                 peer_id: peer_cell_id.to_string(),
@@ -20,6 +20,20 @@ pub trait ContentStore: Send + Sync {
     /// Delete by CID (for garbage collection)
     async fn delete(&self, cid: &Cid) -> VacResult<()>;
     
+    /// Iterate over the CIDs stored at the time of the call
+    ///
+    /// The iterator is a snapshot, so the store may be modified (e.g. swept
+    /// by garbage collection) while it is consumed.
+    fn iter_cids(&self) -> impl Iterator<Item = Cid> + Send;
+    
+    /// Number of stored objects
+    fn len(&self) -> usize;
+    
+    /// Check if the store holds no objects
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    
     /// Get an object by CID
     async fn get<T: ContentAddressable + Send>(&self, cid: &Cid) -> VacResult<T> {
         let bytes = self.get_bytes(cid).await?;
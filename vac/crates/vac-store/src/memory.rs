@@ -28,16 +28,6 @@ impl MemoryStore {
         }
     }
     
-    /// Get the number of stored objects
-    pub fn len(&self) -> usize {
-        self.data.len()
-    }
-    
-    /// Check if empty
-    pub fn is_empty(&self) -> bool {
-        self.data.is_empty()
-    }
-    
     /// Get all CIDs
     pub fn cids(&self) -> Vec<Cid> {
         self.data.iter().map(|r| r.key().clone()).collect()
@@ -70,6 +60,18 @@ impl ContentStore for MemoryStore {
         self.data.remove(cid);
         Ok(())
     }
+    
+    fn iter_cids(&self) -> impl Iterator<Item = Cid> + Send {
+        self.cids().into_iter()
+    }
+    
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+    
+    fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
 }
 
 #[cfg(test)]
@@ -125,4 +127,26 @@ mod tests {
         assert_eq!(cid1, cid2);
         assert_eq!(store.len(), 1); // Only one entry
     }
+    
+    #[tokio::test]
+    async fn test_iter_cids() {
+        let store = MemoryStore::new();
+        assert!(store.is_empty());
+        assert_eq!(store.iter_cids().count(), 0);
+        
+        let a = store.put_bytes(b"a").await.unwrap();
+        let b = store.put_bytes(b"b").await.unwrap();
+        let mut cids: Vec<Cid> = store.iter_cids().collect();
+        cids.sort();
+        let mut expected = vec![a, b];
+        expected.sort();
+        assert_eq!(cids, expected);
+        
+        // Deleting while iterating is safe: the iterator is a snapshot
+        for cid in store.iter_cids() {
+            store.delete(&cid).await.unwrap();
+        }
+        assert!(store.is_empty());
+        assert_eq!(store.len(), 0);
+    }
 }
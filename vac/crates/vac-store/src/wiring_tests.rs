@@ -24,6 +24,7 @@ mod tests {
     };
     use vac_core::types::*;
 
+    use crate::cas::ContentStore;
     use crate::indexdb_bridge::{AsyncPersistenceBackend, IndexDbKernelStore, InMemoryPersistenceBackend};
     use crate::prolly_bridge::ProllyKernelStore;
 
@@ -4,6 +4,8 @@
 
 pub mod protocol;
 pub mod diff;
+pub mod throttle;
 
 pub use protocol::*;
 pub use diff::*;
+pub use throttle::*;
@@ -5,8 +5,10 @@
 use async_trait::async_trait;
 use cid::Cid;
 
-use vac_core::{BlockHeader, VacError, VacResult, VaultPatch};
-use vac_crypto::verify_block_signature;
+use vac_core::{BlockHeader, ContentAddressable, VacError, VacResult, VaultPatch};
+use vac_crypto::{parse_did_key, verify_block_signature};
+
+use crate::throttle::TokenBucket;
 
 /// Sync result
 #[derive(Debug, Clone)]
@@ -18,7 +20,10 @@ pub struct SyncResult {
 /// Sync error
 #[derive(Debug, Clone)]
 pub enum SyncError {
-    InvalidSignature { block_no: u64 },
+    /// A signature failed to verify, or none came from a trusted signer
+    InvalidSignature { block_no: u64, cid: Cid },
+    /// The block carries no signatures and the verifier requires them
+    UnsignedBlock { block_no: u64 },
     PrevHashMismatch { block_no: u64 },
     BlockHashMismatch { block_no: u64 },
     MissingBlock { block_no: u64 },
@@ -27,8 +32,11 @@ pub enum SyncError {
 impl std::fmt::Display for SyncError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            SyncError::InvalidSignature { block_no } => {
-                write!(f, "Invalid signature on block {}", block_no)
+            SyncError::InvalidSignature { block_no, cid } => {
+                write!(f, "Invalid signature on block {} ({})", block_no, cid)
+            }
+            SyncError::UnsignedBlock { block_no } => {
+                write!(f, "Block {} is unsigned", block_no)
             }
             SyncError::PrevHashMismatch { block_no } => {
                 write!(f, "Block {} prev_hash mismatch", block_no)
@@ -73,37 +81,98 @@ pub trait SyncableVault: Send + Sync {
     async fn set_head(&self, block_hash: [u8; 32]) -> VacResult<()>;
 }
 
-/// Verify a block's signature and hash
-pub fn verify_block(block: &BlockHeader, expected_prev_hash: &[u8; 32]) -> Result<(), SyncError> {
-    // 1. Verify prev_hash chain
-    if &block.prev_block_hash != expected_prev_hash {
-        return Err(SyncError::PrevHashMismatch { block_no: block.block_no });
+/// Decides which incoming blocks are trusted
+///
+/// Every signature on a block must verify, and at least one must come from
+/// a trusted signer. Unsigned blocks are rejected unless explicitly allowed.
+#[derive(Debug, Clone)]
+pub struct BlockVerifier {
+    trusted: Vec<[u8; 32]>,
+    allow_unsigned: bool,
+}
+
+impl BlockVerifier {
+    /// Trust blocks signed by any of the given `did:key` identifiers
+    pub fn new<I, D>(trusted_dids: I) -> VacResult<Self>
+    where
+        I: IntoIterator<Item = D>,
+        D: AsRef<str>,
+    {
+        let trusted = trusted_dids
+            .into_iter()
+            .map(|did| parse_did_key(did.as_ref()))
+            .collect::<VacResult<Vec<_>>>()?;
+        Ok(Self { trusted, allow_unsigned: false })
     }
     
-    // 2. Verify all signatures
-    let block_message = compute_block_message(block);
-    for sig in &block.signatures {
-        match verify_block_signature(sig, &block_message) {
-            Ok(true) => {}
-            _ => return Err(SyncError::InvalidSignature { block_no: block.block_no }),
-        }
+    /// Trust blocks signed by any of the given raw Ed25519 public keys
+    pub fn from_public_keys(keys: impl IntoIterator<Item = [u8; 32]>) -> Self {
+        Self { trusted: keys.into_iter().collect(), allow_unsigned: false }
     }
     
-    // 3. Verify block_hash computation
-    let computed_hash = vac_core::compute_block_hash(
-        block.block_no,
-        &block.prev_block_hash,
-        block.ts,
-        &block.links.patch,
-        &block.links.manifest,
-        &block.signatures,
-    ).map_err(|_| SyncError::BlockHashMismatch { block_no: block.block_no })?;
-    
-    if computed_hash != block.block_hash {
-        return Err(SyncError::BlockHashMismatch { block_no: block.block_no });
+    /// Accept blocks that carry no signatures at all
+    pub fn allow_unsigned(mut self) -> Self {
+        self.allow_unsigned = true;
+        self
     }
     
-    Ok(())
+    /// Verify a block's signatures, chain link and hash
+    pub fn verify(&self, block: &BlockHeader, expected_prev_hash: &[u8; 32]) -> Result<(), SyncError> {
+        // 1. Verify prev_hash chain
+        if &block.prev_block_hash != expected_prev_hash {
+            return Err(SyncError::PrevHashMismatch { block_no: block.block_no });
+        }
+        
+        // 2. Verify all signatures, requiring one from a trusted signer
+        if block.signatures.is_empty() {
+            if !self.allow_unsigned {
+                return Err(SyncError::UnsignedBlock { block_no: block.block_no });
+            }
+        } else {
+            let invalid = || SyncError::InvalidSignature {
+                block_no: block.block_no,
+                cid: block.cid().unwrap_or_default(),
+            };
+            let block_message = compute_block_message(block);
+            let mut trusted = false;
+            for sig in &block.signatures {
+                let signer = parse_did_key(&sig.public_key).map_err(|_| invalid())?;
+                match verify_block_signature(sig, &block_message) {
+                    Ok(true) => {}
+                    _ => return Err(invalid()),
+                }
+                trusted |= self.trusted.contains(&signer);
+            }
+            if !trusted {
+                return Err(invalid());
+            }
+        }
+        
+        // 3. Verify block_hash computation
+        let computed_hash = vac_core::compute_block_hash(
+            block.block_no,
+            &block.prev_block_hash,
+            block.ts,
+            &block.links.patch,
+            &block.links.manifest,
+            &block.signatures,
+        ).map_err(|_| SyncError::BlockHashMismatch { block_no: block.block_no })?;
+        
+        if computed_hash != block.block_hash {
+            return Err(SyncError::BlockHashMismatch { block_no: block.block_no });
+        }
+        
+        Ok(())
+    }
+}
+
+/// Verify a block's signature and hash against `verifier`
+pub fn verify_block(
+    block: &BlockHeader,
+    expected_prev_hash: &[u8; 32],
+    verifier: &BlockVerifier,
+) -> Result<(), SyncError> {
+    verifier.verify(block, expected_prev_hash)
 }
 
 /// Compute the message to sign for a block (everything except signatures)
@@ -158,78 +227,350 @@ pub async fn find_common_ancestor<S: SyncableVault, T: SyncableVault>(
     Ok(source_block)
 }
 
-/// Sync target vault to match source vault
-pub async fn sync<S: SyncableVault, T: SyncableVault>(
-    source: &S,
-    target: &T,
-) -> Result<SyncResult, SyncError> {
-    let source_head = source.get_head_block().await
-        .map_err(|_| SyncError::MissingBlock { block_no: 0 })?;
-    let target_head = target.get_head_block().await
-        .map_err(|_| SyncError::MissingBlock { block_no: 0 })?;
-    
-    // Already in sync
-    if source_head.block_hash == target_head.block_hash {
-        return Ok(SyncResult {
-            transferred_blocks: 0,
-            transferred_objects: 0,
-        });
+/// Sync progress, reported after each block is stored
+#[derive(Debug, Clone)]
+pub struct SyncProgress {
+    pub blocks_done: usize,
+    pub blocks_total: usize,
+    pub bytes_transferred: u64,
+    /// Average throughput since the transfer started
+    pub bytes_per_sec: f64,
+}
+
+type ProgressCallback = Box<dyn Fn(&SyncProgress) + Send + Sync>;
+
+/// A configured sync run: block verification, optional bandwidth cap and
+/// progress reporting
+pub struct SyncSession {
+    verifier: BlockVerifier,
+    throttle: Option<TokenBucket>,
+    on_progress: Option<ProgressCallback>,
+}
+
+impl SyncSession {
+    pub fn new(verifier: BlockVerifier) -> Self {
+        Self { verifier, throttle: None, on_progress: None }
     }
     
-    // Find common ancestor
-    let ancestor = find_common_ancestor(source, target).await
-        .map_err(|_| SyncError::MissingBlock { block_no: 0 })?;
-    
-    // Get blocks from ancestor to source head
-    let blocks = source.get_block_range(ancestor.block_no + 1, source_head.block_no).await
-        .map_err(|_| SyncError::MissingBlock { block_no: ancestor.block_no + 1 })?;
+    /// Cap transfer of blocks and objects at `max_bytes_per_sec`
+    pub fn with_max_bytes_per_sec(mut self, max_bytes_per_sec: u64) -> Self {
+        self.throttle = Some(TokenBucket::new(max_bytes_per_sec));
+        self
+    }
     
-    // Transfer and verify each block IN ORDER
-    let mut prev_hash = ancestor.block_hash;
-    let mut total_objects = 0;
+    /// Call `callback` with progress after each block is stored
+    pub fn on_progress(mut self, callback: impl Fn(&SyncProgress) + Send + Sync + 'static) -> Self {
+        self.on_progress = Some(Box::new(callback));
+        self
+    }
     
-    for block in &blocks {
-        // Verify block
-        verify_block(block, &prev_hash)?;
+    /// Sync target vault to match source vault
+    ///
+    /// The whole batch of new blocks is verified before anything is written
+    /// to `target`; one bad block rejects them all.
+    pub async fn run<S: SyncableVault, T: SyncableVault>(
+        &self,
+        source: &S,
+        target: &T,
+    ) -> Result<SyncResult, SyncError> {
+        let source_head = source.get_head_block().await
+            .map_err(|_| SyncError::MissingBlock { block_no: 0 })?;
+        let target_head = target.get_head_block().await
+            .map_err(|_| SyncError::MissingBlock { block_no: 0 })?;
+        
+        // Already in sync
+        if source_head.block_hash == target_head.block_hash {
+            return Ok(SyncResult {
+                transferred_blocks: 0,
+                transferred_objects: 0,
+            });
+        }
+        
+        // Find common ancestor
+        let ancestor = find_common_ancestor(source, target).await
+            .map_err(|_| SyncError::MissingBlock { block_no: 0 })?;
         
-        // Fetch and store objects referenced by this block's patch
-        let patch = source.get_patch(&block.links.patch).await
-            .map_err(|_| SyncError::MissingBlock { block_no: block.block_no })?;
+        // Get blocks from ancestor to source head
+        let blocks = source.get_block_range(ancestor.block_no + 1, source_head.block_no).await
+            .map_err(|_| SyncError::MissingBlock { block_no: ancestor.block_no + 1 })?;
         
-        for cid in &patch.added_cids {
-            let obj = source.get_object(cid).await
+        // Verify every block IN ORDER before transferring any of them
+        let mut prev_hash = ancestor.block_hash;
+        for block in &blocks {
+            self.verifier.verify(block, &prev_hash)?;
+            prev_hash = block.block_hash;
+        }
+        
+        let started = std::time::Instant::now();
+        let mut total_objects = 0;
+        let mut total_bytes = 0u64;
+        
+        for (i, block) in blocks.iter().enumerate() {
+            // Fetch and store objects referenced by this block's patch
+            let patch = source.get_patch(&block.links.patch).await
                 .map_err(|_| SyncError::MissingBlock { block_no: block.block_no })?;
-            target.put_object(&obj).await
+            
+            for cid in &patch.added_cids {
+                let obj = source.get_object(cid).await
+                    .map_err(|_| SyncError::MissingBlock { block_no: block.block_no })?;
+                self.throttle(obj.len() as u64).await;
+                target.put_object(&obj).await
+                    .map_err(|_| SyncError::MissingBlock { block_no: block.block_no })?;
+                total_objects += 1;
+                total_bytes += obj.len() as u64;
+            }
+            
+            // Store the verified block
+            let block_bytes = block.to_bytes().map(|b| b.len() as u64).unwrap_or(0);
+            self.throttle(block_bytes).await;
+            target.put_block(block).await
                 .map_err(|_| SyncError::MissingBlock { block_no: block.block_no })?;
-            total_objects += 1;
+            total_bytes += block_bytes;
+            
+            if let Some(callback) = &self.on_progress {
+                let elapsed = started.elapsed().as_secs_f64();
+                callback(&SyncProgress {
+                    blocks_done: i + 1,
+                    blocks_total: blocks.len(),
+                    bytes_transferred: total_bytes,
+                    bytes_per_sec: if elapsed > 0.0 { total_bytes as f64 / elapsed } else { 0.0 },
+                });
+            }
         }
         
-        // Store the verified block
-        target.put_block(block).await
-            .map_err(|_| SyncError::MissingBlock { block_no: block.block_no })?;
+        // Update target head to last verified block
+        if let Some(last_block) = blocks.last() {
+            target.set_head(last_block.block_hash).await
+                .map_err(|_| SyncError::MissingBlock { block_no: last_block.block_no })?;
+        }
         
-        prev_hash = block.block_hash;
+        Ok(SyncResult {
+            transferred_blocks: blocks.len(),
+            transferred_objects: total_objects,
+        })
     }
     
-    // Update target head to last verified block
-    if let Some(last_block) = blocks.last() {
-        target.set_head(last_block.block_hash).await
-            .map_err(|_| SyncError::MissingBlock { block_no: last_block.block_no })?;
+    async fn throttle(&self, bytes: u64) {
+        if let Some(bucket) = &self.throttle {
+            bucket.acquire(bytes).await;
+        }
     }
-    
-    Ok(SyncResult {
-        transferred_blocks: blocks.len(),
-        transferred_objects: total_objects,
-    })
+}
+
+/// Sync target vault to match source vault, unthrottled
+///
+/// See [`SyncSession::run`].
+pub async fn sync<S: SyncableVault, T: SyncableVault>(
+    source: &S,
+    target: &T,
+    verifier: &BlockVerifier,
+) -> Result<SyncResult, SyncError> {
+    SyncSession::new(verifier.clone()).run(source, target).await
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+    use vac_core::BlockLinks;
+    use vac_crypto::{sign_block, KeyPair};
     
     #[test]
     fn test_sync_error_display() {
-        let err = SyncError::InvalidSignature { block_no: 42 };
+        let err = SyncError::InvalidSignature { block_no: 42, cid: Cid::default() };
         assert!(err.to_string().contains("42"));
     }
+    
+    fn build_block(block_no: u64, prev_block_hash: [u8; 32], signers: &[&KeyPair]) -> BlockHeader {
+        let mut block = BlockHeader {
+            type_: "block".to_string(),
+            version: 1,
+            block_no,
+            prev_block_hash,
+            ts: 1_700_000_000 + block_no as i64,
+            links: BlockLinks { patch: Cid::default(), manifest: Cid::default() },
+            signatures: vec![],
+            block_hash: [0u8; 32],
+            metadata: BTreeMap::new(),
+        };
+        let message = compute_block_message(&block);
+        block.signatures = signers.iter().map(|kp| sign_block(kp, &message)).collect();
+        block.block_hash = vac_core::compute_block_hash(
+            block.block_no,
+            &block.prev_block_hash,
+            block.ts,
+            &block.links.patch,
+            &block.links.manifest,
+            &block.signatures,
+        ).unwrap();
+        block
+    }
+    
+    #[test]
+    fn test_verifier_requires_trusted_signer() {
+        let trusted = KeyPair::generate();
+        let stranger = KeyPair::generate();
+        let verifier = BlockVerifier::new([trusted.did_key()]).unwrap();
+        let genesis = [0u8; 32];
+        
+        assert!(verifier.verify(&build_block(1, genesis, &[&trusted]), &genesis).is_ok());
+        let by_key = BlockVerifier::from_public_keys([trusted.public_bytes()]);
+        assert!(by_key.verify(&build_block(1, genesis, &[&trusted]), &genesis).is_ok());
+        assert!(verifier.verify(&build_block(1, genesis, &[&trusted, &stranger]), &genesis).is_ok());
+        
+        let foreign = build_block(1, genesis, &[&stranger]);
+        match verifier.verify(&foreign, &genesis) {
+            Err(SyncError::InvalidSignature { block_no: 1, cid }) => {
+                assert_eq!(cid, foreign.cid().unwrap());
+            }
+            other => panic!("expected InvalidSignature, got {:?}", other),
+        }
+        
+        // A forged signature fails even when it claims a trusted key
+        let mut forged = build_block(1, genesis, &[&trusted]);
+        forged.signatures[0].signature[0] ^= 0xff;
+        assert!(matches!(
+            verifier.verify(&forged, &genesis),
+            Err(SyncError::InvalidSignature { .. })
+        ));
+        
+        assert!(BlockVerifier::new(["did:web:example.com"]).is_err());
+    }
+    
+    #[test]
+    fn test_unsigned_blocks_need_explicit_mode() {
+        let verifier = BlockVerifier::new([KeyPair::generate().did_key()]).unwrap();
+        let genesis = [0u8; 32];
+        let unsigned = build_block(1, genesis, &[]);
+        
+        assert!(matches!(
+            verifier.verify(&unsigned, &genesis),
+            Err(SyncError::UnsignedBlock { block_no: 1 })
+        ));
+        assert!(verifier.allow_unsigned().verify(&unsigned, &genesis).is_ok());
+    }
+    
+    /// Vault holding a chain of blocks and no patches
+    #[derive(Default)]
+    struct MemoryVault {
+        blocks: Mutex<Vec<BlockHeader>>,
+    }
+    
+    #[async_trait]
+    impl SyncableVault for MemoryVault {
+        async fn get_head_block(&self) -> VacResult<BlockHeader> {
+            self.blocks.lock().unwrap().last().cloned()
+                .ok_or_else(|| VacError::NotFound("head".into()))
+        }
+        
+        async fn get_block(&self, block_no: u64) -> VacResult<BlockHeader> {
+            self.blocks.lock().unwrap().get(block_no as usize).cloned()
+                .ok_or_else(|| VacError::NotFound(format!("block {}", block_no)))
+        }
+        
+        async fn get_block_range(&self, from: u64, to: u64) -> VacResult<Vec<BlockHeader>> {
+            let blocks = self.blocks.lock().unwrap();
+            Ok(blocks[from as usize..=to as usize].to_vec())
+        }
+        
+        async fn get_patch(&self, cid: &Cid) -> VacResult<VaultPatch> {
+            Ok(VaultPatch {
+                type_: "patch".to_string(),
+                version: 1,
+                parent_block_hash: [0u8; 32],
+                added_cids: vec![],
+                removed_refs: vec![],
+                updated_roots: BTreeMap::new(),
+                links: BTreeMap::from([("self".to_string(), vec![*cid])]),
+                metadata: BTreeMap::new(),
+            })
+        }
+        
+        async fn get_object(&self, cid: &Cid) -> VacResult<Vec<u8>> {
+            Err(VacError::NotFound(cid.to_string()))
+        }
+        
+        async fn put_object(&self, _bytes: &[u8]) -> VacResult<Cid> {
+            Ok(Cid::default())
+        }
+        
+        async fn put_block(&self, block: &BlockHeader) -> VacResult<()> {
+            self.blocks.lock().unwrap().push(block.clone());
+            Ok(())
+        }
+        
+        async fn set_head(&self, _block_hash: [u8; 32]) -> VacResult<()> {
+            Ok(())
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_sync_rejects_whole_batch() {
+        let signer = KeyPair::generate();
+        let stranger = KeyPair::generate();
+        let verifier = BlockVerifier::new([signer.did_key()]).unwrap();
+        
+        let genesis = build_block(0, [0u8; 32], &[&signer]);
+        let block1 = build_block(1, genesis.block_hash, &[&signer]);
+        let block2 = build_block(2, block1.block_hash, &[&stranger]);
+        
+        let source = MemoryVault::default();
+        let target = MemoryVault::default();
+        *source.blocks.lock().unwrap() = vec![genesis.clone(), block1.clone(), block2];
+        target.put_block(&genesis).await.unwrap();
+        
+        let result = sync(&source, &target, &verifier).await;
+        assert!(matches!(result, Err(SyncError::InvalidSignature { block_no: 2, .. })));
+        // The valid first block was not applied either
+        assert_eq!(target.blocks.lock().unwrap().len(), 1);
+        
+        source.blocks.lock().unwrap().truncate(2);
+        let result = sync(&source, &target, &verifier).await.unwrap();
+        assert_eq!(result.transferred_blocks, 1);
+        assert_eq!(target.get_head_block().await.unwrap().block_hash, block1.block_hash);
+    }
+    
+    #[tokio::test]
+    async fn test_session_throttles_and_reports_progress() {
+        let signer = KeyPair::generate();
+        let verifier = BlockVerifier::new([signer.did_key()]).unwrap();
+        
+        let genesis = build_block(0, [0u8; 32], &[&signer]);
+        let mut chain = vec![genesis.clone()];
+        for block_no in 1..=20 {
+            let prev = chain.last().unwrap().block_hash;
+            chain.push(build_block(block_no, prev, &[&signer]));
+        }
+        let bytes: u64 = chain[1..].iter().map(|b| b.to_bytes().unwrap().len() as u64).sum();
+        
+        let source = MemoryVault::default();
+        let target = MemoryVault::default();
+        *source.blocks.lock().unwrap() = chain;
+        target.put_block(&genesis).await.unwrap();
+        
+        // Cap at a rate that makes the transfer take about a second beyond
+        // the initial one-second burst
+        let rate = bytes / 2;
+        let reports = std::sync::Arc::new(Mutex::new(Vec::new()));
+        let seen = std::sync::Arc::clone(&reports);
+        let session = SyncSession::new(verifier)
+            .with_max_bytes_per_sec(rate)
+            .on_progress(move |p| seen.lock().unwrap().push(p.clone()));
+        
+        let started = std::time::Instant::now();
+        let result = session.run(&source, &target).await.unwrap();
+        let elapsed = started.elapsed();
+        
+        assert_eq!(result.transferred_blocks, 20);
+        let minimum = (bytes - rate) as f64 / rate as f64;
+        assert!(elapsed.as_secs_f64() >= minimum * 0.95, "took {:?}", elapsed);
+        
+        let reports = reports.lock().unwrap();
+        assert_eq!(reports.len(), 20);
+        let last = reports.last().unwrap();
+        assert_eq!((last.blocks_done, last.blocks_total), (20, 20));
+        assert_eq!(last.bytes_transferred, bytes);
+        assert!(last.bytes_per_sec <= rate as f64 * 2.1);
+    }
 }
@@ -0,0 +1,88 @@
+//! Bandwidth throttling for sync transfers
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Token bucket limiting transfer to a byte rate
+///
+/// The bucket holds up to one second of tokens, so a session may burst that
+/// much before settling to the configured rate. A transfer larger than the
+/// bucket is let through and the debt is waited off, so large objects are
+/// never starved.
+#[derive(Debug)]
+pub struct TokenBucket {
+    bytes_per_sec: f64,
+    state: Mutex<BucketState>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    /// Create a full bucket refilling at `bytes_per_sec`
+    pub fn new(bytes_per_sec: u64) -> Self {
+        let bytes_per_sec = bytes_per_sec.max(1) as f64;
+        Self {
+            bytes_per_sec,
+            state: Mutex::new(BucketState {
+                tokens: bytes_per_sec,
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Configured rate in bytes per second
+    pub fn bytes_per_sec(&self) -> u64 {
+        self.bytes_per_sec as u64
+    }
+
+    /// Take `bytes` tokens, sleeping (without blocking the runtime) until the
+    /// rate allows them
+    pub async fn acquire(&self, bytes: u64) {
+        let wait = {
+            let mut state = self.state.lock().unwrap();
+            let now = Instant::now();
+            let elapsed = now.duration_since(state.refilled_at).as_secs_f64();
+            state.tokens = (state.tokens + elapsed * self.bytes_per_sec).min(self.bytes_per_sec);
+            state.refilled_at = now;
+            state.tokens -= bytes as f64;
+            if state.tokens < 0.0 {
+                Duration::from_secs_f64(-state.tokens / self.bytes_per_sec)
+            } else {
+                Duration::ZERO
+            }
+        };
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_rate_cap_enforces_minimum_duration() {
+        // 40 KB at 20 KB/s with a 20 KB burst: at least one second
+        let bucket = TokenBucket::new(20_000);
+        let started = Instant::now();
+        for _ in 0..10 {
+            bucket.acquire(4_000).await;
+        }
+        let elapsed = started.elapsed();
+        assert!(elapsed >= Duration::from_millis(950), "took {:?}", elapsed);
+        assert!(elapsed < Duration::from_secs(3), "took {:?}", elapsed);
+    }
+
+    #[tokio::test]
+    async fn test_burst_is_not_delayed() {
+        let bucket = TokenBucket::new(1_000_000);
+        let started = Instant::now();
+        bucket.acquire(500_000).await;
+        assert!(started.elapsed() < Duration::from_millis(50));
+    }
+}